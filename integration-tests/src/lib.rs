@@ -0,0 +1,79 @@
+//! # ClawChain Integration Tests
+//!
+//! Helpers for driving the full `clawchain-runtime` through realistic agent
+//! workflows. Each call is dispatched the way an extrinsic would be (through
+//! the runtime `BaseCallFilter` and the `ChargeGasQuota` transaction
+//! extension) and its declared weight and charged fee are accumulated in a
+//! [`WorkflowMeter`], so tests can assert both state invariants and the
+//! cumulative cost of a flow.
+
+use clawchain_runtime::{AccountId, Balance, Runtime, RuntimeCall, RuntimeOrigin, System, UNITS};
+use codec::Encode;
+use frame_support::{
+    dispatch::{DispatchErrorWithPostInfo, DispatchResultWithPostInfo, GetDispatchInfo},
+    weights::Weight,
+};
+use pallet_gas_quota::{ChargeGasQuota, TotalFeesCollected};
+use sp_runtime::{traits::DispatchTransaction, BuildStorage, DispatchError};
+
+/// Balance given to every test account at genesis.
+pub const INITIAL_BALANCE: Balance = 10_000 * UNITS;
+
+/// Build externalities for the runtime with `accounts` endowed.
+pub fn new_test_ext(accounts: &[AccountId]) -> sp_io::TestExternalities {
+    let mut storage = frame_system::GenesisConfig::<Runtime>::default()
+        .build_storage()
+        .unwrap();
+
+    pallet_balances::GenesisConfig::<Runtime> {
+        balances: accounts
+            .iter()
+            .cloned()
+            .map(|who| (who, INITIAL_BALANCE))
+            .collect(),
+        ..Default::default()
+    }
+    .assimilate_storage(&mut storage)
+    .unwrap();
+
+    let mut ext = sp_io::TestExternalities::new(storage);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
+
+/// Cumulative weight and fee of the calls dispatched in one workflow.
+#[derive(Default, Debug)]
+pub struct WorkflowMeter {
+    /// Number of calls dispatched.
+    pub calls: u32,
+    /// Sum of the declared dispatch weights.
+    pub weight: Weight,
+    /// Sum of the over-quota fees `ChargeGasQuota` charged.
+    pub fee: Balance,
+}
+
+impl WorkflowMeter {
+    /// Dispatch `call` from `origin` through `ChargeGasQuota`, recording its
+    /// weight and the fee collected for it.
+    pub fn dispatch(
+        &mut self,
+        origin: RuntimeOrigin,
+        call: impl Into<RuntimeCall>,
+    ) -> DispatchResultWithPostInfo {
+        let call: RuntimeCall = call.into();
+        let info = call.get_dispatch_info();
+        let len = call.encoded_size();
+
+        self.calls = self.calls.saturating_add(1);
+        self.weight = self.weight.saturating_add(info.total_weight());
+
+        let fees_before = TotalFeesCollected::<Runtime>::get();
+        let result = ChargeGasQuota::<Runtime>::new()
+            .dispatch_transaction(origin, call, &info, len, 0)
+            .map_err(|e| DispatchErrorWithPostInfo::from(DispatchError::Other(e.into())))?;
+        self.fee = self.fee.saturating_add(
+            TotalFeesCollected::<Runtime>::get().saturating_sub(fees_before),
+        );
+        result
+    }
+}
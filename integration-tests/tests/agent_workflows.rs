@@ -0,0 +1,249 @@
+//! End-to-end agent workflows against the full runtime.
+//!
+//! Service-market invocation and anon-messaging negotiation are not part of
+//! the runtime yet, so the flows cover the on-chain steps that are: identity,
+//! registration, task escrow, approval and dispute resolution.
+
+use clawchain_integration_tests::{new_test_ext, WorkflowMeter, INITIAL_BALANCE};
+use clawchain_runtime::{
+    AccountId, Balance, Balances, Runtime, RuntimeOrigin, System, TaskMarket, UNITS,
+};
+use frame_support::assert_ok;
+use pallet_task_market::TaskStatus;
+use sp_keyring::Sr25519Keyring;
+
+const REWARD: Balance = 500 * UNITS;
+const DEADLINE: u32 = 1_000;
+
+/// Upper bound on the fees charged for one full workflow. Guards against a
+/// quota regression making the common agent path expensive.
+const WORKFLOW_FEE_BUDGET: Balance = UNITS / 10;
+
+fn poster() -> AccountId {
+    Sr25519Keyring::Alice.to_account_id()
+}
+
+fn worker() -> AccountId {
+    Sr25519Keyring::Bob.to_account_id()
+}
+
+fn signed(who: &AccountId) -> RuntimeOrigin {
+    RuntimeOrigin::signed(who.clone())
+}
+
+/// Register a DID and an agent for each participant, then post task 0 and
+/// have the worker bid on it, get assigned and submit its work.
+fn onboard_and_assign(meter: &mut WorkflowMeter) {
+    for (who, did) in [(poster(), "did:claw:poster"), (worker(), "did:claw:worker")] {
+        assert_ok!(meter.dispatch(
+            signed(&who),
+            pallet_agent_did::Call::<Runtime>::register_did {
+                context: b"https://www.w3.org/ns/did/v1".to_vec(),
+            },
+        ));
+        assert_ok!(meter.dispatch(
+            signed(&who),
+            pallet_agent_registry::Call::<Runtime>::register_agent {
+                did: did.as_bytes().to_vec(),
+                metadata: b"{\"kind\":\"agent\"}".to_vec(),
+            },
+        ));
+    }
+
+    assert_ok!(meter.dispatch(
+        signed(&poster()),
+        pallet_task_market::Call::<Runtime>::post_task {
+            title: b"Summarise block 1".to_vec(),
+            description: b"Produce a summary of all extrinsics".to_vec(),
+            reward: REWARD,
+            deadline: DEADLINE,
+            min_bidder_reputation: None,
+            worker_bond: None,
+        },
+    ));
+    assert_eq!(Balances::reserved_balance(poster()), REWARD);
+
+    assert_ok!(meter.dispatch(
+        signed(&worker()),
+        pallet_task_market::Call::<Runtime>::bid_on_task {
+            task_id: 0,
+            amount: REWARD,
+            proposal: b"Done within ten blocks".to_vec(),
+        },
+    ));
+    assert_ok!(meter.dispatch(
+        signed(&poster()),
+        pallet_task_market::Call::<Runtime>::assign_task {
+            task_id: 0,
+            bidder: worker(),
+        },
+    ));
+    assert_ok!(meter.dispatch(
+        signed(&worker()),
+        pallet_task_market::Call::<Runtime>::submit_work {
+            task_id: 0,
+            proof: b"ipfs://summary".to_vec(),
+            proof_type: pallet_task_market::ProofType::Cid,
+        },
+    ));
+}
+
+/// Budget and block-limit checks shared by every workflow.
+fn assert_within_budget(meter: &WorkflowMeter) {
+    let max_block = <Runtime as frame_system::Config>::BlockWeights::get().max_block;
+    assert!(
+        meter.weight.all_lt(max_block),
+        "workflow weight {:?} does not fit in one block",
+        meter.weight
+    );
+    assert!(
+        meter.fee <= WORKFLOW_FEE_BUDGET,
+        "workflow fee {} outside budget",
+        meter.fee
+    );
+}
+
+#[test]
+fn happy_path_pays_worker_and_releases_escrow() {
+    new_test_ext(&[poster(), worker()]).execute_with(|| {
+        let issuance = Balances::total_issuance();
+        let mut meter = WorkflowMeter::default();
+
+        onboard_and_assign(&mut meter);
+        assert_ok!(meter.dispatch(
+            signed(&poster()),
+            pallet_task_market::Call::<Runtime>::approve_work { task_id: 0 },
+        ));
+
+        assert_eq!(meter.calls, 9);
+        assert_within_budget(&meter);
+        // Neither agent leaves its daily free quota.
+        assert_eq!(meter.fee, 0);
+
+        assert_eq!(TaskMarket::tasks(0).unwrap().status, TaskStatus::Approved);
+        assert_eq!(Balances::reserved_balance(poster()), 0);
+        assert_eq!(Balances::free_balance(poster()), INITIAL_BALANCE - REWARD);
+        assert_eq!(Balances::free_balance(worker()), INITIAL_BALANCE + REWARD);
+        // Moving escrow must not mint or burn.
+        assert_eq!(Balances::total_issuance(), issuance);
+    });
+}
+
+#[test]
+fn disputed_task_resolves_to_poster() {
+    new_test_ext(&[poster(), worker()]).execute_with(|| {
+        let issuance = Balances::total_issuance();
+        let mut meter = WorkflowMeter::default();
+
+        onboard_and_assign(&mut meter);
+        assert_ok!(meter.dispatch(
+            signed(&poster()),
+            pallet_task_market::Call::<Runtime>::dispute_task {
+                task_id: 0,
+                reason: b"Summary is incomplete".to_vec(),
+            },
+        ));
+        assert_eq!(TaskMarket::tasks(0).unwrap().status, TaskStatus::Disputed);
+        // Escrow stays locked while the dispute is open.
+        assert_eq!(Balances::reserved_balance(poster()), REWARD);
+
+        System::set_block_number(10);
+        assert_ok!(meter.dispatch(
+            RuntimeOrigin::root(),
+            pallet_task_market::Call::<Runtime>::resolve_dispute {
+                task_id: 0,
+                winner: poster(),
+            },
+        ));
+
+        assert_eq!(meter.calls, 10);
+        assert_within_budget(&meter);
+
+        assert_eq!(Balances::reserved_balance(poster()), 0);
+        assert_eq!(Balances::free_balance(poster()), INITIAL_BALANCE);
+        assert_eq!(Balances::free_balance(worker()), INITIAL_BALANCE);
+        assert_eq!(Balances::total_issuance(), issuance);
+    });
+}
+
+#[test]
+fn identity_resolves_from_every_form() {
+    use clawchain_runtime::identity::{self, Identifier};
+
+    new_test_ext(&[poster(), worker()]).execute_with(|| {
+        let mut meter = WorkflowMeter::default();
+        onboard_and_assign(&mut meter);
+
+        let record = identity::resolve(Identifier::Account(worker())).unwrap();
+        assert_eq!(record.agents.len(), 1);
+        assert_eq!(record.agents[0].did, b"did:claw:worker".to_vec());
+        let did = record.did.clone().unwrap();
+        assert_eq!(did.did, identity::account_did(&worker()));
+        assert!(!did.deactivated);
+
+        for identifier in [
+            Identifier::Agent(record.agents[0].agent_id),
+            Identifier::Did(b"did:claw:worker".to_vec()),
+            Identifier::Did(did.did.clone()),
+        ] {
+            assert_eq!(identity::resolve(identifier), Some(record.clone()));
+        }
+
+        assert_eq!(identity::resolve(Identifier::Agent(99)), None);
+        assert_eq!(
+            identity::resolve(Identifier::Did(b"did:claw:nobody".to_vec())),
+            None
+        );
+    });
+}
+
+#[test]
+fn deregistering_last_agent_marks_did_dormant() {
+    use clawchain_runtime::identity::{self, Identifier};
+
+    new_test_ext(&[poster(), worker()]).execute_with(|| {
+        let mut meter = WorkflowMeter::default();
+        onboard_and_assign(&mut meter);
+        assert_ok!(pallet_agent_did::Pallet::<Runtime>::set_webhook(
+            signed(&worker()),
+            pallet_agent_did::WebhookEventClass::Message,
+            b"https://worker.example/hook".to_vec(),
+        ));
+
+        let agent_id = pallet_agent_registry::OwnerAgents::<Runtime>::get(worker())[0];
+        assert_ok!(pallet_agent_registry::Pallet::<Runtime>::deregister_agent(
+            signed(&worker()),
+            agent_id
+        ));
+
+        let report = pallet_agent_registry::CascadeReports::<Runtime>::get(agent_id).unwrap();
+        assert!(report.did_marked);
+        assert_eq!(report.messaging_endpoints_removed, 1);
+        let did = identity::resolve(Identifier::Account(worker()))
+            .unwrap()
+            .did
+            .unwrap();
+        assert_eq!(did.dormant_since, Some(System::block_number()));
+        assert!(!did.deactivated);
+        assert_eq!(
+            identity::resolve(Identifier::Did(b"did:claw:worker".to_vec())),
+            None
+        );
+    });
+}
+
+#[test]
+fn meter_records_over_quota_fees() {
+    new_test_ext(&[poster(), worker()]).execute_with(|| {
+        let mut meter = WorkflowMeter::default();
+        while meter.fee == 0 {
+            assert!(meter.calls < 100, "free quota never ran out");
+            assert_ok!(meter.dispatch(
+                signed(&poster()),
+                frame_system::Call::<Runtime>::remark { remark: vec![0; 8] },
+            ));
+        }
+
+        assert_eq!(Balances::free_balance(poster()), INITIAL_BALANCE - meter.fee);
+    });
+}
@@ -0,0 +1,47 @@
+//! Typed governance parameter proposals against the full runtime.
+
+use clawchain_integration_tests::{new_test_ext, WorkflowMeter};
+use clawchain_runtime::{
+    AccountId, GovernanceParameter, QuadraticGovernance, Runtime, RuntimeOrigin,
+};
+use frame_support::{assert_noop, assert_ok};
+use pallet_gas_quota::CallClass;
+use sp_keyring::Sr25519Keyring;
+
+fn proposer() -> AccountId {
+    Sr25519Keyring::Alice.to_account_id()
+}
+
+fn class_weight_proposal(weight: u32) -> frame_support::dispatch::DispatchResult {
+    QuadraticGovernance::submit_parameter_proposal(
+        RuntimeOrigin::signed(proposer()),
+        [0u8; 32],
+        GovernanceParameter::SetQuotaClassWeight {
+            class: CallClass::Market,
+            weight,
+        },
+    )
+}
+
+#[test]
+fn out_of_bounds_class_weight_proposals_are_rejected() {
+    new_test_ext(&[proposer()]).execute_with(|| {
+        assert_ok!(WorkflowMeter::default().dispatch(
+            RuntimeOrigin::signed(proposer()),
+            pallet_agent_did::Call::<Runtime>::register_did {
+                context: b"https://www.w3.org/ns/did/v1".to_vec(),
+            },
+        ));
+
+        let max = <Runtime as pallet_gas_quota::Config>::MaxClassWeight::get();
+        for weight in [0, max + 1] {
+            assert_noop!(
+                class_weight_proposal(weight),
+                pallet_quadratic_governance::Error::<Runtime>::InvalidParameterChange
+            );
+        }
+        for weight in [1, max] {
+            assert_ok!(class_weight_proposal(weight));
+        }
+    });
+}
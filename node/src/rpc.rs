@@ -2,9 +2,11 @@
 //!
 //! A collection of RPC extensions for the ClawChain node.
 
+mod gas_quota;
+
 use std::sync::Arc;
 
-use clawchain_runtime::{opaque::Block, AccountId, Balance, Nonce};
+use clawchain_runtime::{opaque::Block, AccountId, Balance, BlockNumber, Nonce};
 use jsonrpsee::RpcModule;
 use sc_transaction_pool_api::TransactionPool;
 use sp_api::ProvideRuntimeApi;
@@ -33,8 +35,10 @@ where
     C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Nonce>,
     C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
     C::Api: BlockBuilder<Block>,
+    C::Api: pallet_gas_quota::runtime_api::GasQuotaApi<Block, AccountId, Balance, BlockNumber>,
     P: TransactionPool + 'static,
 {
+    use gas_quota::{GasQuota, GasQuotaApiServer};
     use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
     use substrate_frame_rpc_system::{System, SystemApiServer};
 
@@ -42,7 +46,8 @@ where
     let FullDeps { client, pool } = deps;
 
     module.merge(System::new(client.clone(), pool).into_rpc())?;
-    module.merge(TransactionPayment::new(client).into_rpc())?;
+    module.merge(TransactionPayment::new(client.clone()).into_rpc())?;
+    module.merge(GasQuota::new(client).into_rpc())?;
 
     Ok(module)
 }
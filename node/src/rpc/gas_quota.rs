@@ -0,0 +1,145 @@
+//! `gasQuota_*` RPC methods, backed by the gas-quota runtime API.
+
+use std::sync::Arc;
+
+use clawchain_runtime::{opaque::Block, AccountId, Balance, BlockNumber};
+use jsonrpsee::{core::RpcResult, proc_macros::rpc, types::ErrorObjectOwned};
+use pallet_gas_quota::runtime_api::GasQuotaApi as GasQuotaRuntimeApi;
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::Block as BlockT;
+
+/// Error code returned when the runtime API call fails.
+const RUNTIME_ERROR: i32 = 1;
+
+/// Free transactions left to an account, e.g. "7/100 free tx left today".
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FreeTx {
+    /// Free transactions left in the current quota day.
+    pub remaining: u32,
+    /// Free transactions per day, including delegated quota.
+    pub free_quota: u32,
+}
+
+/// Quota units spent today in one call class.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClassUsage {
+    /// Call class name, e.g. `Market`.
+    pub class: String,
+    /// Units spent in the class today.
+    pub used: u32,
+}
+
+#[rpc(server)]
+pub trait GasQuotaApi<BlockHash> {
+    /// Free transactions `account` has left today and its daily allowance.
+    #[method(name = "gasQuota_remainingFreeTx")]
+    fn remaining_free_tx(&self, account: AccountId, at: Option<BlockHash>) -> RpcResult<FreeTx>;
+
+    /// Fees `account` would pay for its next `n_tx` transactions today.
+    #[method(name = "gasQuota_projectedFee")]
+    fn projected_fee(
+        &self,
+        account: AccountId,
+        n_tx: u32,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Balance>;
+
+    /// Block at which `account`'s daily quota resets.
+    #[method(name = "gasQuota_quotaResetBlock")]
+    fn quota_reset_block(
+        &self,
+        account: AccountId,
+        at: Option<BlockHash>,
+    ) -> RpcResult<BlockNumber>;
+
+    /// Quota units `account` has spent today in each call class.
+    #[method(name = "gasQuota_classUsage")]
+    fn class_usage(&self, account: AccountId, at: Option<BlockHash>) -> RpcResult<Vec<ClassUsage>>;
+}
+
+/// Serves [`GasQuotaApiServer`] from a client's runtime.
+pub struct GasQuota<C> {
+    client: Arc<C>,
+}
+
+impl<C> GasQuota<C> {
+    pub fn new(client: Arc<C>) -> Self {
+        Self { client }
+    }
+}
+
+impl<C> GasQuotaApiServer<<Block as BlockT>::Hash> for GasQuota<C>
+where
+    C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+    C::Api: GasQuotaRuntimeApi<Block, AccountId, Balance, BlockNumber>,
+{
+    fn remaining_free_tx(
+        &self,
+        account: AccountId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<FreeTx> {
+        let at = at.unwrap_or_else(|| self.client.info().best_hash);
+        let status = self
+            .client
+            .runtime_api()
+            .remaining_free_tx(at, account)
+            .map_err(runtime_error)?;
+        Ok(FreeTx {
+            remaining: status.remaining,
+            free_quota: status.free_quota,
+        })
+    }
+
+    fn projected_fee(
+        &self,
+        account: AccountId,
+        n_tx: u32,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Balance> {
+        let at = at.unwrap_or_else(|| self.client.info().best_hash);
+        self.client
+            .runtime_api()
+            .projected_fee(at, account, n_tx)
+            .map_err(runtime_error)
+    }
+
+    fn quota_reset_block(
+        &self,
+        account: AccountId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<BlockNumber> {
+        let at = at.unwrap_or_else(|| self.client.info().best_hash);
+        self.client
+            .runtime_api()
+            .quota_reset_block(at, account)
+            .map_err(runtime_error)
+    }
+
+    fn class_usage(
+        &self,
+        account: AccountId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Vec<ClassUsage>> {
+        let at = at.unwrap_or_else(|| self.client.info().best_hash);
+        let usage = self
+            .client
+            .runtime_api()
+            .class_usage(at, account)
+            .map_err(runtime_error)?;
+        Ok(usage
+            .into_iter()
+            .map(|(class, used)| ClassUsage {
+                class: format!("{class:?}"),
+                used,
+            })
+            .collect())
+    }
+}
+
+fn runtime_error(err: sp_api::ApiError) -> ErrorObjectOwned {
+    ErrorObjectOwned::owned(RUNTIME_ERROR, "Runtime error", Some(err.to_string()))
+}
@@ -15,6 +15,25 @@
 //! - `deactivate_did` - Permanently deactivate a DID (irreversible)
 //! - `add_service_endpoint` - Add a service endpoint
 //! - `remove_service_endpoint` - Remove a service endpoint
+//! - `set_webhook` - Record a push endpoint hint for an event class
+//! - `confirm_webhook` - Verifier attests the endpoint answered its challenge
+//! - `remove_webhook` - Remove a push endpoint hint
+//!
+//! ## Webhooks
+//!
+//! A DID controller may record one webhook URL per [`WebhookEventClass`] so
+//! relayer/indexer infrastructure knows where to push notifications. Each
+//! registration gets a fresh challenge hash; the endpoint proves control by
+//! serving it, and `WebhookVerifier` marks the hint verified. Relayers should
+//! only deliver to verified endpoints.
+//!
+//! ## Dormant DIDs
+//!
+//! When a controller's last active agent is deregistered, the runtime calls
+//! [`Pallet::mark_dormant`]: the DID stays active but is recorded in
+//! `DormantControllers` and its webhooks are dropped, so nothing is pushed to
+//! an agent that is gone. The mark clears when the controller next updates
+//! its DID or sets a webhook.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(deprecated, clippy::let_unit_value)]
@@ -33,6 +52,7 @@ pub mod pallet {
     use super::*;
     use frame_support::pallet_prelude::*;
     use frame_system::pallet_prelude::*;
+    use sp_runtime::traits::Hash;
 
     // =========================================================
     // Types
@@ -111,6 +131,47 @@ pub mod pallet {
     /// Manual `DecodeWithMemTracking` impl for `DIDDocument`.
     impl<T: Config> codec::DecodeWithMemTracking for DIDDocument<T> {}
 
+    /// Class of on-chain events a webhook receives pushes for.
+    #[derive(
+        Clone,
+        Copy,
+        Encode,
+        Decode,
+        Eq,
+        PartialEq,
+        RuntimeDebug,
+        TypeInfo,
+        MaxEncodedLen,
+        codec::DecodeWithMemTracking,
+    )]
+    pub enum WebhookEventClass {
+        /// Incoming messages.
+        Message,
+        /// Service invocations and their milestones.
+        Invocation,
+        /// Disputes the controller is party to.
+        Dispute,
+    }
+
+    /// A push endpoint hint for one event class.
+    ///
+    /// Same derive reasoning as `ServiceEndpoint`.
+    #[derive(Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    pub struct WebhookEndpoint<T: Config> {
+        /// Endpoint URI.
+        pub url: BoundedVec<u8, T::MaxEndpointLength>,
+        /// Challenge the endpoint must serve to prove control.
+        pub challenge: T::Hash,
+        /// Whether `WebhookVerifier` has confirmed the challenge.
+        pub verified: bool,
+        /// Block number when the hint was recorded.
+        pub registered_at: BlockNumberFor<T>,
+    }
+
+    /// Manual `DecodeWithMemTracking` impl for `WebhookEndpoint`.
+    impl<T: Config> codec::DecodeWithMemTracking for WebhookEndpoint<T> {}
+
     // =========================================================
     // Config
     // =========================================================
@@ -147,6 +208,9 @@ pub mod pallet {
         /// Max number of verification methods per DID.
         #[pallet::constant]
         type MaxVerificationMethods: Get<u32>;
+        /// Origin that attests a webhook endpoint served its challenge
+        /// (e.g. the relayer committee).
+        type WebhookVerifier: EnsureOrigin<Self::RuntimeOrigin>;
     }
 
     // =========================================================
@@ -192,6 +256,24 @@ pub mod pallet {
         OptionQuery,
     >;
 
+    /// Webhook hints: (controller, event class) → WebhookEndpoint.
+    #[pallet::storage]
+    pub type Webhooks<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Twox64Concat,
+        WebhookEventClass,
+        WebhookEndpoint<T>,
+        OptionQuery,
+    >;
+
+    /// Controllers whose last active agent was deregistered, with the block
+    /// that happened.
+    #[pallet::storage]
+    pub type DormantControllers<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, BlockNumberFor<T>, OptionQuery>;
+
     /// Count of active (non-deactivated) DIDs.
     #[pallet::storage]
     #[pallet::getter(fn did_count)]
@@ -221,6 +303,25 @@ pub mod pallet {
             controller: T::AccountId,
             endpoint_id: Vec<u8>,
         },
+        WebhookRegistered {
+            controller: T::AccountId,
+            class: WebhookEventClass,
+            challenge: T::Hash,
+        },
+        WebhookVerified {
+            controller: T::AccountId,
+            class: WebhookEventClass,
+        },
+        WebhookRemoved {
+            controller: T::AccountId,
+            class: WebhookEventClass,
+        },
+        /// The controller has no active agent left; its webhooks were
+        /// dropped.
+        DIDMarkedDormant {
+            controller: T::AccountId,
+            webhooks_removed: u32,
+        },
     }
 
     // =========================================================
@@ -244,6 +345,9 @@ pub mod pallet {
         KeyTypeTooLong,
         KeyTooLong,
         TooManyVerificationMethods,
+        WebhookNotFound,
+        /// The challenge does not match the current registration.
+        ChallengeMismatch,
     }
 
     // =========================================================
@@ -298,6 +402,7 @@ pub mod pallet {
                 doc.updated = <frame_system::Pallet<T>>::block_number();
                 Ok(())
             })?;
+            DormantControllers::<T>::remove(&who);
             Self::deposit_event(Event::DIDUpdated { controller: who });
             Ok(())
         }
@@ -315,6 +420,9 @@ pub mod pallet {
                 doc.updated = <frame_system::Pallet<T>>::block_number();
                 Ok(())
             })?;
+            // One entry per event class at most.
+            let _ = Webhooks::<T>::clear_prefix(&who, 3, None);
+            DormantControllers::<T>::remove(&who);
             DIDCount::<T>::mutate(|n| *n = n.saturating_sub(1));
             Self::deposit_event(Event::DIDDeactivated { controller: who });
             Ok(())
@@ -402,6 +510,130 @@ pub mod pallet {
             });
             Ok(())
         }
+
+        /// Record (or replace) the caller's webhook for `class`.
+        ///
+        /// The new hint is unverified and carries a fresh challenge that the
+        /// endpoint must serve before `WebhookVerifier` confirms it.
+        #[pallet::call_index(5)]
+        #[pallet::weight(T::WeightInfo::set_webhook())]
+        pub fn set_webhook(
+            origin: OriginFor<T>,
+            class: WebhookEventClass,
+            url: Vec<u8>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let url: BoundedVec<u8, T::MaxEndpointLength> =
+                url.try_into().map_err(|_| Error::<T>::EndpointTooLong)?;
+            Self::ensure_active_did(&who)?;
+
+            let now = <frame_system::Pallet<T>>::block_number();
+            let challenge = T::Hashing::hash_of(&(
+                b"claw/webhook",
+                &who,
+                class,
+                &url,
+                now,
+                frame_system::Pallet::<T>::extrinsic_index(),
+            ));
+            Webhooks::<T>::insert(
+                &who,
+                class,
+                WebhookEndpoint::<T> {
+                    url,
+                    challenge,
+                    verified: false,
+                    registered_at: now,
+                },
+            );
+            DormantControllers::<T>::remove(&who);
+            Self::deposit_event(Event::WebhookRegistered {
+                controller: who,
+                class,
+                challenge,
+            });
+            Ok(())
+        }
+
+        /// Mark a webhook verified after its endpoint served `challenge`.
+        ///
+        /// `challenge` must match the current registration, so a confirmation
+        /// for a replaced URL cannot verify its successor.
+        #[pallet::call_index(6)]
+        #[pallet::weight(T::WeightInfo::confirm_webhook())]
+        pub fn confirm_webhook(
+            origin: OriginFor<T>,
+            controller: T::AccountId,
+            class: WebhookEventClass,
+            challenge: T::Hash,
+        ) -> DispatchResult {
+            T::WebhookVerifier::ensure_origin(origin)?;
+            Webhooks::<T>::try_mutate(&controller, class, |maybe| -> DispatchResult {
+                let hook = maybe.as_mut().ok_or(Error::<T>::WebhookNotFound)?;
+                ensure!(hook.challenge == challenge, Error::<T>::ChallengeMismatch);
+                hook.verified = true;
+                Ok(())
+            })?;
+            Self::deposit_event(Event::WebhookVerified { controller, class });
+            Ok(())
+        }
+
+        /// Remove the caller's webhook for `class`.
+        #[pallet::call_index(7)]
+        #[pallet::weight(T::WeightInfo::remove_webhook())]
+        pub fn remove_webhook(origin: OriginFor<T>, class: WebhookEventClass) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(
+                Webhooks::<T>::contains_key(&who, class),
+                Error::<T>::WebhookNotFound
+            );
+            Webhooks::<T>::remove(&who, class);
+            Self::deposit_event(Event::WebhookRemoved {
+                controller: who,
+                class,
+            });
+            Ok(())
+        }
+    }
+
+    // =========================================================
+    // Helpers
+    // =========================================================
+
+    impl<T: Config> Pallet<T> {
+        fn ensure_active_did(who: &T::AccountId) -> DispatchResult {
+            let doc = DIDDocuments::<T>::get(who).ok_or(Error::<T>::DIDNotFound)?;
+            ensure!(!doc.deactivated, Error::<T>::DIDDeactivated);
+            Ok(())
+        }
+
+        /// Mark `controller`'s DID dormant and drop its webhooks. Returns
+        /// the number of webhooks removed, or `None` if the controller has
+        /// no active DID.
+        pub fn mark_dormant(controller: &T::AccountId) -> Option<u32> {
+            Self::ensure_active_did(controller).ok()?;
+            let mut removed = 0u32;
+            for class in [
+                WebhookEventClass::Message,
+                WebhookEventClass::Invocation,
+                WebhookEventClass::Dispute,
+            ] {
+                if Webhooks::<T>::take(controller, class).is_some() {
+                    removed += 1;
+                }
+            }
+            DormantControllers::<T>::insert(controller, <frame_system::Pallet<T>>::block_number());
+            Self::deposit_event(Event::DIDMarkedDormant {
+                controller: controller.clone(),
+                webhooks_removed: removed,
+            });
+            Some(removed)
+        }
+
+        /// Upper bound on the weight of [`Self::mark_dormant`].
+        pub fn mark_dormant_weight() -> Weight {
+            T::DbWeight::get().reads_writes(1, 4)
+        }
     }
 
     // =========================================================
@@ -414,6 +646,9 @@ pub mod pallet {
         fn deactivate_did() -> Weight;
         fn add_service_endpoint() -> Weight;
         fn remove_service_endpoint() -> Weight;
+        fn set_webhook() -> Weight;
+        fn confirm_webhook() -> Weight;
+        fn remove_webhook() -> Weight;
     }
 
     impl WeightInfo for () {
@@ -432,5 +667,14 @@ pub mod pallet {
         fn remove_service_endpoint() -> Weight {
             Weight::from_parts(10_000, 0)
         }
+        fn set_webhook() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn confirm_webhook() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn remove_webhook() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
     }
 }
@@ -1,7 +1,9 @@
 //! Unit tests for the Agent DID pallet.
 
 use crate as pallet_agent_did;
-use crate::pallet::{DIDCount, DIDDocuments, ServiceEndpoints};
+use crate::pallet::{
+    DIDCount, DIDDocuments, DormantControllers, ServiceEndpoints, WebhookEventClass, Webhooks,
+};
 use frame_support::{assert_noop, assert_ok, derive_impl, traits::ConstU32};
 use sp_runtime::{traits::IdentityLookup, BuildStorage};
 
@@ -33,6 +35,7 @@ impl pallet_agent_did::Config for Test {
     type MaxKeyTypeLength = ConstU32<128>;
     type MaxKeyLength = ConstU32<256>;
     type MaxVerificationMethods = ConstU32<5>;
+    type WebhookVerifier = frame_system::EnsureRoot<u64>;
 }
 
 fn new_test_ext() -> sp_io::TestExternalities {
@@ -400,3 +403,119 @@ fn service_endpoints_are_account_scoped() {
         );
     });
 }
+
+// ========================= webhooks =========================
+
+#[test]
+fn set_webhook_requires_active_did() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            AgentDID::set_webhook(
+                signed(1),
+                WebhookEventClass::Message,
+                b"https://hook".to_vec()
+            ),
+            crate::pallet::Error::<Test>::DIDNotFound
+        );
+
+        assert_ok!(AgentDID::register_did(signed(1), b"".to_vec()));
+        assert_ok!(AgentDID::set_webhook(
+            signed(1),
+            WebhookEventClass::Message,
+            b"https://hook".to_vec()
+        ));
+        let hook = Webhooks::<Test>::get(1u64, WebhookEventClass::Message).unwrap();
+        assert_eq!(hook.url.to_vec(), b"https://hook".to_vec());
+        assert!(!hook.verified);
+
+        // Deactivation drops the hints.
+        assert_ok!(AgentDID::deactivate_did(signed(1)));
+        assert!(Webhooks::<Test>::get(1u64, WebhookEventClass::Message).is_none());
+    });
+}
+
+#[test]
+fn confirm_webhook_checks_current_challenge() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(AgentDID::register_did(signed(1), b"".to_vec()));
+        assert_ok!(AgentDID::set_webhook(
+            signed(1),
+            WebhookEventClass::Dispute,
+            b"https://old".to_vec()
+        ));
+        let old = Webhooks::<Test>::get(1u64, WebhookEventClass::Dispute)
+            .unwrap()
+            .challenge;
+
+        System::set_block_number(2);
+        assert_ok!(AgentDID::set_webhook(
+            signed(1),
+            WebhookEventClass::Dispute,
+            b"https://new".to_vec()
+        ));
+        let new = Webhooks::<Test>::get(1u64, WebhookEventClass::Dispute)
+            .unwrap()
+            .challenge;
+        assert_ne!(old, new);
+
+        assert_noop!(
+            AgentDID::confirm_webhook(signed(2), 1, WebhookEventClass::Dispute, new),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_noop!(
+            AgentDID::confirm_webhook(
+                frame_system::RawOrigin::Root.into(),
+                1,
+                WebhookEventClass::Dispute,
+                old
+            ),
+            crate::pallet::Error::<Test>::ChallengeMismatch
+        );
+        assert_ok!(AgentDID::confirm_webhook(
+            frame_system::RawOrigin::Root.into(),
+            1,
+            WebhookEventClass::Dispute,
+            new
+        ));
+        assert!(
+            Webhooks::<Test>::get(1u64, WebhookEventClass::Dispute)
+                .unwrap()
+                .verified
+        );
+
+        assert_ok!(AgentDID::remove_webhook(
+            signed(1),
+            WebhookEventClass::Dispute
+        ));
+        assert_noop!(
+            AgentDID::remove_webhook(signed(1), WebhookEventClass::Dispute),
+            crate::pallet::Error::<Test>::WebhookNotFound
+        );
+    });
+}
+
+#[test]
+fn mark_dormant_drops_webhooks_until_controller_returns() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(AgentDID::mark_dormant(&1), None);
+
+        assert_ok!(AgentDID::register_did(signed(1), b"".to_vec()));
+        for class in [WebhookEventClass::Message, WebhookEventClass::Invocation] {
+            assert_ok!(AgentDID::set_webhook(
+                signed(1),
+                class,
+                b"https://hook".to_vec()
+            ));
+        }
+
+        System::set_block_number(5);
+        assert_eq!(AgentDID::mark_dormant(&1), Some(2));
+        assert_eq!(DormantControllers::<Test>::get(1u64), Some(5));
+        assert!(Webhooks::<Test>::iter_prefix(1u64).next().is_none());
+        // The DID itself stays active.
+        assert!(!DIDDocuments::<Test>::get(1u64).unwrap().deactivated);
+
+        assert_ok!(AgentDID::update_did(signed(1), b"back".to_vec()));
+        assert!(DormantControllers::<Test>::get(1u64).is_none());
+    });
+}
@@ -15,6 +15,14 @@
 //!
 //! - `submit_receipt` - Submit a new activity receipt for an agent
 //! - `clear_old_receipts` - Prune old receipts before a given nonce
+//!
+//! ### Cross-references
+//!
+//! A receipt may reference the service invocations, messages and governance
+//! proposals it relates to ([`ReceiptRef`]). Each reference is checked with
+//! `T::ReferenceValidator` at submission and indexed in both directions, so
+//! provenance can be followed from a receipt to its sources and back through
+//! [`runtime_api::AgentReceiptsApi`].
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(deprecated, clippy::let_unit_value)]
@@ -23,12 +31,15 @@ extern crate alloc;
 
 pub use pallet::*;
 
+pub mod runtime_api;
+
 #[cfg(test)]
 mod tests;
 
 use alloc::vec::Vec;
 
 #[frame_support::pallet]
+#[allow(clippy::too_many_arguments)]
 pub mod pallet {
     use super::*;
     use frame_support::pallet_prelude::*;
@@ -58,6 +69,40 @@ pub mod pallet {
         pub timestamp: u64,
     }
 
+    /// A typed link from a receipt to an object in another pallet.
+    #[derive(
+        Clone,
+        Copy,
+        Encode,
+        Decode,
+        Eq,
+        PartialEq,
+        RuntimeDebug,
+        TypeInfo,
+        MaxEncodedLen,
+        codec::DecodeWithMemTracking,
+    )]
+    pub enum ReceiptRef {
+        /// A `pallet-service-market` invocation.
+        Invocation(u64),
+        /// A `pallet-anon-messaging` message.
+        Message(u64),
+        /// A `pallet-quadratic-governance` proposal.
+        Proposal(u64),
+    }
+
+    /// Checks that a referenced object exists in its source pallet.
+    pub trait ReferenceValidator {
+        fn exists(reference: &ReceiptRef) -> bool;
+    }
+
+    /// No source pallets wired — every reference is rejected.
+    impl ReferenceValidator for () {
+        fn exists(_reference: &ReceiptRef) -> bool {
+            false
+        }
+    }
+
     /// The pallet's configuration trait.
     #[pallet::config]
     pub trait Config: frame_system::Config {
@@ -78,6 +123,13 @@ pub mod pallet {
 
         /// Weight information for extrinsics in this pallet.
         type WeightInfo: WeightInfo;
+
+        /// Maximum number of references on a single receipt.
+        #[pallet::constant]
+        type MaxReferences: Get<u32>;
+
+        /// Validates receipt references against their source pallets.
+        type ReferenceValidator: ReferenceValidator;
     }
 
     #[pallet::pallet]
@@ -108,6 +160,30 @@ pub mod pallet {
     #[pallet::getter(fn receipt_count)]
     pub type ReceiptCount<T: Config> = StorageValue<_, u64, ValueQuery>;
 
+    /// References attached to each receipt, by (AgentId, nonce).
+    #[pallet::storage]
+    pub type ReceiptReferences<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        AgentIdOf<T>,
+        Blake2_128Concat,
+        u64,
+        BoundedVec<ReceiptRef, T::MaxReferences>,
+        OptionQuery,
+    >;
+
+    /// Reverse index: receipts that reference an object.
+    #[pallet::storage]
+    pub type ReceiptsByReference<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        ReceiptRef,
+        Blake2_128Concat,
+        (AgentIdOf<T>, u64),
+        (),
+        OptionQuery,
+    >;
+
     // ========== Events ==========
 
     #[pallet::event]
@@ -134,6 +210,12 @@ pub mod pallet {
         ActionTypeTooLong,
         /// The metadata exceeds the maximum allowed length.
         MetadataTooLong,
+        /// More than `MaxReferences` references were given.
+        TooManyReferences,
+        /// A referenced object does not exist in its source pallet.
+        InvalidReference,
+        /// The same reference was given twice.
+        DuplicateReference,
     }
 
     // ========== Extrinsics ==========
@@ -152,8 +234,14 @@ pub mod pallet {
         /// * `output_hash` - H256 hash of the action's outputs
         /// * `metadata` - Optional JSON context
         /// * `timestamp` - Caller-provided UNIX timestamp (ms)
+        /// * `references` - Invocations, messages or proposals this receipt relates to
         #[pallet::call_index(0)]
-        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 3))]
+        #[pallet::weight(
+            Weight::from_parts(10_000, 0)
+                + T::DbWeight::get().reads_writes(2, 3)
+                + T::DbWeight::get()
+                    .reads_writes(references.len() as u64, references.len() as u64 + 1)
+        )]
         pub fn submit_receipt(
             origin: OriginFor<T>,
             agent_id: Vec<u8>,
@@ -162,6 +250,7 @@ pub mod pallet {
             output_hash: H256,
             metadata: Vec<u8>,
             timestamp: u64,
+            references: Vec<ReceiptRef>,
         ) -> DispatchResult {
             ensure_signed(origin)?;
 
@@ -176,6 +265,19 @@ pub mod pallet {
             let bounded_metadata: BoundedVec<u8, T::MaxMetadataLen> = metadata
                 .try_into()
                 .map_err(|_| Error::<T>::MetadataTooLong)?;
+            let bounded_references: BoundedVec<ReceiptRef, T::MaxReferences> = references
+                .try_into()
+                .map_err(|_| Error::<T>::TooManyReferences)?;
+            for (i, reference) in bounded_references.iter().enumerate() {
+                ensure!(
+                    !bounded_references[..i].contains(reference),
+                    Error::<T>::DuplicateReference
+                );
+                ensure!(
+                    T::ReferenceValidator::exists(reference),
+                    Error::<T>::InvalidReference
+                );
+            }
 
             let current_block = <frame_system::Pallet<T>>::block_number();
             let nonce = AgentNonce::<T>::get(&bounded_agent_id);
@@ -193,6 +295,18 @@ pub mod pallet {
             // Store the receipt
             Receipts::<T>::insert(&bounded_agent_id, nonce, receipt);
 
+            // Index references in both directions
+            if !bounded_references.is_empty() {
+                for reference in &bounded_references {
+                    ReceiptsByReference::<T>::insert(
+                        reference,
+                        (bounded_agent_id.clone(), nonce),
+                        (),
+                    );
+                }
+                ReceiptReferences::<T>::insert(&bounded_agent_id, nonce, bounded_references);
+            }
+
             // Increment per-agent nonce
             AgentNonce::<T>::insert(&bounded_agent_id, nonce.saturating_add(1));
 
@@ -235,6 +349,7 @@ pub mod pallet {
             for nonce in 0..before_nonce {
                 if Receipts::<T>::contains_key(&bounded_agent_id, nonce) {
                     Receipts::<T>::remove(&bounded_agent_id, nonce);
+                    Self::remove_references(&bounded_agent_id, nonce);
                     cleared = cleared.saturating_add(1);
                 }
             }
@@ -248,6 +363,36 @@ pub mod pallet {
         }
     }
 
+    // ========== Helpers ==========
+
+    impl<T: Config> Pallet<T> {
+        /// Drop a receipt's references and their reverse-index entries.
+        fn remove_references(agent_id: &AgentIdOf<T>, nonce: u64) {
+            if let Some(references) = ReceiptReferences::<T>::take(agent_id, nonce) {
+                for reference in references {
+                    ReceiptsByReference::<T>::remove(reference, (agent_id.clone(), nonce));
+                }
+            }
+        }
+
+        /// Objects referenced by a receipt, for the runtime API.
+        pub fn references_of(agent_id: &[u8], nonce: u64) -> Vec<ReceiptRef> {
+            let Ok(agent_id) = AgentIdOf::<T>::try_from(agent_id.to_vec()) else {
+                return Vec::new();
+            };
+            ReceiptReferences::<T>::get(agent_id, nonce)
+                .map(|references| references.into_inner())
+                .unwrap_or_default()
+        }
+
+        /// Receipts (agent ID, nonce) that reference `reference`, for the runtime API.
+        pub fn receipts_referencing(reference: ReceiptRef) -> Vec<(Vec<u8>, u64)> {
+            ReceiptsByReference::<T>::iter_key_prefix(reference)
+                .map(|(agent_id, nonce)| (agent_id.into_inner(), nonce))
+                .collect()
+        }
+    }
+
     // ========== Weight Info Trait ==========
 
     /// Weight information for the pallet's extrinsics.
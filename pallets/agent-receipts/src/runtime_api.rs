@@ -0,0 +1,15 @@
+//! Runtime API for following receipt provenance off-chain.
+
+use crate::ReceiptRef;
+use alloc::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+    /// Provenance queries over receipt cross-references.
+    pub trait AgentReceiptsApi {
+        /// Objects referenced by the receipt at (`agent_id`, `nonce`).
+        fn references_of(agent_id: Vec<u8>, nonce: u64) -> Vec<ReceiptRef>;
+
+        /// Receipts, as (agent ID, nonce), that reference `reference`.
+        fn receipts_referencing(reference: ReceiptRef) -> Vec<(Vec<u8>, u64)>;
+    }
+}
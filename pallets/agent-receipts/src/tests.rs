@@ -1,16 +1,13 @@
 //! Unit tests for the Agent Receipts pallet.
 
 use crate as pallet_agent_receipts;
-use crate::pallet::{AgentNonce, ReceiptCount, Receipts};
-use frame_support::{
-    assert_noop, assert_ok, derive_impl,
-    traits::{ConstU32, ConstU64},
+use crate::pallet::{
+    AgentNonce, ReceiptCount, ReceiptRef, ReceiptReferences, Receipts, ReceiptsByReference,
+    ReferenceValidator,
 };
+use frame_support::{assert_noop, assert_ok, derive_impl, traits::ConstU32};
 use sp_core::H256;
-use sp_runtime::{
-    traits::{BlakeTwo256, IdentityLookup},
-    BuildStorage,
-};
+use sp_runtime::{traits::IdentityLookup, BuildStorage, DispatchResult};
 
 type Block = frame_system::mocking::MockBlock<Test>;
 
@@ -35,6 +32,21 @@ impl pallet_agent_receipts::Config for Test {
     type MaxAgentIdLen = ConstU32<64>;
     type MaxActionTypeLen = ConstU32<64>;
     type MaxMetadataLen = ConstU32<512>;
+    type MaxReferences = ConstU32<2>;
+    type ReferenceValidator = MockReferenceValidator;
+}
+
+/// Treats IDs below 10 as existing in every source pallet.
+pub struct MockReferenceValidator;
+
+impl ReferenceValidator for MockReferenceValidator {
+    fn exists(reference: &ReceiptRef) -> bool {
+        match reference {
+            ReceiptRef::Invocation(id) | ReceiptRef::Message(id) | ReceiptRef::Proposal(id) => {
+                *id < 10
+            }
+        }
+    }
 }
 
 // Build test externalities from genesis storage.
@@ -75,6 +87,7 @@ fn submit_receipt_works() {
             output_hash,
             metadata.clone(),
             timestamp,
+            vec![],
         ));
 
         // Check receipt was stored at nonce 0
@@ -111,6 +124,7 @@ fn submit_receipt_increments_nonce() {
             H256::repeat_byte(0x02),
             b"".to_vec(),
             1000,
+            vec![],
         ));
         assert_eq!(AgentNonce::<Test>::get(&bid), 1);
 
@@ -123,6 +137,7 @@ fn submit_receipt_increments_nonce() {
             H256::repeat_byte(0x04),
             b"".to_vec(),
             2000,
+            vec![],
         ));
         assert_eq!(AgentNonce::<Test>::get(&bid), 2);
 
@@ -135,6 +150,7 @@ fn submit_receipt_increments_nonce() {
             H256::repeat_byte(0x06),
             b"".to_vec(),
             3000,
+            vec![],
         ));
         assert_eq!(AgentNonce::<Test>::get(&bid), 3);
     });
@@ -155,6 +171,7 @@ fn submit_multiple_receipts_same_agent() {
                 H256::repeat_byte(i + 100),
                 b"{}".to_vec(),
                 (i as u64) * 1000,
+                vec![],
             ));
         }
 
@@ -200,6 +217,7 @@ fn receipt_stored_correctly() {
             output_hash,
             metadata.clone(),
             timestamp,
+            vec![],
         ));
 
         let bid = bounded_agent_id(b"verification-agent");
@@ -236,6 +254,7 @@ fn clear_old_receipts_works() {
                 H256::repeat_byte(i),
                 b"".to_vec(),
                 (i as u64) * 1000,
+                vec![],
             ));
         }
 
@@ -279,6 +298,7 @@ fn submit_receipt_fails_with_too_long_agent_id() {
                 H256::zero(),
                 b"".to_vec(),
                 0,
+                vec![],
             ),
             crate::Error::<Test>::AgentIdTooLong
         );
@@ -299,6 +319,7 @@ fn submit_receipt_fails_with_too_long_action_type() {
                 H256::zero(),
                 b"".to_vec(),
                 0,
+                vec![],
             ),
             crate::Error::<Test>::ActionTypeTooLong
         );
@@ -319,6 +340,7 @@ fn submit_receipt_fails_with_too_long_metadata() {
                 H256::zero(),
                 metadata,
                 0,
+                vec![],
             ),
             crate::Error::<Test>::MetadataTooLong
         );
@@ -343,6 +365,7 @@ fn different_agents_have_independent_nonces() {
                 H256::zero(),
                 b"".to_vec(),
                 0,
+                vec![],
             ));
         }
 
@@ -355,6 +378,7 @@ fn different_agents_have_independent_nonces() {
             H256::zero(),
             b"".to_vec(),
             0,
+            vec![],
         ));
 
         assert_eq!(AgentNonce::<Test>::get(&bid_a), 3);
@@ -364,3 +388,83 @@ fn different_agents_have_independent_nonces() {
         assert_eq!(ReceiptCount::<Test>::get(), 4);
     });
 }
+
+fn submit_with_references(agent_id: &[u8], references: Vec<ReceiptRef>) -> DispatchResult {
+    AgentReceiptsPallet::submit_receipt(
+        account(1),
+        agent_id.to_vec(),
+        b"service_call".to_vec(),
+        H256::zero(),
+        H256::zero(),
+        b"".to_vec(),
+        0,
+        references,
+    )
+}
+
+#[test]
+fn receipt_references_are_validated() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            submit_with_references(b"agent", vec![ReceiptRef::Invocation(10)]),
+            crate::Error::<Test>::InvalidReference
+        );
+        assert_noop!(
+            submit_with_references(
+                b"agent",
+                vec![ReceiptRef::Message(1), ReceiptRef::Message(1)]
+            ),
+            crate::Error::<Test>::DuplicateReference
+        );
+        assert_noop!(
+            submit_with_references(
+                b"agent",
+                vec![
+                    ReceiptRef::Message(1),
+                    ReceiptRef::Proposal(1),
+                    ReceiptRef::Invocation(1)
+                ]
+            ),
+            crate::Error::<Test>::TooManyReferences
+        );
+    });
+}
+
+#[test]
+fn receipt_references_are_indexed_both_ways_and_pruned() {
+    new_test_ext().execute_with(|| {
+        let invocation = ReceiptRef::Invocation(3);
+        assert_ok!(submit_with_references(
+            b"agent-a",
+            vec![invocation, ReceiptRef::Proposal(7)]
+        ));
+        assert_ok!(submit_with_references(b"agent-b", vec![invocation]));
+
+        assert_eq!(
+            AgentReceiptsPallet::references_of(b"agent-a", 0),
+            vec![invocation, ReceiptRef::Proposal(7)]
+        );
+        let mut referencing = AgentReceiptsPallet::receipts_referencing(invocation);
+        referencing.sort();
+        assert_eq!(
+            referencing,
+            vec![(b"agent-a".to_vec(), 0), (b"agent-b".to_vec(), 0)]
+        );
+
+        assert_ok!(AgentReceiptsPallet::clear_old_receipts(
+            account(1),
+            b"agent-a".to_vec(),
+            1,
+        ));
+        let agent_a = bounded_agent_id(b"agent-a");
+        assert!(ReceiptReferences::<Test>::get(&agent_a, 0).is_none());
+        assert!(!ReceiptsByReference::<Test>::contains_key(
+            ReceiptRef::Proposal(7),
+            (agent_a, 0)
+        ));
+        assert_eq!(
+            AgentReceiptsPallet::receipts_referencing(invocation),
+            vec![(b"agent-b".to_vec(), 0)]
+        );
+    });
+}
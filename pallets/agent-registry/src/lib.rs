@@ -19,6 +19,38 @@
 //! - `update_reputation` - Adjust an agent's reputation score
 //! - `deregister_agent` - Remove an agent from the registry
 //! - `set_agent_status` - Change an agent's status
+//! - `grant_registrar` / `revoke_registrar` - Governance manages delegated registrars
+//! - `register_agents_for` - A registrar batch-registers agents on behalf of owners
+//!
+//! ### Delegated Onboarding
+//!
+//! Registrars hold a quota of registrations. Each entry in a
+//! `register_agents_for` batch carries the owner's signature over
+//! `(REGISTRATION_CONTEXT, genesis_hash, registrar, nonce, did, metadata)`,
+//! where `nonce` is the owner's current `RegistrationNonces` value, so a
+//! signature cannot be replayed, used by a different registrar or used on
+//! another chain. A batch holds at most `MaxBatchRegistrations` entries.
+//!
+//! ### Onboarding Fee Allowance
+//!
+//! A brand-new owner (no on-chain account yet) registered through
+//! `register_agents_for` receives a one-off `OnboardingFeeAllowance`, so it can
+//! pay for its first transactions without holding CLAW. The allowance is
+//! bookkeeping only: the runtime charges fees to the treasury while
+//! [`Pallet::consume_fee_allowance`] covers them, and nothing can be
+//! transferred out of it. Each account is granted at most once, and at most
+//! `MaxOnboardingGrantsPerPeriod` grants are issued per `OnboardingPeriod`.
+//!
+//! ### Deregistration Cascade
+//!
+//! `deregister_agent` runs the runtime's [`AgentLifecycleHook`] for immediate
+//! effects in other pallets and queues the agent in `CleanupQueue`. Remaining
+//! cleanup runs in `on_idle`, in order and within the block's spare weight,
+//! possibly over several blocks. When the queue is full the agent waits in
+//! `DeferredCleanups` and moves into the queue as room frees up, so a full
+//! queue never blocks a deregistration. `AgentCleanupCompleted` is emitted
+//! once an agent's cascade has finished, with a [`CascadeReport`] of what
+//! every step did.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(deprecated, clippy::let_unit_value)]
@@ -27,20 +59,91 @@ extern crate alloc;
 
 pub use pallet::*;
 
+pub mod migrations;
 #[cfg(test)]
 mod tests;
 
 use alloc::vec::Vec;
+use frame_support::weights::Weight;
+
+/// Cross-pallet reaction to an agent leaving the registry.
+///
+/// The runtime implements this to pause the owner's service listings, block
+/// new invocations, mark DID and messaging state and drop quota delegations.
+/// Each step records what it did in the agent's [`pallet::CascadeReport`].
+pub trait AgentLifecycleHook<AccountId> {
+    /// Immediate, bounded effects of deregistering `agent_id`, run inside
+    /// `deregister_agent`.
+    fn on_deregistered(
+        agent_id: pallet::AgentId,
+        owner: &AccountId,
+        report: &mut pallet::CascadeReport,
+    );
+
+    /// Upper bound on the weight of [`Self::on_deregistered`].
+    fn on_deregistered_weight() -> Weight;
+
+    /// Continue cleaning up after `agent_id` using at most `max_weight`.
+    /// Returns the weight used and whether cleanup is finished.
+    fn cleanup(
+        agent_id: pallet::AgentId,
+        owner: &AccountId,
+        max_weight: Weight,
+        report: &mut pallet::CascadeReport,
+    ) -> (Weight, bool);
+}
+
+impl<AccountId> AgentLifecycleHook<AccountId> for () {
+    fn on_deregistered(
+        _agent_id: pallet::AgentId,
+        _owner: &AccountId,
+        _report: &mut pallet::CascadeReport,
+    ) {
+    }
+
+    fn on_deregistered_weight() -> Weight {
+        Weight::zero()
+    }
+
+    fn cleanup(
+        _agent_id: pallet::AgentId,
+        _owner: &AccountId,
+        _max_weight: Weight,
+        _report: &mut pallet::CascadeReport,
+    ) -> (Weight, bool) {
+        (Weight::zero(), true)
+    }
+}
 
 #[frame_support::pallet]
 pub mod pallet {
     use super::*;
-    use frame_support::pallet_prelude::*;
+    use frame_support::{pallet_prelude::*, traits::Currency};
     use frame_system::pallet_prelude::*;
+    use sp_runtime::traits::{IdentifyAccount, Saturating, Verify, Zero};
 
     /// Type alias for agent IDs (sequential u64).
     pub type AgentId = u64;
 
+    /// Balance type of the currency the onboarding fee allowance is denominated in.
+    pub type BalanceOf<T> =
+        <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+    /// Domain separator for owner signatures authorising delegated registration.
+    pub const REGISTRATION_CONTEXT: &[u8] = b"clawchain/register-agent";
+
+    /// Ref time of checking one owner signature in `register_agents_for`,
+    /// sized for an sr25519 verification until the call is benchmarked.
+    pub const OWNER_SIGNATURE_WEIGHT: Weight = Weight::from_parts(50_000_000, 0);
+
+    /// A single delegated registration: owner, DID, metadata and the owner's signature.
+    pub type DelegatedRegistration<T> = (
+        <T as frame_system::Config>::AccountId,
+        Vec<u8>,
+        Vec<u8>,
+        <T as Config>::OffchainSignature,
+    );
+
     /// Agent status enum.
     #[derive(
         Clone,
@@ -64,6 +167,29 @@ pub mod pallet {
         Deregistered,
     }
 
+    /// What the deregistration cascade did for one agent, across every block
+    /// its cleanup ran in.
+    #[derive(
+        Clone,
+        Encode,
+        Decode,
+        Eq,
+        PartialEq,
+        RuntimeDebug,
+        TypeInfo,
+        MaxEncodedLen,
+        Default,
+        codec::DecodeWithMemTracking,
+    )]
+    pub struct CascadeReport {
+        /// The owner's DID was marked dormant.
+        pub did_marked: bool,
+        /// Messaging endpoints (DID webhooks) dropped.
+        pub messaging_endpoints_removed: u32,
+        /// Quota delegations revoked.
+        pub delegations_revoked: u32,
+    }
+
     /// Core agent information stored on-chain.
     #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
     #[scale_info(skip_type_params(T))]
@@ -84,6 +210,26 @@ pub mod pallet {
         pub status: AgentStatus,
     }
 
+    /// A registrar's onboarding allowance.
+    #[derive(
+        Clone,
+        Encode,
+        Decode,
+        Eq,
+        PartialEq,
+        RuntimeDebug,
+        TypeInfo,
+        MaxEncodedLen,
+        Default,
+        codec::DecodeWithMemTracking,
+    )]
+    pub struct RegistrarInfo {
+        /// Total registrations this registrar may perform.
+        pub quota: u32,
+        /// Registrations performed so far.
+        pub used: u32,
+    }
+
     /// The pallet's configuration trait.
     #[pallet::config]
     pub trait Config: frame_system::Config {
@@ -104,9 +250,48 @@ pub mod pallet {
         /// Maximum number of agents a single account can own.
         #[pallet::constant]
         type MaxAgentsPerOwner: Get<u32>;
+
+        /// Origin allowed to grant and revoke the registrar role.
+        type RegistrarOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Signature type used by owners to authorise delegated registration.
+        type OffchainSignature: Verify<Signer = Self::SigningPublicKey> + Parameter;
+
+        /// Public key type resolving to an owner account.
+        type SigningPublicKey: IdentifyAccount<AccountId = Self::AccountId>;
+
+        /// Maximum registrations in a single `register_agents_for` batch.
+        #[pallet::constant]
+        type MaxBatchRegistrations: Get<u32>;
+
+        /// Currency the onboarding fee allowance is denominated in.
+        type Currency: Currency<Self::AccountId>;
+
+        /// Fee allowance granted to a new owner registered by a registrar.
+        /// Zero disables onboarding grants.
+        #[pallet::constant]
+        type OnboardingFeeAllowance: Get<BalanceOf<Self>>;
+
+        /// Maximum onboarding grants issued per `OnboardingPeriod`.
+        #[pallet::constant]
+        type MaxOnboardingGrantsPerPeriod: Get<u32>;
+
+        /// Length in blocks of an onboarding rate-limit window.
+        #[pallet::constant]
+        type OnboardingPeriod: Get<BlockNumberFor<Self>>;
+
+        /// Cleanup other pallets run when an agent is deregistered.
+        type LifecycleHook: AgentLifecycleHook<Self::AccountId>;
+
+        /// Maximum deregistered agents awaiting cleanup at once.
+        #[pallet::constant]
+        type MaxPendingCleanups: Get<u32>;
     }
 
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
     #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
     // ========== Storage ==========
@@ -133,6 +318,57 @@ pub mod pallet {
         ValueQuery,
     >;
 
+    /// The first agent registered under each DID, for DID lookups. Cleared
+    /// when that agent is deregistered, so a deregistered agent no longer
+    /// resolves; the next agent registered under the DID takes its place.
+    #[pallet::storage]
+    pub type AgentsByDid<T: Config> =
+        StorageMap<_, Blake2_128Concat, BoundedVec<u8, T::MaxDidLength>, AgentId, OptionQuery>;
+
+    /// Accounts holding the registrar role, with their quota.
+    #[pallet::storage]
+    pub type Registrars<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, RegistrarInfo, OptionQuery>;
+
+    /// Per-owner nonce included in delegated registration signatures.
+    #[pallet::storage]
+    pub type RegistrationNonces<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+    /// Remaining sponsored fee allowance per account.
+    #[pallet::storage]
+    pub type FeeAllowances<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>, OptionQuery>;
+
+    /// Accounts that have received an onboarding grant, with the grant block.
+    /// Never cleared, so an account is sponsored at most once.
+    #[pallet::storage]
+    pub type OnboardedAccounts<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, BlockNumberFor<T>, OptionQuery>;
+
+    /// Start of the current onboarding rate-limit window and grants issued in it.
+    #[pallet::storage]
+    pub type OnboardingWindow<T: Config> = StorageValue<_, (BlockNumberFor<T>, u32), ValueQuery>;
+
+    /// Deregistered agents whose cross-pallet cleanup has not finished, oldest first.
+    #[pallet::storage]
+    pub type CleanupQueue<T: Config> =
+        StorageValue<_, BoundedVec<AgentId, T::MaxPendingCleanups>, ValueQuery>;
+
+    /// Deregistered agents waiting for room in `CleanupQueue`, by arrival
+    /// index.
+    #[pallet::storage]
+    pub type DeferredCleanups<T: Config> = StorageMap<_, Twox64Concat, u32, AgentId, OptionQuery>;
+
+    /// Arrival indexes of the oldest and next `DeferredCleanups` entries.
+    #[pallet::storage]
+    pub type DeferredCleanupRange<T: Config> = StorageValue<_, (u32, u32), ValueQuery>;
+
+    /// What the cascade has done so far for agents still being cleaned up.
+    #[pallet::storage]
+    pub type CascadeReports<T: Config> =
+        StorageMap<_, Twox64Concat, AgentId, CascadeReport, OptionQuery>;
+
     // ========== Events ==========
 
     #[pallet::event]
@@ -162,6 +398,32 @@ pub mod pallet {
             agent_id: AgentId,
             status: AgentStatus,
         },
+        /// An account was granted (or re-granted) the registrar role.
+        RegistrarGranted { registrar: T::AccountId, quota: u32 },
+        /// An account's registrar role was revoked.
+        RegistrarRevoked { registrar: T::AccountId },
+        /// A registrar registered an agent on behalf of its owner.
+        AgentRegisteredByRegistrar {
+            agent_id: AgentId,
+            registrar: T::AccountId,
+            owner: T::AccountId,
+        },
+        /// A new owner received a sponsored fee allowance.
+        FeeAllowanceGranted {
+            owner: T::AccountId,
+            amount: BalanceOf<T>,
+        },
+        /// An account's fee allowance was used up or dropped.
+        FeeAllowanceExhausted { owner: T::AccountId },
+        /// The cleanup queue was full; the agent's cleanup waits in
+        /// `DeferredCleanups`.
+        AgentCleanupDeferred { agent_id: AgentId },
+        /// Every pallet finished cleaning up after a deregistered agent.
+        AgentCleanupCompleted {
+            agent_id: AgentId,
+            owner: T::AccountId,
+            report: CascadeReport,
+        },
     }
 
     // ========== Errors ==========
@@ -184,6 +446,83 @@ pub mod pallet {
         ReputationOverflow,
         /// Reputation score would underflow (min 0).
         ReputationUnderflow,
+        /// The caller does not hold the registrar role.
+        NotRegistrar,
+        /// The batch would exceed the registrar's remaining quota.
+        RegistrarQuotaExceeded,
+        /// An owner signature does not authorise this registration.
+        InvalidOwnerSignature,
+    }
+
+    // ========== Hooks ==========
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_idle(_n: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+            let db = T::DbWeight::get();
+            let mut used = db.reads(2);
+            if remaining_weight.all_lte(used) {
+                return Weight::zero();
+            }
+
+            let mut queue = CleanupQueue::<T>::get();
+            let mut finished = 0usize;
+            for &agent_id in queue.iter() {
+                used = used.saturating_add(db.reads_writes(2, 1));
+                if remaining_weight.all_lte(used) {
+                    break;
+                }
+                let Some(agent) = AgentRegistry::<T>::get(agent_id) else {
+                    CascadeReports::<T>::remove(agent_id);
+                    finished += 1;
+                    continue;
+                };
+
+                let mut report = CascadeReports::<T>::get(agent_id).unwrap_or_default();
+                let (weight, done) = T::LifecycleHook::cleanup(
+                    agent_id,
+                    &agent.owner,
+                    remaining_weight.saturating_sub(used),
+                    &mut report,
+                );
+                used = used.saturating_add(weight);
+                if !done {
+                    CascadeReports::<T>::insert(agent_id, report);
+                    break;
+                }
+                CascadeReports::<T>::remove(agent_id);
+                finished += 1;
+                Self::deposit_event(Event::AgentCleanupCompleted {
+                    agent_id,
+                    owner: agent.owner,
+                    report,
+                });
+            }
+            queue.drain(..finished);
+            let mut changed = finished > 0;
+
+            // Move deferred agents into the room just freed, oldest first.
+            let (mut head, tail) = DeferredCleanupRange::<T>::get();
+            let step = db.reads_writes(1, 1);
+            while head != tail && queue.len() < T::MaxPendingCleanups::get() as usize {
+                if remaining_weight.any_lt(used.saturating_add(step)) {
+                    break;
+                }
+                used = used.saturating_add(step);
+                if let Some(agent_id) = DeferredCleanups::<T>::take(head) {
+                    let _ = queue.try_push(agent_id);
+                }
+                head = head.wrapping_add(1);
+                changed = true;
+            }
+
+            if changed {
+                CleanupQueue::<T>::put(queue);
+                DeferredCleanupRange::<T>::put((head, tail));
+                used = used.saturating_add(db.writes(2));
+            }
+            used
+        }
     }
 
     // ========== Extrinsics ==========
@@ -206,46 +545,7 @@ pub mod pallet {
             metadata: Vec<u8>,
         ) -> DispatchResult {
             let who = ensure_signed(origin)?;
-
-            let bounded_did: BoundedVec<u8, T::MaxDidLength> =
-                did.clone().try_into().map_err(|_| Error::<T>::DidTooLong)?;
-            let bounded_metadata: BoundedVec<u8, T::MaxMetadataLength> = metadata
-                .try_into()
-                .map_err(|_| Error::<T>::MetadataTooLong)?;
-
-            let agent_id = AgentCount::<T>::get();
-            let current_block = <frame_system::Pallet<T>>::block_number();
-
-            let agent_info = AgentInfo::<T> {
-                owner: who.clone(),
-                did: bounded_did,
-                metadata: bounded_metadata,
-                reputation: 5000, // Start at 50%
-                registered_at: current_block,
-                last_active: current_block,
-                status: AgentStatus::Active,
-            };
-
-            // Store the agent
-            AgentRegistry::<T>::insert(agent_id, agent_info);
-
-            // Update agent count
-            AgentCount::<T>::put(agent_id.saturating_add(1));
-
-            // Add to owner's agent list
-            OwnerAgents::<T>::try_mutate(&who, |agents| {
-                agents
-                    .try_push(agent_id)
-                    .map_err(|_| Error::<T>::TooManyAgents)
-            })?;
-
-            Self::deposit_event(Event::AgentRegistered {
-                agent_id,
-                owner: who,
-                did,
-            });
-
-            Ok(())
+            Self::do_register_agent(who, did, metadata).map(|_| ())
         }
 
         /// Update an agent's metadata.
@@ -326,10 +626,17 @@ pub mod pallet {
 
         /// Deregister an agent.
         ///
-        /// Only the agent owner can deregister. Sets the status to Deregistered.
+        /// Only the agent owner can deregister. Sets the status to Deregistered,
+        /// drops the agent from the DID index and starts the cross-pallet
+        /// cleanup cascade, deferring its multi-block part while the cleanup
+        /// queue is full.
         /// The agent data remains on-chain for historical purposes.
         #[pallet::call_index(3)]
-        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+        #[pallet::weight(
+            Weight::from_parts(10_000, 0)
+                + T::DbWeight::get().reads_writes(4, 6)
+                + T::LifecycleHook::on_deregistered_weight()
+        )]
         pub fn deregister_agent(origin: OriginFor<T>, agent_id: AgentId) -> DispatchResult {
             let who = ensure_signed(origin)?;
 
@@ -343,12 +650,28 @@ pub mod pallet {
 
                 agent.status = AgentStatus::Deregistered;
                 agent.last_active = <frame_system::Pallet<T>>::block_number();
+                AgentsByDid::<T>::mutate_exists(&agent.did, |indexed| {
+                    if *indexed == Some(agent_id) {
+                        *indexed = None;
+                    }
+                });
 
                 Ok(())
             })?;
 
+            let mut report = CascadeReport::default();
+            T::LifecycleHook::on_deregistered(agent_id, &who, &mut report);
+            CascadeReports::<T>::insert(agent_id, report);
             Self::deposit_event(Event::AgentDeregistered { agent_id });
 
+            if CleanupQueue::<T>::try_append(agent_id).is_err() {
+                DeferredCleanupRange::<T>::mutate(|(_, tail)| {
+                    DeferredCleanups::<T>::insert(*tail, agent_id);
+                    *tail = tail.wrapping_add(1);
+                });
+                Self::deposit_event(Event::AgentCleanupDeferred { agent_id });
+            }
+
             Ok(())
         }
 
@@ -383,6 +706,254 @@ pub mod pallet {
 
             Ok(())
         }
+
+        /// Grant the registrar role with a registration quota.
+        ///
+        /// Re-granting an existing registrar replaces its quota and keeps the
+        /// count of registrations already performed.
+        #[pallet::call_index(5)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+        pub fn grant_registrar(
+            origin: OriginFor<T>,
+            registrar: T::AccountId,
+            quota: u32,
+        ) -> DispatchResult {
+            T::RegistrarOrigin::ensure_origin(origin)?;
+
+            Registrars::<T>::mutate(&registrar, |maybe| {
+                let info = maybe.get_or_insert_with(Default::default);
+                info.quota = quota;
+            });
+
+            Self::deposit_event(Event::RegistrarGranted { registrar, quota });
+
+            Ok(())
+        }
+
+        /// Revoke the registrar role.
+        #[pallet::call_index(6)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+        pub fn revoke_registrar(origin: OriginFor<T>, registrar: T::AccountId) -> DispatchResult {
+            T::RegistrarOrigin::ensure_origin(origin)?;
+
+            ensure!(
+                Registrars::<T>::take(&registrar).is_some(),
+                Error::<T>::NotRegistrar
+            );
+
+            Self::deposit_event(Event::RegistrarRevoked { registrar });
+
+            Ok(())
+        }
+
+        /// Register a batch of agents on behalf of their owners.
+        ///
+        /// Each entry must carry the owner's signature over the payload built
+        /// by `registration_payload`. The whole batch fails if any entry is
+        /// invalid or the registrar's quota would be exceeded. Owners without
+        /// an on-chain account receive an onboarding fee allowance while the
+        /// rate limit permits.
+        #[pallet::call_index(7)]
+        #[pallet::weight(
+            Weight::from_parts(10_000, 0)
+                + T::DbWeight::get().reads_writes(2, 2)
+                + T::DbWeight::get()
+                    .reads_writes(5, 7)
+                    .saturating_add(OWNER_SIGNATURE_WEIGHT)
+                    .saturating_mul(registrations.len() as u64)
+        )]
+        pub fn register_agents_for(
+            origin: OriginFor<T>,
+            registrations: BoundedVec<DelegatedRegistration<T>, T::MaxBatchRegistrations>,
+        ) -> DispatchResult {
+            let registrar = ensure_signed(origin)?;
+
+            let mut info = Registrars::<T>::get(&registrar).ok_or(Error::<T>::NotRegistrar)?;
+            info.used = info
+                .used
+                .checked_add(registrations.len() as u32)
+                .filter(|used| *used <= info.quota)
+                .ok_or(Error::<T>::RegistrarQuotaExceeded)?;
+            Registrars::<T>::insert(&registrar, info);
+
+            for (owner, did, metadata, signature) in registrations {
+                let nonce = RegistrationNonces::<T>::get(&owner);
+                let payload = Self::registration_payload(&registrar, nonce, &did, &metadata);
+                ensure!(
+                    signature.verify(&payload[..], &owner),
+                    Error::<T>::InvalidOwnerSignature
+                );
+                RegistrationNonces::<T>::insert(&owner, nonce.wrapping_add(1));
+
+                let is_new_account = !frame_system::Pallet::<T>::account_exists(&owner);
+                let agent_id = Self::do_register_agent(owner.clone(), did, metadata)?;
+
+                Self::deposit_event(Event::AgentRegisteredByRegistrar {
+                    agent_id,
+                    registrar: registrar.clone(),
+                    owner: owner.clone(),
+                });
+
+                if is_new_account {
+                    Self::try_grant_fee_allowance(&owner);
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    // ========== Internal Functions ==========
+
+    impl<T: Config> Pallet<T> {
+        /// Store a new agent owned by `who`. Shared by direct and delegated registration.
+        fn do_register_agent(
+            who: T::AccountId,
+            did: Vec<u8>,
+            metadata: Vec<u8>,
+        ) -> Result<AgentId, DispatchError> {
+            let bounded_did: BoundedVec<u8, T::MaxDidLength> =
+                did.clone().try_into().map_err(|_| Error::<T>::DidTooLong)?;
+            let bounded_metadata: BoundedVec<u8, T::MaxMetadataLength> = metadata
+                .try_into()
+                .map_err(|_| Error::<T>::MetadataTooLong)?;
+
+            let agent_id = AgentCount::<T>::get();
+            let current_block = <frame_system::Pallet<T>>::block_number();
+
+            let agent_info = AgentInfo::<T> {
+                owner: who.clone(),
+                did: bounded_did,
+                metadata: bounded_metadata,
+                reputation: 5000, // Start at 50%
+                registered_at: current_block,
+                last_active: current_block,
+                status: AgentStatus::Active,
+            };
+
+            // Store the agent
+            if !AgentsByDid::<T>::contains_key(&agent_info.did) {
+                AgentsByDid::<T>::insert(&agent_info.did, agent_id);
+            }
+            AgentRegistry::<T>::insert(agent_id, agent_info);
+
+            // Update agent count
+            AgentCount::<T>::put(agent_id.saturating_add(1));
+
+            // Add to owner's agent list
+            OwnerAgents::<T>::try_mutate(&who, |agents| {
+                agents
+                    .try_push(agent_id)
+                    .map_err(|_| Error::<T>::TooManyAgents)
+            })?;
+
+            Self::deposit_event(Event::AgentRegistered {
+                agent_id,
+                owner: who,
+                did,
+            });
+
+            Ok(agent_id)
+        }
+
+        /// Grant `owner` the onboarding fee allowance if it has never had one
+        /// and the current window still has room.
+        ///
+        /// The grant adds a provider reference so the otherwise empty account
+        /// can submit transactions; it is released when the allowance ends.
+        fn try_grant_fee_allowance(owner: &T::AccountId) {
+            let amount = T::OnboardingFeeAllowance::get();
+            if amount.is_zero() || OnboardedAccounts::<T>::contains_key(owner) {
+                return;
+            }
+
+            let now = <frame_system::Pallet<T>>::block_number();
+            let (mut start, mut granted) = OnboardingWindow::<T>::get();
+            if now >= start.saturating_add(T::OnboardingPeriod::get()) {
+                start = now;
+                granted = 0;
+            }
+            if granted >= T::MaxOnboardingGrantsPerPeriod::get() {
+                return;
+            }
+
+            OnboardingWindow::<T>::put((start, granted.saturating_add(1)));
+            OnboardedAccounts::<T>::insert(owner, now);
+            FeeAllowances::<T>::insert(owner, amount);
+            frame_system::Pallet::<T>::inc_providers(owner);
+
+            Self::deposit_event(Event::FeeAllowanceGranted {
+                owner: owner.clone(),
+                amount,
+            });
+        }
+
+        /// Remaining sponsored fee allowance of `who`.
+        pub fn fee_allowance(who: &T::AccountId) -> BalanceOf<T> {
+            FeeAllowances::<T>::get(who).unwrap_or_default()
+        }
+
+        /// Deduct `fee` from `who`'s allowance if it covers the fee in full.
+        ///
+        /// Returns `false` when the sender must pay itself. An allowance too
+        /// small for the fee is dropped, since it can no longer be used, and
+        /// so is one the fee uses up.
+        pub fn consume_fee_allowance(who: &T::AccountId, fee: BalanceOf<T>) -> bool {
+            let Some(remaining) = FeeAllowances::<T>::get(who) else {
+                return false;
+            };
+            if remaining < fee {
+                Self::end_fee_allowance(who);
+                return false;
+            }
+            let remaining = remaining.saturating_sub(fee);
+            if remaining.is_zero() {
+                Self::end_fee_allowance(who);
+            } else {
+                FeeAllowances::<T>::insert(who, remaining);
+            }
+            true
+        }
+
+        /// Return the unused part of a sponsored fee to `who`'s allowance and
+        /// end the allowance once nothing is left.
+        pub fn settle_fee_allowance(who: &T::AccountId, refund: BalanceOf<T>) {
+            let remaining = Self::fee_allowance(who).saturating_add(refund);
+            if remaining.is_zero() {
+                Self::end_fee_allowance(who);
+            } else {
+                FeeAllowances::<T>::insert(who, remaining);
+            }
+        }
+
+        fn end_fee_allowance(who: &T::AccountId) {
+            if FeeAllowances::<T>::take(who).is_some() {
+                // Fails only if other pallets still hold consumer references on an
+                // account with no other provider; it then keeps this one.
+                let _ = frame_system::Pallet::<T>::dec_providers(who);
+                Self::deposit_event(Event::FeeAllowanceExhausted { owner: who.clone() });
+            }
+        }
+
+        /// Bytes an owner signs to authorise `registrar` to register an agent
+        /// on this chain.
+        pub fn registration_payload(
+            registrar: &T::AccountId,
+            nonce: u32,
+            did: &[u8],
+            metadata: &[u8],
+        ) -> Vec<u8> {
+            let genesis_hash = frame_system::Pallet::<T>::block_hash(BlockNumberFor::<T>::zero());
+            (
+                REGISTRATION_CONTEXT,
+                genesis_hash,
+                registrar,
+                nonce,
+                did,
+                metadata,
+            )
+                .encode()
+        }
     }
 
     // ========== Weight Info Trait ==========
@@ -394,6 +965,9 @@ pub mod pallet {
         fn update_reputation() -> Weight;
         fn deregister_agent() -> Weight;
         fn set_agent_status() -> Weight;
+        fn grant_registrar() -> Weight;
+        fn revoke_registrar() -> Weight;
+        fn register_agents_for(n: u32) -> Weight;
     }
 
     /// Default weights for testing.
@@ -413,5 +987,14 @@ pub mod pallet {
         fn set_agent_status() -> Weight {
             Weight::from_parts(10_000, 0)
         }
+        fn grant_registrar() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn revoke_registrar() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn register_agents_for(n: u32) -> Weight {
+            Weight::from_parts(10_000, 0).saturating_mul(n.max(1) as u64)
+        }
     }
 }
@@ -0,0 +1,63 @@
+//! Storage migrations for pallet-agent-registry.
+
+use crate::pallet::{AgentRegistry, AgentStatus, AgentsByDid, Config, Pallet};
+use frame_support::{
+    migrations::VersionedMigration,
+    pallet_prelude::*,
+    traits::{Get, UncheckedOnRuntimeUpgrade},
+};
+
+/// v0 → v1: backfill `AgentsByDid` from the agents still registered.
+pub mod v1 {
+    use super::*;
+
+    pub struct UncheckedMigrateToV1<T>(core::marker::PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for UncheckedMigrateToV1<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let mut reads = 0u64;
+            let mut writes = 0u64;
+            for (agent_id, info) in AgentRegistry::<T>::iter() {
+                reads += 2;
+                if info.status == AgentStatus::Deregistered {
+                    continue;
+                }
+                // The registry iterates in hash order; keep the lowest id,
+                // which is the agent registered first.
+                let first = AgentsByDid::<T>::get(&info.did).is_none_or(|id| agent_id < id);
+                if first {
+                    AgentsByDid::<T>::insert(&info.did, agent_id);
+                    writes += 1;
+                }
+            }
+            log::info!(
+                target: "runtime::agent-registry",
+                "indexed {} DIDs",
+                writes
+            );
+            T::DbWeight::get().reads_writes(reads, writes)
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(_state: alloc::vec::Vec<u8>) -> Result<(), sp_runtime::TryRuntimeError> {
+            for (agent_id, info) in AgentRegistry::<T>::iter() {
+                if info.status == AgentStatus::Deregistered {
+                    continue;
+                }
+                let indexed = AgentsByDid::<T>::get(&info.did)
+                    .ok_or(sp_runtime::TryRuntimeError::Other("DID not indexed"))?;
+                ensure!(indexed <= agent_id, "DID indexed to a later agent");
+            }
+            Ok(())
+        }
+    }
+
+    /// Runs [`UncheckedMigrateToV1`] once, bumping the storage version to 1.
+    pub type MigrateToV1<T> = VersionedMigration<
+        0,
+        1,
+        UncheckedMigrateToV1<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
@@ -1,14 +1,20 @@
 //! Unit tests for the Agent Registry pallet.
 
 use crate as pallet_agent_registry;
-use crate::pallet::{AgentCount, AgentRegistry, AgentStatus, Event, OwnerAgents};
+use crate::pallet::{
+    AgentCount, AgentRegistry, AgentStatus, AgentsByDid, CascadeReport, CascadeReports,
+    CleanupQueue, DeferredCleanups, DelegatedRegistration, Error, Event, FeeAllowances,
+    OnboardedAccounts, OwnerAgents, Registrars, RegistrationNonces,
+};
 use frame_support::{
     assert_noop, assert_ok, derive_impl, parameter_types,
-    traits::{ConstU32, ConstU64},
+    traits::{ConstU32, ConstU64, Hooks},
+    weights::Weight,
+    BoundedVec,
 };
-use sp_core::H256;
 use sp_runtime::{
-    traits::{BlakeTwo256, IdentityLookup},
+    testing::{TestSignature, UintAuthorityId, H256},
+    traits::IdentityLookup,
     BuildStorage,
 };
 
@@ -18,6 +24,7 @@ type Block = frame_system::mocking::MockBlock<Test>;
 frame_support::construct_runtime!(
     pub enum Test {
         System: frame_system,
+        Balances: pallet_balances,
         AgentRegistryPallet: pallet_agent_registry,
     }
 );
@@ -27,6 +34,12 @@ impl frame_system::Config for Test {
     type Block = Block;
     type AccountId = u64;
     type Lookup = IdentityLookup<Self::AccountId>;
+    type AccountData = pallet_balances::AccountData<u64>;
+}
+
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+impl pallet_balances::Config for Test {
+    type AccountStore = System;
 }
 
 impl pallet_agent_registry::Config for Test {
@@ -35,13 +48,63 @@ impl pallet_agent_registry::Config for Test {
     type MaxDidLength = ConstU32<256>;
     type MaxMetadataLength = ConstU32<4096>;
     type MaxAgentsPerOwner = ConstU32<10>;
+    type RegistrarOrigin = frame_system::EnsureRoot<u64>;
+    type OffchainSignature = TestSignature;
+    type SigningPublicKey = UintAuthorityId;
+    type MaxBatchRegistrations = ConstU32<3>;
+    type Currency = Balances;
+    type OnboardingFeeAllowance = ConstU64<50>;
+    type MaxOnboardingGrantsPerPeriod = ConstU32<2>;
+    type OnboardingPeriod = ConstU64<10>;
+    type LifecycleHook = MockLifecycleHook;
+    type MaxPendingCleanups = ConstU32<2>;
+}
+
+parameter_types! {
+    /// Agents passed to `on_deregistered`.
+    pub static DeregisteredAgents: Vec<u64> = Vec::new();
+    /// `cleanup` calls so far; every second call finishes an agent.
+    pub static CleanupCalls: u32 = 0;
+}
+
+pub struct MockLifecycleHook;
+
+impl pallet_agent_registry::AgentLifecycleHook<u64> for MockLifecycleHook {
+    fn on_deregistered(agent_id: u64, _owner: &u64, report: &mut CascadeReport) {
+        DeregisteredAgents::mutate(|agents| agents.push(agent_id));
+        report.did_marked = true;
+    }
+
+    fn on_deregistered_weight() -> Weight {
+        Weight::zero()
+    }
+
+    fn cleanup(
+        _agent_id: u64,
+        _owner: &u64,
+        _max_weight: Weight,
+        report: &mut CascadeReport,
+    ) -> (Weight, bool) {
+        CleanupCalls::mutate(|calls| *calls += 1);
+        report.delegations_revoked += 1;
+        (
+            Weight::from_parts(1_000, 0),
+            CleanupCalls::get().is_multiple_of(2),
+        )
+    }
 }
 
 // Build test externalities from genesis storage.
 fn new_test_ext() -> sp_io::TestExternalities {
-    let t = frame_system::GenesisConfig::<Test>::default()
+    let mut t = frame_system::GenesisConfig::<Test>::default()
         .build_storage()
         .unwrap();
+    pallet_balances::GenesisConfig::<Test> {
+        balances: vec![(5, 100)],
+        ..Default::default()
+    }
+    .assimilate_storage(&mut t)
+    .unwrap();
     let mut ext = sp_io::TestExternalities::new(t);
     ext.execute_with(|| System::set_block_number(1));
     ext
@@ -155,6 +218,82 @@ fn register_agents_different_owners() {
     });
 }
 
+#[test]
+fn did_index_keeps_first_registered_agent() {
+    new_test_ext().execute_with(|| {
+        for owner in [1, 2] {
+            assert_ok!(AgentRegistryPallet::register_agent(
+                account(owner),
+                b"did:claw:shared".to_vec(),
+                b"{}".to_vec()
+            ));
+        }
+        let did = AgentRegistry::<Test>::get(1).unwrap().did;
+        assert_eq!(AgentsByDid::<Test>::get(did), Some(0));
+    });
+}
+
+#[test]
+fn deregistering_drops_agent_from_did_index() {
+    new_test_ext().execute_with(|| {
+        for owner in [1, 2] {
+            assert_ok!(AgentRegistryPallet::register_agent(
+                account(owner),
+                b"did:claw:shared".to_vec(),
+                b"{}".to_vec()
+            ));
+        }
+        let did = AgentRegistry::<Test>::get(0).unwrap().did;
+
+        // Deregistering an agent the index does not point at leaves it alone.
+        assert_ok!(AgentRegistryPallet::deregister_agent(account(2), 1));
+        assert_eq!(AgentsByDid::<Test>::get(&did), Some(0));
+
+        assert_ok!(AgentRegistryPallet::deregister_agent(account(1), 0));
+        assert!(AgentsByDid::<Test>::get(&did).is_none());
+
+        // The next registration under the DID is indexed again.
+        assert_ok!(AgentRegistryPallet::register_agent(
+            account(3),
+            b"did:claw:shared".to_vec(),
+            b"{}".to_vec()
+        ));
+        assert_eq!(AgentsByDid::<Test>::get(&did), Some(2));
+    });
+}
+
+#[test]
+fn migrate_to_v1_indexes_existing_dids() {
+    use crate::migrations::v1::MigrateToV1;
+    use frame_support::traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion};
+
+    new_test_ext().execute_with(|| {
+        for (owner, did) in [(1, "did:claw:a"), (2, "did:claw:b"), (3, "did:claw:a")] {
+            assert_ok!(AgentRegistryPallet::register_agent(
+                account(owner),
+                did.as_bytes().to_vec(),
+                b"{}".to_vec()
+            ));
+        }
+        assert_ok!(AgentRegistryPallet::register_agent(
+            account(4),
+            b"did:claw:gone".to_vec(),
+            b"{}".to_vec()
+        ));
+        assert_ok!(AgentRegistryPallet::deregister_agent(account(4), 3));
+        let _ = AgentsByDid::<Test>::clear(u32::MAX, None);
+        StorageVersion::new(0).put::<AgentRegistryPallet>();
+
+        MigrateToV1::<Test>::on_runtime_upgrade();
+
+        let did_of = |agent_id| AgentRegistry::<Test>::get(agent_id).unwrap().did;
+        assert_eq!(AgentsByDid::<Test>::get(did_of(2)), Some(0));
+        assert_eq!(AgentsByDid::<Test>::get(did_of(1)), Some(1));
+        assert!(AgentsByDid::<Test>::get(did_of(3)).is_none());
+        assert_eq!(AgentRegistryPallet::on_chain_storage_version(), 1);
+    });
+}
+
 #[test]
 fn register_agent_with_empty_did() {
     new_test_ext().execute_with(|| {
@@ -599,6 +738,62 @@ fn deregister_agent_emits_event() {
     });
 }
 
+#[test]
+fn deregister_agent_cascades_over_idle_blocks() {
+    new_test_ext().execute_with(|| {
+        for _ in 0..3 {
+            assert_ok!(AgentRegistryPallet::register_agent(
+                account(1),
+                b"did:claw:test".to_vec(),
+                b"{}".to_vec()
+            ));
+        }
+        assert_ok!(AgentRegistryPallet::deregister_agent(account(1), 0));
+        assert_ok!(AgentRegistryPallet::deregister_agent(account(1), 1));
+        // The queue is full: the immediate effects run, the cleanup waits.
+        assert_ok!(AgentRegistryPallet::deregister_agent(account(1), 2));
+        System::assert_last_event(Event::<Test>::AgentCleanupDeferred { agent_id: 2 }.into());
+        assert_eq!(DeregisteredAgents::get(), vec![0, 1, 2]);
+        assert_eq!(DeferredCleanups::<Test>::get(0), Some(2));
+
+        // No spare weight: nothing happens.
+        assert_eq!(
+            AgentRegistryPallet::on_idle(1, Weight::zero()),
+            Weight::zero()
+        );
+
+        // The first pass leaves agent 0 unfinished and stops there.
+        AgentRegistryPallet::on_idle(1, Weight::MAX);
+        assert_eq!(CleanupQueue::<Test>::get().to_vec(), vec![0, 1]);
+
+        // Agent 0 finishes and the deferred agent 2 takes its place.
+        AgentRegistryPallet::on_idle(2, Weight::MAX);
+        assert_eq!(CleanupQueue::<Test>::get().to_vec(), vec![1, 2]);
+        assert!(DeferredCleanups::<Test>::get(0).is_none());
+        System::assert_has_event(
+            Event::<Test>::AgentCleanupCompleted {
+                agent_id: 0,
+                owner: 1,
+                report: CascadeReport {
+                    did_marked: true,
+                    messaging_endpoints_removed: 0,
+                    delegations_revoked: 2,
+                },
+            }
+            .into(),
+        );
+        assert!(CascadeReports::<Test>::get(0).is_none());
+
+        // Agent 1 started in the same pass and finishes in the next one.
+        AgentRegistryPallet::on_idle(3, Weight::MAX);
+        assert_eq!(CleanupQueue::<Test>::get().to_vec(), vec![2]);
+        AgentRegistryPallet::on_idle(4, Weight::MAX);
+        AgentRegistryPallet::on_idle(5, Weight::MAX);
+        assert!(CleanupQueue::<Test>::get().is_empty());
+        assert!(CascadeReports::<Test>::iter().next().is_none());
+    });
+}
+
 #[test]
 fn deregister_agent_updates_last_active() {
     new_test_ext().execute_with(|| {
@@ -932,3 +1127,316 @@ fn multiple_operations_sequence() {
         assert_eq!(agent.metadata.to_vec(), b"{\"v\": 2}");
     });
 }
+
+// ========== Delegated Registration Tests ==========
+
+type BatchOf = BoundedVec<DelegatedRegistration<Test>, ConstU32<3>>;
+
+fn batch(entries: Vec<DelegatedRegistration<Test>>) -> BatchOf {
+    entries.try_into().unwrap()
+}
+
+fn owner_sig(owner: u64, registrar: u64, nonce: u32, did: &[u8]) -> TestSignature {
+    TestSignature(
+        owner,
+        AgentRegistryPallet::registration_payload(&registrar, nonce, did, b"{}"),
+    )
+}
+
+#[test]
+fn grant_and_revoke_registrar_require_root() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            AgentRegistryPallet::grant_registrar(account(1), 9, 5),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_ok!(AgentRegistryPallet::grant_registrar(
+            RuntimeOrigin::root(),
+            9,
+            5
+        ));
+        assert_eq!(Registrars::<Test>::get(9).unwrap().quota, 5);
+
+        assert_ok!(AgentRegistryPallet::revoke_registrar(
+            RuntimeOrigin::root(),
+            9
+        ));
+        assert!(Registrars::<Test>::get(9).is_none());
+        assert_noop!(
+            AgentRegistryPallet::revoke_registrar(RuntimeOrigin::root(), 9),
+            Error::<Test>::NotRegistrar
+        );
+    });
+}
+
+#[test]
+fn register_agents_for_batch_works() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(AgentRegistryPallet::grant_registrar(
+            RuntimeOrigin::root(),
+            9,
+            5
+        ));
+
+        assert_ok!(AgentRegistryPallet::register_agents_for(
+            account(9),
+            batch(vec![
+                (
+                    1,
+                    b"did:claw:a".to_vec(),
+                    b"{}".to_vec(),
+                    owner_sig(1, 9, 0, b"did:claw:a")
+                ),
+                (
+                    2,
+                    b"did:claw:b".to_vec(),
+                    b"{}".to_vec(),
+                    owner_sig(2, 9, 0, b"did:claw:b")
+                ),
+            ]),
+        ));
+
+        assert_eq!(AgentRegistry::<Test>::get(0).unwrap().owner, 1);
+        assert_eq!(AgentRegistry::<Test>::get(1).unwrap().owner, 2);
+        assert_eq!(Registrars::<Test>::get(9).unwrap().used, 2);
+        assert_eq!(RegistrationNonces::<Test>::get(1), 1);
+        System::assert_has_event(
+            Event::<Test>::AgentRegisteredByRegistrar {
+                agent_id: 1,
+                registrar: 9,
+                owner: 2,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn register_agents_for_weight_covers_each_signature_check() {
+    use frame_support::dispatch::GetDispatchInfo;
+
+    new_test_ext().execute_with(|| {
+        let call = pallet_agent_registry::Call::<Test>::register_agents_for {
+            registrations: batch(vec![
+                sponsored(1, 0, b"did:claw:a"),
+                sponsored(2, 0, b"did:claw:b"),
+            ]),
+        };
+        assert!(call
+            .get_dispatch_info()
+            .call_weight
+            .all_gte(pallet_agent_registry::OWNER_SIGNATURE_WEIGHT.saturating_mul(2)));
+    });
+}
+
+#[test]
+fn register_agents_for_rejects_bad_or_replayed_signature() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(AgentRegistryPallet::grant_registrar(
+            RuntimeOrigin::root(),
+            9,
+            5
+        ));
+        assert_ok!(AgentRegistryPallet::grant_registrar(
+            RuntimeOrigin::root(),
+            8,
+            5
+        ));
+
+        // Signed for a different registrar.
+        assert_noop!(
+            AgentRegistryPallet::register_agents_for(
+                account(9),
+                batch(vec![(
+                    1,
+                    b"did:claw:a".to_vec(),
+                    b"{}".to_vec(),
+                    owner_sig(1, 8, 0, b"did:claw:a")
+                )]),
+            ),
+            Error::<Test>::InvalidOwnerSignature
+        );
+
+        let sig = owner_sig(1, 9, 0, b"did:claw:a");
+        assert_ok!(AgentRegistryPallet::register_agents_for(
+            account(9),
+            batch(vec![(1, b"did:claw:a".to_vec(), b"{}".to_vec(), sig.clone())]),
+        ));
+        // Nonce advanced, so the same signature cannot be replayed.
+        assert_noop!(
+            AgentRegistryPallet::register_agents_for(
+                account(9),
+                batch(vec![(1, b"did:claw:a".to_vec(), b"{}".to_vec(), sig)]),
+            ),
+            Error::<Test>::InvalidOwnerSignature
+        );
+    });
+}
+
+#[test]
+fn register_agents_for_rejects_signature_from_another_chain() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(AgentRegistryPallet::grant_registrar(
+            RuntimeOrigin::root(),
+            9,
+            5
+        ));
+        let sig = owner_sig(1, 9, 0, b"did:claw:a");
+
+        // Same owner key, registrar and nonce, different genesis.
+        frame_system::BlockHash::<Test>::insert(0, H256::repeat_byte(9));
+        assert_noop!(
+            AgentRegistryPallet::register_agents_for(
+                account(9),
+                batch(vec![(1, b"did:claw:a".to_vec(), b"{}".to_vec(), sig)]),
+            ),
+            Error::<Test>::InvalidOwnerSignature
+        );
+    });
+}
+
+#[test]
+fn register_agents_for_enforces_role_quota_and_batch_size() {
+    new_test_ext().execute_with(|| {
+        let entry = |owner: u64| {
+            (
+                owner,
+                b"did:claw:x".to_vec(),
+                b"{}".to_vec(),
+                owner_sig(owner, 9, 0, b"did:claw:x"),
+            )
+        };
+
+        assert_noop!(
+            AgentRegistryPallet::register_agents_for(account(9), batch(vec![entry(1)])),
+            Error::<Test>::NotRegistrar
+        );
+
+        assert_ok!(AgentRegistryPallet::grant_registrar(
+            RuntimeOrigin::root(),
+            9,
+            1
+        ));
+        assert_noop!(
+            AgentRegistryPallet::register_agents_for(account(9), batch(vec![entry(1), entry(2)])),
+            Error::<Test>::RegistrarQuotaExceeded
+        );
+        // Batches over `MaxBatchRegistrations` cannot be built, so never decode.
+        assert!(BatchOf::try_from(vec![entry(1), entry(2), entry(3), entry(4)]).is_err());
+        assert_ok!(AgentRegistryPallet::register_agents_for(
+            account(9),
+            batch(vec![entry(1)])
+        ));
+        assert_noop!(
+            AgentRegistryPallet::register_agents_for(account(9), batch(vec![entry(2)])),
+            Error::<Test>::RegistrarQuotaExceeded
+        );
+    });
+}
+
+// ========== Onboarding Fee Allowance Tests ==========
+
+fn sponsored(owner: u64, nonce: u32, did: &[u8]) -> DelegatedRegistration<Test> {
+    (
+        owner,
+        did.to_vec(),
+        b"{}".to_vec(),
+        owner_sig(owner, 9, nonce, did),
+    )
+}
+
+#[test]
+fn onboarding_allowance_is_one_off_and_rate_limited() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(AgentRegistryPallet::grant_registrar(
+            RuntimeOrigin::root(),
+            9,
+            10
+        ));
+
+        // Two grants per window: owner 3 misses out, owner 5 already has funds.
+        assert_ok!(AgentRegistryPallet::register_agents_for(
+            account(9),
+            batch(vec![
+                sponsored(1, 0, b"did:claw:a"),
+                sponsored(2, 0, b"did:claw:b"),
+                sponsored(3, 0, b"did:claw:c"),
+            ]),
+        ));
+        assert_ok!(AgentRegistryPallet::register_agents_for(
+            account(9),
+            batch(vec![sponsored(5, 0, b"did:claw:e")]),
+        ));
+        assert_eq!(FeeAllowances::<Test>::get(1), Some(50));
+        assert_eq!(FeeAllowances::<Test>::get(2), Some(50));
+        assert!(FeeAllowances::<Test>::get(3).is_none());
+        assert!(FeeAllowances::<Test>::get(5).is_none());
+        assert!(System::account_exists(&1));
+        System::assert_has_event(
+            Event::<Test>::FeeAllowanceGranted {
+                owner: 2,
+                amount: 50,
+            }
+            .into(),
+        );
+
+        // A new window opens; an owner is never granted twice.
+        System::set_block_number(11);
+        assert_ok!(AgentRegistryPallet::register_agents_for(
+            account(9),
+            batch(vec![
+                sponsored(3, 1, b"did:claw:c2"),
+                sponsored(1, 1, b"did:claw:a2"),
+            ]),
+        ));
+        assert_eq!(FeeAllowances::<Test>::get(3), Some(50));
+        assert_eq!(OnboardedAccounts::<Test>::get(1), Some(1));
+        assert_eq!(FeeAllowances::<Test>::get(1), Some(50));
+    });
+}
+
+#[test]
+fn fee_allowance_is_consumed_and_released() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(AgentRegistryPallet::grant_registrar(
+            RuntimeOrigin::root(),
+            9,
+            10
+        ));
+        assert_ok!(AgentRegistryPallet::register_agents_for(
+            account(9),
+            batch(vec![sponsored(1, 0, b"did:claw:a")]),
+        ));
+
+        assert!(AgentRegistryPallet::consume_fee_allowance(&1, 30));
+        AgentRegistryPallet::settle_fee_allowance(&1, 5);
+        assert_eq!(AgentRegistryPallet::fee_allowance(&1), 25);
+
+        // Not enough left for this fee: the allowance is dropped.
+        assert!(!AgentRegistryPallet::consume_fee_allowance(&1, 40));
+        assert!(FeeAllowances::<Test>::get(1).is_none());
+        assert!(!System::account_exists(&1));
+        System::assert_last_event(Event::<Test>::FeeAllowanceExhausted { owner: 1 }.into());
+        assert!(!AgentRegistryPallet::consume_fee_allowance(&1, 1));
+    });
+}
+
+#[test]
+fn fee_allowance_ends_once_used_up() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(AgentRegistryPallet::grant_registrar(
+            RuntimeOrigin::root(),
+            9,
+            10
+        ));
+        assert_ok!(AgentRegistryPallet::register_agents_for(
+            account(9),
+            batch(vec![sponsored(1, 0, b"did:claw:a")]),
+        ));
+
+        assert!(AgentRegistryPallet::consume_fee_allowance(&1, 50));
+        assert!(FeeAllowances::<Test>::get(1).is_none());
+        assert!(!System::account_exists(&1));
+        System::assert_last_event(Event::<Test>::FeeAllowanceExhausted { owner: 1 }.into());
+    });
+}
@@ -15,11 +15,90 @@
 //! ## Dispatchable Functions
 //!
 //! - `register_public_key` — Register/update X25519 public key
-//! - `send_message` — Send encrypted message envelope to any agent
+//! - `send_message` — Send encrypted message envelope to an agent with a registered key
 //! - `read_message` — Mark message as read (on-chain read receipt)
 //! - `delete_message` — Delete message by sender or receiver
 //! - `set_auto_response` — Configure auto-response for incoming messages
 //! - `claim_reply_escrow` — Claim escrowed CLAW after replying
+//! - `sponsor_exec` — A sponsor pays fees for a receiver-signed read or claim
+//! - `set_recovery_config` / `remove_recovery_config` — Opt in to guardian recovery
+//! - `initiate_recovery` / `approve_recovery` — Guardians propose and approve a recovery
+//! - `cancel_recovery` — Owner cancels a pending recovery during the notice period
+//! - `accept_recovery` — The proposed new owner agrees to take over the identity
+//! - `execute_recovery` — Complete a recovery once the notice period has elapsed
+//! - `commit_archive` — Commit a Merkle root over the inbox up to a message ID
+//! - `prune_archived` — Delete archived messages, refunding unclaimed escrow
+//! - `prove_archived_message` — Prove a message against a committed archive root
+//! - `file_spam_appeal` — Bond and request review of a spam penalty
+//! - `resolve_spam_appeal` — `AppealOrigin` upholds or rejects a pending appeal
+//! - `send_auto_reply` — Responder sends the reply for a triggered auto-response
+//!
+//! ## Sponsored Execution
+//!
+//! New agents often hold no CLAW for fees. A receiver signs a
+//! [`SponsoredOp`] off-chain over
+//! `(SPONSOR_CONTEXT, genesis_hash, receiver, op, nonce, expires_at)`; any
+//! sponsor may then submit it through `sponsor_exec` until block `expires_at`
+//! and pay the fees. Only read receipts and escrow claims can be sponsored,
+//! and each signature is bound to this chain and to the receiver's current
+//! `SponsorNonces` value.
+//!
+//! ## Messaging Recovery
+//!
+//! An account may opt in to M-of-N guardian recovery of its messaging
+//! identity. Guardians propose a new owner and public key; once `threshold`
+//! guardians approve, a public notice period of `delay_blocks` starts during
+//! which the owner can cancel. The new owner must accept the takeover with
+//! `accept_recovery`, so guardians cannot overwrite an unrelated account's key.
+//! After the notice period anyone may execute the recovery, which registers
+//! the new key for the new owner and moves the inbox (envelopes, escrow claims
+//! and ephemeral expiries) to it, within the new owner's inbox capacity.
+//!
+//! ## Inbox Archives
+//!
+//! Before pruning, a receiver commits the Merkle root of its archived
+//! envelopes with `commit_archive`. Leaves are [`Pallet::archive_leaf`] of each
+//! envelope and inner nodes hash the sorted pair of children, so a proof is
+//! just the list of sibling hashes. Messages up to the committed ID can then
+//! be pruned, and any archived envelope can later be proven against the root.
+//!
+//! ## Inbox Capacity
+//!
+//! Every inbox holds at least `BaseInboxSize` messages. Receivers earn one
+//! extra slot per `InboxStakePerSlot` of staked CLAW (as reported by
+//! `StakeInspect`) and `InboxSlotsPerReputationTier` slots per full 25%
+//! reputation tier, never exceeding the hard `MaxInboxSize`. The send path
+//! checks [`Pallet::effective_inbox_capacity`].
+//!
+//! ## Spam Appeals
+//!
+//! An agent penalised for spam it did not mean to send (typically a client
+//! bug) can file an appeal with `file_spam_appeal`, reserving an appeal bond
+//! and pointing at its off-chain evidence. `AppealOrigin` then decides:
+//! an upheld appeal returns the bond and restores reputation, at least far
+//! enough to clear `MinReputationToSend` again; a rejected one burns the
+//! bond. Every resolved appeal counts as an offense in [`AppealOffenses`],
+//! and each offense adds another `BaseAppealBond` to the next appeal's bond.
+//!
+//! ## Auto-responder
+//!
+//! A message to an account with an enabled [`AutoResponseConfig`] emits
+//! `AutoResponseTriggered` and records the message in
+//! [`PendingAutoReplies`], unless the sender is still within the responder's
+//! per-sender `cooldown_blocks`. The reply itself is `send_auto_reply`, a
+//! plaintext message carrying the configured `response_hash` with `reply_to`
+//! set. Each pending entry is consumed by its reply, so a trigger can be
+//! answered only once; auto-replies never trigger auto-responses.
+//!
+//! Nodes can submit replies on the responder's behalf. An operator opts in by
+//! inserting the responder's key under [`KEY_TYPE`] (`amsg`) into the node
+//! keystore; the offchain worker then answers the local keys' entries in
+//! `PendingAutoReplies`, oldest first, with `send_auto_reply` signed by the
+//! key. A node submits at most `MaxAutoRepliesPerBlock` replies per block, or
+//! the number stored under [`AUTO_REPLY_LIMIT_KEY`] in its persistent
+//! offchain storage. Replies over the limit, failed submissions and replies
+//! still pending [`AUTO_REPLY_RETRY_BLOCKS`] after submission are picked up
+//! again by a later block.
 //!
 //! ## Privacy Model
 //!
@@ -39,6 +118,48 @@ mod tests;
 pub mod weights;
 pub use weights::WeightInfo;
 
+/// Staked CLAW of an account, used to scale its inbox capacity.
+pub trait StakeInspect<AccountId, Balance> {
+    fn staked(who: &AccountId) -> Balance;
+}
+
+impl<AccountId, Balance: sp_runtime::traits::Zero> StakeInspect<AccountId, Balance> for () {
+    fn staked(_who: &AccountId) -> Balance {
+        Balance::zero()
+    }
+}
+
+/// Key type of the auto-responder key a node signs replies with.
+pub const KEY_TYPE: sp_core::crypto::KeyTypeId = sp_core::crypto::KeyTypeId(*b"amsg");
+
+/// Persistent offchain storage key of a node's own per-block auto-reply limit
+/// (SCALE `u32`), overriding `MaxAutoRepliesPerBlock`.
+pub const AUTO_REPLY_LIMIT_KEY: &[u8] = b"anon-messaging::auto-reply-limit";
+
+/// Blocks a node waits for a submitted auto-reply to land before submitting
+/// it again.
+pub const AUTO_REPLY_RETRY_BLOCKS: u32 = 10;
+
+/// Auto-responder keys: sr25519 under [`KEY_TYPE`].
+pub mod crypto {
+    use super::KEY_TYPE;
+    use sp_runtime::{
+        app_crypto::{app_crypto, sr25519},
+        MultiSignature, MultiSigner,
+    };
+
+    app_crypto!(sr25519, KEY_TYPE);
+
+    /// Binds the auto-responder key to the runtime's signature types.
+    pub struct AutoResponderId;
+
+    impl frame_system::offchain::AppCrypto<MultiSigner, MultiSignature> for AutoResponderId {
+        type RuntimeAppPublic = Public;
+        type GenericPublic = sp_core::sr25519::Public;
+        type GenericSignature = sp_core::sr25519::Signature;
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 #[frame_support::pallet]
 pub mod pallet {
@@ -47,10 +168,17 @@ pub mod pallet {
         pallet_prelude::*,
         traits::{Currency, ExistenceRequirement, ReservableCurrency},
     };
-    use frame_system::pallet_prelude::*;
+    use frame_system::{
+        offchain::{AppCrypto, CreateSignedTransaction, SendSignedTransaction, Signer},
+        pallet_prelude::*,
+    };
     use pallet_reputation::ReputationManager;
     use sp_core::H256;
-    use sp_runtime::traits::Saturating;
+    use sp_runtime::offchain::storage::StorageValueRef;
+    use sp_runtime::{
+        traits::{IdentifyAccount, Saturating, Verify, Zero},
+        SaturatedConversion,
+    };
 
     // =========================================================
     // Type aliases
@@ -63,6 +191,9 @@ pub mod pallet {
     /// Globally unique message identifier.
     pub type MessageId = u64;
 
+    /// Reputation (basis points) per tier when scaling inbox capacity.
+    pub const REPUTATION_TIER_BASIS_POINTS: u32 = 2_500;
+
     // =========================================================
     // Core types
     // =========================================================
@@ -103,6 +234,8 @@ pub mod pallet {
         Manual,
         /// Deleted automatically because TTL expired.
         Expired,
+        /// Pruned by the receiver after committing an archive.
+        Archived,
     }
 
     impl codec::DecodeWithMemTracking for DeletionReason {}
@@ -136,6 +269,9 @@ pub mod pallet {
         pub inline_payload: Option<BoundedVec<u8, T::MaxInlinePayloadBytes>>,
         /// ID of the message this is a reply to, if applicable.
         pub reply_to: Option<MessageId>,
+        /// Blake2b-256 of the receiver's public key at send time. `None` if
+        /// the sender overrode the key check with `allow_unkeyed`.
+        pub receiver_key_hash: Option<H256>,
     }
 
     impl<T: Config> codec::DecodeWithMemTracking for MessageEnvelope<T> {}
@@ -167,6 +303,83 @@ pub mod pallet {
 
     impl<T: Config> codec::DecodeWithMemTracking for AutoResponseConfig<T> {}
 
+    /// Domain separator for receiver signatures over sponsored operations.
+    pub const SPONSOR_CONTEXT: &[u8] = b"clawchain/anon-messaging/sponsor";
+
+    /// Operations a sponsor may execute on behalf of a receiver.
+    #[derive(
+        Clone,
+        Encode,
+        Decode,
+        Eq,
+        PartialEq,
+        RuntimeDebug,
+        TypeInfo,
+        MaxEncodedLen,
+        codec::DecodeWithMemTracking,
+    )]
+    pub enum SponsoredOp {
+        /// `read_message(msg_id)`.
+        ReadMessage { msg_id: MessageId },
+        /// `claim_reply_escrow(original_msg_id)`.
+        ClaimReplyEscrow { original_msg_id: MessageId },
+    }
+
+    /// Opt-in guardian recovery settings for an account's messaging identity.
+    #[derive(Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    pub struct RecoveryConfig<T: Config> {
+        /// Accounts allowed to initiate and approve a recovery.
+        pub guardians: BoundedVec<T::AccountId, T::MaxGuardians>,
+        /// Number of guardian approvals required to start the notice period.
+        pub threshold: u32,
+        /// Length of the owner-cancellable notice period in blocks.
+        pub delay_blocks: u32,
+    }
+
+    impl<T: Config> codec::DecodeWithMemTracking for RecoveryConfig<T> {}
+
+    /// A recovery in progress for a lost messaging identity.
+    #[derive(Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    pub struct ActiveRecovery<T: Config> {
+        /// Account that takes over the inbox.
+        pub new_owner: T::AccountId,
+        /// Messaging public key registered for `new_owner`.
+        pub new_key: BoundedVec<u8, T::MaxKeyBytes>,
+        pub key_type: KeyType,
+        /// Guardians that have approved so far.
+        pub approvals: BoundedVec<T::AccountId, T::MaxGuardians>,
+        pub initiated_at: BlockNumberFor<T>,
+        /// Set once the threshold is reached; execution is allowed from this block.
+        pub executable_at: Option<BlockNumberFor<T>>,
+        /// Set once `new_owner` has agreed to take over the identity.
+        pub accepted: bool,
+    }
+
+    impl<T: Config> codec::DecodeWithMemTracking for ActiveRecovery<T> {}
+
+    /// Committed archive of a receiver's inbox.
+    #[derive(
+        Clone,
+        Encode,
+        Decode,
+        Eq,
+        PartialEq,
+        RuntimeDebug,
+        TypeInfo,
+        MaxEncodedLen,
+        codec::DecodeWithMemTracking,
+    )]
+    pub struct ArchiveCommitment<BlockNumber> {
+        /// Merkle root over the archived envelopes.
+        pub merkle_root: H256,
+        pub committed_at: BlockNumber,
+    }
+
+    /// Maximum depth of an archive Merkle proof.
+    pub type MaxArchiveProofDepth = ConstU32<32>;
+
     /// Escrow record for a pay-for-reply message.
     #[derive(Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
     #[scale_info(skip_type_params(T))]
@@ -179,14 +392,29 @@ pub mod pallet {
 
     impl<T: Config> codec::DecodeWithMemTracking for EscrowRecord<T> {}
 
+    /// A pending appeal against a spam penalty.
+    #[derive(Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    pub struct SpamAppeal<T: Config> {
+        /// Reserved from the appellant until the appeal is resolved.
+        pub bond: BalanceOf<T>,
+        /// Hash of the off-chain evidence (e.g. client logs) for review.
+        pub evidence: H256,
+        pub filed_at: BlockNumberFor<T>,
+    }
+
+    impl<T: Config> codec::DecodeWithMemTracking for SpamAppeal<T> {}
+
     // =========================================================
     // Config
     // =========================================================
 
     #[pallet::config]
-    pub trait Config: frame_system::Config {
+    pub trait Config: frame_system::Config + CreateSignedTransaction<Call<Self>> {
         /// Runtime event type.
-        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+        type RuntimeEvent: From<Event<Self>>
+            + IsType<<Self as frame_system::Config>::RuntimeEvent>
+            + TryInto<Event<Self>>;
 
         /// Weight information for extrinsics.
         type WeightInfo: WeightInfo;
@@ -201,10 +429,30 @@ pub mod pallet {
         #[pallet::constant]
         type MaxKeyBytes: Get<u32>;
 
-        /// Maximum number of messages in a single inbox.
+        /// Hard cap on the number of messages in a single inbox.
         #[pallet::constant]
         type MaxInboxSize: Get<u32>;
 
+        /// Inbox capacity before stake and reputation scaling.
+        #[pallet::constant]
+        type BaseInboxSize: Get<u32>;
+
+        /// Blocks after registration at which a public key stops being
+        /// accepted by `send_message` (0 = keys never expire).
+        #[pallet::constant]
+        type MaxKeyAge: Get<u32>;
+
+        /// Source of staked CLAW per account.
+        type StakeInspect: StakeInspect<Self::AccountId, BalanceOf<Self>>;
+
+        /// Staked CLAW that earns one extra inbox slot (zero disables stake scaling).
+        #[pallet::constant]
+        type InboxStakePerSlot: Get<BalanceOf<Self>>;
+
+        /// Extra inbox slots per full reputation tier.
+        #[pallet::constant]
+        type InboxSlotsPerReputationTier: Get<u32>;
+
         /// Maximum byte length for an inline (on-chain) payload.
         #[pallet::constant]
         type MaxInlinePayloadBytes: Get<u32>;
@@ -228,6 +476,34 @@ pub mod pallet {
         /// Safety cap on pay-for-reply escrow amount.
         #[pallet::constant]
         type MaxEscrowAmount: Get<BalanceOf<Self>>;
+
+        /// Signature type receivers use to authorise sponsored operations.
+        type OffchainSignature: Verify<Signer = Self::SigningPublicKey> + Parameter;
+
+        /// Public key type resolving to a receiver account.
+        type SigningPublicKey: IdentifyAccount<AccountId = Self::AccountId>;
+
+        /// Maximum number of recovery guardians per account.
+        #[pallet::constant]
+        type MaxGuardians: Get<u32>;
+
+        /// Minimum notice period (blocks) an account may configure for recovery.
+        #[pallet::constant]
+        type MinRecoveryDelay: Get<u32>;
+
+        /// Origin deciding spam appeals.
+        type AppealOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Bond for a first spam appeal; each recorded offense adds another.
+        #[pallet::constant]
+        type BaseAppealBond: Get<BalanceOf<Self>>;
+
+        /// Key the offchain auto-responder signs `send_auto_reply` with.
+        type AutoResponderId: AppCrypto<Self::Public, Self::Signature>;
+
+        /// Auto-replies a node submits per block unless it sets its own limit.
+        #[pallet::constant]
+        type MaxAutoRepliesPerBlock: Get<u32>;
     }
 
     // =========================================================
@@ -311,6 +587,62 @@ pub mod pallet {
         ValueQuery,
     >;
 
+    /// Per-receiver nonce included in sponsored operation signatures.
+    #[pallet::storage]
+    pub type SponsorNonces<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+    /// Guardian recovery configuration per account.
+    #[pallet::storage]
+    pub type RecoveryConfigs<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, RecoveryConfig<T>, OptionQuery>;
+
+    /// Pending recoveries keyed by the account being recovered.
+    #[pallet::storage]
+    pub type ActiveRecoveries<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, ActiveRecovery<T>, OptionQuery>;
+
+    /// Archive roots per receiver, keyed by the last message ID they cover.
+    #[pallet::storage]
+    pub type Archives<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Twox64Concat,
+        MessageId,
+        ArchiveCommitment<BlockNumberFor<T>>,
+        OptionQuery,
+    >;
+
+    /// Highest message ID covered by a receiver's archives.
+    #[pallet::storage]
+    pub type ArchivedUpTo<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, MessageId, OptionQuery>;
+
+    /// Pending spam appeal per account.
+    #[pallet::storage]
+    pub type SpamAppeals<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, SpamAppeal<T>, OptionQuery>;
+
+    /// Spam appeals resolved for each account, upheld or not. Sets the next
+    /// appeal's bond.
+    #[pallet::storage]
+    pub type AppealOffenses<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+    /// Messages that triggered an auto-response not yet sent, by responder,
+    /// with the response hash at trigger time. Consumed by `send_auto_reply`.
+    #[pallet::storage]
+    pub type PendingAutoReplies<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Blake2_128Concat,
+        MessageId,
+        H256,
+        OptionQuery,
+    >;
+
     // =========================================================
     // Pallet struct & hooks
     // =========================================================
@@ -331,6 +663,12 @@ pub mod pallet {
 
             T::WeightInfo::on_initialize(count)
         }
+
+        /// Submit pending auto-replies for responders whose key is in the
+        /// node's keystore.
+        fn offchain_worker(n: BlockNumberFor<T>) {
+            Self::submit_auto_replies(n);
+        }
     }
 
     // =========================================================
@@ -402,6 +740,91 @@ pub mod pallet {
             sender: T::AccountId,
             amount: BalanceOf<T>,
         },
+
+        /// A sponsor executed a receiver-signed operation.
+        SponsoredExecuted {
+            sponsor: T::AccountId,
+            receiver: T::AccountId,
+            op: SponsoredOp,
+        },
+
+        /// An account configured guardian recovery.
+        RecoveryConfigured {
+            account: T::AccountId,
+            guardians: u32,
+            threshold: u32,
+        },
+
+        /// An account removed its recovery configuration.
+        RecoveryConfigRemoved { account: T::AccountId },
+
+        /// A guardian proposed a recovery.
+        RecoveryInitiated {
+            lost: T::AccountId,
+            new_owner: T::AccountId,
+            guardian: T::AccountId,
+        },
+
+        /// A guardian approved a pending recovery.
+        RecoveryApproved {
+            lost: T::AccountId,
+            guardian: T::AccountId,
+            approvals: u32,
+        },
+
+        /// The approval threshold was reached; the owner may cancel until `executable_at`.
+        RecoveryNoticeStarted {
+            lost: T::AccountId,
+            new_owner: T::AccountId,
+            executable_at: BlockNumberFor<T>,
+        },
+
+        /// The proposed new owner agreed to take over `lost`'s identity.
+        RecoveryAccepted {
+            lost: T::AccountId,
+            new_owner: T::AccountId,
+        },
+
+        /// The owner cancelled a pending recovery.
+        RecoveryCancelled { lost: T::AccountId },
+
+        /// The messaging identity was moved to `new_owner`.
+        RecoveryExecuted {
+            lost: T::AccountId,
+            new_owner: T::AccountId,
+            messages_moved: u32,
+        },
+
+        /// A receiver committed an inbox archive root.
+        ArchiveCommitted {
+            receiver: T::AccountId,
+            up_to_msg_id: MessageId,
+            merkle_root: H256,
+        },
+
+        /// Archived messages were pruned from a receiver's inbox.
+        ArchivePruned { receiver: T::AccountId, count: u32 },
+
+        /// An archived envelope was proven against a committed root.
+        ArchivedMessageProven {
+            receiver: T::AccountId,
+            up_to_msg_id: MessageId,
+            leaf: H256,
+        },
+
+        /// An account appealed a spam penalty.
+        SpamAppealFiled {
+            account: T::AccountId,
+            bond: BalanceOf<T>,
+            evidence: H256,
+        },
+
+        /// A spam appeal was decided. `restored` is the reputation given back.
+        SpamAppealResolved {
+            account: T::AccountId,
+            upheld: bool,
+            restored: u32,
+        },
     }
 
     // =========================================================
@@ -418,7 +841,7 @@ pub mod pallet {
         MessageNotFound,
         /// Caller is not the sender or receiver of this message.
         Unauthorized,
-        /// Public key has not been registered.
+        /// Public key has not been registered, or has outlived `MaxKeyAge`.
         KeyNotRegistered,
         /// Key bytes have invalid length for the specified key type.
         InvalidKeyLength,
@@ -442,6 +865,42 @@ pub mod pallet {
         EphemeralQueueFull,
         /// Caller has insufficient balance to reserve for escrow.
         InsufficientBalance,
+        /// The receiver's signature does not authorise this sponsored operation.
+        InvalidSponsorSignature,
+        /// The sponsored operation's expiry block has passed.
+        SponsoredOpExpired,
+        /// Guardians, threshold or delay are invalid.
+        InvalidRecoveryConfig,
+        /// The account has not opted in to recovery.
+        RecoveryNotConfigured,
+        /// Caller is not a guardian of the account.
+        NotGuardian,
+        /// A recovery is already pending for the account.
+        RecoveryAlreadyActive,
+        /// No recovery is pending for the account.
+        NoActiveRecovery,
+        /// The guardian has already approved this recovery.
+        AlreadyApproved,
+        /// The threshold has not been reached or the notice period has not elapsed.
+        RecoveryNotExecutable,
+        /// The new owner must differ from the account being recovered.
+        InvalidRecoveryAccount,
+        /// Caller is not the new owner proposed by the pending recovery.
+        NotRecoveryNewOwner,
+        /// The proposed new owner has not accepted the recovery.
+        RecoveryNotAccepted,
+        /// Archives must cover sent messages beyond the previous archive.
+        InvalidArchiveRange,
+        /// No archive has been committed for this receiver and message ID.
+        ArchiveNotFound,
+        /// The Merkle proof does not match the committed archive root.
+        InvalidArchiveProof,
+        /// The account already has a spam appeal pending.
+        AppealAlreadyPending,
+        /// The account has no spam appeal pending.
+        NoPendingAppeal,
+        /// The message has no auto-response waiting to be sent.
+        NoPendingAutoReply,
     }
 
     // =========================================================
@@ -496,6 +955,11 @@ pub mod pallet {
         ///
         /// Set `pay_for_reply > 0` to lock CLAW as an incentive for the receiver
         /// to reply. The receiver calls `claim_reply_escrow` after replying.
+        ///
+        /// The receiver must have a registered, unexpired public key so the
+        /// sender cannot encrypt to a stale or missing key; its hash is stored
+        /// in the envelope. Set `allow_unkeyed` to skip the check (e.g. for
+        /// plaintext notices).
         #[pallet::call_index(1)]
         #[pallet::weight(T::WeightInfo::send_message())]
         pub fn send_message(
@@ -507,66 +971,682 @@ pub mod pallet {
             pay_for_reply: BalanceOf<T>,
             inline_payload: Option<BoundedVec<u8, T::MaxInlinePayloadBytes>>,
             reply_to: Option<MessageId>,
+            allow_unkeyed: bool,
         ) -> DispatchResult {
             let sender = ensure_signed(origin)?;
+            Self::do_send_message(
+                sender,
+                receiver,
+                content_hash,
+                nonce,
+                ttl_blocks,
+                pay_for_reply,
+                inline_payload,
+                reply_to,
+                allow_unkeyed,
+                false,
+            )
+        }
 
-            // Reputation gate
-            ensure!(
-                T::ReputationManager::meets_minimum_reputation(
-                    &sender,
-                    T::MinReputationToSend::get()
-                ),
-                Error::<T>::InsufficientReputation
-            );
+        /// Mark a message as read (on-chain read receipt).
+        ///
+        /// Only the receiver may call this. The receipt is visible on-chain and
+        /// via the emitted `MessageRead` event.
+        #[pallet::call_index(2)]
+        #[pallet::weight(T::WeightInfo::read_message())]
+        pub fn read_message(origin: OriginFor<T>, msg_id: MessageId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            Self::do_read_message(who, msg_id)
+        }
 
-            // Validate TTL
-            if ttl_blocks != 0 {
-                ensure!(ttl_blocks >= T::MinTtlBlocks::get(), Error::<T>::InvalidTtl);
-                ensure!(ttl_blocks <= T::MaxTtlBlocks::get(), Error::<T>::InvalidTtl);
-            }
+        /// Delete a message. Callable by sender or receiver.
+        ///
+        /// Any unreleased pay-for-reply escrow is automatically refunded to
+        /// the original sender.
+        #[pallet::call_index(3)]
+        #[pallet::weight(T::WeightInfo::delete_message())]
+        pub fn delete_message(origin: OriginFor<T>, msg_id: MessageId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
 
-            // Validate escrow cap
-            ensure!(
-                pay_for_reply <= T::MaxEscrowAmount::get(),
-                Error::<T>::EscrowTooLarge
-            );
+            // Load envelope from receiver's inbox — we need to know who the receiver is.
+            // Try the caller as receiver first, then check if they're the sender.
+            let envelope = if let Some(env) = Inbox::<T>::get(&who, msg_id) {
+                env
+            } else {
+                // Caller might be the sender — scan isn't viable, so we require
+                // the caller to be the receiver. Sender-side delete is handled via
+                // a separate approach: store a SentIndex for senders in Phase 2.
+                // For Phase 1, only receiver can delete directly. Sender can use
+                // the receiver's account when they know it. We allow sender-side
+                // if the envelope is accessible (e.g., inbox of another account).
+                return Err(Error::<T>::MessageNotFound.into());
+            };
 
-            // Check inbox capacity
-            let inbox = InboxIndex::<T>::get(&receiver);
             ensure!(
-                (inbox.len() as u32) < T::MaxInboxSize::get(),
-                Error::<T>::InboxFull
+                envelope.sender == who || envelope.receiver == who,
+                Error::<T>::Unauthorized
             );
 
-            // Assign message ID
-            let msg_id = NextMessageId::<T>::get();
-            let next = msg_id.checked_add(1).ok_or(Error::<T>::MessageIdOverflow)?;
-            NextMessageId::<T>::put(next);
+            Self::do_delete_message(&envelope.receiver, msg_id, DeletionReason::Manual);
 
-            let now = frame_system::Pallet::<T>::block_number();
+            Self::deposit_event(Event::MessageDeleted {
+                msg_id,
+                deleted_by: who,
+                reason: DeletionReason::Manual,
+            });
 
-            // Reserve escrow if requested
-            {
-                let zero: BalanceOf<T> = 0u32.into();
-                if pay_for_reply > zero {
-                    T::Currency::reserve(&sender, pay_for_reply)
-                        .map_err(|_| Error::<T>::InsufficientBalance)?;
+            Ok(())
+        }
 
-                    MessageEscrow::<T>::insert(
-                        msg_id,
-                        EscrowRecord {
-                            sender: sender.clone(),
-                            receiver: receiver.clone(),
-                            amount: pay_for_reply,
-                            locked_at: now,
-                        },
-                    );
+        /// Configure auto-response for incoming messages.
+        ///
+        /// When enabled, `MessageSent` events will also include an
+        /// `AutoResponseTriggered` event if the conditions are met.
+        #[pallet::call_index(4)]
+        #[pallet::weight(T::WeightInfo::set_auto_response())]
+        pub fn set_auto_response(
+            origin: OriginFor<T>,
+            config: AutoResponseConfig<T>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
 
-                    Self::deposit_event(Event::EscrowLocked {
-                        msg_id,
-                        sender: sender.clone(),
-                        amount: pay_for_reply,
-                    });
+            let enabled = config.enabled;
+            AutoResponses::<T>::insert(&who, config);
+
+            Self::deposit_event(Event::AutoResponseConfigured {
+                account: who,
+                enabled,
+            });
+            Ok(())
+        }
+
+        /// Claim escrowed CLAW after having replied to a pay-for-reply message.
+        ///
+        /// The caller must be the receiver of `original_msg_id` and must have
+        /// previously sent a reply referencing `reply_to = Some(original_msg_id)`.
+        #[pallet::call_index(5)]
+        #[pallet::weight(T::WeightInfo::claim_reply_escrow())]
+        pub fn claim_reply_escrow(
+            origin: OriginFor<T>,
+            original_msg_id: MessageId,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            Self::do_claim_reply_escrow(who, original_msg_id)
+        }
+
+        /// Execute a receiver-signed read or claim, with the caller paying fees.
+        ///
+        /// `signature` must be the receiver's signature over
+        /// `sponsored_op_payload(receiver, op, SponsorNonces[receiver], expires_at)`,
+        /// and the current block must not be past `expires_at`. The nonce
+        /// advances on success so the signature cannot be replayed.
+        #[pallet::call_index(6)]
+        #[pallet::weight(T::WeightInfo::sponsor_exec())]
+        pub fn sponsor_exec(
+            origin: OriginFor<T>,
+            receiver: T::AccountId,
+            op: SponsoredOp,
+            expires_at: BlockNumberFor<T>,
+            signature: T::OffchainSignature,
+        ) -> DispatchResult {
+            let sponsor = ensure_signed(origin)?;
+
+            ensure!(
+                frame_system::Pallet::<T>::block_number() <= expires_at,
+                Error::<T>::SponsoredOpExpired
+            );
+            let nonce = SponsorNonces::<T>::get(&receiver);
+            let payload = Self::sponsored_op_payload(&receiver, &op, nonce, expires_at);
+            ensure!(
+                signature.verify(&payload[..], &receiver),
+                Error::<T>::InvalidSponsorSignature
+            );
+
+            match op {
+                SponsoredOp::ReadMessage { msg_id } => {
+                    Self::do_read_message(receiver.clone(), msg_id)?
+                }
+                SponsoredOp::ClaimReplyEscrow { original_msg_id } => {
+                    Self::do_claim_reply_escrow(receiver.clone(), original_msg_id)?
+                }
+            }
+
+            SponsorNonces::<T>::insert(&receiver, nonce.wrapping_add(1));
+
+            Self::deposit_event(Event::SponsoredExecuted {
+                sponsor,
+                receiver,
+                op,
+            });
+
+            Ok(())
+        }
+
+        /// Opt in to guardian recovery of the caller's messaging identity.
+        ///
+        /// Replaces any existing configuration. Not allowed while a recovery
+        /// is pending.
+        #[pallet::call_index(7)]
+        #[pallet::weight(T::WeightInfo::set_recovery_config())]
+        pub fn set_recovery_config(
+            origin: OriginFor<T>,
+            guardians: alloc::vec::Vec<T::AccountId>,
+            threshold: u32,
+            delay_blocks: u32,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            ensure!(
+                !ActiveRecoveries::<T>::contains_key(&who),
+                Error::<T>::RecoveryAlreadyActive
+            );
+
+            let mut sorted = guardians.clone();
+            sorted.sort();
+            sorted.dedup();
+            ensure!(
+                sorted.len() == guardians.len() && !guardians.contains(&who),
+                Error::<T>::InvalidRecoveryConfig
+            );
+            ensure!(
+                threshold >= 1 && threshold as usize <= guardians.len(),
+                Error::<T>::InvalidRecoveryConfig
+            );
+            ensure!(
+                delay_blocks >= T::MinRecoveryDelay::get(),
+                Error::<T>::InvalidRecoveryConfig
+            );
+            let guardians: BoundedVec<T::AccountId, T::MaxGuardians> = guardians
+                .try_into()
+                .map_err(|_| Error::<T>::InvalidRecoveryConfig)?;
+            let count = guardians.len() as u32;
+
+            RecoveryConfigs::<T>::insert(
+                &who,
+                RecoveryConfig {
+                    guardians,
+                    threshold,
+                    delay_blocks,
+                },
+            );
+
+            Self::deposit_event(Event::RecoveryConfigured {
+                account: who,
+                guardians: count,
+                threshold,
+            });
+            Ok(())
+        }
+
+        /// Opt out of guardian recovery. Not allowed while a recovery is pending.
+        #[pallet::call_index(8)]
+        #[pallet::weight(T::WeightInfo::remove_recovery_config())]
+        pub fn remove_recovery_config(origin: OriginFor<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            ensure!(
+                !ActiveRecoveries::<T>::contains_key(&who),
+                Error::<T>::RecoveryAlreadyActive
+            );
+            ensure!(
+                RecoveryConfigs::<T>::take(&who).is_some(),
+                Error::<T>::RecoveryNotConfigured
+            );
+
+            Self::deposit_event(Event::RecoveryConfigRemoved { account: who });
+            Ok(())
+        }
+
+        /// Guardian proposes moving `lost`'s messaging identity to `new_owner`.
+        ///
+        /// Counts as the initiating guardian's approval.
+        #[pallet::call_index(9)]
+        #[pallet::weight(T::WeightInfo::initiate_recovery())]
+        pub fn initiate_recovery(
+            origin: OriginFor<T>,
+            lost: T::AccountId,
+            new_owner: T::AccountId,
+            new_key: BoundedVec<u8, T::MaxKeyBytes>,
+            key_type: KeyType,
+        ) -> DispatchResult {
+            let guardian = ensure_signed(origin)?;
+
+            let config =
+                RecoveryConfigs::<T>::get(&lost).ok_or(Error::<T>::RecoveryNotConfigured)?;
+            ensure!(
+                config.guardians.contains(&guardian),
+                Error::<T>::NotGuardian
+            );
+            ensure!(
+                !ActiveRecoveries::<T>::contains_key(&lost),
+                Error::<T>::RecoveryAlreadyActive
+            );
+            ensure!(new_owner != lost, Error::<T>::InvalidRecoveryAccount);
+            ensure!(new_key.len() == 32, Error::<T>::InvalidKeyLength);
+
+            let now = frame_system::Pallet::<T>::block_number();
+            let mut recovery = ActiveRecovery {
+                new_owner: new_owner.clone(),
+                new_key,
+                key_type,
+                approvals: BoundedVec::default(),
+                initiated_at: now,
+                executable_at: None,
+                accepted: false,
+            };
+
+            Self::deposit_event(Event::RecoveryInitiated {
+                lost: lost.clone(),
+                new_owner,
+                guardian: guardian.clone(),
+            });
+
+            Self::add_recovery_approval(&lost, &config, &mut recovery, guardian)?;
+            ActiveRecoveries::<T>::insert(&lost, recovery);
+            Ok(())
+        }
+
+        /// Guardian approves the pending recovery of `lost`.
+        #[pallet::call_index(10)]
+        #[pallet::weight(T::WeightInfo::approve_recovery())]
+        pub fn approve_recovery(origin: OriginFor<T>, lost: T::AccountId) -> DispatchResult {
+            let guardian = ensure_signed(origin)?;
+
+            let config =
+                RecoveryConfigs::<T>::get(&lost).ok_or(Error::<T>::RecoveryNotConfigured)?;
+            ensure!(
+                config.guardians.contains(&guardian),
+                Error::<T>::NotGuardian
+            );
+
+            ActiveRecoveries::<T>::try_mutate(&lost, |maybe| -> DispatchResult {
+                let recovery = maybe.as_mut().ok_or(Error::<T>::NoActiveRecovery)?;
+                Self::add_recovery_approval(&lost, &config, recovery, guardian)
+            })
+        }
+
+        /// Owner cancels a pending recovery of their own account.
+        #[pallet::call_index(11)]
+        #[pallet::weight(T::WeightInfo::cancel_recovery())]
+        pub fn cancel_recovery(origin: OriginFor<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            ensure!(
+                ActiveRecoveries::<T>::take(&who).is_some(),
+                Error::<T>::NoActiveRecovery
+            );
+
+            Self::deposit_event(Event::RecoveryCancelled { lost: who });
+            Ok(())
+        }
+
+        /// Complete the recovery of `lost` once its notice period has elapsed
+        /// and the new owner has accepted.
+        ///
+        /// Callable by anyone. Registers the new key for the new owner, moves
+        /// the inbox and clears `lost`'s key and recovery configuration.
+        #[pallet::call_index(12)]
+        #[pallet::weight(T::WeightInfo::execute_recovery(T::MaxInboxSize::get()))]
+        pub fn execute_recovery(origin: OriginFor<T>, lost: T::AccountId) -> DispatchResult {
+            ensure_signed(origin)?;
+
+            let recovery = ActiveRecoveries::<T>::get(&lost).ok_or(Error::<T>::NoActiveRecovery)?;
+            let now = frame_system::Pallet::<T>::block_number();
+            ensure!(
+                recovery.executable_at.is_some_and(|at| now >= at),
+                Error::<T>::RecoveryNotExecutable
+            );
+            ensure!(recovery.accepted, Error::<T>::RecoveryNotAccepted);
+
+            let new_owner = recovery.new_owner;
+            let moved = Self::move_inbox(&lost, &new_owner)?;
+
+            PublicKeys::<T>::remove(&lost);
+            PublicKeys::<T>::insert(
+                &new_owner,
+                PublicKeyRecord {
+                    key: recovery.new_key,
+                    registered_at: now,
+                    key_type: recovery.key_type.clone(),
+                },
+            );
+            ActiveRecoveries::<T>::remove(&lost);
+            RecoveryConfigs::<T>::remove(&lost);
+
+            Self::deposit_event(Event::PublicKeyRegistered {
+                account: new_owner.clone(),
+                key_type: recovery.key_type,
+            });
+            Self::deposit_event(Event::RecoveryExecuted {
+                lost,
+                new_owner,
+                messages_moved: moved,
+            });
+            Ok(())
+        }
+
+        /// Commit the Merkle root of the caller's archived inbox up to `up_to_msg_id`.
+        ///
+        /// Each archive must extend past the previous one. Messages covered by
+        /// an archive can then be removed with `prune_archived`.
+        #[pallet::call_index(13)]
+        #[pallet::weight(T::WeightInfo::commit_archive())]
+        pub fn commit_archive(
+            origin: OriginFor<T>,
+            merkle_root: H256,
+            up_to_msg_id: MessageId,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            ensure!(
+                up_to_msg_id < NextMessageId::<T>::get(),
+                Error::<T>::InvalidArchiveRange
+            );
+            if let Some(previous) = ArchivedUpTo::<T>::get(&who) {
+                ensure!(up_to_msg_id > previous, Error::<T>::InvalidArchiveRange);
+            }
+
+            Archives::<T>::insert(
+                &who,
+                up_to_msg_id,
+                ArchiveCommitment {
+                    merkle_root,
+                    committed_at: frame_system::Pallet::<T>::block_number(),
+                },
+            );
+            ArchivedUpTo::<T>::insert(&who, up_to_msg_id);
+
+            Self::deposit_event(Event::ArchiveCommitted {
+                receiver: who,
+                up_to_msg_id,
+                merkle_root,
+            });
+            Ok(())
+        }
+
+        /// Delete up to `max` archived messages from the caller's inbox.
+        ///
+        /// Unclaimed pay-for-reply escrow on pruned messages is refunded to
+        /// the sender, as with `delete_message`.
+        #[pallet::call_index(14)]
+        #[pallet::weight(T::WeightInfo::prune_archived(*max))]
+        pub fn prune_archived(origin: OriginFor<T>, max: u32) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let up_to = ArchivedUpTo::<T>::get(&who).ok_or(Error::<T>::ArchiveNotFound)?;
+            let archived: alloc::vec::Vec<MessageId> = InboxIndex::<T>::get(&who)
+                .into_iter()
+                .filter(|&id| id <= up_to)
+                .take(max as usize)
+                .collect();
+
+            for &msg_id in archived.iter() {
+                Self::do_delete_message(&who, msg_id, DeletionReason::Archived);
+            }
+
+            Self::deposit_event(Event::ArchivePruned {
+                receiver: who,
+                count: archived.len() as u32,
+            });
+            Ok(())
+        }
+
+        /// Prove that `leaf` is part of `receiver`'s archive ending at `up_to_msg_id`.
+        ///
+        /// Emits `ArchivedMessageProven` as on-chain evidence for disputes.
+        #[pallet::call_index(15)]
+        #[pallet::weight(T::WeightInfo::prove_archived_message())]
+        pub fn prove_archived_message(
+            origin: OriginFor<T>,
+            receiver: T::AccountId,
+            up_to_msg_id: MessageId,
+            leaf: H256,
+            proof: BoundedVec<H256, MaxArchiveProofDepth>,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+
+            let archive =
+                Archives::<T>::get(&receiver, up_to_msg_id).ok_or(Error::<T>::ArchiveNotFound)?;
+            ensure!(
+                Self::archive_root_from_proof(leaf, &proof) == archive.merkle_root,
+                Error::<T>::InvalidArchiveProof
+            );
+
+            Self::deposit_event(Event::ArchivedMessageProven {
+                receiver,
+                up_to_msg_id,
+                leaf,
+            });
+            Ok(())
+        }
+
+        /// Appeal a spam penalty, reserving [`Pallet::appeal_bond`].
+        ///
+        /// `evidence` is the hash of the off-chain material reviewers should
+        /// look at. One appeal may be pending per account.
+        #[pallet::call_index(16)]
+        #[pallet::weight(T::WeightInfo::file_spam_appeal())]
+        pub fn file_spam_appeal(origin: OriginFor<T>, evidence: H256) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            ensure!(
+                !SpamAppeals::<T>::contains_key(&who),
+                Error::<T>::AppealAlreadyPending
+            );
+            let bond = Self::appeal_bond(&who);
+            T::Currency::reserve(&who, bond).map_err(|_| Error::<T>::InsufficientBalance)?;
+
+            SpamAppeals::<T>::insert(
+                &who,
+                SpamAppeal {
+                    bond,
+                    evidence,
+                    filed_at: frame_system::Pallet::<T>::block_number(),
+                },
+            );
+
+            Self::deposit_event(Event::SpamAppealFiled {
+                account: who,
+                bond,
+                evidence,
+            });
+            Ok(())
+        }
+
+        /// Decide `account`'s pending spam appeal.
+        ///
+        /// If `upheld`, the bond is returned and `restore` basis points of
+        /// reputation are given back, raised if needed so the account meets
+        /// `MinReputationToSend` again. Otherwise the bond is burned. Either
+        /// way the appeal counts as an offense.
+        #[pallet::call_index(17)]
+        #[pallet::weight(T::WeightInfo::resolve_spam_appeal())]
+        pub fn resolve_spam_appeal(
+            origin: OriginFor<T>,
+            account: T::AccountId,
+            upheld: bool,
+            restore: u32,
+        ) -> DispatchResult {
+            T::AppealOrigin::ensure_origin(origin)?;
+
+            let appeal = SpamAppeals::<T>::take(&account).ok_or(Error::<T>::NoPendingAppeal)?;
+            AppealOffenses::<T>::mutate(&account, |offenses| {
+                *offenses = offenses.saturating_add(1)
+            });
+
+            let restored = if upheld {
+                T::Currency::unreserve(&account, appeal.bond);
+                let shortfall = T::MinReputationToSend::get()
+                    .saturating_sub(T::ReputationManager::get_reputation(&account));
+                let restored = restore.max(shortfall);
+                T::ReputationManager::restore_reputation(&account, restored);
+                restored
+            } else {
+                let _ = T::Currency::slash_reserved(&account, appeal.bond);
+                0
+            };
+
+            Self::deposit_event(Event::SpamAppealResolved {
+                account,
+                upheld,
+                restored,
+            });
+            Ok(())
+        }
+
+        /// Send the auto-reply to a message that triggered the caller's
+        /// auto-response.
+        ///
+        /// The reply goes to the original sender as a plaintext message with
+        /// the triggering config's `response_hash` as content and `reply_to`
+        /// set, so it also unlocks any pay-for-reply escrow. Normally
+        /// submitted by the responder's node from its offchain worker.
+        #[pallet::call_index(18)]
+        #[pallet::weight(T::WeightInfo::send_auto_reply())]
+        pub fn send_auto_reply(origin: OriginFor<T>, original_msg_id: MessageId) -> DispatchResult {
+            let responder = ensure_signed(origin)?;
+
+            let envelope =
+                Inbox::<T>::get(&responder, original_msg_id).ok_or(Error::<T>::MessageNotFound)?;
+            let response_hash = PendingAutoReplies::<T>::take(&responder, original_msg_id)
+                .ok_or(Error::<T>::NoPendingAutoReply)?;
+
+            // Deterministic per message; the reply is not encrypted.
+            let nonce = BoundedVec::truncate_from(
+                sp_io::hashing::blake2_256(&(b"auto-reply", original_msg_id).encode()).to_vec(),
+            );
+
+            Self::do_send_message(
+                responder,
+                envelope.sender,
+                response_hash,
+                nonce,
+                0,
+                Zero::zero(),
+                None,
+                Some(original_msg_id),
+                true,
+                true,
+            )
+        }
+
+        /// The proposed new owner agrees to take over `lost`'s messaging
+        /// identity: its key is replaced and `lost`'s inbox merged into its own.
+        ///
+        /// Required before `execute_recovery`.
+        #[pallet::call_index(19)]
+        #[pallet::weight(T::WeightInfo::accept_recovery())]
+        pub fn accept_recovery(origin: OriginFor<T>, lost: T::AccountId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            ActiveRecoveries::<T>::try_mutate(&lost, |maybe| -> DispatchResult {
+                let recovery = maybe.as_mut().ok_or(Error::<T>::NoActiveRecovery)?;
+                ensure!(recovery.new_owner == who, Error::<T>::NotRecoveryNewOwner);
+                recovery.accepted = true;
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::RecoveryAccepted {
+                lost,
+                new_owner: who,
+            });
+            Ok(())
+        }
+    }
+
+    // =========================================================
+    // Internal helpers
+    // =========================================================
+
+    impl<T: Config> Pallet<T> {
+        /// Bond `who` must reserve to file a spam appeal: `BaseAppealBond`
+        /// for each recorded offense plus one.
+        pub fn appeal_bond(who: &T::AccountId) -> BalanceOf<T> {
+            let appeals = AppealOffenses::<T>::get(who).saturating_add(1);
+            T::BaseAppealBond::get().saturating_mul(appeals.into())
+        }
+
+        /// Send a message envelope from `sender`; see `send_message`.
+        /// `is_auto_reply` marks replies sent through `send_auto_reply`.
+        fn do_send_message(
+            sender: T::AccountId,
+            receiver: T::AccountId,
+            content_hash: H256,
+            nonce: BoundedVec<u8, ConstU32<24>>,
+            ttl_blocks: u32,
+            pay_for_reply: BalanceOf<T>,
+            inline_payload: Option<BoundedVec<u8, T::MaxInlinePayloadBytes>>,
+            reply_to: Option<MessageId>,
+            allow_unkeyed: bool,
+            is_auto_reply: bool,
+        ) -> DispatchResult {
+            // Reputation gate
+            ensure!(
+                T::ReputationManager::meets_minimum_reputation(
+                    &sender,
+                    T::MinReputationToSend::get()
+                ),
+                Error::<T>::InsufficientReputation
+            );
+
+            let now = frame_system::Pallet::<T>::block_number();
+
+            // Key agreement: the receiver must hold a live encryption key
+            let receiver_key_hash = if allow_unkeyed {
+                None
+            } else {
+                let record = PublicKeys::<T>::get(&receiver)
+                    .filter(|r| !Self::key_expired(r, now))
+                    .ok_or(Error::<T>::KeyNotRegistered)?;
+                Some(H256::from(sp_io::hashing::blake2_256(&record.key)))
+            };
+
+            // Validate TTL
+            if ttl_blocks != 0 {
+                ensure!(ttl_blocks >= T::MinTtlBlocks::get(), Error::<T>::InvalidTtl);
+                ensure!(ttl_blocks <= T::MaxTtlBlocks::get(), Error::<T>::InvalidTtl);
+            }
+
+            // Validate escrow cap
+            ensure!(
+                pay_for_reply <= T::MaxEscrowAmount::get(),
+                Error::<T>::EscrowTooLarge
+            );
+
+            // Check inbox capacity
+            let inbox = InboxIndex::<T>::get(&receiver);
+            ensure!(
+                (inbox.len() as u32) < Self::effective_inbox_capacity(&receiver),
+                Error::<T>::InboxFull
+            );
+
+            // Assign message ID
+            let msg_id = NextMessageId::<T>::get();
+            let next = msg_id.checked_add(1).ok_or(Error::<T>::MessageIdOverflow)?;
+            NextMessageId::<T>::put(next);
+
+            // Reserve escrow if requested
+            {
+                let zero: BalanceOf<T> = 0u32.into();
+                if pay_for_reply > zero {
+                    T::Currency::reserve(&sender, pay_for_reply)
+                        .map_err(|_| Error::<T>::InsufficientBalance)?;
+
+                    MessageEscrow::<T>::insert(
+                        msg_id,
+                        EscrowRecord {
+                            sender: sender.clone(),
+                            receiver: receiver.clone(),
+                            amount: pay_for_reply,
+                            locked_at: now,
+                        },
+                    );
+
+                    Self::deposit_event(Event::EscrowLocked {
+                        msg_id,
+                        sender: sender.clone(),
+                        amount: pay_for_reply,
+                    });
                 }
             }
 
@@ -615,6 +1695,7 @@ pub mod pallet {
                 pay_for_reply,
                 inline_payload,
                 reply_to,
+                receiver_key_hash,
             };
 
             Inbox::<T>::insert(&receiver, msg_id, envelope);
@@ -623,8 +1704,11 @@ pub mod pallet {
                 let _ = idx.try_push(msg_id);
             });
 
-            // Check if receiver has auto-response enabled
-            Self::maybe_trigger_auto_response(&receiver, msg_id, pay_for_reply, now);
+            // Check if receiver has auto-response enabled. Auto-replies never
+            // trigger one, so two auto-responders cannot ping-pong.
+            if !is_auto_reply {
+                Self::maybe_trigger_auto_response(&sender, &receiver, msg_id, pay_for_reply, now);
+            }
 
             Self::deposit_event(Event::MessageSent {
                 msg_id,
@@ -638,15 +1722,8 @@ pub mod pallet {
             Ok(())
         }
 
-        /// Mark a message as read (on-chain read receipt).
-        ///
-        /// Only the receiver may call this. The receipt is visible on-chain and
-        /// via the emitted `MessageRead` event.
-        #[pallet::call_index(2)]
-        #[pallet::weight(T::WeightInfo::read_message())]
-        pub fn read_message(origin: OriginFor<T>, msg_id: MessageId) -> DispatchResult {
-            let who = ensure_signed(origin)?;
-
+        /// Mark `msg_id` in `who`'s inbox as read.
+        fn do_read_message(who: T::AccountId, msg_id: MessageId) -> DispatchResult {
             Inbox::<T>::try_mutate(&who, msg_id, |maybe| -> DispatchResult {
                 if let Some(env) = maybe {
                     ensure!(env.receiver == who, Error::<T>::Unauthorized);
@@ -664,79 +1741,8 @@ pub mod pallet {
             Ok(())
         }
 
-        /// Delete a message. Callable by sender or receiver.
-        ///
-        /// Any unreleased pay-for-reply escrow is automatically refunded to
-        /// the original sender.
-        #[pallet::call_index(3)]
-        #[pallet::weight(T::WeightInfo::delete_message())]
-        pub fn delete_message(origin: OriginFor<T>, msg_id: MessageId) -> DispatchResult {
-            let who = ensure_signed(origin)?;
-
-            // Load envelope from receiver's inbox — we need to know who the receiver is.
-            // Try the caller as receiver first, then check if they're the sender.
-            let envelope = if let Some(env) = Inbox::<T>::get(&who, msg_id) {
-                env
-            } else {
-                // Caller might be the sender — scan isn't viable, so we require
-                // the caller to be the receiver. Sender-side delete is handled via
-                // a separate approach: store a SentIndex for senders in Phase 2.
-                // For Phase 1, only receiver can delete directly. Sender can use
-                // the receiver's account when they know it. We allow sender-side
-                // if the envelope is accessible (e.g., inbox of another account).
-                return Err(Error::<T>::MessageNotFound.into());
-            };
-
-            ensure!(
-                envelope.sender == who || envelope.receiver == who,
-                Error::<T>::Unauthorized
-            );
-
-            Self::do_delete_message(&envelope.receiver, msg_id, DeletionReason::Manual);
-
-            Self::deposit_event(Event::MessageDeleted {
-                msg_id,
-                deleted_by: who,
-                reason: DeletionReason::Manual,
-            });
-
-            Ok(())
-        }
-
-        /// Configure auto-response for incoming messages.
-        ///
-        /// When enabled, `MessageSent` events will also include an
-        /// `AutoResponseTriggered` event if the conditions are met.
-        #[pallet::call_index(4)]
-        #[pallet::weight(T::WeightInfo::set_auto_response())]
-        pub fn set_auto_response(
-            origin: OriginFor<T>,
-            config: AutoResponseConfig<T>,
-        ) -> DispatchResult {
-            let who = ensure_signed(origin)?;
-
-            let enabled = config.enabled;
-            AutoResponses::<T>::insert(&who, config);
-
-            Self::deposit_event(Event::AutoResponseConfigured {
-                account: who,
-                enabled,
-            });
-            Ok(())
-        }
-
-        /// Claim escrowed CLAW after having replied to a pay-for-reply message.
-        ///
-        /// The caller must be the receiver of `original_msg_id` and must have
-        /// previously sent a reply referencing `reply_to = Some(original_msg_id)`.
-        #[pallet::call_index(5)]
-        #[pallet::weight(T::WeightInfo::claim_reply_escrow())]
-        pub fn claim_reply_escrow(
-            origin: OriginFor<T>,
-            original_msg_id: MessageId,
-        ) -> DispatchResult {
-            let who = ensure_signed(origin)?;
-
+        /// Release the pay-for-reply escrow of `original_msg_id` to `who`.
+        fn do_claim_reply_escrow(who: T::AccountId, original_msg_id: MessageId) -> DispatchResult {
             // Verify a reply was sent
             ensure!(
                 EscrowReplied::<T>::contains_key(original_msg_id),
@@ -768,13 +1774,152 @@ pub mod pallet {
 
             Ok(())
         }
-    }
 
-    // =========================================================
-    // Internal helpers
-    // =========================================================
+        /// Whether `record` is older than `MaxKeyAge` at block `now`.
+        pub fn key_expired(record: &PublicKeyRecord<T>, now: BlockNumberFor<T>) -> bool {
+            let max_age = T::MaxKeyAge::get();
+            max_age != 0 && now >= record.registered_at.saturating_add(max_age.into())
+        }
+
+        /// Number of messages `who`'s inbox may hold: `BaseInboxSize` plus stake
+        /// and reputation slots, capped at `MaxInboxSize`.
+        pub fn effective_inbox_capacity(who: &T::AccountId) -> u32 {
+            let mut capacity = T::BaseInboxSize::get();
+
+            let per_slot = T::InboxStakePerSlot::get();
+            if !per_slot.is_zero() {
+                let stake_slots = T::StakeInspect::staked(who) / per_slot;
+                capacity = capacity.saturating_add(stake_slots.saturated_into::<u32>());
+            }
+
+            let tiers = T::ReputationManager::get_reputation(who) / REPUTATION_TIER_BASIS_POINTS;
+            capacity = capacity
+                .saturating_add(tiers.saturating_mul(T::InboxSlotsPerReputationTier::get()));
+
+            capacity.min(T::MaxInboxSize::get())
+        }
+
+        /// Archive Merkle leaf for an envelope: Blake2b-256 of its SCALE encoding.
+        pub fn archive_leaf(envelope: &MessageEnvelope<T>) -> H256 {
+            H256::from(sp_io::hashing::blake2_256(&envelope.encode()))
+        }
+
+        /// Fold `proof` into `leaf`, hashing each pair in sorted order.
+        pub fn archive_root_from_proof(leaf: H256, proof: &[H256]) -> H256 {
+            proof.iter().fold(leaf, |node, sibling| {
+                let (a, b) = if node <= *sibling {
+                    (node, *sibling)
+                } else {
+                    (*sibling, node)
+                };
+                H256::from(sp_io::hashing::blake2_256(&(a, b).encode()))
+            })
+        }
+
+        /// Record `guardian`'s approval, starting the notice period once the
+        /// threshold is reached.
+        fn add_recovery_approval(
+            lost: &T::AccountId,
+            config: &RecoveryConfig<T>,
+            recovery: &mut ActiveRecovery<T>,
+            guardian: T::AccountId,
+        ) -> DispatchResult {
+            ensure!(
+                !recovery.approvals.contains(&guardian),
+                Error::<T>::AlreadyApproved
+            );
+            recovery
+                .approvals
+                .try_push(guardian.clone())
+                .map_err(|_| Error::<T>::AlreadyApproved)?;
+            let approvals = recovery.approvals.len() as u32;
+
+            Self::deposit_event(Event::RecoveryApproved {
+                lost: lost.clone(),
+                guardian,
+                approvals,
+            });
+
+            if recovery.executable_at.is_none() && approvals >= config.threshold {
+                let now = frame_system::Pallet::<T>::block_number();
+                let executable_at = now.saturating_add(config.delay_blocks.into());
+                recovery.executable_at = Some(executable_at);
+                Self::deposit_event(Event::RecoveryNoticeStarted {
+                    lost: lost.clone(),
+                    new_owner: recovery.new_owner.clone(),
+                    executable_at,
+                });
+            }
+            Ok(())
+        }
+
+        /// Move every envelope in `from`'s inbox to `to`, rewriting escrow
+        /// receivers and ephemeral expiry entries. Returns the number moved.
+        fn move_inbox(from: &T::AccountId, to: &T::AccountId) -> Result<u32, DispatchError> {
+            let ids = InboxIndex::<T>::get(from);
+            let mut target = InboxIndex::<T>::get(to);
+            ensure!(
+                target.len().saturating_add(ids.len())
+                    <= Self::effective_inbox_capacity(to) as usize,
+                Error::<T>::InboxFull
+            );
+
+            for &msg_id in ids.iter() {
+                let Some(mut env) = Inbox::<T>::take(from, msg_id) else {
+                    continue;
+                };
+                env.receiver = to.clone();
+
+                MessageEscrow::<T>::mutate(msg_id, |maybe| {
+                    if let Some(record) = maybe {
+                        record.receiver = to.clone();
+                    }
+                });
+
+                if env.ttl_blocks != 0 {
+                    // `send_message` may have rolled the entry over by one block.
+                    let expire_block = env.sent_at.saturating_add(env.ttl_blocks.into());
+                    for block in [expire_block, expire_block.saturating_add(1u32.into())] {
+                        EphemeralQueue::<T>::mutate(block, |q| {
+                            for entry in q.iter_mut() {
+                                if entry.0 == *from && entry.1 == msg_id {
+                                    entry.0 = to.clone();
+                                }
+                            }
+                        });
+                    }
+                }
+
+                Inbox::<T>::insert(to, msg_id, env);
+                let _ = target.try_push(msg_id);
+            }
+
+            InboxIndex::<T>::remove(from);
+            InboxIndex::<T>::insert(to, target);
+
+            Ok(ids.len() as u32)
+        }
+
+        /// Bytes a receiver signs to authorise `op` under `nonce` on this
+        /// chain until block `expires_at`.
+        pub fn sponsored_op_payload(
+            receiver: &T::AccountId,
+            op: &SponsoredOp,
+            nonce: u32,
+            expires_at: BlockNumberFor<T>,
+        ) -> alloc::vec::Vec<u8> {
+            let genesis_hash = frame_system::Pallet::<T>::block_hash(BlockNumberFor::<T>::zero());
+            (
+                SPONSOR_CONTEXT,
+                genesis_hash,
+                receiver,
+                op,
+                nonce,
+                expires_at,
+            )
+                .encode()
+        }
 
-    impl<T: Config> Pallet<T> {
         /// Remove a message envelope and clean up associated storage.
         /// Refunds any unreleased escrow to the original sender.
         pub(crate) fn do_delete_message(
@@ -787,6 +1932,7 @@ pub mod pallet {
                 InboxIndex::<T>::mutate(receiver, |idx| {
                     idx.retain(|&id| id != msg_id);
                 });
+                PendingAutoReplies::<T>::remove(receiver, msg_id);
 
                 // Refund escrow if unclaimed
                 if let Some(record) = MessageEscrow::<T>::take(msg_id) {
@@ -806,8 +1952,10 @@ pub mod pallet {
             }
         }
 
-        /// Emit `AutoResponseTriggered` if receiver has a valid auto-response config.
+        /// Emit `AutoResponseTriggered` and queue the reply if receiver has a
+        /// valid auto-response config and `sender` is outside its cooldown.
         fn maybe_trigger_auto_response(
+            sender: &T::AccountId,
             receiver: &T::AccountId,
             original_msg_id: MessageId,
             pay_for_reply: BalanceOf<T>,
@@ -830,9 +1978,16 @@ pub mod pallet {
                     return;
                 }
 
-                // Auto-response triggered (cooldown tracking is logged but not enforced here
-                // as we don't know the requester in this context — full enforcement is in
-                // Phase 2 per-sender cooldown map).
+                // Per-sender cooldown
+                if AutoReplyCooldown::<T>::contains_key(receiver, sender) {
+                    let last = AutoReplyCooldown::<T>::get(receiver, sender);
+                    if now < last.saturating_add(cfg.cooldown_blocks.into()) {
+                        return;
+                    }
+                }
+                AutoReplyCooldown::<T>::insert(receiver, sender, now);
+
+                PendingAutoReplies::<T>::insert(receiver, original_msg_id, cfg.response_hash);
                 Self::deposit_event(Event::AutoResponseTriggered {
                     original_msg_id,
                     responder: receiver.clone(),
@@ -840,5 +1995,74 @@ pub mod pallet {
                 });
             }
         }
+
+        /// Offchain worker: submit `send_auto_reply` for the entries in
+        /// `PendingAutoReplies` whose responder has a key in the local
+        /// keystore, oldest first, up to the node's per-block limit.
+        ///
+        /// The block each reply was submitted at is kept in persistent
+        /// offchain storage, so a reply in flight is not resubmitted until
+        /// `AUTO_REPLY_RETRY_BLOCKS` have passed; on chain,
+        /// `PendingAutoReplies` rejects anything answered already.
+        pub fn submit_auto_replies(now: BlockNumberFor<T>) {
+            let local: alloc::vec::Vec<T::Public> =
+                Signer::<T, T::AutoResponderId>::keystore_accounts()
+                    .map(|account| account.public)
+                    .collect();
+            if local.is_empty() {
+                return;
+            }
+
+            let limit = StorageValueRef::persistent(AUTO_REPLY_LIMIT_KEY)
+                .get::<u32>()
+                .ok()
+                .flatten()
+                .unwrap_or_else(T::MaxAutoRepliesPerBlock::get);
+            let mut sent = 0u32;
+
+            for public in local {
+                let responder = public.clone().into_account();
+                let mut pending: alloc::vec::Vec<MessageId> =
+                    PendingAutoReplies::<T>::iter_key_prefix(&responder).collect();
+                pending.sort_unstable();
+
+                for original_msg_id in pending {
+                    if sent >= limit {
+                        log::debug!(
+                            target: "runtime::anon-messaging",
+                            "auto-reply limit of {} reached, deferring message {}",
+                            limit,
+                            original_msg_id
+                        );
+                        return;
+                    }
+
+                    let key = (b"anon-messaging::auto-reply-submitted", original_msg_id).encode();
+                    let submitted_at = StorageValueRef::persistent(&key);
+                    if submitted_at
+                        .get::<BlockNumberFor<T>>()
+                        .ok()
+                        .flatten()
+                        .is_some_and(|at| now < at.saturating_add(AUTO_REPLY_RETRY_BLOCKS.into()))
+                    {
+                        continue;
+                    }
+
+                    let submitted = Signer::<T, T::AutoResponderId>::all_accounts()
+                        .with_filter(alloc::vec![public.clone()])
+                        .send_signed_transaction(|_| Call::send_auto_reply { original_msg_id });
+                    if submitted.iter().any(|(_, result)| result.is_ok()) {
+                        submitted_at.set(&now);
+                        sent += 1;
+                    } else {
+                        log::warn!(
+                            target: "runtime::anon-messaging",
+                            "failed to submit auto-reply to message {}, retrying next block",
+                            original_msg_id
+                        );
+                    }
+                }
+            }
+        }
     }
 }
@@ -4,10 +4,10 @@ use frame_support::{
     traits::{ConstU32, ConstU64},
 };
 use frame_system as system;
+use frame_system::offchain::{AppCrypto, CreateSignedTransaction, CreateTransactionBase};
 use pallet_balances::AccountData;
-use sp_core::H256;
 use sp_runtime::{
-    traits::{BlakeTwo256, IdentityLookup},
+    testing::{TestSignature, TestXt, UintAuthorityId},
     BuildStorage,
 };
 
@@ -72,17 +72,93 @@ impl pallet_reputation::ReputationManager<u64, u64> for MockReputation {
     fn meets_minimum_reputation(account: &u64, minimum: u32) -> bool {
         Self::get_reputation(account) >= minimum
     }
+
+    fn restore_reputation(account: &u64, amount: u32) {
+        set_reputation(
+            *account,
+            (Self::get_reputation(account) + amount).min(10_000),
+        );
+    }
+}
+
+/// Mock stake source — returns configurable stakes.
+pub struct MockStake;
+
+thread_local! {
+    static MOCK_STAKE: std::cell::RefCell<std::collections::HashMap<u64, u64>> =
+        std::cell::RefCell::new(std::collections::HashMap::new());
+}
+
+/// Set the staked CLAW for an account in tests.
+pub fn set_stake(account: u64, stake: u64) {
+    MOCK_STAKE.with(|s| {
+        s.borrow_mut().insert(account, stake);
+    });
+}
+
+impl pallet_anon_messaging::StakeInspect<u64, u64> for MockStake {
+    fn staked(account: &u64) -> u64 {
+        MOCK_STAKE.with(|s| *s.borrow().get(account).unwrap_or(&0))
+    }
 }
 
 parameter_types! {
     pub const MaxKeyBytes: u32 = 64;
     pub const MaxInboxSize: u32 = 100;
+    // Default reputation (5000) is two tiers: 50 + 2 * 25 = 100 slots.
+    pub const BaseInboxSize: u32 = 50;
+    pub const MaxKeyAge: u32 = 100;
+    pub const InboxSlotsPerReputationTier: u32 = 25;
+    pub const InboxStakePerSlot: u64 = 1_000;
     pub const MaxInlinePayloadBytes: u32 = 512;
     pub const MaxEphemeralPerBlock: u32 = 50;
-    pub const MinReputationToSend: u32 = 0; // off by default in tests
+    pub static MinReputationToSend: u32 = 0; // off by default in tests
     pub const MinTtlBlocks: u32 = 10;
     pub const MaxTtlBlocks: u32 = 1_000_000;
     pub const MaxEscrowAmount: u64 = 1_000_000_000;
+    pub const MaxGuardians: u32 = 3;
+    pub const MinRecoveryDelay: u32 = 10;
+    pub const BaseAppealBond: u64 = 1_000;
+    pub const MaxAutoRepliesPerBlock: u32 = 2;
+}
+
+/// Test auto-responder keys: `UintAuthorityId` keys sign for the account of
+/// the same number.
+pub struct TestAutoResponderId;
+
+impl AppCrypto<UintAuthorityId, TestSignature> for TestAutoResponderId {
+    type RuntimeAppPublic = UintAuthorityId;
+    type GenericPublic = UintAuthorityId;
+    type GenericSignature = TestSignature;
+}
+
+pub type Extrinsic = TestXt<RuntimeCall, ()>;
+
+impl frame_system::offchain::SigningTypes for Test {
+    type Public = UintAuthorityId;
+    type Signature = TestSignature;
+}
+
+impl<LocalCall> CreateTransactionBase<LocalCall> for Test
+where
+    RuntimeCall: From<LocalCall>,
+{
+    type Extrinsic = Extrinsic;
+    type RuntimeCall = RuntimeCall;
+}
+
+impl<LocalCall> CreateSignedTransaction<LocalCall> for Test
+where
+    RuntimeCall: From<LocalCall>,
+{
+    fn create_signed_transaction<C: AppCrypto<Self::Public, Self::Signature>>(
+        call: RuntimeCall,
+        _public: UintAuthorityId,
+        account: u64,
+        _nonce: Self::Nonce,
+    ) -> Option<Extrinsic> {
+        Some(Extrinsic::new_signed(call, account, (), ()))
+    }
 }
 
 impl pallet_anon_messaging::Config for Test {
@@ -92,12 +168,25 @@ impl pallet_anon_messaging::Config for Test {
     type ReputationManager = MockReputation;
     type MaxKeyBytes = MaxKeyBytes;
     type MaxInboxSize = MaxInboxSize;
+    type BaseInboxSize = BaseInboxSize;
+    type MaxKeyAge = MaxKeyAge;
+    type StakeInspect = MockStake;
+    type InboxStakePerSlot = InboxStakePerSlot;
+    type InboxSlotsPerReputationTier = InboxSlotsPerReputationTier;
     type MaxInlinePayloadBytes = MaxInlinePayloadBytes;
     type MaxEphemeralPerBlock = MaxEphemeralPerBlock;
     type MinReputationToSend = MinReputationToSend;
     type MinTtlBlocks = MinTtlBlocks;
     type MaxTtlBlocks = MaxTtlBlocks;
     type MaxEscrowAmount = MaxEscrowAmount;
+    type OffchainSignature = TestSignature;
+    type SigningPublicKey = UintAuthorityId;
+    type MaxGuardians = MaxGuardians;
+    type MinRecoveryDelay = MinRecoveryDelay;
+    type AppealOrigin = frame_system::EnsureRoot<u64>;
+    type BaseAppealBond = BaseAppealBond;
+    type AutoResponderId = TestAutoResponderId;
+    type MaxAutoRepliesPerBlock = MaxAutoRepliesPerBlock;
 }
 
 /// Build a test externalities environment.
@@ -1,5 +1,10 @@
 pub mod mock;
+pub mod test_appeals;
+pub mod test_archive;
+pub mod test_auto_reply;
 pub mod test_ephemeral;
 pub mod test_escrow;
 pub mod test_keys;
 pub mod test_messaging;
+pub mod test_recovery;
+pub mod test_sponsored;
@@ -0,0 +1,90 @@
+use crate::{
+    pallet::{AppealOffenses, Error, Event, SpamAppeals},
+    tests::mock::*,
+};
+use frame_support::{assert_noop, assert_ok};
+use pallet_reputation::ReputationManager;
+use sp_core::H256;
+
+const EVIDENCE: H256 = H256::repeat_byte(7);
+
+#[test]
+fn upheld_appeal_refunds_bond_and_clears_send_floor() {
+    new_test_ext().execute_with(|| {
+        MinReputationToSend::set(3_000);
+        set_reputation(ALICE, 1_000);
+
+        assert_ok!(AnonMessaging::file_spam_appeal(
+            RuntimeOrigin::signed(ALICE),
+            EVIDENCE
+        ));
+        assert_eq!(Balances::reserved_balance(ALICE), 1_000);
+        assert_noop!(
+            AnonMessaging::file_spam_appeal(RuntimeOrigin::signed(ALICE), EVIDENCE),
+            Error::<Test>::AppealAlreadyPending
+        );
+        assert_noop!(
+            AnonMessaging::resolve_spam_appeal(RuntimeOrigin::signed(BOB), ALICE, true, 500),
+            sp_runtime::DispatchError::BadOrigin
+        );
+
+        // 500 asked for, but 2_000 are needed to send again.
+        assert_ok!(AnonMessaging::resolve_spam_appeal(
+            RuntimeOrigin::root(),
+            ALICE,
+            true,
+            500
+        ));
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+        assert_eq!(MockReputation::get_reputation(&ALICE), 3_000);
+        assert!(SpamAppeals::<Test>::get(ALICE).is_none());
+        System::assert_last_event(
+            Event::SpamAppealResolved {
+                account: ALICE,
+                upheld: true,
+                restored: 2_000,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn rejected_appeal_burns_bond_and_next_bond_escalates() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            AnonMessaging::resolve_spam_appeal(RuntimeOrigin::root(), ALICE, false, 0),
+            Error::<Test>::NoPendingAppeal
+        );
+
+        let issuance = Balances::total_issuance();
+        assert_ok!(AnonMessaging::file_spam_appeal(
+            RuntimeOrigin::signed(ALICE),
+            EVIDENCE
+        ));
+        assert_ok!(AnonMessaging::resolve_spam_appeal(
+            RuntimeOrigin::root(),
+            ALICE,
+            false,
+            0
+        ));
+        assert_eq!(Balances::free_balance(ALICE), 99_000);
+        assert_eq!(Balances::total_issuance(), issuance - 1_000);
+        assert_eq!(AppealOffenses::<Test>::get(ALICE), 1);
+
+        // A repeat offender bonds twice as much.
+        assert_eq!(AnonMessaging::appeal_bond(&ALICE), 2_000);
+        assert_ok!(AnonMessaging::file_spam_appeal(
+            RuntimeOrigin::signed(ALICE),
+            EVIDENCE
+        ));
+        System::assert_last_event(
+            Event::SpamAppealFiled {
+                account: ALICE,
+                bond: 2_000,
+                evidence: EVIDENCE,
+            }
+            .into(),
+        );
+    });
+}
@@ -0,0 +1,149 @@
+use crate::{
+    pallet::{
+        ArchivedUpTo, Archives, DeletionReason, Error, Event, Inbox, InboxIndex, MessageEscrow,
+    },
+    tests::mock::*,
+};
+use frame_support::{assert_noop, assert_ok, BoundedVec};
+use sp_core::H256;
+
+fn zero_nonce() -> BoundedVec<u8, sp_runtime::traits::ConstU32<24>> {
+    BoundedVec::try_from(vec![0u8; 24]).unwrap()
+}
+
+fn send(from: u64, to: u64, pay_for_reply: u64) {
+    assert_ok!(AnonMessaging::send_message(
+        RuntimeOrigin::signed(from),
+        to,
+        H256::zero(),
+        zero_nonce(),
+        0,
+        pay_for_reply,
+        None,
+        None,
+        true,
+    ));
+}
+
+#[test]
+fn test_commit_archive_requires_increasing_range() {
+    new_test_ext().execute_with(|| {
+        send(ALICE, BOB, 0);
+        send(ALICE, BOB, 0);
+
+        // Message 2 has not been sent yet.
+        assert_noop!(
+            AnonMessaging::commit_archive(RuntimeOrigin::signed(BOB), H256::repeat_byte(1), 2),
+            Error::<Test>::InvalidArchiveRange
+        );
+
+        assert_ok!(AnonMessaging::commit_archive(
+            RuntimeOrigin::signed(BOB),
+            H256::repeat_byte(1),
+            0
+        ));
+        assert_noop!(
+            AnonMessaging::commit_archive(RuntimeOrigin::signed(BOB), H256::repeat_byte(2), 0),
+            Error::<Test>::InvalidArchiveRange
+        );
+        assert_ok!(AnonMessaging::commit_archive(
+            RuntimeOrigin::signed(BOB),
+            H256::repeat_byte(2),
+            1
+        ));
+
+        assert_eq!(ArchivedUpTo::<Test>::get(BOB), Some(1));
+        assert_eq!(
+            Archives::<Test>::get(BOB, 0).unwrap().merkle_root,
+            H256::repeat_byte(1)
+        );
+    });
+}
+
+#[test]
+fn test_prune_archived_refunds_escrow() {
+    new_test_ext().execute_with(|| {
+        send(ALICE, BOB, 100);
+        send(ALICE, BOB, 0);
+        send(ALICE, BOB, 0);
+        assert_eq!(Balances::reserved_balance(ALICE), 100);
+
+        assert_noop!(
+            AnonMessaging::prune_archived(RuntimeOrigin::signed(BOB), 10),
+            Error::<Test>::ArchiveNotFound
+        );
+
+        assert_ok!(AnonMessaging::commit_archive(
+            RuntimeOrigin::signed(BOB),
+            H256::repeat_byte(1),
+            1
+        ));
+        assert_ok!(AnonMessaging::prune_archived(
+            RuntimeOrigin::signed(BOB),
+            10
+        ));
+
+        // Only messages covered by the archive are removed.
+        assert_eq!(InboxIndex::<Test>::get(BOB).to_vec(), vec![2]);
+        assert!(Inbox::<Test>::get(BOB, 0).is_none());
+        assert!(MessageEscrow::<Test>::get(0).is_none());
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+        System::assert_has_event(
+            Event::MessageDeleted {
+                msg_id: 0,
+                deleted_by: BOB,
+                reason: DeletionReason::Archived,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn test_prove_archived_message() {
+    new_test_ext().execute_with(|| {
+        send(ALICE, BOB, 0);
+        send(ALICE, BOB, 0);
+
+        let leaf0 = AnonMessaging::archive_leaf(&Inbox::<Test>::get(BOB, 0).unwrap());
+        let leaf1 = AnonMessaging::archive_leaf(&Inbox::<Test>::get(BOB, 1).unwrap());
+        let root = AnonMessaging::archive_root_from_proof(leaf0, &[leaf1]);
+
+        assert_ok!(AnonMessaging::commit_archive(
+            RuntimeOrigin::signed(BOB),
+            root,
+            1
+        ));
+        assert_ok!(AnonMessaging::prune_archived(
+            RuntimeOrigin::signed(BOB),
+            10
+        ));
+
+        assert_ok!(AnonMessaging::prove_archived_message(
+            RuntimeOrigin::signed(ALICE),
+            BOB,
+            1,
+            leaf1,
+            BoundedVec::try_from(vec![leaf0]).unwrap(),
+        ));
+        System::assert_last_event(
+            Event::ArchivedMessageProven {
+                receiver: BOB,
+                up_to_msg_id: 1,
+                leaf: leaf1,
+            }
+            .into(),
+        );
+
+        assert_noop!(
+            AnonMessaging::prove_archived_message(
+                RuntimeOrigin::signed(ALICE),
+                BOB,
+                1,
+                H256::repeat_byte(9),
+                BoundedVec::try_from(vec![leaf0]).unwrap(),
+            ),
+            Error::<Test>::InvalidArchiveProof
+        );
+    });
+}
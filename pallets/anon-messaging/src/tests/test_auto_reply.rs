@@ -1,14 +1,25 @@
-//! Tests for M1 — auto-reply cooldown enforcement.
+//! Tests for M1 — auto-reply cooldown enforcement, and the offchain
+//! auto-responder.
 
 use crate::{
-    pallet::{AutoReplyCooldown, AutoResponses, Event},
+    pallet::{AutoReplyCooldown, Call, EscrowReplied, Event, Inbox, PendingAutoReplies},
     tests::mock::*,
-    AutoResponseConfig, KeyType,
+    AutoResponseConfig, Error, AUTO_REPLY_LIMIT_KEY, AUTO_REPLY_RETRY_BLOCKS,
 };
-use frame_support::{assert_ok, BoundedVec};
+use codec::Decode;
+use frame_support::{assert_noop, assert_ok, traits::Hooks, BoundedVec};
 use frame_system::RawOrigin;
-use sp_core::H256;
-use sp_runtime::traits::ConstU32;
+use sp_core::{
+    offchain::{
+        testing::{TestOffchainExt, TestTransactionPoolExt},
+        OffchainDbExt, OffchainWorkerExt, TransactionPoolExt,
+    },
+    H256,
+};
+use sp_runtime::{
+    generic::Preamble, offchain::storage::StorageValueRef, testing::UintAuthorityId,
+    traits::ConstU32,
+};
 
 fn zero_hash() -> H256 {
     H256::zero()
@@ -29,6 +40,7 @@ fn send_msg(sender: u64, receiver: u64) {
         0, // no pay-for-reply
         None,
         None,
+        true,
     ));
 }
 
@@ -177,3 +189,143 @@ fn auto_reply_cooldown_is_per_sender() {
         }
     });
 }
+
+/// Test externalities with offchain storage and a transaction pool, plus a
+/// reader of the transactions submitted so far.
+fn offchain_test_ext() -> (sp_io::TestExternalities, impl Fn() -> Vec<Extrinsic>) {
+    let (offchain, _) = TestOffchainExt::new();
+    let (pool, pool_state) = TestTransactionPoolExt::new();
+    let mut ext = new_test_ext();
+    ext.register_extension(OffchainDbExt::new(offchain.clone()));
+    ext.register_extension(OffchainWorkerExt::new(offchain));
+    ext.register_extension(TransactionPoolExt::new(pool));
+
+    let submitted = move || {
+        pool_state
+            .read()
+            .transactions
+            .iter()
+            .map(|tx| Extrinsic::decode(&mut &tx[..]).unwrap())
+            .collect()
+    };
+    (ext, submitted)
+}
+
+fn auto_reply_call(original_msg_id: u64) -> RuntimeCall {
+    RuntimeCall::AnonMessaging(Call::send_auto_reply { original_msg_id })
+}
+
+#[test]
+fn offchain_worker_submits_replies_for_local_responders() {
+    let (mut ext, submitted) = offchain_test_ext();
+    ext.execute_with(|| {
+        enable_auto_response(BOB, 0);
+        enable_auto_response(CHARLIE, 0);
+        send_msg(ALICE, BOB); // msg 0
+        send_msg(ALICE, CHARLIE); // msg 1, CHARLIE's key is not local
+
+        UintAuthorityId::set_all_keys(vec![BOB]);
+        AnonMessaging::offchain_worker(1);
+
+        let txs = submitted();
+        assert_eq!(txs.len(), 1);
+        assert_eq!(txs[0].function, auto_reply_call(0));
+        assert!(matches!(txs[0].preamble, Preamble::Signed(BOB, ..)));
+
+        // The same block seen again is not answered twice.
+        AnonMessaging::offchain_worker(1);
+        assert_eq!(submitted().len(), 1);
+    });
+}
+
+#[test]
+fn offchain_worker_respects_the_node_limit() {
+    let (mut ext, submitted) = offchain_test_ext();
+    ext.execute_with(|| {
+        enable_auto_response(BOB, 0);
+        for _ in 0..3 {
+            send_msg(ALICE, BOB);
+        }
+
+        UintAuthorityId::set_all_keys(vec![BOB]);
+        AnonMessaging::offchain_worker(1);
+        let calls: Vec<_> = submitted().into_iter().map(|tx| tx.function).collect();
+        assert_eq!(calls, vec![auto_reply_call(0), auto_reply_call(1)]);
+
+        // The node raises its own limit; only the skipped message is sent.
+        StorageValueRef::persistent(AUTO_REPLY_LIMIT_KEY).set(&3u32);
+        AnonMessaging::offchain_worker(1);
+        let calls: Vec<_> = submitted().into_iter().map(|tx| tx.function).collect();
+        assert_eq!(
+            calls,
+            vec![auto_reply_call(0), auto_reply_call(1), auto_reply_call(2)]
+        );
+    });
+}
+
+#[test]
+fn offchain_worker_retries_deferred_and_unlanded_replies() {
+    let (mut ext, submitted) = offchain_test_ext();
+    ext.execute_with(|| {
+        enable_auto_response(BOB, 0);
+        for _ in 0..3 {
+            send_msg(ALICE, BOB);
+        }
+
+        UintAuthorityId::set_all_keys(vec![BOB]);
+        AnonMessaging::offchain_worker(1);
+        assert_eq!(submitted().len(), 2);
+
+        // The reply over the limit goes out with the next block.
+        AnonMessaging::offchain_worker(2);
+        let calls: Vec<_> = submitted().into_iter().map(|tx| tx.function).collect();
+        assert_eq!(calls[2..], [auto_reply_call(2)]);
+
+        // Message 0 is answered; the others never landed and are resubmitted
+        // once the retry delay has passed.
+        assert_ok!(AnonMessaging::send_auto_reply(
+            RawOrigin::Signed(BOB).into(),
+            0
+        ));
+        AnonMessaging::offchain_worker(u64::from(AUTO_REPLY_RETRY_BLOCKS));
+        assert_eq!(submitted().len(), 3);
+        AnonMessaging::offchain_worker(1 + u64::from(AUTO_REPLY_RETRY_BLOCKS));
+        let calls: Vec<_> = submitted().into_iter().map(|tx| tx.function).collect();
+        assert_eq!(calls[3..], [auto_reply_call(1)]);
+    });
+}
+
+#[test]
+fn send_auto_reply_answers_each_trigger_once() {
+    new_test_ext().execute_with(|| {
+        enable_auto_response(ALICE, 0);
+        enable_auto_response(BOB, 0);
+        send_msg(ALICE, BOB); // msg 0
+        assert_eq!(
+            PendingAutoReplies::<Test>::get(BOB, 0),
+            Some(H256::repeat_byte(0xab))
+        );
+
+        assert_noop!(
+            AnonMessaging::send_auto_reply(RawOrigin::Signed(CHARLIE).into(), 0),
+            Error::<Test>::MessageNotFound
+        );
+        assert_ok!(AnonMessaging::send_auto_reply(
+            RawOrigin::Signed(BOB).into(),
+            0
+        ));
+
+        let reply = Inbox::<Test>::get(ALICE, 1).unwrap();
+        assert_eq!(reply.sender, BOB);
+        assert_eq!(reply.content_hash, H256::repeat_byte(0xab));
+        assert_eq!(reply.reply_to, Some(0));
+        assert_eq!(EscrowReplied::<Test>::get(0), Some(1));
+        // ALICE's own auto-response does not answer the auto-reply.
+        assert!(PendingAutoReplies::<Test>::get(ALICE, 1).is_none());
+
+        assert_noop!(
+            AnonMessaging::send_auto_reply(RawOrigin::Signed(BOB).into(), 0),
+            Error::<Test>::NoPendingAutoReply
+        );
+    });
+}
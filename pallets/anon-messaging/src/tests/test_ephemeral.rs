@@ -25,6 +25,7 @@ fn test_send_message_permanent_not_in_queue() {
             0,
             None,
             None,
+            true,
         ));
 
         // No ephemeral queue entries should exist
@@ -47,6 +48,7 @@ fn test_send_message_ephemeral_adds_to_queue() {
             0,
             None,
             None,
+            true,
         ));
 
         let expire_block: u64 = 101;
@@ -70,6 +72,7 @@ fn test_ephemeral_auto_delete_on_initialize() {
             0,
             None,
             None,
+            true,
         ));
 
         // Message should exist at block 10
@@ -99,6 +102,7 @@ fn test_ephemeral_message_not_deleted_before_expiry() {
             0,
             None,
             None,
+            true,
         ));
 
         // on_initialize at block 10 — should NOT delete
@@ -1,5 +1,5 @@
 use crate::{
-    pallet::{Error, Event, MessageEscrow},
+    pallet::{Error, MessageEscrow},
     tests::mock::*,
 };
 use frame_support::{assert_noop, assert_ok, BoundedVec};
@@ -27,6 +27,7 @@ fn test_send_message_pay_for_reply_locks_escrow() {
             escrow_amount,
             None,
             None,
+            true,
         ));
 
         // Escrow record should exist
@@ -58,6 +59,7 @@ fn test_claim_reply_escrow_success() {
             escrow_amount,
             None,
             None,
+            true,
         ));
         let original_msg_id = 0u64;
 
@@ -71,6 +73,7 @@ fn test_claim_reply_escrow_success() {
             0,
             None,
             Some(original_msg_id),
+            true,
         ));
 
         let alice_free_before = pallet_balances::Pallet::<Test>::free_balance(BOB);
@@ -103,6 +106,7 @@ fn test_claim_reply_escrow_no_reply_error() {
             100,
             None,
             None,
+            true,
         ));
 
         assert_noop!(
@@ -124,6 +128,7 @@ fn test_claim_reply_escrow_already_claimed() {
             100,
             None,
             None,
+            true,
         ));
 
         // BOB replies
@@ -136,6 +141,7 @@ fn test_claim_reply_escrow_already_claimed() {
             0,
             None,
             Some(0u64),
+            true,
         ));
 
         // First claim succeeds
@@ -166,6 +172,7 @@ fn test_delete_message_refunds_escrow() {
             escrow_amount,
             None,
             None,
+            true,
         ));
 
         let alice_free_before = pallet_balances::Pallet::<Test>::free_balance(ALICE);
@@ -197,6 +204,7 @@ fn test_escrow_amount_exceeds_max() {
                 2_000_000_000, // over MaxEscrowAmount
                 None,
                 None,
+                true,
             ),
             Error::<Test>::EscrowTooLarge
         );
@@ -1,10 +1,24 @@
 use crate::{
-    pallet::{Error, Event, PublicKeys},
+    pallet::{Error, Event, Inbox, PublicKeys},
     tests::mock::*,
     KeyType,
 };
 use frame_support::{assert_noop, assert_ok, BoundedVec};
-use sp_runtime::traits::ConstU32;
+use sp_core::H256;
+
+fn send_keyed(from: u64, to: u64, allow_unkeyed: bool) -> sp_runtime::DispatchResult {
+    AnonMessaging::send_message(
+        RuntimeOrigin::signed(from),
+        to,
+        H256::zero(),
+        BoundedVec::try_from(vec![0u8; 24]).unwrap(),
+        0,
+        0,
+        None,
+        None,
+        allow_unkeyed,
+    )
+}
 
 #[test]
 fn test_register_public_key_success() {
@@ -77,3 +91,49 @@ fn test_register_public_key_emits_event() {
         );
     });
 }
+
+#[test]
+fn test_send_requires_receiver_key_unless_overridden() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            send_keyed(ALICE, BOB, false),
+            Error::<Test>::KeyNotRegistered
+        );
+
+        assert_ok!(send_keyed(ALICE, BOB, true));
+        assert_eq!(Inbox::<Test>::get(BOB, 0).unwrap().receiver_key_hash, None);
+
+        assert_ok!(AnonMessaging::register_public_key(
+            RuntimeOrigin::signed(BOB),
+            BoundedVec::try_from(BOB_KEY.to_vec()).unwrap(),
+            KeyType::X25519,
+        ));
+        assert_ok!(send_keyed(ALICE, BOB, false));
+        assert_eq!(
+            Inbox::<Test>::get(BOB, 1).unwrap().receiver_key_hash,
+            Some(H256::from(sp_io::hashing::blake2_256(&BOB_KEY)))
+        );
+    });
+}
+
+#[test]
+fn test_send_rejects_expired_receiver_key() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(AnonMessaging::register_public_key(
+            RuntimeOrigin::signed(BOB),
+            BoundedVec::try_from(BOB_KEY.to_vec()).unwrap(),
+            KeyType::X25519,
+        ));
+
+        // Registered at block 1 with MaxKeyAge = 100.
+        System::set_block_number(100);
+        assert_ok!(send_keyed(ALICE, BOB, false));
+
+        System::set_block_number(101);
+        assert_noop!(
+            send_keyed(ALICE, BOB, false),
+            Error::<Test>::KeyNotRegistered
+        );
+        assert_ok!(send_keyed(ALICE, BOB, true));
+    });
+}
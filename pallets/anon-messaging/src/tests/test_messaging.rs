@@ -1,7 +1,6 @@
 use crate::{
     pallet::{Error, Event, Inbox, InboxIndex, NextMessageId},
     tests::mock::*,
-    DeletionReason, KeyType,
 };
 use frame_support::{assert_noop, assert_ok, BoundedVec};
 use sp_core::H256;
@@ -26,6 +25,7 @@ fn test_send_message_basic() {
             0,    // no escrow
             None, // no inline payload
             None, // not a reply
+            true, // skip key check
         ));
 
         let msg_id = 0u64;
@@ -76,6 +76,7 @@ fn test_send_message_inbox_full_error() {
                 0,
                 None,
                 None,
+                true,
             ));
         }
 
@@ -90,6 +91,7 @@ fn test_send_message_inbox_full_error() {
                 0,
                 None,
                 None,
+                true,
             ),
             Error::<Test>::InboxFull
         );
@@ -110,6 +112,7 @@ fn test_send_message_inline_payload() {
             0,
             Some(payload.clone()),
             None,
+            true,
         ));
 
         let envelope = Inbox::<Test>::get(BOB, 0u64).unwrap();
@@ -129,6 +132,7 @@ fn test_send_message_emits_event() {
             0,
             None,
             None,
+            true,
         ));
 
         System::assert_last_event(
@@ -157,6 +161,7 @@ fn test_read_message_marks_read() {
             0,
             None,
             None,
+            true,
         ));
 
         assert_ok!(AnonMessaging::read_message(RuntimeOrigin::signed(BOB), 0));
@@ -178,6 +183,7 @@ fn test_read_message_unauthorized() {
             0,
             None,
             None,
+            true,
         ));
 
         // CHARLIE tries to read BOB's message
@@ -200,6 +206,7 @@ fn test_delete_message_by_receiver() {
             0,
             None,
             None,
+            true,
         ));
 
         assert_ok!(AnonMessaging::delete_message(RuntimeOrigin::signed(BOB), 0));
@@ -231,6 +238,7 @@ fn test_message_id_increments() {
                 0,
                 None,
                 None,
+                true,
             ));
             assert_eq!(NextMessageId::<Test>::get(), expected_id + 1);
         }
@@ -251,6 +259,7 @@ fn test_send_message_invalid_ttl_too_short() {
                 0,
                 None,
                 None,
+                true,
             ),
             Error::<Test>::InvalidTtl
         );
@@ -271,8 +280,64 @@ fn test_send_message_invalid_ttl_too_long() {
                 0,
                 None,
                 None,
+                true,
             ),
             Error::<Test>::InvalidTtl
         );
     });
 }
+
+#[test]
+fn test_effective_inbox_capacity_scales_with_stake_and_reputation() {
+    new_test_ext().execute_with(|| {
+        // Default reputation 5000 = two tiers.
+        assert_eq!(AnonMessaging::effective_inbox_capacity(&BOB), 100);
+
+        set_reputation(BOB, 0);
+        assert_eq!(AnonMessaging::effective_inbox_capacity(&BOB), 50);
+
+        set_stake(BOB, 10_999);
+        set_reputation(BOB, 2_500);
+        assert_eq!(AnonMessaging::effective_inbox_capacity(&BOB), 50 + 10 + 25);
+
+        // Bounded by the hard MaxInboxSize.
+        set_stake(BOB, 1_000_000);
+        assert_eq!(AnonMessaging::effective_inbox_capacity(&BOB), 100);
+    });
+}
+
+#[test]
+fn test_send_message_respects_effective_capacity() {
+    new_test_ext().execute_with(|| {
+        set_reputation(BOB, 0);
+        set_stake(BOB, 2_000);
+
+        for _ in 0..52 {
+            assert_ok!(AnonMessaging::send_message(
+                RuntimeOrigin::signed(ALICE),
+                BOB,
+                zero_hash(),
+                zero_nonce(),
+                0,
+                0,
+                None,
+                None,
+                true,
+            ));
+        }
+        assert_noop!(
+            AnonMessaging::send_message(
+                RuntimeOrigin::signed(ALICE),
+                BOB,
+                zero_hash(),
+                zero_nonce(),
+                0,
+                0,
+                None,
+                None,
+                true,
+            ),
+            Error::<Test>::InboxFull
+        );
+    });
+}
@@ -0,0 +1,291 @@
+use crate::{
+    pallet::{
+        ActiveRecoveries, EphemeralQueue, Error, Event, Inbox, InboxIndex, KeyType, MessageEscrow,
+        PublicKeys, RecoveryConfigs,
+    },
+    tests::mock::*,
+};
+use frame_support::{assert_noop, assert_ok, BoundedVec};
+use sp_core::H256;
+
+const DAVE: u64 = 4;
+const NEW_KEY: [u8; 32] = [9u8; 32];
+
+fn zero_nonce() -> BoundedVec<u8, sp_runtime::traits::ConstU32<24>> {
+    BoundedVec::try_from(vec![0u8; 24]).unwrap()
+}
+
+fn new_key() -> BoundedVec<u8, MaxKeyBytes> {
+    BoundedVec::try_from(NEW_KEY.to_vec()).unwrap()
+}
+
+/// ALICE opts in with BOB and CHARLIE as 2-of-2 guardians and a 10-block notice.
+fn configure_alice() {
+    assert_ok!(AnonMessaging::set_recovery_config(
+        RuntimeOrigin::signed(ALICE),
+        vec![BOB, CHARLIE],
+        2,
+        10,
+    ));
+}
+
+fn initiate(guardian: u64) -> sp_runtime::DispatchResult {
+    AnonMessaging::initiate_recovery(
+        RuntimeOrigin::signed(guardian),
+        ALICE,
+        DAVE,
+        new_key(),
+        KeyType::X25519,
+    )
+}
+
+#[test]
+fn test_set_recovery_config_validates() {
+    new_test_ext().execute_with(|| {
+        // Threshold above guardian count.
+        assert_noop!(
+            AnonMessaging::set_recovery_config(RuntimeOrigin::signed(ALICE), vec![BOB], 2, 10),
+            Error::<Test>::InvalidRecoveryConfig
+        );
+        // Duplicate guardians.
+        assert_noop!(
+            AnonMessaging::set_recovery_config(RuntimeOrigin::signed(ALICE), vec![BOB, BOB], 1, 10),
+            Error::<Test>::InvalidRecoveryConfig
+        );
+        // Delay below MinRecoveryDelay.
+        assert_noop!(
+            AnonMessaging::set_recovery_config(RuntimeOrigin::signed(ALICE), vec![BOB], 1, 5),
+            Error::<Test>::InvalidRecoveryConfig
+        );
+
+        configure_alice();
+        assert_eq!(RecoveryConfigs::<Test>::get(ALICE).unwrap().threshold, 2);
+
+        assert_ok!(AnonMessaging::remove_recovery_config(
+            RuntimeOrigin::signed(ALICE)
+        ));
+        assert!(RecoveryConfigs::<Test>::get(ALICE).is_none());
+    });
+}
+
+#[test]
+fn test_recovery_requires_guardian_threshold_and_delay() {
+    new_test_ext().execute_with(|| {
+        configure_alice();
+
+        assert_noop!(initiate(DAVE), Error::<Test>::NotGuardian);
+        assert_ok!(initiate(BOB));
+        assert_noop!(initiate(CHARLIE), Error::<Test>::RecoveryAlreadyActive);
+
+        // One of two approvals: not executable yet.
+        assert_noop!(
+            AnonMessaging::execute_recovery(RuntimeOrigin::signed(DAVE), ALICE),
+            Error::<Test>::RecoveryNotExecutable
+        );
+        assert_noop!(
+            AnonMessaging::approve_recovery(RuntimeOrigin::signed(BOB), ALICE),
+            Error::<Test>::AlreadyApproved
+        );
+
+        assert_ok!(AnonMessaging::approve_recovery(
+            RuntimeOrigin::signed(CHARLIE),
+            ALICE
+        ));
+        System::assert_last_event(
+            Event::RecoveryNoticeStarted {
+                lost: ALICE,
+                new_owner: DAVE,
+                executable_at: 11,
+            }
+            .into(),
+        );
+
+        System::set_block_number(10);
+        assert_noop!(
+            AnonMessaging::execute_recovery(RuntimeOrigin::signed(DAVE), ALICE),
+            Error::<Test>::RecoveryNotExecutable
+        );
+    });
+}
+
+#[test]
+fn test_owner_can_cancel_during_notice() {
+    new_test_ext().execute_with(|| {
+        configure_alice();
+        assert_ok!(initiate(BOB));
+        assert_ok!(AnonMessaging::approve_recovery(
+            RuntimeOrigin::signed(CHARLIE),
+            ALICE
+        ));
+
+        assert_noop!(
+            AnonMessaging::cancel_recovery(RuntimeOrigin::signed(BOB)),
+            Error::<Test>::NoActiveRecovery
+        );
+        assert_ok!(AnonMessaging::cancel_recovery(RuntimeOrigin::signed(ALICE)));
+        assert!(ActiveRecoveries::<Test>::get(ALICE).is_none());
+
+        System::set_block_number(20);
+        assert_noop!(
+            AnonMessaging::execute_recovery(RuntimeOrigin::signed(DAVE), ALICE),
+            Error::<Test>::NoActiveRecovery
+        );
+    });
+}
+
+#[test]
+fn test_execute_recovery_moves_key_and_inbox() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(AnonMessaging::register_public_key(
+            RuntimeOrigin::signed(ALICE),
+            BoundedVec::try_from(ALICE_KEY.to_vec()).unwrap(),
+            KeyType::X25519,
+        ));
+        // A permanent message with escrow and an ephemeral one expiring at block 21.
+        assert_ok!(AnonMessaging::send_message(
+            RuntimeOrigin::signed(BOB),
+            ALICE,
+            H256::zero(),
+            zero_nonce(),
+            0,
+            100,
+            None,
+            None,
+            true,
+        ));
+        assert_ok!(AnonMessaging::send_message(
+            RuntimeOrigin::signed(CHARLIE),
+            ALICE,
+            H256::zero(),
+            zero_nonce(),
+            20,
+            0,
+            None,
+            None,
+            true,
+        ));
+
+        configure_alice();
+        assert_ok!(initiate(BOB));
+        assert_ok!(AnonMessaging::approve_recovery(
+            RuntimeOrigin::signed(CHARLIE),
+            ALICE
+        ));
+        System::set_block_number(11);
+        assert_ok!(AnonMessaging::accept_recovery(
+            RuntimeOrigin::signed(DAVE),
+            ALICE
+        ));
+        assert_ok!(AnonMessaging::execute_recovery(
+            RuntimeOrigin::signed(BOB),
+            ALICE
+        ));
+
+        assert!(PublicKeys::<Test>::get(ALICE).is_none());
+        assert_eq!(PublicKeys::<Test>::get(DAVE).unwrap().key.to_vec(), NEW_KEY);
+        assert!(InboxIndex::<Test>::get(ALICE).is_empty());
+        assert_eq!(InboxIndex::<Test>::get(DAVE).to_vec(), vec![0, 1]);
+        assert_eq!(Inbox::<Test>::get(DAVE, 0).unwrap().receiver, DAVE);
+        assert_eq!(MessageEscrow::<Test>::get(0).unwrap().receiver, DAVE);
+        assert_eq!(EphemeralQueue::<Test>::get(21).to_vec(), vec![(DAVE, 1)]);
+        assert!(RecoveryConfigs::<Test>::get(ALICE).is_none());
+        System::assert_last_event(
+            Event::RecoveryExecuted {
+                lost: ALICE,
+                new_owner: DAVE,
+                messages_moved: 2,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn test_execute_recovery_requires_new_owner_acceptance() {
+    new_test_ext().execute_with(|| {
+        configure_alice();
+        assert_ok!(initiate(BOB));
+        assert_ok!(AnonMessaging::approve_recovery(
+            RuntimeOrigin::signed(CHARLIE),
+            ALICE
+        ));
+        System::set_block_number(11);
+
+        assert_noop!(
+            AnonMessaging::execute_recovery(RuntimeOrigin::signed(BOB), ALICE),
+            Error::<Test>::RecoveryNotAccepted
+        );
+        assert_noop!(
+            AnonMessaging::accept_recovery(RuntimeOrigin::signed(BOB), ALICE),
+            Error::<Test>::NotRecoveryNewOwner
+        );
+
+        assert_ok!(AnonMessaging::accept_recovery(
+            RuntimeOrigin::signed(DAVE),
+            ALICE
+        ));
+        System::assert_last_event(
+            Event::RecoveryAccepted {
+                lost: ALICE,
+                new_owner: DAVE,
+            }
+            .into(),
+        );
+        assert_ok!(AnonMessaging::execute_recovery(
+            RuntimeOrigin::signed(BOB),
+            ALICE
+        ));
+        assert_eq!(PublicKeys::<Test>::get(DAVE).unwrap().key.to_vec(), NEW_KEY);
+    });
+}
+
+#[test]
+fn test_execute_recovery_respects_new_owner_inbox_capacity() {
+    new_test_ext().execute_with(|| {
+        set_reputation(DAVE, 0);
+        set_stake(DAVE, 0);
+        assert_ok!(AnonMessaging::register_public_key(
+            RuntimeOrigin::signed(DAVE),
+            BoundedVec::try_from([4u8; 32].to_vec()).unwrap(),
+            KeyType::X25519,
+        ));
+        assert_ok!(AnonMessaging::register_public_key(
+            RuntimeOrigin::signed(ALICE),
+            BoundedVec::try_from(ALICE_KEY.to_vec()).unwrap(),
+            KeyType::X25519,
+        ));
+        // DAVE's effective capacity is BaseInboxSize (50): 49 + 2 do not fit.
+        for (receiver, count) in [(DAVE, 49), (ALICE, 2)] {
+            for _ in 0..count {
+                assert_ok!(AnonMessaging::send_message(
+                    RuntimeOrigin::signed(BOB),
+                    receiver,
+                    H256::zero(),
+                    zero_nonce(),
+                    0,
+                    0,
+                    None,
+                    None,
+                    true,
+                ));
+            }
+        }
+
+        configure_alice();
+        assert_ok!(initiate(BOB));
+        assert_ok!(AnonMessaging::approve_recovery(
+            RuntimeOrigin::signed(CHARLIE),
+            ALICE
+        ));
+        assert_ok!(AnonMessaging::accept_recovery(
+            RuntimeOrigin::signed(DAVE),
+            ALICE
+        ));
+        System::set_block_number(11);
+
+        assert_noop!(
+            AnonMessaging::execute_recovery(RuntimeOrigin::signed(BOB), ALICE),
+            Error::<Test>::InboxFull
+        );
+    });
+}
@@ -0,0 +1,192 @@
+use crate::{
+    pallet::{Error, Event, Inbox, MessageEscrow, SponsorNonces, SponsoredOp},
+    tests::mock::*,
+};
+use frame_support::{assert_noop, assert_ok, BoundedVec};
+use sp_core::H256;
+use sp_runtime::testing::TestSignature;
+
+fn zero_nonce() -> BoundedVec<u8, sp_runtime::traits::ConstU32<24>> {
+    BoundedVec::try_from(vec![0u8; 24]).unwrap()
+}
+
+fn send(from: u64, to: u64, pay_for_reply: u64, reply_to: Option<u64>) {
+    assert_ok!(AnonMessaging::send_message(
+        RuntimeOrigin::signed(from),
+        to,
+        H256::zero(),
+        zero_nonce(),
+        0,
+        pay_for_reply,
+        None,
+        reply_to,
+        true,
+    ));
+}
+
+/// Last block at which the test signatures are valid.
+const EXPIRES_AT: u64 = 10;
+
+/// Signature by `receiver` over `op` at the receiver's current nonce.
+fn receiver_sig(receiver: u64, op: &SponsoredOp) -> TestSignature {
+    let nonce = SponsorNonces::<Test>::get(receiver);
+    TestSignature(
+        receiver,
+        AnonMessaging::sponsored_op_payload(&receiver, op, nonce, EXPIRES_AT),
+    )
+}
+
+#[test]
+fn test_sponsor_exec_read_message() {
+    new_test_ext().execute_with(|| {
+        send(ALICE, BOB, 0, None);
+        let op = SponsoredOp::ReadMessage { msg_id: 0 };
+
+        assert_ok!(AnonMessaging::sponsor_exec(
+            RuntimeOrigin::signed(CHARLIE),
+            BOB,
+            op.clone(),
+            EXPIRES_AT,
+            receiver_sig(BOB, &op),
+        ));
+
+        assert!(Inbox::<Test>::get(BOB, 0).unwrap().read);
+        assert_eq!(SponsorNonces::<Test>::get(BOB), 1);
+        System::assert_last_event(
+            Event::SponsoredExecuted {
+                sponsor: CHARLIE,
+                receiver: BOB,
+                op,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn test_sponsor_exec_claims_escrow_for_receiver() {
+    new_test_ext().execute_with(|| {
+        send(ALICE, BOB, 500, None);
+        send(BOB, ALICE, 0, Some(0));
+        let bob_before = pallet_balances::Pallet::<Test>::free_balance(BOB);
+
+        let op = SponsoredOp::ClaimReplyEscrow { original_msg_id: 0 };
+        assert_ok!(AnonMessaging::sponsor_exec(
+            RuntimeOrigin::signed(CHARLIE),
+            BOB,
+            op.clone(),
+            EXPIRES_AT,
+            receiver_sig(BOB, &op),
+        ));
+
+        assert!(MessageEscrow::<Test>::get(0).is_none());
+        assert_eq!(
+            pallet_balances::Pallet::<Test>::free_balance(BOB),
+            bob_before + 500
+        );
+    });
+}
+
+#[test]
+fn test_sponsor_exec_rejects_wrong_signer_and_replay() {
+    new_test_ext().execute_with(|| {
+        send(ALICE, BOB, 0, None);
+        let op = SponsoredOp::ReadMessage { msg_id: 0 };
+
+        // Signed by the sponsor instead of the receiver.
+        let nonce = SponsorNonces::<Test>::get(BOB);
+        let forged = TestSignature(
+            CHARLIE,
+            AnonMessaging::sponsored_op_payload(&BOB, &op, nonce, EXPIRES_AT),
+        );
+        assert_noop!(
+            AnonMessaging::sponsor_exec(
+                RuntimeOrigin::signed(CHARLIE),
+                BOB,
+                op.clone(),
+                EXPIRES_AT,
+                forged
+            ),
+            Error::<Test>::InvalidSponsorSignature
+        );
+
+        let sig = receiver_sig(BOB, &op);
+        assert_ok!(AnonMessaging::sponsor_exec(
+            RuntimeOrigin::signed(CHARLIE),
+            BOB,
+            op.clone(),
+            EXPIRES_AT,
+            sig.clone(),
+        ));
+        assert_noop!(
+            AnonMessaging::sponsor_exec(RuntimeOrigin::signed(CHARLIE), BOB, op, EXPIRES_AT, sig),
+            Error::<Test>::InvalidSponsorSignature
+        );
+    });
+}
+
+#[test]
+fn test_sponsor_exec_failed_op_keeps_nonce() {
+    new_test_ext().execute_with(|| {
+        let op = SponsoredOp::ReadMessage { msg_id: 42 };
+        assert_noop!(
+            AnonMessaging::sponsor_exec(
+                RuntimeOrigin::signed(CHARLIE),
+                BOB,
+                op.clone(),
+                EXPIRES_AT,
+                receiver_sig(BOB, &op),
+            ),
+            Error::<Test>::MessageNotFound
+        );
+        assert_eq!(SponsorNonces::<Test>::get(BOB), 0);
+    });
+}
+
+#[test]
+fn test_sponsor_exec_rejects_expired_signature() {
+    new_test_ext().execute_with(|| {
+        send(ALICE, BOB, 0, None);
+        let op = SponsoredOp::ReadMessage { msg_id: 0 };
+        let sig = receiver_sig(BOB, &op);
+
+        System::set_block_number(EXPIRES_AT + 1);
+        assert_noop!(
+            AnonMessaging::sponsor_exec(
+                RuntimeOrigin::signed(CHARLIE),
+                BOB,
+                op.clone(),
+                EXPIRES_AT,
+                sig.clone()
+            ),
+            Error::<Test>::SponsoredOpExpired
+        );
+        // Extending the expiry invalidates the signature.
+        assert_noop!(
+            AnonMessaging::sponsor_exec(
+                RuntimeOrigin::signed(CHARLIE),
+                BOB,
+                op,
+                EXPIRES_AT + 10,
+                sig
+            ),
+            Error::<Test>::InvalidSponsorSignature
+        );
+    });
+}
+
+#[test]
+fn test_sponsor_exec_rejects_signature_from_another_chain() {
+    new_test_ext().execute_with(|| {
+        send(ALICE, BOB, 0, None);
+        let op = SponsoredOp::ReadMessage { msg_id: 0 };
+        let sig = receiver_sig(BOB, &op);
+
+        // Same receiver key and nonce, different genesis.
+        frame_system::BlockHash::<Test>::insert(0, H256::repeat_byte(9));
+        assert_noop!(
+            AnonMessaging::sponsor_exec(RuntimeOrigin::signed(CHARLIE), BOB, op, EXPIRES_AT, sig),
+            Error::<Test>::InvalidSponsorSignature
+        );
+    });
+}
@@ -11,7 +11,21 @@ pub trait WeightInfo {
     fn delete_message() -> Weight;
     fn set_auto_response() -> Weight;
     fn claim_reply_escrow() -> Weight;
+    fn sponsor_exec() -> Weight;
+    fn set_recovery_config() -> Weight;
+    fn remove_recovery_config() -> Weight;
+    fn initiate_recovery() -> Weight;
+    fn approve_recovery() -> Weight;
+    fn cancel_recovery() -> Weight;
+    fn execute_recovery(n: u32) -> Weight;
+    fn commit_archive() -> Weight;
+    fn prune_archived(n: u32) -> Weight;
+    fn prove_archived_message() -> Weight;
     fn on_initialize(n: u32) -> Weight;
+    fn file_spam_appeal() -> Weight;
+    fn resolve_spam_appeal() -> Weight;
+    fn send_auto_reply() -> Weight;
+    fn accept_recovery() -> Weight;
 }
 
 /// Placeholder weights — all operations cost a flat 10_000 ref_time.
@@ -36,9 +50,51 @@ impl WeightInfo for SubstrateWeight {
     fn claim_reply_escrow() -> Weight {
         Weight::from_parts(10_000, 0)
     }
+    fn sponsor_exec() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+    fn set_recovery_config() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+    fn remove_recovery_config() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+    fn initiate_recovery() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+    fn approve_recovery() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+    fn cancel_recovery() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+    fn execute_recovery(_n: u32) -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+    fn commit_archive() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+    fn prune_archived(_n: u32) -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+    fn prove_archived_message() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
     fn on_initialize(_n: u32) -> Weight {
         Weight::from_parts(10_000, 0)
     }
+    fn file_spam_appeal() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+    fn resolve_spam_appeal() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+    fn send_auto_reply() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+    fn accept_recovery() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
 }
 
 impl WeightInfo for () {
@@ -60,7 +116,49 @@ impl WeightInfo for () {
     fn claim_reply_escrow() -> Weight {
         Weight::from_parts(10_000, 0)
     }
+    fn sponsor_exec() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+    fn set_recovery_config() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+    fn remove_recovery_config() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+    fn initiate_recovery() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+    fn approve_recovery() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+    fn cancel_recovery() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+    fn execute_recovery(_n: u32) -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+    fn commit_archive() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+    fn prune_archived(_n: u32) -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+    fn prove_archived_message() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
     fn on_initialize(_n: u32) -> Weight {
         Weight::from_parts(10_000, 0)
     }
+    fn file_spam_appeal() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+    fn resolve_spam_appeal() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+    fn send_auto_reply() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+    fn accept_recovery() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
 }
@@ -179,9 +179,9 @@ impl Pallet<Test> {
         );
 
         let current_block = System::block_number();
-        let already_tracked = AuditorAttestations::<Test>::get(&auditor).contains(&target);
+        let already_tracked = AuditorAttestations::<Test>::get(auditor).contains(&target);
         if !already_tracked {
-            AuditorAttestations::<Test>::try_mutate(&auditor, |list| {
+            AuditorAttestations::<Test>::try_mutate(auditor, |list| {
                 list.try_push(target)
                     .map_err(|_| Error::<Test>::TooManyAttestations)
             })?;
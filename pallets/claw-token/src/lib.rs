@@ -8,6 +8,11 @@
 //! - Contributor score tracking for airdrop eligibility
 //! - Airdrop claim mechanism based on contribution scores
 //! - Treasury spending for community initiatives
+//! - Hashed-timelock conditional transfers (`lock_conditional` / `claim` / `refund`)
+//!   for atomic swaps and conditional agent-to-agent payments, hashed with
+//!   blake2-256, sha2-256 or keccak-256 to match the counterparty chain.
+//!   Transfers are keyed by sender and hashlock, so locking under a hashlock
+//!   seen elsewhere cannot block the sender's own swap
 //!
 //! ## Tokenomics (from whitepaper)
 //!
@@ -29,8 +34,69 @@ mod tests;
 
 #[frame_support::pallet]
 pub mod pallet {
-    use frame_support::{pallet_prelude::*, traits::Currency};
+    use alloc::vec::Vec;
+    use frame_support::{
+        pallet_prelude::*,
+        traits::{BalanceStatus, Currency, NamedReservableCurrency},
+    };
     use frame_system::pallet_prelude::*;
+    use sp_core::H256;
+    use sp_runtime::traits::{Saturating, Zero};
+
+    pub type BalanceOf<T> =
+        <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+    /// Hash function a hashlock was computed with. Swaps against other
+    /// chains must use the function their HTLCs verify.
+    #[derive(
+        Clone,
+        Copy,
+        Encode,
+        Decode,
+        codec::DecodeWithMemTracking,
+        MaxEncodedLen,
+        TypeInfo,
+        RuntimeDebug,
+        PartialEq,
+        Eq,
+    )]
+    pub enum HashAlgorithm {
+        Blake2_256,
+        /// Bitcoin and most EVM HTLCs.
+        Sha2_256,
+        Keccak256,
+    }
+
+    impl HashAlgorithm {
+        /// Hash `preimage` with this algorithm.
+        pub fn hash(&self, preimage: &[u8]) -> H256 {
+            H256::from(match self {
+                HashAlgorithm::Blake2_256 => sp_io::hashing::blake2_256(preimage),
+                HashAlgorithm::Sha2_256 => sp_io::hashing::sha2_256(preimage),
+                HashAlgorithm::Keccak256 => sp_io::hashing::keccak_256(preimage),
+            })
+        }
+    }
+
+    /// Named reserve conditional transfers hold their funds under, kept apart
+    /// from the sender's other reserves.
+    pub const HTLC_RESERVE_ID: [u8; 8] = *b"clawhtlc";
+
+    /// A hashed-timelock transfer: `amount` is reserved on `from` until either
+    /// the preimage of the hashlock is revealed (paying `to`) or `timelock`
+    /// passes (refundable to `from`).
+    #[derive(Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    pub struct ConditionalTransfer<T: Config> {
+        pub from: T::AccountId,
+        pub to: T::AccountId,
+        pub amount: BalanceOf<T>,
+        /// Function the hashlock was computed with.
+        pub hash_algorithm: HashAlgorithm,
+        pub timelock: BlockNumberFor<T>,
+    }
+
+    impl<T: Config> codec::DecodeWithMemTracking for ConditionalTransfer<T> {}
 
     /// The pallet's configuration trait.
     #[pallet::config]
@@ -41,8 +107,9 @@ pub mod pallet {
         /// Weight information for extrinsics in this pallet.
         type WeightInfo: WeightInfo;
 
-        /// The currency implementation (typically pallet_balances).
-        type Currency: Currency<Self::AccountId>;
+        /// The currency implementation (typically pallet_balances). Needs at
+        /// least one named reserve per account for conditional transfers.
+        type Currency: NamedReservableCurrency<Self::AccountId, ReserveIdentifier = [u8; 8]>;
 
         /// Total airdrop pool size in base units.
         #[pallet::constant]
@@ -51,6 +118,14 @@ pub mod pallet {
         /// Maximum contribution score a single account can accumulate.
         #[pallet::constant]
         type MaxContributionScore: Get<u64>;
+
+        /// Maximum number of blocks a conditional transfer may stay locked.
+        #[pallet::constant]
+        type MaxTimelockBlocks: Get<u32>;
+
+        /// Maximum length of a hashlock preimage in bytes.
+        #[pallet::constant]
+        type MaxPreimageLength: Get<u32>;
     }
 
     #[pallet::pallet]
@@ -85,6 +160,19 @@ pub mod pallet {
     #[pallet::getter(fn treasury_balance)]
     pub type TreasuryBalance<T: Config> = StorageValue<_, u128, ValueQuery>;
 
+    /// Open hashed-timelock transfers, keyed by sender and hashlock.
+    #[pallet::storage]
+    #[pallet::getter(fn conditional_transfers)]
+    pub type ConditionalTransfers<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Blake2_128Concat,
+        H256,
+        ConditionalTransfer<T>,
+        OptionQuery,
+    >;
+
     // ========== Events ==========
 
     #[pallet::event]
@@ -100,6 +188,29 @@ pub mod pallet {
         AirdropClaimed { who: T::AccountId, amount: u128 },
         /// Treasury funds were spent.
         TreasurySpend { to: T::AccountId, amount: u128 },
+        /// Funds were locked behind a hashlock and timelock.
+        ConditionalTransferLocked {
+            hashlock: H256,
+            from: T::AccountId,
+            to: T::AccountId,
+            amount: BalanceOf<T>,
+            hash_algorithm: HashAlgorithm,
+            timelock: BlockNumberFor<T>,
+        },
+        /// The preimage was revealed and the locked funds paid out.
+        ConditionalTransferClaimed {
+            hashlock: H256,
+            from: T::AccountId,
+            to: T::AccountId,
+            amount: BalanceOf<T>,
+            preimage: BoundedVec<u8, T::MaxPreimageLength>,
+        },
+        /// The timelock passed and the locked funds returned to the sender.
+        ConditionalTransferRefunded {
+            hashlock: H256,
+            from: T::AccountId,
+            amount: BalanceOf<T>,
+        },
     }
 
     // ========== Errors ==========
@@ -120,6 +231,24 @@ pub mod pallet {
         InsufficientTreasuryBalance,
         /// Arithmetic overflow in calculations.
         ArithmeticOverflow,
+        /// Conditional transfers must move a non-zero amount.
+        ZeroAmount,
+        /// The timelock is in the past or further out than `MaxTimelockBlocks`.
+        InvalidTimelock,
+        /// The sender already has a conditional transfer with this hashlock.
+        HashlockInUse,
+        /// The sender has no conditional transfer with this hashlock.
+        ConditionalTransferNotFound,
+        /// The preimage exceeds `MaxPreimageLength`.
+        PreimageTooLong,
+        /// The timelock has passed; the transfer can only be refunded.
+        TimelockExpired,
+        /// The timelock has not passed yet; the transfer cannot be refunded.
+        TimelockNotExpired,
+        /// The sender's free balance cannot cover the locked amount.
+        InsufficientBalance,
+        /// The sender's reserve no longer covers the locked amount.
+        ReserveShortfall,
     }
 
     // ========== Extrinsics ==========
@@ -237,6 +366,147 @@ pub mod pallet {
 
             Ok(())
         }
+
+        /// Lock funds for `to`, claimable by revealing the preimage of `hashlock`
+        /// before block `timelock`.
+        ///
+        /// The amount is reserved on the caller under [`HTLC_RESERVE_ID`]. After
+        /// `timelock` the caller may reclaim it with `refund`. Each sender may
+        /// have one open transfer per hashlock.
+        ///
+        /// # Arguments
+        /// * `to` - The account paid when the preimage is revealed
+        /// * `amount` - The amount to lock
+        /// * `hashlock` - Hash of the secret preimage
+        /// * `hash_algorithm` - Function `hashlock` was computed with
+        /// * `timelock` - Block number from which the transfer becomes refundable
+        #[pallet::call_index(3)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 2))]
+        pub fn lock_conditional(
+            origin: OriginFor<T>,
+            to: T::AccountId,
+            amount: BalanceOf<T>,
+            hashlock: H256,
+            hash_algorithm: HashAlgorithm,
+            timelock: BlockNumberFor<T>,
+        ) -> DispatchResult {
+            let from = ensure_signed(origin)?;
+
+            ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
+            let now = frame_system::Pallet::<T>::block_number();
+            ensure!(
+                timelock > now
+                    && timelock <= now.saturating_add(T::MaxTimelockBlocks::get().into()),
+                Error::<T>::InvalidTimelock
+            );
+            ensure!(
+                !ConditionalTransfers::<T>::contains_key(&from, hashlock),
+                Error::<T>::HashlockInUse
+            );
+
+            T::Currency::reserve_named(&HTLC_RESERVE_ID, &from, amount)
+                .map_err(|_| Error::<T>::InsufficientBalance)?;
+
+            ConditionalTransfers::<T>::insert(
+                &from,
+                hashlock,
+                ConditionalTransfer {
+                    from: from.clone(),
+                    to: to.clone(),
+                    amount,
+                    hash_algorithm,
+                    timelock,
+                },
+            );
+
+            Self::deposit_event(Event::ConditionalTransferLocked {
+                hashlock,
+                from,
+                to,
+                amount,
+                hash_algorithm,
+                timelock,
+            });
+
+            Ok(())
+        }
+
+        /// Reveal the preimage of a hashlock computed with `hash_algorithm` and
+        /// pay the funds `from` locked under it to the recipient.
+        ///
+        /// Anyone may submit the preimage (e.g. a relayer completing a swap);
+        /// funds always go to the recipient recorded at lock time. The preimage
+        /// is emitted in the event so the counterparty can use it on the other
+        /// chain.
+        #[pallet::call_index(4)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(3, 3))]
+        pub fn claim(
+            origin: OriginFor<T>,
+            from: T::AccountId,
+            preimage: Vec<u8>,
+            hash_algorithm: HashAlgorithm,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+
+            let preimage: BoundedVec<u8, T::MaxPreimageLength> = preimage
+                .try_into()
+                .map_err(|_| Error::<T>::PreimageTooLong)?;
+            let hashlock = hash_algorithm.hash(&preimage);
+
+            let transfer = ConditionalTransfers::<T>::get(&from, hashlock)
+                .filter(|transfer| transfer.hash_algorithm == hash_algorithm)
+                .ok_or(Error::<T>::ConditionalTransferNotFound)?;
+            ensure!(
+                frame_system::Pallet::<T>::block_number() < transfer.timelock,
+                Error::<T>::TimelockExpired
+            );
+
+            let shortfall = T::Currency::repatriate_reserved_named(
+                &HTLC_RESERVE_ID,
+                &transfer.from,
+                &transfer.to,
+                transfer.amount,
+                BalanceStatus::Free,
+            )?;
+            ensure!(shortfall.is_zero(), Error::<T>::ReserveShortfall);
+            ConditionalTransfers::<T>::remove(&from, hashlock);
+
+            Self::deposit_event(Event::ConditionalTransferClaimed {
+                hashlock,
+                from,
+                to: transfer.to,
+                amount: transfer.amount,
+                preimage,
+            });
+
+            Ok(())
+        }
+
+        /// Return the funds the caller locked under `hashlock` once the
+        /// timelock has passed.
+        #[pallet::call_index(5)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 2))]
+        pub fn refund(origin: OriginFor<T>, hashlock: H256) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let transfer = ConditionalTransfers::<T>::get(&who, hashlock)
+                .ok_or(Error::<T>::ConditionalTransferNotFound)?;
+            ensure!(
+                frame_system::Pallet::<T>::block_number() >= transfer.timelock,
+                Error::<T>::TimelockNotExpired
+            );
+
+            T::Currency::unreserve_named(&HTLC_RESERVE_ID, &transfer.from, transfer.amount);
+            ConditionalTransfers::<T>::remove(&who, hashlock);
+
+            Self::deposit_event(Event::ConditionalTransferRefunded {
+                hashlock,
+                from: who,
+                amount: transfer.amount,
+            });
+
+            Ok(())
+        }
     }
 
     // ========== Weight Info Trait ==========
@@ -246,6 +516,9 @@ pub mod pallet {
         fn record_contribution() -> Weight;
         fn claim_airdrop() -> Weight;
         fn treasury_spend() -> Weight;
+        fn lock_conditional() -> Weight;
+        fn claim() -> Weight;
+        fn refund() -> Weight;
     }
 
     /// Default weights for testing.
@@ -259,5 +532,14 @@ pub mod pallet {
         fn treasury_spend() -> Weight {
             Weight::from_parts(10_000, 0)
         }
+        fn lock_conditional() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn claim() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn refund() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
     }
 }
@@ -2,17 +2,15 @@
 
 use crate as pallet_claw_token;
 use crate::pallet::{
-    AirdropClaimed, AirdropDistributed, ContributorScores, Event, TotalContributionScore,
+    AirdropClaimed, AirdropDistributed, ConditionalTransfers, ContributorScores, Error, Event,
+    HashAlgorithm, TotalContributionScore, HTLC_RESERVE_ID,
 };
 use frame_support::{
     assert_noop, assert_ok, derive_impl, parameter_types,
-    traits::{ConstU128, ConstU32, ConstU64},
+    traits::{ConstU128, ConstU32, ConstU64, NamedReservableCurrency},
 };
 use sp_core::H256;
-use sp_runtime::{
-    traits::{BlakeTwo256, IdentityLookup},
-    BuildStorage,
-};
+use sp_runtime::{traits::IdentityLookup, BuildStorage};
 
 type Block = frame_system::mocking::MockBlock<Test>;
 
@@ -35,7 +33,7 @@ impl frame_system::Config for Test {
 
 impl pallet_balances::Config for Test {
     type MaxLocks = ConstU32<50>;
-    type MaxReserves = ();
+    type MaxReserves = ConstU32<50>;
     type ReserveIdentifier = [u8; 8];
     type Balance = u128;
     type RuntimeEvent = RuntimeEvent;
@@ -61,6 +59,8 @@ impl pallet_claw_token::Config for Test {
     type Currency = Balances;
     type AirdropPool = TestAirdropPool;
     type MaxContributionScore = ConstU64<{ u64::MAX }>;
+    type MaxTimelockBlocks = ConstU32<100>;
+    type MaxPreimageLength = ConstU32<32>;
 }
 
 fn new_test_ext() -> sp_io::TestExternalities {
@@ -439,3 +439,309 @@ fn record_contribution_to_nonexistent_account() {
         assert_eq!(ContributorScores::<Test>::get(99), 500);
     });
 }
+
+// ========== Conditional transfer (HTLC) tests ==========
+
+const SECRET: &[u8] = b"swap-secret";
+
+fn hashlock_of(preimage: &[u8]) -> H256 {
+    H256::from(sp_io::hashing::blake2_256(preimage))
+}
+
+#[test]
+fn lock_conditional_reserves_funds() {
+    new_test_ext().execute_with(|| {
+        let hashlock = hashlock_of(SECRET);
+        assert_ok!(ClawTokenPallet::lock_conditional(
+            account(1),
+            2,
+            500,
+            hashlock,
+            HashAlgorithm::Blake2_256,
+            11
+        ));
+
+        assert_eq!(Balances::reserved_balance(1), 500);
+        assert_eq!(Balances::reserved_balance_named(&HTLC_RESERVE_ID, &1), 500);
+        assert_eq!(Balances::free_balance(1), 1_000_000 - 500);
+        let transfer = ConditionalTransfers::<Test>::get(1, hashlock).unwrap();
+        assert_eq!(transfer.to, 2);
+        assert_eq!(transfer.timelock, 11);
+    });
+}
+
+#[test]
+fn lock_conditional_timelock_bound_saturates() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(u64::MAX - 10);
+        assert_ok!(ClawTokenPallet::lock_conditional(
+            account(1),
+            2,
+            500,
+            hashlock_of(SECRET),
+            HashAlgorithm::Blake2_256,
+            u64::MAX
+        ));
+    });
+}
+
+#[test]
+fn lock_conditional_validates_inputs() {
+    new_test_ext().execute_with(|| {
+        let hashlock = hashlock_of(SECRET);
+        assert_noop!(
+            ClawTokenPallet::lock_conditional(
+                account(1),
+                2,
+                0,
+                hashlock,
+                HashAlgorithm::Blake2_256,
+                11
+            ),
+            Error::<Test>::ZeroAmount
+        );
+        assert_noop!(
+            ClawTokenPallet::lock_conditional(
+                account(1),
+                2,
+                500,
+                hashlock,
+                HashAlgorithm::Blake2_256,
+                1
+            ),
+            Error::<Test>::InvalidTimelock
+        );
+        assert_noop!(
+            ClawTokenPallet::lock_conditional(
+                account(1),
+                2,
+                500,
+                hashlock,
+                HashAlgorithm::Blake2_256,
+                102
+            ),
+            Error::<Test>::InvalidTimelock
+        );
+        assert_noop!(
+            ClawTokenPallet::lock_conditional(
+                account(1),
+                2,
+                2_000_000,
+                hashlock,
+                HashAlgorithm::Blake2_256,
+                11
+            ),
+            Error::<Test>::InsufficientBalance
+        );
+
+        assert_ok!(ClawTokenPallet::lock_conditional(
+            account(1),
+            2,
+            500,
+            hashlock,
+            HashAlgorithm::Blake2_256,
+            11
+        ));
+        assert_noop!(
+            ClawTokenPallet::lock_conditional(
+                account(1),
+                3,
+                500,
+                hashlock,
+                HashAlgorithm::Blake2_256,
+                11
+            ),
+            Error::<Test>::HashlockInUse
+        );
+    });
+}
+
+#[test]
+fn locking_a_seen_hashlock_does_not_block_the_real_swap() {
+    new_test_ext().execute_with(|| {
+        let hashlock = hashlock_of(SECRET);
+        // Account 3 squats the hashlock with dust before the real lock.
+        assert_ok!(ClawTokenPallet::lock_conditional(
+            account(3),
+            3,
+            1,
+            hashlock,
+            HashAlgorithm::Blake2_256,
+            11
+        ));
+        assert_ok!(ClawTokenPallet::lock_conditional(
+            account(1),
+            2,
+            500,
+            hashlock,
+            HashAlgorithm::Blake2_256,
+            11
+        ));
+
+        assert_ok!(ClawTokenPallet::claim(
+            account(2),
+            1,
+            SECRET.to_vec(),
+            HashAlgorithm::Blake2_256
+        ));
+        assert_eq!(Balances::free_balance(2), 1_000_000 + 500);
+        assert!(ConditionalTransfers::<Test>::get(3, hashlock).is_some());
+    });
+}
+
+#[test]
+fn claim_with_preimage_pays_recipient() {
+    new_test_ext().execute_with(|| {
+        let hashlock = hashlock_of(SECRET);
+        assert_ok!(ClawTokenPallet::lock_conditional(
+            account(1),
+            2,
+            500,
+            hashlock,
+            HashAlgorithm::Blake2_256,
+            11
+        ));
+
+        // Anyone may reveal the preimage; funds go to the recorded recipient.
+        assert_ok!(ClawTokenPallet::claim(
+            account(3),
+            1,
+            SECRET.to_vec(),
+            HashAlgorithm::Blake2_256
+        ));
+
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_eq!(Balances::free_balance(1), 1_000_000 - 500);
+        assert_eq!(Balances::free_balance(2), 1_000_000 + 500);
+        assert!(ConditionalTransfers::<Test>::get(1, hashlock).is_none());
+        System::assert_last_event(RuntimeEvent::ClawTokenPallet(
+            Event::ConditionalTransferClaimed {
+                hashlock,
+                from: 1,
+                to: 2,
+                amount: 500,
+                preimage: SECRET.to_vec().try_into().unwrap(),
+            },
+        ));
+    });
+}
+
+#[test]
+fn claim_fails_with_wrong_preimage_or_after_timelock() {
+    new_test_ext().execute_with(|| {
+        let hashlock = hashlock_of(SECRET);
+        assert_ok!(ClawTokenPallet::lock_conditional(
+            account(1),
+            2,
+            500,
+            hashlock,
+            HashAlgorithm::Blake2_256,
+            11
+        ));
+
+        assert_noop!(
+            ClawTokenPallet::claim(account(2), 1, b"wrong".to_vec(), HashAlgorithm::Blake2_256),
+            Error::<Test>::ConditionalTransferNotFound
+        );
+        assert_noop!(
+            ClawTokenPallet::claim(account(2), 1, vec![0u8; 33], HashAlgorithm::Blake2_256),
+            Error::<Test>::PreimageTooLong
+        );
+
+        System::set_block_number(11);
+        assert_noop!(
+            ClawTokenPallet::claim(account(2), 1, SECRET.to_vec(), HashAlgorithm::Blake2_256),
+            Error::<Test>::TimelockExpired
+        );
+    });
+}
+
+#[test]
+fn claim_verifies_with_the_locked_hash_algorithm() {
+    new_test_ext().execute_with(|| {
+        for (hashlock, algorithm) in [
+            (
+                H256::from(sp_io::hashing::sha2_256(SECRET)),
+                HashAlgorithm::Sha2_256,
+            ),
+            (
+                H256::from(sp_io::hashing::keccak_256(SECRET)),
+                HashAlgorithm::Keccak256,
+            ),
+        ] {
+            assert_ok!(ClawTokenPallet::lock_conditional(
+                account(1),
+                2,
+                500,
+                hashlock,
+                algorithm,
+                11
+            ));
+            assert_noop!(
+                ClawTokenPallet::claim(account(2), 1, SECRET.to_vec(), HashAlgorithm::Blake2_256),
+                Error::<Test>::ConditionalTransferNotFound
+            );
+            assert_ok!(ClawTokenPallet::claim(
+                account(2),
+                1,
+                SECRET.to_vec(),
+                algorithm
+            ));
+            assert!(ConditionalTransfers::<Test>::get(1, hashlock).is_none());
+        }
+        assert_eq!(Balances::free_balance(2), 1_000_000 + 1_000);
+    });
+}
+
+#[test]
+fn claim_fails_if_the_reserve_was_slashed() {
+    new_test_ext().execute_with(|| {
+        let hashlock = hashlock_of(SECRET);
+        assert_ok!(ClawTokenPallet::lock_conditional(
+            account(1),
+            2,
+            500,
+            hashlock,
+            HashAlgorithm::Blake2_256,
+            11
+        ));
+        let _ = Balances::slash_reserved_named(&HTLC_RESERVE_ID, &1, 200);
+
+        assert_noop!(
+            ClawTokenPallet::claim(account(2), 1, SECRET.to_vec(), HashAlgorithm::Blake2_256),
+            Error::<Test>::ReserveShortfall
+        );
+        assert!(ConditionalTransfers::<Test>::get(1, hashlock).is_some());
+    });
+}
+
+#[test]
+fn refund_after_timelock_returns_funds() {
+    new_test_ext().execute_with(|| {
+        let hashlock = hashlock_of(SECRET);
+        assert_ok!(ClawTokenPallet::lock_conditional(
+            account(1),
+            2,
+            500,
+            hashlock,
+            HashAlgorithm::Blake2_256,
+            11
+        ));
+
+        assert_noop!(
+            ClawTokenPallet::refund(account(1), hashlock),
+            Error::<Test>::TimelockNotExpired
+        );
+
+        System::set_block_number(11);
+        assert_noop!(
+            ClawTokenPallet::refund(account(2), hashlock),
+            Error::<Test>::ConditionalTransferNotFound
+        );
+        assert_ok!(ClawTokenPallet::refund(account(1), hashlock));
+
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_eq!(Balances::free_balance(1), 1_000_000);
+        assert!(ConditionalTransfers::<Test>::get(1, hashlock).is_none());
+    });
+}
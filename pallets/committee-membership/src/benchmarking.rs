@@ -0,0 +1,69 @@
+//! Benchmarking for pallet-committee-membership.
+//!
+//! Weights are generated by running:
+//!   `cargo build --features runtime-benchmarks`
+//!   `./target/release/clawchain-node benchmark pallet --pallet pallet_committee_membership ...`
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use crate::pallet::{MemberInfo, Members};
+use frame_benchmarking::v2::*;
+use frame_support::{traits::Get, BoundedVec};
+use frame_system::RawOrigin;
+
+/// Fill the committee with `n` members and return the last one seated.
+fn seat_members<T: Config<I>, I: 'static>(n: u32) -> Option<T::AccountId> {
+    let members: alloc::vec::Vec<_> = (0..n)
+        .map(|i| MemberInfo {
+            who: account::<T::AccountId>("member", i, 0),
+            expires_at: None,
+        })
+        .collect();
+    let last = members.last().map(|m| m.who.clone());
+    Members::<T, I>::put(BoundedVec::truncate_from(members));
+    last
+}
+
+#[instance_benchmarks]
+mod benchmarks {
+    use super::*;
+
+    /// Worst case: every existing member is scanned for a duplicate.
+    #[benchmark]
+    fn add_member() {
+        seat_members::<T, I>(T::MaxMembers::get().saturating_sub(1));
+        let who: T::AccountId = account("new", 0, 0);
+
+        #[extrinsic_call]
+        add_member(RawOrigin::Root, who.clone());
+
+        assert!(Members::<T, I>::get().iter().any(|m| m.who == who));
+    }
+
+    /// Worst case: the member sits last in a full committee.
+    #[benchmark]
+    fn remove_member() {
+        let who = seat_members::<T, I>(T::MaxMembers::get()).expect("MaxMembers is non-zero");
+
+        #[extrinsic_call]
+        remove_member(RawOrigin::Root, who.clone());
+
+        assert!(!Members::<T, I>::get().iter().any(|m| m.who == who));
+    }
+
+    /// Worst case: the member sits last in a full committee.
+    #[benchmark]
+    fn renew_member() {
+        let who = seat_members::<T, I>(T::MaxMembers::get()).expect("MaxMembers is non-zero");
+
+        #[extrinsic_call]
+        renew_member(RawOrigin::Root, who);
+    }
+
+    impl_benchmark_test_suite!(
+        Pallet,
+        crate::mock::new_test_ext(alloc::vec::Vec::new()),
+        crate::mock::Test
+    );
+}
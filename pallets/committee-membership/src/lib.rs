@@ -0,0 +1,371 @@
+//! # Committee Membership Pallet
+//!
+//! Standing committees for protocol working groups (dispute court admins,
+//! oracle committee, relayer committee, ...).
+//!
+//! ## Overview
+//!
+//! The pallet is instanceable: each committee is a separate instance with its
+//! own member set, size bound and term length. Governance adds, renews and
+//! removes members; each membership lasts `TermDuration` blocks (0 = no
+//! expiry) and is dropped automatically in `on_initialize` once it lapses.
+//!
+//! Other pallets gate calls on a committee by using [`EnsureMember`] as an
+//! origin, or query membership through the [`Contains`] or [`SortedMembers`]
+//! implementations:
+//!
+//! ```ignore
+//! type OracleOrigin = pallet_committee_membership::EnsureMember<Runtime, OracleCommitteeInstance>;
+//! ```
+//!
+//! ## Storage
+//!
+//! - [`Members`] — bounded list of members and their term end
+//!
+//! ## Extrinsics
+//!
+//! | Call | Who |
+//! |------|-----|
+//! | `add_member` | `GovernanceOrigin` |
+//! | `remove_member` | `GovernanceOrigin` |
+//! | `renew_member` | `GovernanceOrigin` |
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(deprecated, clippy::let_unit_value)]
+
+extern crate alloc;
+
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+pub mod weights;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(test)]
+pub mod mock;
+#[cfg(test)]
+mod tests;
+
+use core::marker::PhantomData;
+use frame_support::traits::{Contains, EnsureOrigin, SortedMembers};
+
+#[frame_support::pallet]
+pub mod pallet {
+    use super::*;
+    use alloc::vec::Vec;
+    use frame_support::{pallet_prelude::*, traits::BuildGenesisConfig};
+    use frame_system::pallet_prelude::*;
+    use sp_runtime::traits::{Saturating, Zero};
+
+    // =========================================================================
+    // Types
+    // =========================================================================
+
+    /// A committee member and the end of its current term.
+    #[derive(
+        Clone,
+        Encode,
+        Decode,
+        Eq,
+        PartialEq,
+        RuntimeDebug,
+        TypeInfo,
+        MaxEncodedLen,
+        codec::DecodeWithMemTracking,
+    )]
+    pub struct MemberInfo<AccountId, BlockNumber> {
+        pub who: AccountId,
+        /// First block at which the member no longer belongs to the committee.
+        /// `None` if the term does not expire.
+        pub expires_at: Option<BlockNumber>,
+    }
+
+    pub type MemberInfoOf<T> =
+        MemberInfo<<T as frame_system::Config>::AccountId, BlockNumberFor<T>>;
+
+    // =========================================================================
+    // Config
+    // =========================================================================
+
+    #[pallet::config]
+    pub trait Config<I: 'static = ()>: frame_system::Config {
+        /// The overarching event type.
+        type RuntimeEvent: From<Event<Self, I>>
+            + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+        /// Weight information for extrinsics.
+        type WeightInfo: WeightInfo;
+
+        /// Origin allowed to add, renew and remove members.
+        type GovernanceOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Maximum number of members in the committee.
+        #[pallet::constant]
+        type MaxMembers: Get<u32>;
+
+        /// Length of a membership term in blocks. 0 means terms never expire.
+        #[pallet::constant]
+        type TermDuration: Get<BlockNumberFor<Self>>;
+    }
+
+    #[pallet::pallet]
+    pub struct Pallet<T, I = ()>(_);
+
+    // =========================================================================
+    // Storage
+    // =========================================================================
+
+    /// Current committee members.
+    #[pallet::storage]
+    pub type Members<T: Config<I>, I: 'static = ()> =
+        StorageValue<_, BoundedVec<MemberInfoOf<T>, T::MaxMembers>, ValueQuery>;
+
+    // =========================================================================
+    // Genesis
+    // =========================================================================
+
+    #[pallet::genesis_config]
+    #[derive(frame_support::DefaultNoBound)]
+    pub struct GenesisConfig<T: Config<I>, I: 'static = ()> {
+        /// Initial committee members, each starting a full term at genesis.
+        pub members: Vec<T::AccountId>,
+        #[serde(skip)]
+        pub phantom: PhantomData<I>,
+    }
+
+    #[pallet::genesis_build]
+    impl<T: Config<I>, I: 'static> BuildGenesisConfig for GenesisConfig<T, I> {
+        fn build(&self) {
+            let expires_at = Pallet::<T, I>::term_end(Zero::zero());
+            let mut members = BoundedVec::<MemberInfoOf<T>, T::MaxMembers>::new();
+            for who in &self.members {
+                assert!(
+                    !members.iter().any(|m| &m.who == who),
+                    "duplicate committee member in genesis"
+                );
+                members
+                    .try_push(MemberInfo {
+                        who: who.clone(),
+                        expires_at,
+                    })
+                    .expect("genesis committee exceeds MaxMembers");
+            }
+            Members::<T, I>::put(members);
+        }
+    }
+
+    // =========================================================================
+    // Events
+    // =========================================================================
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config<I>, I: 'static = ()> {
+        /// A member joined the committee.
+        MemberAdded {
+            who: T::AccountId,
+            expires_at: Option<BlockNumberFor<T>>,
+        },
+        /// A member was removed by governance.
+        MemberRemoved { who: T::AccountId },
+        /// A member's term was restarted.
+        MemberRenewed {
+            who: T::AccountId,
+            expires_at: Option<BlockNumberFor<T>>,
+        },
+        /// A member's term ended.
+        MemberExpired { who: T::AccountId },
+    }
+
+    // =========================================================================
+    // Errors
+    // =========================================================================
+
+    #[pallet::error]
+    pub enum Error<T, I = ()> {
+        /// The account is already a member.
+        AlreadyMember,
+        /// The account is not a member.
+        NotMember,
+        /// The committee is at `MaxMembers`.
+        TooManyMembers,
+    }
+
+    // =========================================================================
+    // Hooks
+    // =========================================================================
+
+    #[pallet::hooks]
+    impl<T: Config<I>, I: 'static> Hooks<BlockNumberFor<T>> for Pallet<T, I> {
+        /// Drop members whose term has ended.
+        fn on_initialize(now: BlockNumberFor<T>) -> Weight {
+            let mut members = Members::<T, I>::get();
+            let before = members.len();
+            members.retain(|m| {
+                let active = m.expires_at.is_none_or(|end| now < end);
+                if !active {
+                    Self::deposit_event(Event::MemberExpired { who: m.who.clone() });
+                }
+                active
+            });
+
+            if members.len() == before {
+                return T::DbWeight::get().reads(1);
+            }
+            Members::<T, I>::put(members);
+            T::DbWeight::get().reads_writes(1, 1)
+        }
+    }
+
+    // =========================================================================
+    // Extrinsics
+    // =========================================================================
+
+    #[pallet::call]
+    impl<T: Config<I>, I: 'static> Pallet<T, I> {
+        /// Add `who` to the committee for one term.
+        #[pallet::call_index(0)]
+        #[pallet::weight(T::WeightInfo::add_member())]
+        pub fn add_member(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+            T::GovernanceOrigin::ensure_origin(origin)?;
+
+            let expires_at = Self::term_end(frame_system::Pallet::<T>::block_number());
+            Members::<T, I>::try_mutate(|members| {
+                ensure!(
+                    !members.iter().any(|m| m.who == who),
+                    Error::<T, I>::AlreadyMember
+                );
+                members
+                    .try_push(MemberInfo {
+                        who: who.clone(),
+                        expires_at,
+                    })
+                    .map_err(|_| Error::<T, I>::TooManyMembers)?;
+                Ok::<(), DispatchError>(())
+            })?;
+
+            Self::deposit_event(Event::MemberAdded { who, expires_at });
+            Ok(())
+        }
+
+        /// Remove `who` from the committee before its term ends.
+        #[pallet::call_index(1)]
+        #[pallet::weight(T::WeightInfo::remove_member())]
+        pub fn remove_member(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+            T::GovernanceOrigin::ensure_origin(origin)?;
+
+            Members::<T, I>::try_mutate(|members| {
+                let pos = members
+                    .iter()
+                    .position(|m| m.who == who)
+                    .ok_or(Error::<T, I>::NotMember)?;
+                members.remove(pos);
+                Ok::<(), DispatchError>(())
+            })?;
+
+            Self::deposit_event(Event::MemberRemoved { who });
+            Ok(())
+        }
+
+        /// Start a fresh term for an existing member.
+        #[pallet::call_index(2)]
+        #[pallet::weight(T::WeightInfo::renew_member())]
+        pub fn renew_member(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+            T::GovernanceOrigin::ensure_origin(origin)?;
+
+            let expires_at = Self::term_end(frame_system::Pallet::<T>::block_number());
+            Members::<T, I>::try_mutate(|members| {
+                let member = members
+                    .iter_mut()
+                    .find(|m| m.who == who)
+                    .ok_or(Error::<T, I>::NotMember)?;
+                member.expires_at = expires_at;
+                Ok::<(), DispatchError>(())
+            })?;
+
+            Self::deposit_event(Event::MemberRenewed { who, expires_at });
+            Ok(())
+        }
+    }
+
+    // =========================================================================
+    // Helpers
+    // =========================================================================
+
+    impl<T: Config<I>, I: 'static> Pallet<T, I> {
+        /// Whether `who` currently sits on the committee.
+        pub fn is_member(who: &T::AccountId) -> bool {
+            let now = frame_system::Pallet::<T>::block_number();
+            Members::<T, I>::get()
+                .iter()
+                .any(|m| &m.who == who && m.expires_at.is_none_or(|end| now < end))
+        }
+
+        /// Term end for a membership starting at `start`.
+        pub(crate) fn term_end(start: BlockNumberFor<T>) -> Option<BlockNumberFor<T>> {
+            let term = T::TermDuration::get();
+            (!term.is_zero()).then(|| start.saturating_add(term))
+        }
+    }
+}
+
+impl<T: Config<I>, I: 'static> Contains<T::AccountId> for Pallet<T, I> {
+    fn contains(who: &T::AccountId) -> bool {
+        Self::is_member(who)
+    }
+}
+
+impl<T: Config<I>, I: 'static> SortedMembers<T::AccountId> for Pallet<T, I> {
+    fn sorted_members() -> alloc::vec::Vec<T::AccountId> {
+        let now = frame_system::Pallet::<T>::block_number();
+        let mut members: alloc::vec::Vec<_> = Members::<T, I>::get()
+            .into_iter()
+            .filter(|m| m.expires_at.is_none_or(|end| now < end))
+            .map(|m| m.who)
+            .collect();
+        members.sort();
+        members
+    }
+
+    fn contains(who: &T::AccountId) -> bool {
+        Self::is_member(who)
+    }
+
+    /// Seat `who` for one term, ignoring a full committee.
+    #[cfg(feature = "runtime-benchmarks")]
+    fn add(who: &T::AccountId) {
+        let expires_at = Self::term_end(frame_system::Pallet::<T>::block_number());
+        Members::<T, I>::mutate(|members| {
+            if !members.iter().any(|m| &m.who == who) {
+                let _ = members.try_push(MemberInfo {
+                    who: who.clone(),
+                    expires_at,
+                });
+            }
+        });
+    }
+}
+
+/// Origin check that succeeds for a signed, current member of committee `I`,
+/// yielding the member's account.
+pub struct EnsureMember<T, I = ()>(PhantomData<(T, I)>);
+
+impl<T: Config<I>, I: 'static> EnsureOrigin<T::RuntimeOrigin> for EnsureMember<T, I> {
+    type Success = T::AccountId;
+
+    fn try_origin(o: T::RuntimeOrigin) -> Result<Self::Success, T::RuntimeOrigin> {
+        o.into().and_then(|o| match o {
+            frame_system::RawOrigin::Signed(who) if Pallet::<T, I>::is_member(&who) => Ok(who),
+            r => Err(T::RuntimeOrigin::from(r)),
+        })
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn try_successful_origin() -> Result<T::RuntimeOrigin, ()> {
+        Members::<T, I>::get()
+            .first()
+            .map(|m| frame_system::RawOrigin::Signed(m.who.clone()).into())
+            .ok_or(())
+    }
+}
@@ -0,0 +1,84 @@
+//! Mock runtime for pallet-committee-membership unit tests.
+//!
+//! Two instances stand in for independent committees; the default instance
+//! is only exercised by the benchmark tests.
+
+use crate as pallet_committee_membership;
+use frame_support::{derive_impl, parameter_types};
+use frame_system::EnsureRoot;
+use sp_runtime::{traits::IdentityLookup, BuildStorage};
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+    pub enum Test {
+        System: frame_system,
+        Court: pallet_committee_membership::<Instance1>,
+        Oracles: pallet_committee_membership::<Instance2>,
+        Committee: pallet_committee_membership,
+    }
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig as frame_system::DefaultConfig)]
+impl frame_system::Config for Test {
+    type Block = Block;
+    type AccountId = u64;
+    type Lookup = IdentityLookup<Self::AccountId>;
+}
+
+parameter_types! {
+    pub const CourtTerm: u64 = 100;
+    pub const NoTerm: u64 = 0;
+}
+
+impl pallet_committee_membership::Config<pallet_committee_membership::Instance1> for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type WeightInfo = ();
+    type GovernanceOrigin = EnsureRoot<u64>;
+    type MaxMembers = frame_support::traits::ConstU32<3>;
+    type TermDuration = CourtTerm;
+}
+
+impl pallet_committee_membership::Config<pallet_committee_membership::Instance2> for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type WeightInfo = ();
+    type GovernanceOrigin = EnsureRoot<u64>;
+    type MaxMembers = frame_support::traits::ConstU32<5>;
+    type TermDuration = NoTerm;
+}
+
+impl pallet_committee_membership::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type WeightInfo = ();
+    type GovernanceOrigin = EnsureRoot<u64>;
+    type MaxMembers = frame_support::traits::ConstU32<3>;
+    type TermDuration = CourtTerm;
+}
+
+/// Build test externalities with `court` seated in the court committee at genesis.
+pub fn new_test_ext(court: Vec<u64>) -> sp_io::TestExternalities {
+    let mut t = frame_system::GenesisConfig::<Test>::default()
+        .build_storage()
+        .unwrap();
+
+    pallet_committee_membership::GenesisConfig::<Test, pallet_committee_membership::Instance1> {
+        members: court,
+        phantom: Default::default(),
+    }
+    .assimilate_storage(&mut t)
+    .unwrap();
+
+    let mut ext = sp_io::TestExternalities::new(t);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
+
+/// Advance the block number to `n`, running the court's `on_initialize`.
+pub fn roll_to(n: u64) {
+    use frame_support::traits::Hooks;
+    let current = System::block_number();
+    for i in (current + 1)..=n {
+        System::set_block_number(i);
+        Court::on_initialize(i);
+    }
+}
@@ -0,0 +1,107 @@
+//! Unit tests for pallet-committee-membership.
+
+use crate::{mock::*, pallet::Members, EnsureMember, Error, Event, Instance1, Instance2};
+use frame_support::{
+    assert_noop, assert_ok,
+    traits::{Contains, EnsureOrigin},
+};
+use sp_runtime::DispatchError;
+
+type CourtOrigin = EnsureMember<Test, Instance1>;
+
+#[test]
+fn genesis_seats_members_for_one_term() {
+    new_test_ext(vec![1, 2]).execute_with(|| {
+        let members = Members::<Test, Instance1>::get();
+        assert_eq!(members.len(), 2);
+        assert_eq!(members[0].expires_at, Some(100));
+        assert!(Court::contains(&1));
+        assert!(!Oracles::contains(&1));
+    });
+}
+
+#[test]
+fn governance_manages_members() {
+    new_test_ext(vec![]).execute_with(|| {
+        assert_noop!(
+            Court::add_member(RuntimeOrigin::signed(1), 1),
+            DispatchError::BadOrigin
+        );
+
+        assert_ok!(Court::add_member(RuntimeOrigin::root(), 1));
+        System::assert_last_event(
+            Event::<Test, Instance1>::MemberAdded {
+                who: 1,
+                expires_at: Some(101),
+            }
+            .into(),
+        );
+        assert_noop!(
+            Court::add_member(RuntimeOrigin::root(), 1),
+            Error::<Test, Instance1>::AlreadyMember
+        );
+
+        assert_ok!(Court::add_member(RuntimeOrigin::root(), 2));
+        assert_ok!(Court::add_member(RuntimeOrigin::root(), 3));
+        assert_noop!(
+            Court::add_member(RuntimeOrigin::root(), 4),
+            Error::<Test, Instance1>::TooManyMembers
+        );
+
+        assert_ok!(Court::remove_member(RuntimeOrigin::root(), 2));
+        assert!(!Court::contains(&2));
+        assert_noop!(
+            Court::remove_member(RuntimeOrigin::root(), 2),
+            Error::<Test, Instance1>::NotMember
+        );
+    });
+}
+
+#[test]
+fn terms_expire_unless_renewed() {
+    new_test_ext(vec![1, 2]).execute_with(|| {
+        roll_to(50);
+        assert_ok!(Court::renew_member(RuntimeOrigin::root(), 2));
+
+        roll_to(100);
+        assert!(!Court::contains(&1));
+        assert!(Court::contains(&2));
+        System::assert_has_event(Event::<Test, Instance1>::MemberExpired { who: 1 }.into());
+        assert_eq!(Members::<Test, Instance1>::get().len(), 1);
+
+        roll_to(150);
+        assert!(!Court::contains(&2));
+        assert_noop!(
+            Court::renew_member(RuntimeOrigin::root(), 2),
+            Error::<Test, Instance1>::NotMember
+        );
+    });
+}
+
+#[test]
+fn instance_without_term_never_expires() {
+    new_test_ext(vec![]).execute_with(|| {
+        assert_ok!(Oracles::add_member(RuntimeOrigin::root(), 7));
+        assert_eq!(Members::<Test, Instance2>::get()[0].expires_at, None);
+        System::set_block_number(1_000_000);
+        assert!(Oracles::contains(&7));
+        assert!(!Court::contains(&7));
+    });
+}
+
+#[test]
+fn ensure_member_origin() {
+    new_test_ext(vec![1]).execute_with(|| {
+        assert_eq!(
+            CourtOrigin::try_origin(RuntimeOrigin::signed(1)).ok(),
+            Some(1)
+        );
+        assert!(CourtOrigin::try_origin(RuntimeOrigin::signed(2)).is_err());
+        assert!(CourtOrigin::try_origin(RuntimeOrigin::root()).is_err());
+        assert!(EnsureMember::<Test, Instance2>::try_origin(RuntimeOrigin::signed(1)).is_err());
+
+        // Membership lapses with the term even before on_initialize prunes it.
+        System::set_block_number(100);
+        assert!(CourtOrigin::try_origin(RuntimeOrigin::signed(1)).is_err());
+    });
+}
@@ -0,0 +1,29 @@
+//! Weight definitions for pallet-committee-membership.
+//!
+//! These are placeholder weights. Real weights should be generated by running
+//! the Substrate frame benchmarking suite against this pallet.
+
+use frame_support::weights::Weight;
+
+/// Weight functions needed for `pallet_committee_membership`.
+pub trait WeightInfo {
+    fn add_member() -> Weight;
+    fn remove_member() -> Weight;
+    fn renew_member() -> Weight;
+}
+
+/// Default weights used in unit tests and when no benchmarked weights are
+/// available.
+impl WeightInfo for () {
+    fn add_member() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+
+    fn remove_member() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+
+    fn renew_member() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+}
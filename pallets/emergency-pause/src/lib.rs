@@ -37,6 +37,11 @@
 
 extern crate alloc;
 
+use alloc::vec::Vec;
+use clawchain_primitives::{BudgetedHook, HookUsage};
+use frame_support::{traits::Get, weights::Weight};
+use frame_system::pallet_prelude::BlockNumberFor;
+
 pub use pallet::*;
 pub use traits::{AuditTrailProvider, EmergencyPauseProvider};
 pub use weights::WeightInfo;
@@ -357,60 +362,6 @@ pub mod pallet {
         ThresholdExceedsCouncilSize,
     }
 
-    // =========================================================================
-    // Hooks
-    // =========================================================================
-
-    #[pallet::hooks]
-    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
-        /// Clean up expired proposals and lift emergency pauses on every block.
-        fn on_initialize(now: BlockNumberFor<T>) -> Weight {
-            let mut weight = T::DbWeight::get().reads(1);
-
-            // --- 1. Expire proposals ---
-            // We collect IDs first to avoid mutating the map while iterating.
-            let expired_ids: Vec<ProposalId> = PauseVotes::<T>::iter()
-                .filter_map(|(id, proposal)| {
-                    if proposal.expires_at <= now {
-                        Some(id)
-                    } else {
-                        None
-                    }
-                })
-                .collect();
-
-            for id in expired_ids {
-                PauseVotes::<T>::remove(id);
-                ActiveProposalCount::<T>::mutate(|c| *c = c.saturating_sub(1));
-                Self::deposit_event(Event::ProposalExpired { proposal_id: id });
-                weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 1));
-            }
-
-            // --- 2. Lift emergency pauses that have reached their expiry ---
-            let expired_pallets: Vec<PalletId<T>> = PausedPallets::<T>::iter()
-                .filter_map(|(pid, info)| {
-                    if info.reason == PauseReason::EmergencyTrigger
-                        && info.expires_at > BlockNumberFor::<T>::from(0u32)
-                        && info.expires_at <= now
-                    {
-                        Some(pid)
-                    } else {
-                        None
-                    }
-                })
-                .collect();
-
-            for pid in expired_pallets {
-                let pid_vec = pid.to_vec();
-                PausedPallets::<T>::remove(&pid);
-                Self::deposit_event(Event::EmergencyPauseExpired { pallet_id: pid_vec });
-                weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 1));
-            }
-
-            weight
-        }
-    }
-
     // =========================================================================
     // Extrinsics
     // =========================================================================
@@ -878,3 +829,87 @@ pub mod pallet {
         }
     }
 }
+
+/// Expire lapsed proposals and lift lapsed emergency pauses.
+///
+/// Run by `pallet-hook-budget`. Every entry the scans read is metered, and so
+/// is each expiry; whatever does not fit in `limit` is still `<= now` next
+/// block and is picked up then. Both maps are bounded (`MaxActiveProposals`,
+/// `MaxPausedPallets`), so a scan never reads more than that many entries.
+impl<T: Config> BudgetedHook<BlockNumberFor<T>> for Pallet<T> {
+    fn run(now: BlockNumberFor<T>, limit: Weight) -> HookUsage {
+        let scan = T::DbWeight::get().reads(1);
+        let handle = T::DbWeight::get().writes(1);
+        let mut used = T::DbWeight::get().reads(1);
+        let mut deferred = false;
+
+        // --- 1. Expire proposals ---
+        // We collect IDs first to avoid mutating the map while iterating.
+        let (expired_ids, more) = Self::scan_due(
+            PauseVotes::<T>::iter(),
+            |proposal| proposal.expires_at <= now,
+            &mut used,
+            limit,
+            scan,
+            handle,
+        );
+        deferred |= more;
+        for id in expired_ids {
+            PauseVotes::<T>::remove(id);
+            ActiveProposalCount::<T>::mutate(|c| *c = c.saturating_sub(1));
+            Self::deposit_event(Event::ProposalExpired { proposal_id: id });
+        }
+
+        // --- 2. Lift emergency pauses that have reached their expiry ---
+        let (expired_pallets, more) = Self::scan_due(
+            PausedPallets::<T>::iter(),
+            |info| {
+                info.reason == PauseReason::EmergencyTrigger
+                    && info.expires_at > BlockNumberFor::<T>::from(0u32)
+                    && info.expires_at <= now
+            },
+            &mut used,
+            limit,
+            scan,
+            handle,
+        );
+        deferred |= more;
+        for pid in expired_pallets {
+            let pid_vec = pid.to_vec();
+            PausedPallets::<T>::remove(&pid);
+            Self::deposit_event(Event::EmergencyPauseExpired { pallet_id: pid_vec });
+        }
+
+        HookUsage { used, deferred }
+    }
+}
+
+impl<T: Config> Pallet<T> {
+    /// Read entries of `entries` while `limit` still covers reading one more
+    /// and handling it, charging `scan` per entry read and `handle` per due
+    /// entry to `used`. Returns the due keys and whether the budget stopped
+    /// the scan early.
+    fn scan_due<K, V>(
+        mut entries: impl Iterator<Item = (K, V)>,
+        is_due: impl Fn(&V) -> bool,
+        used: &mut Weight,
+        limit: Weight,
+        scan: Weight,
+        handle: Weight,
+    ) -> (Vec<K>, bool) {
+        let mut due = Vec::new();
+        loop {
+            if !limit.all_gte(used.saturating_add(scan).saturating_add(handle)) {
+                return (due, true);
+            }
+            let Some((key, value)) = entries.next() else {
+                return (due, false);
+            };
+            *used = used.saturating_add(scan);
+            if is_due(&value) {
+                *used = used.saturating_add(handle);
+                due.push(key);
+            }
+        }
+    }
+}
@@ -18,6 +18,7 @@ impl frame_system::Config for Test {
     type Block = Block;
     type AccountId = u64;
     type Lookup = IdentityLookup<Self::AccountId>;
+    type DbWeight = frame_support::weights::constants::RocksDbWeight;
 }
 
 parameter_types! {
@@ -77,12 +78,12 @@ pub fn root() -> <Test as frame_system::Config>::RuntimeOrigin {
     frame_system::RawOrigin::Root.into()
 }
 
-/// Advance the block number by `n` and run `on_initialize`.
+/// Advance the block number by `n` and run the budgeted hook with no limit.
 pub fn roll_to(n: u64) {
-    use frame_support::traits::Hooks;
+    use clawchain_primitives::BudgetedHook;
     let current = System::block_number();
     for i in (current + 1)..=n {
         System::set_block_number(i);
-        EmergencyPause::on_initialize(i);
+        EmergencyPause::run(i, frame_support::weights::Weight::MAX);
     }
 }
@@ -5,8 +5,8 @@
 //! - Propose pause / unpause
 //! - Voting flow through to execution
 //! - Emergency pause
-//! - Proposal expiry (on_initialize)
-//! - Emergency pause expiry (on_initialize)
+//! - Proposal expiry (budgeted hook)
+//! - Emergency pause expiry (budgeted hook)
 //! - All error paths
 //! - EmergencyPauseProvider trait
 //! - Genesis config
@@ -440,6 +440,56 @@ fn emergency_pause_expires_via_on_initialize() {
     });
 }
 
+#[test]
+fn emergency_pause_expiry_resumes_when_budget_runs_out() {
+    use clawchain_primitives::BudgetedHook;
+
+    new_test_ext_with_members(vec![1]).execute_with(|| {
+        System::set_block_number(100);
+        assert_ok!(EmergencyPause::emergency_pause(origin(1)));
+        let total = EmergencyPause::custom_pallet_ids().len();
+
+        // Room for the base read plus five lifts.
+        let db = <Test as frame_system::Config>::DbWeight::get();
+        let limit = db
+            .reads(1)
+            .saturating_add(db.reads_writes(1, 1).saturating_mul(5));
+        let usage = EmergencyPause::run(1300, limit);
+        assert!(usage.deferred);
+        assert_eq!(usage.used, limit);
+        assert_eq!(PausedPallets::<Test>::iter().count(), total - 5);
+
+        let usage = EmergencyPause::run(1301, frame_support::weights::Weight::MAX);
+        assert!(!usage.deferred);
+        assert_eq!(PausedPallets::<Test>::iter().count(), 0);
+    });
+}
+
+#[test]
+fn expiry_sweep_charges_for_entries_it_reads() {
+    use clawchain_primitives::BudgetedHook;
+
+    new_test_ext_with_members(vec![1, 2, 3]).execute_with(|| {
+        assert_ok!(EmergencyPause::propose_pause(origin(1), pid(b"pallet-y")));
+        assert_ok!(EmergencyPause::vote(origin(2), 0));
+        assert_ok!(EmergencyPause::vote(origin(3), 0));
+        assert_ok!(EmergencyPause::propose_pause(origin(1), pid(b"pallet-z")));
+
+        // Nothing is due, but every entry read is still paid for.
+        let read = PauseVotes::<Test>::iter().count() + PausedPallets::<Test>::iter().count();
+        assert!(read >= 2);
+        let db = <Test as frame_system::Config>::DbWeight::get();
+        let usage =
+            EmergencyPause::run(System::block_number(), frame_support::weights::Weight::MAX);
+        assert!(!usage.deferred);
+        assert_eq!(usage.used, db.reads(1 + read as u64));
+
+        // A budget that cannot cover the scan defers it.
+        let usage = EmergencyPause::run(System::block_number(), db.reads(2));
+        assert!(usage.deferred);
+    });
+}
+
 #[test]
 fn council_vote_pause_does_not_expire_automatically() {
     // Indefinite (expires_at = 0) pauses must be unpaused via proposal.
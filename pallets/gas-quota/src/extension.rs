@@ -0,0 +1,124 @@
+//! Transaction extension that enforces the gas quota on signed transactions.
+
+use crate::{CallClass, Config, FeeSponsor, Pallet};
+use codec::{Decode, DecodeWithMemTracking, Encode};
+use core::marker::PhantomData;
+use frame_support::pallet_prelude::{Get, TransactionSource, Weight};
+use scale_info::TypeInfo;
+use sp_runtime::{
+    traits::{
+        DispatchInfoOf, Dispatchable, Implication, TransactionExtension, ValidateResult, Zero,
+    },
+    transaction_validity::{InvalidTransaction, TransactionValidityError, ValidTransaction},
+};
+
+/// Consumes the sender's daily free quota per signed transaction, as many
+/// units as `Config::QuotaWeigher` assigns the call scaled by its class
+/// weight, counts the call under its class, and charges the
+/// stake-discounted `BaseFeePerTx` per unit once the quota is used up.
+/// Transactions are prioritised by the quota units they consume.
+///
+/// Takes the place of `ChargeTransactionPayment` in the runtime's transaction
+/// extensions. Unsigned transactions pass through untouched.
+#[derive(Encode, Decode, DecodeWithMemTracking, Clone, Eq, PartialEq, TypeInfo)]
+#[scale_info(skip_type_params(T))]
+pub struct ChargeGasQuota<T: Config>(PhantomData<T>);
+
+impl<T: Config> ChargeGasQuota<T> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: Config> Default for ChargeGasQuota<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Config> core::fmt::Debug for ChargeGasQuota<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "ChargeGasQuota")
+    }
+}
+
+impl<T: Config + Send + Sync> TransactionExtension<T::RuntimeCall> for ChargeGasQuota<T> {
+    const IDENTIFIER: &'static str = "ChargeGasQuota";
+    type Implicit = ();
+    /// The signer, the call's quota units and its class, if the transaction
+    /// is signed.
+    type Val = Option<(T::AccountId, u32, Option<CallClass>)>;
+    type Pre = ();
+
+    fn weight(&self, _call: &T::RuntimeCall) -> Weight {
+        // Worst case: a new staking era and quota epoch for the sender, a
+        // delegation that falls short and a fee paid through `FeeSponsor`.
+        let db = T::DbWeight::get();
+        let classes = CallClass::ALL.len() as u64;
+        // Class weight and the sender's class counter.
+        let class = db.reads_writes(2, 1);
+        // Quota record, reputation, suspension and delegated totals; the
+        // class counters are cleared on an epoch reset.
+        let quota = db.reads_writes(4, classes.saturating_add(1));
+        // Current era, stake snapshot and staking ledger of the sender and of
+        // its delegator; the sender's snapshot is recorded.
+        let stake = db.reads_writes(8, 1);
+        // `DelegatorOf`, `Delegations`, and the delegator's reputation and
+        // delegated totals.
+        let delegation = db.reads_writes(4, 1);
+        // `Sponsorships`, the payer and fee pot accounts, `TotalFeesCollected`
+        // and `EpochFeeStats`.
+        let fee = db.reads_writes(5, 5);
+        class
+            .saturating_add(quota)
+            .saturating_add(stake)
+            .saturating_add(delegation)
+            .saturating_add(fee)
+            .saturating_add(T::FeeSponsor::sponsor_weight())
+    }
+
+    fn validate(
+        &self,
+        origin: <T::RuntimeCall as Dispatchable>::RuntimeOrigin,
+        call: &T::RuntimeCall,
+        _info: &DispatchInfoOf<T::RuntimeCall>,
+        _len: usize,
+        _self_implicit: (),
+        _inherited_implication: &impl Implication,
+        _source: TransactionSource,
+    ) -> ValidateResult<Self::Val, T::RuntimeCall> {
+        let Ok(who) = frame_system::ensure_signed(origin.clone()) else {
+            return Ok((ValidTransaction::default(), None, origin));
+        };
+
+        let (units, class) = Pallet::<T>::call_quota(call);
+        if units == 0 {
+            return Ok((ValidTransaction::default(), None, origin));
+        }
+        let fee = Pallet::<T>::pending_fee(&who, units);
+        if !fee.is_zero() && !Pallet::<T>::can_pay_fee(&who, fee) {
+            return Err(InvalidTransaction::Payment.into());
+        }
+
+        let validity = ValidTransaction {
+            priority: units.into(),
+            ..Default::default()
+        };
+        Ok((validity, Some((who, units, class)), origin))
+    }
+
+    fn prepare(
+        self,
+        val: Self::Val,
+        _origin: &<T::RuntimeCall as Dispatchable>::RuntimeOrigin,
+        _call: &T::RuntimeCall,
+        _info: &DispatchInfoOf<T::RuntimeCall>,
+        _len: usize,
+    ) -> Result<Self::Pre, TransactionValidityError> {
+        if let Some((who, units, class)) = val {
+            Pallet::<T>::consume_call_quota(&who, units, class)
+                .map_err(|_| InvalidTransaction::Payment)?;
+        }
+        Ok(())
+    }
+}
@@ -15,6 +15,114 @@
 //!
 //! Reputation multipliers: High rep → 1.5×, Verified contributor → 2×
 //!
+//! ## Reputation Tiers
+//!
+//! `AgentQuota::reputation_tier` is derived from the score reported by
+//! `ReputationProvider` every time an agent's quota is evaluated: a score of
+//! at least `VerifiedContributorScore` is tier 2, at least `HighReputationScore`
+//! tier 1, anything lower tier 0. A tier set through `update_reputation_tier`
+//! holds only until the next evaluation.
+//!
+//! ## Quota Epochs
+//!
+//! Quota days are global epochs of `BlocksPerDay` blocks
+//! (`block_number / BlocksPerDay`), so every account's day rolls over at the
+//! same block. An agent's counters are reset lazily on its first call in a new
+//! epoch.
+//!
+//! ## Stake Tracking
+//!
+//! Quotas are computed from a per-era stake snapshot, so they stay fixed for
+//! a whole staking era however the stake moves within it.
+//! `QuotaStakeSnapshot` holds each account's active stake (as reported by
+//! `StakeProvider`) at the start of the era: it is recorded on the account's
+//! first use in the era, or by the first bond, unbond or slash in it, which
+//! the pallet hears about through [`sp_staking::OnStakingUpdate`] when wired
+//! into pallet-staking's `EventListeners`. Changes then take effect in the
+//! next era. Only the last `StakeSnapshotEras` eras are kept; older entries
+//! are pruned in `on_initialize`.
+//!
+//! Stake being unbonded stays locked for the bonding duration, so until it
+//! is withdrawn `UnbondingStakeWeight` of it still counts toward the
+//! snapshot. An agent that unbonds keeps part of its quota for as long as
+//! its funds stay committed instead of losing it all at once.
+//!
+//! `AgentQuota::stake` mirrors the snapshot for the current era and is
+//! refreshed on the first transaction in a new era, on staking updates, or on
+//! demand with `sync_stake`. `QuotaTierChanged` is emitted whenever a refresh
+//! changes the free quota.
+//!
+//! ## Quota Delegation
+//!
+//! A staked account can carve sub-quotas out of its own free quota for worker
+//! agents with `delegate_quota` (up to `MaxDelegations` beneficiaries, one
+//! delegator per beneficiary). Delegated transactions are deducted from the
+//! delegator's free quota up front. A beneficiary draws on its delegation only
+//! once its own free quota is used up, and its delegated usage resets with the
+//! quota epoch. `revoke_delegation` returns the units to the delegator. If the
+//! delegator's stake later drops below what it has delegated, the delegations
+//! it gives are scaled down pro rata to what its current stake still backs.
+//!
+//! ## Rollover
+//!
+//! Agents that opt in with `set_rollover` carry unused free transactions into
+//! the next quota epoch as `AgentQuota::rollover`, up to `MaxRolloverPct` of
+//! their daily allowance. Credits do not compound past one allowance, so a
+//! day's total never exceeds twice the daily free quota. An agent idle for
+//! more than one epoch carries over as if the last full day went unused.
+//!
+//! ## Suspension
+//!
+//! `GovernanceOrigin` can suspend an agent's free-transaction privileges
+//! until a given block with `suspend_quota`, without touching its stake.
+//! While suspended, every quota unit pays the excess fee: the agent's own
+//! quota, rollover credits and delegations are all ignored.
+//! `restore_quota` lifts a suspension early.
+//!
+//! ## Call Classes
+//!
+//! [`QuotaWeigher::call_class`] attributes a call to a [`CallClass`]. The
+//! call's quota units are multiplied by the governance-set weight of its class
+//! (`set_class_weight`, default 1, at most `MaxClassWeight`), and per-account
+//! daily counters in `DailyClassUsage` show which workloads consume quota.
+//! Other callers may attribute usage directly with `consume_quota_for_class`.
+//!
+//! ## Enforcement
+//!
+//! [`ChargeGasQuota`] is the runtime's fee-charging transaction extension:
+//! every signed transaction consumes the quota units `QuotaWeigher` assigns
+//! its call (one by default), scaled by its class weight, and over-quota
+//! transactions pay the excess fee per unit instead of a weight-based fee.
+//! Accounts without funds may have the excess fee covered by a [`FeeSponsor`].
+//!
+//! ## Sponsorships
+//!
+//! A service provider can offer to cover its customers' over-quota fees with
+//! `sponsor_account(beneficiary, max_per_day)`; the offer takes effect once
+//! the beneficiary calls `accept_sponsorship(sponsor)`, which replaces any
+//! sponsor it had. The current sponsor can change its cap without a new
+//! acceptance. Before debiting the sender, the fee charger checks
+//! `Sponsorships`: while the sponsor's spending for the beneficiary in the
+//! current quota epoch stays within `max_per_day`, the fee is withdrawn from
+//! the sponsor and `SponsoredFeePaid` is emitted. Fees past the cap fall back
+//! to the `FeeSponsor` hook, then to the sender. `revoke_sponsorship` ends a
+//! sponsorship.
+//!
+//! [`runtime_api::DryRunApi`] lets clients simulate a call, including the
+//! quota it would consume and the fee it would pay, before submitting it.
+//! [`runtime_api::GasQuotaApi`] (served over RPC as `gasQuota_*`) reports the
+//! free transactions an account has left, the fee for its next transactions,
+//! the block its quota resets at and its usage per call class.
+//!
+//! ## Fee Routing
+//!
+//! Over-quota fees are collected in a pot account derived from `PalletId`.
+//! Once the pot holds at least `FeeSweepThreshold`, the next block's
+//! `on_initialize` moves all but the existential deposit to `TreasuryAccount`,
+//! keeping the pot alive, and emits
+//! `FeesSweptToTreasury`. `EpochFeeStats` records what was collected and
+//! swept in each quota epoch, for explorers.
+//!
 //! ## Rationale
 //!
 //! Pure zero-gas is trivially spammable. This approach gives agents 0-gas UX
@@ -23,24 +131,105 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(deprecated, clippy::let_unit_value)]
 
+pub use extension::ChargeGasQuota;
 pub use pallet::*;
 
+pub mod extension;
+pub mod migrations;
+pub mod runtime_api;
+
 #[cfg(test)]
 mod tests;
 
+use frame_support::weights::Weight;
+use sp_runtime::traits::Saturating;
+
+/// Pays over-quota fees on behalf of an account, e.g. from an onboarding
+/// allowance.
+pub trait FeeSponsor<AccountId, Balance> {
+    /// Account that would pay `fee` for `who`, if any. Must not modify state.
+    fn sponsor_of(who: &AccountId, fee: Balance) -> Option<AccountId>;
+
+    /// Called after the sponsor returned by `sponsor_of` paid `fee` for `who`.
+    fn on_sponsored(who: &AccountId, fee: Balance);
+
+    /// Upper bound on the weight of one [`Self::sponsor_of`] and
+    /// [`Self::on_sponsored`] pair.
+    fn sponsor_weight() -> Weight;
+}
+
+/// Source of an account's active stake.
+pub trait StakeProvider<AccountId, Balance> {
+    fn active_stake(who: &AccountId) -> Balance;
+
+    /// Stake being unbonded: no longer active, but still locked until it is
+    /// withdrawn.
+    fn unbonding_stake(who: &AccountId) -> Balance;
+
+    /// Current staking era. Quotas use the stake snapshotted for it.
+    fn current_era() -> u32 {
+        0
+    }
+}
+
+impl<AccountId, Balance: Default> StakeProvider<AccountId, Balance> for () {
+    fn active_stake(_who: &AccountId) -> Balance {
+        Balance::default()
+    }
+
+    fn unbonding_stake(_who: &AccountId) -> Balance {
+        Balance::default()
+    }
+}
+
+/// Quota units a call consumes from its sender's daily free allowance.
+pub trait QuotaWeigher<Call> {
+    /// Units charged for `call` before its class weight. Zero makes the call
+    /// quota-free.
+    fn quota_units(call: &Call) -> u32;
+
+    /// Class `call` is counted under; its class weight scales the units.
+    /// Unclassified calls are charged their units as-is.
+    fn call_class(_call: &Call) -> Option<CallClass> {
+        None
+    }
+}
+
+/// Every call costs one unit.
+impl<Call> QuotaWeigher<Call> for () {
+    fn quota_units(_call: &Call) -> u32 {
+        1
+    }
+}
+
+impl<AccountId, Balance> FeeSponsor<AccountId, Balance> for () {
+    fn sponsor_of(_who: &AccountId, _fee: Balance) -> Option<AccountId> {
+        None
+    }
+
+    fn on_sponsored(_who: &AccountId, _fee: Balance) {}
+
+    fn sponsor_weight() -> Weight {
+        Weight::zero()
+    }
+}
+
 #[frame_support::pallet]
 pub mod pallet {
+    use super::{runtime_api, FeeSponsor, QuotaWeigher, StakeProvider};
     use frame_support::{
         pallet_prelude::*,
-        traits::{Currency, Get, ReservableCurrency},
+        traits::{Currency, ExistenceRequirement, Get, ReservableCurrency, WithdrawReasons},
+        PalletId,
     };
     use frame_system::pallet_prelude::*;
+    use pallet_reputation::ReputationManager;
     use sp_runtime::{
-        traits::{Saturating, Zero},
-        Perbill,
+        traits::{AccountIdConversion, CheckedSub, One, Saturating, Zero},
+        Perbill, Percent,
     };
 
-    type BalanceOf<T> =
+    pub type BalanceOf<T> =
         <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
     // =========================================================================
@@ -81,23 +270,161 @@ pub mod pallet {
         /// Higher stake → lower excess fee.
         #[pallet::constant]
         type FeeDiscountPerKStake: Get<Perbill>;
+
+        /// Pays over-quota fees for accounts that cannot pay themselves.
+        type FeeSponsor: FeeSponsor<Self::AccountId, BalanceOf<Self>>;
+
+        /// Active stake backing each account's quota.
+        type StakeProvider: StakeProvider<Self::AccountId, BalanceOf<Self>>;
+
+        /// Source of the reputation scores quota tiers are derived from.
+        type ReputationProvider: ReputationManager<Self::AccountId, BalanceOf<Self>>;
+
+        /// Minimum reputation score (basis points) for the 1.5× high-reputation tier.
+        #[pallet::constant]
+        type HighReputationScore: Get<u32>;
+
+        /// Minimum reputation score (basis points) for the 2× verified-contributor tier.
+        #[pallet::constant]
+        type VerifiedContributorScore: Get<u32>;
+
+        /// Maximum number of beneficiaries one account can delegate quota to.
+        #[pallet::constant]
+        type MaxDelegations: Get<u32>;
+
+        /// Share of the daily free quota that opted-in agents may carry over
+        /// to the next epoch.
+        #[pallet::constant]
+        type MaxRolloverPct: Get<Percent>;
+
+        /// Largest weight `set_class_weight` accepts for a call class.
+        #[pallet::constant]
+        type MaxClassWeight: Get<u32>;
+
+        /// Quota units each signed call consumes in [`ChargeGasQuota`].
+        type QuotaWeigher: QuotaWeigher<<Self as frame_system::Config>::RuntimeCall>;
+
+        /// Derives the pot account over-quota fees are collected in.
+        #[pallet::constant]
+        type PalletId: Get<PalletId>;
+
+        /// Account collected fees are swept to.
+        type TreasuryAccount: Get<Self::AccountId>;
+
+        /// Pot balance at which collected fees are swept to the treasury.
+        #[pallet::constant]
+        type FeeSweepThreshold: Get<BalanceOf<Self>>;
+
+        /// Origin allowed to suspend and restore agents' free quota.
+        type GovernanceOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Staking eras of stake snapshots kept, including the current one.
+        #[pallet::constant]
+        type StakeSnapshotEras: Get<u32>;
+
+        /// Most stake snapshot entries pruned per block.
+        #[pallet::constant]
+        type MaxSnapshotPrunePerBlock: Get<u32>;
+
+        /// Share of unbonding stake (still locked, not yet withdrawn) that
+        /// counts toward quota alongside active stake.
+        #[pallet::constant]
+        type UnbondingStakeWeight: Get<Perbill>;
     }
 
     // =========================================================================
     // Storage
     // =========================================================================
 
+    /// Workload category a quota-consuming call is attributed to.
+    #[derive(
+        Clone,
+        Copy,
+        Encode,
+        Decode,
+        codec::DecodeWithMemTracking,
+        MaxEncodedLen,
+        TypeInfo,
+        Debug,
+        PartialEq,
+        Eq,
+    )]
+    pub enum CallClass {
+        /// Service and task market calls.
+        Market,
+        /// Messaging calls.
+        Messaging,
+        /// Governance calls.
+        Governance,
+        /// Identity and registry calls.
+        Identity,
+    }
+
+    impl CallClass {
+        /// Every class, in a stable order.
+        pub const ALL: [CallClass; 4] = [
+            CallClass::Market,
+            CallClass::Messaging,
+            CallClass::Governance,
+            CallClass::Identity,
+        ];
+    }
+
     /// Per-agent quota tracking.
     #[derive(Clone, Encode, Decode, MaxEncodedLen, TypeInfo, Debug, PartialEq)]
     pub struct AgentQuota<Balance, BlockNumber> {
         /// Current staked balance (snapshot, updated on stake changes).
         pub stake: Balance,
-        /// Number of free TXs used in the current day.
+        /// Number of free TXs used in `epoch`.
         pub daily_used: u32,
-        /// Block number when the current day started (for reset tracking).
-        pub day_start_block: BlockNumber,
+        /// Quota epoch (`block_number / BlocksPerDay`) `daily_used` belongs to.
+        pub epoch: BlockNumber,
         /// Reputation tier: 0=normal, 1=high, 2=verified_contributor.
         pub reputation_tier: u8,
+        /// Whether unused free transactions carry over to the next epoch.
+        pub rollover_enabled: bool,
+        /// Free transactions carried into `epoch` on top of the daily quota.
+        pub rollover: u32,
+    }
+
+    /// Daily quota delegated by one account to another.
+    #[derive(Clone, Encode, Decode, MaxEncodedLen, TypeInfo, Debug, PartialEq)]
+    pub struct QuotaDelegation<BlockNumber> {
+        /// Free transactions per day the beneficiary may draw.
+        pub tx_per_day: u32,
+        /// Delegated transactions used in `epoch`.
+        pub used: u32,
+        /// Quota epoch `used` belongs to.
+        pub epoch: BlockNumber,
+    }
+
+    /// An account covering another's over-quota fees, up to a daily budget.
+    #[derive(Clone, Encode, Decode, MaxEncodedLen, TypeInfo, Debug, PartialEq)]
+    pub struct Sponsorship<AccountId, Balance, BlockNumber> {
+        /// Account the fees are withdrawn from.
+        pub sponsor: AccountId,
+        /// Most the sponsor pays for the beneficiary per quota epoch.
+        pub max_per_day: Balance,
+        /// Fees paid in `epoch`.
+        pub spent: Balance,
+        /// Quota epoch `spent` belongs to.
+        pub epoch: BlockNumber,
+    }
+
+    /// Over-quota fees collected and swept to the treasury in one quota epoch.
+    #[derive(Clone, Encode, Decode, MaxEncodedLen, TypeInfo, Debug, PartialEq, Default)]
+    pub struct FeeStats<Balance> {
+        pub collected: Balance,
+        pub swept: Balance,
+    }
+
+    /// Everything an account has delegated away.
+    #[derive(Clone, Encode, Decode, MaxEncodedLen, TypeInfo, Debug, PartialEq, Default)]
+    pub struct DelegationTotals {
+        /// Number of beneficiaries.
+        pub count: u32,
+        /// Sum of `tx_per_day` over all beneficiaries.
+        pub tx_per_day: u32,
     }
 
     #[pallet::storage]
@@ -115,6 +442,100 @@ pub mod pallet {
     #[pallet::getter(fn total_fees_collected)]
     pub type TotalFeesCollected<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
 
+    /// Total excess fees swept to the treasury.
+    #[pallet::storage]
+    pub type TotalFeesSwept<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
+    /// Fees collected and swept per quota epoch.
+    #[pallet::storage]
+    pub type EpochFeeStats<T: Config> =
+        StorageMap<_, Twox64Concat, BlockNumberFor<T>, FeeStats<BalanceOf<T>>, ValueQuery>;
+
+    /// Quota delegations, keyed by (delegator, beneficiary).
+    #[pallet::storage]
+    pub type Delegations<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Blake2_128Concat,
+        T::AccountId,
+        QuotaDelegation<BlockNumberFor<T>>,
+        OptionQuery,
+    >;
+
+    /// The account delegating quota to each beneficiary.
+    #[pallet::storage]
+    pub type DelegatorOf<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, T::AccountId, OptionQuery>;
+
+    /// Delegation totals per delegator.
+    #[pallet::storage]
+    pub type DelegatedTotals<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, DelegationTotals, OptionQuery>;
+
+    /// Registered sponsor of each beneficiary's over-quota fees.
+    #[pallet::storage]
+    pub type Sponsorships<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Sponsorship<T::AccountId, BalanceOf<T>, BlockNumberFor<T>>,
+        OptionQuery,
+    >;
+
+    /// Sponsorships offered and not yet accepted, by (beneficiary, sponsor),
+    /// with the offered daily cap.
+    #[pallet::storage]
+    pub type SponsorshipOffers<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Blake2_128Concat,
+        T::AccountId,
+        BalanceOf<T>,
+        OptionQuery,
+    >;
+
+    /// Quota units consumed per call of each class. Unset classes cost 1.
+    #[pallet::storage]
+    pub type ClassWeights<T: Config> = StorageMap<_, Twox64Concat, CallClass, u32, OptionQuery>;
+
+    /// Calls per class made by an agent in its current quota epoch.
+    /// Cleared when the agent's daily counter resets.
+    #[pallet::storage]
+    pub type DailyClassUsage<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Twox64Concat,
+        CallClass,
+        u32,
+        ValueQuery,
+    >;
+
+    /// Agents whose free quota is suspended, with the first block it applies
+    /// again.
+    #[pallet::storage]
+    pub type QuotaSuspensions<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, BlockNumberFor<T>, OptionQuery>;
+
+    /// Active stake of each account as of the start of a staking era,
+    /// recorded on its first use or first stake change in the era.
+    #[pallet::storage]
+    pub type QuotaStakeSnapshot<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        u32,
+        Blake2_128Concat,
+        T::AccountId,
+        BalanceOf<T>,
+        OptionQuery,
+    >;
+
+    /// Oldest staking era that may still have entries in `QuotaStakeSnapshot`.
+    #[pallet::storage]
+    pub type OldestStakeSnapshotEra<T: Config> = StorageValue<_, u32, ValueQuery>;
+
     // =========================================================================
     // Events
     // =========================================================================
@@ -141,6 +562,69 @@ pub mod pallet {
         },
         /// Reputation tier updated. [agent, tier]
         ReputationTierUpdated { agent: T::AccountId, tier: u8 },
+        /// Quota units charged per call of a class changed. [class, weight]
+        ClassWeightSet { class: CallClass, weight: u32 },
+        /// A stake change moved an agent to a different free quota. [agent, free_quota]
+        QuotaTierChanged {
+            agent: T::AccountId,
+            free_quota: u32,
+        },
+        /// Quota was delegated. [delegator, beneficiary, tx_per_day]
+        QuotaDelegated {
+            delegator: T::AccountId,
+            beneficiary: T::AccountId,
+            tx_per_day: u32,
+        },
+        /// A delegation was revoked. [delegator, beneficiary]
+        DelegationRevoked {
+            delegator: T::AccountId,
+            beneficiary: T::AccountId,
+        },
+        /// A beneficiary used delegated quota. [delegator, beneficiary, remaining]
+        DelegatedQuotaUsed {
+            delegator: T::AccountId,
+            beneficiary: T::AccountId,
+            remaining: u32,
+        },
+        /// An agent opted in or out of quota rollover. [agent, enabled]
+        RolloverSet { agent: T::AccountId, enabled: bool },
+        /// Collected fees were moved to the treasury. [amount, epoch]
+        FeesSweptToTreasury {
+            amount: BalanceOf<T>,
+            epoch: BlockNumberFor<T>,
+        },
+        /// An agent's free quota was suspended. [agent, until]
+        QuotaSuspended {
+            agent: T::AccountId,
+            until: BlockNumberFor<T>,
+        },
+        /// An agent's suspension was lifted early. [agent]
+        QuotaRestored { agent: T::AccountId },
+        /// An account offered to pay a beneficiary's over-quota fees.
+        /// [sponsor, beneficiary, max_per_day]
+        SponsorshipOffered {
+            sponsor: T::AccountId,
+            beneficiary: T::AccountId,
+            max_per_day: BalanceOf<T>,
+        },
+        /// A beneficiary accepted a sponsorship, or its sponsor changed the
+        /// cap. [sponsor, beneficiary, max_per_day]
+        SponsorshipSet {
+            sponsor: T::AccountId,
+            beneficiary: T::AccountId,
+            max_per_day: BalanceOf<T>,
+        },
+        /// A sponsorship or sponsorship offer was revoked. [sponsor, beneficiary]
+        SponsorshipRevoked {
+            sponsor: T::AccountId,
+            beneficiary: T::AccountId,
+        },
+        /// A sponsor paid a beneficiary's over-quota fee. [sponsor, beneficiary, amount]
+        SponsoredFeePaid {
+            sponsor: T::AccountId,
+            beneficiary: T::AccountId,
+            amount: BalanceOf<T>,
+        },
     }
 
     // =========================================================================
@@ -153,17 +637,50 @@ pub mod pallet {
         InsufficientBalance,
         /// Quota record not found (not yet initialized).
         QuotaNotInitialized,
+        /// An account cannot delegate quota to itself.
+        SelfDelegation,
+        /// Delegations must grant at least one transaction per day.
+        ZeroDelegation,
+        /// The beneficiary already receives quota from another account.
+        AlreadyDelegated,
+        /// The delegator is at `MaxDelegations` beneficiaries.
+        TooManyDelegations,
+        /// The delegator's free quota does not cover its delegations.
+        InsufficientQuota,
+        /// No delegation from the caller to this beneficiary.
+        DelegationNotFound,
+        /// A suspension must end after the current block.
+        SuspensionInPast,
+        /// The agent's quota is not suspended.
+        NotSuspended,
+        /// An account cannot sponsor itself.
+        SelfSponsorship,
+        /// Sponsorships must cover a non-zero daily amount.
+        ZeroSponsorship,
+        /// No sponsorship or offer between these accounts.
+        SponsorshipNotFound,
+        /// Class weights must be between 1 and `MaxClassWeight`.
+        InvalidClassWeight,
     }
 
     // =========================================================================
     // Pallet Struct & Hooks
     // =========================================================================
 
+    /// v1: `AgentQuota::day_start_block` replaced by a global `epoch`.
+    /// v2: `AgentQuota::rollover_enabled` and `AgentQuota::rollover` added.
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
+
     #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
     #[pallet::hooks]
-    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(_n: BlockNumberFor<T>) -> Weight {
+            Self::sweep_fees_to_treasury().saturating_add(Self::prune_stake_snapshots())
+        }
+    }
 
     // =========================================================================
     // Extrinsics
@@ -181,56 +698,285 @@ pub mod pallet {
             Ok(())
         }
 
-        /// Update stake snapshot for an agent (called by staking hooks).
-        /// Only callable by the agent themselves or root.
-        #[pallet::call_index(1)]
+        /// Override an agent's reputation tier. The tier is recomputed from
+        /// `ReputationProvider` the next time the agent's quota is evaluated.
+        #[pallet::call_index(2)]
         #[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
-        pub fn update_stake(
+        pub fn update_reputation_tier(
             origin: OriginFor<T>,
             agent: T::AccountId,
-            new_stake: BalanceOf<T>,
+            tier: u8,
         ) -> DispatchResult {
-            // Allow agent to update own stake, or root for any
-            let caller = ensure_signed(origin)?;
-            ensure!(caller == agent, frame_support::error::BadOrigin);
+            ensure_root(origin)?;
+            ensure!(tier <= 2, Error::<T>::QuotaNotInitialized); // reuse error for now
 
             AgentQuotas::<T>::mutate(&agent, |maybe_quota| {
                 if let Some(quota) = maybe_quota {
-                    quota.stake = new_stake;
+                    quota.reputation_tier = tier;
                 }
             });
 
-            Self::deposit_event(Event::StakeUpdated {
+            Self::deposit_event(Event::ReputationTierUpdated { agent, tier });
+            Ok(())
+        }
+
+        /// Set how many quota units one call of `class` consumes, from 1 to
+        /// `MaxClassWeight`.
+        #[pallet::call_index(3)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(0, 1))]
+        pub fn set_class_weight(
+            origin: OriginFor<T>,
+            class: CallClass,
+            weight: u32,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            ensure!(
+                (1..=T::MaxClassWeight::get()).contains(&weight),
+                Error::<T>::InvalidClassWeight
+            );
+            ClassWeights::<T>::insert(class, weight);
+            Self::deposit_event(Event::ClassWeightSet { class, weight });
+            Ok(())
+        }
+
+        /// Refresh `agent`'s stake snapshot from the staking system.
+        #[pallet::call_index(4)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(2, 1))]
+        pub fn sync_stake(origin: OriginFor<T>, agent: T::AccountId) -> DispatchResult {
+            ensure_signed(origin)?;
+            ensure!(
+                AgentQuotas::<T>::contains_key(&agent),
+                Error::<T>::QuotaNotInitialized
+            );
+            Self::sync_stake_of(&agent);
+            Ok(())
+        }
+
+        /// Delegate `tx_per_day` of the caller's free daily quota to
+        /// `beneficiary`, replacing any existing delegation between the two.
+        #[pallet::call_index(5)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(5, 4))]
+        pub fn delegate_quota(
+            origin: OriginFor<T>,
+            beneficiary: T::AccountId,
+            tx_per_day: u32,
+        ) -> DispatchResult {
+            let delegator = ensure_signed(origin)?;
+            ensure!(delegator != beneficiary, Error::<T>::SelfDelegation);
+            ensure!(tx_per_day > 0, Error::<T>::ZeroDelegation);
+            if let Some(current) = DelegatorOf::<T>::get(&beneficiary) {
+                ensure!(current == delegator, Error::<T>::AlreadyDelegated);
+            }
+
+            let previous = Delegations::<T>::get(&delegator, &beneficiary);
+            let mut totals = DelegatedTotals::<T>::get(&delegator).unwrap_or_default();
+            if previous.is_none() {
+                ensure!(
+                    totals.count < T::MaxDelegations::get(),
+                    Error::<T>::TooManyDelegations
+                );
+                totals.count = totals.count.saturating_add(1);
+            }
+            totals.tx_per_day = totals
+                .tx_per_day
+                .saturating_sub(previous.as_ref().map_or(0, |d| d.tx_per_day))
+                .saturating_add(tx_per_day);
+
+            Self::ensure_quota_initialized(&delegator);
+            Self::sync_stake_of(&delegator);
+            let quota = AgentQuotas::<T>::get(&delegator).ok_or(Error::<T>::QuotaNotInitialized)?;
+            let free_quota =
+                Self::calculate_free_quota(quota.stake, Self::reputation_tier_of(&delegator));
+            ensure!(
+                free_quota == u32::MAX || totals.tx_per_day <= free_quota,
+                Error::<T>::InsufficientQuota
+            );
+
+            let epoch = Self::current_epoch();
+            let used = previous.filter(|d| d.epoch == epoch).map_or(0, |d| d.used);
+            Delegations::<T>::insert(
+                &delegator,
+                &beneficiary,
+                QuotaDelegation {
+                    tx_per_day,
+                    used,
+                    epoch,
+                },
+            );
+            DelegatedTotals::<T>::insert(&delegator, totals);
+            DelegatorOf::<T>::insert(&beneficiary, &delegator);
+
+            Self::deposit_event(Event::QuotaDelegated {
+                delegator,
+                beneficiary,
+                tx_per_day,
+            });
+            Ok(())
+        }
+
+        /// Revoke the caller's delegation to `beneficiary`.
+        #[pallet::call_index(6)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(2, 3))]
+        pub fn revoke_delegation(
+            origin: OriginFor<T>,
+            beneficiary: T::AccountId,
+        ) -> DispatchResult {
+            let delegator = ensure_signed(origin)?;
+            ensure!(
+                Self::remove_delegation(&delegator, &beneficiary),
+                Error::<T>::DelegationNotFound
+            );
+            Ok(())
+        }
+
+        /// Opt in or out of carrying unused free transactions over to the
+        /// next epoch. Opting out forfeits credits already carried over.
+        #[pallet::call_index(7)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(2, 1))]
+        pub fn set_rollover(origin: OriginFor<T>, enabled: bool) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            Self::ensure_quota_initialized(&who);
+            AgentQuotas::<T>::try_mutate(&who, |maybe_quota| -> DispatchResult {
+                let quota = maybe_quota
+                    .as_mut()
+                    .ok_or(Error::<T>::QuotaNotInitialized)?;
+                quota.rollover_enabled = enabled;
+                if !enabled {
+                    quota.rollover = 0;
+                }
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::RolloverSet {
+                agent: who,
+                enabled,
+            });
+            Ok(())
+        }
+
+        /// Suspend `agent`'s free quota until `until_block`, leaving its
+        /// stake untouched. Replaces any existing suspension.
+        #[pallet::call_index(8)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(0, 1))]
+        pub fn suspend_quota(
+            origin: OriginFor<T>,
+            agent: T::AccountId,
+            until_block: BlockNumberFor<T>,
+        ) -> DispatchResult {
+            T::GovernanceOrigin::ensure_origin(origin)?;
+            ensure!(
+                until_block > <frame_system::Pallet<T>>::block_number(),
+                Error::<T>::SuspensionInPast
+            );
+            QuotaSuspensions::<T>::insert(&agent, until_block);
+            Self::deposit_event(Event::QuotaSuspended {
                 agent,
-                stake: new_stake,
+                until: until_block,
             });
             Ok(())
         }
 
-        /// Update reputation tier for an agent (called by reputation pallet).
-        #[pallet::call_index(2)]
+        /// Lift `agent`'s quota suspension before it expires.
+        #[pallet::call_index(9)]
         #[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
-        pub fn update_reputation_tier(
+        pub fn restore_quota(origin: OriginFor<T>, agent: T::AccountId) -> DispatchResult {
+            T::GovernanceOrigin::ensure_origin(origin)?;
+            ensure!(Self::is_quota_suspended(&agent), Error::<T>::NotSuspended);
+            QuotaSuspensions::<T>::remove(&agent);
+            Self::deposit_event(Event::QuotaRestored { agent });
+            Ok(())
+        }
+
+        /// Offer to pay `beneficiary`'s over-quota fees, up to `max_per_day`
+        /// per quota epoch, once it accepts. From the current sponsor this
+        /// updates the cap directly; fees already paid today still count
+        /// against it.
+        #[pallet::call_index(10)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+        pub fn sponsor_account(
             origin: OriginFor<T>,
-            agent: T::AccountId,
-            tier: u8,
+            beneficiary: T::AccountId,
+            max_per_day: BalanceOf<T>,
         ) -> DispatchResult {
-            ensure_root(origin)?;
-            ensure!(tier <= 2, Error::<T>::QuotaNotInitialized); // reuse error for now
+            let sponsor = ensure_signed(origin)?;
+            ensure!(sponsor != beneficiary, Error::<T>::SelfSponsorship);
+            ensure!(!max_per_day.is_zero(), Error::<T>::ZeroSponsorship);
 
-            AgentQuotas::<T>::mutate(&agent, |maybe_quota| {
-                if let Some(quota) = maybe_quota {
-                    quota.reputation_tier = tier;
+            let updated = Sponsorships::<T>::mutate(&beneficiary, |maybe| match maybe {
+                Some(s) if s.sponsor == sponsor => {
+                    s.max_per_day = max_per_day;
+                    true
                 }
+                _ => false,
             });
+            if updated {
+                Self::deposit_event(Event::SponsorshipSet {
+                    sponsor,
+                    beneficiary,
+                    max_per_day,
+                });
+            } else {
+                SponsorshipOffers::<T>::insert(&beneficiary, &sponsor, max_per_day);
+                Self::deposit_event(Event::SponsorshipOffered {
+                    sponsor,
+                    beneficiary,
+                    max_per_day,
+                });
+            }
+            Ok(())
+        }
 
-            Self::deposit_event(Event::ReputationTierUpdated { agent, tier });
+        /// Withdraw an offer to `beneficiary`, or stop paying its over-quota
+        /// fees.
+        #[pallet::call_index(11)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(2, 1))]
+        pub fn revoke_sponsorship(
+            origin: OriginFor<T>,
+            beneficiary: T::AccountId,
+        ) -> DispatchResult {
+            let sponsor = ensure_signed(origin)?;
+            if Sponsorships::<T>::get(&beneficiary).is_some_and(|s| s.sponsor == sponsor) {
+                Sponsorships::<T>::remove(&beneficiary);
+            } else {
+                SponsorshipOffers::<T>::take(&beneficiary, &sponsor)
+                    .ok_or(Error::<T>::SponsorshipNotFound)?;
+            }
+            Self::deposit_event(Event::SponsorshipRevoked {
+                sponsor,
+                beneficiary,
+            });
+            Ok(())
+        }
+
+        /// Accept `sponsor`'s offer to pay the caller's over-quota fees,
+        /// replacing any current sponsor.
+        #[pallet::call_index(12)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(1, 2))]
+        pub fn accept_sponsorship(origin: OriginFor<T>, sponsor: T::AccountId) -> DispatchResult {
+            let beneficiary = ensure_signed(origin)?;
+            let max_per_day = SponsorshipOffers::<T>::take(&beneficiary, &sponsor)
+                .ok_or(Error::<T>::SponsorshipNotFound)?;
+
+            Sponsorships::<T>::insert(
+                &beneficiary,
+                Sponsorship {
+                    sponsor: sponsor.clone(),
+                    max_per_day,
+                    spent: Zero::zero(),
+                    epoch: Self::current_epoch(),
+                },
+            );
+            Self::deposit_event(Event::SponsorshipSet {
+                sponsor,
+                beneficiary,
+                max_per_day,
+            });
             Ok(())
         }
     }
 
     // =========================================================================
-    // Public API (called by SignedExtension / other pallets)
+    // Public API (called by ChargeGasQuota / other pallets)
     // =========================================================================
 
     impl<T: Config> Pallet<T> {
@@ -263,33 +1009,326 @@ pub mod pallet {
         /// Returns Ok(()) if within quota (free TX), or charges a fee if over quota.
         /// Returns Err if fee payment fails.
         pub fn consume_quota(who: &T::AccountId) -> DispatchResult {
-            let current_block = <frame_system::Pallet<T>>::block_number();
+            Self::consume_quota_units(who, 1)
+        }
+
+        /// Consume quota for a call of `class`, charging its class weight in
+        /// units and bumping the agent's daily counter for that class.
+        pub fn consume_quota_for_class(who: &T::AccountId, class: CallClass) -> DispatchResult {
+            Self::consume_call_quota(who, Self::class_weight(class), Some(class))
+        }
+
+        /// Quota units `call` consumes, already scaled by its class weight,
+        /// and the class it is counted under.
+        pub fn call_quota(
+            call: &<T as frame_system::Config>::RuntimeCall,
+        ) -> (u32, Option<CallClass>) {
+            let units = T::QuotaWeigher::quota_units(call);
+            let class = T::QuotaWeigher::call_class(call);
+            let units = class.map_or(units, |class| {
+                units.saturating_mul(Self::class_weight(class))
+            });
+            (units, class)
+        }
+
+        /// Consume `units` already weighted by [`Self::call_quota`] and count
+        /// the call under `class`, if any.
+        pub fn consume_call_quota(
+            who: &T::AccountId,
+            units: u32,
+            class: Option<CallClass>,
+        ) -> DispatchResult {
+            Self::consume_quota_units(who, units)?;
+            if let Some(class) = class {
+                DailyClassUsage::<T>::mutate(who, class, |count| *count = count.saturating_add(1));
+            }
+            Ok(())
+        }
+
+        /// Quota units charged per call of `class`.
+        pub fn class_weight(class: CallClass) -> u32 {
+            ClassWeights::<T>::get(class).unwrap_or(1)
+        }
+
+        /// `who`'s stake as snapshotted for the current staking era, or its
+        /// active stake if the era has no snapshot of it yet. Does not modify
+        /// state.
+        pub fn era_stake(who: &T::AccountId) -> BalanceOf<T> {
+            QuotaStakeSnapshot::<T>::get(T::StakeProvider::current_era(), who)
+                .unwrap_or_else(|| Self::quota_stake(who))
+        }
+
+        /// Stake backing `who`'s quota right now: its active stake plus
+        /// `UnbondingStakeWeight` of what it is still unbonding.
+        pub fn quota_stake(who: &T::AccountId) -> BalanceOf<T> {
+            Self::quota_stake_of(
+                T::StakeProvider::active_stake(who),
+                T::StakeProvider::unbonding_stake(who),
+            )
+        }
+
+        pub(crate) fn quota_stake_of(
+            active: BalanceOf<T>,
+            unbonding: BalanceOf<T>,
+        ) -> BalanceOf<T> {
+            active.saturating_add(T::UnbondingStakeWeight::get().mul_floor(unbonding))
+        }
+
+        /// [`Self::era_stake`], recording the snapshot if the era has none.
+        fn snapshot_stake(who: &T::AccountId) -> BalanceOf<T> {
+            let era = T::StakeProvider::current_era();
+            QuotaStakeSnapshot::<T>::get(era, who).unwrap_or_else(|| {
+                let stake = Self::quota_stake(who);
+                QuotaStakeSnapshot::<T>::insert(era, who, stake);
+                stake
+            })
+        }
+
+        /// Drop snapshot entries of eras older than `StakeSnapshotEras`, at
+        /// most `MaxSnapshotPrunePerBlock` per block.
+        fn prune_stake_snapshots() -> Weight {
+            let oldest = OldestStakeSnapshotEra::<T>::get();
+            if oldest.saturating_add(T::StakeSnapshotEras::get()) > T::StakeProvider::current_era()
+            {
+                return T::DbWeight::get().reads(2);
+            }
+            let result = QuotaStakeSnapshot::<T>::clear_prefix(
+                oldest,
+                T::MaxSnapshotPrunePerBlock::get(),
+                None,
+            );
+            if result.maybe_cursor.is_none() {
+                OldestStakeSnapshotEra::<T>::put(oldest.saturating_add(1));
+            }
+            T::DbWeight::get().reads_writes(
+                2u64.saturating_add(result.loops.into()),
+                1u64.saturating_add(result.backend.into()),
+            )
+        }
+
+        /// Refresh `who`'s quota record to its stake snapshot for the current
+        /// staking era. Accounts without a quota record are skipped; they read
+        /// their stake on first use.
+        pub fn sync_stake_of(who: &T::AccountId) {
+            let Some(mut quota) = AgentQuotas::<T>::get(who) else {
+                return;
+            };
+            let stake = Self::snapshot_stake(who);
+            if quota.stake == stake {
+                return;
+            }
+
+            let old_quota = Self::calculate_free_quota(quota.stake, quota.reputation_tier);
+            quota.stake = stake;
+            let new_quota = Self::calculate_free_quota(stake, quota.reputation_tier);
+            AgentQuotas::<T>::insert(who, quota);
+
+            Self::deposit_event(Event::StakeUpdated {
+                agent: who.clone(),
+                stake,
+            });
+            if old_quota != new_quota {
+                Self::deposit_event(Event::QuotaTierChanged {
+                    agent: who.clone(),
+                    free_quota: new_quota,
+                });
+            }
+        }
+
+        /// Free daily quota `who` keeps for itself after its delegations.
+        pub fn own_free_quota(who: &T::AccountId, stake: BalanceOf<T>, reputation_tier: u8) -> u32 {
+            let free_quota = Self::calculate_free_quota(stake, reputation_tier);
+            if free_quota == u32::MAX {
+                return free_quota;
+            }
+            let delegated = DelegatedTotals::<T>::get(who).map_or(0, |totals| totals.tx_per_day);
+            free_quota.saturating_sub(delegated)
+        }
+
+        /// Delegated transactions `who` can still draw in the current epoch.
+        pub fn delegated_remaining(who: &T::AccountId) -> u32 {
+            let Some(delegator) = DelegatorOf::<T>::get(who) else {
+                return 0;
+            };
+            Delegations::<T>::get(&delegator, who).map_or(0, |d| {
+                let allowance = Self::delegation_allowance(&delegator, d.tx_per_day);
+                if d.epoch == Self::current_epoch() {
+                    allowance.saturating_sub(d.used)
+                } else {
+                    allowance
+                }
+            })
+        }
+
+        /// Part of a `tx_per_day` delegation from `delegator` that its current
+        /// stake still backs. Once the delegator's free quota falls below what
+        /// it has delegated, every delegation it gives is scaled down pro rata.
+        pub fn delegation_allowance(delegator: &T::AccountId, tx_per_day: u32) -> u32 {
+            let free_quota = Self::calculate_free_quota(
+                Self::era_stake(delegator),
+                Self::reputation_tier_of(delegator),
+            );
+            let delegated = DelegatedTotals::<T>::get(delegator).map_or(0, |t| t.tx_per_day);
+            if free_quota == u32::MAX || delegated <= free_quota {
+                return tx_per_day;
+            }
+            (u64::from(tx_per_day) * u64::from(free_quota) / u64::from(delegated)) as u32
+        }
+
+        /// Rollover credits `quota` has available in `epoch`, given the
+        /// agent's own free quota. Computes the carry-over for an epoch the
+        /// record has not been reset into yet.
+        pub fn rollover_for(
+            quota: &AgentQuota<BalanceOf<T>, BlockNumberFor<T>>,
+            free_quota: u32,
+            epoch: BlockNumberFor<T>,
+        ) -> u32 {
+            if !quota.rollover_enabled || free_quota == u32::MAX {
+                return 0;
+            }
+            if quota.epoch == epoch {
+                return quota.rollover;
+            }
+            let unused = if quota.epoch.saturating_add(One::one()) == epoch {
+                free_quota
+                    .saturating_add(quota.rollover)
+                    .saturating_sub(quota.daily_used)
+            } else {
+                free_quota
+            };
+            unused.min(T::MaxRolloverPct::get().mul_floor(free_quota))
+        }
+
+        /// `who`'s free allowance for the current epoch: its own free quota
+        /// plus rollover credits. `u32::MAX` for unlimited accounts.
+        pub fn daily_allowance(who: &T::AccountId) -> u32 {
+            let Some(quota) = AgentQuotas::<T>::get(who) else {
+                let stake = Self::era_stake(who);
+                return Self::own_free_quota(who, stake, Self::reputation_tier_of(who));
+            };
+            let free_quota = Self::own_free_quota(who, quota.stake, Self::reputation_tier_of(who));
+            if free_quota == u32::MAX {
+                return free_quota;
+            }
+            free_quota.saturating_add(Self::rollover_for(
+                &quota,
+                free_quota,
+                Self::current_epoch(),
+            ))
+        }
+
+        /// Whether `who`'s free quota is currently suspended.
+        pub fn is_quota_suspended(who: &T::AccountId) -> bool {
+            QuotaSuspensions::<T>::get(who)
+                .is_some_and(|until| <frame_system::Pallet<T>>::block_number() < until)
+        }
+
+        /// Reputation tier for `who`'s current `ReputationProvider` score.
+        pub fn reputation_tier_of(who: &T::AccountId) -> u8 {
+            let score = T::ReputationProvider::get_reputation(who);
+            if score >= T::VerifiedContributorScore::get() {
+                2
+            } else if score >= T::HighReputationScore::get() {
+                1
+            } else {
+                0
+            }
+        }
+
+        /// Quota units `who` has used in the current epoch.
+        pub fn used_today(who: &T::AccountId) -> u32 {
+            AgentQuotas::<T>::get(who)
+                .filter(|quota| quota.epoch == Self::current_epoch())
+                .map_or(0, |quota| quota.daily_used)
+        }
+
+        /// Quota epoch containing `block`.
+        pub fn epoch_of(block: BlockNumberFor<T>) -> BlockNumberFor<T> {
             let blocks_per_day = T::BlocksPerDay::get();
+            if blocks_per_day.is_zero() {
+                return Zero::zero();
+            }
+            block / blocks_per_day
+        }
+
+        /// Quota epoch containing the current block.
+        pub fn current_epoch() -> BlockNumberFor<T> {
+            Self::epoch_of(<frame_system::Pallet<T>>::block_number())
+        }
+
+        /// Per-class call counts for the agent's current quota epoch.
+        ///
+        /// Returns zeros once the epoch has rolled over, even before the next
+        /// call clears the stored counters.
+        pub fn class_usage(who: &T::AccountId) -> sp_std::vec::Vec<(CallClass, u32)> {
+            let current_epoch = Self::current_epoch();
+            let current_day =
+                AgentQuotas::<T>::get(who).is_some_and(|quota| quota.epoch == current_epoch);
+            CallClass::ALL
+                .iter()
+                .map(|class| {
+                    let count = if current_day {
+                        DailyClassUsage::<T>::get(who, class)
+                    } else {
+                        0
+                    };
+                    (*class, count)
+                })
+                .collect()
+        }
+
+        /// Consume `units` from an agent's daily quota, charging the excess
+        /// fee per unit once the free quota is exhausted.
+        pub fn consume_quota_units(who: &T::AccountId, units: u32) -> DispatchResult {
+            let current_epoch = Self::current_epoch();
 
             Self::ensure_quota_initialized(who);
+            // Picks up a new staking era's snapshot.
+            Self::sync_stake_of(who);
 
             AgentQuotas::<T>::try_mutate(who, |maybe_quota| -> DispatchResult {
                 let quota = maybe_quota
                     .as_mut()
                     .ok_or(Error::<T>::QuotaNotInitialized)?;
 
-                // Reset daily counter if a new day has started
-                let blocks_since_day_start = current_block.saturating_sub(quota.day_start_block);
-                if blocks_since_day_start >= blocks_per_day {
-                    let free_quota = Self::calculate_free_quota(quota.stake, quota.reputation_tier);
+                let tier = Self::reputation_tier_of(who);
+                if quota.reputation_tier != tier {
+                    quota.reputation_tier = tier;
+                    Self::deposit_event(Event::ReputationTierUpdated {
+                        agent: who.clone(),
+                        tier,
+                    });
+                }
+
+                // Reset daily counter on first use in a new epoch, carrying
+                // unused quota over for opted-in agents
+                if quota.epoch != current_epoch {
+                    let free_quota = Self::own_free_quota(who, quota.stake, quota.reputation_tier);
+                    quota.rollover = Self::rollover_for(quota, free_quota, current_epoch);
                     quota.daily_used = 0;
-                    quota.day_start_block = current_block;
+                    quota.epoch = current_epoch;
+                    let _ =
+                        DailyClassUsage::<T>::clear_prefix(who, CallClass::ALL.len() as u32, None);
                     Self::deposit_event(Event::QuotaReset {
                         agent: who.clone(),
-                        free_quota,
+                        free_quota: free_quota.saturating_add(quota.rollover),
                     });
                 }
 
-                let free_quota = Self::calculate_free_quota(quota.stake, quota.reputation_tier);
+                let own_quota = Self::own_free_quota(who, quota.stake, quota.reputation_tier);
+                let free_quota = if own_quota == u32::MAX {
+                    own_quota
+                } else {
+                    own_quota.saturating_add(quota.rollover)
+                };
+                let suspended = Self::is_quota_suspended(who);
 
-                if free_quota == u32::MAX || quota.daily_used < free_quota {
+                if !suspended
+                    && (free_quota == u32::MAX
+                        || quota.daily_used.saturating_add(units) <= free_quota)
+                {
                     // Within free quota
-                    quota.daily_used = quota.daily_used.saturating_add(1);
+                    quota.daily_used = quota.daily_used.saturating_add(units);
                     let remaining = if free_quota == u32::MAX {
                         u32::MAX
                     } else {
@@ -299,22 +1338,50 @@ pub mod pallet {
                         agent: who.clone(),
                         remaining,
                     });
-                } else {
-                    // Over quota — charge fee
-                    let fee = Self::calculate_excess_fee(quota.stake);
-                    _ = T::Currency::withdraw(
+                } else if !suspended
+                    && Self::draw_delegated_quota(
                         who,
+                        Self::overage(free_quota, quota.daily_used, units),
+                        current_epoch,
+                    )
+                {
+                    // Over own quota, the excess covered by a delegation
+                    quota.daily_used = quota.daily_used.saturating_add(units);
+                } else {
+                    // Over quota — charge fee on the units past the free quota
+                    let charged = if suspended {
+                        units
+                    } else {
+                        Self::overage(free_quota, quota.daily_used, units)
+                    };
+                    let fee =
+                        Self::calculate_excess_fee(quota.stake).saturating_mul(charged.into());
+                    let registered = Self::registered_sponsor_of(who, fee);
+                    let sponsor = registered
+                        .clone()
+                        .or_else(|| T::FeeSponsor::sponsor_of(who, fee));
+                    let paid = T::Currency::withdraw(
+                        sponsor.as_ref().unwrap_or(who),
                         fee,
-                        frame_support::traits::WithdrawReasons::FEE,
-                        frame_support::traits::ExistenceRequirement::KeepAlive,
+                        WithdrawReasons::FEE,
+                        ExistenceRequirement::KeepAlive,
                     )
                     .map_err(|_| Error::<T>::InsufficientBalance)?;
+                    T::Currency::resolve_creating(&Self::fee_pot_account(), paid);
+                    if let Some(sponsor) = registered {
+                        Self::record_sponsored_fee(who, sponsor, fee, current_epoch);
+                    } else if sponsor.is_some() {
+                        T::FeeSponsor::on_sponsored(who, fee);
+                    }
 
                     TotalFeesCollected::<T>::mutate(|total| {
                         *total = total.saturating_add(fee);
                     });
+                    EpochFeeStats::<T>::mutate(current_epoch, |stats| {
+                        stats.collected = stats.collected.saturating_add(fee);
+                    });
 
-                    quota.daily_used = quota.daily_used.saturating_add(1);
+                    quota.daily_used = quota.daily_used.saturating_add(units);
                     Self::deposit_event(Event::FeeCharged {
                         agent: who.clone(),
                         amount: fee,
@@ -325,6 +1392,218 @@ pub mod pallet {
             })
         }
 
+        /// Fee the next `units` of quota would cost `who`; zero while they fit
+        /// in the free quota. Does not modify state.
+        pub fn pending_fee(who: &T::AccountId, units: u32) -> BalanceOf<T> {
+            let stake = AgentQuotas::<T>::get(who)
+                .map_or_else(|| Self::era_stake(who), |quota| quota.stake);
+            let daily_used = Self::used_today(who);
+
+            let charged = if Self::is_quota_suspended(who) {
+                units
+            } else {
+                let overage = Self::overage(Self::daily_allowance(who), daily_used, units);
+                if Self::delegated_remaining(who) >= overage {
+                    return Zero::zero();
+                }
+                overage
+            };
+            Self::calculate_excess_fee(stake).saturating_mul(charged.into())
+        }
+
+        /// Units of `units` that do not fit in what is left of `free_quota`
+        /// after `daily_used`.
+        fn overage(free_quota: u32, daily_used: u32, units: u32) -> u32 {
+            if free_quota == u32::MAX {
+                return 0;
+            }
+            units.saturating_sub(free_quota.saturating_sub(daily_used))
+        }
+
+        /// Free transactions `who` has left in the current epoch, including
+        /// delegated and rolled-over ones, and its full daily allowance. Both
+        /// are `u32::MAX` for unlimited accounts and zero while suspended.
+        pub fn remaining_free_tx(who: &T::AccountId) -> runtime_api::FreeTxStatus {
+            if Self::is_quota_suspended(who) {
+                return runtime_api::FreeTxStatus {
+                    remaining: 0,
+                    free_quota: 0,
+                };
+            }
+            let own_quota = Self::daily_allowance(who);
+            if own_quota == u32::MAX {
+                return runtime_api::FreeTxStatus {
+                    remaining: u32::MAX,
+                    free_quota: u32::MAX,
+                };
+            }
+
+            let delegated = DelegatorOf::<T>::get(who).map_or(0, |delegator| {
+                Delegations::<T>::get(&delegator, who)
+                    .map_or(0, |d| Self::delegation_allowance(&delegator, d.tx_per_day))
+            });
+            runtime_api::FreeTxStatus {
+                remaining: own_quota
+                    .saturating_sub(Self::used_today(who))
+                    .saturating_add(Self::delegated_remaining(who)),
+                free_quota: own_quota.saturating_add(delegated),
+            }
+        }
+
+        /// Total fee `who` would pay for its next `n_tx` single-unit
+        /// transactions in the current epoch.
+        pub fn projected_fee(who: &T::AccountId, n_tx: u32) -> BalanceOf<T> {
+            let remaining = Self::remaining_free_tx(who).remaining;
+            let charged = n_tx.saturating_sub(remaining);
+            if charged == 0 {
+                return Zero::zero();
+            }
+            let stake = AgentQuotas::<T>::get(who)
+                .map_or_else(|| Self::era_stake(who), |quota| quota.stake);
+            Self::calculate_excess_fee(stake).saturating_mul(charged.into())
+        }
+
+        /// First block of the next quota epoch, when daily counters reset.
+        pub fn quota_reset_block() -> BlockNumberFor<T> {
+            Self::current_epoch()
+                .saturating_add(One::one())
+                .saturating_mul(T::BlocksPerDay::get())
+        }
+
+        /// Whether `fee` can be withdrawn for `who`, from its sponsor if it has
+        /// one, without reaping the paying account.
+        pub fn can_pay_fee(who: &T::AccountId, fee: BalanceOf<T>) -> bool {
+            let payer = Self::registered_sponsor_of(who, fee)
+                .or_else(|| T::FeeSponsor::sponsor_of(who, fee))
+                .unwrap_or_else(|| who.clone());
+            T::Currency::free_balance(&payer)
+                .checked_sub(&fee)
+                .filter(|new_balance| *new_balance >= T::Currency::minimum_balance())
+                .is_some_and(|new_balance| {
+                    T::Currency::ensure_can_withdraw(&payer, fee, WithdrawReasons::FEE, new_balance)
+                        .is_ok()
+                })
+        }
+
+        /// Drop every delegation `who` gives or receives, at most `limit` of
+        /// them. Returns how many were dropped and whether none remain; used
+        /// when an agent leaves the registry.
+        pub fn revoke_delegations_of(who: &T::AccountId, limit: u32) -> (u32, bool) {
+            let mut budget = limit;
+            if let Some(delegator) = DelegatorOf::<T>::get(who) {
+                if budget == 0 {
+                    return (0, false);
+                }
+                Self::remove_delegation(&delegator, who);
+                budget -= 1;
+            }
+
+            // Collect before removing: the prefix must not change while iterated.
+            let mut given = Delegations::<T>::iter_key_prefix(who);
+            let batch: sp_std::vec::Vec<T::AccountId> =
+                given.by_ref().take(budget as usize).collect();
+            let more = given.next().is_some();
+            for beneficiary in &batch {
+                Self::remove_delegation(who, beneficiary);
+            }
+            (limit - budget + batch.len() as u32, !more)
+        }
+
+        fn remove_delegation(delegator: &T::AccountId, beneficiary: &T::AccountId) -> bool {
+            let Some(delegation) = Delegations::<T>::take(delegator, beneficiary) else {
+                return false;
+            };
+            DelegatorOf::<T>::remove(beneficiary);
+            DelegatedTotals::<T>::mutate_exists(delegator, |maybe_totals| {
+                if let Some(totals) = maybe_totals {
+                    totals.count = totals.count.saturating_sub(1);
+                    totals.tx_per_day = totals.tx_per_day.saturating_sub(delegation.tx_per_day);
+                    if totals.count == 0 {
+                        *maybe_totals = None;
+                    }
+                }
+            });
+
+            Self::deposit_event(Event::DelegationRevoked {
+                delegator: delegator.clone(),
+                beneficiary: beneficiary.clone(),
+            });
+            true
+        }
+
+        /// `who`'s registered sponsor, if its remaining budget for the current
+        /// quota epoch covers `fee`. Registered sponsors take precedence over
+        /// `FeeSponsor`.
+        pub fn registered_sponsor_of(
+            who: &T::AccountId,
+            fee: BalanceOf<T>,
+        ) -> Option<T::AccountId> {
+            let sponsorship = Sponsorships::<T>::get(who)?;
+            let spent = if sponsorship.epoch == Self::current_epoch() {
+                sponsorship.spent
+            } else {
+                Zero::zero()
+            };
+            (spent.saturating_add(fee) <= sponsorship.max_per_day).then_some(sponsorship.sponsor)
+        }
+
+        fn record_sponsored_fee(
+            who: &T::AccountId,
+            sponsor: T::AccountId,
+            fee: BalanceOf<T>,
+            epoch: BlockNumberFor<T>,
+        ) {
+            Sponsorships::<T>::mutate(who, |maybe| {
+                if let Some(sponsorship) = maybe {
+                    if sponsorship.epoch != epoch {
+                        sponsorship.spent = Zero::zero();
+                        sponsorship.epoch = epoch;
+                    }
+                    sponsorship.spent = sponsorship.spent.saturating_add(fee);
+                }
+            });
+            Self::deposit_event(Event::SponsoredFeePaid {
+                sponsor,
+                beneficiary: who.clone(),
+                amount: fee,
+            });
+        }
+
+        /// Account over-quota fees are collected in until swept.
+        pub fn fee_pot_account() -> T::AccountId {
+            T::PalletId::get().into_account_truncating()
+        }
+
+        /// Move the fee pot, less the existential deposit, to
+        /// `TreasuryAccount` once it holds at least `FeeSweepThreshold`.
+        fn sweep_fees_to_treasury() -> Weight {
+            let pot = Self::fee_pot_account();
+            let balance = T::Currency::free_balance(&pot);
+            // The existential deposit stays behind so the pot is never reaped.
+            let amount = balance.saturating_sub(T::Currency::minimum_balance());
+            if amount.is_zero() || balance < T::FeeSweepThreshold::get() {
+                return T::DbWeight::get().reads(1);
+            }
+            if T::Currency::transfer(
+                &pot,
+                &T::TreasuryAccount::get(),
+                amount,
+                ExistenceRequirement::KeepAlive,
+            )
+            .is_err()
+            {
+                return T::DbWeight::get().reads(2);
+            }
+
+            let epoch = Self::current_epoch();
+            TotalFeesSwept::<T>::mutate(|total| *total = total.saturating_add(amount));
+            EpochFeeStats::<T>::mutate(epoch, |stats| {
+                stats.swept = stats.swept.saturating_add(amount);
+            });
+            Self::deposit_event(Event::FeesSweptToTreasury { amount, epoch });
+            T::DbWeight::get().reads_writes(4, 5)
+        }
+
         /// Calculate excess fee based on stake level.
         /// Higher stake → lower per-tx fee over quota.
         fn calculate_excess_fee(stake: BalanceOf<T>) -> BalanceOf<T> {
@@ -339,21 +1618,73 @@ pub mod pallet {
                 .max(base_fee / 10u32.into()) // floor at 10% of base
         }
 
+        /// Draw `units` from `who`'s delegation, if it has enough left this
+        /// epoch of what the delegator's current stake still backs.
+        fn draw_delegated_quota(who: &T::AccountId, units: u32, epoch: BlockNumberFor<T>) -> bool {
+            let Some(delegator) = DelegatorOf::<T>::get(who) else {
+                return false;
+            };
+            let remaining = Delegations::<T>::mutate(&delegator, who, |maybe_delegation| {
+                let delegation = maybe_delegation.as_mut()?;
+                if delegation.epoch != epoch {
+                    delegation.used = 0;
+                    delegation.epoch = epoch;
+                }
+                let allowance = Self::delegation_allowance(&delegator, delegation.tx_per_day);
+                let used = delegation.used.saturating_add(units);
+                if used > allowance {
+                    return None;
+                }
+                delegation.used = used;
+                Some(allowance - used)
+            });
+            let Some(remaining) = remaining else {
+                return false;
+            };
+
+            Self::deposit_event(Event::DelegatedQuotaUsed {
+                delegator,
+                beneficiary: who.clone(),
+                remaining,
+            });
+            true
+        }
+
         /// Ensure an agent has a quota record, initializing if missing.
         fn ensure_quota_initialized(who: &T::AccountId) {
             if !AgentQuotas::<T>::contains_key(who) {
-                let stake = T::Currency::reserved_balance(who);
-                let current_block = <frame_system::Pallet<T>>::block_number();
+                let stake = Self::snapshot_stake(who);
                 AgentQuotas::<T>::insert(
                     who,
                     AgentQuota {
                         stake,
                         daily_used: 0,
-                        day_start_block: current_block,
-                        reputation_tier: 0,
+                        epoch: Self::current_epoch(),
+                        reputation_tier: Self::reputation_tier_of(who),
+                        rollover_enabled: false,
+                        rollover: 0,
                     },
                 );
             }
         }
     }
 }
+
+impl<T: Config> sp_staking::OnStakingUpdate<T::AccountId, BalanceOf<T>> for Pallet<T> {
+    fn on_stake_update(who: &T::AccountId, prev_stake: Option<sp_staking::Stake<BalanceOf<T>>>) {
+        // The first change in an era fixes the era's snapshot at the stake
+        // held before it; the new stake applies from the next era.
+        let era = T::StakeProvider::current_era();
+        if !QuotaStakeSnapshot::<T>::contains_key(era, who) {
+            let stake = prev_stake.map_or_else(sp_runtime::traits::Zero::zero, |stake| {
+                Self::quota_stake_of(stake.active, stake.total.saturating_sub(stake.active))
+            });
+            QuotaStakeSnapshot::<T>::insert(era, who, stake);
+        }
+        Self::sync_stake_of(who);
+    }
+
+    fn on_unstake(who: &T::AccountId) {
+        Self::sync_stake_of(who);
+    }
+}
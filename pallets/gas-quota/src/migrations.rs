@@ -0,0 +1,141 @@
+//! Storage migrations for pallet-gas-quota.
+
+use crate::pallet::{AgentQuota, AgentQuotas, BalanceOf, Config, Pallet};
+use frame_support::{
+    migrations::VersionedMigration,
+    pallet_prelude::*,
+    traits::{Get, UncheckedOnRuntimeUpgrade},
+};
+use frame_system::pallet_prelude::BlockNumberFor;
+
+/// v0 → v1: replace each agent's personal `day_start_block` with the global
+/// quota epoch that block falls in.
+pub mod v1 {
+    use super::*;
+
+    /// `AgentQuota` as stored before v1.
+    #[derive(Encode, Decode)]
+    pub struct OldAgentQuota<Balance, BlockNumber> {
+        pub stake: Balance,
+        pub daily_used: u32,
+        pub day_start_block: BlockNumber,
+        pub reputation_tier: u8,
+    }
+
+    pub struct UncheckedMigrateToV1<T>(core::marker::PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for UncheckedMigrateToV1<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let mut translated = 0u64;
+            AgentQuotas::<T>::translate::<OldAgentQuota<BalanceOf<T>, BlockNumberFor<T>>, _>(
+                |_, old| {
+                    translated += 1;
+                    Some(AgentQuota {
+                        stake: old.stake,
+                        daily_used: old.daily_used,
+                        epoch: Pallet::<T>::epoch_of(old.day_start_block),
+                        reputation_tier: old.reputation_tier,
+                        rollover_enabled: false,
+                        rollover: 0,
+                    })
+                },
+            );
+            log::info!(
+                target: "runtime::gas-quota",
+                "migrated {} quota records to global epochs",
+                translated
+            );
+            T::DbWeight::get().reads_writes(translated, translated)
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<sp_std::vec::Vec<u8>, sp_runtime::TryRuntimeError> {
+            Ok((AgentQuotas::<T>::iter_keys().count() as u64).encode())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: sp_std::vec::Vec<u8>) -> Result<(), sp_runtime::TryRuntimeError> {
+            let before = u64::decode(&mut &state[..])
+                .map_err(|_| sp_runtime::TryRuntimeError::Other("bad pre_upgrade state"))?;
+            let after = AgentQuotas::<T>::iter().count() as u64;
+            ensure!(before == after, "quota records lost in migration");
+            Ok(())
+        }
+    }
+
+    /// Runs [`UncheckedMigrateToV1`] once, bumping the storage version to 1.
+    pub type MigrateToV1<T> = VersionedMigration<
+        0,
+        1,
+        UncheckedMigrateToV1<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
+
+/// v1 → v2: add the rollover fields to every quota record, opted out.
+///
+/// Records a same-upgrade v1 migration already wrote in the v2 layout decode
+/// as their v1 prefix and come out unchanged.
+pub mod v2 {
+    use super::*;
+
+    /// `AgentQuota` as stored in v1.
+    #[derive(Encode, Decode)]
+    pub struct OldAgentQuota<Balance, BlockNumber> {
+        pub stake: Balance,
+        pub daily_used: u32,
+        pub epoch: BlockNumber,
+        pub reputation_tier: u8,
+    }
+
+    pub struct UncheckedMigrateToV2<T>(core::marker::PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for UncheckedMigrateToV2<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let mut translated = 0u64;
+            AgentQuotas::<T>::translate::<OldAgentQuota<BalanceOf<T>, BlockNumberFor<T>>, _>(
+                |_, old| {
+                    translated += 1;
+                    Some(AgentQuota {
+                        stake: old.stake,
+                        daily_used: old.daily_used,
+                        epoch: old.epoch,
+                        reputation_tier: old.reputation_tier,
+                        rollover_enabled: false,
+                        rollover: 0,
+                    })
+                },
+            );
+            log::info!(
+                target: "runtime::gas-quota",
+                "added rollover fields to {} quota records",
+                translated
+            );
+            T::DbWeight::get().reads_writes(translated, translated)
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<sp_std::vec::Vec<u8>, sp_runtime::TryRuntimeError> {
+            Ok((AgentQuotas::<T>::iter_keys().count() as u64).encode())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: sp_std::vec::Vec<u8>) -> Result<(), sp_runtime::TryRuntimeError> {
+            let before = u64::decode(&mut &state[..])
+                .map_err(|_| sp_runtime::TryRuntimeError::Other("bad pre_upgrade state"))?;
+            let after = AgentQuotas::<T>::iter().count() as u64;
+            ensure!(before == after, "quota records lost in migration");
+            Ok(())
+        }
+    }
+
+    /// Runs [`UncheckedMigrateToV2`] once, bumping the storage version to 2.
+    pub type MigrateToV2<T> = VersionedMigration<
+        1,
+        2,
+        UncheckedMigrateToV2<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
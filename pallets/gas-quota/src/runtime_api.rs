@@ -0,0 +1,67 @@
+//! Runtime APIs for quota queries and for simulating a call before
+//! submitting it.
+
+use crate::CallClass;
+use codec::{Codec, Decode, Encode};
+use scale_info::TypeInfo;
+use sp_runtime::{ApplyExtrinsicResult, RuntimeDebug};
+use sp_std::vec::Vec;
+
+/// What dispatching a call would do, as reported by [`DryRunApi::dry_run_call`].
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct CallDryRunEffects<Event, Balance> {
+    /// Outer error if the transaction would be invalid (e.g. cannot pay the
+    /// over-quota fee), inner error if the call itself would fail.
+    pub result: ApplyExtrinsicResult,
+    /// Events the call would emit.
+    pub events: Vec<Event>,
+    /// Quota units the transaction and call would consume.
+    pub quota_consumed: u32,
+    /// Over-quota fees that would be charged.
+    pub fee: Balance,
+}
+
+/// Free transactions left to an account, as reported by
+/// [`GasQuotaApi::remaining_free_tx`].
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct FreeTxStatus {
+    /// Free transactions left in the current quota epoch.
+    pub remaining: u32,
+    /// Free transactions per epoch, including delegated quota.
+    pub free_quota: u32,
+}
+
+sp_api::decl_runtime_apis! {
+    /// Quota queries for wallets deciding whether a transaction will be free.
+    pub trait GasQuotaApi<AccountId, Balance, BlockNumber>
+    where
+        AccountId: Codec,
+        Balance: Codec,
+        BlockNumber: Codec,
+    {
+        /// Free transactions `account` has left today and its daily allowance.
+        fn remaining_free_tx(account: AccountId) -> FreeTxStatus;
+
+        /// Fees `account` would pay for its next `n_tx` transactions today.
+        fn projected_fee(account: AccountId, n_tx: u32) -> Balance;
+
+        /// Block at which `account`'s daily quota resets.
+        fn quota_reset_block(account: AccountId) -> BlockNumber;
+
+        /// Quota units `account` has spent today in each call class.
+        fn class_usage(account: AccountId) -> Vec<(CallClass, u32)>;
+    }
+
+    /// Dry-run of signed calls against current state.
+    pub trait DryRunApi<AccountId, Call, Event, Balance>
+    where
+        AccountId: Codec,
+        Call: Codec,
+        Event: Codec,
+        Balance: Codec,
+    {
+        /// Dispatch `call` as signed by `origin`, including quota charging,
+        /// and report the outcome. Nothing is committed.
+        fn dry_run_call(origin: AccountId, call: Call) -> CallDryRunEffects<Event, Balance>;
+    }
+}
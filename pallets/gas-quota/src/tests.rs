@@ -1,11 +1,24 @@
 //! Tests for pallet-gas-quota
 
-use crate::{self as pallet_gas_quota, AgentQuotas};
-use frame_support::{assert_noop, assert_ok, parameter_types, traits::ConstU32};
+use crate::{
+    self as pallet_gas_quota, AgentQuotas, CallClass, ChargeGasQuota, DailyClassUsage,
+    OldestStakeSnapshotEra, QuotaStakeSnapshot,
+};
+use frame_support::traits::OnRuntimeUpgrade;
+use frame_support::{
+    assert_noop, assert_ok,
+    dispatch::GetDispatchInfo,
+    pallet_prelude::TransactionSource,
+    parameter_types,
+    traits::{ConstU32, ConstU64, Hooks},
+    weights::Weight,
+    PalletId,
+};
 use sp_core::H256;
 use sp_runtime::{
-    traits::{BlakeTwo256, IdentityLookup},
-    BuildStorage, Perbill,
+    traits::{BlakeTwo256, DispatchTransaction, IdentityLookup},
+    transaction_validity::{InvalidTransaction, TransactionValidityError},
+    BuildStorage, Perbill, Percent,
 };
 
 type Block = frame_system::mocking::MockBlock<Test>;
@@ -80,6 +93,9 @@ parameter_types! {
     pub const UnlimitedStakeThreshold: u64 = 10_000_000_000; // 10,000 $CLAW
     pub const BaseFeePerTx: u64 = 1_000; // 0.001 $CLAW
     pub const FeeDiscountPerKStake: Perbill = Perbill::from_percent(90); // 10% discount per kStake
+    pub const MaxRolloverPct: Percent = Percent::from_percent(50);
+    pub const UnbondingStakeWeight: Perbill = Perbill::from_percent(50);
+    pub const GasQuotaPalletId: PalletId = PalletId(*b"gasquota");
 }
 
 impl pallet_gas_quota::Config for Test {
@@ -91,6 +107,114 @@ impl pallet_gas_quota::Config for Test {
     type UnlimitedStakeThreshold = UnlimitedStakeThreshold;
     type BaseFeePerTx = BaseFeePerTx;
     type FeeDiscountPerKStake = FeeDiscountPerKStake;
+    type FeeSponsor = MockFeeSponsor;
+    type StakeProvider = MockStakeProvider;
+    type ReputationProvider = MockReputation;
+    type HighReputationScore = ConstU32<8_000>;
+    type VerifiedContributorScore = ConstU32<9_500>;
+    type MaxDelegations = ConstU32<2>;
+    type MaxRolloverPct = MaxRolloverPct;
+    type MaxClassWeight = ConstU32<100>;
+    type QuotaWeigher = MockQuotaWeigher;
+    type PalletId = GasQuotaPalletId;
+    type TreasuryAccount = ConstU64<100>;
+    type FeeSweepThreshold = ConstU64<2_000>;
+    type GovernanceOrigin = frame_system::EnsureRoot<u64>;
+    type StakeSnapshotEras = ConstU32<2>;
+    type MaxSnapshotPrunePerBlock = ConstU32<1>;
+    type UnbondingStakeWeight = UnbondingStakeWeight;
+}
+
+parameter_types! {
+    /// Active stake reported for account 8.
+    pub static AccountEightStake: u64 = 0;
+    /// Unbonding stake reported for account 8.
+    pub static AccountEightUnbonding: u64 = 0;
+    /// Staking era reported by the stake provider.
+    pub static StakingEra: u32 = 0;
+    /// Reputation score reported for account 9.
+    pub static AccountNineReputation: u32 = 5_000;
+}
+
+pub struct MockReputation;
+
+impl pallet_reputation::ReputationManager<u64, u64> for MockReputation {
+    fn on_task_completed(_worker: &u64, _earned: u64) {}
+    fn on_task_posted(_poster: &u64, _spent: u64) {}
+    fn on_dispute_resolved(_winner: &u64, _loser: &u64) {}
+
+    fn get_reputation(account: &u64) -> u32 {
+        if *account == 9 {
+            AccountNineReputation::get()
+        } else {
+            5_000
+        }
+    }
+
+    fn meets_minimum_reputation(account: &u64, minimum: u32) -> bool {
+        Self::get_reputation(account) >= minimum
+    }
+}
+
+pub struct MockStakeProvider;
+
+impl pallet_gas_quota::StakeProvider<u64, u64> for MockStakeProvider {
+    fn active_stake(who: &u64) -> u64 {
+        if *who == 8 {
+            AccountEightStake::get()
+        } else {
+            0
+        }
+    }
+
+    fn unbonding_stake(who: &u64) -> u64 {
+        if *who == 8 {
+            AccountEightUnbonding::get()
+        } else {
+            0
+        }
+    }
+
+    fn current_era() -> u32 {
+        StakingEra::get()
+    }
+}
+
+/// Pays account 6's over-quota fees from account 1.
+pub struct MockFeeSponsor;
+
+impl pallet_gas_quota::FeeSponsor<u64, u64> for MockFeeSponsor {
+    fn sponsor_of(who: &u64, _fee: u64) -> Option<u64> {
+        (*who == 6).then_some(1)
+    }
+
+    fn on_sponsored(_who: &u64, _fee: u64) {}
+
+    fn sponsor_weight() -> Weight {
+        Weight::zero()
+    }
+}
+
+/// Delegating quota costs three units and counts as a market call;
+/// everything else costs one unclassified unit.
+pub struct MockQuotaWeigher;
+
+impl pallet_gas_quota::QuotaWeigher<RuntimeCall> for MockQuotaWeigher {
+    fn quota_units(call: &RuntimeCall) -> u32 {
+        match call {
+            RuntimeCall::GasQuota(pallet_gas_quota::Call::delegate_quota { .. }) => 3,
+            _ => 1,
+        }
+    }
+
+    fn call_class(call: &RuntimeCall) -> Option<CallClass> {
+        match call {
+            RuntimeCall::GasQuota(pallet_gas_quota::Call::delegate_quota { .. }) => {
+                Some(CallClass::Market)
+            }
+            _ => None,
+        }
+    }
 }
 
 fn new_test_ext() -> sp_io::TestExternalities {
@@ -223,3 +347,965 @@ fn quota_resets_after_day() {
         );
     });
 }
+
+#[test]
+fn class_usage_counts_calls_per_class() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(pallet_gas_quota::Pallet::<Test>::consume_quota_for_class(
+            &4,
+            CallClass::Market
+        ));
+        assert_ok!(pallet_gas_quota::Pallet::<Test>::consume_quota_for_class(
+            &4,
+            CallClass::Market
+        ));
+        assert_ok!(pallet_gas_quota::Pallet::<Test>::consume_quota_for_class(
+            &4,
+            CallClass::Messaging
+        ));
+
+        assert_eq!(
+            pallet_gas_quota::Pallet::<Test>::class_usage(&4),
+            vec![
+                (CallClass::Market, 2),
+                (CallClass::Messaging, 1),
+                (CallClass::Governance, 0),
+                (CallClass::Identity, 0),
+            ]
+        );
+        assert_eq!(AgentQuotas::<Test>::get(4).unwrap().daily_used, 3);
+    });
+}
+
+#[test]
+fn class_weight_must_be_within_bounds() {
+    new_test_ext().execute_with(|| {
+        for weight in [0, 101] {
+            assert_noop!(
+                pallet_gas_quota::Pallet::<Test>::set_class_weight(
+                    RuntimeOrigin::root(),
+                    CallClass::Market,
+                    weight
+                ),
+                pallet_gas_quota::Error::<Test>::InvalidClassWeight
+            );
+        }
+        for weight in [1, 100] {
+            assert_ok!(pallet_gas_quota::Pallet::<Test>::set_class_weight(
+                RuntimeOrigin::root(),
+                CallClass::Market,
+                weight
+            ));
+            assert_eq!(
+                pallet_gas_quota::ClassWeights::<Test>::get(CallClass::Market),
+                Some(weight)
+            );
+        }
+    });
+}
+
+#[test]
+fn class_weight_scales_quota_units() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            pallet_gas_quota::Pallet::<Test>::set_class_weight(
+                RuntimeOrigin::signed(1),
+                CallClass::Governance,
+                5
+            ),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_ok!(pallet_gas_quota::Pallet::<Test>::set_class_weight(
+            RuntimeOrigin::root(),
+            CallClass::Governance,
+            5
+        ));
+
+        // Two governance calls use the full 10-unit free quota.
+        for _ in 0..2 {
+            assert_ok!(pallet_gas_quota::Pallet::<Test>::consume_quota_for_class(
+                &3,
+                CallClass::Governance
+            ));
+        }
+        assert_eq!(AgentQuotas::<Test>::get(3).unwrap().daily_used, 10);
+
+        // A third is charged for all 5 units.
+        let balance_before = pallet_balances::Pallet::<Test>::free_balance(3);
+        assert_ok!(pallet_gas_quota::Pallet::<Test>::consume_quota_for_class(
+            &3,
+            CallClass::Governance
+        ));
+        assert_eq!(
+            balance_before - pallet_balances::Pallet::<Test>::free_balance(3),
+            5 * BaseFeePerTx::get()
+        );
+    });
+}
+
+#[test]
+fn class_usage_resets_with_day() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(pallet_gas_quota::Pallet::<Test>::consume_quota_for_class(
+            &4,
+            CallClass::Identity
+        ));
+
+        frame_system::Pallet::<Test>::set_block_number(14_401);
+        assert_eq!(
+            pallet_gas_quota::Pallet::<Test>::class_usage(&4)[3],
+            (CallClass::Identity, 0)
+        );
+
+        assert_ok!(pallet_gas_quota::Pallet::<Test>::consume_quota_for_class(
+            &4,
+            CallClass::Market
+        ));
+        assert_eq!(DailyClassUsage::<Test>::get(4, CallClass::Identity), 0);
+        assert_eq!(DailyClassUsage::<Test>::get(4, CallClass::Market), 1);
+    });
+}
+
+#[test]
+fn quota_epochs_are_global() {
+    new_test_ext().execute_with(|| {
+        // Agent 3 starts mid-epoch, agent 4 near its end: both roll over at 14_400.
+        frame_system::Pallet::<Test>::set_block_number(7_000);
+        assert_ok!(pallet_gas_quota::Pallet::<Test>::consume_quota(&3));
+        frame_system::Pallet::<Test>::set_block_number(14_399);
+        assert_ok!(pallet_gas_quota::Pallet::<Test>::consume_quota(&4));
+        assert_eq!(AgentQuotas::<Test>::get(3).unwrap().epoch, 0);
+        assert_eq!(AgentQuotas::<Test>::get(4).unwrap().epoch, 0);
+
+        frame_system::Pallet::<Test>::set_block_number(14_400);
+        assert_eq!(pallet_gas_quota::Pallet::<Test>::current_epoch(), 1);
+        assert_ok!(pallet_gas_quota::Pallet::<Test>::consume_quota(&4));
+        let quota = AgentQuotas::<Test>::get(4).unwrap();
+        assert_eq!((quota.epoch, quota.daily_used), (1, 1));
+
+        // Agent 3 is reset lazily on its first call in the new epoch.
+        assert_eq!(AgentQuotas::<Test>::get(3).unwrap().epoch, 0);
+        assert_ok!(pallet_gas_quota::Pallet::<Test>::consume_quota(&3));
+        let quota = AgentQuotas::<Test>::get(3).unwrap();
+        assert_eq!((quota.epoch, quota.daily_used), (1, 1));
+    });
+}
+
+#[test]
+fn migrate_to_v1_converts_day_start_to_epoch() {
+    use crate::migrations::v1::{MigrateToV1, OldAgentQuota};
+    use codec::Encode;
+    use frame_support::traits::{GetStorageVersion, StorageVersion};
+
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(0).put::<GasQuota>();
+        let old = OldAgentQuota::<u64, u64> {
+            stake: 500,
+            daily_used: 7,
+            day_start_block: 30_000,
+            reputation_tier: 1,
+        };
+        frame_support::storage::unhashed::put_raw(
+            &AgentQuotas::<Test>::hashed_key_for(3),
+            &old.encode(),
+        );
+
+        MigrateToV1::<Test>::on_runtime_upgrade();
+
+        let quota = AgentQuotas::<Test>::get(3).unwrap();
+        assert_eq!(quota.epoch, 2);
+        assert_eq!(quota.daily_used, 7);
+        assert_eq!(quota.stake, 500);
+        assert_eq!(quota.reputation_tier, 1);
+        assert_eq!(GasQuota::on_chain_storage_version(), 1);
+    });
+}
+
+#[test]
+fn migrate_to_v2_adds_rollover_fields() {
+    use crate::migrations::v2::{MigrateToV2, OldAgentQuota};
+    use codec::Encode;
+    use frame_support::traits::{GetStorageVersion, StorageVersion};
+
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(1).put::<GasQuota>();
+        let old = OldAgentQuota::<u64, u64> {
+            stake: 500,
+            daily_used: 7,
+            epoch: 2,
+            reputation_tier: 1,
+        };
+        frame_support::storage::unhashed::put_raw(
+            &AgentQuotas::<Test>::hashed_key_for(3),
+            &old.encode(),
+        );
+
+        MigrateToV2::<Test>::on_runtime_upgrade();
+
+        let quota = AgentQuotas::<Test>::get(3).unwrap();
+        assert_eq!((quota.epoch, quota.daily_used, quota.stake), (2, 7, 500));
+        assert!(!quota.rollover_enabled);
+        assert_eq!(quota.rollover, 0);
+        assert_eq!(GasQuota::on_chain_storage_version(), 2);
+    });
+}
+
+#[test]
+fn unused_quota_rolls_over_when_enabled() {
+    new_test_ext().execute_with(|| {
+        // Agent 4 has the minimum 10 free tx; half may roll over.
+        assert_ok!(GasQuota::set_rollover(RuntimeOrigin::signed(4), true));
+        for _ in 0..2 {
+            assert_ok!(pallet_gas_quota::Pallet::<Test>::consume_quota(&4));
+        }
+
+        // 8 unused, capped at 5 carried over.
+        frame_system::Pallet::<Test>::set_block_number(14_401);
+        assert_eq!(GasQuota::daily_allowance(&4), 15);
+        for _ in 0..15 {
+            assert_ok!(pallet_gas_quota::Pallet::<Test>::consume_quota(&4));
+        }
+        assert_eq!(AgentQuotas::<Test>::get(4).unwrap().rollover, 5);
+        assert_eq!(GasQuota::remaining_free_tx(&4).remaining, 0);
+        assert!(GasQuota::pending_fee(&4, 1) > 0);
+
+        // Everything used: nothing carries into the next day.
+        frame_system::Pallet::<Test>::set_block_number(28_801);
+        assert_eq!(GasQuota::daily_allowance(&4), 10);
+
+        // Opting out forfeits credits.
+        frame_system::Pallet::<Test>::set_block_number(43_201);
+        assert_eq!(GasQuota::daily_allowance(&4), 15);
+        assert_ok!(GasQuota::set_rollover(RuntimeOrigin::signed(4), false));
+        assert_eq!(GasQuota::daily_allowance(&4), 10);
+    });
+}
+
+// ========== ChargeGasQuota ==========
+
+fn submit_signed(who: u64) -> Result<(), TransactionValidityError> {
+    let call = RuntimeCall::GasQuota(pallet_gas_quota::Call::initialize_quota {});
+    let info = call.get_dispatch_info();
+    ChargeGasQuota::<Test>::new()
+        .validate_and_prepare(RuntimeOrigin::signed(who), &call, &info, 0, 0)
+        .map(|_| ())
+}
+
+#[test]
+fn extension_consumes_quota_then_charges_fee() {
+    new_test_ext().execute_with(|| {
+        for _ in 0..10 {
+            assert_ok!(submit_signed(4));
+        }
+        assert_eq!(pallet_balances::Pallet::<Test>::free_balance(4), 5_000);
+        assert_eq!(AgentQuotas::<Test>::get(4).unwrap().daily_used, 10);
+
+        assert_ok!(submit_signed(4));
+        assert_eq!(pallet_balances::Pallet::<Test>::free_balance(4), 4_000);
+        assert_eq!(pallet_gas_quota::Pallet::<Test>::used_today(&4), 11);
+
+        // Counters from an earlier epoch no longer count as used today.
+        System::set_block_number(14_400);
+        assert_eq!(pallet_gas_quota::Pallet::<Test>::used_today(&4), 0);
+
+        // An account without funds is rejected once its free quota is gone.
+        for _ in 0..10 {
+            assert_ok!(submit_signed(7));
+        }
+        assert_eq!(submit_signed(7), Err(InvalidTransaction::Payment.into()));
+    });
+}
+
+#[test]
+fn extension_charges_weighted_calls_multiple_units() {
+    new_test_ext().execute_with(|| {
+        let call = RuntimeCall::GasQuota(pallet_gas_quota::Call::delegate_quota {
+            beneficiary: 5,
+            tx_per_day: 1,
+        });
+        let info = call.get_dispatch_info();
+        let (validity, ..) = ChargeGasQuota::<Test>::new()
+            .validate_only(
+                RuntimeOrigin::signed(4),
+                &call,
+                &info,
+                0,
+                TransactionSource::External,
+                0,
+            )
+            .unwrap();
+        assert_eq!(validity.priority, 3);
+        for _ in 0..3 {
+            assert_ok!(ChargeGasQuota::<Test>::new()
+                .validate_and_prepare(RuntimeOrigin::signed(4), &call, &info, 0, 0)
+                .map(|_| ()));
+        }
+        assert_eq!(pallet_gas_quota::Pallet::<Test>::used_today(&4), 9);
+        assert_eq!(pallet_balances::Pallet::<Test>::free_balance(4), 5_000);
+
+        // Only one free unit left: the next weighted call pays for the
+        // other two.
+        assert_ok!(ChargeGasQuota::<Test>::new()
+            .validate_and_prepare(RuntimeOrigin::signed(4), &call, &info, 0, 0)
+            .map(|_| ()));
+        assert_eq!(pallet_balances::Pallet::<Test>::free_balance(4), 3_000);
+        assert_eq!(pallet_gas_quota::Pallet::<Test>::used_today(&4), 12);
+    });
+}
+
+#[test]
+fn extension_scales_units_by_class_weight_and_counts_class_usage() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(pallet_gas_quota::Pallet::<Test>::set_class_weight(
+            RuntimeOrigin::root(),
+            CallClass::Market,
+            2
+        ));
+        let call = RuntimeCall::GasQuota(pallet_gas_quota::Call::delegate_quota {
+            beneficiary: 5,
+            tx_per_day: 1,
+        });
+        let info = call.get_dispatch_info();
+        assert_ok!(ChargeGasQuota::<Test>::new()
+            .validate_and_prepare(RuntimeOrigin::signed(4), &call, &info, 0, 0)
+            .map(|_| ()));
+        assert_eq!(pallet_gas_quota::Pallet::<Test>::used_today(&4), 6);
+
+        // Unclassified calls are charged but not counted under a class.
+        assert_ok!(submit_signed(4));
+        assert_eq!(pallet_gas_quota::Pallet::<Test>::used_today(&4), 7);
+        assert_eq!(
+            pallet_gas_quota::Pallet::<Test>::class_usage(&4),
+            vec![
+                (CallClass::Market, 1),
+                (CallClass::Messaging, 0),
+                (CallClass::Governance, 0),
+                (CallClass::Identity, 0),
+            ]
+        );
+    });
+}
+
+#[test]
+fn extension_charges_sponsor_for_sponsored_account() {
+    new_test_ext().execute_with(|| {
+        for _ in 0..10 {
+            assert_ok!(submit_signed(6));
+        }
+        let sponsor_before = pallet_balances::Pallet::<Test>::free_balance(1);
+
+        assert_ok!(submit_signed(6));
+        assert_eq!(
+            pallet_balances::Pallet::<Test>::free_balance(1),
+            sponsor_before - 1_000
+        );
+        assert_eq!(pallet_balances::Pallet::<Test>::free_balance(6), 0);
+    });
+}
+
+// ========== Stake Tracking ==========
+
+#[test]
+fn staking_updates_refresh_quota_tier() {
+    use sp_staking::OnStakingUpdate;
+
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        AccountEightStake::set(5_000_000);
+        assert_ok!(pallet_gas_quota::Pallet::<Test>::consume_quota(&8));
+        assert_eq!(AgentQuotas::<Test>::get(8).unwrap().stake, 5_000_000);
+
+        // Bonding more moves the agent from 10 to 100 free TX, but only from
+        // the next staking era on.
+        AccountEightStake::set(100_000_000);
+        pallet_gas_quota::Pallet::<Test>::on_stake_update(
+            &8,
+            Some(sp_staking::Stake {
+                total: 5_000_000,
+                active: 5_000_000,
+            }),
+        );
+        assert_eq!(AgentQuotas::<Test>::get(8).unwrap().stake, 5_000_000);
+        assert_eq!(QuotaStakeSnapshot::<Test>::get(0, 8), Some(5_000_000));
+
+        StakingEra::set(1);
+        assert_ok!(pallet_gas_quota::Pallet::<Test>::consume_quota(&8));
+        assert_eq!(AgentQuotas::<Test>::get(8).unwrap().stake, 100_000_000);
+        System::assert_has_event(
+            pallet_gas_quota::Event::QuotaTierChanged {
+                agent: 8,
+                free_quota: 100,
+            }
+            .into(),
+        );
+
+        StakingEra::set(2);
+        AccountEightStake::set(0);
+        pallet_gas_quota::Pallet::<Test>::on_unstake(&8);
+        assert_eq!(AgentQuotas::<Test>::get(8).unwrap().stake, 0);
+    });
+}
+
+#[test]
+fn unbonding_stake_counts_at_a_discount_until_withdrawn() {
+    use sp_staking::OnStakingUpdate;
+
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        AccountEightStake::set(100_000_000);
+        assert_ok!(pallet_gas_quota::Pallet::<Test>::consume_quota(&8));
+
+        // Unbonding 60 keeps half of it counted while it is still locked.
+        AccountEightStake::set(40_000_000);
+        AccountEightUnbonding::set(60_000_000);
+        pallet_gas_quota::Pallet::<Test>::on_stake_update(
+            &8,
+            Some(sp_staking::Stake {
+                total: 100_000_000,
+                active: 100_000_000,
+            }),
+        );
+        assert_eq!(
+            pallet_gas_quota::Pallet::<Test>::quota_stake(&8),
+            70_000_000
+        );
+
+        // Withdrawing early in the next era snapshots the stake held before
+        // it, unbonding chunks included.
+        StakingEra::set(1);
+        AccountEightUnbonding::set(0);
+        pallet_gas_quota::Pallet::<Test>::on_stake_update(
+            &8,
+            Some(sp_staking::Stake {
+                total: 100_000_000,
+                active: 40_000_000,
+            }),
+        );
+        assert_eq!(QuotaStakeSnapshot::<Test>::get(1, 8), Some(70_000_000));
+        assert_eq!(AgentQuotas::<Test>::get(8).unwrap().stake, 70_000_000);
+
+        // Withdrawn: only the active stake is left from the next era.
+        StakingEra::set(2);
+        assert_ok!(pallet_gas_quota::Pallet::<Test>::consume_quota(&8));
+        assert_eq!(AgentQuotas::<Test>::get(8).unwrap().stake, 40_000_000);
+    });
+}
+
+#[test]
+fn stake_snapshots_are_pruned_after_retention() {
+    let mut ext = new_test_ext();
+    ext.execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(pallet_gas_quota::Pallet::<Test>::consume_quota(&1));
+        assert_ok!(pallet_gas_quota::Pallet::<Test>::consume_quota(&2));
+        assert!(QuotaStakeSnapshot::<Test>::contains_key(0, 1));
+    });
+    // A limited `clear_prefix` walks committed keys, so each block's changes
+    // are committed before the next one runs, as on a live chain.
+    fn run_block(ext: &mut sp_io::TestExternalities, n: u64) {
+        ext.commit_all().unwrap();
+        ext.execute_with(|| {
+            GasQuota::on_initialize(n);
+        });
+    }
+
+    // Era 1 still keeps era 0 (two eras retained).
+    StakingEra::set(1);
+    run_block(&mut ext, 2);
+    ext.execute_with(|| assert!(QuotaStakeSnapshot::<Test>::contains_key(0, 1)));
+
+    // From era 2 on, era 0 is pruned one entry per block.
+    StakingEra::set(2);
+    run_block(&mut ext, 3);
+    ext.execute_with(|| {
+        assert_eq!(QuotaStakeSnapshot::<Test>::iter_prefix(0).count(), 1);
+        assert_eq!(OldestStakeSnapshotEra::<Test>::get(), 0);
+    });
+    run_block(&mut ext, 4);
+    run_block(&mut ext, 5);
+    ext.execute_with(|| {
+        assert_eq!(QuotaStakeSnapshot::<Test>::iter_prefix(0).count(), 0);
+        assert_eq!(OldestStakeSnapshotEra::<Test>::get(), 1);
+    });
+}
+
+#[test]
+fn sync_stake_requires_quota_record() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_noop!(
+            pallet_gas_quota::Pallet::<Test>::sync_stake(RuntimeOrigin::signed(1), 8),
+            pallet_gas_quota::Error::<Test>::QuotaNotInitialized
+        );
+
+        assert_ok!(pallet_gas_quota::Pallet::<Test>::consume_quota(&8));
+        AccountEightStake::set(100_000_000);
+        StakingEra::set(1);
+        assert_ok!(pallet_gas_quota::Pallet::<Test>::sync_stake(
+            RuntimeOrigin::signed(1),
+            8
+        ));
+        assert_eq!(AgentQuotas::<Test>::get(8).unwrap().stake, 100_000_000);
+    });
+}
+
+// ========== Reputation Tiers ==========
+
+#[test]
+fn reputation_tier_follows_provider_on_evaluation() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(pallet_gas_quota::Pallet::<Test>::consume_quota(&9));
+        assert_eq!(AgentQuotas::<Test>::get(9).unwrap().reputation_tier, 0);
+
+        // A verified contributor gets twice the 10 TX minimum.
+        AccountNineReputation::set(9_500);
+        assert_ok!(pallet_gas_quota::Pallet::<Test>::consume_quota(&9));
+        assert_eq!(AgentQuotas::<Test>::get(9).unwrap().reputation_tier, 2);
+        System::assert_has_event(
+            pallet_gas_quota::Event::ReputationTierUpdated { agent: 9, tier: 2 }.into(),
+        );
+        for _ in 2..15 {
+            assert_ok!(pallet_gas_quota::Pallet::<Test>::consume_quota(&9));
+        }
+        assert_eq!(pallet_gas_quota::Pallet::<Test>::pending_fee(&9, 1), 0);
+
+        // Dropping to the high tier shrinks the allowance to 15, all used.
+        AccountNineReputation::set(8_000);
+        assert_eq!(pallet_gas_quota::Pallet::<Test>::reputation_tier_of(&9), 1);
+        assert_eq!(pallet_gas_quota::Pallet::<Test>::pending_fee(&9, 1), 1_000);
+    });
+}
+
+#[test]
+fn root_tier_override_is_replaced_on_next_evaluation() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(pallet_gas_quota::Pallet::<Test>::consume_quota(&9));
+        assert_ok!(pallet_gas_quota::Pallet::<Test>::update_reputation_tier(
+            RuntimeOrigin::root(),
+            9,
+            2
+        ));
+        assert_eq!(AgentQuotas::<Test>::get(9).unwrap().reputation_tier, 2);
+
+        assert_ok!(pallet_gas_quota::Pallet::<Test>::consume_quota(&9));
+        assert_eq!(AgentQuotas::<Test>::get(9).unwrap().reputation_tier, 0);
+    });
+}
+
+// ========== Quota Delegation ==========
+
+#[test]
+fn delegated_quota_is_drawn_after_own_quota() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        // Account 8 stakes 100 $CLAW for 100 free TX and gives 60 to agent 9.
+        AccountEightStake::set(100_000_000);
+        assert_ok!(pallet_gas_quota::Pallet::<Test>::delegate_quota(
+            RuntimeOrigin::signed(8),
+            9,
+            60
+        ));
+        assert_noop!(
+            pallet_gas_quota::Pallet::<Test>::delegate_quota(RuntimeOrigin::signed(8), 10, 41),
+            pallet_gas_quota::Error::<Test>::InsufficientQuota
+        );
+        assert_eq!(pallet_gas_quota::Pallet::<Test>::pending_fee(&8, 40), 0);
+        // Only the unit past the 40 left is charged.
+        assert_eq!(pallet_gas_quota::Pallet::<Test>::pending_fee(&8, 41), 1_000);
+
+        // Agent 9 spends its own 10 free TX, then the 60 delegated ones.
+        for _ in 0..10 {
+            assert_ok!(pallet_gas_quota::Pallet::<Test>::consume_quota(&9));
+        }
+        assert_eq!(
+            pallet_gas_quota::Pallet::<Test>::delegated_remaining(&9),
+            60
+        );
+        for _ in 0..60 {
+            assert_ok!(pallet_gas_quota::Pallet::<Test>::consume_quota(&9));
+        }
+        System::assert_last_event(
+            pallet_gas_quota::Event::DelegatedQuotaUsed {
+                delegator: 8,
+                beneficiary: 9,
+                remaining: 0,
+            }
+            .into(),
+        );
+        assert_eq!(pallet_gas_quota::Pallet::<Test>::pending_fee(&9, 1), 1_000);
+
+        // The delegation refills with the next epoch.
+        System::set_block_number(14_401);
+        assert_eq!(
+            pallet_gas_quota::Pallet::<Test>::delegated_remaining(&9),
+            60
+        );
+    });
+}
+
+#[test]
+fn unbonding_delegator_scales_down_delegated_draws() {
+    use sp_staking::OnStakingUpdate;
+
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        AccountEightStake::set(100_000_000);
+        assert_ok!(pallet_gas_quota::Pallet::<Test>::delegate_quota(
+            RuntimeOrigin::signed(8),
+            9,
+            60
+        ));
+
+        // Account 8 unbonds down to 40 free TX; from the next era its
+        // delegation is cut to what that stake still backs.
+        AccountEightStake::set(40_000_000);
+        pallet_gas_quota::Pallet::<Test>::on_stake_update(
+            &8,
+            Some(sp_staking::Stake {
+                total: 100_000_000,
+                active: 100_000_000,
+            }),
+        );
+        assert_eq!(
+            pallet_gas_quota::Pallet::<Test>::delegated_remaining(&9),
+            60
+        );
+        StakingEra::set(1);
+        assert_eq!(
+            pallet_gas_quota::Pallet::<Test>::delegated_remaining(&9),
+            40
+        );
+
+        for _ in 0..50 {
+            assert_ok!(pallet_gas_quota::Pallet::<Test>::consume_quota(&9));
+        }
+        System::assert_last_event(
+            pallet_gas_quota::Event::DelegatedQuotaUsed {
+                delegator: 8,
+                beneficiary: 9,
+                remaining: 0,
+            }
+            .into(),
+        );
+        assert_eq!(pallet_gas_quota::Pallet::<Test>::pending_fee(&9, 1), 1_000);
+    });
+}
+
+#[test]
+fn delegation_limits_and_revocation() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        AccountEightStake::set(100_000_000);
+        assert_noop!(
+            pallet_gas_quota::Pallet::<Test>::delegate_quota(RuntimeOrigin::signed(8), 8, 5),
+            pallet_gas_quota::Error::<Test>::SelfDelegation
+        );
+        assert_noop!(
+            pallet_gas_quota::Pallet::<Test>::delegate_quota(RuntimeOrigin::signed(8), 9, 0),
+            pallet_gas_quota::Error::<Test>::ZeroDelegation
+        );
+        assert_ok!(pallet_gas_quota::Pallet::<Test>::delegate_quota(
+            RuntimeOrigin::signed(8),
+            9,
+            5
+        ));
+        assert_ok!(pallet_gas_quota::Pallet::<Test>::delegate_quota(
+            RuntimeOrigin::signed(8),
+            10,
+            5
+        ));
+        assert_noop!(
+            pallet_gas_quota::Pallet::<Test>::delegate_quota(RuntimeOrigin::signed(8), 11, 5),
+            pallet_gas_quota::Error::<Test>::TooManyDelegations
+        );
+        assert_noop!(
+            pallet_gas_quota::Pallet::<Test>::delegate_quota(RuntimeOrigin::signed(1), 9, 5),
+            pallet_gas_quota::Error::<Test>::AlreadyDelegated
+        );
+
+        // Updating an existing delegation replaces its size.
+        assert_ok!(pallet_gas_quota::Pallet::<Test>::delegate_quota(
+            RuntimeOrigin::signed(8),
+            9,
+            20
+        ));
+        assert_eq!(
+            pallet_gas_quota::DelegatedTotals::<Test>::get(8),
+            Some(pallet_gas_quota::DelegationTotals {
+                count: 2,
+                tx_per_day: 25,
+            })
+        );
+
+        assert_noop!(
+            pallet_gas_quota::Pallet::<Test>::revoke_delegation(RuntimeOrigin::signed(1), 9),
+            pallet_gas_quota::Error::<Test>::DelegationNotFound
+        );
+        assert_ok!(pallet_gas_quota::Pallet::<Test>::revoke_delegation(
+            RuntimeOrigin::signed(8),
+            9
+        ));
+        System::assert_last_event(
+            pallet_gas_quota::Event::DelegationRevoked {
+                delegator: 8,
+                beneficiary: 9,
+            }
+            .into(),
+        );
+        assert_eq!(pallet_gas_quota::Pallet::<Test>::delegated_remaining(&9), 0);
+        assert_ok!(pallet_gas_quota::Pallet::<Test>::revoke_delegation(
+            RuntimeOrigin::signed(8),
+            10
+        ));
+        assert!(pallet_gas_quota::DelegatedTotals::<Test>::get(8).is_none());
+        assert_eq!(pallet_gas_quota::Pallet::<Test>::pending_fee(&8, 100), 0);
+    });
+}
+
+#[test]
+fn departing_account_delegations_are_revoked_in_batches() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        AccountEightStake::set(100_000_000);
+        for beneficiary in [9, 10] {
+            assert_ok!(pallet_gas_quota::Pallet::<Test>::delegate_quota(
+                RuntimeOrigin::signed(8),
+                beneficiary,
+                5
+            ));
+        }
+        assert_ok!(pallet_gas_quota::Pallet::<Test>::delegate_quota(
+            RuntimeOrigin::signed(1),
+            8,
+            5
+        ));
+
+        // The delegation 8 receives goes first, then those it gives.
+        assert_eq!(GasQuota::revoke_delegations_of(&8, 2), (2, false));
+        assert!(pallet_gas_quota::DelegatorOf::<Test>::get(8).is_none());
+        assert_eq!(GasQuota::revoke_delegations_of(&8, 2), (1, true));
+        assert!(pallet_gas_quota::DelegatedTotals::<Test>::get(8).is_none());
+        assert!(pallet_gas_quota::DelegatorOf::<Test>::get(9).is_none());
+        assert!(pallet_gas_quota::DelegatorOf::<Test>::get(10).is_none());
+    });
+}
+
+// ========== Quota Queries ==========
+
+#[test]
+fn quota_queries_report_remaining_free_tx_and_fees() {
+    use pallet_gas_quota::runtime_api::FreeTxStatus;
+
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        for _ in 0..3 {
+            assert_ok!(pallet_gas_quota::Pallet::<Test>::consume_quota(&9));
+        }
+        assert_eq!(
+            pallet_gas_quota::Pallet::<Test>::remaining_free_tx(&9),
+            FreeTxStatus {
+                remaining: 7,
+                free_quota: 10,
+            }
+        );
+        assert_eq!(pallet_gas_quota::Pallet::<Test>::projected_fee(&9, 7), 0);
+        assert_eq!(
+            pallet_gas_quota::Pallet::<Test>::projected_fee(&9, 10),
+            3_000
+        );
+
+        // Delegated quota counts towards the free transactions left.
+        AccountEightStake::set(100_000_000);
+        assert_ok!(pallet_gas_quota::Pallet::<Test>::delegate_quota(
+            RuntimeOrigin::signed(8),
+            9,
+            20
+        ));
+        assert_eq!(
+            pallet_gas_quota::Pallet::<Test>::remaining_free_tx(&9),
+            FreeTxStatus {
+                remaining: 27,
+                free_quota: 30,
+            }
+        );
+
+        assert_eq!(
+            pallet_gas_quota::Pallet::<Test>::remaining_free_tx(&1).remaining,
+            10
+        );
+        assert_eq!(
+            pallet_gas_quota::Pallet::<Test>::quota_reset_block(),
+            14_400
+        );
+        System::set_block_number(14_400);
+        assert_eq!(
+            pallet_gas_quota::Pallet::<Test>::quota_reset_block(),
+            28_800
+        );
+    });
+}
+
+#[test]
+fn collected_fees_are_swept_to_treasury_at_threshold() {
+    new_test_ext().execute_with(|| {
+        frame_system::Pallet::<Test>::set_block_number(1);
+        for _ in 0..11 {
+            assert_ok!(pallet_gas_quota::Pallet::<Test>::consume_quota(&4));
+        }
+        let pot = GasQuota::fee_pot_account();
+        assert_eq!(Balances::free_balance(pot), 1_000);
+
+        // Below the 2,000 threshold: nothing moves.
+        GasQuota::on_initialize(2);
+        assert_eq!(Balances::free_balance(100), 0);
+
+        assert_ok!(pallet_gas_quota::Pallet::<Test>::consume_quota(&4));
+        GasQuota::on_initialize(3);
+        // The existential deposit keeps the pot alive.
+        assert_eq!(Balances::free_balance(100), 1_999);
+        assert_eq!(Balances::free_balance(pot), 1);
+        assert_eq!(pallet_gas_quota::TotalFeesSwept::<Test>::get(), 1_999);
+        let stats = pallet_gas_quota::EpochFeeStats::<Test>::get(0);
+        assert_eq!((stats.collected, stats.swept), (2_000, 1_999));
+        frame_system::Pallet::<Test>::assert_last_event(
+            pallet_gas_quota::Event::FeesSweptToTreasury {
+                amount: 1_999,
+                epoch: 0,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn suspended_agents_pay_for_every_transaction() {
+    new_test_ext().execute_with(|| {
+        frame_system::Pallet::<Test>::set_block_number(1);
+        assert_noop!(
+            GasQuota::suspend_quota(RuntimeOrigin::signed(1), 4, 100),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_noop!(
+            GasQuota::suspend_quota(RuntimeOrigin::root(), 4, 1),
+            pallet_gas_quota::Error::<Test>::SuspensionInPast
+        );
+        assert_ok!(GasQuota::suspend_quota(RuntimeOrigin::root(), 4, 100));
+        assert_eq!(GasQuota::remaining_free_tx(&4).remaining, 0);
+
+        // The first transaction of the day is charged.
+        assert_ok!(pallet_gas_quota::Pallet::<Test>::consume_quota(&4));
+        assert_eq!(pallet_balances::Pallet::<Test>::free_balance(4), 4_000);
+
+        assert_ok!(GasQuota::restore_quota(RuntimeOrigin::root(), 4));
+        assert_ok!(pallet_gas_quota::Pallet::<Test>::consume_quota(&4));
+        assert_eq!(pallet_balances::Pallet::<Test>::free_balance(4), 4_000);
+        assert_noop!(
+            GasQuota::restore_quota(RuntimeOrigin::root(), 4),
+            pallet_gas_quota::Error::<Test>::NotSuspended
+        );
+
+        // Suspensions lapse on their own.
+        assert_ok!(GasQuota::suspend_quota(RuntimeOrigin::root(), 4, 10));
+        frame_system::Pallet::<Test>::set_block_number(10);
+        assert!(!GasQuota::is_quota_suspended(&4));
+    });
+}
+
+#[test]
+fn sponsorship_covers_fees_up_to_daily_cap() {
+    new_test_ext().execute_with(|| {
+        frame_system::Pallet::<Test>::set_block_number(1);
+        assert_noop!(
+            GasQuota::sponsor_account(RuntimeOrigin::signed(4), 4, 1_000),
+            pallet_gas_quota::Error::<Test>::SelfSponsorship
+        );
+        assert_ok!(GasQuota::sponsor_account(
+            RuntimeOrigin::signed(2),
+            4,
+            1_000
+        ));
+        // Nothing is covered until the beneficiary accepts.
+        assert!(pallet_gas_quota::Sponsorships::<Test>::get(4).is_none());
+        assert_noop!(
+            GasQuota::accept_sponsorship(RuntimeOrigin::signed(4), 3),
+            pallet_gas_quota::Error::<Test>::SponsorshipNotFound
+        );
+        assert_ok!(GasQuota::accept_sponsorship(RuntimeOrigin::signed(4), 2));
+
+        // Exhaust the free quota, then pay one over-quota fee via the sponsor.
+        for _ in 0..10 {
+            assert_ok!(pallet_gas_quota::Pallet::<Test>::consume_quota(&4));
+        }
+        assert_ok!(pallet_gas_quota::Pallet::<Test>::consume_quota(&4));
+        assert_eq!(Balances::free_balance(4), 5_000);
+        assert_eq!(Balances::free_balance(2), 1_000_000_000 - 1_000);
+        frame_system::Pallet::<Test>::assert_last_event(
+            pallet_gas_quota::Event::FeeCharged {
+                agent: 4,
+                amount: 1_000,
+            }
+            .into(),
+        );
+        assert_eq!(
+            pallet_gas_quota::Sponsorships::<Test>::get(4)
+                .unwrap()
+                .spent,
+            1_000
+        );
+
+        // The cap is spent: the next fee falls back to the sender.
+        assert_ok!(pallet_gas_quota::Pallet::<Test>::consume_quota(&4));
+        assert_eq!(Balances::free_balance(4), 4_000);
+
+        assert_noop!(
+            GasQuota::revoke_sponsorship(RuntimeOrigin::signed(3), 4),
+            pallet_gas_quota::Error::<Test>::SponsorshipNotFound
+        );
+        assert_ok!(GasQuota::revoke_sponsorship(RuntimeOrigin::signed(2), 4));
+        assert!(pallet_gas_quota::Sponsorships::<Test>::get(4).is_none());
+    });
+}
+
+#[test]
+fn unaccepted_offers_cannot_displace_a_sponsor() {
+    new_test_ext().execute_with(|| {
+        frame_system::Pallet::<Test>::set_block_number(1);
+        assert_ok!(GasQuota::sponsor_account(
+            RuntimeOrigin::signed(2),
+            4,
+            1_000
+        ));
+        assert_ok!(GasQuota::accept_sponsorship(RuntimeOrigin::signed(4), 2));
+
+        // A stranger's tiny offer changes nothing until accepted.
+        assert_ok!(GasQuota::sponsor_account(RuntimeOrigin::signed(3), 4, 1));
+        assert_eq!(
+            pallet_gas_quota::Sponsorships::<Test>::get(4)
+                .unwrap()
+                .sponsor,
+            2
+        );
+
+        // The current sponsor adjusts its cap without a new acceptance.
+        assert_ok!(GasQuota::sponsor_account(
+            RuntimeOrigin::signed(2),
+            4,
+            2_000
+        ));
+        assert_eq!(
+            pallet_gas_quota::Sponsorships::<Test>::get(4)
+                .unwrap()
+                .max_per_day,
+            2_000
+        );
+
+        assert_ok!(GasQuota::revoke_sponsorship(RuntimeOrigin::signed(3), 4));
+        assert!(pallet_gas_quota::SponsorshipOffers::<Test>::get(4, 3).is_none());
+        assert_noop!(
+            GasQuota::accept_sponsorship(RuntimeOrigin::signed(4), 3),
+            pallet_gas_quota::Error::<Test>::SponsorshipNotFound
+        );
+    });
+}
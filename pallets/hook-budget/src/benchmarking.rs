@@ -0,0 +1,36 @@
+//! Benchmarking for pallet-hook-budget.
+//!
+//! The registered hooks are benchmarked by their own pallets; this covers the
+//! bookkeeping `on_initialize` adds on top of them.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::v2::*;
+use frame_support::weights::Weight;
+
+#[benchmarks]
+mod benchmarks {
+    use super::*;
+
+    /// Worst case: a backlog streak ends, so `BacklogSince` is taken and
+    /// `BacklogCleared` emitted.
+    #[benchmark]
+    fn note_usage() {
+        let now = frame_system::Pallet::<T>::block_number();
+        BacklogSince::<T>::put(now);
+        let usage = HookUsage {
+            used: Weight::zero(),
+            deferred: false,
+        };
+
+        #[block]
+        {
+            Pallet::<T>::note_usage(now, usage);
+        }
+
+        assert!(BacklogSince::<T>::get().is_none());
+    }
+
+    impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
+}
@@ -0,0 +1,143 @@
+//! # Hook Budget Pallet
+//!
+//! Runs deferrable per-block pallet work under one shared weight budget.
+//!
+//! ## Overview
+//!
+//! Pallets that sweep queues at the start of a block implement
+//! [`BudgetedHook`] instead of `on_initialize`. The runtime lists them in
+//! `Config::Hooks` as a tuple in priority order, and this pallet's
+//! `on_initialize` runs the tuple under `Config::HookBudget` (normally
+//! `AVERAGE_ON_INITIALIZE_RATIO` of the block). Earlier hooks take what they
+//! need; later ones get what is left, and anything a hook could not reach
+//! stays queued for the next block.
+//!
+//! Work that must not be delayed (session rotation, anything consensus
+//! depends on) keeps its own `on_initialize` and is not registered here.
+//!
+//! ```ignore
+//! impl pallet_hook_budget::Config for Runtime {
+//!     type RuntimeEvent = RuntimeEvent;
+//!     type Hooks = (EmergencyPause, ServiceMarket);
+//!     type HookBudget = HookBudget;
+//!     type WeightInfo = ();
+//! }
+//! ```
+//!
+//! ## Back-pressure
+//!
+//! Hooks report when they left due work behind for lack of budget. The
+//! pallet emits [`Event::WorkDeferred`] for such blocks and remembers when
+//! the streak started in [`BacklogSince`]; once a block clears everything
+//! that was due it emits [`Event::BacklogCleared`]. Monitoring can alert on
+//! streaks that last too long.
+//!
+//! ## Storage
+//!
+//! - [`LastUsage`] — weight the hooks used in the latest block
+//! - [`BacklogSince`] — first block of the current run of blocks that deferred work
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(deprecated, clippy::let_unit_value)]
+
+pub use clawchain_primitives::{BudgetedHook, HookUsage};
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+pub mod weights;
+
+#[cfg(feature = "runtime-benchmarks")]
+pub mod benchmarking;
+
+#[cfg(test)]
+pub mod mock;
+#[cfg(test)]
+mod tests;
+
+#[frame_support::pallet]
+pub mod pallet {
+    use super::*;
+    use frame_support::pallet_prelude::*;
+    use frame_system::pallet_prelude::*;
+
+    // =========================================================================
+    // Config
+    // =========================================================================
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        /// The overarching event type.
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+        /// The budgeted hooks, highest priority first.
+        type Hooks: BudgetedHook<BlockNumberFor<Self>>;
+
+        /// Total weight the hooks may use per block.
+        #[pallet::constant]
+        type HookBudget: Get<Weight>;
+
+        /// Weight of this pallet's own bookkeeping.
+        type WeightInfo: WeightInfo;
+    }
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(_);
+
+    // =========================================================================
+    // Storage
+    // =========================================================================
+
+    /// Weight the hooks used in the latest block.
+    #[pallet::storage]
+    pub type LastUsage<T: Config> = StorageValue<_, Weight, ValueQuery>;
+
+    /// First block of the current run of blocks in which the hooks deferred
+    /// due work. `None` when the latest block cleared everything.
+    #[pallet::storage]
+    pub type BacklogSince<T: Config> = StorageValue<_, BlockNumberFor<T>, OptionQuery>;
+
+    // =========================================================================
+    // Events
+    // =========================================================================
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// The hooks ran out of budget and left due work for a later block.
+        WorkDeferred { used: Weight },
+        /// The hooks cleared all due work after a run of deferring blocks.
+        BacklogCleared { since: BlockNumberFor<T> },
+    }
+
+    // =========================================================================
+    // Hooks
+    // =========================================================================
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        /// Run the registered hooks under `HookBudget`.
+        fn on_initialize(now: BlockNumberFor<T>) -> Weight {
+            let budget = T::HookBudget::get();
+            let usage = T::Hooks::run(now, budget);
+            Self::note_usage(now, usage);
+            usage.used.saturating_add(T::WeightInfo::note_usage())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Record what the hooks used in block `now` and track the backlog.
+        pub(crate) fn note_usage(now: BlockNumberFor<T>, usage: HookUsage) {
+            let HookUsage { used, deferred } = usage;
+            LastUsage::<T>::put(used);
+
+            if deferred {
+                if BacklogSince::<T>::get().is_none() {
+                    BacklogSince::<T>::put(now);
+                }
+                Self::deposit_event(Event::WorkDeferred { used });
+            } else if let Some(since) = BacklogSince::<T>::take() {
+                Self::deposit_event(Event::BacklogCleared { since });
+            }
+        }
+    }
+}
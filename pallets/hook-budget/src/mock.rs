@@ -0,0 +1,104 @@
+//! Mock runtime for pallet-hook-budget unit tests.
+//!
+//! Two queue-draining hooks stand in for pallets: each item costs 10
+//! ref-time, and the urgent queue is registered ahead of the routine one.
+
+use crate as pallet_hook_budget;
+use crate::{BudgetedHook, HookUsage};
+use core::cell::RefCell;
+use frame_support::{derive_impl, parameter_types, weights::Weight};
+use sp_runtime::{traits::IdentityLookup, BuildStorage};
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+    pub enum Test {
+        System: frame_system,
+        HookBudget: pallet_hook_budget,
+    }
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig as frame_system::DefaultConfig)]
+impl frame_system::Config for Test {
+    type Block = Block;
+    type AccountId = u64;
+    type Lookup = IdentityLookup<Self::AccountId>;
+}
+
+pub const ITEM_WEIGHT: u64 = 10;
+
+thread_local! {
+    static URGENT: RefCell<u32> = const { RefCell::new(0) };
+    static ROUTINE: RefCell<u32> = const { RefCell::new(0) };
+}
+
+/// Drain up to `limit` worth of items from `queue`.
+fn drain(queue: &'static std::thread::LocalKey<RefCell<u32>>, limit: Weight) -> HookUsage {
+    queue.with(|q| {
+        let mut q = q.borrow_mut();
+        let fits = (limit.ref_time() / ITEM_WEIGHT).min(u64::from(*q)) as u32;
+        *q -= fits;
+        HookUsage {
+            used: Weight::from_parts(u64::from(fits) * ITEM_WEIGHT, 0),
+            deferred: *q > 0,
+        }
+    })
+}
+
+pub struct Urgent;
+impl BudgetedHook<u64> for Urgent {
+    fn run(_: u64, limit: Weight) -> HookUsage {
+        drain(&URGENT, limit)
+    }
+}
+
+pub struct Routine;
+impl BudgetedHook<u64> for Routine {
+    fn run(_: u64, limit: Weight) -> HookUsage {
+        drain(&ROUTINE, limit)
+    }
+}
+
+/// Queue `urgent` and `routine` more items.
+pub fn enqueue(urgent: u32, routine: u32) {
+    URGENT.with(|q| *q.borrow_mut() += urgent);
+    ROUTINE.with(|q| *q.borrow_mut() += routine);
+}
+
+/// Items still queued, `(urgent, routine)`.
+pub fn queued() -> (u32, u32) {
+    (URGENT.with(|q| *q.borrow()), ROUTINE.with(|q| *q.borrow()))
+}
+
+parameter_types! {
+    /// Five items per block.
+    pub Budget: Weight = Weight::from_parts(5 * ITEM_WEIGHT, 0);
+}
+
+impl pallet_hook_budget::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type Hooks = (Urgent, Routine);
+    type HookBudget = Budget;
+    type WeightInfo = ();
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    URGENT.with(|q| *q.borrow_mut() = 0);
+    ROUTINE.with(|q| *q.borrow_mut() = 0);
+    let t = frame_system::GenesisConfig::<Test>::default()
+        .build_storage()
+        .unwrap();
+    let mut ext = sp_io::TestExternalities::new(t);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
+
+/// Advance to block `n`, running the pallet's `on_initialize`.
+pub fn roll_to(n: u64) {
+    use frame_support::traits::Hooks;
+    let current = System::block_number();
+    for i in (current + 1)..=n {
+        System::set_block_number(i);
+        HookBudget::on_initialize(i);
+    }
+}
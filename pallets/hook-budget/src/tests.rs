@@ -0,0 +1,45 @@
+//! Unit tests for pallet-hook-budget.
+
+use crate::{mock::*, BacklogSince, Event, LastUsage};
+use frame_support::weights::Weight;
+
+#[test]
+fn hooks_share_one_budget_in_priority_order() {
+    new_test_ext().execute_with(|| {
+        enqueue(3, 4);
+        roll_to(2);
+        // Urgent drains first; routine gets the two remaining slots.
+        assert_eq!(queued(), (0, 2));
+        assert_eq!(LastUsage::<Test>::get(), Weight::from_parts(50, 0));
+        assert_eq!(BacklogSince::<Test>::get(), Some(2));
+        System::assert_last_event(
+            Event::WorkDeferred {
+                used: Weight::from_parts(50, 0),
+            }
+            .into(),
+        );
+
+        // New urgent work still goes first; routine waits another block.
+        enqueue(5, 0);
+        roll_to(3);
+        assert_eq!(queued(), (0, 2));
+        assert_eq!(BacklogSince::<Test>::get(), Some(2));
+
+        roll_to(4);
+        assert_eq!(queued(), (0, 0));
+        assert_eq!(LastUsage::<Test>::get(), Weight::from_parts(20, 0));
+        assert_eq!(BacklogSince::<Test>::get(), None);
+        System::assert_last_event(Event::BacklogCleared { since: 2 }.into());
+    });
+}
+
+#[test]
+fn blocks_within_budget_emit_nothing() {
+    new_test_ext().execute_with(|| {
+        enqueue(2, 3);
+        roll_to(2);
+        assert_eq!(queued(), (0, 0));
+        assert_eq!(BacklogSince::<Test>::get(), None);
+        assert!(System::events().is_empty());
+    });
+}
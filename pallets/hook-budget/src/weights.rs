@@ -0,0 +1,20 @@
+//! Weight definitions for pallet-hook-budget.
+//!
+//! These are placeholder weights. Real weights should be generated by running
+//! the Substrate frame benchmarking suite against this pallet.
+
+use frame_support::weights::{constants::RocksDbWeight, Weight};
+
+/// Weight functions needed for `pallet_hook_budget`.
+pub trait WeightInfo {
+    /// Bookkeeping after the hooks ran: `LastUsage`, `BacklogSince` and the
+    /// backlog event. The hooks' own work is reported by the hooks.
+    fn note_usage() -> Weight;
+}
+
+/// Conservative default: one read, two writes and an event.
+impl WeightInfo for () {
+    fn note_usage() -> Weight {
+        Weight::from_parts(10_000, 0).saturating_add(RocksDbWeight::get().reads_writes(1, 2))
+    }
+}
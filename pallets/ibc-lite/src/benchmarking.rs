@@ -7,7 +7,82 @@
 
 use super::*;
 use frame_benchmarking::v2::*;
+use frame_support::traits::Currency;
 use frame_system::RawOrigin;
+use sp_runtime::traits::Saturating;
+
+/// Park a max-sized packet in `channel-0`'s dead-letter queue at sequence 1.
+fn insert_dead_letter<T: Config>() -> Vec<u8> {
+    let channel_id: ChannelId<T> = b"channel-0".to_vec().try_into().unwrap();
+    let payload = PacketPayload::<T>::Raw(
+        vec![1u8; T::MaxPayloadLen::get() as usize]
+            .try_into()
+            .unwrap_or_else(|_| BoundedVec::default()),
+    );
+    let packet = Packet::<T> {
+        sequence: 1,
+        src_channel_id: b"benchmark-channel-0".to_vec().try_into().unwrap(),
+        dst_channel_id: channel_id.clone(),
+        dst_chain_id: b"benchmark-chain".to_vec().try_into().unwrap(),
+        src_agent_id: None,
+        dst_agent_id: None,
+        payload,
+        timeout_height: 10000u32.into(),
+        created_at: 100u32.into(),
+    };
+    DeadLetters::<T>::insert(
+        &channel_id,
+        1u64,
+        DeadLetter {
+            packet,
+            reason: DispatchError::Other("benchmark"),
+            failed_at: 1u32.into(),
+        },
+    );
+    DeadLetterCount::<T>::insert(&channel_id, 1);
+    channel_id.to_vec()
+}
+
+/// Open `channel-0` and return a max-sized inbound packet for sequence 1
+/// together with a submitter funded for the open-relaying bond.
+fn open_submission_setup<T: Config>() -> Result<(T::AccountId, Packet<T>), BenchmarkError> {
+    Pallet::<T>::open_channel(
+        RawOrigin::Root.into(),
+        b"benchmark-chain".to_vec(),
+        b"benchmark-channel-0".to_vec(),
+        Vec::new(),
+    )?;
+    let channel_id: ChannelId<T> = b"channel-0".to_vec().try_into().unwrap();
+    Channels::<T>::mutate(&channel_id, |maybe_channel| {
+        if let Some(channel) = maybe_channel {
+            channel.state = ChannelState::Open;
+        }
+    });
+
+    let payload = PacketPayload::<T>::Raw(
+        vec![1u8; T::MaxPayloadLen::get() as usize]
+            .try_into()
+            .unwrap_or_else(|_| BoundedVec::default()),
+    );
+    let packet = Packet::<T> {
+        sequence: 1,
+        src_channel_id: b"benchmark-channel-0".to_vec().try_into().unwrap(),
+        dst_channel_id: channel_id,
+        dst_chain_id: b"benchmark-chain".to_vec().try_into().unwrap(),
+        src_agent_id: None,
+        dst_agent_id: None,
+        payload,
+        timeout_height: 10000u32.into(),
+        created_at: 100u32.into(),
+    };
+
+    let submitter: T::AccountId = account("submitter", 0, 0);
+    let funds = T::OpenSubmissionBond::get()
+        .saturating_mul(10u32.into())
+        .saturating_add(T::Currency::minimum_balance());
+    T::Currency::make_free_balance_be(&submitter, funds);
+    Ok((submitter, packet))
+}
 
 #[benchmarks]
 mod benchmarks {
@@ -24,6 +99,7 @@ mod benchmarks {
             RawOrigin::Root,
             counterparty_chain_id,
             counterparty_channel_id,
+            Vec::new(),
         );
     }
 
@@ -37,6 +113,7 @@ mod benchmarks {
             RawOrigin::Root.into(),
             counterparty_chain_id,
             counterparty_channel_id,
+            Vec::new(),
         )?;
 
         let channel_id = b"channel-0".to_vec();
@@ -55,6 +132,7 @@ mod benchmarks {
             RawOrigin::Root.into(),
             counterparty_chain_id,
             counterparty_channel_id,
+            Vec::new(),
         )?;
 
         let channel_id = b"channel-0".to_vec();
@@ -78,6 +156,7 @@ mod benchmarks {
             RawOrigin::Root.into(),
             counterparty_chain_id,
             counterparty_channel_id,
+            Vec::new(),
         )?;
 
         let channel_id = b"channel-0".to_vec();
@@ -114,6 +193,7 @@ mod benchmarks {
             RawOrigin::Root.into(),
             counterparty_chain_id,
             counterparty_channel_id,
+            Vec::new(),
         )?;
 
         let channel_id: ChannelId<T> = b"channel-0".to_vec().try_into().unwrap();
@@ -159,6 +239,7 @@ mod benchmarks {
             RawOrigin::Root.into(),
             counterparty_chain_id,
             counterparty_channel_id,
+            Vec::new(),
         )?;
 
         let channel_id = b"channel-0".to_vec();
@@ -206,6 +287,7 @@ mod benchmarks {
             RawOrigin::Root.into(),
             counterparty_chain_id,
             counterparty_channel_id,
+            Vec::new(),
         )?;
 
         let channel_id = b"channel-0".to_vec();
@@ -246,24 +328,171 @@ mod benchmarks {
         _(RawOrigin::Root, relayer);
     }
 
-    /// Benchmark for registering a cross-chain agent.
+    /// Benchmark for consuming a retained ack payload.
     #[benchmark]
-    fn register_cross_chain_agent() {
-        // Setup: add a relayer
+    fn consume_ack_payload() {
+        let counterparty_chain_id = b"benchmark-chain".to_vec();
+        let counterparty_channel_id = b"benchmark-channel-0".to_vec();
+        Pallet::<T>::open_channel(
+            RawOrigin::Root.into(),
+            counterparty_chain_id,
+            counterparty_channel_id,
+            Vec::new(),
+        )?;
+
+        let channel_id = b"channel-0".to_vec();
+        let caller: T::AccountId = account("caller", 0, 0);
+
+        Pallet::<T>::send_packet(
+            RawOrigin::Signed(caller.clone()).into(),
+            channel_id.clone(),
+            b"benchmark-chain".to_vec(),
+            b"benchmark-channel-0".to_vec(),
+            None,
+            PacketPayload::<T>::Raw(BoundedVec::default()),
+        )?;
+
         let relayer: T::AccountId = account("relayer", 0, 0);
         Pallet::<T>::add_relayer(RawOrigin::Root.into(), relayer.clone())?;
 
-        let chain_id = b"remote-chain".to_vec();
-        let remote_agent_id = b"remote-agent-1".to_vec();
-        let local_agent_id = 1u64;
+        // Worst case: maximum-length ack data retained for the sender
+        let ack = PacketPayload::<T>::Ack {
+            success: true,
+            error_code: None,
+            data: vec![1u8; T::MaxPayloadLen::get() as usize]
+                .try_into()
+                .unwrap_or_else(|_| BoundedVec::default()),
+        };
+        Pallet::<T>::acknowledge_packet(
+            RawOrigin::Signed(relayer).into(),
+            channel_id.clone(),
+            1u64,
+            ack,
+        )?;
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller), channel_id, 1u64);
+    }
+
+    /// Benchmark for setting a channel's relayer whitelist.
+    #[benchmark]
+    fn set_channel_relayers() {
+        Pallet::<T>::open_channel(
+            RawOrigin::Root.into(),
+            b"benchmark-chain".to_vec(),
+            b"benchmark-channel-0".to_vec(),
+            Vec::new(),
+        )?;
+        let mut relayers = Vec::new();
+        for i in 0..T::MaxRelayers::get() {
+            let relayer: T::AccountId = account("relayer", i, 0);
+            Pallet::<T>::add_relayer(RawOrigin::Root.into(), relayer.clone())?;
+            relayers.push(relayer);
+        }
+
+        #[extrinsic_call]
+        _(RawOrigin::Root, b"channel-0".to_vec(), relayers);
+    }
+
+    /// Benchmark for retrying a dead-lettered packet.
+    #[benchmark]
+    fn retry_dead_letter() {
+        let channel_id = insert_dead_letter::<T>();
+
+        #[extrinsic_call]
+        _(RawOrigin::Root, channel_id, 1u64);
+    }
+
+    /// Benchmark for discarding a dead-lettered packet.
+    #[benchmark]
+    fn discard_dead_letter() {
+        let channel_id = insert_dead_letter::<T>();
+
+        #[extrinsic_call]
+        _(RawOrigin::Root, channel_id, 1u64);
+    }
+
+    /// Benchmark for a bonded packet submission.
+    #[benchmark]
+    fn submit_packet_bonded() -> Result<(), BenchmarkError> {
+        let (submitter, packet) = open_submission_setup::<T>()?;
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(submitter), packet);
+
+        Ok(())
+    }
+
+    /// Benchmark for challenging a bonded submission with a fraud proof.
+    #[benchmark]
+    fn challenge_submission() -> Result<(), BenchmarkError> {
+        use crate::traits::FraudProofVerifier;
+
+        let (submitter, packet) = open_submission_setup::<T>()?;
+        let proof = T::FraudProofVerifier::valid_proof(
+            b"benchmark-chain",
+            Pallet::<T>::packet_commitment(&packet),
+        );
+        Pallet::<T>::submit_packet_bonded(RawOrigin::Signed(submitter).into(), packet)?;
+        let challenger: T::AccountId = account("challenger", 0, 0);
 
         #[extrinsic_call]
         _(
-            RawOrigin::Signed(relayer),
-            chain_id,
-            remote_agent_id,
-            local_agent_id,
+            RawOrigin::Signed(challenger),
+            b"channel-0".to_vec(),
+            1u64,
+            proof,
         );
+
+        Ok(())
+    }
+
+    /// Benchmark for replacing a counterparty chain's signing committee.
+    #[benchmark]
+    fn set_counterparty_signers() {
+        let signers: Vec<sp_core::sr25519::Public> = (0..T::MaxRelayers::get())
+            .map(|i| sp_core::sr25519::Public::from_raw([i as u8; 32]))
+            .collect();
+
+        #[extrinsic_call]
+        _(RawOrigin::Root, b"benchmark-chain".to_vec(), signers);
+    }
+
+    /// Benchmark for finalizing an unchallenged bonded submission.
+    #[benchmark]
+    fn finalize_submission() -> Result<(), BenchmarkError> {
+        let (submitter, packet) = open_submission_setup::<T>()?;
+        Pallet::<T>::submit_packet_bonded(RawOrigin::Signed(submitter.clone()).into(), packet)?;
+        let now = frame_system::Pallet::<T>::block_number();
+        frame_system::Pallet::<T>::set_block_number(
+            now.saturating_add(T::ChallengePeriod::get().into()),
+        );
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(submitter), b"channel-0".to_vec(), 1u64);
+
+        Ok(())
+    }
+
+    /// Benchmark for pruning an expired attested agent mapping.
+    #[benchmark]
+    fn prune_cross_chain_agent() {
+        let chain_id = b"remote-chain".to_vec();
+        let remote_agent_id = b"remote-agent-1".to_vec();
+        let bounded_chain_id: ChainId<T> = chain_id.clone().try_into().unwrap();
+        let bounded_remote_agent_id: RemoteAgentId<T> = remote_agent_id.clone().try_into().unwrap();
+        CrossChainAgentMap::<T>::insert(&bounded_chain_id, &bounded_remote_agent_id, 1u64);
+        CrossChainAgentExpiry::<T>::insert(
+            &bounded_chain_id,
+            &bounded_remote_agent_id,
+            BlockNumberFor::<T>::from(1u32),
+        );
+        frame_system::Pallet::<T>::set_block_number(2u32.into());
+
+        let caller: T::AccountId = account("caller", 0, 0);
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller), chain_id, remote_agent_id);
     }
 
     impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Runtime);
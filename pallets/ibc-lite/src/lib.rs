@@ -19,13 +19,67 @@
 //! - `receive_packet` - Receive a packet from a relayer
 //! - `acknowledge_packet` - Acknowledge a packet (relayer only)
 //! - `timeout_packet` - Timeout an unacknowledged packet
+//! - `retry_dead_letter` - Re-run application handling for a dead-lettered packet
+//! - `discard_dead_letter` - Drop a dead-lettered packet
+//!
+//! An inbound packet whose `T::IbcApplication::on_recv` handler fails is
+//! still marked received, but is parked in the channel's bounded dead-letter
+//! queue with the failure reason instead of being dropped. The queue is
+//! readable through [`runtime_api::IbcLiteApi`].
+//!
+//! ### Open Relaying
+//! - `submit_packet_bonded` - Submit an inbound packet as any account, with a bond
+//! - `challenge_submission` - Challenge a bonded submission within its window
+//! - `finalize_submission` - Receive an unchallenged submission after its window
+//!
+//! Any account may relay a packet by reserving `OpenSubmissionBond`. The
+//! packet is held for `ChallengePeriod` blocks, during which anyone
+//! holding a fraud proof accepted by `T::FraudProofVerifier` can challenge
+//! it; the submitter's bond then goes to the challenger. Unchallenged
+//! submissions are received as if delivered by a trusted relayer, and the
+//! bond is returned. A submission that can no longer be received when it is
+//! finalized (e.g. its channel closed) is dropped and the bond still returned.
+//!
+//! [`CounterpartySignerSet`] is a `FraudProofVerifier` and
+//! `AttestationVerifier` backed by a per-chain
+//! committee of sr25519 keys set with `set_counterparty_signers`: a proof is
+//! a list of committee signatures, and a strict majority must sign.
 //!
 //! ### Relayer Management
 //! - `add_relayer` - Add a trusted relayer
 //! - `remove_relayer` - Remove a trusted relayer
+//! - `set_channel_relayers` - Restrict a channel to a subset of trusted relayers
+//!
+//! A channel with a relayer whitelist (set at `open_channel` or later by
+//! `RelayerManagerOrigin`) only accepts receives, acks and close confirmations
+//! from the listed relayers, so one compromised relayer key cannot act on
+//! every channel. Channels without a whitelist accept any trusted relayer.
+//!
+//! ### Primary Relayer Rotation
+//!
+//! To stop relayers racing for the same packet, each inbound sequence has a
+//! primary relayer, drawn deterministically from the channel's eligible
+//! relayers by hashing (channel, sequence) and weighting by
+//! `T::RelayerStake` (uniformly when no relayer reports stake). The first
+//! time a backup relayer offers a packet, the block is recorded and the call
+//! succeeds without receiving it; for `RelayerExclusivityWindow` blocks from
+//! then only the primary may deliver it, and after that any eligible relayer
+//! may. The window is measured in local blocks, never from the
+//! relayer-supplied `created_at`. When a backup delivers, the primary is
+//! still paid `PrimaryRelayerCompensation` from `RelayerCompensationSource`,
+//! if that account can afford it.
 //!
 //! ### Cross-Chain Agents
-//! - `register_cross_chain_agent` - Register a cross-chain agent mapping
+//! - `prune_cross_chain_agent` - Remove a mapping whose attestation expired
+//!
+//! Mappings are only imported by receiving an `AgentAttestation` packet.
+//! The remote registry attestation is checked by `T::AttestationVerifier`
+//! against the channel's counterparty chain before the mapping is created,
+//! and the mapping expires `AttestationValidityBlocks` later unless a fresh
+//! attestation refreshes it. A mapping without an expiry is treated as
+//! expired. An attestation that fails to import does not block the channel:
+//! the packet is still received, with a `Failed` receipt and a
+//! `PacketErrorAcknowledged` event the relayer returns to the sender chain.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(deprecated, clippy::let_unit_value)]
@@ -34,6 +88,7 @@ extern crate alloc;
 
 pub use pallet::*;
 
+pub mod runtime_api;
 pub mod traits;
 pub mod types;
 pub mod weights;
@@ -47,7 +102,9 @@ mod tests;
 mod benchmarking;
 
 use alloc::{format, vec::Vec};
+use clawchain_primitives::EventBytes;
 use frame_support::pallet_prelude::*;
+use frame_support::traits::{BalanceStatus, ReservableCurrency};
 use frame_system::pallet_prelude::*;
 use sp_core::H256;
 use sp_std::prelude::*;
@@ -57,12 +114,15 @@ pub mod pallet {
     use super::*;
     use crate::weights::WeightInfo;
     use sp_runtime::traits::Saturating;
-    use traits::AgentRegistryInterface;
+    use traits::{
+        AgentRegistryInterface, AttestationVerifier, FraudProofVerifier, IbcApplication,
+        RelayerStake,
+    };
 
     // Import types from the types module
     pub use crate::types::{
-        AckStatus, AgentId, ChainId, ChannelId, ChannelInfo, ChannelState, Packet, PacketPayload,
-        ReceiptStatus, RemoteAgentId, Sequence,
+        AckStatus, AgentId, BalanceOf, ChainId, ChannelId, ChannelInfo, ChannelState, DeadLetter,
+        Packet, PacketPayload, PendingSubmission, ReceiptStatus, RemoteAgentId, Sequence,
     };
 
     // =========================================================
@@ -107,6 +167,55 @@ pub mod pallet {
 
         /// Interface to agent-registry for cross-chain agent identity validation.
         type AgentRegistry: AgentRegistryInterface<Self::AccountId>;
+
+        /// Application handler that receives ack payloads for sent packets.
+        type IbcApplication: IbcApplication;
+
+        /// Verifies remote registry attestations in `AgentAttestation` packets.
+        type AttestationVerifier: AttestationVerifier;
+
+        /// Number of blocks an attested cross-chain agent mapping stays valid.
+        #[pallet::constant]
+        type AttestationValidityBlocks: Get<u32>;
+
+        /// Maximum dead-lettered packets held per channel. Once full, packets
+        /// that fail application handling are rejected so the relayer retries.
+        #[pallet::constant]
+        type MaxDeadLetters: Get<u32>;
+
+        /// Maximum unconsumed ack payloads held for one sender. Once reached,
+        /// further unconsumed payloads for that sender are dropped.
+        #[pallet::constant]
+        type MaxRetainedAckPayloads: Get<u32>;
+
+        /// Currency used for open-relaying bonds.
+        type Currency: ReservableCurrency<Self::AccountId>;
+
+        /// Bond reserved by a non-relayer submitting a packet.
+        #[pallet::constant]
+        type OpenSubmissionBond: Get<BalanceOf<Self>>;
+
+        /// Number of blocks a bonded submission can be challenged.
+        #[pallet::constant]
+        type ChallengePeriod: Get<u32>;
+
+        /// Checks fraud proofs against bonded submissions.
+        type FraudProofVerifier: FraudProofVerifier;
+
+        /// Stake weighting each relayer's share of primary-relayer slots.
+        type RelayerStake: RelayerStake<Self::AccountId>;
+
+        /// Blocks after a backup relayer first offers a packet during which
+        /// only its primary relayer may deliver it.
+        #[pallet::constant]
+        type RelayerExclusivityWindow: Get<u32>;
+
+        /// Account paying primary relayers whose packet a backup delivered.
+        type RelayerCompensationSource: Get<Self::AccountId>;
+
+        /// Paid to the primary relayer when a backup delivers its packet.
+        #[pallet::constant]
+        type PrimaryRelayerCompensation: Get<BalanceOf<Self>>;
     }
 
     // =========================================================
@@ -192,12 +301,99 @@ pub mod pallet {
         OptionQuery,
     >;
 
+    /// Original sender of each outstanding packet, kept until the packet is
+    /// timed out or its ack payload is consumed.
+    #[pallet::storage]
+    pub type PacketSenders<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        ChannelId<T>,
+        Blake2_128Concat,
+        Sequence,
+        T::AccountId,
+        OptionQuery,
+    >;
+
+    /// Application-level ack data not consumed by `T::IbcApplication`,
+    /// held until the original sender takes it with `consume_ack_payload`.
+    #[pallet::storage]
+    pub type AckPayloads<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        ChannelId<T>,
+        Blake2_128Concat,
+        Sequence,
+        BoundedVec<u8, T::MaxPayloadLen>,
+        OptionQuery,
+    >;
+
+    /// Number of entries in `AckPayloads` held for each sender.
+    #[pallet::storage]
+    pub type RetainedAckPayloads<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+    /// Inbound packets that failed application handling, by channel and sequence.
+    #[pallet::storage]
+    pub type DeadLetters<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        ChannelId<T>,
+        Blake2_128Concat,
+        Sequence,
+        DeadLetter<T>,
+        OptionQuery,
+    >;
+
+    /// Number of entries in each channel's dead-letter queue.
+    #[pallet::storage]
+    pub type DeadLetterCount<T: Config> =
+        StorageMap<_, Blake2_128Concat, ChannelId<T>, u32, ValueQuery>;
+
+    /// Bonded packet submissions awaiting their challenge window.
+    #[pallet::storage]
+    pub type PendingSubmissions<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        ChannelId<T>,
+        Blake2_128Concat,
+        Sequence,
+        PendingSubmission<T>,
+        OptionQuery,
+    >;
+
+    /// sr25519 keys of each counterparty chain's signing committee, used by
+    /// [`CounterpartySignerSet`] to check proofs about that chain.
+    #[pallet::storage]
+    pub type CounterpartySigners<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        ChainId<T>,
+        BoundedVec<sp_core::sr25519::Public, T::MaxRelayers>,
+        ValueQuery,
+    >;
+
+    /// Next inbound sequence of a channel and the local block at which a
+    /// backup relayer first offered it, starting its exclusivity window.
+    #[pallet::storage]
+    pub type PacketFirstSeen<T: Config> =
+        StorageMap<_, Blake2_128Concat, ChannelId<T>, (Sequence, BlockNumberFor<T>), OptionQuery>;
+
     /// Set of trusted relayers that may submit packets and acks.
     #[pallet::storage]
     #[pallet::getter(fn trusted_relayers)]
     pub type TrustedRelayers<T: Config> =
         StorageValue<_, BoundedVec<T::AccountId, T::MaxRelayers>, ValueQuery>;
 
+    /// Relayers allowed to act on a channel. Empty means any trusted relayer.
+    #[pallet::storage]
+    pub type ChannelRelayers<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        ChannelId<T>,
+        BoundedVec<T::AccountId, T::MaxRelayers>,
+        ValueQuery,
+    >;
+
     /// Maps (remote_chain_id, remote_agent_id) → local AgentId.
     #[pallet::storage]
     pub type CrossChainAgentMap<T: Config> = StorageDoubleMap<
@@ -210,6 +406,19 @@ pub mod pallet {
         OptionQuery,
     >;
 
+    /// Expiry block of attested cross-chain agent mappings. A mapping with no
+    /// entry is treated as expired.
+    #[pallet::storage]
+    pub type CrossChainAgentExpiry<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        ChainId<T>,
+        Blake2_128Concat,
+        RemoteAgentId<T>,
+        BlockNumberFor<T>,
+        OptionQuery,
+    >;
+
     // =========================================================
     // Events
     // =========================================================
@@ -218,46 +427,133 @@ pub mod pallet {
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config> {
         ChannelOpened {
-            channel_id: Vec<u8>,
-            counterparty_chain: Vec<u8>,
-            counterparty_channel: Vec<u8>,
+            channel_id: EventBytes,
+            counterparty_chain: EventBytes,
+            counterparty_channel: EventBytes,
         },
         ChannelCloseInitiated {
-            channel_id: Vec<u8>,
+            channel_id: EventBytes,
         },
         ChannelClosed {
-            channel_id: Vec<u8>,
+            channel_id: EventBytes,
         },
         PacketSent {
-            channel_id: Vec<u8>,
+            channel_id: EventBytes,
             sequence: Sequence,
             src_agent: Option<AgentId>,
             payload_hash: H256,
         },
         PacketReceived {
-            channel_id: Vec<u8>,
+            channel_id: EventBytes,
             sequence: Sequence,
             dst_agent: Option<RemoteAgentId<T>>,
         },
         PacketAcknowledged {
-            channel_id: Vec<u8>,
+            channel_id: EventBytes,
             sequence: Sequence,
             success: bool,
         },
         PacketTimeout {
-            channel_id: Vec<u8>,
+            channel_id: EventBytes,
             sequence: Sequence,
         },
+        /// An inbound packet was received but could not be applied; the
+        /// relayer returns this error acknowledgement to the sender chain.
+        PacketErrorAcknowledged {
+            channel_id: EventBytes,
+            sequence: Sequence,
+            reason: DispatchError,
+        },
+        PacketDeadLettered {
+            channel_id: EventBytes,
+            sequence: Sequence,
+            reason: DispatchError,
+        },
+        DeadLetterRetried {
+            channel_id: EventBytes,
+            sequence: Sequence,
+        },
+        DeadLetterDiscarded {
+            channel_id: EventBytes,
+            sequence: Sequence,
+        },
+        AckPayloadConsumed {
+            channel_id: EventBytes,
+            sequence: Sequence,
+            data: EventBytes,
+        },
+        /// An unconsumed ack payload was discarded because its sender already
+        /// holds `MaxRetainedAckPayloads` payloads.
+        AckPayloadDropped {
+            channel_id: EventBytes,
+            sequence: Sequence,
+            sender: T::AccountId,
+        },
+        PacketSubmitted {
+            channel_id: EventBytes,
+            sequence: Sequence,
+            submitter: T::AccountId,
+            challenge_until: BlockNumberFor<T>,
+        },
+        /// A bonded submission was rejected; the bond went to the challenger.
+        SubmissionChallenged {
+            channel_id: EventBytes,
+            sequence: Sequence,
+            challenger: T::AccountId,
+            slashed: BalanceOf<T>,
+        },
+        /// A bonded submission outlived its challenge window; the bond was returned.
+        SubmissionFinalized {
+            channel_id: EventBytes,
+            sequence: Sequence,
+            submitter: T::AccountId,
+        },
+        /// A finalized submission could no longer be received; it was
+        /// discarded and the bond returned.
+        SubmissionDropped {
+            channel_id: EventBytes,
+            sequence: Sequence,
+            submitter: T::AccountId,
+            reason: DispatchError,
+        },
+        CounterpartySignersSet {
+            chain_id: EventBytes,
+            signers: u32,
+        },
         RelayerAdded {
             relayer: T::AccountId,
         },
         RelayerRemoved {
             relayer: T::AccountId,
         },
-        CrossChainAgentRegistered {
-            chain_id: Vec<u8>,
+        ChannelRelayersSet {
+            channel_id: EventBytes,
+            relayers: Vec<T::AccountId>,
+        },
+        CrossChainAgentAttested {
+            chain_id: EventBytes,
             remote_agent_id: RemoteAgentId<T>,
             local_agent_id: AgentId,
+            record_hash: H256,
+            expires_at: BlockNumberFor<T>,
+            refreshed: bool,
+        },
+        CrossChainAgentExpired {
+            chain_id: EventBytes,
+            remote_agent_id: RemoteAgentId<T>,
+        },
+        /// A backup relayer offered a packet inside its primary's exclusivity
+        /// window; the window runs until `exclusive_until`.
+        PacketFirstSeen {
+            channel_id: EventBytes,
+            sequence: Sequence,
+            exclusive_until: BlockNumberFor<T>,
+        },
+        PrimaryRelayerCompensated {
+            channel_id: EventBytes,
+            sequence: Sequence,
+            primary: T::AccountId,
+            amount: BalanceOf<T>,
         },
     }
 
@@ -289,6 +585,24 @@ pub mod pallet {
         ChannelIdTooLong,
         InvalidAgent,
         PendingPacketLimitExceeded,
+        AckPayloadNotFound,
+        NotPacketSender,
+        InvalidAttestation,
+        CrossChainAgentNotFound,
+        AttestationNotExpired,
+        NotChannelRelayer,
+        DeadLetterQueueFull,
+        DeadLetterNotFound,
+        SubmissionAlreadyPending,
+        SubmissionNotFound,
+        ChallengePeriodOver,
+        ChallengePeriodNotOver,
+        InvalidFraudProof,
+        InsufficientBond,
+        TooManySigners,
+        /// Only the primary relayer may deliver this packet until its
+        /// exclusivity window ends.
+        NotPrimaryRelayer,
     }
 
     // =========================================================
@@ -300,14 +614,18 @@ pub mod pallet {
         /// Open a new channel to a counterparty chain.
         ///
         /// Creates a channel in Init state. Relayer confirms opening.
+        /// A non-empty `relayers` list restricts the channel to those
+        /// trusted relayers.
         #[pallet::call_index(0)]
         #[pallet::weight(T::WeightInfo::open_channel())]
         pub fn open_channel(
             origin: OriginFor<T>,
             counterparty_chain_id: Vec<u8>,
             counterparty_channel_id: Vec<u8>,
+            relayers: Vec<T::AccountId>,
         ) -> DispatchResult {
             T::RelayerManagerOrigin::ensure_origin(origin)?;
+            let channel_relayers = Self::validate_channel_relayers(&relayers)?;
 
             // Validate lengths
             let chain_id: ChainId<T> = counterparty_chain_id
@@ -360,11 +678,19 @@ pub mod pallet {
             AckSequences::<T>::insert(&channel_id, 1u64);
 
             Self::deposit_event(Event::ChannelOpened {
-                channel_id: channel_id.to_vec(),
-                counterparty_chain: counterparty_chain_id,
-                counterparty_channel: counterparty_channel_id,
+                channel_id: EventBytes::new(&channel_id),
+                counterparty_chain: EventBytes::new(&counterparty_chain_id),
+                counterparty_channel: EventBytes::new(&counterparty_channel_id),
             });
 
+            if !channel_relayers.is_empty() {
+                ChannelRelayers::<T>::insert(&channel_id, channel_relayers);
+                Self::deposit_event(Event::ChannelRelayersSet {
+                    channel_id: EventBytes::new(&channel_id),
+                    relayers,
+                });
+            }
+
             Ok(())
         }
 
@@ -389,7 +715,9 @@ pub mod pallet {
                 Ok(())
             })?;
 
-            Self::deposit_event(Event::ChannelCloseInitiated { channel_id });
+            Self::deposit_event(Event::ChannelCloseInitiated {
+                channel_id: EventBytes::new(&channel_id),
+            });
             Ok(())
         }
 
@@ -398,12 +726,12 @@ pub mod pallet {
         #[pallet::weight(T::WeightInfo::close_channel_confirm())]
         pub fn close_channel_confirm(origin: OriginFor<T>, channel_id: Vec<u8>) -> DispatchResult {
             let who = ensure_signed(origin)?;
-            Self::ensure_trusted_relayer(&who)?;
 
             let bounded_channel_id: ChannelId<T> = channel_id
                 .clone()
                 .try_into()
                 .map_err(|_| Error::<T>::ChannelIdTooLong)?;
+            Self::ensure_channel_relayer(&who, &bounded_channel_id)?;
 
             let now = <frame_system::Pallet<T>>::block_number();
             Channels::<T>::try_mutate(&bounded_channel_id, |maybe_channel| -> DispatchResult {
@@ -417,7 +745,9 @@ pub mod pallet {
                 Ok(())
             })?;
 
-            Self::deposit_event(Event::ChannelClosed { channel_id });
+            Self::deposit_event(Event::ChannelClosed {
+                channel_id: EventBytes::new(&channel_id),
+            });
             Ok(())
         }
 
@@ -432,7 +762,7 @@ pub mod pallet {
             dst_agent_id: Option<Vec<u8>>,
             payload: PacketPayload<T>,
         ) -> DispatchResult {
-            let _who = ensure_signed(origin)?;
+            let who = ensure_signed(origin)?;
 
             let bounded_channel_id: ChannelId<T> = channel_id
                 .clone()
@@ -496,12 +826,13 @@ pub mod pallet {
 
             // Store commitment
             PacketCommitments::<T>::insert(&bounded_channel_id, sequence, commitment);
+            PacketSenders::<T>::insert(&bounded_channel_id, sequence, who);
             SendSequences::<T>::insert(&bounded_channel_id, sequence + 1);
 
             let payload_hash = sp_io::hashing::blake2_256(&packet.payload.encode());
 
             Self::deposit_event(Event::PacketSent {
-                channel_id,
+                channel_id: EventBytes::new(&channel_id),
                 sequence,
                 src_agent: None,
                 payload_hash: H256::from(payload_hash),
@@ -511,57 +842,44 @@ pub mod pallet {
         }
 
         /// Receive a packet from a trusted relayer.
+        ///
+        /// A backup relayer's first offer of a packet only starts the
+        /// primary's exclusivity window; it must be resubmitted after it.
         #[pallet::call_index(4)]
         #[pallet::weight(T::WeightInfo::receive_packet())]
         pub fn receive_packet(origin: OriginFor<T>, packet: Packet<T>) -> DispatchResult {
             let who = ensure_signed(origin)?;
-            Self::ensure_trusted_relayer(&who)?;
+            Self::ensure_channel_relayer(&who, &packet.dst_channel_id)?;
 
-            // Verify channel exists and is open
-            let channel =
-                Channels::<T>::get(&packet.dst_channel_id).ok_or(Error::<T>::ChannelNotFound)?;
-            ensure!(
-                channel.state == ChannelState::Open,
-                Error::<T>::ChannelNotOpen
-            );
-
-            // Verify this chain is the destination
-            // (In a real implementation, we'd check dst_chain_id against our chain ID)
-
-            // Verify no replay (check before sequence to give more specific error)
-            ensure!(
-                !PacketReceipts::<T>::contains_key(&packet.dst_channel_id, packet.sequence),
-                Error::<T>::PacketAlreadyReceived
-            );
-
-            // Verify sequence
-            let expected_seq = RecvSequences::<T>::get(&packet.dst_channel_id);
-            ensure!(
-                packet.sequence == expected_seq,
-                Error::<T>::SequenceMismatch
-            );
-
-            // Verify not timed out
             let now = <frame_system::Pallet<T>>::block_number();
-            ensure!(now < packet.timeout_height, Error::<T>::PacketTimedOut);
-
-            // Store receipt
-            PacketReceipts::<T>::insert(
-                &packet.dst_channel_id,
-                packet.sequence,
-                ReceiptStatus::Received,
-            );
-            RecvSequences::<T>::insert(&packet.dst_channel_id, packet.sequence + 1);
-
-            // Handle payload
-            // (In a real implementation, this would dispatch to application handlers)
-
-            Self::deposit_event(Event::PacketReceived {
-                channel_id: packet.dst_channel_id.to_vec(),
-                sequence: packet.sequence,
-                dst_agent: packet.dst_agent_id,
-            });
+            let channel_id = packet.dst_channel_id.clone();
+            let sequence = packet.sequence;
+            let backup_for =
+                Self::primary_relayer(&channel_id, sequence).filter(|primary| *primary != who);
+            if backup_for.is_some() {
+                Self::ensure_receivable(&packet, now)?;
+                let first_seen = PacketFirstSeen::<T>::get(&channel_id)
+                    .filter(|(seen_sequence, _)| *seen_sequence == sequence)
+                    .map(|(_, seen_at)| seen_at);
+                let window_end = first_seen
+                    .unwrap_or(now)
+                    .saturating_add(T::RelayerExclusivityWindow::get().into());
+                if first_seen.is_none() && now < window_end {
+                    PacketFirstSeen::<T>::insert(&channel_id, (sequence, now));
+                    Self::deposit_event(Event::PacketFirstSeen {
+                        channel_id: EventBytes::new(&channel_id),
+                        sequence,
+                        exclusive_until: window_end,
+                    });
+                    return Ok(());
+                }
+                ensure!(now >= window_end, Error::<T>::NotPrimaryRelayer);
+            }
 
+            Self::do_receive_packet(packet, now)?;
+            if let Some(primary) = backup_for {
+                Self::compensate_primary(&channel_id, sequence, primary);
+            }
             Ok(())
         }
 
@@ -575,12 +893,12 @@ pub mod pallet {
             ack: PacketPayload<T>,
         ) -> DispatchResult {
             let who = ensure_signed(origin)?;
-            Self::ensure_trusted_relayer(&who)?;
 
             let bounded_channel_id: ChannelId<T> = channel_id
                 .clone()
                 .try_into()
                 .map_err(|_| Error::<T>::ChannelIdTooLong)?;
+            Self::ensure_channel_relayer(&who, &bounded_channel_id)?;
 
             // Verify commitment exists
             ensure!(
@@ -588,19 +906,35 @@ pub mod pallet {
                 Error::<T>::PacketNotFound
             );
 
-            // Extract success from ack payload
-            let (success, _error_code) = match &ack {
-                PacketPayload::Ack {
-                    success,
-                    error_code,
-                    ..
-                } => (*success, *error_code),
-                _ => (false, Some(1)), // Invalid ack format
+            // Extract success and application data from ack payload
+            let (success, data) = match ack {
+                PacketPayload::Ack { success, data, .. } => (success, data),
+                _ => (false, BoundedVec::default()), // Invalid ack format
             };
 
             // Delete commitment
             PacketCommitments::<T>::remove(&bounded_channel_id, sequence);
 
+            // Route ack data to the application; keep it for the sender if
+            // unconsumed and the sender has room, otherwise forget the sender
+            let consumed = T::IbcApplication::on_ack(&channel_id, sequence, success, &data);
+            if let Some(sender) = PacketSenders::<T>::take(&bounded_channel_id, sequence) {
+                if !consumed && !data.is_empty() {
+                    let retained = RetainedAckPayloads::<T>::get(&sender);
+                    if retained < T::MaxRetainedAckPayloads::get() {
+                        RetainedAckPayloads::<T>::insert(&sender, retained + 1);
+                        AckPayloads::<T>::insert(&bounded_channel_id, sequence, data);
+                        PacketSenders::<T>::insert(&bounded_channel_id, sequence, sender);
+                    } else {
+                        Self::deposit_event(Event::AckPayloadDropped {
+                            channel_id: EventBytes::new(&channel_id),
+                            sequence,
+                            sender,
+                        });
+                    }
+                }
+            }
+
             // Store acknowledgement
             PacketAcknowledgements::<T>::insert(
                 &bounded_channel_id,
@@ -610,7 +944,7 @@ pub mod pallet {
             AckSequences::<T>::mutate(&bounded_channel_id, |seq| *seq += 1);
 
             Self::deposit_event(Event::PacketAcknowledged {
-                channel_id,
+                channel_id: EventBytes::new(&channel_id),
                 sequence,
                 success,
             });
@@ -651,9 +985,10 @@ pub mod pallet {
 
             // Delete commitment
             PacketCommitments::<T>::remove(&bounded_channel_id, sequence);
+            PacketSenders::<T>::remove(&bounded_channel_id, sequence);
 
             Self::deposit_event(Event::PacketTimeout {
-                channel_id,
+                channel_id: EventBytes::new(&channel_id),
                 sequence,
             });
 
@@ -703,51 +1038,364 @@ pub mod pallet {
             Ok(())
         }
 
-        /// Register a cross-chain agent mapping.
-        #[pallet::call_index(9)]
-        #[pallet::weight(T::WeightInfo::register_cross_chain_agent())]
-        pub fn register_cross_chain_agent(
+        /// Take the ack payload of a packet the caller sent.
+        ///
+        /// The payload is removed from storage and emitted in
+        /// `AckPayloadConsumed` so the sender receives the remote result.
+        #[pallet::call_index(10)]
+        #[pallet::weight(T::WeightInfo::consume_ack_payload())]
+        pub fn consume_ack_payload(
+            origin: OriginFor<T>,
+            channel_id: Vec<u8>,
+            sequence: Sequence,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let bounded_channel_id: ChannelId<T> = channel_id
+                .clone()
+                .try_into()
+                .map_err(|_| Error::<T>::ChannelIdTooLong)?;
+
+            let sender = PacketSenders::<T>::get(&bounded_channel_id, sequence)
+                .ok_or(Error::<T>::AckPayloadNotFound)?;
+            ensure!(sender == who, Error::<T>::NotPacketSender);
+
+            let data = AckPayloads::<T>::take(&bounded_channel_id, sequence)
+                .ok_or(Error::<T>::AckPayloadNotFound)?;
+            PacketSenders::<T>::remove(&bounded_channel_id, sequence);
+            RetainedAckPayloads::<T>::mutate_exists(&who, |retained| {
+                *retained = retained.map(|n| n.saturating_sub(1)).filter(|n| *n > 0);
+            });
+
+            Self::deposit_event(Event::AckPayloadConsumed {
+                channel_id: EventBytes::new(&channel_id),
+                sequence,
+                data: EventBytes::new(&data),
+            });
+
+            Ok(())
+        }
+
+        /// Remove an attested cross-chain agent mapping whose attestation expired.
+        ///
+        /// Anyone can call. Mappings without an attestation expiry count as expired.
+        #[pallet::call_index(11)]
+        #[pallet::weight(T::WeightInfo::prune_cross_chain_agent())]
+        pub fn prune_cross_chain_agent(
             origin: OriginFor<T>,
             chain_id: Vec<u8>,
             remote_agent_id: Vec<u8>,
-            local_agent_id: AgentId,
         ) -> DispatchResult {
-            let who = ensure_signed(origin)?;
-            Self::ensure_trusted_relayer(&who)?;
+            let _who = ensure_signed(origin)?;
 
             let bounded_chain_id: ChainId<T> = chain_id
                 .clone()
                 .try_into()
                 .map_err(|_| Error::<T>::ChainIdTooLong)?;
             let bounded_remote_agent_id: RemoteAgentId<T> = remote_agent_id
+                .try_into()
+                .map_err(|_| Error::<T>::ChannelIdTooLong)?;
+
+            ensure!(
+                CrossChainAgentMap::<T>::contains_key(&bounded_chain_id, &bounded_remote_agent_id),
+                Error::<T>::CrossChainAgentNotFound
+            );
+            let now = <frame_system::Pallet<T>>::block_number();
+            ensure!(
+                Self::attestation_expired(&bounded_chain_id, &bounded_remote_agent_id, now),
+                Error::<T>::AttestationNotExpired
+            );
+
+            CrossChainAgentMap::<T>::remove(&bounded_chain_id, &bounded_remote_agent_id);
+            CrossChainAgentExpiry::<T>::remove(&bounded_chain_id, &bounded_remote_agent_id);
+
+            Self::deposit_event(Event::CrossChainAgentExpired {
+                chain_id: EventBytes::new(&chain_id),
+                remote_agent_id: bounded_remote_agent_id,
+            });
+
+            Ok(())
+        }
+
+        /// Replace a channel's relayer whitelist.
+        ///
+        /// Every listed relayer must already be trusted. An empty list lifts
+        /// the restriction so any trusted relayer may act on the channel.
+        #[pallet::call_index(12)]
+        #[pallet::weight(T::WeightInfo::set_channel_relayers())]
+        pub fn set_channel_relayers(
+            origin: OriginFor<T>,
+            channel_id: Vec<u8>,
+            relayers: Vec<T::AccountId>,
+        ) -> DispatchResult {
+            T::RelayerManagerOrigin::ensure_origin(origin)?;
+
+            let bounded_channel_id: ChannelId<T> = channel_id
+                .clone()
+                .try_into()
+                .map_err(|_| Error::<T>::ChannelIdTooLong)?;
+            ensure!(
+                Channels::<T>::contains_key(&bounded_channel_id),
+                Error::<T>::ChannelNotFound
+            );
+            let channel_relayers = Self::validate_channel_relayers(&relayers)?;
+
+            if channel_relayers.is_empty() {
+                ChannelRelayers::<T>::remove(&bounded_channel_id);
+            } else {
+                ChannelRelayers::<T>::insert(&bounded_channel_id, channel_relayers);
+            }
+
+            Self::deposit_event(Event::ChannelRelayersSet {
+                channel_id: EventBytes::new(&channel_id),
+                relayers,
+            });
+            Ok(())
+        }
+
+        /// Hand a dead-lettered packet to the application again.
+        ///
+        /// On success the packet leaves the queue; on failure the call fails
+        /// with the handler's error and the entry is kept.
+        #[pallet::call_index(13)]
+        #[pallet::weight(T::WeightInfo::retry_dead_letter())]
+        pub fn retry_dead_letter(
+            origin: OriginFor<T>,
+            channel_id: Vec<u8>,
+            sequence: Sequence,
+        ) -> DispatchResult {
+            T::RelayerManagerOrigin::ensure_origin(origin)?;
+
+            let bounded_channel_id: ChannelId<T> = channel_id
                 .clone()
                 .try_into()
                 .map_err(|_| Error::<T>::ChannelIdTooLong)?;
+            let dead_letter = DeadLetters::<T>::get(&bounded_channel_id, sequence)
+                .ok_or(Error::<T>::DeadLetterNotFound)?;
+
+            Self::deliver_to_application(&dead_letter.packet)?;
+            Self::remove_dead_letter(&bounded_channel_id, sequence);
+
+            Self::deposit_event(Event::DeadLetterRetried {
+                channel_id: EventBytes::new(&channel_id),
+                sequence,
+            });
+            Ok(())
+        }
+
+        /// Drop a dead-lettered packet without delivering it.
+        #[pallet::call_index(14)]
+        #[pallet::weight(T::WeightInfo::discard_dead_letter())]
+        pub fn discard_dead_letter(
+            origin: OriginFor<T>,
+            channel_id: Vec<u8>,
+            sequence: Sequence,
+        ) -> DispatchResult {
+            T::RelayerManagerOrigin::ensure_origin(origin)?;
 
-            // Verify local agent exists
+            let bounded_channel_id: ChannelId<T> = channel_id
+                .clone()
+                .try_into()
+                .map_err(|_| Error::<T>::ChannelIdTooLong)?;
             ensure!(
-                T::AgentRegistry::agent_exists(local_agent_id),
-                Error::<T>::AgentNotFound
+                DeadLetters::<T>::contains_key(&bounded_channel_id, sequence),
+                Error::<T>::DeadLetterNotFound
             );
+            Self::remove_dead_letter(&bounded_channel_id, sequence);
 
-            // Check not already mapped
+            Self::deposit_event(Event::DeadLetterDiscarded {
+                channel_id: EventBytes::new(&channel_id),
+                sequence,
+            });
+            Ok(())
+        }
+
+        /// Submit an inbound packet without being a trusted relayer.
+        ///
+        /// Reserves `OpenSubmissionBond` and holds the packet for
+        /// `ChallengePeriod` blocks before it can be finalized.
+        #[pallet::call_index(15)]
+        #[pallet::weight(T::WeightInfo::submit_packet_bonded())]
+        pub fn submit_packet_bonded(origin: OriginFor<T>, packet: Packet<T>) -> DispatchResult {
+            let submitter = ensure_signed(origin)?;
+
+            let channel =
+                Channels::<T>::get(&packet.dst_channel_id).ok_or(Error::<T>::ChannelNotFound)?;
             ensure!(
-                !CrossChainAgentMap::<T>::contains_key(&bounded_chain_id, &bounded_remote_agent_id),
-                Error::<T>::CrossChainAgentAlreadyMapped
+                channel.state == ChannelState::Open,
+                Error::<T>::ChannelNotOpen
+            );
+            ensure!(
+                !PendingSubmissions::<T>::contains_key(&packet.dst_channel_id, packet.sequence),
+                Error::<T>::SubmissionAlreadyPending
             );
+            let now = <frame_system::Pallet<T>>::block_number();
+            Self::ensure_receivable(&packet, now)?;
 
-            CrossChainAgentMap::<T>::insert(
-                &bounded_chain_id,
-                &bounded_remote_agent_id,
-                local_agent_id,
+            let bond = T::OpenSubmissionBond::get();
+            T::Currency::reserve(&submitter, bond).map_err(|_| Error::<T>::InsufficientBond)?;
+
+            let challenge_until = now.saturating_add(T::ChallengePeriod::get().into());
+            let channel_id = packet.dst_channel_id.clone();
+            let sequence = packet.sequence;
+            PendingSubmissions::<T>::insert(
+                &channel_id,
+                sequence,
+                PendingSubmission {
+                    submitter: submitter.clone(),
+                    bond,
+                    packet,
+                    submitted_at: now,
+                    challenge_until,
+                },
             );
 
-            Self::deposit_event(Event::CrossChainAgentRegistered {
-                chain_id,
-                remote_agent_id: bounded_remote_agent_id,
-                local_agent_id,
+            Self::deposit_event(Event::PacketSubmitted {
+                channel_id: EventBytes::new(&channel_id),
+                sequence,
+                submitter,
+                challenge_until,
+            });
+            Ok(())
+        }
+
+        /// Challenge a bonded submission before its window closes.
+        ///
+        /// The challenger, relayer or not, must supply a proof accepted by
+        /// `T::FraudProofVerifier`. The submitter's bond is transferred to
+        /// the challenger.
+        #[pallet::call_index(16)]
+        #[pallet::weight(T::WeightInfo::challenge_submission())]
+        pub fn challenge_submission(
+            origin: OriginFor<T>,
+            channel_id: Vec<u8>,
+            sequence: Sequence,
+            proof: Vec<u8>,
+        ) -> DispatchResult {
+            let challenger = ensure_signed(origin)?;
+
+            let bounded_channel_id: ChannelId<T> = channel_id
+                .clone()
+                .try_into()
+                .map_err(|_| Error::<T>::ChannelIdTooLong)?;
+            let submission = PendingSubmissions::<T>::get(&bounded_channel_id, sequence)
+                .ok_or(Error::<T>::SubmissionNotFound)?;
+            let now = <frame_system::Pallet<T>>::block_number();
+            ensure!(
+                now < submission.challenge_until,
+                Error::<T>::ChallengePeriodOver
+            );
+
+            let channel =
+                Channels::<T>::get(&bounded_channel_id).ok_or(Error::<T>::ChannelNotFound)?;
+            ensure!(
+                T::FraudProofVerifier::verify(
+                    &channel.counterparty_chain_id,
+                    Self::packet_commitment(&submission.packet),
+                    &proof,
+                ),
+                Error::<T>::InvalidFraudProof
+            );
+
+            PendingSubmissions::<T>::remove(&bounded_channel_id, sequence);
+            let unpaid = T::Currency::repatriate_reserved(
+                &submission.submitter,
+                &challenger,
+                submission.bond,
+                BalanceStatus::Free,
+            )?;
+
+            Self::deposit_event(Event::SubmissionChallenged {
+                channel_id: EventBytes::new(&channel_id),
+                sequence,
+                challenger,
+                slashed: submission.bond.saturating_sub(unpaid),
             });
+            Ok(())
+        }
+
+        /// Receive an unchallenged bonded submission once its window has
+        /// passed, returning the submitter's bond.
+        ///
+        /// If a trusted relayer already delivered the packet meanwhile, only
+        /// the bond is returned. If the packet can no longer be received, it
+        /// is dropped with `SubmissionDropped` and the bond is still returned.
+        #[pallet::call_index(17)]
+        #[pallet::weight(T::WeightInfo::finalize_submission())]
+        pub fn finalize_submission(
+            origin: OriginFor<T>,
+            channel_id: Vec<u8>,
+            sequence: Sequence,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+
+            let bounded_channel_id: ChannelId<T> = channel_id
+                .clone()
+                .try_into()
+                .map_err(|_| Error::<T>::ChannelIdTooLong)?;
+            let submission = PendingSubmissions::<T>::take(&bounded_channel_id, sequence)
+                .ok_or(Error::<T>::SubmissionNotFound)?;
+            let now = <frame_system::Pallet<T>>::block_number();
+            ensure!(
+                now >= submission.challenge_until,
+                Error::<T>::ChallengePeriodNotOver
+            );
 
+            T::Currency::unreserve(&submission.submitter, submission.bond);
+            if !PacketReceipts::<T>::contains_key(&bounded_channel_id, sequence) {
+                let received = frame_support::storage::with_storage_layer(|| {
+                    Self::do_receive_packet(submission.packet, submission.submitted_at)
+                });
+                if let Err(reason) = received {
+                    Self::deposit_event(Event::SubmissionDropped {
+                        channel_id: EventBytes::new(&channel_id),
+                        sequence,
+                        submitter: submission.submitter,
+                        reason,
+                    });
+                    return Ok(());
+                }
+            }
+
+            Self::deposit_event(Event::SubmissionFinalized {
+                channel_id: EventBytes::new(&channel_id),
+                sequence,
+                submitter: submission.submitter,
+            });
+            Ok(())
+        }
+
+        /// Replace the signing committee of a counterparty chain.
+        ///
+        /// An empty list removes the committee, after which no proof about
+        /// that chain verifies.
+        #[pallet::call_index(18)]
+        #[pallet::weight(T::WeightInfo::set_counterparty_signers())]
+        pub fn set_counterparty_signers(
+            origin: OriginFor<T>,
+            chain_id: Vec<u8>,
+            signers: Vec<sp_core::sr25519::Public>,
+        ) -> DispatchResult {
+            T::RelayerManagerOrigin::ensure_origin(origin)?;
+
+            let bounded_chain_id: ChainId<T> = chain_id
+                .clone()
+                .try_into()
+                .map_err(|_| Error::<T>::ChainIdTooLong)?;
+            let count = signers.len() as u32;
+            let bounded_signers: BoundedVec<_, T::MaxRelayers> =
+                signers.try_into().map_err(|_| Error::<T>::TooManySigners)?;
+
+            if bounded_signers.is_empty() {
+                CounterpartySigners::<T>::remove(&bounded_chain_id);
+            } else {
+                CounterpartySigners::<T>::insert(&bounded_chain_id, bounded_signers);
+            }
+
+            Self::deposit_event(Event::CounterpartySignersSet {
+                chain_id: EventBytes::new(&chain_id),
+                signers: count,
+            });
             Ok(())
         }
     }
@@ -757,17 +1405,346 @@ pub mod pallet {
     // =========================================================
 
     impl<T: Config> Pallet<T> {
+        /// Whether `who` is in the trusted relayer set.
+        ///
+        /// Exposed so the runtime can treat relayers as guarded accounts that
+        /// must not be reaped while they hold relaying duties.
+        pub fn is_trusted_relayer(who: &T::AccountId) -> bool {
+            TrustedRelayers::<T>::get().contains(who)
+        }
+
         /// Ensure the caller is a trusted relayer.
         fn ensure_trusted_relayer(who: &T::AccountId) -> DispatchResult {
+            ensure!(Self::is_trusted_relayer(who), Error::<T>::NotTrustedRelayer);
+            Ok(())
+        }
+
+        /// Ensure the caller is a trusted relayer allowed on `channel_id`.
+        fn ensure_channel_relayer(who: &T::AccountId, channel_id: &ChannelId<T>) -> DispatchResult {
+            Self::ensure_trusted_relayer(who)?;
+            let allowed = ChannelRelayers::<T>::get(channel_id);
             ensure!(
-                TrustedRelayers::<T>::get().contains(who),
-                Error::<T>::NotTrustedRelayer
+                allowed.is_empty() || allowed.contains(who),
+                Error::<T>::NotChannelRelayer
             );
             Ok(())
         }
 
+        /// Primary relayer for inbound `sequence` on `channel_id`, chosen by
+        /// stake-weighted hashing over the channel's eligible relayers.
+        pub fn primary_relayer(
+            channel_id: &ChannelId<T>,
+            sequence: Sequence,
+        ) -> Option<T::AccountId> {
+            let whitelist = ChannelRelayers::<T>::get(channel_id);
+            let candidates = if whitelist.is_empty() {
+                TrustedRelayers::<T>::get()
+            } else {
+                whitelist
+            };
+            if candidates.is_empty() {
+                return None;
+            }
+
+            let seed = sp_io::hashing::blake2_256(&(channel_id, sequence).encode());
+            let mut point_bytes = [0u8; 16];
+            point_bytes.copy_from_slice(&seed[..16]);
+            let point = u128::from_le_bytes(point_bytes);
+
+            let stakes: Vec<u128> = candidates.iter().map(T::RelayerStake::stake_of).collect();
+            let total = stakes
+                .iter()
+                .fold(0u128, |total, stake| total.saturating_add(*stake));
+            if total == 0 {
+                let index = (point % candidates.len() as u128) as usize;
+                return candidates.get(index).cloned();
+            }
+
+            let mut target = point % total;
+            for (relayer, stake) in candidates.iter().zip(stakes) {
+                if target < stake {
+                    return Some(relayer.clone());
+                }
+                target -= stake;
+            }
+            None
+        }
+
+        /// Pay `primary` for a packet a backup relayer delivered. Skipped if
+        /// the compensation source cannot afford it.
+        fn compensate_primary(
+            channel_id: &ChannelId<T>,
+            sequence: Sequence,
+            primary: T::AccountId,
+        ) {
+            use frame_support::traits::{Currency, ExistenceRequirement};
+            use sp_runtime::traits::Zero;
+
+            let amount = T::PrimaryRelayerCompensation::get();
+            if amount.is_zero() {
+                return;
+            }
+            if T::Currency::transfer(
+                &T::RelayerCompensationSource::get(),
+                &primary,
+                amount,
+                ExistenceRequirement::KeepAlive,
+            )
+            .is_ok()
+            {
+                Self::deposit_event(Event::PrimaryRelayerCompensated {
+                    channel_id: EventBytes::new(channel_id),
+                    sequence,
+                    primary,
+                    amount,
+                });
+            }
+        }
+
+        /// Check a channel whitelist: trusted relayers only, no duplicates.
+        fn validate_channel_relayers(
+            relayers: &[T::AccountId],
+        ) -> Result<BoundedVec<T::AccountId, T::MaxRelayers>, DispatchError> {
+            let mut bounded = BoundedVec::<T::AccountId, T::MaxRelayers>::new();
+            for relayer in relayers {
+                Self::ensure_trusted_relayer(relayer)?;
+                ensure!(
+                    !bounded.contains(relayer),
+                    Error::<T>::RelayerAlreadyRegistered
+                );
+                bounded
+                    .try_push(relayer.clone())
+                    .map_err(|_| Error::<T>::TooManyRelayers)?;
+            }
+            Ok(bounded)
+        }
+
+        /// Local agent mapped to a remote agent, ignoring expired attestations.
+        pub fn resolve_cross_chain_agent(
+            chain_id: &ChainId<T>,
+            remote_agent_id: &RemoteAgentId<T>,
+        ) -> Option<AgentId> {
+            let now = <frame_system::Pallet<T>>::block_number();
+            if Self::attestation_expired(chain_id, remote_agent_id, now) {
+                return None;
+            }
+            CrossChainAgentMap::<T>::get(chain_id, remote_agent_id)
+        }
+
+        /// Whether the mapping has no attestation expiry, or one at or before `now`.
+        fn attestation_expired(
+            chain_id: &ChainId<T>,
+            remote_agent_id: &RemoteAgentId<T>,
+            now: BlockNumberFor<T>,
+        ) -> bool {
+            CrossChainAgentExpiry::<T>::get(chain_id, remote_agent_id)
+                .is_none_or(|expires_at| now >= expires_at)
+        }
+
+        /// Verify a remote registry attestation and create or refresh the mapping.
+        ///
+        /// `chain_id` is the counterparty chain of the receiving channel, so a
+        /// relayer cannot attribute an attestation to a different chain.
+        fn import_agent_attestation(
+            chain_id: &ChainId<T>,
+            remote_agent_id: &RemoteAgentId<T>,
+            local_agent_id: AgentId,
+            record_hash: H256,
+            proof: &[u8],
+        ) -> DispatchResult {
+            ensure!(
+                T::AgentRegistry::agent_exists(local_agent_id),
+                Error::<T>::AgentNotFound
+            );
+            ensure!(
+                T::AttestationVerifier::verify(
+                    chain_id,
+                    remote_agent_id,
+                    local_agent_id,
+                    record_hash,
+                    proof
+                ),
+                Error::<T>::InvalidAttestation
+            );
+
+            let now = <frame_system::Pallet<T>>::block_number();
+            let refreshed = match CrossChainAgentMap::<T>::get(chain_id, remote_agent_id) {
+                Some(existing) if existing == local_agent_id => true,
+                Some(_) => {
+                    // A different agent may only take over an expired mapping.
+                    ensure!(
+                        Self::attestation_expired(chain_id, remote_agent_id, now),
+                        Error::<T>::CrossChainAgentAlreadyMapped
+                    );
+                    false
+                }
+                None => false,
+            };
+
+            let expires_at = now.saturating_add(T::AttestationValidityBlocks::get().into());
+            CrossChainAgentMap::<T>::insert(chain_id, remote_agent_id, local_agent_id);
+            CrossChainAgentExpiry::<T>::insert(chain_id, remote_agent_id, expires_at);
+
+            Self::deposit_event(Event::CrossChainAgentAttested {
+                chain_id: EventBytes::new(chain_id),
+                remote_agent_id: remote_agent_id.clone(),
+                local_agent_id,
+                record_hash,
+                expires_at,
+                refreshed,
+            });
+
+            Ok(())
+        }
+
+        /// Validate and receive `packet`, which arrived on-chain at `arrived_at`.
+        ///
+        /// Shared by trusted-relayer delivery and finalized bonded submissions;
+        /// the timeout is judged against arrival so the challenge window does
+        /// not time packets out.
+        fn do_receive_packet(packet: Packet<T>, arrived_at: BlockNumberFor<T>) -> DispatchResult {
+            // Verify channel exists and is open
+            let channel =
+                Channels::<T>::get(&packet.dst_channel_id).ok_or(Error::<T>::ChannelNotFound)?;
+            ensure!(
+                channel.state == ChannelState::Open,
+                Error::<T>::ChannelNotOpen
+            );
+
+            // Verify this chain is the destination
+            // (In a real implementation, we'd check dst_chain_id against our chain ID)
+
+            Self::ensure_receivable(&packet, arrived_at)?;
+            let now = <frame_system::Pallet<T>>::block_number();
+
+            // Store receipt
+            PacketReceipts::<T>::insert(
+                &packet.dst_channel_id,
+                packet.sequence,
+                ReceiptStatus::Received,
+            );
+            RecvSequences::<T>::insert(&packet.dst_channel_id, packet.sequence + 1);
+            PacketFirstSeen::<T>::remove(&packet.dst_channel_id);
+
+            // A rejected attestation is answered with an error ack rather than
+            // failing the receive, which would stall the channel's sequence.
+            if let PacketPayload::AgentAttestation {
+                remote_agent_id,
+                local_agent_id,
+                record_hash,
+                proof,
+            } = &packet.payload
+            {
+                let imported = frame_support::storage::with_storage_layer(|| {
+                    Self::import_agent_attestation(
+                        &channel.counterparty_chain_id,
+                        remote_agent_id,
+                        *local_agent_id,
+                        *record_hash,
+                        proof,
+                    )
+                });
+                if let Err(reason) = imported {
+                    PacketReceipts::<T>::insert(
+                        &packet.dst_channel_id,
+                        packet.sequence,
+                        ReceiptStatus::Failed,
+                    );
+                    Self::deposit_event(Event::PacketErrorAcknowledged {
+                        channel_id: EventBytes::new(&packet.dst_channel_id),
+                        sequence: packet.sequence,
+                        reason,
+                    });
+                    return Ok(());
+                }
+            }
+
+            Self::deposit_event(Event::PacketReceived {
+                channel_id: EventBytes::new(&packet.dst_channel_id),
+                sequence: packet.sequence,
+                dst_agent: packet.dst_agent_id.clone(),
+            });
+
+            if let Err(reason) = Self::deliver_to_application(&packet) {
+                let channel_id = packet.dst_channel_id.clone();
+                DeadLetterCount::<T>::try_mutate(&channel_id, |count| -> DispatchResult {
+                    ensure!(
+                        *count < T::MaxDeadLetters::get(),
+                        Error::<T>::DeadLetterQueueFull
+                    );
+                    *count += 1;
+                    Ok(())
+                })?;
+                let sequence = packet.sequence;
+                DeadLetters::<T>::insert(
+                    &channel_id,
+                    sequence,
+                    DeadLetter {
+                        packet,
+                        reason,
+                        failed_at: now,
+                    },
+                );
+                Self::deposit_event(Event::PacketDeadLettered {
+                    channel_id: EventBytes::new(&channel_id),
+                    sequence,
+                    reason,
+                });
+            }
+
+            Ok(())
+        }
+
+        /// Replay, sequence and timeout checks for an inbound packet.
+        fn ensure_receivable(packet: &Packet<T>, arrived_at: BlockNumberFor<T>) -> DispatchResult {
+            // Verify no replay (check before sequence to give more specific error)
+            ensure!(
+                !PacketReceipts::<T>::contains_key(&packet.dst_channel_id, packet.sequence),
+                Error::<T>::PacketAlreadyReceived
+            );
+
+            // Verify sequence
+            let expected_seq = RecvSequences::<T>::get(&packet.dst_channel_id);
+            ensure!(
+                packet.sequence == expected_seq,
+                Error::<T>::SequenceMismatch
+            );
+
+            // Verify not timed out
+            ensure!(
+                arrived_at < packet.timeout_height,
+                Error::<T>::PacketTimedOut
+            );
+            Ok(())
+        }
+
+        /// Dead-lettered packets on `channel_id`, for the runtime API.
+        pub fn dead_letters(channel_id: &[u8]) -> Vec<DeadLetter<T>> {
+            let Ok(bounded_channel_id) = ChannelId::<T>::try_from(channel_id.to_vec()) else {
+                return Vec::new();
+            };
+            DeadLetters::<T>::iter_prefix_values(&bounded_channel_id).collect()
+        }
+
+        /// Run application handling in its own storage layer so a failing
+        /// handler leaves no partial writes behind.
+        fn deliver_to_application(packet: &Packet<T>) -> DispatchResult {
+            frame_support::storage::with_storage_layer(|| {
+                T::IbcApplication::on_recv(
+                    &packet.dst_channel_id,
+                    packet.sequence,
+                    &packet.payload.encode(),
+                )
+            })
+        }
+
+        fn remove_dead_letter(channel_id: &ChannelId<T>, sequence: Sequence) {
+            DeadLetters::<T>::remove(channel_id, sequence);
+            DeadLetterCount::<T>::mutate(channel_id, |count| *count = count.saturating_sub(1));
+        }
+
         /// Calculate the packet commitment hash.
-        fn packet_commitment(packet: &Packet<T>) -> H256 {
+        pub(crate) fn packet_commitment(packet: &Packet<T>) -> H256 {
             use sp_io::hashing::blake2_256;
 
             let payload_hash = blake2_256(&packet.payload.encode());
@@ -782,3 +1759,119 @@ pub mod pallet {
         }
     }
 }
+
+/// Key type of counterparty committee keys generated for benchmarks.
+#[cfg(feature = "runtime-benchmarks")]
+const COUNTERPARTY_KEY_TYPE: sp_core::crypto::KeyTypeId = sp_core::crypto::KeyTypeId(*b"ibcl");
+
+/// Verifies proofs signed by a strict majority of a counterparty chain's
+/// committee in [`CounterpartySigners`].
+///
+/// A proof is the SCALE encoding of `Vec<(u32, sr25519::Signature)>`, each
+/// entry a committee index and that member's signature over the statement.
+pub struct CounterpartySignerSet<T>(core::marker::PhantomData<T>);
+
+impl<T: Config> CounterpartySignerSet<T> {
+    /// Statement a committee signs to show `chain_id`'s agent registry binds
+    /// `remote_agent_id` to `local_agent_id` in the record `record_hash`.
+    pub fn attestation_statement(
+        chain_id: &[u8],
+        remote_agent_id: &[u8],
+        local_agent_id: u64,
+        record_hash: H256,
+    ) -> Vec<u8> {
+        (
+            b"ibc-lite/attest",
+            chain_id,
+            remote_agent_id,
+            local_agent_id,
+            record_hash,
+        )
+            .encode()
+    }
+
+    /// Statement a committee signs to show `chain_id` never committed a
+    /// packet with `packet_commitment`.
+    pub fn fraud_statement(chain_id: &[u8], packet_commitment: H256) -> Vec<u8> {
+        (b"ibc-lite/fraud", chain_id, packet_commitment).encode()
+    }
+
+    /// Whether `proof` carries valid signatures over `statement` from more
+    /// than half of `chain_id`'s committee.
+    fn quorum_signed(chain_id: &[u8], statement: &[u8], proof: &[u8]) -> bool {
+        use sp_core::sr25519::Signature;
+
+        let Ok(bounded_chain_id) = ChainId::<T>::try_from(chain_id.to_vec()) else {
+            return false;
+        };
+        let signers = CounterpartySigners::<T>::get(&bounded_chain_id);
+        let Ok(signatures) = Vec::<(u32, Signature)>::decode(&mut &proof[..]) else {
+            return false;
+        };
+
+        let mut signed = Vec::new();
+        for (index, signature) in signatures {
+            let Some(signer) = signers.get(index as usize) else {
+                return false;
+            };
+            if signed.contains(&index)
+                || !sp_io::crypto::sr25519_verify(&signature, statement, signer)
+            {
+                return false;
+            }
+            signed.push(index);
+        }
+        !signers.is_empty() && signed.len() * 2 > signers.len()
+    }
+
+    /// Install a freshly generated single-member committee for `chain_id`
+    /// and return a proof it signed over `statement`.
+    #[cfg(feature = "runtime-benchmarks")]
+    fn benchmark_proof(chain_id: &[u8], statement: &[u8]) -> Vec<u8> {
+        let signer = sp_io::crypto::sr25519_generate(COUNTERPARTY_KEY_TYPE, None);
+        let Ok(bounded_chain_id) = ChainId::<T>::try_from(chain_id.to_vec()) else {
+            return Vec::new();
+        };
+        let Ok(signers) = BoundedVec::try_from(alloc::vec![signer]) else {
+            return Vec::new();
+        };
+        CounterpartySigners::<T>::insert(&bounded_chain_id, signers);
+        sp_io::crypto::sr25519_sign(COUNTERPARTY_KEY_TYPE, &signer, statement)
+            .map(|signature| alloc::vec![(0u32, signature)].encode())
+            .unwrap_or_default()
+    }
+}
+
+impl<T: Config> traits::AttestationVerifier for CounterpartySignerSet<T> {
+    fn verify(
+        chain_id: &[u8],
+        remote_agent_id: &[u8],
+        local_agent_id: u64,
+        record_hash: H256,
+        proof: &[u8],
+    ) -> bool {
+        Self::quorum_signed(
+            chain_id,
+            &Self::attestation_statement(chain_id, remote_agent_id, local_agent_id, record_hash),
+            proof,
+        )
+    }
+}
+
+impl<T: Config> traits::FraudProofVerifier for CounterpartySignerSet<T> {
+    fn verify(chain_id: &[u8], packet_commitment: H256, proof: &[u8]) -> bool {
+        Self::quorum_signed(
+            chain_id,
+            &Self::fraud_statement(chain_id, packet_commitment),
+            proof,
+        )
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn valid_proof(chain_id: &[u8], packet_commitment: H256) -> Vec<u8> {
+        Self::benchmark_proof(
+            chain_id,
+            &Self::fraud_statement(chain_id, packet_commitment),
+        )
+    }
+}
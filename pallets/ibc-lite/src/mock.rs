@@ -3,11 +3,13 @@
 #![cfg(test)]
 
 use super::*;
-use crate::traits::AgentRegistryInterface;
+use crate::traits::{
+    AgentRegistryInterface, AttestationVerifier, FraudProofVerifier, IbcApplication, RelayerStake,
+};
 use frame_support::derive_impl;
-use frame_support::traits::ConstU32;
+use frame_support::traits::{ConstU32, ConstU64};
 use sp_core::H256;
-use sp_runtime::BuildStorage;
+use sp_runtime::{BuildStorage, DispatchError};
 
 // =========================================================
 // Mock Agent Registry
@@ -33,6 +35,117 @@ impl AgentRegistryInterface<u64> for MockAgentRegistry {
     }
 }
 
+// =========================================================
+// Mock IBC Application
+// =========================================================
+
+/// Channel, sequence, success flag and ack data of a delivered ack.
+pub type ReceivedAck = (Vec<u8>, Sequence, bool, Vec<u8>);
+
+thread_local! {
+    static ACKS_RECEIVED: core::cell::RefCell<Vec<ReceivedAck>> =
+        const { core::cell::RefCell::new(Vec::new()) };
+    static REJECT_RECV: core::cell::Cell<bool> = const { core::cell::Cell::new(false) };
+}
+
+/// Records every ack and consumes payloads starting with `0xff`. Inbound
+/// packets fail while `set_reject_recv(true)` is in effect.
+pub struct MockIbcApplication;
+
+impl MockIbcApplication {
+    pub fn acks_received() -> Vec<ReceivedAck> {
+        ACKS_RECEIVED.with(|a| a.borrow().clone())
+    }
+
+    pub fn set_reject_recv(reject: bool) {
+        REJECT_RECV.with(|r| r.set(reject));
+    }
+}
+
+impl IbcApplication for MockIbcApplication {
+    fn on_recv(_channel_id: &[u8], _sequence: Sequence, _payload: &[u8]) -> DispatchResult {
+        if REJECT_RECV.with(|r| r.get()) {
+            return Err(DispatchError::Unavailable);
+        }
+        Ok(())
+    }
+
+    fn on_ack(channel_id: &[u8], sequence: Sequence, success: bool, data: &[u8]) -> bool {
+        ACKS_RECEIVED.with(|a| {
+            a.borrow_mut()
+                .push((channel_id.to_vec(), sequence, success, data.to_vec()))
+        });
+        data.first() == Some(&0xff)
+    }
+}
+
+// =========================================================
+// Mock Attestation Verifier
+// =========================================================
+
+/// Accepts an attestation whose proof equals its record hash.
+pub struct MockAttestationVerifier;
+
+impl AttestationVerifier for MockAttestationVerifier {
+    fn verify(
+        _chain_id: &[u8],
+        _remote_agent_id: &[u8],
+        _local_agent_id: u64,
+        record_hash: H256,
+        proof: &[u8],
+    ) -> bool {
+        proof == record_hash.as_bytes()
+    }
+}
+
+// =========================================================
+// Mock Fraud Proof Verifier
+// =========================================================
+
+/// Accepts the literal proof `b"fraud"`.
+pub struct MockFraudProofVerifier;
+
+impl FraudProofVerifier for MockFraudProofVerifier {
+    fn verify(_chain_id: &[u8], _packet_commitment: H256, proof: &[u8]) -> bool {
+        proof == b"fraud"
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn valid_proof(_chain_id: &[u8], _packet_commitment: H256) -> Vec<u8> {
+        b"fraud".to_vec()
+    }
+}
+
+// =========================================================
+// Mock Relayer Stake
+// =========================================================
+
+thread_local! {
+    static RELAYER_STAKES: core::cell::RefCell<Vec<(u64, u128)>> =
+        const { core::cell::RefCell::new(Vec::new()) };
+}
+
+/// Reports stakes set with `set_stake`; everyone else has none.
+pub struct MockRelayerStake;
+
+impl MockRelayerStake {
+    pub fn set_stake(relayer: u64, stake: u128) {
+        RELAYER_STAKES.with(|s| s.borrow_mut().push((relayer, stake)));
+    }
+}
+
+impl RelayerStake<u64> for MockRelayerStake {
+    fn stake_of(who: &u64) -> u128 {
+        RELAYER_STAKES.with(|s| {
+            s.borrow()
+                .iter()
+                .rev()
+                .find(|(relayer, _)| relayer == who)
+                .map_or(0, |(_, stake)| *stake)
+        })
+    }
+}
+
 // =========================================================
 // Mock Runtime
 // =========================================================
@@ -40,6 +153,7 @@ impl AgentRegistryInterface<u64> for MockAgentRegistry {
 frame_support::construct_runtime!(
     pub enum Runtime {
         System: frame_system,
+        Balances: pallet_balances,
         IbcLite: crate,
     }
 );
@@ -47,7 +161,12 @@ frame_support::construct_runtime!(
 #[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
 impl frame_system::Config for Runtime {
     type Block = frame_system::mocking::MockBlockU32<Runtime>;
-    type AccountData = ();
+    type AccountData = pallet_balances::AccountData<u64>;
+}
+
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+impl pallet_balances::Config for Runtime {
+    type AccountStore = System;
 }
 
 impl Config for Runtime {
@@ -62,6 +181,19 @@ impl Config for Runtime {
     type MaxPendingPackets = ConstU32<1000>;
     type PacketTimeoutBlocks = ConstU32<100>;
     type AgentRegistry = MockAgentRegistry;
+    type IbcApplication = MockIbcApplication;
+    type AttestationVerifier = MockAttestationVerifier;
+    type AttestationValidityBlocks = ConstU32<50>;
+    type MaxDeadLetters = ConstU32<2>;
+    type MaxRetainedAckPayloads = ConstU32<2>;
+    type Currency = Balances;
+    type OpenSubmissionBond = ConstU64<100>;
+    type ChallengePeriod = ConstU32<10>;
+    type FraudProofVerifier = MockFraudProofVerifier;
+    type RelayerStake = MockRelayerStake;
+    type RelayerExclusivityWindow = ConstU32<10>;
+    type RelayerCompensationSource = ConstU64<20>;
+    type PrimaryRelayerCompensation = ConstU64<5>;
 }
 
 // =========================================================
@@ -69,8 +201,14 @@ impl Config for Runtime {
 // =========================================================
 
 pub fn new_test_ext() -> sp_io::TestExternalities {
-    frame_system::GenesisConfig::<Runtime>::default()
+    let mut t = frame_system::GenesisConfig::<Runtime>::default()
         .build_storage()
-        .unwrap()
-        .into()
+        .unwrap();
+    pallet_balances::GenesisConfig::<Runtime> {
+        balances: vec![(20, 1_000), (21, 1_000)],
+        ..Default::default()
+    }
+    .assimilate_storage(&mut t)
+    .unwrap();
+    t.into()
 }
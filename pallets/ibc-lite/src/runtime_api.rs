@@ -0,0 +1,17 @@
+//! Runtime API for querying IBC-lite state off-chain.
+
+use alloc::vec::Vec;
+use codec::Codec;
+
+sp_api::decl_runtime_apis! {
+    /// IBC-lite queries for relayers and operators.
+    ///
+    /// `DeadLetter` is the runtime's `pallet_ibc_lite::DeadLetter<Runtime>`.
+    pub trait IbcLiteApi<DeadLetter>
+    where
+        DeadLetter: Codec,
+    {
+        /// Packets in `channel_id`'s dead-letter queue.
+        fn dead_letters(channel_id: Vec<u8>) -> Vec<DeadLetter>;
+    }
+}
@@ -4,6 +4,7 @@
 
 use super::*;
 use crate::mock::*;
+use clawchain_primitives::EventBytes;
 use frame_support::{assert_err, assert_ok};
 
 // =========================================================
@@ -19,6 +20,7 @@ fn open_channel_helper(channel_num: u64) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
         frame_system::RawOrigin::Root.into(),
         counterparty_chain.clone(),
         counterparty_channel.clone(),
+        vec![],
     ));
 
     // Manually transition to Open for testing
@@ -43,7 +45,7 @@ fn open_channel_helper(channel_num: u64) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
 #[test]
 fn open_channel_works() {
     new_test_ext().execute_with(|| {
-        let (channel_id, chain, remote) = open_channel_helper(0);
+        let (channel_id, chain, _) = open_channel_helper(0);
 
         // Verify channel was created
         let bounded_id: ChannelId<Runtime> = channel_id.clone().try_into().unwrap();
@@ -66,6 +68,7 @@ fn open_channel_requires_authorized_origin() {
                 frame_system::RawOrigin::Signed(1).into(),
                 b"chain-0".to_vec(),
                 b"remote-channel-0".to_vec(),
+                vec![],
             ),
             sp_runtime::DispatchError::BadOrigin
         );
@@ -237,8 +240,8 @@ fn receive_packet_works() {
             src_agent_id: None,
             dst_agent_id: None,
             payload: PacketPayload::Raw(vec![1, 2, 3].try_into().unwrap()),
-            timeout_height: 1000u32.into(),
-            created_at: 100u32.into(),
+            timeout_height: 1000,
+            created_at: 100,
         };
 
         assert_ok!(IbcLite::receive_packet(
@@ -276,8 +279,8 @@ fn receive_packet_rejects_replay() {
             src_agent_id: None,
             dst_agent_id: None,
             payload: PacketPayload::Raw(vec![1, 2, 3].try_into().unwrap()),
-            timeout_height: 1000u32.into(),
-            created_at: 100u32.into(),
+            timeout_height: 1000,
+            created_at: 100,
         };
 
         // First receive
@@ -310,8 +313,8 @@ fn receive_packet_rejects_non_relayer() {
             src_agent_id: None,
             dst_agent_id: None,
             payload: PacketPayload::Raw(vec![1, 2, 3].try_into().unwrap()),
-            timeout_height: 1000u32.into(),
-            created_at: 100u32.into(),
+            timeout_height: 1000,
+            created_at: 100,
         };
 
         assert_err!(
@@ -394,6 +397,165 @@ fn acknowledge_packet_fails_if_no_commitment() {
     });
 }
 
+fn send_and_ack(channel_id: &[u8], data: Vec<u8>) {
+    assert_ok!(IbcLite::add_relayer(
+        frame_system::RawOrigin::Root.into(),
+        10,
+    ));
+    assert_ok!(IbcLite::send_packet(
+        frame_system::RawOrigin::Signed(1).into(),
+        channel_id.to_vec(),
+        b"chain-0".to_vec(),
+        b"remote-channel-0".to_vec(),
+        None,
+        PacketPayload::Raw(vec![1, 2, 3].try_into().unwrap()),
+    ));
+    assert_ok!(IbcLite::acknowledge_packet(
+        frame_system::RawOrigin::Signed(10).into(),
+        channel_id.to_vec(),
+        1,
+        PacketPayload::Ack {
+            success: true,
+            error_code: None,
+            data: data.try_into().unwrap(),
+        },
+    ));
+}
+
+#[test]
+fn acknowledge_packet_routes_ack_data_to_application() {
+    new_test_ext().execute_with(|| {
+        let (channel_id, _, _) = open_channel_helper(0);
+        send_and_ack(&channel_id, vec![7, 7]);
+
+        assert_eq!(
+            MockIbcApplication::acks_received(),
+            vec![(channel_id.clone(), 1, true, vec![7, 7])]
+        );
+
+        // Not consumed by the application, so retained for the sender
+        let bounded_id: ChannelId<Runtime> = channel_id.try_into().unwrap();
+        assert_eq!(
+            AckPayloads::<Runtime>::get(&bounded_id, 1)
+                .unwrap()
+                .to_vec(),
+            vec![7, 7]
+        );
+        assert_eq!(PacketSenders::<Runtime>::get(&bounded_id, 1), Some(1));
+    });
+}
+
+#[test]
+fn acknowledge_packet_drops_payload_consumed_by_application() {
+    new_test_ext().execute_with(|| {
+        let (channel_id, _, _) = open_channel_helper(0);
+        send_and_ack(&channel_id, vec![0xff, 1]);
+
+        let bounded_id: ChannelId<Runtime> = channel_id.try_into().unwrap();
+        assert!(!AckPayloads::<Runtime>::contains_key(&bounded_id, 1));
+        assert!(!PacketSenders::<Runtime>::contains_key(&bounded_id, 1));
+    });
+}
+
+#[test]
+fn consume_ack_payload_works() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let (channel_id, _, _) = open_channel_helper(0);
+        send_and_ack(&channel_id, vec![7, 7]);
+
+        assert_err!(
+            IbcLite::consume_ack_payload(
+                frame_system::RawOrigin::Signed(2).into(),
+                channel_id.clone(),
+                1,
+            ),
+            Error::<Runtime>::NotPacketSender
+        );
+
+        assert_ok!(IbcLite::consume_ack_payload(
+            frame_system::RawOrigin::Signed(1).into(),
+            channel_id.clone(),
+            1,
+        ));
+        System::assert_last_event(
+            Event::AckPayloadConsumed {
+                channel_id: EventBytes::new(&channel_id),
+                sequence: 1,
+                data: EventBytes::new(&[7, 7]),
+            }
+            .into(),
+        );
+
+        let bounded_id: ChannelId<Runtime> = channel_id.clone().try_into().unwrap();
+        assert!(!AckPayloads::<Runtime>::contains_key(&bounded_id, 1));
+        assert_err!(
+            IbcLite::consume_ack_payload(frame_system::RawOrigin::Signed(1).into(), channel_id, 1),
+            Error::<Runtime>::AckPayloadNotFound
+        );
+    });
+}
+
+#[test]
+fn unconsumed_ack_payloads_are_bounded_per_sender() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let (channel_id, _, _) = open_channel_helper(0);
+        assert_ok!(IbcLite::add_relayer(
+            frame_system::RawOrigin::Root.into(),
+            10,
+        ));
+        let send_and_ack = |sequence: Sequence| {
+            assert_ok!(IbcLite::send_packet(
+                frame_system::RawOrigin::Signed(1).into(),
+                channel_id.clone(),
+                b"chain-0".to_vec(),
+                b"remote-channel-0".to_vec(),
+                None,
+                PacketPayload::Raw(vec![1, 2, 3].try_into().unwrap()),
+            ));
+            assert_ok!(IbcLite::acknowledge_packet(
+                frame_system::RawOrigin::Signed(10).into(),
+                channel_id.clone(),
+                sequence,
+                PacketPayload::Ack {
+                    success: true,
+                    error_code: None,
+                    data: vec![7].try_into().unwrap(),
+                },
+            ));
+        };
+
+        // MaxRetainedAckPayloads = 2: the third payload is dropped.
+        for sequence in 1..=3 {
+            send_and_ack(sequence);
+        }
+        let bounded_id: ChannelId<Runtime> = channel_id.clone().try_into().unwrap();
+        assert_eq!(RetainedAckPayloads::<Runtime>::get(1), 2);
+        assert!(!AckPayloads::<Runtime>::contains_key(&bounded_id, 3));
+        assert!(!PacketSenders::<Runtime>::contains_key(&bounded_id, 3));
+        System::assert_has_event(
+            Event::AckPayloadDropped {
+                channel_id: EventBytes::new(&channel_id),
+                sequence: 3,
+                sender: 1,
+            }
+            .into(),
+        );
+
+        // Consuming one frees room for the next.
+        assert_ok!(IbcLite::consume_ack_payload(
+            frame_system::RawOrigin::Signed(1).into(),
+            channel_id.clone(),
+            1,
+        ));
+        assert_eq!(RetainedAckPayloads::<Runtime>::get(1), 1);
+        send_and_ack(4);
+        assert!(AckPayloads::<Runtime>::contains_key(&bounded_id, 4));
+        assert_eq!(RetainedAckPayloads::<Runtime>::get(1), 2);
+    });
+}
+
 #[test]
 fn timeout_packet_works() {
     new_test_ext().execute_with(|| {
@@ -491,82 +653,761 @@ fn remove_relayer_fails_if_not_registered() {
     });
 }
 
+#[test]
+fn channel_relayers_must_be_trusted() {
+    new_test_ext().execute_with(|| {
+        assert_err!(
+            IbcLite::open_channel(
+                frame_system::RawOrigin::Root.into(),
+                b"chain-0".to_vec(),
+                b"remote-channel-0".to_vec(),
+                vec![10],
+            ),
+            Error::<Runtime>::NotTrustedRelayer
+        );
+
+        let (channel_id, _, _) = open_channel_helper(0);
+        assert_ok!(IbcLite::add_relayer(
+            frame_system::RawOrigin::Root.into(),
+            10,
+        ));
+        assert_err!(
+            IbcLite::set_channel_relayers(
+                frame_system::RawOrigin::Root.into(),
+                channel_id.clone(),
+                vec![10, 10],
+            ),
+            Error::<Runtime>::RelayerAlreadyRegistered
+        );
+        assert_err!(
+            IbcLite::set_channel_relayers(
+                frame_system::RawOrigin::Signed(10).into(),
+                channel_id,
+                vec![10],
+            ),
+            sp_runtime::DispatchError::BadOrigin
+        );
+    });
+}
+
+#[test]
+fn channel_relayer_whitelist_is_enforced() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let (channel_id, _, remote) = open_channel_helper(0);
+        for relayer in [10, 11] {
+            assert_ok!(IbcLite::add_relayer(
+                frame_system::RawOrigin::Root.into(),
+                relayer,
+            ));
+        }
+        assert_ok!(IbcLite::set_channel_relayers(
+            frame_system::RawOrigin::Root.into(),
+            channel_id.clone(),
+            vec![10],
+        ));
+        System::assert_last_event(
+            Event::<Runtime>::ChannelRelayersSet {
+                channel_id: EventBytes::new(&channel_id),
+                relayers: vec![10],
+            }
+            .into(),
+        );
+
+        let bounded_id: ChannelId<Runtime> = channel_id.clone().try_into().unwrap();
+        let packet = Packet::<Runtime> {
+            sequence: 1,
+            src_channel_id: remote.try_into().unwrap(),
+            dst_channel_id: bounded_id.clone(),
+            dst_chain_id: b"clawchain".to_vec().try_into().unwrap(),
+            src_agent_id: None,
+            dst_agent_id: None,
+            payload: PacketPayload::Raw(vec![1, 2, 3].try_into().unwrap()),
+            timeout_height: 1000,
+            created_at: 100,
+        };
+
+        // A trusted relayer outside the whitelist is rejected on this channel.
+        assert_err!(
+            IbcLite::receive_packet(frame_system::RawOrigin::Signed(11).into(), packet.clone()),
+            Error::<Runtime>::NotChannelRelayer
+        );
+        assert_err!(
+            IbcLite::close_channel_confirm(
+                frame_system::RawOrigin::Signed(11).into(),
+                channel_id.clone(),
+            ),
+            Error::<Runtime>::NotChannelRelayer
+        );
+        assert_ok!(IbcLite::receive_packet(
+            frame_system::RawOrigin::Signed(10).into(),
+            packet,
+        ));
+
+        // Clearing the whitelist reopens the channel to every trusted relayer.
+        assert_ok!(IbcLite::set_channel_relayers(
+            frame_system::RawOrigin::Root.into(),
+            channel_id.clone(),
+            vec![],
+        ));
+        assert!(!ChannelRelayers::<Runtime>::contains_key(&bounded_id));
+        assert_ok!(IbcLite::close_channel_confirm(
+            frame_system::RawOrigin::Signed(11).into(),
+            channel_id,
+        ));
+    });
+}
+
+#[test]
+fn primary_relayer_has_exclusive_window_then_is_compensated() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let (channel_id, _, _) = open_channel_helper(0);
+        for relayer in [10, 11, 12] {
+            assert_ok!(IbcLite::add_relayer(
+                frame_system::RawOrigin::Root.into(),
+                relayer,
+            ));
+        }
+        let bounded_id: ChannelId<Runtime> = channel_id.clone().try_into().unwrap();
+
+        // Without stake every relayer gets a share of the sequences.
+        let primaries: alloc::collections::BTreeSet<u64> = (1..=20)
+            .filter_map(|sequence| IbcLite::primary_relayer(&bounded_id, sequence))
+            .collect();
+        assert!(primaries.len() > 1);
+
+        // All stake on relayer 12 makes it primary for every sequence.
+        MockRelayerStake::set_stake(12, 100);
+        assert_eq!(IbcLite::primary_relayer(&bounded_id, 1), Some(12));
+        assert_eq!(IbcLite::primary_relayer(&bounded_id, 2), Some(12));
+
+        // A backup's first offer only starts the window.
+        System::set_block_number(5);
+        assert_ok!(IbcLite::receive_packet(
+            frame_system::RawOrigin::Signed(10).into(),
+            raw_packet(1),
+        ));
+        System::assert_last_event(
+            Event::<Runtime>::PacketFirstSeen {
+                channel_id: EventBytes::new(&channel_id),
+                sequence: 1,
+                exclusive_until: 15,
+            }
+            .into(),
+        );
+        assert!(!PacketReceipts::<Runtime>::contains_key(&bounded_id, 1));
+        System::set_block_number(6);
+        assert_err!(
+            IbcLite::receive_packet(frame_system::RawOrigin::Signed(10).into(), raw_packet(1)),
+            Error::<Runtime>::NotPrimaryRelayer
+        );
+        assert_ok!(IbcLite::receive_packet(
+            frame_system::RawOrigin::Signed(12).into(),
+            raw_packet(1),
+        ));
+        assert_eq!(Balances::free_balance(12), 0);
+        assert!(!PacketFirstSeen::<Runtime>::contains_key(&bounded_id));
+
+        // Packet 2 claims creation at block 1, but its window runs from the
+        // block a backup first offered it.
+        assert_ok!(IbcLite::receive_packet(
+            frame_system::RawOrigin::Signed(10).into(),
+            raw_packet(2),
+        ));
+        System::set_block_number(15);
+        assert_err!(
+            IbcLite::receive_packet(frame_system::RawOrigin::Signed(10).into(), raw_packet(2)),
+            Error::<Runtime>::NotPrimaryRelayer
+        );
+        System::set_block_number(16);
+        assert_ok!(IbcLite::receive_packet(
+            frame_system::RawOrigin::Signed(10).into(),
+            raw_packet(2),
+        ));
+        assert_eq!(Balances::free_balance(12), 5);
+        assert_eq!(Balances::free_balance(20), 995);
+        System::assert_last_event(
+            Event::<Runtime>::PrimaryRelayerCompensated {
+                channel_id: EventBytes::new(&channel_id),
+                sequence: 2,
+                primary: 12,
+                amount: 5,
+            }
+            .into(),
+        );
+    });
+}
+
 // =========================================================
-// Cross-Chain Agent Tests
+// Dead Letter Tests
 // =========================================================
 
+fn raw_packet(sequence: Sequence) -> Packet<Runtime> {
+    Packet::<Runtime> {
+        sequence,
+        src_channel_id: b"remote-channel-0".to_vec().try_into().unwrap(),
+        dst_channel_id: b"channel-0".to_vec().try_into().unwrap(),
+        dst_chain_id: b"clawchain".to_vec().try_into().unwrap(),
+        src_agent_id: None,
+        dst_agent_id: None,
+        payload: PacketPayload::Raw(vec![1, 2, 3].try_into().unwrap()),
+        timeout_height: 1000,
+        created_at: 1,
+    }
+}
+
 #[test]
-fn register_cross_chain_agent_works() {
+fn failed_application_handling_dead_letters_packet() {
     new_test_ext().execute_with(|| {
-        // Add relayer
+        System::set_block_number(1);
+        let (channel_id, _, _) = open_channel_helper(0);
         assert_ok!(IbcLite::add_relayer(
             frame_system::RawOrigin::Root.into(),
             10,
         ));
+        MockIbcApplication::set_reject_recv(true);
 
-        assert_ok!(IbcLite::register_cross_chain_agent(
-            frame_system::RawOrigin::Signed(10).into(),
-            b"remote-chain".to_vec(),
-            b"remote-agent-1".to_vec(),
-            5, // Valid agent ID (1-100 in mock)
+        for sequence in 1..=2 {
+            assert_ok!(IbcLite::receive_packet(
+                frame_system::RawOrigin::Signed(10).into(),
+                raw_packet(sequence),
+            ));
+        }
+        System::assert_last_event(
+            Event::PacketDeadLettered {
+                channel_id: EventBytes::new(&channel_id),
+                sequence: 2,
+                reason: sp_runtime::DispatchError::Unavailable,
+            }
+            .into(),
+        );
+
+        // The packet is received (no replay) but parked for retry.
+        let bounded_id: ChannelId<Runtime> = channel_id.clone().try_into().unwrap();
+        assert!(PacketReceipts::<Runtime>::contains_key(&bounded_id, 1));
+        assert_eq!(IbcLite::dead_letters(&channel_id).len(), 2);
+        assert_eq!(DeadLetterCount::<Runtime>::get(&bounded_id), 2);
+
+        // MaxDeadLetters = 2: a third failure is rejected outright.
+        assert_err!(
+            IbcLite::receive_packet(frame_system::RawOrigin::Signed(10).into(), raw_packet(3)),
+            Error::<Runtime>::DeadLetterQueueFull
+        );
+        assert!(!PacketReceipts::<Runtime>::contains_key(&bounded_id, 3));
+    });
+}
+
+#[test]
+fn retry_and_discard_dead_letters() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let (channel_id, _, _) = open_channel_helper(0);
+        assert_ok!(IbcLite::add_relayer(
+            frame_system::RawOrigin::Root.into(),
+            10,
+        ));
+        MockIbcApplication::set_reject_recv(true);
+        for sequence in 1..=2 {
+            assert_ok!(IbcLite::receive_packet(
+                frame_system::RawOrigin::Signed(10).into(),
+                raw_packet(sequence),
+            ));
+        }
+
+        // Retry while the application still fails keeps the entry.
+        assert_err!(
+            IbcLite::retry_dead_letter(frame_system::RawOrigin::Root.into(), channel_id.clone(), 1),
+            sp_runtime::DispatchError::Unavailable
+        );
+        assert_err!(
+            IbcLite::retry_dead_letter(
+                frame_system::RawOrigin::Signed(10).into(),
+                channel_id.clone(),
+                1,
+            ),
+            sp_runtime::DispatchError::BadOrigin
+        );
+
+        MockIbcApplication::set_reject_recv(false);
+        assert_ok!(IbcLite::retry_dead_letter(
+            frame_system::RawOrigin::Root.into(),
+            channel_id.clone(),
+            1,
+        ));
+        System::assert_last_event(
+            Event::DeadLetterRetried {
+                channel_id: EventBytes::new(&channel_id),
+                sequence: 1,
+            }
+            .into(),
+        );
+
+        assert_ok!(IbcLite::discard_dead_letter(
+            frame_system::RawOrigin::Root.into(),
+            channel_id.clone(),
+            2,
+        ));
+        assert_err!(
+            IbcLite::discard_dead_letter(
+                frame_system::RawOrigin::Root.into(),
+                channel_id.clone(),
+                2
+            ),
+            Error::<Runtime>::DeadLetterNotFound
+        );
+
+        let bounded_id: ChannelId<Runtime> = channel_id.clone().try_into().unwrap();
+        assert!(IbcLite::dead_letters(&channel_id).is_empty());
+        assert_eq!(DeadLetterCount::<Runtime>::get(&bounded_id), 0);
+    });
+}
+
+// =========================================================
+// Open Relaying Tests
+// =========================================================
+
+#[test]
+fn unchallenged_submission_finalizes_and_returns_bond() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let (channel_id, _, _) = open_channel_helper(0);
+
+        assert_ok!(IbcLite::submit_packet_bonded(
+            frame_system::RawOrigin::Signed(20).into(),
+            raw_packet(1),
         ));
+        assert_eq!(Balances::reserved_balance(20), 100);
+        assert_err!(
+            IbcLite::submit_packet_bonded(
+                frame_system::RawOrigin::Signed(21).into(),
+                raw_packet(1)
+            ),
+            Error::<Runtime>::SubmissionAlreadyPending
+        );
+
+        System::set_block_number(10);
+        assert_err!(
+            IbcLite::finalize_submission(
+                frame_system::RawOrigin::Signed(21).into(),
+                channel_id.clone(),
+                1
+            ),
+            Error::<Runtime>::ChallengePeriodNotOver
+        );
+
+        System::set_block_number(11);
+        assert_ok!(IbcLite::finalize_submission(
+            frame_system::RawOrigin::Signed(21).into(),
+            channel_id.clone(),
+            1,
+        ));
+        let bounded_id: ChannelId<Runtime> = channel_id.clone().try_into().unwrap();
+        assert!(PacketReceipts::<Runtime>::contains_key(&bounded_id, 1));
+        assert_eq!(Balances::reserved_balance(20), 0);
+        System::assert_last_event(
+            Event::SubmissionFinalized {
+                channel_id: EventBytes::new(&channel_id),
+                sequence: 1,
+                submitter: 20,
+            }
+            .into(),
+        );
+    });
+}
 
-        let chain_id: ChainId<Runtime> = b"remote-chain".to_vec().try_into().unwrap();
-        let agent_id: RemoteAgentId<Runtime> = b"remote-agent-1".to_vec().try_into().unwrap();
+#[test]
+fn challenged_submission_slashes_bond_to_challenger() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let (channel_id, _, _) = open_channel_helper(0);
+        assert_ok!(IbcLite::submit_packet_bonded(
+            frame_system::RawOrigin::Signed(20).into(),
+            raw_packet(1),
+        ));
 
+        // A non-relayer needs a valid fraud proof.
+        assert_err!(
+            IbcLite::challenge_submission(
+                frame_system::RawOrigin::Signed(21).into(),
+                channel_id.clone(),
+                1,
+                Vec::new(),
+            ),
+            Error::<Runtime>::InvalidFraudProof
+        );
+        assert_err!(
+            IbcLite::challenge_submission(
+                frame_system::RawOrigin::Signed(21).into(),
+                channel_id.clone(),
+                1,
+                b"bogus".to_vec(),
+            ),
+            Error::<Runtime>::InvalidFraudProof
+        );
+        assert_ok!(IbcLite::challenge_submission(
+            frame_system::RawOrigin::Signed(21).into(),
+            channel_id.clone(),
+            1,
+            b"fraud".to_vec(),
+        ));
+
+        assert_eq!(Balances::free_balance(20), 900);
+        assert_eq!(Balances::free_balance(21), 1_100);
+        let bounded_id: ChannelId<Runtime> = channel_id.clone().try_into().unwrap();
+        assert!(!PacketReceipts::<Runtime>::contains_key(&bounded_id, 1));
+        assert_err!(
+            IbcLite::finalize_submission(frame_system::RawOrigin::Signed(20).into(), channel_id, 1),
+            Error::<Runtime>::SubmissionNotFound
+        );
+    });
+}
+
+#[test]
+fn challenge_needs_proof_and_must_land_within_window() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let (channel_id, _, _) = open_channel_helper(0);
+        assert_ok!(IbcLite::add_relayer(
+            frame_system::RawOrigin::Root.into(),
+            10,
+        ));
+        assert_ok!(IbcLite::submit_packet_bonded(
+            frame_system::RawOrigin::Signed(20).into(),
+            raw_packet(1),
+        ));
+
+        // Being a channel relayer is not a substitute for a fraud proof.
+        assert_err!(
+            IbcLite::challenge_submission(
+                frame_system::RawOrigin::Signed(10).into(),
+                channel_id.clone(),
+                1,
+                Vec::new(),
+            ),
+            Error::<Runtime>::InvalidFraudProof
+        );
+
+        System::set_block_number(11);
+        assert_err!(
+            IbcLite::challenge_submission(
+                frame_system::RawOrigin::Signed(21).into(),
+                channel_id.clone(),
+                1,
+                b"fraud".to_vec(),
+            ),
+            Error::<Runtime>::ChallengePeriodOver
+        );
+
+        System::set_block_number(10);
+        assert_ok!(IbcLite::challenge_submission(
+            frame_system::RawOrigin::Signed(21).into(),
+            channel_id,
+            1,
+            b"fraud".to_vec(),
+        ));
+        assert_eq!(Balances::free_balance(21), 1_100);
+    });
+}
+
+#[test]
+fn unreceivable_submission_is_dropped_and_bond_returned() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let (channel_id, _, _) = open_channel_helper(0);
+        assert_ok!(IbcLite::submit_packet_bonded(
+            frame_system::RawOrigin::Signed(20).into(),
+            raw_packet(1),
+        ));
+        assert_ok!(IbcLite::close_channel_init(
+            frame_system::RawOrigin::Root.into(),
+            channel_id.clone(),
+        ));
+
+        System::set_block_number(11);
+        assert_ok!(IbcLite::finalize_submission(
+            frame_system::RawOrigin::Signed(21).into(),
+            channel_id.clone(),
+            1,
+        ));
+        let bounded_id: ChannelId<Runtime> = channel_id.clone().try_into().unwrap();
+        assert!(!PendingSubmissions::<Runtime>::contains_key(&bounded_id, 1));
+        assert!(!PacketReceipts::<Runtime>::contains_key(&bounded_id, 1));
+        assert_eq!(RecvSequences::<Runtime>::get(&bounded_id), 1);
+        assert_eq!(Balances::reserved_balance(20), 0);
+        System::assert_last_event(
+            Event::SubmissionDropped {
+                channel_id: EventBytes::new(&channel_id),
+                sequence: 1,
+                submitter: 20,
+                reason: Error::<Runtime>::ChannelNotOpen.into(),
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn counterparty_signer_set_requires_majority() {
+    use crate::traits::FraudProofVerifier;
+    use sp_core::{sr25519, Pair};
+
+    new_test_ext().execute_with(|| {
+        let pairs: Vec<sr25519::Pair> = (1u8..=3)
+            .map(|seed| sr25519::Pair::from_seed(&[seed; 32]))
+            .collect();
+        assert_ok!(IbcLite::set_counterparty_signers(
+            frame_system::RawOrigin::Root.into(),
+            b"chain-0".to_vec(),
+            pairs.iter().map(|pair| pair.public()).collect(),
+        ));
+
+        let commitment = H256::repeat_byte(7);
+        let statement = CounterpartySignerSet::<Runtime>::fraud_statement(b"chain-0", commitment);
+        let proof = |signers: &[u32]| {
+            signers
+                .iter()
+                .map(|i| (*i, pairs[*i as usize].sign(&statement)))
+                .collect::<Vec<_>>()
+                .encode()
+        };
+        let verify = |chain: &[u8], proof: &[u8]| {
+            <CounterpartySignerSet<Runtime> as FraudProofVerifier>::verify(chain, commitment, proof)
+        };
+
+        assert!(!verify(b"chain-0", &proof(&[0])));
+        assert!(!verify(b"chain-0", &proof(&[0, 0])));
+        assert!(verify(b"chain-0", &proof(&[0, 2])));
+        assert!(!verify(b"chain-1", &proof(&[0, 2])));
+        assert!(!verify(b"chain-0", b"garbage"));
+    });
+}
+
+// =========================================================
+// Attested Cross-Chain Agent Tests
+// =========================================================
+
+/// Deliver an `AgentAttestation` packet on `channel-0` (counterparty `chain-0`).
+fn receive_attestation(
+    sequence: Sequence,
+    local_agent_id: AgentId,
+    record_hash: H256,
+    proof: &[u8],
+) -> frame_support::dispatch::DispatchResult {
+    let bounded_id: ChannelId<Runtime> = b"channel-0".to_vec().try_into().unwrap();
+    let packet = Packet::<Runtime> {
+        sequence,
+        src_channel_id: b"remote-channel-0".to_vec().try_into().unwrap(),
+        dst_channel_id: bounded_id,
+        dst_chain_id: b"clawchain".to_vec().try_into().unwrap(),
+        src_agent_id: None,
+        dst_agent_id: None,
+        payload: PacketPayload::AgentAttestation {
+            remote_agent_id: b"remote-agent-1".to_vec().try_into().unwrap(),
+            local_agent_id,
+            record_hash,
+            proof: proof.to_vec().try_into().unwrap(),
+        },
+        timeout_height: 1000,
+        created_at: 1,
+    };
+    IbcLite::receive_packet(frame_system::RawOrigin::Signed(10).into(), packet)
+}
+
+fn attested_keys() -> (ChainId<Runtime>, RemoteAgentId<Runtime>) {
+    (
+        b"chain-0".to_vec().try_into().unwrap(),
+        b"remote-agent-1".to_vec().try_into().unwrap(),
+    )
+}
+
+#[test]
+fn attestation_packet_creates_expiring_mapping() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        open_channel_helper(0);
+        assert_ok!(IbcLite::add_relayer(
+            frame_system::RawOrigin::Root.into(),
+            10,
+        ));
+
+        let record_hash = H256::repeat_byte(7);
+        assert_ok!(receive_attestation(
+            1,
+            5,
+            record_hash,
+            record_hash.as_bytes()
+        ));
+
+        let (chain_id, remote_id) = attested_keys();
+        assert_eq!(
+            CrossChainAgentMap::<Runtime>::get(&chain_id, &remote_id),
+            Some(5)
+        );
+        assert_eq!(
+            CrossChainAgentExpiry::<Runtime>::get(&chain_id, &remote_id),
+            Some(51)
+        );
         assert_eq!(
-            CrossChainAgentMap::<Runtime>::get(&chain_id, &agent_id),
+            IbcLite::resolve_cross_chain_agent(&chain_id, &remote_id),
             Some(5)
         );
     });
 }
 
 #[test]
-fn register_cross_chain_agent_rejects_duplicate() {
+fn attestation_packet_rejects_invalid_proof() {
     new_test_ext().execute_with(|| {
-        // Add relayer
+        System::set_block_number(1);
+        let (channel_id, _, _) = open_channel_helper(0);
         assert_ok!(IbcLite::add_relayer(
             frame_system::RawOrigin::Root.into(),
             10,
         ));
 
-        assert_ok!(IbcLite::register_cross_chain_agent(
-            frame_system::RawOrigin::Signed(10).into(),
-            b"remote-chain".to_vec(),
-            b"remote-agent-1".to_vec(),
+        // The packet is received with an error ack so the channel moves on.
+        assert_ok!(receive_attestation(1, 5, H256::repeat_byte(7), b"forged"));
+        System::assert_last_event(
+            Event::PacketErrorAcknowledged {
+                channel_id: EventBytes::new(&channel_id),
+                sequence: 1,
+                reason: Error::<Runtime>::InvalidAttestation.into(),
+            }
+            .into(),
+        );
+
+        let (chain_id, remote_id) = attested_keys();
+        let bounded_id: ChannelId<Runtime> = channel_id.try_into().unwrap();
+        assert!(!CrossChainAgentMap::<Runtime>::contains_key(
+            &chain_id, &remote_id
+        ));
+        assert_eq!(
+            PacketReceipts::<Runtime>::get(&bounded_id, 1),
+            Some(ReceiptStatus::Failed)
+        );
+        assert_eq!(RecvSequences::<Runtime>::get(&bounded_id), 2);
+    });
+}
+
+#[test]
+fn attestation_refresh_extends_expiry_and_blocks_takeover() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        open_channel_helper(0);
+        assert_ok!(IbcLite::add_relayer(
+            frame_system::RawOrigin::Root.into(),
+            10,
+        ));
+
+        let record_hash = H256::repeat_byte(7);
+        assert_ok!(receive_attestation(
+            1,
             5,
+            record_hash,
+            record_hash.as_bytes()
         ));
 
-        assert_err!(
-            IbcLite::register_cross_chain_agent(
-                frame_system::RawOrigin::Signed(10).into(),
-                b"remote-chain".to_vec(),
-                b"remote-agent-1".to_vec(),
-                6,
-            ),
-            Error::<Runtime>::CrossChainAgentAlreadyMapped
+        // A different local agent cannot take over a live mapping.
+        System::set_block_number(20);
+        assert_ok!(receive_attestation(
+            2,
+            6,
+            record_hash,
+            record_hash.as_bytes()
+        ));
+        System::assert_last_event(
+            Event::PacketErrorAcknowledged {
+                channel_id: EventBytes::new(b"channel-0"),
+                sequence: 2,
+                reason: Error::<Runtime>::CrossChainAgentAlreadyMapped.into(),
+            }
+            .into(),
+        );
+        let (chain_id, remote_id) = attested_keys();
+        assert_eq!(
+            CrossChainAgentMap::<Runtime>::get(&chain_id, &remote_id),
+            Some(5)
+        );
+
+        assert_ok!(receive_attestation(
+            3,
+            5,
+            record_hash,
+            record_hash.as_bytes()
+        ));
+        assert_eq!(
+            CrossChainAgentExpiry::<Runtime>::get(&chain_id, &remote_id),
+            Some(70)
         );
     });
 }
 
 #[test]
-fn register_cross_chain_agent_validates_agent_exists() {
+fn prune_cross_chain_agent_requires_expiry() {
     new_test_ext().execute_with(|| {
-        // Add relayer
+        System::set_block_number(1);
+        open_channel_helper(0);
         assert_ok!(IbcLite::add_relayer(
             frame_system::RawOrigin::Root.into(),
             10,
         ));
 
-        // Agent ID 999 doesn't exist in mock
+        let record_hash = H256::repeat_byte(7);
+        assert_ok!(receive_attestation(
+            1,
+            5,
+            record_hash,
+            record_hash.as_bytes()
+        ));
+
         assert_err!(
-            IbcLite::register_cross_chain_agent(
-                frame_system::RawOrigin::Signed(10).into(),
-                b"remote-chain".to_vec(),
+            IbcLite::prune_cross_chain_agent(
+                frame_system::RawOrigin::Signed(1).into(),
+                b"chain-0".to_vec(),
                 b"remote-agent-1".to_vec(),
-                999,
             ),
-            Error::<Runtime>::AgentNotFound
+            Error::<Runtime>::AttestationNotExpired
+        );
+
+        System::set_block_number(51);
+        let (chain_id, remote_id) = attested_keys();
+        assert_eq!(
+            IbcLite::resolve_cross_chain_agent(&chain_id, &remote_id),
+            None
         );
+
+        assert_ok!(IbcLite::prune_cross_chain_agent(
+            frame_system::RawOrigin::Signed(1).into(),
+            b"chain-0".to_vec(),
+            b"remote-agent-1".to_vec(),
+        ));
+        assert!(!CrossChainAgentMap::<Runtime>::contains_key(
+            &chain_id, &remote_id
+        ));
+        assert!(!CrossChainAgentExpiry::<Runtime>::contains_key(
+            &chain_id, &remote_id
+        ));
+    });
+}
+
+#[test]
+fn mapping_without_expiry_is_treated_as_expired() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let (chain_id, remote_id) = attested_keys();
+        CrossChainAgentMap::<Runtime>::insert(&chain_id, &remote_id, 5);
+
+        assert_eq!(
+            IbcLite::resolve_cross_chain_agent(&chain_id, &remote_id),
+            None
+        );
+        assert_ok!(IbcLite::prune_cross_chain_agent(
+            frame_system::RawOrigin::Signed(1).into(),
+            b"chain-0".to_vec(),
+            b"remote-agent-1".to_vec(),
+        ));
+        assert!(!CrossChainAgentMap::<Runtime>::contains_key(
+            &chain_id, &remote_id
+        ));
     });
 }
@@ -1,5 +1,9 @@
 //! IBC-lite traits and interfaces.
 
+use crate::types::Sequence;
+use sp_core::H256;
+use sp_runtime::DispatchResult;
+
 // =========================================================
 // Agent Registry Interface
 // =========================================================
@@ -17,34 +21,99 @@ pub trait AgentRegistryInterface<AccountId> {
 }
 
 // =========================================================
-// Mock Implementation for Testing
+// Application Callbacks
+// =========================================================
+
+/// Application-level handler for packets sent to and from this chain.
+pub trait IbcApplication {
+    /// Called when a relayer delivers an inbound packet.
+    ///
+    /// `payload` is the SCALE-encoded `PacketPayload`. An error moves the
+    /// packet to the channel's dead-letter queue instead of dropping it.
+    fn on_recv(channel_id: &[u8], sequence: Sequence, payload: &[u8]) -> DispatchResult;
+
+    /// Called when a relayer acknowledges a packet.
+    ///
+    /// `data` is the application-level ack payload (e.g. a remote invocation
+    /// ID). Return `true` if the application consumed it; otherwise the payload
+    /// is kept in `AckPayloads` until the original sender takes it, unless the
+    /// sender already holds `MaxRetainedAckPayloads` of them.
+    fn on_ack(channel_id: &[u8], sequence: Sequence, success: bool, data: &[u8]) -> bool;
+}
+
+/// No application wired — inbound packets are accepted as-is and every ack
+/// payload is retained for the sender.
+impl IbcApplication for () {
+    fn on_recv(_channel_id: &[u8], _sequence: Sequence, _payload: &[u8]) -> DispatchResult {
+        Ok(())
+    }
+
+    fn on_ack(_channel_id: &[u8], _sequence: Sequence, _success: bool, _data: &[u8]) -> bool {
+        false
+    }
+}
+
+// =========================================================
+// Attestation Verification
 // =========================================================
 
-#[cfg(test)]
-mod mock {
-    use super::*;
-    use sp_runtime::RuntimeDebug;
-
-    /// Mock agent registry for testing.
-    #[derive(RuntimeDebug)]
-    pub struct MockAgentRegistry;
-
-    impl AgentRegistryInterface<u64> for MockAgentRegistry {
-        fn agent_exists(agent_id: u64) -> bool {
-            // Simple mock: agents 1-10 exist
-            agent_id > 0 && agent_id <= 10
-        }
-
-        fn agent_owner(agent_id: u64) -> Option<u64> {
-            if Self::agent_exists(agent_id) {
-                Some(agent_id) // Owner ID = agent ID for simplicity
-            } else {
-                None
-            }
-        }
-
-        fn is_agent_active(agent_id: u64) -> bool {
-            Self::agent_exists(agent_id)
-        }
+/// Verifies remote agent registry attestations (light client or signer set).
+pub trait AttestationVerifier {
+    /// Return `true` if `proof` shows that `chain_id`'s registry holds a record
+    /// with `record_hash` binding `remote_agent_id` to `local_agent_id`.
+    fn verify(
+        chain_id: &[u8],
+        remote_agent_id: &[u8],
+        local_agent_id: u64,
+        record_hash: H256,
+        proof: &[u8],
+    ) -> bool;
+}
+
+/// No verifier wired — every attestation is rejected.
+impl AttestationVerifier for () {
+    fn verify(
+        _chain_id: &[u8],
+        _remote_agent_id: &[u8],
+        _local_agent_id: u64,
+        _record_hash: H256,
+        _proof: &[u8],
+    ) -> bool {
+        false
+    }
+}
+
+/// Verifies fraud proofs against bonded packet submissions.
+pub trait FraudProofVerifier {
+    /// Return `true` if `proof` shows that `chain_id` never committed a
+    /// packet with `packet_commitment`.
+    fn verify(chain_id: &[u8], packet_commitment: H256, proof: &[u8]) -> bool;
+
+    /// A proof that `verify` accepts, setting up any state it needs.
+    #[cfg(feature = "runtime-benchmarks")]
+    fn valid_proof(chain_id: &[u8], packet_commitment: H256) -> alloc::vec::Vec<u8>;
+}
+
+/// No verifier wired — no submission can be challenged.
+impl FraudProofVerifier for () {
+    fn verify(_chain_id: &[u8], _packet_commitment: H256, _proof: &[u8]) -> bool {
+        false
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn valid_proof(_chain_id: &[u8], _packet_commitment: H256) -> alloc::vec::Vec<u8> {
+        alloc::vec::Vec::new()
+    }
+}
+
+/// Stake backing each relayer in primary-relayer rotation.
+pub trait RelayerStake<AccountId> {
+    fn stake_of(who: &AccountId) -> u128;
+}
+
+/// No stake source wired — relayers rotate uniformly.
+impl<AccountId> RelayerStake<AccountId> for () {
+    fn stake_of(_who: &AccountId) -> u128 {
+        0
     }
 }
@@ -3,8 +3,10 @@
 use super::Config;
 use codec::{Decode, DecodeWithMemTracking, Encode, MaxEncodedLen};
 use frame_support::pallet_prelude::*;
+use frame_support::traits::Currency;
 use frame_system::pallet_prelude::BlockNumberFor;
 use sp_core::H256;
+use sp_runtime::DispatchError;
 use sp_std::prelude::*;
 
 // =========================================================
@@ -16,6 +18,8 @@ pub type AgentId = u64;
 pub type ChannelId<T> = BoundedVec<u8, <T as Config>::MaxChannelIdLen>;
 pub type ChainId<T> = BoundedVec<u8, <T as Config>::MaxChainIdLen>;
 pub type RemoteAgentId<T> = BoundedVec<u8, <T as Config>::MaxChannelIdLen>;
+pub type BalanceOf<T> =
+    <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
 // =========================================================
 // Channel State
@@ -163,6 +167,68 @@ pub enum PacketPayload<T: Config> {
         error_code: Option<u32>,
         data: BoundedVec<u8, T::MaxPayloadLen>,
     },
+    /// Remote registry attestation binding a remote agent to a local agent.
+    AgentAttestation {
+        remote_agent_id: RemoteAgentId<T>,
+        local_agent_id: AgentId,
+        /// Hash of the agent record in the remote chain's registry.
+        record_hash: H256,
+        /// Signature or commitment checked by `T::AttestationVerifier`.
+        proof: BoundedVec<u8, T::MaxPayloadLen>,
+    },
+}
+
+// =========================================================
+// Dead Letter
+// =========================================================
+
+/// An inbound packet the application failed to handle, kept for retry.
+#[derive(
+    CloneNoBound,
+    Encode,
+    Decode,
+    DecodeWithMemTracking,
+    EqNoBound,
+    PartialEqNoBound,
+    RuntimeDebugNoBound,
+    TypeInfo,
+    MaxEncodedLen,
+)]
+#[scale_info(skip_type_params(T))]
+pub struct DeadLetter<T: Config> {
+    /// The undelivered packet.
+    pub packet: Packet<T>,
+    /// Error returned by the application handler.
+    pub reason: DispatchError,
+    /// Block at which handling (last) failed.
+    pub failed_at: BlockNumberFor<T>,
+}
+
+// =========================================================
+// Pending Submission
+// =========================================================
+
+/// A packet submitted with a bond by a non-relayer, open to challenge.
+#[derive(
+    CloneNoBound,
+    Encode,
+    Decode,
+    EqNoBound,
+    PartialEqNoBound,
+    RuntimeDebugNoBound,
+    TypeInfo,
+    MaxEncodedLen,
+)]
+#[scale_info(skip_type_params(T))]
+pub struct PendingSubmission<T: Config> {
+    pub submitter: T::AccountId,
+    /// Bond reserved from the submitter.
+    pub bond: BalanceOf<T>,
+    pub packet: Packet<T>,
+    /// Block the packet arrived on-chain; its timeout is checked against this.
+    pub submitted_at: BlockNumberFor<T>,
+    /// First block at which the submission can no longer be challenged.
+    pub challenge_until: BlockNumberFor<T>,
 }
 
 // =========================================================
@@ -176,6 +242,8 @@ pub enum ReceiptStatus {
     Received,
     /// Packet has been processed.
     Processed,
+    /// Packet was received but rejected with an error acknowledgement.
+    Failed,
 }
 
 // =========================================================
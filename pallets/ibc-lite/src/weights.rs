@@ -20,13 +20,25 @@ pub trait WeightInfo {
     fn receive_packet() -> Weight;
     fn acknowledge_packet() -> Weight;
     fn timeout_packet() -> Weight;
+    fn retry_dead_letter() -> Weight;
+    fn discard_dead_letter() -> Weight;
+
+    // Open relaying
+    fn submit_packet_bonded() -> Weight;
+    fn challenge_submission() -> Weight;
+    fn finalize_submission() -> Weight;
 
     // Relayer management
     fn add_relayer() -> Weight;
     fn remove_relayer() -> Weight;
+    fn set_channel_relayers() -> Weight;
+    fn set_counterparty_signers() -> Weight;
+
+    // Ack payloads
+    fn consume_ack_payload() -> Weight;
 
-    // Cross-chain agents
-    fn register_cross_chain_agent() -> Weight;
+    // Attested agent mappings
+    fn prune_cross_chain_agent() -> Weight;
 }
 
 // =========================================================
@@ -66,6 +78,27 @@ impl WeightInfo for () {
         Weight::from_parts(10_000, 0)
     }
 
+    fn retry_dead_letter() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+
+    fn discard_dead_letter() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+
+    // Open relaying
+    fn submit_packet_bonded() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+
+    fn challenge_submission() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+
+    fn finalize_submission() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+
     // Relayer management
     fn add_relayer() -> Weight {
         Weight::from_parts(10_000, 0)
@@ -75,8 +108,21 @@ impl WeightInfo for () {
         Weight::from_parts(10_000, 0)
     }
 
-    // Cross-chain agents
-    fn register_cross_chain_agent() -> Weight {
+    fn set_channel_relayers() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+
+    fn set_counterparty_signers() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+
+    // Ack payloads
+    fn consume_ack_payload() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+
+    // Attested agent mappings
+    fn prune_cross_chain_agent() -> Weight {
         Weight::from_parts(10_000, 0)
     }
 }
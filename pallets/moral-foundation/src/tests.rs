@@ -555,11 +555,11 @@ fn multiple_agents_can_attest_independently() {
             did2()
         ));
 
-        assert!(AgentAttestation::<Test>::get(&did1()).is_some());
-        assert!(AgentAttestation::<Test>::get(&did2()).is_some());
+        assert!(AgentAttestation::<Test>::get(did1()).is_some());
+        assert!(AgentAttestation::<Test>::get(did2()).is_some());
 
-        assert_eq!(EmpathyScore::<Test>::get(&did1()), 500);
-        assert_eq!(EmpathyScore::<Test>::get(&did2()), 500);
+        assert_eq!(EmpathyScore::<Test>::get(did1()), 500);
+        assert_eq!(EmpathyScore::<Test>::get(did2()), 500);
     });
 }
 
@@ -582,7 +582,7 @@ fn empathy_score_independent_per_agent() {
         ));
 
         // did2 score unchanged.
-        assert_eq!(EmpathyScore::<Test>::get(&did1()), 900);
-        assert_eq!(EmpathyScore::<Test>::get(&did2()), 500);
+        assert_eq!(EmpathyScore::<Test>::get(did1()), 900);
+        assert_eq!(EmpathyScore::<Test>::get(did2()), 500);
     });
 }
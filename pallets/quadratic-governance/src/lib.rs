@@ -15,6 +15,34 @@
 //! - `vote` — Cast a quadratic vote on an active proposal
 //! - `finalize_proposal` — Close voting after the period ends
 //! - `cancel_proposal` — Cancel a proposal (proposer only, refunds deposit)
+//! - `submit_parameter_proposal` — Create a proposal carrying a typed parameter change
+//! - `enact_proposal` — Apply the parameter change of a passed proposal (`EnactmentOrigin`)
+//!
+//! ## Parameter Proposals
+//!
+//! A proposal may carry a typed [`Config::ParameterChange`] payload (defined
+//! by the runtime, e.g. setting a quota class weight). The payload is checked
+//! by [`ParameterChangeHandler::validate`] on submission. Once the proposal
+//! finalises as `Passed`, `EnactmentOrigin` applies it with
+//! `enact_proposal`, which calls [`ParameterChangeHandler::apply`], so common
+//! parameter updates need no general call dispatch. Vote stakes are declared
+//! rather than locked, so a passing vote alone does not enact anything.
+//!
+//! ## Execution Receipts
+//!
+//! Every enactment is recorded in `ExecutionReceipts`: the hash of the
+//! encoded change, the dispatch result, the weight the handler reports for it
+//! and the block. `ProposalExecuted` carries the same data and is indexed by
+//! the proposal id and change hash, so auditors can match enacted changes
+//! against what was voted on.
+//!
+//! ## Spam Deposits
+//!
+//! Deposits are refunded at finalisation unless the proposal looks like spam:
+//! rejected with a Yes share below `SpamYesThreshold`, or expired with total
+//! weight below `SpamQuorumThreshold` of the quorum. In those cases
+//! `SpamSlashShare` of the deposit goes to `TreasuryAccount` and the rest is
+//! refunded.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(deprecated, clippy::let_unit_value)]
@@ -26,14 +54,45 @@ pub use pallet::*;
 #[cfg(test)]
 mod tests;
 
+use frame_support::weights::Weight;
+use sp_runtime::{DispatchError, DispatchResult};
+
+/// Validates and applies the typed parameter changes carried by proposals.
+///
+/// Implemented by the runtime, which routes each change to the pallet that
+/// owns the parameter.
+pub trait ParameterChangeHandler<Change> {
+    /// Reject changes that could never be applied (checked on submission).
+    fn validate(change: &Change) -> DispatchResult;
+    /// Apply a change from a passed proposal.
+    fn apply(change: &Change) -> DispatchResult;
+    /// Weight of applying `change`, recorded in its execution receipt.
+    fn weight(_change: &Change) -> Weight {
+        Weight::zero()
+    }
+}
+
+/// No parameter changes are supported.
+impl<Change> ParameterChangeHandler<Change> for () {
+    fn validate(_: &Change) -> DispatchResult {
+        Err(DispatchError::Other("parameter proposals unsupported"))
+    }
+    fn apply(_: &Change) -> DispatchResult {
+        Err(DispatchError::Other("parameter proposals unsupported"))
+    }
+}
+
 #[frame_support::pallet]
 pub mod pallet {
     use frame_support::{
         pallet_prelude::*,
-        traits::{Currency, ReservableCurrency},
+        traits::{Currency, Imbalance, ReservableCurrency},
     };
     use frame_system::pallet_prelude::*;
-    use sp_runtime::traits::Saturating;
+    use sp_runtime::{
+        traits::{Hash, Saturating},
+        Perbill,
+    };
 
     /// Type alias for balance (same pattern as pallet-reputation / pallet-task-market).
     pub type BalanceOf<T> =
@@ -109,10 +168,28 @@ pub mod pallet {
 
     impl<T: Config> codec::DecodeWithMemTracking for Proposal<T> {}
 
+    /// Record of a passed proposal's enactment.
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    pub struct ExecutionReceipt<T: Config> {
+        /// Hash of the encoded parameter change.
+        pub call_hash: T::Hash,
+        /// Outcome of applying the change.
+        pub result: DispatchResult,
+        /// Weight reported by the parameter handler.
+        pub weight: Weight,
+        /// Block the change was applied in.
+        pub executed_at: BlockNumberFor<T>,
+    }
+
+    impl<T: Config> codec::DecodeWithMemTracking for ExecutionReceipt<T> {}
+
     // =========================================================
     // Config
     // =========================================================
 
+    use crate::ParameterChangeHandler;
+
     #[pallet::config]
     pub trait Config: frame_system::Config + pallet_agent_did::Config {
         /// The overarching runtime event type.
@@ -134,8 +211,34 @@ pub mod pallet {
         #[pallet::constant]
         type MinQuorumPct: Get<u32>;
 
+        /// Account receiving slashed spam deposits.
+        type TreasuryAccount: Get<Self::AccountId>;
+
+        /// Share of the deposit slashed from a spam proposal.
+        #[pallet::constant]
+        type SpamSlashShare: Get<Perbill>;
+
+        /// A rejected proposal whose Yes weight is below this share of the
+        /// total weight is treated as spam.
+        #[pallet::constant]
+        type SpamYesThreshold: Get<Perbill>;
+
+        /// An expired proposal whose total weight is below this share of the
+        /// quorum is treated as spam.
+        #[pallet::constant]
+        type SpamQuorumThreshold: Get<Perbill>;
+
         /// Weight information for extrinsics.
         type WeightInfo: WeightInfo;
+
+        /// Typed parameter change a proposal may carry.
+        type ParameterChange: Parameter + Member + MaxEncodedLen + codec::DecodeWithMemTracking;
+
+        /// Validates parameter changes on submission and applies them on enactment.
+        type ParameterHandler: ParameterChangeHandler<Self::ParameterChange>;
+
+        /// Origin allowed to enact the parameter change of a passed proposal.
+        type EnactmentOrigin: EnsureOrigin<<Self as frame_system::Config>::RuntimeOrigin>;
     }
 
     // =========================================================
@@ -178,6 +281,17 @@ pub mod pallet {
     #[pallet::getter(fn proposal_count)]
     pub type ProposalCount<T: Config> = StorageValue<_, u32, ValueQuery>;
 
+    /// Parameter change carried by a proposal, kept after it passes until
+    /// `EnactmentOrigin` enacts it.
+    #[pallet::storage]
+    pub type ProposalPayloads<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalId, T::ParameterChange, OptionQuery>;
+
+    /// Enactment receipts of passed parameter proposals.
+    #[pallet::storage]
+    pub type ExecutionReceipts<T: Config> =
+        StorageMap<_, Blake2_128Concat, ProposalId, ExecutionReceipt<T>, OptionQuery>;
+
     // =========================================================
     // Events
     // =========================================================
@@ -208,6 +322,26 @@ pub mod pallet {
             proposal_id: ProposalId,
             proposer: T::AccountId,
         },
+        /// Part of a spam proposal's deposit was sent to the treasury.
+        DepositSlashed {
+            proposal_id: ProposalId,
+            proposer: T::AccountId,
+            amount: BalanceOf<T>,
+        },
+        /// A passed proposal's parameter change was applied (or failed to apply).
+        ParameterChangeEnacted {
+            proposal_id: ProposalId,
+            change: T::ParameterChange,
+            result: DispatchResult,
+        },
+        /// Execution receipt of an enacted proposal. Indexed by the hash of
+        /// the proposal id and by `call_hash`.
+        ProposalExecuted {
+            proposal_id: ProposalId,
+            call_hash: T::Hash,
+            result: DispatchResult,
+            weight: Weight,
+        },
     }
 
     // =========================================================
@@ -230,8 +364,13 @@ pub mod pallet {
         NotProposer,
         /// Cannot finalise — voting period has not ended yet.
         ProposalStillActive,
-        /// Quorum was not reached.
+        /// Quorum was not reached. No longer returned: such proposals now
+        /// finalise as `Expired`. Kept so error indices stay stable.
         QuorumNotMet,
+        /// The parameter change was rejected by the parameter handler.
+        InvalidParameterChange,
+        /// The proposal did not pass or carries no parameter change to enact.
+        NothingToEnact,
     }
 
     // =========================================================
@@ -251,41 +390,7 @@ pub mod pallet {
         #[pallet::weight(T::DbWeight::get().reads_writes(2, 3))]
         pub fn submit_proposal(origin: OriginFor<T>, description_hash: [u8; 32]) -> DispatchResult {
             let who = ensure_signed(origin)?;
-
-            // DID check — must have an active (non-deactivated) DID document.
-            Self::ensure_has_active_did(&who)?;
-
-            // Reserve deposit.
-            let deposit = T::MinProposalDeposit::get();
-            T::Currency::reserve(&who, deposit).map_err(|_| Error::<T>::InsufficientDeposit)?;
-
-            let now = frame_system::Pallet::<T>::block_number();
-            let end_block = now.saturating_add(T::VotingPeriod::get());
-
-            let proposal_id = NextProposalId::<T>::get();
-
-            let proposal = Proposal::<T> {
-                proposer: who.clone(),
-                description_hash,
-                start_block: now,
-                end_block,
-                yes_votes: 0u128,
-                no_votes: 0u128,
-                status: ProposalStatus::Active,
-                deposit,
-            };
-
-            Proposals::<T>::insert(proposal_id, proposal);
-            NextProposalId::<T>::put(proposal_id.saturating_add(1));
-            ProposalCount::<T>::mutate(|c| *c = c.saturating_add(1));
-
-            Self::deposit_event(Event::ProposalSubmitted {
-                proposal_id,
-                proposer: who,
-                description_hash,
-            });
-
-            Ok(())
+            Self::do_submit_proposal(who, description_hash).map(|_| ())
         }
 
         /// Cast a quadratic vote on an active proposal.
@@ -359,9 +464,10 @@ pub mod pallet {
         /// Finalise a proposal after its voting period has ended.
         ///
         /// Determines Passed / Rejected / Expired based on quorum and vote
-        /// totals.  Unreserves the proposer's deposit regardless of outcome.
+        /// totals. Unreserves the proposer's deposit, less the spam slash
+        /// when the outcome qualifies (see module docs).
         #[pallet::call_index(2)]
-        #[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+        #[pallet::weight(T::DbWeight::get().reads_writes(2, 2))]
         pub fn finalize_proposal(origin: OriginFor<T>, proposal_id: ProposalId) -> DispatchResult {
             let _who = ensure_signed(origin)?;
 
@@ -381,18 +487,40 @@ pub mod pallet {
                 // absolute minimum weight, treating the percentage as the
                 // minimum vote-weight threshold for simplicity on a testnet).
                 let min_quorum = T::MinQuorumPct::get() as u128;
-                ensure!(total_votes >= min_quorum, Error::<T>::QuorumNotMet);
 
-                let new_status = if proposal.yes_votes > proposal.no_votes {
-                    ProposalStatus::Passed
+                let (new_status, is_spam) = if total_votes < min_quorum {
+                    (
+                        ProposalStatus::Expired,
+                        total_votes < T::SpamQuorumThreshold::get().mul_floor(min_quorum),
+                    )
+                } else if proposal.yes_votes > proposal.no_votes {
+                    (ProposalStatus::Passed, false)
                 } else {
-                    ProposalStatus::Rejected
+                    (
+                        ProposalStatus::Rejected,
+                        proposal.yes_votes < T::SpamYesThreshold::get().mul_floor(total_votes),
+                    )
                 };
 
                 proposal.status = new_status;
 
-                // Unreserve proposer deposit
-                T::Currency::unreserve(&proposal.proposer, proposal.deposit);
+                // Slash spam, refund the rest of the proposer deposit
+                let mut refund = proposal.deposit;
+                if is_spam {
+                    let slash = T::SpamSlashShare::get().mul_floor(proposal.deposit);
+                    // Resolve rather than repatriate so an unfunded treasury
+                    // account is created instead of failing finalisation.
+                    let (imbalance, _) = T::Currency::slash_reserved(&proposal.proposer, slash);
+                    let slashed = imbalance.peek();
+                    T::Currency::resolve_creating(&T::TreasuryAccount::get(), imbalance);
+                    refund = refund.saturating_sub(slashed);
+                    Self::deposit_event(Event::DepositSlashed {
+                        proposal_id,
+                        proposer: proposal.proposer.clone(),
+                        amount: slashed,
+                    });
+                }
+                T::Currency::unreserve(&proposal.proposer, refund);
 
                 Self::deposit_event(Event::ProposalFinalized {
                     proposal_id,
@@ -400,7 +528,16 @@ pub mod pallet {
                 });
 
                 Ok(())
-            })
+            })?;
+
+            // Only a passed proposal keeps its parameter change for enactment.
+            let passed = Proposals::<T>::get(proposal_id)
+                .is_some_and(|p| p.status == ProposalStatus::Passed);
+            if !passed {
+                ProposalPayloads::<T>::remove(proposal_id);
+            }
+
+            Ok(())
         }
 
         /// Cancel an active proposal.
@@ -425,6 +562,7 @@ pub mod pallet {
 
             // Remove proposal
             Proposals::<T>::remove(proposal_id);
+            ProposalPayloads::<T>::remove(proposal_id);
             ProposalCount::<T>::mutate(|c| *c = c.saturating_sub(1));
 
             Self::deposit_event(Event::ProposalCancelled {
@@ -434,6 +572,55 @@ pub mod pallet {
 
             Ok(())
         }
+
+        /// Submit a proposal that applies `change` if it passes.
+        ///
+        /// Same requirements as `submit_proposal`; `change` must also pass
+        /// the parameter handler's validation.
+        #[pallet::call_index(4)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(2, 4))]
+        pub fn submit_parameter_proposal(
+            origin: OriginFor<T>,
+            description_hash: [u8; 32],
+            change: T::ParameterChange,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            T::ParameterHandler::validate(&change)
+                .map_err(|_| Error::<T>::InvalidParameterChange)?;
+
+            let proposal_id = Self::do_submit_proposal(who, description_hash)?;
+            ProposalPayloads::<T>::insert(proposal_id, change);
+            Ok(())
+        }
+
+        /// Apply the parameter change of a passed proposal.
+        ///
+        /// Restricted to `EnactmentOrigin`. A failed apply is reported in the
+        /// event and receipt and rolled back; the change is consumed either way.
+        #[pallet::call_index(5)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(2, 2))]
+        pub fn enact_proposal(origin: OriginFor<T>, proposal_id: ProposalId) -> DispatchResult {
+            T::EnactmentOrigin::ensure_origin(origin)?;
+
+            let passed = Proposals::<T>::get(proposal_id)
+                .is_some_and(|p| p.status == ProposalStatus::Passed);
+            ensure!(passed, Error::<T>::NothingToEnact);
+            let change =
+                ProposalPayloads::<T>::take(proposal_id).ok_or(Error::<T>::NothingToEnact)?;
+
+            let result =
+                frame_support::storage::with_storage_layer(|| T::ParameterHandler::apply(&change));
+            let call_hash = T::Hashing::hash_of(&change);
+            let weight = T::ParameterHandler::weight(&change);
+            Self::deposit_event(Event::ParameterChangeEnacted {
+                proposal_id,
+                change,
+                result,
+            });
+            Self::record_execution(proposal_id, call_hash, result, weight);
+
+            Ok(())
+        }
     }
 
     // =========================================================
@@ -441,6 +628,75 @@ pub mod pallet {
     // =========================================================
 
     impl<T: Config> Pallet<T> {
+        /// Create a proposal for `who`, reserving the deposit.
+        fn do_submit_proposal(
+            who: T::AccountId,
+            description_hash: [u8; 32],
+        ) -> Result<ProposalId, DispatchError> {
+            // DID check — must have an active (non-deactivated) DID document.
+            Self::ensure_has_active_did(&who)?;
+
+            // Reserve deposit.
+            let deposit = T::MinProposalDeposit::get();
+            T::Currency::reserve(&who, deposit).map_err(|_| Error::<T>::InsufficientDeposit)?;
+
+            let now = frame_system::Pallet::<T>::block_number();
+            let end_block = now.saturating_add(T::VotingPeriod::get());
+
+            let proposal_id = NextProposalId::<T>::get();
+
+            let proposal = Proposal::<T> {
+                proposer: who.clone(),
+                description_hash,
+                start_block: now,
+                end_block,
+                yes_votes: 0u128,
+                no_votes: 0u128,
+                status: ProposalStatus::Active,
+                deposit,
+            };
+
+            Proposals::<T>::insert(proposal_id, proposal);
+            NextProposalId::<T>::put(proposal_id.saturating_add(1));
+            ProposalCount::<T>::mutate(|c| *c = c.saturating_add(1));
+
+            Self::deposit_event(Event::ProposalSubmitted {
+                proposal_id,
+                proposer: who,
+                description_hash,
+            });
+
+            Ok(proposal_id)
+        }
+
+        /// Store the execution receipt of `proposal_id` and emit it as an
+        /// indexed event.
+        fn record_execution(
+            proposal_id: ProposalId,
+            call_hash: T::Hash,
+            result: DispatchResult,
+            weight: Weight,
+        ) {
+            ExecutionReceipts::<T>::insert(
+                proposal_id,
+                ExecutionReceipt {
+                    call_hash,
+                    result,
+                    weight,
+                    executed_at: frame_system::Pallet::<T>::block_number(),
+                },
+            );
+
+            let topics = [T::Hashing::hash_of(&proposal_id), call_hash];
+            let event = <T as Config>::RuntimeEvent::from(Event::ProposalExecuted {
+                proposal_id,
+                call_hash,
+                result,
+                weight,
+            });
+            frame_system::Pallet::<T>::deposit_event_indexed(&topics, event.into());
+        }
+
         /// Ensure account has an active DID document.
         ///
         /// Uses `pallet_agent_did::DIDDocuments` storage directly (tight
@@ -1,9 +1,9 @@
 use crate::pallet::*;
 use frame_support::{
     assert_noop, assert_ok, derive_impl, parameter_types,
-    traits::{ConstU128, ConstU32, ConstU64},
+    traits::{ConstU128, ConstU32},
 };
-use sp_runtime::BuildStorage;
+use sp_runtime::{traits::Hash, BuildStorage, Perbill};
 
 // =========================================================
 // Mock runtime
@@ -55,12 +55,17 @@ impl pallet_agent_did::Config for Test {
     type MaxKeyTypeLength = ConstU32<128>;
     type MaxKeyLength = ConstU32<256>;
     type MaxVerificationMethods = ConstU32<5>;
+    type WebhookVerifier = frame_system::EnsureRoot<u64>;
 }
 
 parameter_types! {
     pub const MinProposalDeposit: u128 = 100;
     pub const VotingPeriod: u64 = 100;  // 100 blocks
     pub const MinQuorumPct: u32 = 10;   // require >= 10 total vote-weight
+    pub const TreasuryAccount: u64 = 99;
+    pub const SpamSlashShare: Perbill = Perbill::from_percent(50);
+    pub const SpamYesThreshold: Perbill = Perbill::from_percent(20);
+    pub const SpamQuorumThreshold: Perbill = Perbill::from_percent(50);
 }
 
 impl crate::pallet::Config for Test {
@@ -69,7 +74,54 @@ impl crate::pallet::Config for Test {
     type MinProposalDeposit = MinProposalDeposit;
     type VotingPeriod = VotingPeriod;
     type MinQuorumPct = MinQuorumPct;
+    type TreasuryAccount = TreasuryAccount;
+    type SpamSlashShare = SpamSlashShare;
+    type SpamYesThreshold = SpamYesThreshold;
+    type SpamQuorumThreshold = SpamQuorumThreshold;
     type WeightInfo = ();
+    type ParameterChange = TestParameter;
+    type ParameterHandler = TestParameterHandler;
+    type EnactmentOrigin = frame_system::EnsureRoot<u64>;
+}
+
+/// Single governed parameter for the parameter-proposal tests.
+#[derive(
+    Clone,
+    codec::Encode,
+    codec::Decode,
+    codec::DecodeWithMemTracking,
+    Eq,
+    PartialEq,
+    sp_runtime::RuntimeDebug,
+    scale_info::TypeInfo,
+    codec::MaxEncodedLen,
+)]
+pub enum TestParameter {
+    /// Valid up to 1_000.
+    SetLimit(u32),
+}
+
+parameter_types! {
+    pub static GovernedLimit: u32 = 0;
+}
+
+pub struct TestParameterHandler;
+
+impl crate::ParameterChangeHandler<TestParameter> for TestParameterHandler {
+    fn validate(change: &TestParameter) -> sp_runtime::DispatchResult {
+        match change {
+            TestParameter::SetLimit(limit) if *limit <= 1_000 => Ok(()),
+            TestParameter::SetLimit(_) => Err(sp_runtime::DispatchError::Other("limit too high")),
+        }
+    }
+    fn apply(change: &TestParameter) -> sp_runtime::DispatchResult {
+        let TestParameter::SetLimit(limit) = change;
+        GovernedLimit::set(*limit);
+        Ok(())
+    }
+    fn weight(_change: &TestParameter) -> frame_support::weights::Weight {
+        frame_support::weights::Weight::from_parts(1_000, 0)
+    }
 }
 
 // =========================================================
@@ -141,7 +193,7 @@ fn submit_proposal_works() {
         assert_eq!(proposal.deposit, 100);
 
         // Deposit was reserved
-        assert_eq!(pallet_balances::Pallet::<Test>::reserved_balance(&1), 100);
+        assert_eq!(pallet_balances::Pallet::<Test>::reserved_balance(1), 100);
 
         // Next ID incremented
         assert_eq!(QuadraticGovernance::next_proposal_id(), 1);
@@ -282,7 +334,7 @@ fn finalize_passes_when_yes_wins_and_quorum_met() {
         assert_eq!(proposal.status, ProposalStatus::Passed);
 
         // Deposit unreserved
-        assert_eq!(pallet_balances::Pallet::<Test>::reserved_balance(&1), 0);
+        assert_eq!(pallet_balances::Pallet::<Test>::reserved_balance(1), 0);
 
         System::assert_last_event(RuntimeEvent::QuadraticGovernance(
             Event::ProposalFinalized {
@@ -328,9 +380,9 @@ fn finalize_rejects_when_no_wins() {
     });
 }
 
-// 8. finalize fails when quorum not met
+// 8. finalize expires (and slashes) when quorum is badly missed
 #[test]
-fn finalize_fails_when_quorum_not_met() {
+fn finalize_expires_when_quorum_not_met() {
     new_test_ext().execute_with(|| {
         assert_ok!(QuadraticGovernance::submit_proposal(
             RuntimeOrigin::signed(1),
@@ -347,10 +399,17 @@ fn finalize_fails_when_quorum_not_met() {
 
         System::set_block_number(102);
 
-        assert_noop!(
-            QuadraticGovernance::finalize_proposal(RuntimeOrigin::signed(1), 0),
-            Error::<Test>::QuorumNotMet
-        );
+        assert_ok!(QuadraticGovernance::finalize_proposal(
+            RuntimeOrigin::signed(1),
+            0
+        ));
+
+        let proposal = QuadraticGovernance::proposals(0).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Expired);
+        // 3 < 50% of quorum → half the deposit goes to the treasury.
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_eq!(Balances::free_balance(99), 50);
+        assert_eq!(Balances::free_balance(1), 10_000 - 50);
     });
 }
 
@@ -362,7 +421,7 @@ fn cancel_by_proposer_refunds_deposit() {
             RuntimeOrigin::signed(1),
             desc_hash()
         ));
-        assert_eq!(pallet_balances::Pallet::<Test>::reserved_balance(&1), 100);
+        assert_eq!(pallet_balances::Pallet::<Test>::reserved_balance(1), 100);
 
         assert_ok!(QuadraticGovernance::cancel_proposal(
             RuntimeOrigin::signed(1),
@@ -370,7 +429,7 @@ fn cancel_by_proposer_refunds_deposit() {
         ));
 
         // Deposit refunded
-        assert_eq!(pallet_balances::Pallet::<Test>::reserved_balance(&1), 0);
+        assert_eq!(pallet_balances::Pallet::<Test>::reserved_balance(1), 0);
 
         // Proposal removed
         assert!(QuadraticGovernance::proposals(0).is_none());
@@ -453,3 +512,217 @@ fn finalize_fails_while_still_active() {
         );
     });
 }
+
+// =========================================================
+// Spam deposit slashing
+// =========================================================
+
+fn submit_vote_and_finalize(votes: &[(u64, Vote, u128)]) {
+    assert_ok!(QuadraticGovernance::submit_proposal(
+        RuntimeOrigin::signed(1),
+        desc_hash()
+    ));
+    for (voter, vote, stake) in votes {
+        assert_ok!(QuadraticGovernance::vote(
+            RuntimeOrigin::signed(*voter),
+            0,
+            *vote,
+            *stake
+        ));
+    }
+    System::set_block_number(102);
+    assert_ok!(QuadraticGovernance::finalize_proposal(
+        RuntimeOrigin::signed(1),
+        0
+    ));
+}
+
+#[test]
+fn narrowly_missed_quorum_is_fully_refunded() {
+    new_test_ext().execute_with(|| {
+        // weight 6 < quorum 10, but >= 50% of it → good faith
+        submit_vote_and_finalize(&[(2, Vote::Yes, 36)]);
+
+        assert_eq!(
+            QuadraticGovernance::proposals(0).unwrap().status,
+            ProposalStatus::Expired
+        );
+        assert_eq!(Balances::free_balance(1), 10_000);
+        assert_eq!(Balances::free_balance(99), 0);
+    });
+}
+
+#[test]
+fn lopsided_rejection_slashes_deposit() {
+    new_test_ext().execute_with(|| {
+        // Yes 1 vs No 10 → 1/11 < 20% Yes share
+        submit_vote_and_finalize(&[(2, Vote::Yes, 1), (3, Vote::No, 100)]);
+
+        assert_eq!(
+            QuadraticGovernance::proposals(0).unwrap().status,
+            ProposalStatus::Rejected
+        );
+        assert_eq!(Balances::free_balance(99), 50);
+        assert_eq!(Balances::free_balance(1), 10_000 - 50);
+        System::assert_has_event(
+            Event::<Test>::DepositSlashed {
+                proposal_id: 0,
+                proposer: 1,
+                amount: 50,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn good_faith_rejection_is_fully_refunded() {
+    new_test_ext().execute_with(|| {
+        // Yes 5 vs No 10 → 33% Yes share
+        submit_vote_and_finalize(&[(2, Vote::Yes, 25), (3, Vote::No, 100)]);
+
+        assert_eq!(
+            QuadraticGovernance::proposals(0).unwrap().status,
+            ProposalStatus::Rejected
+        );
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_eq!(Balances::free_balance(1), 10_000);
+    });
+}
+
+#[test]
+fn parameter_proposal_is_validated_on_submission() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            QuadraticGovernance::submit_parameter_proposal(
+                RuntimeOrigin::signed(1),
+                desc_hash(),
+                TestParameter::SetLimit(5_000)
+            ),
+            Error::<Test>::InvalidParameterChange
+        );
+        assert_ok!(QuadraticGovernance::submit_parameter_proposal(
+            RuntimeOrigin::signed(1),
+            desc_hash(),
+            TestParameter::SetLimit(500)
+        ));
+        assert_eq!(
+            ProposalPayloads::<Test>::get(0),
+            Some(TestParameter::SetLimit(500))
+        );
+        assert_eq!(Balances::reserved_balance(1), 100);
+    });
+}
+
+#[test]
+fn passed_parameter_proposal_is_enacted() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(QuadraticGovernance::submit_parameter_proposal(
+            RuntimeOrigin::signed(1),
+            desc_hash(),
+            TestParameter::SetLimit(500)
+        ));
+        assert_ok!(QuadraticGovernance::vote(
+            RuntimeOrigin::signed(2),
+            0,
+            Vote::Yes,
+            100
+        ));
+        assert_ok!(QuadraticGovernance::vote(
+            RuntimeOrigin::signed(3),
+            0,
+            Vote::No,
+            25
+        ));
+        System::set_block_number(102);
+        assert_ok!(QuadraticGovernance::finalize_proposal(
+            RuntimeOrigin::signed(1),
+            0
+        ));
+
+        // Passing alone does not apply the change.
+        assert_eq!(GovernedLimit::get(), 0);
+        assert!(ProposalPayloads::<Test>::get(0).is_some());
+        assert_noop!(
+            QuadraticGovernance::enact_proposal(RuntimeOrigin::signed(2), 0),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_ok!(QuadraticGovernance::enact_proposal(
+            RuntimeOrigin::root(),
+            0
+        ));
+
+        assert_eq!(GovernedLimit::get(), 500);
+        assert!(ProposalPayloads::<Test>::get(0).is_none());
+        assert_noop!(
+            QuadraticGovernance::enact_proposal(RuntimeOrigin::root(), 0),
+            Error::<Test>::NothingToEnact
+        );
+        System::assert_has_event(RuntimeEvent::QuadraticGovernance(
+            Event::ParameterChangeEnacted {
+                proposal_id: 0,
+                change: TestParameter::SetLimit(500),
+                result: Ok(()),
+            },
+        ));
+
+        // The receipt matches the change that was voted on.
+        let call_hash =
+            <Test as frame_system::Config>::Hashing::hash_of(&TestParameter::SetLimit(500));
+        let receipt = ExecutionReceipts::<Test>::get(0).unwrap();
+        assert_eq!(receipt.call_hash, call_hash);
+        assert_eq!(receipt.result, Ok(()));
+        assert_eq!(
+            receipt.weight,
+            frame_support::weights::Weight::from_parts(1_000, 0)
+        );
+        assert_eq!(receipt.executed_at, 102);
+        let record = System::events().pop().unwrap();
+        assert_eq!(
+            record.topics,
+            vec![
+                <Test as frame_system::Config>::Hashing::hash_of(&0u64),
+                call_hash
+            ]
+        );
+        assert_eq!(
+            record.event,
+            RuntimeEvent::QuadraticGovernance(Event::ProposalExecuted {
+                proposal_id: 0,
+                call_hash,
+                result: Ok(()),
+                weight: frame_support::weights::Weight::from_parts(1_000, 0),
+            })
+        );
+    });
+}
+
+#[test]
+fn rejected_parameter_proposal_is_not_enacted() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(QuadraticGovernance::submit_parameter_proposal(
+            RuntimeOrigin::signed(1),
+            desc_hash(),
+            TestParameter::SetLimit(500)
+        ));
+        assert_ok!(QuadraticGovernance::vote(
+            RuntimeOrigin::signed(2),
+            0,
+            Vote::No,
+            100
+        ));
+        System::set_block_number(102);
+        assert_ok!(QuadraticGovernance::finalize_proposal(
+            RuntimeOrigin::signed(1),
+            0
+        ));
+
+        assert!(ProposalPayloads::<Test>::get(0).is_none());
+        assert_noop!(
+            QuadraticGovernance::enact_proposal(RuntimeOrigin::root(), 0),
+            Error::<Test>::NothingToEnact
+        );
+        assert_eq!(GovernedLimit::get(), 0);
+        assert!(ExecutionReceipts::<Test>::get(0).is_none());
+    });
+}
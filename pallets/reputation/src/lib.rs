@@ -17,14 +17,26 @@
 //!
 //! - `submit_review` - Leave a review for another agent after task completion
 //! - `slash_reputation` - Governance/sudo can slash reputation for misbehavior
+//! - `set_reputation_weight` - Governance/sudo can retune the delta applied per event source
 //!
 //! ### Public Functions (for cross-pallet calls)
 //!
 //! - `on_task_completed` - Called by task-market when work is approved
 //! - `on_task_posted` - Called by task-market when task is created
 //! - `on_dispute_resolved` - Called by task-market when dispute is resolved
+//! - `on_reputation_event` - Apply the weighted delta for an SLA violation
 //! - `get_reputation` - Get current reputation score for an account
 //! - `meets_minimum_reputation` - Check if account meets minimum reputation threshold
+//!
+//! ## Leaderboard
+//!
+//! The pallet keeps a bounded top-`MaxLeaderboardSize` list of scores, updated
+//! on every score change: an account is inserted at its rank and the lowest
+//! entry is evicted once the list is full. It is approximate — an account that
+//! falls off is only reconsidered on its next score change. Every
+//! `LeaderboardEraLength` blocks the list is snapshotted for the new era and
+//! the last `LeaderboardHistoryDepth` snapshots are kept. Both are served by
+//! [`runtime_api::ReputationApi::leaderboard`].
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(deprecated, clippy::let_unit_value)]
@@ -33,10 +45,54 @@ extern crate alloc;
 
 pub use pallet::*;
 
+pub mod runtime_api;
+
 #[cfg(test)]
 mod tests;
 
 use alloc::vec::Vec;
+use codec::{Decode, DecodeWithMemTracking, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+use sp_runtime::RuntimeDebug;
+
+/// Origin of a reputation adjustment.
+///
+/// Each source maps to a governance-adjustable weight (basis points) in
+/// `ReputationWeights`; `default_weight` is used until one is set.
+#[derive(
+    Clone,
+    Copy,
+    Encode,
+    Decode,
+    DecodeWithMemTracking,
+    Eq,
+    PartialEq,
+    RuntimeDebug,
+    TypeInfo,
+    MaxEncodedLen,
+)]
+pub enum ReputationSource {
+    /// Peer review; the weight is applied once per star.
+    Review,
+    /// Winning side of a resolved dispute.
+    DisputeWon,
+    /// Losing side of a resolved dispute.
+    DisputeLost,
+    /// Provider missed an agreed SLA deadline.
+    SlaViolation,
+}
+
+impl ReputationSource {
+    /// Weight applied when governance has not set an override.
+    pub fn default_weight(&self) -> i32 {
+        match self {
+            ReputationSource::Review => 100,
+            ReputationSource::DisputeWon => 200,
+            ReputationSource::DisputeLost => -500,
+            ReputationSource::SlaViolation => -300,
+        }
+    }
+}
 
 /// Trait for cross-pallet reputation management.
 pub trait ReputationManager<AccountId, Balance> {
@@ -45,12 +101,30 @@ pub trait ReputationManager<AccountId, Balance> {
     fn on_dispute_resolved(winner: &AccountId, loser: &AccountId);
     fn get_reputation(account: &AccountId) -> u32;
     fn meets_minimum_reputation(account: &AccountId, minimum: u32) -> bool;
+    /// Apply the configured weight for `source` to `account`.
+    fn on_reputation_event(_account: &AccountId, _source: ReputationSource) {}
+    /// Record a review left through another pallet. `rating` is 1-5 and
+    /// `comment` is stored as given, cut to the maximum comment length.
+    fn on_review(
+        _reviewer: &AccountId,
+        _reviewee: &AccountId,
+        _rating: u8,
+        _task_id: u64,
+        _comment: &[u8],
+    ) {
+    }
+    /// Give back `amount` basis points of reputation, e.g. after an appeal
+    /// against a slash was upheld.
+    fn restore_reputation(_account: &AccountId, _amount: u32) {}
 }
 
 #[frame_support::pallet]
 pub mod pallet {
     use super::*;
-    use frame_support::{pallet_prelude::*, sp_runtime::traits::Saturating};
+    use frame_support::{
+        pallet_prelude::*,
+        sp_runtime::traits::{SaturatedConversion, Saturating},
+    };
     use frame_system::pallet_prelude::*;
 
     /// Type alias for balance (compatible with pallet-balances).
@@ -138,6 +212,9 @@ pub mod pallet {
             amount: u32,
             reason: BoundedVec<u8, T::MaxCommentLength>,
         },
+        Restored {
+            amount: u32,
+        },
     }
 
     /// The pallet's configuration trait.
@@ -167,8 +244,26 @@ pub mod pallet {
         /// Maximum number of reputation events to store per account.
         #[pallet::constant]
         type MaxHistoryLength: Get<u32>;
+
+        /// Number of accounts kept on the leaderboard.
+        #[pallet::constant]
+        type MaxLeaderboardSize: Get<u32>;
+
+        /// Blocks per leaderboard era (0 disables snapshots).
+        #[pallet::constant]
+        type LeaderboardEraLength: Get<BlockNumberFor<Self>>;
+
+        /// Number of past era snapshots to retain.
+        #[pallet::constant]
+        type LeaderboardHistoryDepth: Get<u32>;
     }
 
+    /// Leaderboard entries `(account, score)`, highest score first.
+    pub type LeaderboardOf<T> = BoundedVec<
+        (<T as frame_system::Config>::AccountId, u32),
+        <T as Config>::MaxLeaderboardSize,
+    >;
+
     #[pallet::pallet]
     pub struct Pallet<T>(_);
 
@@ -205,6 +300,21 @@ pub mod pallet {
         ValueQuery,
     >;
 
+    /// Governance overrides of the per-source reputation weights.
+    /// Sources without an entry fall back to `ReputationSource::default_weight`.
+    #[pallet::storage]
+    pub type ReputationWeights<T: Config> =
+        StorageMap<_, Blake2_128Concat, ReputationSource, i32, OptionQuery>;
+
+    /// Live top-K leaderboard.
+    #[pallet::storage]
+    pub type Leaderboard<T: Config> = StorageValue<_, LeaderboardOf<T>, ValueQuery>;
+
+    /// Leaderboard as of the start of each era.
+    #[pallet::storage]
+    pub type LeaderboardSnapshots<T: Config> =
+        StorageMap<_, Twox64Concat, u32, LeaderboardOf<T>, OptionQuery>;
+
     // ========== Events ==========
 
     #[pallet::event]
@@ -246,6 +356,15 @@ pub mod pallet {
             winner: T::AccountId,
             loser: T::AccountId,
         },
+        /// The weight for a reputation source was changed by governance.
+        ReputationWeightSet {
+            source: ReputationSource,
+            weight: i32,
+        },
+        /// The leaderboard was snapshotted at the start of an era.
+        LeaderboardSnapshotted { era: u32, entries: u32 },
+        /// Reputation was restored through another pallet.
+        ReputationRestored { account: T::AccountId, amount: u32 },
     }
 
     // ========== Errors ==========
@@ -260,6 +379,32 @@ pub mod pallet {
         SelfReview,
         /// Reputation history is full.
         HistoryOverflow,
+        /// Weight magnitude exceeds the 10000 basis point score range.
+        InvalidWeight,
+    }
+
+    // ========== Hooks ==========
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        /// Snapshot the leaderboard at each era boundary.
+        fn on_initialize(now: BlockNumberFor<T>) -> Weight {
+            let era_length = T::LeaderboardEraLength::get();
+            if era_length.is_zero() || !(now % era_length).is_zero() {
+                return Weight::zero();
+            }
+            let era: u32 = (now / era_length).saturated_into();
+
+            let board = Leaderboard::<T>::get();
+            let entries = board.len() as u32;
+            LeaderboardSnapshots::<T>::insert(era, board);
+            if let Some(expired) = era.checked_sub(T::LeaderboardHistoryDepth::get()) {
+                LeaderboardSnapshots::<T>::remove(expired);
+            }
+
+            Self::deposit_event(Event::LeaderboardSnapshotted { era, entries });
+            T::DbWeight::get().reads_writes(1, 2)
+        }
     }
 
     // ========== Extrinsics ==========
@@ -290,35 +435,7 @@ pub mod pallet {
             let bounded_comment: BoundedVec<u8, T::MaxCommentLength> =
                 comment.try_into().map_err(|_| Error::<T>::CommentTooLong)?;
 
-            let current_block = <frame_system::Pallet<T>>::block_number();
-
-            // Store the review
-            let review = Review::<T> {
-                rating,
-                comment: bounded_comment,
-                task_id,
-                created_at: current_block,
-            };
-            Reviews::<T>::insert(&reviewer, &reviewee, review);
-
-            // Update reviewee's reputation based on rating
-            // 1 star = +100, 2 stars = +200, ... 5 stars = +500
-            let delta = (rating as u32) * 100;
-            Self::apply_reputation_change(&reviewee, delta as i32, true);
-
-            // Record event in history
-            let event = ReputationEvent::<T>::ReviewReceived {
-                from: reviewer.clone(),
-                rating,
-            };
-            Self::add_to_history(&reviewee, event);
-
-            Self::deposit_event(Event::ReviewSubmitted {
-                reviewer,
-                reviewee,
-                rating,
-                task_id,
-            });
+            Self::record_review(reviewer, reviewee, rating, bounded_comment, task_id);
 
             Ok(())
         }
@@ -362,11 +479,57 @@ pub mod pallet {
 
             Ok(())
         }
+
+        /// Set the reputation weight for an event source (governance/sudo only).
+        ///
+        /// # Arguments
+        /// * `source` - The event source being retuned
+        /// * `weight` - New delta in basis points, or `None` to restore the default
+        #[pallet::call_index(2)]
+        #[pallet::weight(T::WeightInfo::set_reputation_weight())]
+        pub fn set_reputation_weight(
+            origin: OriginFor<T>,
+            source: ReputationSource,
+            weight: Option<i32>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            match weight {
+                Some(w) => {
+                    ensure!(w.unsigned_abs() <= 10000, Error::<T>::InvalidWeight);
+                    ReputationWeights::<T>::insert(source, w);
+                }
+                None => ReputationWeights::<T>::remove(source),
+            }
+
+            Self::deposit_event(Event::ReputationWeightSet {
+                source,
+                weight: Self::reputation_weight(source),
+            });
+
+            Ok(())
+        }
     }
 
     // ========== Internal Functions ==========
 
     impl<T: Config> Pallet<T> {
+        /// Current weight for a reputation source, falling back to its default.
+        pub fn reputation_weight(source: ReputationSource) -> i32 {
+            ReputationWeights::<T>::get(source).unwrap_or_else(|| source.default_weight())
+        }
+
+        /// Apply `units` times the configured weight for `source`.
+        fn apply_weighted_change(
+            account: &T::AccountId,
+            source: ReputationSource,
+            units: i32,
+            limit_delta: bool,
+        ) {
+            let delta = Self::reputation_weight(source).saturating_mul(units);
+            Self::apply_reputation_change(account, delta, limit_delta);
+        }
+
         /// Apply a reputation change (clamped to 0-10000).
         fn apply_reputation_change(account: &T::AccountId, delta: i32, limit_delta: bool) {
             Reputations::<T>::mutate(account, |rep| {
@@ -389,6 +552,8 @@ pub mod pallet {
                 rep.score = new_score;
                 rep.last_active = <frame_system::Pallet<T>>::block_number();
 
+                Self::update_leaderboard(account, new_score);
+
                 Self::deposit_event(Event::ReputationChanged {
                     account: account.clone(),
                     old_score,
@@ -397,6 +562,61 @@ pub mod pallet {
             });
         }
 
+        /// Move `account` to its rank for `score`, evicting the lowest entry
+        /// if the board overflows. Ties keep the earlier entry ahead.
+        fn update_leaderboard(account: &T::AccountId, score: u32) {
+            Leaderboard::<T>::mutate(|board| {
+                board.retain(|(who, _)| who != account);
+                let rank = board.partition_point(|(_, s)| *s >= score);
+                let _ = board.force_insert_keep_left(rank, (account.clone(), score));
+            });
+        }
+
+        /// Leaderboard snapshot for `era`, or the live board for `None`.
+        pub fn leaderboard(era: Option<u32>) -> Vec<(T::AccountId, u32)> {
+            match era {
+                Some(era) => LeaderboardSnapshots::<T>::get(era)
+                    .map(|board| board.into_inner())
+                    .unwrap_or_default(),
+                None => Leaderboard::<T>::get().into_inner(),
+            }
+        }
+
+        /// Store a validated review and apply its rating to the reviewee.
+        fn record_review(
+            reviewer: T::AccountId,
+            reviewee: T::AccountId,
+            rating: u8,
+            comment: BoundedVec<u8, T::MaxCommentLength>,
+            task_id: u64,
+        ) {
+            let review = Review::<T> {
+                rating,
+                comment,
+                task_id,
+                created_at: <frame_system::Pallet<T>>::block_number(),
+            };
+            Reviews::<T>::insert(&reviewer, &reviewee, review);
+
+            // Update reviewee's reputation based on rating
+            // (default weight: 1 star = +100, ... 5 stars = +500)
+            Self::apply_weighted_change(&reviewee, ReputationSource::Review, rating as i32, true);
+
+            // Record event in history
+            let event = ReputationEvent::<T>::ReviewReceived {
+                from: reviewer.clone(),
+                rating,
+            };
+            Self::add_to_history(&reviewee, event);
+
+            Self::deposit_event(Event::ReviewSubmitted {
+                reviewer,
+                reviewee,
+                rating,
+                task_id,
+            });
+        }
+
         /// Add an event to reputation history (removes oldest if full).
         fn add_to_history(account: &T::AccountId, event: ReputationEvent<T>) {
             ReputationHistory::<T>::mutate(account, |history| {
@@ -434,14 +654,14 @@ pub mod pallet {
         }
 
         fn on_dispute_resolved(winner: &T::AccountId, loser: &T::AccountId) {
-            // Winner gains +200 reputation
-            Self::apply_reputation_change(winner, 200, false);
+            // Winner gains the DisputeWon weight (default +200)
+            Self::apply_weighted_change(winner, ReputationSource::DisputeWon, 1, false);
             Reputations::<T>::mutate(winner, |rep| {
                 rep.disputes_won = rep.disputes_won.saturating_add(1);
             });
 
-            // Loser loses -500 reputation
-            Self::apply_reputation_change(loser, -500, false);
+            // Loser takes the DisputeLost weight (default -500)
+            Self::apply_weighted_change(loser, ReputationSource::DisputeLost, 1, false);
             Reputations::<T>::mutate(loser, |rep| {
                 rep.disputes_lost = rep.disputes_lost.saturating_add(1);
             });
@@ -459,6 +679,41 @@ pub mod pallet {
         fn meets_minimum_reputation(account: &T::AccountId, minimum: u32) -> bool {
             Self::get_reputation(account) >= minimum
         }
+
+        fn on_reputation_event(account: &T::AccountId, source: ReputationSource) {
+            Self::apply_weighted_change(account, source, 1, false);
+        }
+
+        fn restore_reputation(account: &T::AccountId, amount: u32) {
+            if amount == 0 {
+                return;
+            }
+            Self::apply_reputation_change(account, amount.min(10000) as i32, false);
+            Self::add_to_history(account, ReputationEvent::<T>::Restored { amount });
+            Self::deposit_event(Event::ReputationRestored {
+                account: account.clone(),
+                amount,
+            });
+        }
+
+        fn on_review(
+            reviewer: &T::AccountId,
+            reviewee: &T::AccountId,
+            rating: u8,
+            task_id: u64,
+            comment: &[u8],
+        ) {
+            if !(1_u8..=5).contains(&rating) || reviewer == reviewee {
+                return;
+            }
+            Self::record_review(
+                reviewer.clone(),
+                reviewee.clone(),
+                rating,
+                BoundedVec::truncate_from(comment.to_vec()),
+                task_id,
+            );
+        }
     }
 
     // ========== Weight Info Trait ==========
@@ -466,6 +721,7 @@ pub mod pallet {
     pub trait WeightInfo {
         fn submit_review() -> Weight;
         fn slash_reputation() -> Weight;
+        fn set_reputation_weight() -> Weight;
     }
 
     impl WeightInfo for () {
@@ -475,5 +731,8 @@ pub mod pallet {
         fn slash_reputation() -> Weight {
             Weight::from_parts(10_000, 0)
         }
+        fn set_reputation_weight() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
     }
 }
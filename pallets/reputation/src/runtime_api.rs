@@ -0,0 +1,17 @@
+//! Runtime API for reputation queries.
+
+use alloc::vec::Vec;
+use codec::Codec;
+
+sp_api::decl_runtime_apis! {
+    /// Reputation queries for explorers and dashboards.
+    pub trait ReputationApi<AccountId>
+    where
+        AccountId: Codec,
+    {
+        /// `(account, score)` pairs, highest first, as snapshotted at the start
+        /// of `era`, or the live leaderboard for `None`. Empty if the era's
+        /// snapshot was never taken or has been pruned.
+        fn leaderboard(era: Option<u32>) -> Vec<(AccountId, u32)>;
+    }
+}
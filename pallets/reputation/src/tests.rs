@@ -1,6 +1,6 @@
 //! Unit tests for the Reputation pallet.
 
-use crate::{self as pallet_reputation, pallet::*, *};
+use crate::{self as pallet_reputation, *};
 use frame_support::{assert_noop, assert_ok, parameter_types};
 use sp_core::H256;
 use sp_runtime::{
@@ -83,6 +83,9 @@ parameter_types! {
     pub const InitialReputation: u32 = 5000;
     pub const MaxReputationDelta: u32 = 500;
     pub const MaxHistoryLength: u32 = 100;
+    pub const MaxLeaderboardSize: u32 = 3;
+    pub const LeaderboardEraLength: u64 = 10;
+    pub const LeaderboardHistoryDepth: u32 = 2;
 }
 
 impl pallet_reputation::Config for Test {
@@ -93,6 +96,9 @@ impl pallet_reputation::Config for Test {
     type InitialReputation = InitialReputation;
     type MaxReputationDelta = MaxReputationDelta;
     type MaxHistoryLength = MaxHistoryLength;
+    type MaxLeaderboardSize = MaxLeaderboardSize;
+    type LeaderboardEraLength = LeaderboardEraLength;
+    type LeaderboardHistoryDepth = LeaderboardHistoryDepth;
 }
 
 // Build genesis storage according to the mock runtime.
@@ -476,6 +482,28 @@ fn slash_reputation_emits_events() {
     });
 }
 
+#[test]
+fn restore_reputation_gives_back_score() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Reputation::slash_reputation(
+            RuntimeOrigin::root(),
+            1,
+            1000,
+            b"spam".to_vec()
+        ));
+
+        <Reputation as ReputationManager<_, _>>::restore_reputation(&1, 600);
+        assert_eq!(Reputation::reputations(1).score, 4600);
+        System::assert_has_event(
+            Event::<Test>::ReputationRestored {
+                account: 1,
+                amount: 600,
+            }
+            .into(),
+        );
+    });
+}
+
 #[test]
 fn slash_reputation_requires_root() {
     new_test_ext().execute_with(|| {
@@ -840,3 +868,186 @@ fn last_active_updated_by_operations() {
         assert_eq!(Reputation::reputations(1).last_active, 20);
     });
 }
+
+// ========== Reputation Weight Tests ==========
+
+#[test]
+fn default_weights_match_previous_constants() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(Reputation::reputation_weight(ReputationSource::Review), 100);
+        assert_eq!(
+            Reputation::reputation_weight(ReputationSource::DisputeWon),
+            200
+        );
+        assert_eq!(
+            Reputation::reputation_weight(ReputationSource::DisputeLost),
+            -500
+        );
+    });
+}
+
+#[test]
+fn set_reputation_weight_changes_review_delta() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Reputation::set_reputation_weight(
+            RuntimeOrigin::root(),
+            ReputationSource::Review,
+            Some(50)
+        ));
+        System::assert_has_event(
+            Event::<Test>::ReputationWeightSet {
+                source: ReputationSource::Review,
+                weight: 50,
+            }
+            .into(),
+        );
+
+        assert_ok!(Reputation::submit_review(
+            RuntimeOrigin::signed(1),
+            2,
+            4,
+            b"ok".to_vec(),
+            1
+        ));
+        assert_eq!(Reputation::reputations(2).score, 5200); // 4 * 50
+    });
+}
+
+#[test]
+fn set_reputation_weight_changes_dispute_deltas() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Reputation::set_reputation_weight(
+            RuntimeOrigin::root(),
+            ReputationSource::DisputeWon,
+            Some(300)
+        ));
+        assert_ok!(Reputation::set_reputation_weight(
+            RuntimeOrigin::root(),
+            ReputationSource::DisputeLost,
+            Some(-1000)
+        ));
+
+        Reputation::on_dispute_resolved(&1, &2);
+
+        assert_eq!(Reputation::reputations(1).score, 5300);
+        assert_eq!(Reputation::reputations(2).score, 4000);
+    });
+}
+
+#[test]
+fn reset_reputation_weight_restores_default() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Reputation::set_reputation_weight(
+            RuntimeOrigin::root(),
+            ReputationSource::SlaViolation,
+            Some(-50)
+        ));
+        assert_ok!(Reputation::set_reputation_weight(
+            RuntimeOrigin::root(),
+            ReputationSource::SlaViolation,
+            None
+        ));
+        assert_eq!(
+            Reputation::reputation_weight(ReputationSource::SlaViolation),
+            -300
+        );
+
+        Reputation::on_reputation_event(&1, ReputationSource::SlaViolation);
+        assert_eq!(Reputation::reputations(1).score, 4700);
+    });
+}
+
+#[test]
+fn set_reputation_weight_validation() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Reputation::set_reputation_weight(
+                RuntimeOrigin::signed(1),
+                ReputationSource::Review,
+                Some(10)
+            ),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_noop!(
+            Reputation::set_reputation_weight(
+                RuntimeOrigin::root(),
+                ReputationSource::SlaViolation,
+                Some(-10001)
+            ),
+            Error::<Test>::InvalidWeight
+        );
+    });
+}
+
+// ========== Leaderboard Tests ==========
+
+#[test]
+fn leaderboard_keeps_top_scores_with_eviction() {
+    new_test_ext().execute_with(|| {
+        for (account, amount) in [(1, 100), (2, 200), (3, 300), (4, 400)] {
+            assert_ok!(Reputation::slash_reputation(
+                RuntimeOrigin::root(),
+                account,
+                amount,
+                b"test".to_vec()
+            ));
+        }
+        // MaxLeaderboardSize = 3: account 4 does not make the cut.
+        assert_eq!(
+            Reputation::leaderboard(None),
+            vec![(1, 4900), (2, 4800), (3, 4700)]
+        );
+
+        // A 5-star review lifts account 4 to the top and evicts account 3.
+        assert_ok!(Reputation::submit_review(
+            RuntimeOrigin::signed(1),
+            4,
+            5,
+            b"great".to_vec(),
+            1
+        ));
+        assert_eq!(
+            Reputation::leaderboard(None),
+            vec![(4, 5100), (1, 4900), (2, 4800)]
+        );
+    });
+}
+
+#[test]
+fn leaderboard_snapshots_per_era() {
+    use frame_support::traits::Hooks;
+
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(Reputation::slash_reputation(
+            RuntimeOrigin::root(),
+            1,
+            100,
+            b"test".to_vec()
+        ));
+
+        // LeaderboardEraLength = 10: no snapshot mid-era.
+        Reputation::on_initialize(5);
+        assert!(LeaderboardSnapshots::<Test>::iter().next().is_none());
+
+        Reputation::on_initialize(10);
+        System::assert_last_event(Event::LeaderboardSnapshotted { era: 1, entries: 1 }.into());
+
+        assert_ok!(Reputation::slash_reputation(
+            RuntimeOrigin::root(),
+            2,
+            200,
+            b"test".to_vec()
+        ));
+        Reputation::on_initialize(20);
+        Reputation::on_initialize(30);
+
+        // LeaderboardHistoryDepth = 2: era 1 is pruned once era 3 starts.
+        assert!(Reputation::leaderboard(Some(1)).is_empty());
+        assert_eq!(Reputation::leaderboard(Some(3)), vec![(1, 4900), (2, 4800)]);
+        assert_eq!(
+            Reputation::leaderboard(Some(2)),
+            Reputation::leaderboard(Some(3))
+        );
+    });
+}
@@ -9,6 +9,7 @@
 //! - Storing node metadata (region, type, capabilities)
 //! - Tracking node health via periodic heartbeats
 //! - Automated discovery of available RPC endpoints by EvoClaw agents
+//! - Rewarding public RPC operators from a treasury budget by measured uptime
 //!
 //! ## Interface
 //!
@@ -19,6 +20,15 @@
 //! - `heartbeat` - Prove that a node is still alive
 //! - `deregister_node` - Remove an RPC endpoint from the registry
 //! - `report_inactive` - Mark a node as inactive if heartbeat expired
+//! - `set_reward_opt_out` - Opt the caller's nodes out of uptime rewards
+//!
+//! ### Uptime Rewards
+//!
+//! A heartbeat sent at least `UptimeSampleInterval` blocks after the previous
+//! one counts as one uptime sample for the current reward era. Every
+//! `RewardEraLength` blocks, `EraRewardBudget` is paid from `RewardSource` to
+//! the owners of Active nodes pro rata to their samples, skipping operators
+//! that opted out. Payouts are recorded per era in `EraPayouts`.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(deprecated, clippy::let_unit_value)]
@@ -31,13 +41,23 @@ pub use pallet::*;
 mod tests;
 
 use alloc::vec::Vec;
+use clawchain_primitives::EventBytes;
 
 #[frame_support::pallet]
 pub mod pallet {
     use super::*;
-    use frame_support::pallet_prelude::*;
+    use frame_support::{
+        pallet_prelude::*,
+        traits::{Currency, ExistenceRequirement},
+    };
     use frame_system::pallet_prelude::*;
-    use sp_runtime::traits::Saturating;
+    use sp_runtime::{
+        traits::{Saturating, Zero},
+        Perbill,
+    };
+
+    pub type BalanceOf<T> =
+        <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
     /// Type alias for RPC node IDs (sequential u64).
     pub type RpcNodeId = u64;
@@ -142,6 +162,25 @@ pub mod pallet {
         /// Maximum heartbeat interval in blocks before a node is considered inactive.
         #[pallet::constant]
         type MaxHeartbeatInterval: Get<u32>;
+
+        /// Currency used to pay uptime rewards.
+        type Currency: Currency<Self::AccountId>;
+
+        /// Account funding uptime rewards (e.g. the treasury).
+        type RewardSource: Get<Self::AccountId>;
+
+        /// Amount distributed among eligible nodes at the end of each reward era.
+        #[pallet::constant]
+        type EraRewardBudget: Get<BalanceOf<Self>>;
+
+        /// Length of a reward era in blocks.
+        #[pallet::constant]
+        type RewardEraLength: Get<u32>;
+
+        /// Minimum blocks between two heartbeats for the later one to count
+        /// as an uptime sample.
+        #[pallet::constant]
+        type UptimeSampleInterval: Get<u32>;
     }
 
     #[pallet::pallet]
@@ -177,6 +216,48 @@ pub mod pallet {
     pub type ActiveNodes<T: Config> =
         StorageValue<_, BoundedVec<RpcNodeId, T::MaxActiveNodes>, ValueQuery>;
 
+    /// Index of the current reward era.
+    #[pallet::storage]
+    pub type CurrentRewardEra<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+    /// Uptime samples collected by each node in the current reward era.
+    #[pallet::storage]
+    pub type EraUptime<T: Config> = StorageMap<_, Blake2_128Concat, RpcNodeId, u32, ValueQuery>;
+
+    /// Reward paid to each node for a completed era.
+    #[pallet::storage]
+    pub type EraPayouts<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        u32,
+        Blake2_128Concat,
+        RpcNodeId,
+        BalanceOf<T>,
+        OptionQuery,
+    >;
+
+    /// Operators who declined uptime rewards.
+    #[pallet::storage]
+    pub type RewardOptOut<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, (), OptionQuery>;
+
+    // ========== Hooks ==========
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(n: BlockNumberFor<T>) -> Weight {
+            let era_length: BlockNumberFor<T> = T::RewardEraLength::get().into();
+            if era_length.is_zero() || n.is_zero() || !(n % era_length).is_zero() {
+                return Weight::zero();
+            }
+            let nodes = Self::end_reward_era();
+            T::DbWeight::get().reads_writes(
+                3u64.saturating_add(nodes.saturating_mul(3)),
+                2u64.saturating_add(nodes.saturating_mul(3)),
+            )
+        }
+    }
+
     // ========== Events ==========
 
     #[pallet::event]
@@ -186,11 +267,11 @@ pub mod pallet {
         NodeRegistered {
             node_id: RpcNodeId,
             owner: T::AccountId,
-            url: Vec<u8>,
-            region: Vec<u8>,
+            url: EventBytes,
+            region: EventBytes,
         },
         /// An RPC node's info was updated.
-        NodeUpdated { node_id: RpcNodeId, url: Vec<u8> },
+        NodeUpdated { node_id: RpcNodeId, url: EventBytes },
         /// An RPC node sent a heartbeat.
         Heartbeat {
             node_id: RpcNodeId,
@@ -203,6 +284,24 @@ pub mod pallet {
             node_id: RpcNodeId,
             last_heartbeat: BlockNumberFor<T>,
         },
+        /// An operator opted in to or out of uptime rewards.
+        RewardOptOutSet {
+            account: T::AccountId,
+            opted_out: bool,
+        },
+        /// A node's owner was paid its share of the era budget.
+        UptimeRewardPaid {
+            era: u32,
+            node_id: RpcNodeId,
+            owner: T::AccountId,
+            amount: BalanceOf<T>,
+        },
+        /// A reward era closed.
+        RewardEraEnded {
+            era: u32,
+            rewarded_nodes: u32,
+            total_paid: BalanceOf<T>,
+        },
     }
 
     // ========== Errors ==========
@@ -298,8 +397,8 @@ pub mod pallet {
             Self::deposit_event(Event::NodeRegistered {
                 node_id,
                 owner: who,
-                url,
-                region,
+                url: EventBytes::new(&url),
+                region: EventBytes::new(&region),
             });
 
             Ok(())
@@ -342,7 +441,10 @@ pub mod pallet {
                 Ok(())
             })?;
 
-            Self::deposit_event(Event::NodeUpdated { node_id, url });
+            Self::deposit_event(Event::NodeUpdated {
+                node_id,
+                url: EventBytes::new(&url),
+            });
 
             Ok(())
         }
@@ -368,6 +470,10 @@ pub mod pallet {
                 );
 
                 let current_block = <frame_system::Pallet<T>>::block_number();
+                let sample_interval: BlockNumberFor<T> = T::UptimeSampleInterval::get().into();
+                if current_block.saturating_sub(node.last_heartbeat) >= sample_interval {
+                    EraUptime::<T>::mutate(node_id, |samples| *samples = samples.saturating_add(1));
+                }
                 node.last_heartbeat = current_block;
 
                 // If the node was inactive, mark it as active again
@@ -464,6 +570,98 @@ pub mod pallet {
 
             Ok(())
         }
+
+        /// Opt the caller's nodes out of (or back in to) uptime rewards.
+        ///
+        /// Opted-out nodes still collect uptime samples but their share is
+        /// not paid and is not redistributed.
+        #[pallet::call_index(5)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(0, 1))]
+        pub fn set_reward_opt_out(origin: OriginFor<T>, opted_out: bool) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            if opted_out {
+                RewardOptOut::<T>::insert(&who, ());
+            } else {
+                RewardOptOut::<T>::remove(&who);
+            }
+
+            Self::deposit_event(Event::RewardOptOutSet {
+                account: who,
+                opted_out,
+            });
+
+            Ok(())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Pay the era budget to Active nodes by uptime share and start a new
+        /// era. Returns the number of nodes considered.
+        pub(crate) fn end_reward_era() -> u64 {
+            let era = CurrentRewardEra::<T>::get();
+            let active = ActiveNodes::<T>::get();
+
+            let samples: Vec<(RpcNodeId, u32)> = active
+                .iter()
+                .map(|&node_id| (node_id, EraUptime::<T>::get(node_id)))
+                .filter(|(_, samples)| *samples > 0)
+                .collect();
+            let total_samples = samples
+                .iter()
+                .fold(0u32, |acc, (_, s)| acc.saturating_add(*s));
+
+            let budget = T::EraRewardBudget::get();
+            let source = T::RewardSource::get();
+            let mut rewarded_nodes = 0u32;
+            let mut total_paid = BalanceOf::<T>::zero();
+
+            for (node_id, node_samples) in samples {
+                let Some(node) = RpcNodes::<T>::get(node_id) else {
+                    continue;
+                };
+                if node.status != NodeStatus::Active || RewardOptOut::<T>::contains_key(&node.owner)
+                {
+                    continue;
+                }
+                let amount = Perbill::from_rational(node_samples, total_samples).mul_floor(budget);
+                if amount.is_zero() {
+                    continue;
+                }
+                // An underfunded source skips the payout rather than failing the block.
+                if T::Currency::transfer(
+                    &source,
+                    &node.owner,
+                    amount,
+                    ExistenceRequirement::KeepAlive,
+                )
+                .is_err()
+                {
+                    continue;
+                }
+
+                EraPayouts::<T>::insert(era, node_id, amount);
+                rewarded_nodes = rewarded_nodes.saturating_add(1);
+                total_paid = total_paid.saturating_add(amount);
+                Self::deposit_event(Event::UptimeRewardPaid {
+                    era,
+                    node_id,
+                    owner: node.owner,
+                    amount,
+                });
+            }
+
+            let _ = EraUptime::<T>::clear(u32::MAX, None);
+            CurrentRewardEra::<T>::put(era.saturating_add(1));
+
+            Self::deposit_event(Event::RewardEraEnded {
+                era,
+                rewarded_nodes,
+                total_paid,
+            });
+
+            active.len() as u64
+        }
     }
 
     // ========== Weight Info Trait ==========
@@ -1,8 +1,14 @@
 //! Unit tests for the RPC Registry pallet.
 
 use crate as pallet_rpc_registry;
-use crate::pallet::{ActiveNodes, NodeCount, NodeStatus, NodeType, OwnerNodes, RpcNodes};
-use frame_support::{assert_noop, assert_ok, derive_impl, traits::ConstU32};
+use crate::pallet::{
+    ActiveNodes, CurrentRewardEra, EraPayouts, EraUptime, Event, NodeCount, NodeStatus, NodeType,
+    OwnerNodes, RpcNodes,
+};
+use frame_support::{
+    assert_noop, assert_ok, derive_impl, parameter_types,
+    traits::{ConstU32, ConstU64, Hooks},
+};
 use sp_runtime::{traits::IdentityLookup, BuildStorage};
 
 type Block = frame_system::mocking::MockBlock<Test>;
@@ -11,6 +17,7 @@ type Block = frame_system::mocking::MockBlock<Test>;
 frame_support::construct_runtime!(
     pub enum Test {
         System: frame_system,
+        Balances: pallet_balances,
         RpcRegistryPallet: pallet_rpc_registry,
     }
 );
@@ -20,6 +27,19 @@ impl frame_system::Config for Test {
     type Block = Block;
     type AccountId = u64;
     type Lookup = IdentityLookup<Self::AccountId>;
+    type AccountData = pallet_balances::AccountData<u64>;
+}
+
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+impl pallet_balances::Config for Test {
+    type AccountStore = System;
+    type ExistentialDeposit = ConstU64<1>;
+}
+
+const TREASURY: u64 = 99;
+
+parameter_types! {
+    pub const RewardSource: u64 = TREASURY;
 }
 
 impl pallet_rpc_registry::Config for Test {
@@ -30,13 +50,24 @@ impl pallet_rpc_registry::Config for Test {
     type MaxNodesPerOwner = ConstU32<10>;
     type MaxActiveNodes = ConstU32<1000>;
     type MaxHeartbeatInterval = ConstU32<300>;
+    type Currency = Balances;
+    type RewardSource = RewardSource;
+    type EraRewardBudget = ConstU64<1_000>;
+    type RewardEraLength = ConstU32<100>;
+    type UptimeSampleInterval = ConstU32<10>;
 }
 
 // Build test externalities from genesis storage.
 fn new_test_ext() -> sp_io::TestExternalities {
-    let t = frame_system::GenesisConfig::<Test>::default()
+    let mut t = frame_system::GenesisConfig::<Test>::default()
         .build_storage()
         .unwrap();
+    pallet_balances::GenesisConfig::<Test> {
+        balances: vec![(TREASURY, 100_000)],
+        ..Default::default()
+    }
+    .assimilate_storage(&mut t)
+    .unwrap();
     let mut ext = sp_io::TestExternalities::new(t);
     ext.execute_with(|| System::set_block_number(1));
     ext
@@ -69,8 +100,8 @@ fn register_node_works() {
         assert_eq!(node.url.to_vec(), url);
         assert_eq!(node.region.to_vec(), region);
         assert_eq!(node.node_type, NodeType::FullNode);
-        assert_eq!(node.supports_ws, true);
-        assert_eq!(node.supports_http, false);
+        assert!(node.supports_ws);
+        assert!(!node.supports_http);
         assert_eq!(node.status, NodeStatus::Active);
         assert_eq!(node.registered_at, 1);
         assert_eq!(node.last_heartbeat, 1);
@@ -518,3 +549,84 @@ fn active_nodes_list_management() {
         assert!(active.contains(&2));
     });
 }
+
+// ========== Uptime reward tests ==========
+
+fn register(owner: u64) {
+    assert_ok!(RpcRegistryPallet::register_node(
+        account(owner),
+        b"wss://rpc.test".to_vec(),
+        b"region".to_vec(),
+        NodeType::FullNode,
+        true,
+        false
+    ));
+}
+
+#[test]
+fn heartbeat_counts_uptime_samples_at_interval() {
+    new_test_ext().execute_with(|| {
+        register(1);
+
+        System::set_block_number(5);
+        assert_ok!(RpcRegistryPallet::heartbeat(account(1), 0));
+        assert_eq!(EraUptime::<Test>::get(0), 0);
+
+        System::set_block_number(15);
+        assert_ok!(RpcRegistryPallet::heartbeat(account(1), 0));
+        assert_eq!(EraUptime::<Test>::get(0), 1);
+    });
+}
+
+#[test]
+fn era_end_pays_owners_by_uptime_share() {
+    new_test_ext().execute_with(|| {
+        register(1);
+        register(2);
+        register(3);
+
+        // Node 0: three samples, node 1: one sample, node 2: none.
+        for block in [11u64, 21, 31] {
+            System::set_block_number(block);
+            assert_ok!(RpcRegistryPallet::heartbeat(account(1), 0));
+        }
+        assert_ok!(RpcRegistryPallet::heartbeat(account(2), 1));
+
+        RpcRegistryPallet::on_initialize(100);
+
+        assert_eq!(Balances::free_balance(1), 750);
+        assert_eq!(Balances::free_balance(2), 250);
+        assert_eq!(Balances::free_balance(3), 0);
+        assert_eq!(EraPayouts::<Test>::get(0, 0), Some(750));
+        assert_eq!(EraPayouts::<Test>::get(0, 2), None);
+        assert_eq!(CurrentRewardEra::<Test>::get(), 1);
+        assert_eq!(EraUptime::<Test>::get(0), 0);
+        System::assert_last_event(
+            Event::RewardEraEnded {
+                era: 0,
+                rewarded_nodes: 2,
+                total_paid: 1_000,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn opted_out_operators_are_not_paid() {
+    new_test_ext().execute_with(|| {
+        register(1);
+        register(2);
+        System::set_block_number(11);
+        assert_ok!(RpcRegistryPallet::heartbeat(account(1), 0));
+        assert_ok!(RpcRegistryPallet::heartbeat(account(2), 1));
+
+        assert_ok!(RpcRegistryPallet::set_reward_opt_out(account(2), true));
+        RpcRegistryPallet::on_initialize(100);
+
+        assert_eq!(Balances::free_balance(1), 500);
+        assert_eq!(Balances::free_balance(2), 0);
+        assert_eq!(Balances::free_balance(TREASURY), 99_500);
+        assert_eq!(EraPayouts::<Test>::get(0, 1), None);
+    });
+}
@@ -0,0 +1,206 @@
+//! Benchmarking for pallet-security-bounty.
+//!
+//! Weights are generated by running:
+//!   `cargo build --features runtime-benchmarks`
+//!   `./target/release/clawchain-node benchmark pallet --pallet pallet_security_bounty ...`
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use crate::pallet::{BalanceOf, Categories, Reports};
+use alloc::{vec, vec::Vec};
+use frame_benchmarking::v2::*;
+use frame_support::traits::{Currency, Get, SortedMembers};
+use frame_system::RawOrigin;
+use sp_runtime::traits::{Bounded, Hash, Saturating};
+
+const SALT: [u8; 32] = [3u8; 32];
+
+/// Give `who` far more than any deposit, award or pot used below.
+fn fund<T: Config>(who: &T::AccountId) {
+    T::Currency::make_free_balance_be(who, BalanceOf::<T>::max_value() / 4u32.into());
+}
+
+/// Seat `n` committee members and return them.
+fn committee<T: Config>(n: u32) -> Vec<T::AccountId> {
+    (0..n)
+        .map(|i| {
+            let member: T::AccountId = account("member", i, 0);
+            T::SecurityCommittee::add(&member);
+            member
+        })
+        .collect()
+}
+
+/// Open category 0 with a pot covering `max_payout`.
+fn open_category<T: Config>() -> BalanceOf<T> {
+    fund::<T>(&T::TreasuryAccount::get());
+    let max_payout = T::Currency::minimum_balance().saturating_mul(100u32.into());
+    Pallet::<T>::create_category(
+        RawOrigin::Root.into(),
+        vec![b'x'; T::MaxNameLength::get() as usize],
+        max_payout,
+        max_payout.saturating_mul(10u32.into()),
+    )
+    .expect("treasury is funded");
+    max_payout
+}
+
+/// Commit report 0 for `revealer` and return its report hash.
+fn commit<T: Config>(revealer: &T::AccountId) -> T::Hash {
+    let reporter: T::AccountId = account("reporter", 0, 0);
+    fund::<T>(&reporter);
+    let report_hash = T::Hashing::hash(b"report");
+    Pallet::<T>::submit_report(
+        RawOrigin::Signed(reporter).into(),
+        0,
+        T::Hashing::hash_of(&(report_hash, SALT, revealer)),
+    )
+    .expect("category is open and reporter funded");
+    report_hash
+}
+
+/// Commit and reveal report 0 to the first of `members`.
+fn revealed<T: Config>(members: &[T::AccountId]) {
+    let revealer: T::AccountId = account("revealer", 0, 0);
+    let report_hash = commit::<T>(&revealer);
+    Pallet::<T>::reveal_report(
+        RawOrigin::Signed(revealer).into(),
+        0,
+        report_hash,
+        SALT,
+        members[0].clone(),
+        None,
+    )
+    .expect("commitment matches");
+}
+
+#[benchmarks]
+mod benchmarks {
+    use super::*;
+
+    #[benchmark]
+    fn create_category() {
+        let treasury = T::TreasuryAccount::get();
+        fund::<T>(&treasury);
+        let funding = T::Currency::minimum_balance().saturating_mul(1_000u32.into());
+
+        #[extrinsic_call]
+        create_category(
+            RawOrigin::Root,
+            vec![b'x'; T::MaxNameLength::get() as usize],
+            funding,
+            funding,
+        );
+
+        assert!(Categories::<T>::contains_key(0));
+    }
+
+    #[benchmark]
+    fn close_category() {
+        open_category::<T>();
+
+        #[extrinsic_call]
+        close_category(RawOrigin::Root, 0);
+
+        assert!(!Categories::<T>::contains_key(0));
+    }
+
+    #[benchmark]
+    fn submit_report() {
+        open_category::<T>();
+        let reporter: T::AccountId = whitelisted_caller();
+        fund::<T>(&reporter);
+
+        #[extrinsic_call]
+        submit_report(
+            RawOrigin::Signed(reporter),
+            0,
+            T::Hashing::hash(b"commitment"),
+        );
+
+        assert!(Reports::<T>::contains_key(0));
+    }
+
+    #[benchmark]
+    fn reveal_report() {
+        let members = committee::<T>(1);
+        open_category::<T>();
+        let revealer: T::AccountId = whitelisted_caller();
+        let report_hash = commit::<T>(&revealer);
+
+        #[extrinsic_call]
+        reveal_report(
+            RawOrigin::Signed(revealer),
+            0,
+            report_hash,
+            SALT,
+            members[0].clone(),
+            None,
+        );
+
+        assert!(Reports::<T>::get(0).is_some_and(|r| r.reveal.is_some()));
+    }
+
+    /// Worst case: re-proposing over an approval and rejections one short of
+    /// closing the report, all of which are scanned.
+    #[benchmark]
+    fn propose_award() {
+        let threshold = T::ApprovalThreshold::get();
+        let members = committee::<T>(threshold.saturating_add(1));
+        let max_payout = open_category::<T>();
+        revealed::<T>(&members);
+        Pallet::<T>::propose_award(RawOrigin::Signed(members[0].clone()).into(), 0, max_payout)
+            .expect("report is revealed");
+        for member in members.iter().take(threshold as usize).skip(1) {
+            Pallet::<T>::vote_on_report(RawOrigin::Signed(member.clone()).into(), 0, false)
+                .expect("member has not voted");
+        }
+        let proposer = members[threshold as usize].clone();
+
+        #[extrinsic_call]
+        propose_award(RawOrigin::Signed(proposer), 0, max_payout);
+
+        assert!(Reports::<T>::get(0).is_some_and(|r| r.award == Some(max_payout)));
+    }
+
+    /// Worst case: the deciding approval, so the award is transferred.
+    #[benchmark]
+    fn vote_on_report() {
+        let threshold = T::ApprovalThreshold::get();
+        let members = committee::<T>(threshold);
+        let max_payout = open_category::<T>();
+        revealed::<T>(&members);
+        Pallet::<T>::propose_award(RawOrigin::Signed(members[0].clone()).into(), 0, max_payout)
+            .expect("report is revealed");
+        for member in members.iter().take(threshold as usize - 1).skip(1) {
+            Pallet::<T>::vote_on_report(RawOrigin::Signed(member.clone()).into(), 0, true)
+                .expect("member has not voted");
+        }
+        let voter = members[threshold as usize - 1].clone();
+
+        #[extrinsic_call]
+        vote_on_report(RawOrigin::Signed(voter), 0, true);
+
+        assert!(!Reports::<T>::contains_key(0));
+    }
+
+    #[benchmark]
+    fn expire_report() {
+        open_category::<T>();
+        commit::<T>(&account::<T::AccountId>("revealer", 0, 0));
+        let now = frame_system::Pallet::<T>::block_number();
+        frame_system::Pallet::<T>::set_block_number(
+            now.saturating_add(T::RevealPeriod::get())
+                .saturating_add(1u32.into()),
+        );
+        let caller: T::AccountId = whitelisted_caller();
+
+        #[extrinsic_call]
+        expire_report(RawOrigin::Signed(caller), 0);
+
+        assert!(!Reports::<T>::contains_key(0));
+    }
+
+    impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
+}
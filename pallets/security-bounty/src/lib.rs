@@ -0,0 +1,644 @@
+//! # Security Bounty Pallet
+//!
+//! Treasury-funded bug bounties paid out on security committee approval.
+//!
+//! ## Overview
+//!
+//! Governance opens bounty **categories** (e.g. "consensus", "runtime
+//! escrow"), each with a maximum payout and a pot moved from the treasury
+//! into the category's own sub-account. Reports then go through three steps:
+//!
+//! 1. **Commit** — the reporter submits `hash(report_hash, salt, reveal_key)`
+//!    with a `ReportDeposit`. This timestamps the finding without disclosing
+//!    it, and binds the report to a reveal key account that is not linked to
+//!    the reporter on-chain.
+//! 2. **Reveal** — within `RevealPeriod` blocks the reveal key reveals
+//!    `report_hash` and `salt`, naming the committee member the encrypted
+//!    report was delivered to. If that member has a messaging key registered
+//!    (see [`MessagingKeys`]), its fingerprint is recorded so the committee
+//!    knows which key opens the report. The award is paid to the reveal key
+//!    or an optional `payee`, so neither is tied to the committing account.
+//! 3. **Award** — a committee member proposes an award up to the category's
+//!    `max_payout`, and once `ApprovalThreshold` members approve it the award
+//!    is paid from the category pot and the deposit returned. The same number
+//!    of rejections closes the report and sends the deposit to the treasury.
+//!    Proposing a new amount discards approvals of the old one but keeps
+//!    rejections.
+//!
+//! Commitments left unrevealed past `RevealPeriod` can be expired by anyone;
+//! their deposit also goes to the treasury.
+//!
+//! ## Extrinsics
+//!
+//! | Call | Who |
+//! |------|-----|
+//! | `create_category` | `GovernanceOrigin` |
+//! | `close_category` | `GovernanceOrigin` |
+//! | `submit_report` | Anyone |
+//! | `reveal_report` | Reveal key |
+//! | `propose_award` | Committee member |
+//! | `vote_on_report` | Committee member |
+//! | `expire_report` | Anyone |
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(deprecated, clippy::let_unit_value)]
+
+extern crate alloc;
+
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+pub mod weights;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(test)]
+pub mod mock;
+#[cfg(test)]
+mod tests;
+
+/// Lookup into the messaging key registry, used to record which key an
+/// encrypted report was delivered to.
+pub trait MessagingKeys<AccountId> {
+    /// Fingerprint of the encryption key `who` has registered, if any.
+    fn key_fingerprint(who: &AccountId) -> Option<[u8; 32]>;
+}
+
+impl<AccountId> MessagingKeys<AccountId> for () {
+    fn key_fingerprint(_: &AccountId) -> Option<[u8; 32]> {
+        None
+    }
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+    use super::*;
+    use alloc::vec::Vec;
+    use frame_support::{
+        pallet_prelude::*,
+        traits::{
+            BalanceStatus, Currency, ExistenceRequirement, ReservableCurrency, SortedMembers,
+        },
+        PalletId,
+    };
+    use frame_system::pallet_prelude::*;
+    use sp_runtime::traits::{AccountIdConversion, Hash, Saturating, Zero};
+
+    pub type BalanceOf<T> =
+        <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+    pub type CategoryId = u32;
+    pub type ReportId = u64;
+
+    // =========================================================================
+    // Types
+    // =========================================================================
+
+    /// A bounty category and its payout limit.
+    #[derive(Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    pub struct BountyCategory<T: Config> {
+        pub name: BoundedVec<u8, T::MaxNameLength>,
+        /// Largest award a single report in this category can receive.
+        pub max_payout: BalanceOf<T>,
+        /// Reports filed against the category that are not yet closed.
+        pub open_reports: u32,
+    }
+
+    impl<T: Config> codec::DecodeWithMemTracking for BountyCategory<T> {}
+
+    /// What a reporter disclosed when revealing a commitment.
+    #[derive(Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    pub struct Reveal<T: Config> {
+        /// Hash of the encrypted report delivered off-chain.
+        pub report_hash: T::Hash,
+        /// Committee member the report was encrypted to.
+        pub recipient: T::AccountId,
+        /// Fingerprint of the recipient's messaging key at reveal time.
+        pub recipient_key: Option<[u8; 32]>,
+        /// Account any award is paid to.
+        pub payee: T::AccountId,
+    }
+
+    impl<T: Config> codec::DecodeWithMemTracking for Reveal<T> {}
+
+    /// A vulnerability report.
+    #[derive(Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    pub struct Report<T: Config> {
+        pub category_id: CategoryId,
+        pub reporter: T::AccountId,
+        /// `hash(report_hash, salt, reveal_key)`.
+        pub commitment: T::Hash,
+        pub submitted_at: BlockNumberFor<T>,
+        pub deposit: BalanceOf<T>,
+        pub reveal: Option<Reveal<T>>,
+        /// Award currently up for approval.
+        pub award: Option<BalanceOf<T>>,
+        /// Committee votes on the current award (`true` approves).
+        pub votes: BoundedVec<(T::AccountId, bool), T::MaxCommitteeVotes>,
+    }
+
+    impl<T: Config> codec::DecodeWithMemTracking for Report<T> {}
+
+    // =========================================================================
+    // Config
+    // =========================================================================
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        /// The overarching event type.
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+        /// Weight information for extrinsics.
+        type WeightInfo: WeightInfo;
+
+        /// Currency for pots, deposits and awards.
+        type Currency: ReservableCurrency<Self::AccountId>;
+
+        /// Origin allowed to open and close categories.
+        type GovernanceOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Members of the security committee.
+        type SecurityCommittee: SortedMembers<Self::AccountId>;
+
+        /// Messaging key registry consulted on reveal.
+        type MessagingKeys: MessagingKeys<Self::AccountId>;
+
+        /// Account category pots are funded from and returned to.
+        type TreasuryAccount: Get<Self::AccountId>;
+
+        /// Pallet id deriving the category pot accounts.
+        #[pallet::constant]
+        type PalletId: Get<PalletId>;
+
+        /// Deposit reserved with each commitment.
+        #[pallet::constant]
+        type ReportDeposit: Get<BalanceOf<Self>>;
+
+        /// Blocks after submission within which a report must be revealed.
+        #[pallet::constant]
+        type RevealPeriod: Get<BlockNumberFor<Self>>;
+
+        /// Committee votes needed to pay or reject a report.
+        #[pallet::constant]
+        type ApprovalThreshold: Get<u32>;
+
+        /// Maximum votes recorded on one award; at least the committee size.
+        #[pallet::constant]
+        type MaxCommitteeVotes: Get<u32>;
+
+        /// Maximum length of a category name.
+        #[pallet::constant]
+        type MaxNameLength: Get<u32>;
+    }
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(_);
+
+    // =========================================================================
+    // Storage
+    // =========================================================================
+
+    #[pallet::storage]
+    pub type Categories<T: Config> =
+        StorageMap<_, Twox64Concat, CategoryId, BountyCategory<T>, OptionQuery>;
+
+    #[pallet::storage]
+    pub type NextCategoryId<T: Config> = StorageValue<_, CategoryId, ValueQuery>;
+
+    #[pallet::storage]
+    pub type Reports<T: Config> = StorageMap<_, Twox64Concat, ReportId, Report<T>, OptionQuery>;
+
+    #[pallet::storage]
+    pub type NextReportId<T: Config> = StorageValue<_, ReportId, ValueQuery>;
+
+    // =========================================================================
+    // Events
+    // =========================================================================
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// Governance opened a category, moving `funding` from the treasury.
+        CategoryCreated {
+            category_id: CategoryId,
+            max_payout: BalanceOf<T>,
+            funding: BalanceOf<T>,
+        },
+        /// A category was closed and its remaining pot returned.
+        CategoryClosed {
+            category_id: CategoryId,
+            returned: BalanceOf<T>,
+        },
+        ReportSubmitted {
+            report_id: ReportId,
+            category_id: CategoryId,
+            commitment: T::Hash,
+        },
+        ReportRevealed {
+            report_id: ReportId,
+            recipient: T::AccountId,
+        },
+        /// A member put an award up for approval, discarding approvals of any
+        /// earlier amount.
+        AwardProposed {
+            report_id: ReportId,
+            proposer: T::AccountId,
+            amount: BalanceOf<T>,
+        },
+        ReportVoted {
+            report_id: ReportId,
+            member: T::AccountId,
+            approve: bool,
+        },
+        /// The award was approved and paid.
+        ReportAwarded {
+            report_id: ReportId,
+            payee: T::AccountId,
+            amount: BalanceOf<T>,
+        },
+        /// The committee rejected the report; its deposit went to the treasury.
+        ReportRejected { report_id: ReportId },
+        /// The report was never revealed; its deposit went to the treasury.
+        ReportExpired { report_id: ReportId },
+    }
+
+    // =========================================================================
+    // Errors
+    // =========================================================================
+
+    #[pallet::error]
+    pub enum Error<T> {
+        CategoryNotFound,
+        /// The category still has open reports.
+        CategoryHasOpenReports,
+        NameTooLong,
+        ReportNotFound,
+        /// The caller or recipient is not on the security committee.
+        NotCommitteeMember,
+        /// The report has already been revealed.
+        AlreadyRevealed,
+        /// The report has not been revealed yet.
+        NotRevealed,
+        /// The revealed hash, salt and signer do not match the commitment.
+        CommitmentMismatch,
+        /// The reveal period has ended.
+        RevealPeriodOver,
+        /// The reveal period has not ended yet.
+        RevealPeriodOpen,
+        /// Above the category's `max_payout`.
+        AwardTooHigh,
+        /// No award has been proposed to approve.
+        NoAwardProposed,
+        /// The member has already voted on the current award.
+        AlreadyVoted,
+        /// More votes than `MaxCommitteeVotes`.
+        TooManyVotes,
+        /// The treasury or category pot cannot cover the transfer.
+        InsufficientFunds,
+    }
+
+    // =========================================================================
+    // Extrinsics
+    // =========================================================================
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Open a category paying up to `max_payout` per report, funded with
+        /// `funding` from the treasury.
+        #[pallet::call_index(0)]
+        #[pallet::weight(T::WeightInfo::create_category())]
+        pub fn create_category(
+            origin: OriginFor<T>,
+            name: Vec<u8>,
+            max_payout: BalanceOf<T>,
+            funding: BalanceOf<T>,
+        ) -> DispatchResult {
+            T::GovernanceOrigin::ensure_origin(origin)?;
+            let name: BoundedVec<u8, T::MaxNameLength> =
+                name.try_into().map_err(|_| Error::<T>::NameTooLong)?;
+
+            let category_id = NextCategoryId::<T>::get();
+            T::Currency::transfer(
+                &T::TreasuryAccount::get(),
+                &Self::category_account(category_id),
+                funding,
+                ExistenceRequirement::KeepAlive,
+            )
+            .map_err(|_| Error::<T>::InsufficientFunds)?;
+
+            Categories::<T>::insert(
+                category_id,
+                BountyCategory {
+                    name,
+                    max_payout,
+                    open_reports: 0,
+                },
+            );
+            NextCategoryId::<T>::put(category_id.saturating_add(1));
+
+            Self::deposit_event(Event::CategoryCreated {
+                category_id,
+                max_payout,
+                funding,
+            });
+            Ok(())
+        }
+
+        /// Close a category with no open reports, returning its pot to the
+        /// treasury.
+        #[pallet::call_index(1)]
+        #[pallet::weight(T::WeightInfo::close_category())]
+        pub fn close_category(origin: OriginFor<T>, category_id: CategoryId) -> DispatchResult {
+            T::GovernanceOrigin::ensure_origin(origin)?;
+            let category = Categories::<T>::get(category_id).ok_or(Error::<T>::CategoryNotFound)?;
+            ensure!(
+                category.open_reports == 0,
+                Error::<T>::CategoryHasOpenReports
+            );
+
+            let pot = Self::category_account(category_id);
+            let returned = T::Currency::free_balance(&pot);
+            if !returned.is_zero() {
+                T::Currency::transfer(
+                    &pot,
+                    &T::TreasuryAccount::get(),
+                    returned,
+                    ExistenceRequirement::AllowDeath,
+                )?;
+            }
+            Categories::<T>::remove(category_id);
+
+            Self::deposit_event(Event::CategoryClosed {
+                category_id,
+                returned,
+            });
+            Ok(())
+        }
+
+        /// Commit to a report with `hash(report_hash, salt, reveal_key)`,
+        /// reserving `ReportDeposit`.
+        #[pallet::call_index(2)]
+        #[pallet::weight(T::WeightInfo::submit_report())]
+        pub fn submit_report(
+            origin: OriginFor<T>,
+            category_id: CategoryId,
+            commitment: T::Hash,
+        ) -> DispatchResult {
+            let reporter = ensure_signed(origin)?;
+            Categories::<T>::try_mutate(category_id, |maybe| {
+                let category = maybe.as_mut().ok_or(Error::<T>::CategoryNotFound)?;
+                category.open_reports = category.open_reports.saturating_add(1);
+                Ok::<(), DispatchError>(())
+            })?;
+
+            let deposit = T::ReportDeposit::get();
+            T::Currency::reserve(&reporter, deposit).map_err(|_| Error::<T>::InsufficientFunds)?;
+
+            let report_id = NextReportId::<T>::get();
+            Reports::<T>::insert(
+                report_id,
+                Report {
+                    category_id,
+                    reporter,
+                    commitment,
+                    submitted_at: frame_system::Pallet::<T>::block_number(),
+                    deposit,
+                    reveal: None,
+                    award: None,
+                    votes: BoundedVec::new(),
+                },
+            );
+            NextReportId::<T>::put(report_id.saturating_add(1));
+
+            Self::deposit_event(Event::ReportSubmitted {
+                report_id,
+                category_id,
+                commitment,
+            });
+            Ok(())
+        }
+
+        /// Reveal a committed report from the reveal key bound into its
+        /// commitment, naming the committee member its encrypted contents were
+        /// delivered to and, optionally, a `payee` other than the reveal key.
+        #[pallet::call_index(3)]
+        #[pallet::weight(T::WeightInfo::reveal_report())]
+        pub fn reveal_report(
+            origin: OriginFor<T>,
+            report_id: ReportId,
+            report_hash: T::Hash,
+            salt: [u8; 32],
+            recipient: T::AccountId,
+            payee: Option<T::AccountId>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(
+                T::SecurityCommittee::contains(&recipient),
+                Error::<T>::NotCommitteeMember
+            );
+            let now = frame_system::Pallet::<T>::block_number();
+
+            Reports::<T>::try_mutate(report_id, |maybe| {
+                let report = maybe.as_mut().ok_or(Error::<T>::ReportNotFound)?;
+                ensure!(report.reveal.is_none(), Error::<T>::AlreadyRevealed);
+                ensure!(
+                    now <= report.submitted_at.saturating_add(T::RevealPeriod::get()),
+                    Error::<T>::RevealPeriodOver
+                );
+                ensure!(
+                    T::Hashing::hash_of(&(report_hash, salt, &who)) == report.commitment,
+                    Error::<T>::CommitmentMismatch
+                );
+
+                report.reveal = Some(Reveal {
+                    report_hash,
+                    recipient_key: T::MessagingKeys::key_fingerprint(&recipient),
+                    recipient: recipient.clone(),
+                    payee: payee.unwrap_or(who),
+                });
+                Ok::<(), DispatchError>(())
+            })?;
+
+            Self::deposit_event(Event::ReportRevealed {
+                report_id,
+                recipient,
+            });
+            Ok(())
+        }
+
+        /// Committee member proposes an award for a revealed report. Approvals
+        /// of an earlier amount are discarded but rejections stand, and the
+        /// proposal counts as the proposer's approval.
+        #[pallet::call_index(4)]
+        #[pallet::weight(T::WeightInfo::propose_award())]
+        pub fn propose_award(
+            origin: OriginFor<T>,
+            report_id: ReportId,
+            amount: BalanceOf<T>,
+        ) -> DispatchResult {
+            let member = Self::ensure_member(origin)?;
+
+            Reports::<T>::try_mutate(report_id, |maybe| {
+                let report = maybe.as_mut().ok_or(Error::<T>::ReportNotFound)?;
+                ensure!(report.reveal.is_some(), Error::<T>::NotRevealed);
+                let category =
+                    Categories::<T>::get(report.category_id).ok_or(Error::<T>::CategoryNotFound)?;
+                ensure!(amount <= category.max_payout, Error::<T>::AwardTooHigh);
+
+                report.votes.retain(|(_, approve)| !*approve);
+                ensure!(
+                    !report.votes.iter().any(|(m, _)| *m == member),
+                    Error::<T>::AlreadyVoted
+                );
+                report
+                    .votes
+                    .try_push((member.clone(), true))
+                    .map_err(|_| Error::<T>::TooManyVotes)?;
+                report.award = Some(amount);
+                Ok::<(), DispatchError>(())
+            })?;
+
+            Self::deposit_event(Event::AwardProposed {
+                report_id,
+                proposer: member,
+                amount,
+            });
+            Self::conclude(report_id)
+        }
+
+        /// Committee member approves the proposed award or rejects the
+        /// report.
+        #[pallet::call_index(5)]
+        #[pallet::weight(T::WeightInfo::vote_on_report())]
+        pub fn vote_on_report(
+            origin: OriginFor<T>,
+            report_id: ReportId,
+            approve: bool,
+        ) -> DispatchResult {
+            let member = Self::ensure_member(origin)?;
+
+            Reports::<T>::try_mutate(report_id, |maybe| {
+                let report = maybe.as_mut().ok_or(Error::<T>::ReportNotFound)?;
+                ensure!(report.reveal.is_some(), Error::<T>::NotRevealed);
+                ensure!(
+                    !approve || report.award.is_some(),
+                    Error::<T>::NoAwardProposed
+                );
+                ensure!(
+                    !report.votes.iter().any(|(m, _)| *m == member),
+                    Error::<T>::AlreadyVoted
+                );
+                report
+                    .votes
+                    .try_push((member.clone(), approve))
+                    .map_err(|_| Error::<T>::TooManyVotes)?;
+                Ok::<(), DispatchError>(())
+            })?;
+
+            Self::deposit_event(Event::ReportVoted {
+                report_id,
+                member,
+                approve,
+            });
+            Self::conclude(report_id)
+        }
+
+        /// Expire a report left unrevealed past `RevealPeriod`, sending its
+        /// deposit to the treasury.
+        #[pallet::call_index(6)]
+        #[pallet::weight(T::WeightInfo::expire_report())]
+        pub fn expire_report(origin: OriginFor<T>, report_id: ReportId) -> DispatchResult {
+            ensure_signed(origin)?;
+            let report = Reports::<T>::get(report_id).ok_or(Error::<T>::ReportNotFound)?;
+            ensure!(report.reveal.is_none(), Error::<T>::AlreadyRevealed);
+            ensure!(
+                frame_system::Pallet::<T>::block_number()
+                    > report.submitted_at.saturating_add(T::RevealPeriod::get()),
+                Error::<T>::RevealPeriodOpen
+            );
+
+            Self::forfeit_deposit(&report);
+            Self::close_report(report_id, &report);
+            Self::deposit_event(Event::ReportExpired { report_id });
+            Ok(())
+        }
+    }
+
+    // =========================================================================
+    // Helpers
+    // =========================================================================
+
+    impl<T: Config> Pallet<T> {
+        /// Sub-account holding a category's pot.
+        pub fn category_account(category_id: CategoryId) -> T::AccountId {
+            T::PalletId::get().into_sub_account_truncating(category_id)
+        }
+
+        fn ensure_member(origin: OriginFor<T>) -> Result<T::AccountId, DispatchError> {
+            let who = ensure_signed(origin)?;
+            ensure!(
+                T::SecurityCommittee::contains(&who),
+                Error::<T>::NotCommitteeMember
+            );
+            Ok(who)
+        }
+
+        /// Pay or reject a report once its votes reach `ApprovalThreshold`.
+        fn conclude(report_id: ReportId) -> DispatchResult {
+            let report = Reports::<T>::get(report_id).ok_or(Error::<T>::ReportNotFound)?;
+            let threshold = T::ApprovalThreshold::get() as usize;
+            let approvals = report.votes.iter().filter(|(_, approve)| *approve).count();
+            let rejections = report.votes.len() - approvals;
+
+            if let (Some(amount), Some(reveal)) = (report.award, report.reveal.as_ref()) {
+                if approvals >= threshold {
+                    T::Currency::transfer(
+                        &Self::category_account(report.category_id),
+                        &reveal.payee,
+                        amount,
+                        ExistenceRequirement::KeepAlive,
+                    )
+                    .map_err(|_| Error::<T>::InsufficientFunds)?;
+                    T::Currency::unreserve(&report.reporter, report.deposit);
+
+                    Self::close_report(report_id, &report);
+                    Self::deposit_event(Event::ReportAwarded {
+                        report_id,
+                        payee: reveal.payee.clone(),
+                        amount,
+                    });
+                    return Ok(());
+                }
+            }
+            if rejections >= threshold {
+                Self::forfeit_deposit(&report);
+                Self::close_report(report_id, &report);
+                Self::deposit_event(Event::ReportRejected { report_id });
+            }
+            Ok(())
+        }
+
+        /// Move a report's deposit to the treasury.
+        fn forfeit_deposit(report: &Report<T>) {
+            // Best effort: the deposit stays reserved if it cannot be moved.
+            let _ = T::Currency::repatriate_reserved(
+                &report.reporter,
+                &T::TreasuryAccount::get(),
+                report.deposit,
+                BalanceStatus::Free,
+            );
+        }
+
+        fn close_report(report_id: ReportId, report: &Report<T>) {
+            Reports::<T>::remove(report_id);
+            Categories::<T>::mutate(report.category_id, |maybe| {
+                if let Some(category) = maybe {
+                    category.open_reports = category.open_reports.saturating_sub(1);
+                }
+            });
+        }
+    }
+}
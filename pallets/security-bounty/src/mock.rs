@@ -0,0 +1,110 @@
+//! Mock runtime for pallet-security-bounty unit tests.
+
+use crate as pallet_security_bounty;
+use frame_support::{
+    derive_impl, parameter_types,
+    traits::{ConstU32, ConstU64, SortedMembers},
+    PalletId,
+};
+use frame_system::EnsureRoot;
+use sp_runtime::BuildStorage;
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+    pub enum Test {
+        System: frame_system,
+        Balances: pallet_balances,
+        SecurityBounty: pallet_security_bounty,
+    }
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig as frame_system::DefaultConfig)]
+impl frame_system::Config for Test {
+    type Block = Block;
+    type AccountData = pallet_balances::AccountData<u64>;
+}
+
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+impl pallet_balances::Config for Test {
+    type AccountStore = System;
+}
+
+pub const REPORTER: u64 = 1;
+pub const FRESH: u64 = 2;
+/// Reveal key the reporter commits to.
+pub const REVEALER: u64 = 3;
+pub const TREASURY: u64 = 99;
+pub const MEMBERS: [u64; 3] = [10, 11, 12];
+/// Member with a registered messaging key.
+pub const KEYED_MEMBER: u64 = 10;
+pub const KEY_FINGERPRINT: [u8; 32] = [7u8; 32];
+
+parameter_types! {
+    pub static Committee: Vec<u64> = MEMBERS.to_vec();
+}
+
+/// Security committee, initially [`MEMBERS`].
+pub struct MockCommittee;
+
+impl SortedMembers<u64> for MockCommittee {
+    fn sorted_members() -> Vec<u64> {
+        Committee::get()
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn add(who: &u64) {
+        Committee::mutate(|members| {
+            if let Err(pos) = members.binary_search(who) {
+                members.insert(pos, *who);
+            }
+        });
+    }
+}
+
+/// Key registry where only [`KEYED_MEMBER`] has registered a key.
+pub struct MockKeys;
+
+impl crate::MessagingKeys<u64> for MockKeys {
+    fn key_fingerprint(who: &u64) -> Option<[u8; 32]> {
+        (*who == KEYED_MEMBER).then_some(KEY_FINGERPRINT)
+    }
+}
+
+parameter_types! {
+    pub const BountyPalletId: PalletId = PalletId(*b"py/bnty!");
+    pub const TreasuryAccount: u64 = TREASURY;
+}
+
+impl pallet_security_bounty::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type WeightInfo = ();
+    type Currency = Balances;
+    type GovernanceOrigin = EnsureRoot<u64>;
+    type SecurityCommittee = MockCommittee;
+    type MessagingKeys = MockKeys;
+    type TreasuryAccount = TreasuryAccount;
+    type PalletId = BountyPalletId;
+    type ReportDeposit = ConstU64<50>;
+    type RevealPeriod = ConstU64<10>;
+    type ApprovalThreshold = ConstU32<2>;
+    type MaxCommitteeVotes = ConstU32<3>;
+    type MaxNameLength = ConstU32<32>;
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let mut t = frame_system::GenesisConfig::<Test>::default()
+        .build_storage()
+        .unwrap();
+
+    pallet_balances::GenesisConfig::<Test> {
+        balances: vec![(REPORTER, 1_000), (TREASURY, 10_000)],
+        dev_accounts: Default::default(),
+    }
+    .assimilate_storage(&mut t)
+    .unwrap();
+
+    let mut ext = sp_io::TestExternalities::new(t);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
@@ -0,0 +1,265 @@
+//! Unit tests for pallet-security-bounty.
+
+use crate::{
+    mock::*,
+    pallet::{Categories, Reports},
+    Error, Event,
+};
+use frame_support::{assert_noop, assert_ok};
+use sp_core::H256;
+use sp_runtime::traits::{BlakeTwo256, Hash};
+
+const SALT: [u8; 32] = [3u8; 32];
+
+fn report_hash() -> H256 {
+    H256::repeat_byte(0xab)
+}
+
+fn open_category() {
+    assert_ok!(SecurityBounty::create_category(
+        RuntimeOrigin::root(),
+        b"runtime".to_vec(),
+        500,
+        2_000,
+    ));
+}
+
+fn commit() {
+    assert_ok!(SecurityBounty::submit_report(
+        RuntimeOrigin::signed(REPORTER),
+        0,
+        BlakeTwo256::hash_of(&(report_hash(), SALT, REVEALER)),
+    ));
+}
+
+#[test]
+fn categories_are_funded_from_and_returned_to_treasury() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            SecurityBounty::create_category(
+                RuntimeOrigin::signed(REPORTER),
+                b"runtime".to_vec(),
+                500,
+                2_000
+            ),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        open_category();
+        assert_eq!(Balances::free_balance(TREASURY), 8_000);
+        assert_eq!(
+            Balances::free_balance(SecurityBounty::category_account(0)),
+            2_000
+        );
+
+        commit();
+        assert_noop!(
+            SecurityBounty::close_category(RuntimeOrigin::root(), 0),
+            Error::<Test>::CategoryHasOpenReports
+        );
+
+        // Unrevealed past the reveal period: anyone expires it.
+        assert_noop!(
+            SecurityBounty::expire_report(RuntimeOrigin::signed(FRESH), 0),
+            Error::<Test>::RevealPeriodOpen
+        );
+        System::set_block_number(12);
+        assert_ok!(SecurityBounty::expire_report(
+            RuntimeOrigin::signed(FRESH),
+            0
+        ));
+        assert_eq!(Balances::reserved_balance(REPORTER), 0);
+        assert_eq!(Balances::free_balance(TREASURY), 8_050);
+
+        assert_ok!(SecurityBounty::close_category(RuntimeOrigin::root(), 0));
+        assert_eq!(Balances::free_balance(TREASURY), 10_050);
+        assert!(Categories::<Test>::get(0).is_none());
+    });
+}
+
+#[test]
+fn revealed_report_is_paid_to_fresh_account_on_committee_approval() {
+    new_test_ext().execute_with(|| {
+        open_category();
+        commit();
+
+        assert_noop!(
+            SecurityBounty::reveal_report(
+                RuntimeOrigin::signed(REVEALER),
+                0,
+                report_hash(),
+                [0u8; 32],
+                KEYED_MEMBER,
+                Some(FRESH),
+            ),
+            Error::<Test>::CommitmentMismatch
+        );
+        // Only the committed reveal key can open the commitment.
+        assert_noop!(
+            SecurityBounty::reveal_report(
+                RuntimeOrigin::signed(REPORTER),
+                0,
+                report_hash(),
+                SALT,
+                KEYED_MEMBER,
+                Some(FRESH),
+            ),
+            Error::<Test>::CommitmentMismatch
+        );
+        assert_noop!(
+            SecurityBounty::reveal_report(
+                RuntimeOrigin::signed(REVEALER),
+                0,
+                report_hash(),
+                SALT,
+                FRESH,
+                Some(FRESH),
+            ),
+            Error::<Test>::NotCommitteeMember
+        );
+        assert_ok!(SecurityBounty::reveal_report(
+            RuntimeOrigin::signed(REVEALER),
+            0,
+            report_hash(),
+            SALT,
+            KEYED_MEMBER,
+            Some(FRESH),
+        ));
+        let reveal = Reports::<Test>::get(0).unwrap().reveal.unwrap();
+        assert_eq!(reveal.recipient_key, Some(KEY_FINGERPRINT));
+
+        assert_noop!(
+            SecurityBounty::propose_award(RuntimeOrigin::signed(REPORTER), 0, 300),
+            Error::<Test>::NotCommitteeMember
+        );
+        assert_noop!(
+            SecurityBounty::propose_award(RuntimeOrigin::signed(10), 0, 501),
+            Error::<Test>::AwardTooHigh
+        );
+        assert_ok!(SecurityBounty::propose_award(
+            RuntimeOrigin::signed(10),
+            0,
+            300
+        ));
+        assert_noop!(
+            SecurityBounty::vote_on_report(RuntimeOrigin::signed(10), 0, true),
+            Error::<Test>::AlreadyVoted
+        );
+        assert_ok!(SecurityBounty::vote_on_report(
+            RuntimeOrigin::signed(11),
+            0,
+            true
+        ));
+
+        System::assert_last_event(
+            Event::ReportAwarded {
+                report_id: 0,
+                payee: FRESH,
+                amount: 300,
+            }
+            .into(),
+        );
+        assert_eq!(Balances::free_balance(FRESH), 300);
+        assert_eq!(Balances::free_balance(REPORTER), 1_000);
+        assert!(Reports::<Test>::get(0).is_none());
+        assert_eq!(Categories::<Test>::get(0).unwrap().open_reports, 0);
+    });
+}
+
+#[test]
+fn committee_rejection_forfeits_deposit() {
+    new_test_ext().execute_with(|| {
+        open_category();
+        commit();
+        assert_ok!(SecurityBounty::reveal_report(
+            RuntimeOrigin::signed(REVEALER),
+            0,
+            report_hash(),
+            SALT,
+            11,
+            None,
+        ));
+        assert_eq!(
+            Reports::<Test>::get(0)
+                .unwrap()
+                .reveal
+                .unwrap()
+                .recipient_key,
+            None
+        );
+
+        assert_noop!(
+            SecurityBounty::vote_on_report(RuntimeOrigin::signed(11), 0, true),
+            Error::<Test>::NoAwardProposed
+        );
+        assert_ok!(SecurityBounty::vote_on_report(
+            RuntimeOrigin::signed(11),
+            0,
+            false
+        ));
+        assert_ok!(SecurityBounty::vote_on_report(
+            RuntimeOrigin::signed(12),
+            0,
+            false
+        ));
+
+        System::assert_last_event(Event::ReportRejected { report_id: 0 }.into());
+        assert_eq!(Balances::free_balance(REPORTER), 950);
+        assert_eq!(Balances::free_balance(TREASURY), 8_050);
+    });
+}
+
+#[test]
+fn re_proposing_an_award_keeps_rejections() {
+    new_test_ext().execute_with(|| {
+        open_category();
+        commit();
+        assert_ok!(SecurityBounty::reveal_report(
+            RuntimeOrigin::signed(REVEALER),
+            0,
+            report_hash(),
+            SALT,
+            KEYED_MEMBER,
+            None,
+        ));
+
+        assert_ok!(SecurityBounty::propose_award(
+            RuntimeOrigin::signed(10),
+            0,
+            300
+        ));
+        assert_ok!(SecurityBounty::vote_on_report(
+            RuntimeOrigin::signed(11),
+            0,
+            false
+        ));
+        // A rejecting member cannot wipe its own vote by proposing.
+        assert_noop!(
+            SecurityBounty::propose_award(RuntimeOrigin::signed(11), 0, 100),
+            Error::<Test>::AlreadyVoted
+        );
+
+        // A new amount drops 10's approval of 300 but keeps 11's rejection.
+        assert_ok!(SecurityBounty::propose_award(
+            RuntimeOrigin::signed(12),
+            0,
+            200
+        ));
+        let report = Reports::<Test>::get(0).unwrap();
+        assert_eq!(report.award, Some(200));
+        assert_eq!(report.votes.to_vec(), vec![(11, false), (12, true)]);
+
+        assert_ok!(SecurityBounty::vote_on_report(
+            RuntimeOrigin::signed(10),
+            0,
+            true
+        ));
+        System::assert_last_event(
+            Event::ReportAwarded {
+                report_id: 0,
+                payee: REVEALER,
+                amount: 200,
+            }
+            .into(),
+        );
+    });
+}
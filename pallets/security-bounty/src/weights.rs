@@ -0,0 +1,49 @@
+//! Weight definitions for pallet-security-bounty.
+//!
+//! These are placeholder weights. Real weights should be generated by running
+//! the Substrate frame benchmarking suite against this pallet.
+
+use frame_support::weights::Weight;
+
+/// Weight functions needed for `pallet_security_bounty`.
+pub trait WeightInfo {
+    fn create_category() -> Weight;
+    fn close_category() -> Weight;
+    fn submit_report() -> Weight;
+    fn reveal_report() -> Weight;
+    fn propose_award() -> Weight;
+    fn vote_on_report() -> Weight;
+    fn expire_report() -> Weight;
+}
+
+/// Default weights used in unit tests and when no benchmarked weights are
+/// available.
+impl WeightInfo for () {
+    fn create_category() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+
+    fn close_category() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+
+    fn submit_report() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+
+    fn reveal_report() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+
+    fn propose_award() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+
+    fn vote_on_report() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+
+    fn expire_report() -> Weight {
+        Weight::from_parts(10_000, 0)
+    }
+}
@@ -0,0 +1,235 @@
+//! Provider bonds and SLA enforcement.
+//!
+//! Every new listing reserves `ProviderBondAmount` from its provider. Each
+//! missed SLA slashes `SlaSlashPercent` of what is left of the bond: an
+//! invocation that expires past its deadline, or one the invoker cancels
+//! after the listing's `sla_response_blocks` went unanswered. A dispute lost
+//! by the provider is slashed the same way. `SlashInvokerShare` of each
+//! slash goes to the invoker and the rest to `SlashTreasury`. Once a listing
+//! is delisted and has no open invocations, `withdraw_provider_bond` returns
+//! the remainder.
+//!
+//! An approved listing applicant is provisional: while its first
+//! `ProvisionalInvocations` invocations are outstanding, every missed
+//! deadline also costs it `ProvisionalSlaPenalty` of the application bond
+//! (paid to the invoker) plus an `SlaViolation` reputation hit. The bond is
+//! returned once that many invocations are fully approved.
+
+use crate::pallet::{
+    BalanceOf, Config, Error, Event, InvocationExpiryCursor, InvocationId, InvocationStatus,
+    InvocationsByDeadline, ListingApplications, ListingId, Pallet, ProviderBonds,
+    ProvisionalProvider, ProvisionalProviders, ServiceInvocations,
+};
+use clawchain_primitives::hook_budget::drain_due;
+use frame_support::{
+    pallet_prelude::*,
+    traits::{BalanceStatus, Currency, ExistenceRequirement, ReservableCurrency},
+};
+use frame_system::pallet_prelude::*;
+use pallet_reputation::ReputationManager;
+use sp_runtime::traits::{One, Saturating, Zero};
+
+impl<T: Config> Pallet<T> {
+    /// Approve (start probation) or reject (return the bond) an application.
+    pub(crate) fn conclude_application(applicant: T::AccountId, approve: bool) -> DispatchResult {
+        let application =
+            ListingApplications::<T>::take(&applicant).ok_or(Error::<T>::ApplicationNotFound)?;
+
+        if approve {
+            ProvisionalProviders::<T>::insert(
+                &applicant,
+                ProvisionalProvider {
+                    bond: application.bond,
+                    remaining_invocations: T::ProvisionalInvocations::get(),
+                },
+            );
+            Self::deposit_event(Event::ListingApplicationApproved { applicant });
+        } else {
+            T::Currency::unreserve(&applicant, application.bond);
+            Self::deposit_event(Event::ListingApplicationRejected { applicant });
+        }
+        Ok(())
+    }
+
+    /// Count a fully approved invocation towards a provisional provider's
+    /// probation, returning its bond once probation is complete.
+    pub(crate) fn record_provisional_success(provider: &T::AccountId) {
+        let Some(mut status) = ProvisionalProviders::<T>::get(provider) else {
+            return;
+        };
+        status.remaining_invocations = status.remaining_invocations.saturating_sub(1);
+        if status.remaining_invocations > 0 {
+            ProvisionalProviders::<T>::insert(provider, status);
+            return;
+        }
+
+        ProvisionalProviders::<T>::remove(provider);
+        T::Currency::unreserve(provider, status.bond);
+        Self::deposit_event(Event::ProviderGraduated {
+            provider: provider.clone(),
+            bond_returned: status.bond,
+        });
+    }
+
+    /// Stricter SLA consequence for provisional providers: a missed
+    /// deadline pays `ProvisionalSlaPenalty` of the bond to the invoker
+    /// and counts as an `SlaViolation` for reputation.
+    pub(crate) fn penalize_provisional_sla_miss(
+        provider: &T::AccountId,
+        invoker: &T::AccountId,
+        invocation_id: InvocationId,
+    ) {
+        ProvisionalProviders::<T>::mutate(provider, |maybe| {
+            let Some(status) = maybe else {
+                return;
+            };
+            let penalty = T::ProvisionalSlaPenalty::get().min(status.bond);
+            let unpaid =
+                T::Currency::repatriate_reserved(provider, invoker, penalty, BalanceStatus::Free)
+                    .unwrap_or(penalty);
+            let amount = penalty.saturating_sub(unpaid);
+            status.bond = status.bond.saturating_sub(amount);
+
+            T::ReputationManager::on_reputation_event(
+                provider,
+                pallet_reputation::ReputationSource::SlaViolation,
+            );
+            Self::deposit_event(Event::ProvisionalSlaPenalized {
+                provider: provider.clone(),
+                invocation_id,
+                amount,
+            });
+        });
+    }
+
+    /// Slash `SlaSlashPercent` of a listing's remaining provider bond,
+    /// paying `SlashInvokerShare` of it to `invoker` and the rest to
+    /// `SlashTreasury`. Best-effort: anything that cannot be moved stays
+    /// reserved.
+    pub(crate) fn slash_provider_bond(
+        listing_id: ListingId,
+        provider: &T::AccountId,
+        invoker: &T::AccountId,
+        invocation_id: InvocationId,
+    ) {
+        ProviderBonds::<T>::mutate(listing_id, |maybe| {
+            let Some(bond) = maybe else {
+                return;
+            };
+            let slash = Self::percent_of(*bond, T::SlaSlashPercent::get().min(100) as u128);
+            let invoker_share = T::SlashInvokerShare::get().mul_floor(slash);
+            let treasury_share = slash.saturating_sub(invoker_share);
+
+            let repatriate = |to: &T::AccountId, amount: BalanceOf<T>| {
+                let unpaid =
+                    T::Currency::repatriate_reserved(provider, to, amount, BalanceStatus::Free)
+                        .unwrap_or(amount);
+                amount.saturating_sub(unpaid)
+            };
+            let to_invoker = repatriate(invoker, invoker_share);
+            let to_treasury = repatriate(&T::SlashTreasury::get(), treasury_share);
+            *bond = bond.saturating_sub(to_invoker.saturating_add(to_treasury));
+
+            Self::deposit_event(Event::ProviderBondSlashed {
+                listing_id,
+                invocation_id,
+                to_invoker,
+                to_treasury,
+            });
+        });
+    }
+
+    /// Bounty for expiring an invocation with `remaining` left in escrow:
+    /// `ExpireBountyShare` of it, clamped to `MinExpireBounty` and
+    /// `MaxExpireBounty`, and never more than `remaining`.
+    pub fn expire_bounty(remaining: BalanceOf<T>) -> BalanceOf<T> {
+        T::ExpireBountyShare::get()
+            .mul_floor(remaining)
+            .max(T::MinExpireBounty::get())
+            .min(T::MaxExpireBounty::get())
+            .min(remaining)
+    }
+
+    /// Pay out the escrow of an invocation being expired: the expiry
+    /// bounty to `bounty_to`, the rest to the invoker. Shared by
+    /// `try_expire_invocation` and the per-block sweep.
+    pub(crate) fn settle_expired_escrow(
+        invocation_id: InvocationId,
+        invoker: &T::AccountId,
+        bounty_to: &T::AccountId,
+    ) {
+        let escrow = Self::invocation_escrow_account(invocation_id);
+        let existence = Self::escrow_existence_requirement(invocation_id);
+        let mut remaining = T::Currency::free_balance(&escrow);
+        if existence == ExistenceRequirement::KeepAlive {
+            remaining = remaining.saturating_sub(T::Currency::minimum_balance());
+        }
+
+        let bounty = Self::expire_bounty(remaining);
+        if !bounty.is_zero() {
+            T::Currency::transfer(&escrow, bounty_to, bounty, existence).ok();
+        }
+        let refund = remaining.saturating_sub(bounty);
+        if !refund.is_zero() {
+            T::Currency::transfer(&escrow, invoker, refund, existence).ok();
+        }
+    }
+
+    /// Process invocations whose deadline passed before `n`, at most
+    /// `max` items, walking deadline blocks from `InvocationExpiryCursor`.
+    ///
+    /// Refunds invokers and marks invocations `Expired`. The expiry
+    /// bounty is charged exactly as in `try_expire_invocation` and goes
+    /// to `EcosystemFund`, so invokers get the same refund whichever
+    /// path expires them.
+    /// Returns the number of items spent.
+    pub fn expire_overdue_invocations(n: BlockNumberFor<T>, max: u32) -> u32 {
+        let mut cursor = InvocationExpiryCursor::<T>::get();
+        let spent = drain_due(
+            &mut cursor,
+            n.saturating_sub(One::one()),
+            max,
+            |deadline, count| {
+                InvocationsByDeadline::<T>::iter_key_prefix(deadline)
+                    .take(count)
+                    .collect()
+            },
+            |deadline, invocation_id| {
+                ServiceInvocations::<T>::mutate(invocation_id, |maybe| {
+                    if let Some(inv) = maybe {
+                        if matches!(
+                            inv.status,
+                            InvocationStatus::Pending
+                                | InvocationStatus::Accepted
+                                | InvocationStatus::InProgress
+                        ) {
+                            inv.status = InvocationStatus::Expired;
+                            Self::close_yield_position(invocation_id);
+                            Self::settle_expired_escrow(
+                                invocation_id,
+                                &inv.invoker,
+                                &T::EcosystemFund::get(),
+                            );
+
+                            Self::penalize_provisional_sla_miss(
+                                &inv.provider,
+                                &inv.invoker,
+                                invocation_id,
+                            );
+                            Self::slash_provider_bond(
+                                inv.listing_id,
+                                &inv.provider,
+                                &inv.invoker,
+                                invocation_id,
+                            );
+                        }
+                    }
+                });
+
+                InvocationsByDeadline::<T>::remove(deadline, invocation_id);
+            },
+        );
+        InvocationExpiryCursor::<T>::put(cursor);
+        spent
+    }
+}
@@ -0,0 +1,302 @@
+//! Listing discovery and the per-tag price index, served by the runtime API.
+//!
+//! `listings_by_tag` and `listings_by_provider` page through the listing
+//! indexes, and `search` returns active listings carrying every given tag
+//! whose provider meets `min_reputation` and whose minimum price is at most
+//! `max_price`. Pages hold at most `MAX_PAGE_SIZE` results.
+//!
+//! `top_listings` reads `TagRankings`, which orders each tag's listings by
+//! provider reputation times success rate. A listing is re-ranked only when
+//! one of its invocations completes; its score is then discounted by the
+//! blocks since, counting half after `RankingHalfLife`, so every client sees
+//! the same order for a given block.
+//!
+//! Every fully approved invocation adds its price to a per-tag summary for
+//! the current price era (`PriceEraLength` blocks) in `TagPriceHistory`:
+//! count, minimum, maximum and a histogram of `PRICE_BUCKETS` doubling
+//! buckets starting at `PriceBucketUnit`, from which a median is
+//! approximated. Only the last `MaxPriceEras` eras are kept per tag.
+
+use crate::{
+    pallet::{
+        BalanceOf, Config, ListingDisputeStats, ListingId, ListingSummaryOf, ListingsByProvider,
+        ListingsByTag, Pallet, RankEntry, ServiceListing, ServiceListings, TagPriceHistory,
+        TagPriceStats, TagRankings, PRICE_BUCKETS,
+    },
+    runtime_api,
+};
+use alloc::vec::Vec;
+use frame_support::pallet_prelude::*;
+use pallet_reputation::ReputationManager;
+use sp_runtime::traits::{SaturatedConversion, Saturating};
+
+impl<T: Config> Pallet<T> {
+    /// Listing details with its dispute counters, or `None` if the listing
+    /// does not exist.
+    pub fn listing_summary(listing_id: ListingId) -> Option<ListingSummaryOf<T>> {
+        let listing = ServiceListings::<T>::get(listing_id)?;
+        let disputes = ListingDisputeStats::<T>::get(listing_id);
+        let high_dispute = listing.total_invocations
+            >= T::MinInvocationsForDisputeFlag::get().max(1)
+            && u64::from(disputes.raised) * 100
+                >= u64::from(listing.total_invocations)
+                    * u64::from(T::HighDisputePercent::get().min(100));
+
+        Some(runtime_api::ListingSummary {
+            provider: listing.provider,
+            min_price: listing.min_price,
+            max_price: listing.max_price,
+            active: listing.active,
+            total_invocations: listing.total_invocations,
+            successful_invocations: listing.successful_invocations,
+            disputes,
+            high_dispute,
+        })
+    }
+
+    /// Summaries of the listings indexed under `tag` (active ones only if
+    /// `only_active`), `limit` at a time starting at `offset`.
+    pub fn listings_by_tag(
+        tag: &[u8],
+        only_active: bool,
+        offset: u32,
+        limit: u32,
+    ) -> Vec<(ListingId, ListingSummaryOf<T>)> {
+        let Ok(tag) = BoundedVec::<u8, T::MaxTagLength>::try_from(tag.to_vec()) else {
+            return Vec::new();
+        };
+        Self::page_summaries(
+            ListingsByTag::<T>::get(&tag).into_iter(),
+            |listing| !only_active || listing.active,
+            offset,
+            limit,
+        )
+    }
+
+    /// Summaries of `provider`'s listings, `limit` at a time starting at
+    /// `offset`.
+    pub fn listings_by_provider(
+        provider: &T::AccountId,
+        offset: u32,
+        limit: u32,
+    ) -> Vec<(ListingId, ListingSummaryOf<T>)> {
+        Self::page_summaries(
+            ListingsByProvider::<T>::get(provider).into_iter(),
+            |_| true,
+            offset,
+            limit,
+        )
+    }
+
+    /// Active listings carrying all of `tags`, from providers meeting
+    /// `min_reputation`, with a minimum price of at most `max_price`.
+    /// Without tags every listing is scanned.
+    pub fn search(
+        tags: &[Vec<u8>],
+        min_reputation: Option<u32>,
+        max_price: Option<BalanceOf<T>>,
+        offset: u32,
+        limit: u32,
+    ) -> Vec<(ListingId, ListingSummaryOf<T>)> {
+        let matches = |listing: &ServiceListing<T>| {
+            listing.active
+                && tags
+                    .iter()
+                    .all(|tag| listing.tags.iter().any(|t| t.as_slice() == tag.as_slice()))
+                && max_price.is_none_or(|max| listing.min_price <= max)
+                && min_reputation.is_none_or(|min| {
+                    T::ReputationManager::meets_minimum_reputation(&listing.provider, min)
+                })
+        };
+
+        match tags.first() {
+            Some(first) => {
+                let Ok(first) = BoundedVec::<u8, T::MaxTagLength>::try_from(first.clone()) else {
+                    return Vec::new();
+                };
+                Self::page_summaries(
+                    ListingsByTag::<T>::get(&first).into_iter(),
+                    matches,
+                    offset,
+                    limit,
+                )
+            }
+            None => Self::page_summaries(ServiceListings::<T>::iter_keys(), matches, offset, limit),
+        }
+    }
+
+    /// One page of summaries of the listings in `ids` that pass `filter`.
+    pub(crate) fn page_summaries(
+        ids: impl Iterator<Item = ListingId>,
+        filter: impl Fn(&ServiceListing<T>) -> bool,
+        offset: u32,
+        limit: u32,
+    ) -> Vec<(ListingId, ListingSummaryOf<T>)> {
+        ids.filter(|&id| ServiceListings::<T>::get(id).is_some_and(|l| filter(&l)))
+            .skip(offset as usize)
+            .take(limit.min(runtime_api::MAX_PAGE_SIZE) as usize)
+            .filter_map(|id| Some((id, Self::listing_summary(id)?)))
+            .collect()
+    }
+
+    /// Re-rank `listing` under each of its tags after a completed
+    /// invocation.
+    pub(crate) fn update_rankings(listing: &ServiceListing<T>) {
+        let reputation = u64::from(T::ReputationManager::get_reputation(&listing.provider));
+        let success_per_mille = u64::from(listing.successful_invocations).saturating_mul(1_000)
+            / u64::from(listing.total_invocations.max(1));
+        let score = reputation.saturating_mul(success_per_mille.min(1_000));
+        let now = <frame_system::Pallet<T>>::block_number();
+
+        for tag in &listing.tags {
+            TagRankings::<T>::mutate(tag, |entries| {
+                entries.retain(|entry| entry.listing_id != listing.id);
+                let at = entries.partition_point(|entry| entry.score >= score);
+                // Bounded by the same limit as ListingsByTag.
+                let _ = entries.try_insert(
+                    at,
+                    RankEntry {
+                        listing_id: listing.id,
+                        score,
+                        completed_at: now,
+                    },
+                );
+            });
+        }
+    }
+
+    /// Up to `n` active listings under `tag`, best first.
+    ///
+    /// A listing's stored score is weighted by recency when read:
+    /// `RankingHalfLife` blocks after its last completed invocation it
+    /// counts half, after twice that a third, and so on. Listings that
+    /// have never completed an invocation are not ranked.
+    pub fn top_listings(tag: &[u8], n: u32) -> Vec<(ListingId, ListingSummaryOf<T>)> {
+        let Ok(tag) = BoundedVec::<u8, T::MaxTagLength>::try_from(tag.to_vec()) else {
+            return Vec::new();
+        };
+        let now = <frame_system::Pallet<T>>::block_number();
+        let half_life = u128::from(T::RankingHalfLife::get().max(1));
+
+        let mut ranked: Vec<(u128, ListingId)> = TagRankings::<T>::get(&tag)
+            .into_iter()
+            .map(|entry| {
+                let age: u128 = now.saturating_sub(entry.completed_at).saturated_into();
+                let weighted =
+                    u128::from(entry.score).saturating_mul(half_life) / (half_life + age);
+                (weighted, entry.listing_id)
+            })
+            .collect();
+        // Stable, so equal scores keep their stored order.
+        ranked.sort_by_key(|&(score, _)| core::cmp::Reverse(score));
+
+        Self::page_summaries(
+            ranked.into_iter().map(|(_, id)| id),
+            |listing| listing.active,
+            0,
+            n,
+        )
+    }
+
+    /// Current price-index era.
+    pub fn current_price_era() -> u32 {
+        let now: u32 = <frame_system::Pallet<T>>::block_number().saturated_into();
+        now / T::PriceEraLength::get().max(1)
+    }
+
+    /// Histogram bucket `price` falls in.
+    pub(crate) fn price_bucket(price: BalanceOf<T>) -> usize {
+        let units = price.saturated_into::<u128>()
+            / T::PriceBucketUnit::get().saturated_into::<u128>().max(1);
+        ((u128::BITS - units.leading_zeros()) as usize).min(PRICE_BUCKETS - 1)
+    }
+
+    /// Add a settled `price` to the current era of each of `tags`,
+    /// dropping the era that falls out of the `MaxPriceEras` window.
+    pub(crate) fn record_settled_price(
+        tags: &[BoundedVec<u8, T::MaxTagLength>],
+        price: BalanceOf<T>,
+    ) {
+        let era = Self::current_price_era();
+        let bucket = Self::price_bucket(price);
+        for tag in tags {
+            TagPriceHistory::<T>::mutate(tag, era, |maybe| {
+                let stats = maybe.get_or_insert_with(|| TagPriceStats {
+                    min: price,
+                    max: price,
+                    ..Default::default()
+                });
+                stats.count = stats.count.saturating_add(1);
+                stats.min = stats.min.min(price);
+                stats.max = stats.max.max(price);
+                stats.buckets[bucket] = stats.buckets[bucket].saturating_add(1);
+            });
+            if let Some(expired) = era.checked_sub(T::MaxPriceEras::get()) {
+                TagPriceHistory::<T>::remove(tag, expired);
+            }
+        }
+    }
+
+    /// Settled prices of `tag` over the last `last_n_eras` price eras,
+    /// including the current one. `None` if nothing settled.
+    pub fn price_stats(
+        tag: &[u8],
+        last_n_eras: u32,
+    ) -> Option<runtime_api::PriceStats<BalanceOf<T>>> {
+        let tag: BoundedVec<u8, T::MaxTagLength> = tag.to_vec().try_into().ok()?;
+        let current = Self::current_price_era();
+        let eras = last_n_eras.clamp(1, T::MaxPriceEras::get().max(1));
+
+        let mut merged: Option<TagPriceStats<BalanceOf<T>>> = None;
+        for era in current.saturating_sub(eras - 1)..=current {
+            let Some(stats) = TagPriceHistory::<T>::get(&tag, era) else {
+                continue;
+            };
+            merged = Some(match merged {
+                None => stats,
+                Some(mut acc) => {
+                    acc.count = acc.count.saturating_add(stats.count);
+                    acc.min = acc.min.min(stats.min);
+                    acc.max = acc.max.max(stats.max);
+                    for (total, n) in acc.buckets.iter_mut().zip(stats.buckets) {
+                        *total = total.saturating_add(n);
+                    }
+                    acc
+                }
+            });
+        }
+
+        let stats = merged?;
+        Some(runtime_api::PriceStats {
+            count: stats.count,
+            min: stats.min,
+            median: Self::approximate_median(&stats),
+            max: stats.max,
+        })
+    }
+
+    /// Midpoint of the bucket holding the median sample, clamped to the
+    /// observed range.
+    pub(crate) fn approximate_median(stats: &TagPriceStats<BalanceOf<T>>) -> BalanceOf<T> {
+        let unit = T::PriceBucketUnit::get().saturated_into::<u128>();
+        let rank = stats.count.div_ceil(2);
+        let mut seen = 0u32;
+        let bucket = stats
+            .buckets
+            .iter()
+            .position(|n| {
+                seen = seen.saturating_add(*n);
+                seen >= rank
+            })
+            .unwrap_or(PRICE_BUCKETS - 1);
+
+        let midpoint = match bucket {
+            0 => unit / 2,
+            b if b == PRICE_BUCKETS - 1 => unit.saturating_mul(1 << (b - 1)),
+            b => unit.saturating_mul(3 << (b - 1)) / 2,
+        };
+        midpoint
+            .saturated_into::<BalanceOf<T>>()
+            .clamp(stats.min, stats.max)
+    }
+}
@@ -0,0 +1,446 @@
+//! Dispute settlement, default judgment and arbitration.
+//!
+//! ## Dispute Tracks
+//!
+//! A dispute's tier is fixed when it is raised from the invocation price:
+//! prices at or above `PriorityDisputeThreshold` go on `PriorityDisputeTrack`,
+//! everything else on `StandardDisputeTrack`. A track sets the evidence
+//! window and the juror panel size, stake and reward for the dispute.
+//!
+//! ## Default Judgment
+//!
+//! The party that did not raise a dispute must respond, by submitting
+//! evidence, within `DisputeResponseWindow` blocks. Otherwise the per-block
+//! sweep resolves the dispute for the raiser and emits `DisputeDefaultJudgment`.
+//! Governance can `escalate_dispute` before then to decide it itself.
+//!
+//! ## Arbitration
+//!
+//! Agents meeting `MinArbiterReputation` join the arbiter pool by reserving a
+//! stake. Either party may `escalate_to_arbitration` an open dispute, paying
+//! the track's `juror_reward` per seat. `juror_panel_size` arbiters staking
+//! at least the track's `juror_stake`, other than the parties, are drawn by
+//! stake-weighted hashing of the dispute id and parent block hash. The first
+//! winner to gather a majority of the panel within `ArbitrationVotingPeriod`
+//! blocks settles the dispute, and the arbiters who voted for it are paid;
+//! the rest of the fee is refunded. A panel that misses its deadline leaves
+//! the dispute `Escalated` with the fee refunded, and
+//! `resolve_dispute_governance` can override a panel at any time.
+//!
+//! Neither path has to be all-or-nothing. Governance may pass a
+//! `provider_share` to split the unreleased escrow, and arbiters may propose
+//! one with their vote; the median proposal among the majority is applied.
+//! The named winner still takes the reputation outcome.
+//!
+//! ## Consequences
+//!
+//! A provider losing `DisputeLossThreshold` governance-resolved disputes within
+//! `DisputeLossWindow` blocks is suspended: all of its active listings are
+//! paused and it cannot create new ones until it reserves `ReactivationBond`
+//! or governance reinstates it. `ListingDisputeStats` counts, per listing,
+//! disputes raised and disputes lost by each side.
+
+use crate::pallet::{
+    ArbiterPool, Arbiters, ArbitrationCursor, ArbitrationPanels, ArbitrationsByDeadline, BalanceOf,
+    Config, DefaultJudgmentCursor, DefaultJudgmentDeadlines, DefaultJudgmentsByDeadline, DisputeId,
+    DisputeStatus, DisputeTier, DisputeTrack, Disputes, Error, Event, InvocationId,
+    InvocationStatus, ListingDisputeStats, ListingId, ListingsByProvider, Pallet,
+    ProviderDisputeLosses, ServiceInvocations, ServiceListings, SuspendedProviders,
+};
+use alloc::vec::Vec;
+use clawchain_primitives::hook_budget::drain_due;
+use frame_support::{
+    pallet_prelude::*,
+    traits::{Currency, ExistenceRequirement},
+};
+use frame_system::pallet_prelude::*;
+use pallet_reputation::ReputationManager;
+use sp_runtime::{
+    traits::{AccountIdConversion, One, SaturatedConversion, Saturating, Zero},
+    Perbill,
+};
+
+impl<T: Config> Pallet<T> {
+    /// Dispute tier for an invocation of the given price.
+    pub fn dispute_tier(price: BalanceOf<T>) -> DisputeTier {
+        if price >= T::PriorityDisputeThreshold::get() {
+            DisputeTier::Priority
+        } else {
+            DisputeTier::Standard
+        }
+    }
+
+    /// Resolution parameters for a dispute tier.
+    pub fn dispute_track(tier: DisputeTier) -> DisputeTrack<BalanceOf<T>> {
+        match tier {
+            DisputeTier::Standard => T::StandardDisputeTrack::get(),
+            DisputeTier::Priority => T::PriorityDisputeTrack::get(),
+        }
+    }
+
+    /// Count a dispute lost by `provider`, suspending it once the
+    /// threshold is reached within the current loss window.
+    pub(crate) fn record_provider_dispute_loss(provider: &T::AccountId) {
+        let threshold = T::DisputeLossThreshold::get();
+        if threshold == 0 || SuspendedProviders::<T>::contains_key(provider) {
+            return;
+        }
+
+        let now = <frame_system::Pallet<T>>::block_number();
+        let losses = ProviderDisputeLosses::<T>::mutate(provider, |(window_start, losses)| {
+            if now.saturating_sub(*window_start) >= T::DisputeLossWindow::get().into() {
+                *window_start = now;
+                *losses = 0;
+            }
+            *losses = losses.saturating_add(1);
+            *losses
+        });
+
+        if losses >= threshold {
+            Self::suspend_provider(provider);
+        }
+    }
+
+    /// Pause every active listing of `provider` and mark it suspended.
+    pub(crate) fn suspend_provider(provider: &T::AccountId) {
+        let mut paused: BoundedVec<ListingId, T::MaxListingsPerProvider> = BoundedVec::new();
+        for listing_id in ListingsByProvider::<T>::get(provider) {
+            ServiceListings::<T>::mutate(listing_id, |maybe| {
+                if let Some(listing) = maybe {
+                    if listing.active {
+                        listing.active = false;
+                        // Bounded by the same limit as ListingsByProvider.
+                        let _ = paused.try_push(listing_id);
+                    }
+                }
+            });
+        }
+
+        let paused_listings = paused.len() as u32;
+        SuspendedProviders::<T>::insert(provider, paused);
+
+        Self::deposit_event(Event::ProviderSuspended {
+            provider: provider.clone(),
+            paused_listings,
+        });
+    }
+
+    /// Lift a suspension, reactivating the listings it paused.
+    pub(crate) fn reactivate_provider(provider: &T::AccountId, bond: Option<BalanceOf<T>>) {
+        for listing_id in SuspendedProviders::<T>::take(provider).unwrap_or_default() {
+            ServiceListings::<T>::mutate(listing_id, |maybe| {
+                if let Some(listing) = maybe {
+                    listing.active = true;
+                }
+            });
+        }
+        ProviderDisputeLosses::<T>::remove(provider);
+
+        Self::deposit_event(Event::ProviderReactivated {
+            provider: provider.clone(),
+            bond,
+        });
+    }
+
+    /// Sub-account holding a dispute's arbitration fee.
+    pub fn arbitration_fee_account(dispute_id: DisputeId) -> T::AccountId {
+        T::PalletId::get().into_sub_account_truncating((b"arbitrat", dispute_id))
+    }
+
+    /// Draw `size` distinct arbiters staking at least `min_stake`, other
+    /// than the dispute's parties, by stake-weighted hashing of the
+    /// dispute id and parent block hash.
+    pub(crate) fn draw_arbitration_panel(
+        dispute_id: DisputeId,
+        size: u32,
+        min_stake: BalanceOf<T>,
+        parties: &[T::AccountId],
+    ) -> Result<Vec<T::AccountId>, DispatchError> {
+        ensure!(
+            size > 0 && size <= T::MaxArbitrationPanel::get(),
+            Error::<T>::NotEnoughArbiters
+        );
+        let mut candidates: Vec<(T::AccountId, u128)> = ArbiterPool::<T>::get()
+            .into_iter()
+            .filter(|a| !parties.contains(a))
+            .filter_map(|a| {
+                let stake = Arbiters::<T>::get(&a)?.stake;
+                (stake >= min_stake).then(|| (a, stake.saturated_into::<u128>().max(1)))
+            })
+            .collect();
+        ensure!(
+            candidates.len() >= size as usize,
+            Error::<T>::NotEnoughArbiters
+        );
+
+        let parent = <frame_system::Pallet<T>>::parent_hash();
+        let mut panel = Vec::with_capacity(size as usize);
+        for round in 0..size {
+            let seed = sp_io::hashing::blake2_256(&(dispute_id, &parent, round).encode());
+            let mut point_bytes = [0u8; 16];
+            point_bytes.copy_from_slice(&seed[..16]);
+            let total = candidates
+                .iter()
+                .fold(0u128, |total, (_, stake)| total.saturating_add(*stake));
+            let mut target = u128::from_le_bytes(point_bytes) % total;
+            let index = candidates
+                .iter()
+                .position(|(_, stake)| {
+                    if target < *stake {
+                        true
+                    } else {
+                        target -= stake;
+                        false
+                    }
+                })
+                .unwrap_or(candidates.len() - 1);
+            panel.push(candidates.swap_remove(index).0);
+        }
+        Ok(panel)
+    }
+
+    /// Dissolve a dispute's arbitration panel, if it has one. With a
+    /// `winner`, each arbiter that voted for it is paid the track's
+    /// `juror_reward` from the fee; what is left of the fee goes back to
+    /// the party that paid it.
+    pub(crate) fn close_arbitration(dispute_id: DisputeId, winner: Option<&T::AccountId>) {
+        let Some(panel) = ArbitrationPanels::<T>::take(dispute_id) else {
+            return;
+        };
+        ArbitrationsByDeadline::<T>::remove(panel.voting_deadline, dispute_id);
+        let fee_account = Self::arbitration_fee_account(dispute_id);
+        let reward = Disputes::<T>::get(dispute_id)
+            .map(|d| Self::dispute_track(d.tier).juror_reward)
+            .unwrap_or_else(Zero::zero);
+
+        for (arbiter, vote) in &panel.arbiters {
+            Arbiters::<T>::mutate(arbiter, |info| {
+                if let Some(info) = info {
+                    info.active_panels = info.active_panels.saturating_sub(1);
+                }
+            });
+            let voted_for = vote.as_ref().map(|(w, _)| w);
+            if winner.is_some() && voted_for == winner && !reward.is_zero() {
+                // Best effort
+                T::Currency::transfer(
+                    &fee_account,
+                    arbiter,
+                    reward,
+                    Self::drain_existence_requirement(&fee_account),
+                )
+                .ok();
+            }
+        }
+
+        let rest = T::Currency::free_balance(&fee_account);
+        if !rest.is_zero() {
+            T::Currency::transfer(
+                &fee_account,
+                &panel.fee_payer,
+                rest,
+                Self::drain_existence_requirement(&fee_account),
+            )
+            .ok();
+        }
+    }
+
+    /// Hand disputes whose panel missed its voting deadline before `n`
+    /// to governance, refunding the fee, at most `max` items, walking
+    /// deadline blocks from `ArbitrationCursor`. Returns the number of
+    /// items spent.
+    pub fn expire_arbitrations(n: BlockNumberFor<T>, max: u32) -> u32 {
+        let mut cursor = ArbitrationCursor::<T>::get();
+        let spent = drain_due(
+            &mut cursor,
+            n.saturating_sub(One::one()),
+            max,
+            |deadline, count| {
+                ArbitrationsByDeadline::<T>::iter_key_prefix(deadline)
+                    .take(count)
+                    .collect()
+            },
+            Self::expire_arbitration,
+        );
+        ArbitrationCursor::<T>::put(cursor);
+        spent
+    }
+
+    /// Hand `dispute_id`, whose panel's voting deadline was `deadline`,
+    /// to governance.
+    pub(crate) fn expire_arbitration(deadline: BlockNumberFor<T>, dispute_id: DisputeId) {
+        ArbitrationsByDeadline::<T>::remove(deadline, dispute_id);
+        if !ArbitrationPanels::<T>::contains_key(dispute_id) {
+            return;
+        }
+        Self::close_arbitration(dispute_id, None);
+        Disputes::<T>::mutate(dispute_id, |maybe| {
+            if let Some(d) = maybe {
+                d.status = DisputeStatus::Escalated;
+            }
+        });
+        Self::deposit_event(Event::ArbitrationExpired { dispute_id });
+    }
+
+    /// Resolve `dispute_id` for `winner`: pay it the unreleased escrow,
+    /// update reputation and dispute statistics, and close the invocation.
+    /// Returns the amount paid.
+    pub(crate) fn settle_dispute(
+        dispute_id: DisputeId,
+        winner: &T::AccountId,
+        provider_share: Option<Perbill>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let invocation_id = Disputes::<T>::try_mutate(dispute_id, |maybe| {
+            let dispute = maybe.as_mut().ok_or(Error::<T>::DisputeNotFound)?;
+            ensure!(
+                matches!(
+                    dispute.status,
+                    DisputeStatus::Open | DisputeStatus::Escalated | DisputeStatus::Arbitration
+                ),
+                Error::<T>::DisputeNotOpen
+            );
+            dispute.status = DisputeStatus::Resolved;
+            dispute.winner = Some(winner.clone());
+            Ok::<InvocationId, DispatchError>(dispute.invocation_id)
+        })?;
+        Self::clear_default_judgment(dispute_id);
+
+        // Mark invocation resolved. Approved milestones are already paid
+        // out, so only the unreleased remainder is adjudicated.
+        let remaining = ServiceInvocations::<T>::mutate(invocation_id, |maybe| {
+            maybe.as_mut().map_or_else(Zero::zero, |inv| {
+                let remaining = inv.price.saturating_sub(inv.released);
+                inv.released = inv.price;
+                inv.status = InvocationStatus::FullyApproved;
+                remaining
+            })
+        });
+
+        // Transfer the remainder to the winner
+        Self::close_yield_position(invocation_id);
+        let escrow_account = Self::invocation_escrow_account(invocation_id);
+        let existence = Self::escrow_existence_requirement(invocation_id);
+        let mut available = T::Currency::free_balance(&escrow_account);
+        if existence == ExistenceRequirement::KeepAlive {
+            available = available.saturating_sub(T::Currency::minimum_balance());
+        }
+        let mut amount = remaining.min(available);
+        let inv = ServiceInvocations::<T>::get(invocation_id);
+        let payee = inv
+            .as_ref()
+            .map(|inv| Self::listing_payee(inv.listing_id, &inv.provider));
+        match (provider_share, &inv, &payee) {
+            (Some(share), Some(inv), Some(payee)) => {
+                // Best effort, as below
+                let to_provider = share.mul_floor(amount);
+                let to_invoker = amount.saturating_sub(to_provider);
+                for (who, part) in [(&inv.invoker, to_invoker), (payee, to_provider)] {
+                    if !part.is_zero() {
+                        T::Currency::transfer(&escrow_account, who, part, existence).ok();
+                    }
+                }
+                amount = if *winner == inv.provider {
+                    to_provider
+                } else {
+                    to_invoker
+                };
+                Self::deposit_event(Event::DisputeSplitSettled {
+                    dispute_id,
+                    to_provider,
+                    to_invoker,
+                });
+            }
+            _ => {
+                if !amount.is_zero() {
+                    let to = match (&inv, &payee) {
+                        (Some(inv), Some(payee)) if *winner == inv.provider => payee,
+                        _ => winner,
+                    };
+                    T::Currency::transfer(&escrow_account, to, amount, existence).ok();
+                    // Best effort
+                }
+            }
+        }
+
+        // Reputation update via dispute resolution
+        if let Some(inv) = inv {
+            let loser = if inv.invoker == *winner {
+                inv.provider.clone()
+            } else {
+                inv.invoker.clone()
+            };
+            T::ReputationManager::on_dispute_resolved(winner, &loser);
+            let provider_lost = loser == inv.provider;
+            ListingDisputeStats::<T>::mutate(inv.listing_id, |stats| {
+                if provider_lost {
+                    stats.lost_by_provider = stats.lost_by_provider.saturating_add(1);
+                } else {
+                    stats.lost_by_invoker = stats.lost_by_invoker.saturating_add(1);
+                }
+            });
+            if provider_lost {
+                Self::record_provider_dispute_loss(&loser);
+                Self::slash_provider_bond(
+                    inv.listing_id,
+                    &inv.provider,
+                    &inv.invoker,
+                    invocation_id,
+                );
+            }
+        }
+
+        Self::cleanup_invocation(invocation_id);
+        Ok(amount)
+    }
+
+    /// Stop the default-judgment clock of `dispute_id`, if it runs.
+    pub(crate) fn clear_default_judgment(dispute_id: DisputeId) {
+        if let Some(deadline) = DefaultJudgmentDeadlines::<T>::take(dispute_id) {
+            DefaultJudgmentsByDeadline::<T>::remove(deadline, dispute_id);
+        }
+    }
+
+    /// Resolve disputes whose counterparty missed its response deadline
+    /// before `n` for the raiser, at most `max` items, walking deadline
+    /// blocks from `DefaultJudgmentCursor`. Returns the number of items
+    /// spent.
+    pub fn apply_default_judgments(n: BlockNumberFor<T>, max: u32) -> u32 {
+        let mut cursor = DefaultJudgmentCursor::<T>::get();
+        let spent = drain_due(
+            &mut cursor,
+            n.saturating_sub(One::one()),
+            max,
+            |deadline, count| {
+                DefaultJudgmentsByDeadline::<T>::iter_key_prefix(deadline)
+                    .take(count)
+                    .collect()
+            },
+            Self::apply_default_judgment,
+        );
+        DefaultJudgmentCursor::<T>::put(cursor);
+        spent
+    }
+
+    /// Resolve `dispute_id`, whose response deadline was `deadline`, for
+    /// its raiser.
+    pub(crate) fn apply_default_judgment(deadline: BlockNumberFor<T>, dispute_id: DisputeId) {
+        DefaultJudgmentsByDeadline::<T>::remove(deadline, dispute_id);
+        if DefaultJudgmentDeadlines::<T>::get(dispute_id) != Some(deadline) {
+            return;
+        }
+        let raiser = Disputes::<T>::get(dispute_id)
+            .filter(|d| d.status == DisputeStatus::Open)
+            .map(|d| d.raised_by);
+        let settled = raiser.and_then(|raiser| {
+            let amount = Self::settle_dispute(dispute_id, &raiser, None).ok()?;
+            Some((raiser, amount))
+        });
+        match settled {
+            Some((winner, amount)) => Self::deposit_event(Event::DisputeDefaultJudgment {
+                dispute_id,
+                winner,
+                amount,
+            }),
+            None => DefaultJudgmentDeadlines::<T>::remove(dispute_id),
+        }
+    }
+}
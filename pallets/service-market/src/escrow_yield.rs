@@ -0,0 +1,103 @@
+//! Staking idle invocation escrow.
+//!
+//! An invoker may `stake_escrow` on an open invocation, moving what its
+//! escrow holds above the existential deposit into the runtime's
+//! [`EscrowYield`] backend. The position is closed before the escrow moves
+//! again — a milestone payout, refund, expiry, cancellation or dispute
+//! settlement — which frees the principal and splits the reward:
+//! `YieldInvokerShare` to the invoker and the rest to the provider's payee.
+//! If the backend returns less than was staked, `EscrowYieldShortfall` is
+//! emitted and every later payout from the escrow is scaled down pro rata,
+//! so settlement still goes through.
+
+use crate::pallet::{
+    BalanceOf, Config, EscrowRecoveryRates, Event, InvocationId, Pallet, ServiceInvocations,
+    YieldPositions,
+};
+use frame_support::{
+    pallet_prelude::*,
+    traits::{Currency, ExistenceRequirement},
+};
+use sp_runtime::{
+    traits::{Saturating, Zero},
+    Perbill,
+};
+
+/// Staking backend escrow can be parked in while an invocation runs.
+///
+/// `stake` locks `amount` of `position`'s free balance in a virtual staking
+/// position (e.g. under a hold delegated to a nomination pool). `unstake`
+/// must make the whole principal free again at once, credit any reward to
+/// `position` and return it. `()` offers no yield.
+pub trait EscrowYield<AccountId, Balance> {
+    fn stake(position: &AccountId, amount: Balance) -> sp_runtime::DispatchResult;
+    fn unstake(position: &AccountId) -> Balance;
+}
+
+impl<AccountId, Balance: Zero> EscrowYield<AccountId, Balance> for () {
+    fn stake(_: &AccountId, _: Balance) -> sp_runtime::DispatchResult {
+        Err(sp_runtime::DispatchError::Unavailable)
+    }
+    fn unstake(_: &AccountId) -> Balance {
+        Zero::zero()
+    }
+}
+
+impl<T: Config> Pallet<T> {
+    /// Close `invocation_id`'s yield position, if any, so its escrow is
+    /// free to move, and split the reward between invoker and provider.
+    pub(crate) fn close_yield_position(invocation_id: InvocationId) {
+        let Some(principal) = YieldPositions::<T>::take(invocation_id) else {
+            return;
+        };
+        let Some(inv) = ServiceInvocations::<T>::get(invocation_id) else {
+            return;
+        };
+        let escrow_account = Self::invocation_escrow_account(invocation_id);
+        let reward = T::EscrowYield::unstake(&escrow_account);
+
+        // The escrow held the principal and the existential deposit when it
+        // was staked; whatever is missing now was lost by the backend.
+        let staked = principal.saturating_add(T::Currency::minimum_balance());
+        let held = T::Currency::free_balance(&escrow_account).saturating_sub(reward);
+        if held < staked {
+            EscrowRecoveryRates::<T>::insert(invocation_id, Perbill::from_rational(held, staked));
+            Self::deposit_event(Event::EscrowYieldShortfall {
+                invocation_id,
+                principal,
+                shortfall: staked.saturating_sub(held),
+            });
+        }
+
+        let to_invoker = T::YieldInvokerShare::get().mul_floor(reward);
+        let to_provider = reward.saturating_sub(to_invoker);
+
+        // Best-effort: a share that cannot be paid stays in escrow and is
+        // swept with the rest of it.
+        let pay = |to: &T::AccountId, amount: BalanceOf<T>| {
+            if amount.is_zero() {
+                return Zero::zero();
+            }
+            T::Currency::transfer(&escrow_account, to, amount, ExistenceRequirement::KeepAlive)
+                .map_or(Zero::zero(), |_| amount)
+        };
+        let to_invoker = pay(&inv.invoker, to_invoker);
+        let to_provider = pay(
+            &Self::invocation_payee(invocation_id, &inv.provider),
+            to_provider,
+        );
+
+        Self::deposit_event(Event::EscrowYieldPaid {
+            invocation_id,
+            principal,
+            to_invoker,
+            to_provider,
+        });
+    }
+
+    /// `amount` scaled down to what `invocation_id`'s escrow got back from a
+    /// yield position that closed short; `amount` itself otherwise.
+    pub(crate) fn escrow_payout(invocation_id: InvocationId, amount: BalanceOf<T>) -> BalanceOf<T> {
+        EscrowRecoveryRates::<T>::get(invocation_id).map_or(amount, |rate| rate.mul_floor(amount))
+    }
+}
@@ -0,0 +1,92 @@
+//! Reaper guards for accounts that still carry obligations.
+
+use crate::pallet::{
+    Config, InvocationId, InvocationStatus, InvocationsByInvoker, Pallet, ProviderOpenInvocations,
+    ServiceInvocation, ServiceInvocations,
+};
+use frame_support::{pallet_prelude::*, traits::ExistenceRequirement, PalletId};
+use sp_runtime::traits::AccountIdConversion;
+
+/// Cross-pallet hook answering "does this account still carry obligations?".
+///
+/// Pallets that move funds out of accounts with `ExistenceRequirement::AllowDeath`
+/// consult this before doing so. A guarded account is only ever debited with
+/// `KeepAlive`, so it cannot be reaped while invocations, disputes, relayer
+/// duties or escrows still reference it.
+///
+/// Implemented for `()` (nothing guarded) and for tuples, so the runtime can
+/// compose guards from several pallets. Implementations must answer from a
+/// bounded number of reads.
+pub trait ProviderOfAccountGuards<AccountId> {
+    /// Returns `true` if `who` must not be reaped.
+    fn is_guarded(who: &AccountId) -> bool;
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(8)]
+impl<AccountId> ProviderOfAccountGuards<AccountId> for Tuple {
+    fn is_guarded(who: &AccountId) -> bool {
+        for_tuples!( #( if Tuple::is_guarded(who) { return true; } )* );
+        false
+    }
+}
+
+/// Guards escrow sub-accounts of open invocations, plus invokers and
+/// providers that are still party to an open (or disputed) invocation.
+///
+/// Reads at most the invoker's bounded invocation list and the
+/// provider's open-invocation counter.
+impl<T: Config> ProviderOfAccountGuards<T::AccountId> for Pallet<T> {
+    fn is_guarded(who: &T::AccountId) -> bool {
+        let is_open = |id: InvocationId| {
+            ServiceInvocations::<T>::get(id).is_some_and(|inv| Self::is_open_invocation(&inv))
+        };
+
+        if let Some((pallet_id, id)) = PalletId::try_from_sub_account::<InvocationId>(who) {
+            if pallet_id == T::PalletId::get() && is_open(id) {
+                return true;
+            }
+        }
+
+        ProviderOpenInvocations::<T>::get(who) > 0
+            || InvocationsByInvoker::<T>::get(who)
+                .iter()
+                .any(|&id| is_open(id))
+    }
+}
+
+impl<T: Config> Pallet<T> {
+    /// Whether an invocation still holds funds or obligations
+    /// (not yet fully approved, cancelled, expired or rejected).
+    pub(crate) fn is_open_invocation(inv: &ServiceInvocation<T>) -> bool {
+        !matches!(
+            inv.status,
+            InvocationStatus::FullyApproved
+                | InvocationStatus::Cancelled
+                | InvocationStatus::Expired
+                | InvocationStatus::Rejected
+        )
+    }
+
+    /// Existence requirement for debiting an invocation's escrow account:
+    /// `KeepAlive` while the invocation is still open or another pallet
+    /// guards the account, `AllowDeath` once it has settled.
+    pub fn escrow_existence_requirement(invocation_id: InvocationId) -> ExistenceRequirement {
+        let open = ServiceInvocations::<T>::get(invocation_id)
+            .is_some_and(|inv| Self::is_open_invocation(&inv));
+        if open || T::AccountGuards::is_guarded(&Self::invocation_escrow_account(invocation_id)) {
+            ExistenceRequirement::KeepAlive
+        } else {
+            ExistenceRequirement::AllowDeath
+        }
+    }
+
+    /// Existence requirement for emptying `source`: `AllowDeath` unless
+    /// this pallet or `T::AccountGuards` still guards it.
+    pub(crate) fn drain_existence_requirement(source: &T::AccountId) -> ExistenceRequirement {
+        if Self::is_guarded(source) || T::AccountGuards::is_guarded(source) {
+            ExistenceRequirement::KeepAlive
+        } else {
+            ExistenceRequirement::AllowDeath
+        }
+    }
+}
@@ -12,32 +12,84 @@
 //!   and milestone-based partial payment release.
 //! - **Reputation Gating**: Minimum reputation score required to list services; optional
 //!   per-listing gate for invokers.
-//! - **Dispute Resolution**: Either party can raise a dispute; governance resolves.
+//! - **Dispute Resolution**: Either party can raise a dispute; an arbiter panel or
+//!   governance resolves it.
 //!
-//! ## Extrinsics (Phase 1 — indices 10–27 where implemented)
+//! Each call documents its own rules. Feature areas with their own helpers
+//! live in submodules:
 //!
-//! - `list_service` (10) — Create a service listing
-//! - `update_listing` (11) — Update listing metadata
-//! - `delist_service` (12) — Deactivate a listing
-//! - `invoke_service` (13) — Invoke a service (escrow path)
-//! - `submit_invocation_work` (18) — Provider submits work proof
-//! - `approve_milestone` (19) — Invoker approves milestone, releases partial escrow
-//! - `raise_dispute` (20) — Either party raises a dispute
-//! - `resolve_dispute_governance` (23) — Governance resolves escalated dispute
-//! - `cancel_invocation` (26) — Invoker cancels pending invocation
-//! - `try_expire_invocation` (27) — Anyone triggers expiry after deadline
+//! - `bonds` — provider bonds, provisional providers and SLA expiry
+//! - `disputes` — dispute tracks, default judgment, arbitration and suspension
+//! - `escrow_yield` — staking idle escrow through [`EscrowYield`]
+//! - `guards` — [`ProviderOfAccountGuards`] and escrow existence requirements
+//! - `discovery` — listing search, rankings and the price index
+//! - `subscriptions` — recurring subscriptions
+//! - `settlement` — deferred settlement statements
+//!
+//! Deadline-driven work (expiries, default judgments, subscription payments,
+//! lapsed arbitration panels and due settlements) runs in the pallet's
+//! `BudgetedHook`, which the runtime registers with `pallet-hook-budget`.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::let_unit_value)]
 
 extern crate alloc;
 
+pub use escrow_yield::EscrowYield;
+pub use guards::ProviderOfAccountGuards;
 pub use pallet::*;
 
+mod bonds;
+mod discovery;
+mod disputes;
+mod escrow_yield;
+mod guards;
+pub mod runtime_api;
+mod settlement;
+mod subscriptions;
+
 #[cfg(test)]
 mod tests;
 
 use alloc::vec::Vec;
+use clawchain_primitives::{BudgetedHook, HookUsage};
+use frame_support::weights::Weight;
+
+/// Weight charged per item processed by a per-block sweep.
+pub const SWEEP_ITEM_WEIGHT: Weight = Weight::from_parts(10_000, 0);
+
+/// Exchange-rate feed for stable-denominated listings.
+///
+/// Returns the CLAW amount (in the smallest unit) per one reference unit,
+/// together with the block at which the rate was last updated. `()` reports
+/// no feed, so stable-priced invocations are rejected.
+pub trait PriceOracle<Balance, BlockNumber> {
+    fn rate() -> Option<(Balance, BlockNumber)>;
+}
+
+impl<Balance, BlockNumber> PriceOracle<Balance, BlockNumber> for () {
+    fn rate() -> Option<(Balance, BlockNumber)> {
+        None
+    }
+}
+
+/// Identifier of an insurance policy, as issued by the insurance pool.
+pub type InsurancePolicyId = u64;
+
+/// Insurance cover held by providers.
+///
+/// `active_policy` returns the policy in force that covers `provider` for at
+/// least `coverage`, if any. `()` reports no cover, so invocations that
+/// require insurance are rejected.
+pub trait InsurancePolicies<AccountId, Balance> {
+    fn active_policy(provider: &AccountId, coverage: Balance) -> Option<InsurancePolicyId>;
+}
+
+impl<AccountId, Balance> InsurancePolicies<AccountId, Balance> for () {
+    fn active_policy(_: &AccountId, _: Balance) -> Option<InsurancePolicyId> {
+        None
+    }
+}
 
 #[frame_support::pallet]
 #[allow(clippy::too_many_arguments)]
@@ -45,12 +97,19 @@ pub mod pallet {
     use super::*;
     use frame_support::{
         pallet_prelude::*,
-        traits::{Currency, ExistenceRequirement},
+        traits::{BalanceStatus, Currency, ExistenceRequirement, ReservableCurrency},
         PalletId,
     };
     use frame_system::pallet_prelude::*;
     use pallet_reputation::ReputationManager;
-    use sp_runtime::traits::AccountIdConversion;
+    use sp_runtime::{
+        helpers_128bit::multiply_by_rational_with_rounding,
+        traits::{
+            AccountIdConversion, CheckedMul, Hash as HashT, IntegerSquareRoot, SaturatedConversion,
+            Saturating, Zero,
+        },
+        Perbill, Rounding,
+    };
 
     // =========================================================
     // Type Aliases
@@ -59,15 +118,38 @@ pub mod pallet {
     pub type ListingId = u64;
     pub type InvocationId = u64;
     pub type DisputeId = u64;
+    pub type SubscriptionId = u64;
+    pub type TemplateId = u64;
+    pub type CompetitionId = u64;
+
+    /// ISO 3166-1 alpha-2 jurisdiction code (e.g. `*b"DE"`).
+    pub type JurisdictionCode = [u8; 2];
 
     pub type BalanceOf<T> =
         <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
+    /// Runtime API summary of a listing.
+    pub type ListingSummaryOf<T> =
+        runtime_api::ListingSummary<<T as frame_system::Config>::AccountId, BalanceOf<T>>;
+
+    /// An arbitration panel seat: the arbiter and, once cast, its vote for a
+    /// winner with an optional provider share of the escrow.
+    pub type ArbiterSeat<T> = (
+        <T as frame_system::Config>::AccountId,
+        Option<(<T as frame_system::Config>::AccountId, Option<Perbill>)>,
+    );
+
     // =========================================================
     // Enums
     // =========================================================
 
     /// Payment mode for a service listing.
+    ///
+    /// An X402 invocation locks no escrow: the invoker pays the provider
+    /// off-chain and records the proof with `register_x402_payment`, which
+    /// the provider needs before it can accept. Approvals and cancellations
+    /// then move no funds on-chain. Providers may require a minimum
+    /// reputation of X402 invokers, and X402 cannot be used for trials.
     #[derive(
         Clone,
         Encode,
@@ -91,6 +173,14 @@ pub mod pallet {
     }
 
     /// Status of a service invocation.
+    ///
+    /// `Pending` → `Accepted` (`accept_invocation`, within the listing's
+    /// `sla_response_blocks`) → `InProgress` (first progress report or work
+    /// submission) → `WorkSubmitted` (once no milestone is left
+    /// unsubmitted). `accepted_at` and `completed_at` record the first and
+    /// last of these transitions so SLA measurement covers the actual
+    /// working window. A provider may instead reject a pending invocation;
+    /// rejections do not count against the invoker's completion history.
     #[derive(
         Clone,
         Encode,
@@ -121,6 +211,8 @@ pub mod pallet {
         Cancelled,
         /// Deadline passed with no action.
         Expired,
+        /// Provider declined the invocation; escrow refunded.
+        Rejected,
     }
 
     /// Status of a milestone within an invocation.
@@ -162,6 +254,232 @@ pub mod pallet {
         Open,
         Resolved,
         Escalated,
+        /// Before an arbitration panel; see `ArbitrationPanels`.
+        Arbitration,
+    }
+
+    /// Value tier of a dispute, fixed when it is raised.
+    #[derive(
+        Clone,
+        Copy,
+        Encode,
+        Decode,
+        Eq,
+        PartialEq,
+        RuntimeDebug,
+        TypeInfo,
+        MaxEncodedLen,
+        Default,
+        codec::DecodeWithMemTracking,
+    )]
+    pub enum DisputeTier {
+        #[default]
+        Standard,
+        /// Invocation price at or above `PriorityDisputeThreshold`.
+        Priority,
+    }
+
+    /// Resolution parameters for a dispute tier.
+    #[derive(
+        Clone,
+        Encode,
+        Decode,
+        Eq,
+        PartialEq,
+        RuntimeDebug,
+        TypeInfo,
+        MaxEncodedLen,
+        Default,
+        codec::DecodeWithMemTracking,
+    )]
+    pub struct DisputeTrack<Balance> {
+        /// Blocks after the dispute is raised during which parties may add evidence.
+        pub evidence_window: u32,
+        /// Number of jurors drawn for the dispute.
+        pub juror_panel_size: u32,
+        /// Stake each juror must lock to sit on the panel.
+        pub juror_stake: Balance,
+        /// Reward paid to each juror voting with the outcome.
+        pub juror_reward: Balance,
+    }
+
+    /// Dispute counters for a listing.
+    #[derive(
+        Clone,
+        Encode,
+        Decode,
+        Eq,
+        PartialEq,
+        RuntimeDebug,
+        TypeInfo,
+        MaxEncodedLen,
+        Default,
+        codec::DecodeWithMemTracking,
+    )]
+    pub struct DisputeStats {
+        /// Disputes raised on the listing's invocations.
+        pub raised: u32,
+        /// Disputes resolved against the provider.
+        pub lost_by_provider: u32,
+        /// Disputes resolved against the invoker.
+        pub lost_by_invoker: u32,
+    }
+
+    /// An agent in the arbiter pool.
+    #[derive(
+        Clone,
+        Encode,
+        Decode,
+        Eq,
+        PartialEq,
+        RuntimeDebug,
+        TypeInfo,
+        MaxEncodedLen,
+        codec::DecodeWithMemTracking,
+    )]
+    pub struct ArbiterInfo<Balance> {
+        /// Reserved stake; weights the arbiter's chance of being drawn.
+        pub stake: Balance,
+        /// Arbitration panels the arbiter currently sits on.
+        pub active_panels: u32,
+    }
+
+    /// Limits a provider places on the work it accepts.
+    #[derive(
+        Clone,
+        Encode,
+        Decode,
+        Eq,
+        PartialEq,
+        RuntimeDebug,
+        TypeInfo,
+        MaxEncodedLen,
+        Default,
+        codec::DecodeWithMemTracking,
+    )]
+    pub struct ProviderCapacity<BlockNumber> {
+        /// Most open invocations across the provider's listings.
+        pub max_concurrent_invocations: Option<u32>,
+        /// The provider takes no new invocations before this block.
+        pub paused_until: Option<BlockNumber>,
+    }
+
+    /// A listing's offer to match-makers: `fee` of every milestone payout,
+    /// up to `cap` per invocation.
+    #[derive(
+        Clone,
+        Encode,
+        Decode,
+        Eq,
+        PartialEq,
+        RuntimeDebug,
+        TypeInfo,
+        MaxEncodedLen,
+        codec::DecodeWithMemTracking,
+    )]
+    pub struct ReferralTerms<Balance> {
+        pub fee: Perbill,
+        pub cap: Balance,
+    }
+
+    /// The referrer of an invocation and the terms it was referred under.
+    #[derive(
+        Clone,
+        Encode,
+        Decode,
+        Eq,
+        PartialEq,
+        RuntimeDebug,
+        TypeInfo,
+        MaxEncodedLen,
+        codec::DecodeWithMemTracking,
+    )]
+    pub struct Referral<AccountId, Balance> {
+        pub referrer: AccountId,
+        pub fee: Perbill,
+        /// Part of the cap not yet paid out.
+        pub remaining: Balance,
+    }
+
+    /// An off-chain X402 settlement recorded for an invocation.
+    #[derive(
+        Clone,
+        Encode,
+        Decode,
+        Eq,
+        PartialEq,
+        RuntimeDebug,
+        TypeInfo,
+        MaxEncodedLen,
+        codec::DecodeWithMemTracking,
+    )]
+    pub struct X402Payment<Hash, Balance, BlockNumber> {
+        /// Hash of the X402 payment proof, for off-chain verification.
+        pub proof_hash: Hash,
+        pub amount: Balance,
+        pub registered_at: BlockNumber,
+    }
+
+    /// Histogram buckets in a [`TagPriceStats`].
+    pub const PRICE_BUCKETS: usize = 16;
+
+    /// Settled prices of one tag in one price era.
+    ///
+    /// Bucket 0 counts prices below `PriceBucketUnit`; bucket `i` counts
+    /// prices in `[unit * 2^(i-1), unit * 2^i)`, and the last bucket is
+    /// open-ended.
+    #[derive(
+        Clone,
+        Encode,
+        Decode,
+        Eq,
+        PartialEq,
+        RuntimeDebug,
+        TypeInfo,
+        MaxEncodedLen,
+        Default,
+        codec::DecodeWithMemTracking,
+    )]
+    pub struct TagPriceStats<Balance> {
+        pub count: u32,
+        pub min: Balance,
+        pub max: Balance,
+        pub buckets: [u32; PRICE_BUCKETS],
+    }
+
+    /// Governed formula for an invoker's open-invocation cap.
+    #[derive(
+        Clone,
+        Encode,
+        Decode,
+        Eq,
+        PartialEq,
+        RuntimeDebug,
+        TypeInfo,
+        MaxEncodedLen,
+        Default,
+        codec::DecodeWithMemTracking,
+    )]
+    pub struct InvocationCapParams {
+        /// Cap for an account with no reputation tier or history.
+        pub base: u32,
+        /// Extra open invocations per reputation tier.
+        pub per_tier: u32,
+        /// Reputation score (basis points) per tier. Zero disables tiers.
+        pub tier_width: u32,
+        /// Finished invocations needed before the completion ratio applies.
+        pub min_history: u32,
+    }
+
+    /// Invocations an account has finished as invoker.
+    #[derive(
+        Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen, Default,
+    )]
+    pub struct InvokerHistory {
+        /// Invocations closed for any reason.
+        pub finished: u32,
+        /// Of those, invocations that were fully approved.
+        pub completed: u32,
     }
 
     /// Proof type for work submission.
@@ -200,6 +518,8 @@ pub mod pallet {
         pub name: BoundedVec<u8, T::MaxNameLength>,
         pub description: BoundedVec<u8, T::MaxDescriptionLength>,
         pub tags: BoundedVec<BoundedVec<u8, T::MaxTagLength>, T::MaxTagsPerListing>,
+        /// Jurisdictions the provider serves. Empty means unspecified.
+        pub jurisdictions: BoundedVec<JurisdictionCode, T::MaxJurisdictionsPerListing>,
         pub min_price: BalanceOf<T>,
         pub max_price: BalanceOf<T>,
         pub payment_mode: PaymentMode,
@@ -212,10 +532,150 @@ pub mod pallet {
         pub created_at: BlockNumberFor<T>,
         pub total_invocations: u32,
         pub successful_invocations: u32,
+        /// Account escrow releases are paid to instead of the provider, set
+        /// through the provider's payout controller.
+        pub payout_account: Option<T::AccountId>,
     }
 
     impl<T: Config> codec::DecodeWithMemTracking for ServiceListing<T> {}
 
+    /// A payout-account change waiting out `PayoutChangeDelay`.
+    #[derive(Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    pub struct PendingPayoutChange<T: Config> {
+        /// New payout account; `None` pays the provider again.
+        pub payout_account: Option<T::AccountId>,
+        /// First block at which the change can be applied.
+        pub effective_at: BlockNumberFor<T>,
+    }
+
+    impl<T: Config> codec::DecodeWithMemTracking for PendingPayoutChange<T> {}
+
+    /// A bonded report that a listing is illegal or malicious.
+    #[derive(Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    pub struct ListingFlag<T: Config> {
+        pub flagger: T::AccountId,
+        pub reason: BoundedVec<u8, T::MaxFlagReasonLength>,
+        /// `FlagBond` reserved from the flagger when the flag was raised.
+        pub bond: BalanceOf<T>,
+    }
+
+    impl<T: Config> codec::DecodeWithMemTracking for ListingFlag<T> {}
+
+    /// A listing's place in its tags' rankings.
+    #[derive(Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    pub struct RankEntry<T: Config> {
+        pub listing_id: ListingId,
+        /// Provider reputation times per-mille success rate, as of the
+        /// listing's last completed invocation.
+        pub score: u64,
+        /// Block of the listing's last completed invocation.
+        pub completed_at: BlockNumberFor<T>,
+    }
+
+    impl<T: Config> codec::DecodeWithMemTracking for RankEntry<T> {}
+
+    /// A provider's slot in a competition.
+    #[derive(Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    pub struct CompetitionEntry<T: Config> {
+        pub provider: T::AccountId,
+        /// Listing the provider entered with; its payout account is paid.
+        pub listing_id: ListingId,
+        /// Result CID, `None` until the provider submits.
+        pub result: Option<BoundedVec<u8, T::MaxDescriptionLength>>,
+    }
+
+    impl<T: Config> codec::DecodeWithMemTracking for CompetitionEntry<T> {}
+
+    /// A request several providers of one tag work on at once. The invoker
+    /// escrows once and pays the reward to the submission it picks.
+    #[derive(Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    pub struct Competition<T: Config> {
+        pub invoker: T::AccountId,
+        pub tag: BoundedVec<u8, T::MaxTagLength>,
+        pub requirements: BoundedVec<u8, T::MaxDescriptionLength>,
+        /// Paid to the winning provider.
+        pub reward: BalanceOf<T>,
+        /// Paid to every other provider that submitted a result.
+        pub participation_fee: BalanceOf<T>,
+        pub max_entrants: u32,
+        /// Last block providers may enter or submit.
+        pub deadline: BlockNumberFor<T>,
+        pub entries: BoundedVec<CompetitionEntry<T>, T::MaxCompetitionEntrants>,
+    }
+
+    impl<T: Config> codec::DecodeWithMemTracking for Competition<T> {}
+
+    /// Maximum length of a builder signature over a code hash.
+    pub const MAX_BUILD_SIGNATURE_LEN: u32 = 128;
+
+    /// An approved attestor's claim that a code hash is reproducibly built
+    /// from published sources.
+    #[derive(Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    pub struct BuildAttestation<T: Config> {
+        pub attestor: T::AccountId,
+        /// Toolchain and build metadata, e.g. compiler version and lockfile
+        /// hash.
+        pub toolchain: BoundedVec<u8, T::MaxToolchainLength>,
+        /// The builder's detached signature over the code hash, checked
+        /// off-chain against the attestor's published build key.
+        pub builder_signature: BoundedVec<u8, ConstU32<MAX_BUILD_SIGNATURE_LEN>>,
+        pub attested_at: BlockNumberFor<T>,
+    }
+
+    impl<T: Config> codec::DecodeWithMemTracking for BuildAttestation<T> {}
+
+    /// One milestone payout on a settlement statement.
+    #[derive(
+        Clone,
+        Encode,
+        Decode,
+        PartialEq,
+        Eq,
+        RuntimeDebug,
+        TypeInfo,
+        MaxEncodedLen,
+        codec::DecodeWithMemTracking,
+    )]
+    pub struct SettlementEntry<Balance> {
+        pub invocation_id: InvocationId,
+        /// Amount released from escrow.
+        pub amount: Balance,
+        /// Ecosystem-fund and referral shares withheld from `amount`.
+        pub fees: Balance,
+    }
+
+    /// Payouts accrued for a payee under deferred settlement.
+    #[derive(Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    pub struct SettlementStatement<T: Config> {
+        pub entries: BoundedVec<SettlementEntry<BalanceOf<T>>, T::MaxSettlementEntries>,
+        /// Net amount held for the payee.
+        pub total: BalanceOf<T>,
+        /// Block from which the sweep pays the statement out.
+        pub due: BlockNumberFor<T>,
+    }
+
+    impl<T: Config> codec::DecodeWithMemTracking for SettlementStatement<T> {}
+
+    /// A listing's terms as they stood at one version.
+    #[derive(Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    pub struct ListingTerms<T: Config> {
+        /// Hash of the name, price range, payment mode, SLA parameters and
+        /// stable reference price (see `listing_terms_hash`).
+        pub terms_hash: T::Hash,
+        /// Block from which this version was in force.
+        pub effective_from: BlockNumberFor<T>,
+    }
+
+    impl<T: Config> codec::DecodeWithMemTracking for ListingTerms<T> {}
+
     /// A single milestone within an invocation.
     #[derive(Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
     #[scale_info(skip_type_params(T))]
@@ -236,10 +696,14 @@ pub mod pallet {
     pub struct ServiceInvocation<T: Config> {
         pub id: InvocationId,
         pub listing_id: ListingId,
+        /// Listing terms version the invocation was created against.
+        pub listing_version: u32,
         pub invoker: T::AccountId,
         pub provider: T::AccountId,
         pub requirements: BoundedVec<u8, T::MaxDescriptionLength>,
         pub price: BalanceOf<T>,
+        /// Portion of `price` already paid out of escrow (approved milestones).
+        pub released: BalanceOf<T>,
         pub payment_mode: PaymentMode,
         pub status: InvocationStatus,
         pub milestones: BoundedVec<Milestone<T>, T::MaxMilestones>,
@@ -247,6 +711,8 @@ pub mod pallet {
         pub created_at: BlockNumberFor<T>,
         pub accepted_at: Option<BlockNumberFor<T>>,
         pub completed_at: Option<BlockNumberFor<T>>,
+        /// Trial invocation, refundable in full via `reject_trial`.
+        pub trial: bool,
     }
 
     impl<T: Config> codec::DecodeWithMemTracking for ServiceInvocation<T> {}
@@ -262,6 +728,18 @@ pub mod pallet {
 
     impl<T: Config> codec::DecodeWithMemTracking for WorkProof<T> {}
 
+    /// A progress checkpoint reported by the provider of an in-flight invocation.
+    #[derive(Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    pub struct ProgressCheckpoint<T: Config> {
+        /// Completion percentage (1–100).
+        pub percent: u8,
+        pub proof_cid: BoundedVec<u8, T::MaxCidLength>,
+        pub reported_at: BlockNumberFor<T>,
+    }
+
+    impl<T: Config> codec::DecodeWithMemTracking for ProgressCheckpoint<T> {}
+
     /// A dispute record.
     #[derive(Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
     #[scale_info(skip_type_params(T))]
@@ -274,36 +752,190 @@ pub mod pallet {
         pub status: DisputeStatus,
         pub raised_at: BlockNumberFor<T>,
         pub winner: Option<T::AccountId>,
+        pub tier: DisputeTier,
+        /// Last block at which a party may submit evidence.
+        pub evidence_deadline: BlockNumberFor<T>,
     }
 
     impl<T: Config> codec::DecodeWithMemTracking for DisputeRecord<T> {}
 
-    /// Spec for a milestone provided at invocation time.
-    #[derive(
-        Clone,
-        Encode,
-        Decode,
-        PartialEq,
-        RuntimeDebug,
-        TypeInfo,
-        MaxEncodedLen,
-        codec::DecodeWithMemTracking,
-    )]
-    pub struct MilestoneSpec {
-        pub pct_of_total: u8,
+    /// The arbiters drawn for a dispute and their votes.
+    #[derive(Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    pub struct ArbitrationPanel<T: Config> {
+        /// Each arbiter with its vote, if it has voted: the winner and an
+        /// optional provider share of the escrow.
+        pub arbiters: BoundedVec<ArbiterSeat<T>, T::MaxArbitrationPanel>,
+        /// Last block at which arbiters may vote.
+        pub voting_deadline: BlockNumberFor<T>,
+        /// Party that escalated and paid the arbitration fee.
+        pub fee_payer: T::AccountId,
     }
 
-    // =========================================================
-    // Config
-    // =========================================================
-
-    #[pallet::config]
-    pub trait Config: frame_system::Config {
-        type WeightInfo: WeightInfo;
+    impl<T: Config> codec::DecodeWithMemTracking for ArbitrationPanel<T> {}
 
-        type Currency: Currency<Self::AccountId>;
+    /// Reusable invocation parameters for agents that repeatedly invoke the
+    /// same listing. The stored requirements and deadline are defaults that
+    /// each invocation may override.
+    #[derive(Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    pub struct InvocationTemplate<T: Config> {
+        pub id: TemplateId,
+        pub owner: T::AccountId,
+        pub listing_id: ListingId,
+        pub price: BalanceOf<T>,
+        pub milestones: BoundedVec<MilestoneSpec, T::MaxMilestones>,
+        pub deadline_blocks: u32,
+        pub created_at: BlockNumberFor<T>,
+        /// Default requirements CID; empty if every invocation supplies one.
+        pub requirements: BoundedVec<u8, T::MaxDescriptionLength>,
+    }
 
-        type ReputationManager: ReputationManager<Self::AccountId, BalanceOf<Self>>;
+    impl<T: Config> codec::DecodeWithMemTracking for InvocationTemplate<T> {}
+
+    /// A booked invocation window on a listing.
+    #[derive(Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    pub struct SlotReservation<T: Config> {
+        pub invoker: T::AccountId,
+        pub start_block: BlockNumberFor<T>,
+        /// First block after the window.
+        pub end_block: BlockNumberFor<T>,
+        /// Reservation fee held from the invoker.
+        pub fee: BalanceOf<T>,
+    }
+
+    impl<T: Config> codec::DecodeWithMemTracking for SlotReservation<T> {}
+
+    /// A pending application for a provisional listing slot.
+    #[derive(Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    pub struct ListingApplication<T: Config> {
+        /// Bond reserved from the applicant.
+        pub bond: BalanceOf<T>,
+        pub submitted_at: BlockNumberFor<T>,
+        pub ayes: BoundedVec<T::AccountId, T::ApplicationApprovalVotes>,
+        pub nays: BoundedVec<T::AccountId, T::ApplicationApprovalVotes>,
+    }
+
+    impl<T: Config> codec::DecodeWithMemTracking for ListingApplication<T> {}
+
+    /// Probation state of an approved applicant.
+    #[derive(Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    pub struct ProvisionalProvider<T: Config> {
+        /// Remainder of the application bond still reserved.
+        pub bond: BalanceOf<T>,
+        /// Fully approved invocations still needed to graduate.
+        pub remaining_invocations: u32,
+    }
+
+    impl<T: Config> codec::DecodeWithMemTracking for ProvisionalProvider<T> {}
+
+    /// Spec for a milestone provided at invocation time.
+    #[derive(
+        Clone,
+        Encode,
+        Decode,
+        PartialEq,
+        RuntimeDebug,
+        TypeInfo,
+        MaxEncodedLen,
+        codec::DecodeWithMemTracking,
+    )]
+    pub struct MilestoneSpec {
+        pub pct_of_total: u8,
+    }
+
+    /// The open quadratic-funding round.
+    #[derive(Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    pub struct FundingRound<T: Config> {
+        pub id: u32,
+        pub ends_at: BlockNumberFor<T>,
+        pub projects: BoundedVec<T::AccountId, T::MaxRoundProjects>,
+    }
+
+    impl<T: Config> codec::DecodeWithMemTracking for FundingRound<T> {}
+
+    /// An open challenge against a listing's liveness bond.
+    #[derive(Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    pub struct LivenessChallenge<T: Config> {
+        pub challenger: T::AccountId,
+        /// Challenge fee reserved from the challenger.
+        pub fee: BalanceOf<T>,
+        /// Last block at which the provider may respond.
+        pub deadline: BlockNumberFor<T>,
+    }
+
+    impl<T: Config> codec::DecodeWithMemTracking for LivenessChallenge<T> {}
+
+    /// State of a recurring subscription.
+    #[derive(
+        Clone,
+        Copy,
+        Encode,
+        Decode,
+        Eq,
+        PartialEq,
+        RuntimeDebug,
+        TypeInfo,
+        MaxEncodedLen,
+        codec::DecodeWithMemTracking,
+    )]
+    pub enum SubscriptionStatus {
+        /// Periods elapse and are paid out when due.
+        Active,
+        /// The subscriber stopped the clock.
+        Paused,
+        /// Payments are held until governance settles the dispute.
+        Disputed,
+    }
+
+    /// A recurring subscription to a listing, paid from escrow one period
+    /// at a time.
+    #[derive(Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    pub struct Subscription<T: Config> {
+        pub listing_id: ListingId,
+        pub subscriber: T::AccountId,
+        pub provider: T::AccountId,
+        pub price_per_period: BalanceOf<T>,
+        pub period_blocks: u32,
+        /// Periods still held in escrow, including the current one.
+        pub periods_remaining: u32,
+        /// Block the current period's payment falls due.
+        pub next_payment: BlockNumberFor<T>,
+        /// Block the clock stopped at while paused or disputed.
+        pub paused_at: Option<BlockNumberFor<T>>,
+        pub status: SubscriptionStatus,
+    }
+
+    impl<T: Config> codec::DecodeWithMemTracking for Subscription<T> {}
+
+    /// Contributions to one project in a funding round.
+    #[derive(
+        Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen, Default,
+    )]
+    pub struct ProjectTally<Balance> {
+        /// Sum of all contributions.
+        pub contributed: Balance,
+        /// Sum over contributors of the square root of their total.
+        pub sqrt_sum: u128,
+    }
+
+    // =========================================================
+    // Config
+    // =========================================================
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        type WeightInfo: WeightInfo;
+
+        type Currency: ReservableCurrency<Self::AccountId>;
+
+        type ReputationManager: ReputationManager<Self::AccountId, BalanceOf<Self>>;
 
         #[pallet::constant]
         type PalletId: Get<PalletId>;
@@ -356,13 +988,302 @@ pub mod pallet {
         #[pallet::constant]
         type AutoApproveMaxDelay: Get<u32>;
 
-        /// Bounty paid to the caller of `try_expire_invocation`.
+        /// Share of an expired invocation's remaining escrow paid as the
+        /// expiry bounty, before `MinExpireBounty`/`MaxExpireBounty` apply.
+        #[pallet::constant]
+        type ExpireBountyShare: Get<Perbill>;
+
+        /// Smallest expiry bounty, unless the escrow holds less.
+        #[pallet::constant]
+        type MinExpireBounty: Get<BalanceOf<Self>>;
+
+        /// Largest expiry bounty.
         #[pallet::constant]
-        type ExpireBounty: Get<BalanceOf<Self>>;
+        type MaxExpireBounty: Get<BalanceOf<Self>>;
 
-        /// Maximum expirations processed per `on_initialize` block.
+        /// Maximum items each per-block sweep processes, however much hook
+        /// budget is left.
         #[pallet::constant]
         type MaxExpirationsPerBlock: Get<u32>;
+
+        /// Additional reaper guards supplied by other pallets (e.g. IBC relayer
+        /// sets). This pallet's own obligations are always checked as well.
+        type AccountGuards: ProviderOfAccountGuards<Self::AccountId>;
+
+        /// Maximum invocation templates stored per account.
+        #[pallet::constant]
+        type MaxTemplatesPerAccount: Get<u32>;
+
+        /// Maximum invocations created by one `invoke_from_template_batch`.
+        #[pallet::constant]
+        type MaxTemplateBatch: Get<u32>;
+
+        /// Exchange-rate source used to convert stable listing prices into CLAW.
+        type PriceOracle: PriceOracle<BalanceOf<Self>, BlockNumberFor<Self>>;
+
+        /// Oldest oracle update (in blocks) accepted for stable-priced invocations.
+        #[pallet::constant]
+        type MaxOracleAge: Get<u32>;
+
+        /// Dispute losses within `DisputeLossWindow` that suspend a provider (0 disables).
+        #[pallet::constant]
+        type DisputeLossThreshold: Get<u32>;
+
+        /// Length (blocks) of the window in which dispute losses are counted.
+        #[pallet::constant]
+        type DisputeLossWindow: Get<u32>;
+
+        /// Bond a suspended provider reserves to restore its listings.
+        #[pallet::constant]
+        type ReactivationBond: Get<BalanceOf<Self>>;
+
+        /// Maximum number of jurisdiction codes per listing.
+        #[pallet::constant]
+        type MaxJurisdictionsPerListing: Get<u32>;
+
+        /// Maximum number of listings indexed per jurisdiction.
+        #[pallet::constant]
+        type MaxListingsPerJurisdiction: Get<u32>;
+
+        /// Fee reserved from an invoker to book a slot.
+        #[pallet::constant]
+        type SlotReservationFee: Get<BalanceOf<Self>>;
+
+        /// Maximum length of a reserved slot in blocks.
+        #[pallet::constant]
+        type MaxSlotDuration: Get<u32>;
+
+        /// Maximum number of outstanding reservations per listing.
+        #[pallet::constant]
+        type MaxReservationsPerListing: Get<u32>;
+
+        /// Invocation price at or above which disputes take the priority track.
+        #[pallet::constant]
+        type PriorityDisputeThreshold: Get<BalanceOf<Self>>;
+
+        /// Evidence window and juror parameters for standard disputes.
+        #[pallet::constant]
+        type StandardDisputeTrack: Get<DisputeTrack<BalanceOf<Self>>>;
+
+        /// Evidence window and juror parameters for priority disputes.
+        #[pallet::constant]
+        type PriorityDisputeTrack: Get<DisputeTrack<BalanceOf<Self>>>;
+
+        /// Maximum number of progress checkpoints per invocation.
+        #[pallet::constant]
+        type MaxProgressCheckpoints: Get<u32>;
+
+        /// Blocks without a new checkpoint after which the invoker may cancel
+        /// an in-flight invocation with partial payment.
+        #[pallet::constant]
+        type ProgressStallPeriod: Get<u32>;
+
+        /// Percentage (0–100) of the next unapproved milestone paid to the
+        /// provider when the invoker cancels mid-work.
+        #[pallet::constant]
+        type KillFeePercent: Get<u8>;
+
+        /// Blocks after a cancellation request at which it can be settled
+        /// without the provider's acknowledgement.
+        #[pallet::constant]
+        type CancellationAckTimeout: Get<u32>;
+
+        /// Origin that may approve or reject listing applications directly
+        /// (e.g. a provider committee).
+        type OnboardingOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Bond reserved by an applicant for a provisional listing slot.
+        #[pallet::constant]
+        type ListingApplicationBond: Get<BalanceOf<Self>>;
+
+        /// Aye (or nay) votes from qualified providers that decide an application.
+        #[pallet::constant]
+        type ApplicationApprovalVotes: Get<u32>;
+
+        /// Fully approved invocations a provisional provider needs to graduate.
+        #[pallet::constant]
+        type ProvisionalInvocations: Get<u32>;
+
+        /// Bond amount paid to the invoker when a provisional provider misses
+        /// an invocation deadline.
+        #[pallet::constant]
+        type ProvisionalSlaPenalty: Get<BalanceOf<Self>>;
+
+        /// Highest price allowed for a trial invocation.
+        #[pallet::constant]
+        type MaxTrialPrice: Get<BalanceOf<Self>>;
+
+        /// Blocks after a trial invocation is created during which the invoker
+        /// may reject it for a full refund.
+        #[pallet::constant]
+        type TrialRefundWindow: Get<u32>;
+
+        /// Disputes raised, as a percentage (0–100) of a listing's invocations,
+        /// at which the listing is flagged as high-dispute.
+        #[pallet::constant]
+        type HighDisputePercent: Get<u8>;
+
+        /// Invocations a listing needs before it can be flagged as high-dispute.
+        #[pallet::constant]
+        type MinInvocationsForDisputeFlag: Get<u32>;
+
+        /// Account receiving the ecosystem-fund share of milestone payouts and
+        /// paying out quadratic-funding matches. Distinct from the treasury.
+        type EcosystemFund: Get<Self::AccountId>;
+
+        /// Highest `EcosystemFundShare` governance may set.
+        #[pallet::constant]
+        type MaxEcosystemFundShare: Get<Perbill>;
+
+        /// Length (blocks) of a quadratic-funding round.
+        #[pallet::constant]
+        type FundingRoundLength: Get<u32>;
+
+        /// Maximum projects in a quadratic-funding round.
+        #[pallet::constant]
+        type MaxRoundProjects: Get<u32>;
+
+        /// Fee reserved from an account challenging a listing's liveness.
+        #[pallet::constant]
+        type LivenessChallengeFee: Get<BalanceOf<Self>>;
+
+        /// Percentage (0–100) of a liveness bond paid to the challenger when
+        /// the provider fails to respond in time.
+        #[pallet::constant]
+        type LivenessSlashPercent: Get<u8>;
+
+        /// Length (blocks) of a price-index era.
+        #[pallet::constant]
+        type PriceEraLength: Get<u32>;
+
+        /// Price eras kept per tag in `TagPriceHistory`.
+        #[pallet::constant]
+        type MaxPriceEras: Get<u32>;
+
+        /// Upper bound of the lowest price-histogram bucket.
+        #[pallet::constant]
+        type PriceBucketUnit: Get<BalanceOf<Self>>;
+
+        /// Blocks the non-raising party has to respond to a dispute before it
+        /// is resolved for the raiser by default judgment.
+        #[pallet::constant]
+        type DisputeResponseWindow: Get<u32>;
+
+        /// Bond reserved from the provider for every new listing. Zero
+        /// disables provider bonds.
+        #[pallet::constant]
+        type ProviderBondAmount: Get<BalanceOf<Self>>;
+
+        /// Percentage (0–100) of the remaining provider bond slashed for each
+        /// missed SLA or lost dispute.
+        #[pallet::constant]
+        type SlaSlashPercent: Get<u8>;
+
+        /// Share of a provider-bond slash paid to the affected invoker. The
+        /// rest goes to `SlashTreasury`.
+        #[pallet::constant]
+        type SlashInvokerShare: Get<Perbill>;
+
+        /// Account receiving the treasury share of provider-bond slashes.
+        type SlashTreasury: Get<Self::AccountId>;
+
+        /// Highest referral fee a listing may offer.
+        #[pallet::constant]
+        type MaxReferralFee: Get<Perbill>;
+
+        /// Most periods a subscription can escrow up front.
+        #[pallet::constant]
+        type MaxSubscriptionPeriods: Get<u32>;
+
+        /// Reputation an agent needs to join the arbiter pool.
+        #[pallet::constant]
+        type MinArbiterReputation: Get<u32>;
+
+        /// Maximum number of agents in the arbiter pool.
+        #[pallet::constant]
+        type MaxArbiters: Get<u32>;
+
+        /// Upper bound on a track's `juror_panel_size`.
+        #[pallet::constant]
+        type MaxArbitrationPanel: Get<u32>;
+
+        /// Blocks an arbitration panel has to reach a majority.
+        #[pallet::constant]
+        type ArbitrationVotingPeriod: Get<u32>;
+
+        /// Blocks between scheduling a payout-account change and applying it.
+        #[pallet::constant]
+        type PayoutChangeDelay: Get<u32>;
+
+        /// Origin that reviews flagged listings (e.g. a moderation committee).
+        type ModerationOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Bond reserved from an account for each listing it flags.
+        #[pallet::constant]
+        type FlagBond: Get<BalanceOf<Self>>;
+
+        /// Distinct flags that pause a listing pending review.
+        #[pallet::constant]
+        type FlagThreshold: Get<u32>;
+
+        /// Most open flags a listing can carry.
+        #[pallet::constant]
+        type MaxFlagsPerListing: Get<u32>;
+
+        /// Maximum length of a flag's reason.
+        #[pallet::constant]
+        type MaxFlagReasonLength: Get<u32>;
+
+        /// Blocks since its last completed invocation after which a
+        /// listing's ranking score counts half.
+        #[pallet::constant]
+        type RankingHalfLife: Get<u32>;
+
+        /// Staking backend invokers may park escrow in.
+        type EscrowYield: EscrowYield<Self::AccountId, BalanceOf<Self>>;
+
+        /// Share of escrow staking rewards paid to the invoker; the rest goes
+        /// to the provider.
+        #[pallet::constant]
+        type YieldInvokerShare: Get<Perbill>;
+
+        /// Maximum providers that can enter one competition.
+        #[pallet::constant]
+        type MaxCompetitionEntrants: Get<u32>;
+
+        /// Blocks after a competition's deadline the invoker has to pick a
+        /// winner before anyone may close it and split the reward.
+        #[pallet::constant]
+        type CompetitionAwardPeriod: Get<u32>;
+
+        /// Origin that approves build attestors and can revoke any build
+        /// attestation.
+        type AttestorOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Maximum length of a build attestation's toolchain metadata.
+        #[pallet::constant]
+        type MaxToolchainLength: Get<u32>;
+
+        /// Maximum listings bound to one attested code hash.
+        #[pallet::constant]
+        type MaxListingsPerBuild: Get<u32>;
+
+        /// Maximum accounts on a listing's invoker allowlist.
+        #[pallet::constant]
+        type MaxAllowlistSize: Get<u32>;
+
+        /// Blocks a settlement statement stays open before it is paid out.
+        #[pallet::constant]
+        type SettlementPeriod: Get<u32>;
+
+        /// Payouts per settlement statement; a full statement is paid out
+        /// before the next payout is recorded.
+        #[pallet::constant]
+        type MaxSettlementEntries: Get<u32>;
+
+        /// Insurance cover required of providers in tags governance marks
+        /// as high-risk.
+        type Insurance: InsurancePolicies<Self::AccountId, BalanceOf<Self>>;
     }
 
     // =========================================================
@@ -383,620 +1304,5172 @@ pub mod pallet {
     #[pallet::storage]
     pub type ListingCount<T: Config> = StorageValue<_, u64, ValueQuery>;
 
+    /// Current terms version of each listing: 0 when listed, incremented
+    /// whenever a change alters the terms hash.
     #[pallet::storage]
-    pub type ListingsByTag<T: Config> = StorageMap<
+    pub type ListingVersion<T: Config> =
+        StorageMap<_, Blake2_128Concat, ListingId, u32, ValueQuery>;
+
+    /// Open flags against a listing, oldest first.
+    #[pallet::storage]
+    pub type ListingFlags<T: Config> = StorageMap<
         _,
         Blake2_128Concat,
-        BoundedVec<u8, T::MaxTagLength>,
-        BoundedVec<ListingId, T::MaxListingsPerTag>,
+        ListingId,
+        BoundedVec<ListingFlag<T>, T::MaxFlagsPerListing>,
         ValueQuery,
     >;
 
+    /// Listings paused pending moderation review, with the block they were
+    /// paused at.
     #[pallet::storage]
-    pub type ListingsByProvider<T: Config> = StorageMap<
+    pub type ListingsUnderReview<T: Config> =
+        StorageMap<_, Blake2_128Concat, ListingId, BlockNumberFor<T>, OptionQuery>;
+
+    /// Listings per tag that have completed an invocation, highest score
+    /// first.
+    #[pallet::storage]
+    pub type TagRankings<T: Config> = StorageMap<
         _,
         Blake2_128Concat,
-        T::AccountId,
-        BoundedVec<ListingId, T::MaxListingsPerProvider>,
+        BoundedVec<u8, T::MaxTagLength>,
+        BoundedVec<RankEntry<T>, T::MaxListingsPerTag>,
         ValueQuery,
     >;
 
+    /// Escrow principal staked through `EscrowYield`, by invocation.
     #[pallet::storage]
-    pub type ServiceInvocations<T: Config> =
-        StorageMap<_, Blake2_128Concat, InvocationId, ServiceInvocation<T>, OptionQuery>;
+    pub type YieldPositions<T: Config> =
+        StorageMap<_, Blake2_128Concat, InvocationId, BalanceOf<T>, OptionQuery>;
 
+    /// Share of its staked value an invocation's escrow got back from a
+    /// yield position that closed short. Later payouts from the escrow are
+    /// scaled by it.
     #[pallet::storage]
-    pub type InvocationCount<T: Config> = StorageValue<_, u64, ValueQuery>;
+    pub type EscrowRecoveryRates<T: Config> =
+        StorageMap<_, Blake2_128Concat, InvocationId, Perbill, OptionQuery>;
 
+    /// Capacity limits set by providers.
     #[pallet::storage]
-    pub type InvocationsByListing<T: Config> = StorageDoubleMap<
+    pub type ProviderCapacities<T: Config> = StorageMap<
         _,
         Blake2_128Concat,
-        ListingId,
-        Blake2_128Concat,
-        InvocationId,
-        (),
+        T::AccountId,
+        ProviderCapacity<BlockNumberFor<T>>,
         OptionQuery,
     >;
 
+    /// Open invocations per provider, across all of its listings.
     #[pallet::storage]
-    pub type InvocationsByDeadline<T: Config> = StorageDoubleMap<
+    pub type ProviderOpenInvocations<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+    /// Cold controller bound to a provider. Only it can change the payout
+    /// account of the provider's listings.
+    #[pallet::storage]
+    pub type PayoutControllers<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, T::AccountId, OptionQuery>;
+
+    /// Scheduled payout-account changes per listing.
+    #[pallet::storage]
+    pub type PendingPayoutChanges<T: Config> =
+        StorageMap<_, Blake2_128Concat, ListingId, PendingPayoutChange<T>, OptionQuery>;
+
+    /// Terms of every version of a listing. Entries are never rewritten, so
+    /// an invocation's `listing_version` can be checked against them.
+    ///
+    /// Name, price range, payment mode, SLA parameters and stable reference
+    /// price are hashed; an update that changes the hash archives a new
+    /// version.
+    #[pallet::storage]
+    pub type ListingTermsHistory<T: Config> = StorageDoubleMap<
         _,
-        Twox64Concat,
-        BlockNumberFor<T>,
         Blake2_128Concat,
-        InvocationId,
-        (),
+        ListingId,
+        Twox64Concat,
+        u32,
+        ListingTerms<T>,
         OptionQuery,
     >;
 
     #[pallet::storage]
-    pub type InvocationsByInvoker<T: Config> = StorageMap<
+    pub type ListingsByTag<T: Config> = StorageMap<
         _,
         Blake2_128Concat,
-        T::AccountId,
-        BoundedVec<InvocationId, T::MaxActiveInvocationsPerAccount>,
+        BoundedVec<u8, T::MaxTagLength>,
+        BoundedVec<ListingId, T::MaxListingsPerTag>,
         ValueQuery,
     >;
 
     #[pallet::storage]
-    pub type InvocationProofs<T: Config> = StorageDoubleMap<
+    pub type ListingsByJurisdiction<T: Config> = StorageMap<
         _,
         Blake2_128Concat,
-        InvocationId,
-        Twox64Concat,
-        u32,
-        WorkProof<T>,
-        OptionQuery,
+        JurisdictionCode,
+        BoundedVec<ListingId, T::MaxListingsPerJurisdiction>,
+        ValueQuery,
     >;
 
     #[pallet::storage]
-    pub type Disputes<T: Config> =
+    pub type ListingsByProvider<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        BoundedVec<ListingId, T::MaxListingsPerProvider>,
+        ValueQuery,
+    >;
+
+    #[pallet::storage]
+    pub type ServiceInvocations<T: Config> =
+        StorageMap<_, Blake2_128Concat, InvocationId, ServiceInvocation<T>, OptionQuery>;
+
+    #[pallet::storage]
+    pub type InvocationCount<T: Config> = StorageValue<_, u64, ValueQuery>;
+
+    #[pallet::storage]
+    pub type InvocationsByListing<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        ListingId,
+        Blake2_128Concat,
+        InvocationId,
+        (),
+        OptionQuery,
+    >;
+
+    #[pallet::storage]
+    pub type InvocationsByDeadline<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        BlockNumberFor<T>,
+        Blake2_128Concat,
+        InvocationId,
+        (),
+        OptionQuery,
+    >;
+
+    /// First deadline block the invocation-expiry sweep has not finished with.
+    #[pallet::storage]
+    pub type InvocationExpiryCursor<T: Config> = StorageValue<_, BlockNumberFor<T>, ValueQuery>;
+
+    #[pallet::storage]
+    pub type InvocationsByInvoker<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        BoundedVec<InvocationId, T::MaxActiveInvocationsPerAccount>,
+        ValueQuery,
+    >;
+
+    #[pallet::storage]
+    pub type InvocationProofs<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        InvocationId,
+        Twox64Concat,
+        u32,
+        WorkProof<T>,
+        OptionQuery,
+    >;
+
+    /// Progress checkpoints of in-flight invocations, oldest first.
+    #[pallet::storage]
+    pub type InvocationProgress<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        InvocationId,
+        BoundedVec<ProgressCheckpoint<T>, T::MaxProgressCheckpoints>,
+        ValueQuery,
+    >;
+
+    /// Mid-work cancellation requests: invocation → block requested.
+    #[pallet::storage]
+    pub type PendingCancellations<T: Config> =
+        StorageMap<_, Blake2_128Concat, InvocationId, BlockNumberFor<T>, OptionQuery>;
+
+    #[pallet::storage]
+    pub type Disputes<T: Config> =
         StorageMap<_, Blake2_128Concat, DisputeId, DisputeRecord<T>, OptionQuery>;
 
     #[pallet::storage]
     pub type DisputeCount<T: Config> = StorageValue<_, u64, ValueQuery>;
 
-    // =========================================================
-    // Hooks
-    // =========================================================
+    #[pallet::storage]
+    pub type InvocationTemplates<T: Config> =
+        StorageMap<_, Blake2_128Concat, TemplateId, InvocationTemplate<T>, OptionQuery>;
+
+    #[pallet::storage]
+    pub type TemplateCount<T: Config> = StorageValue<_, u64, ValueQuery>;
+
+    #[pallet::storage]
+    pub type TemplatesByOwner<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        BoundedVec<TemplateId, T::MaxTemplatesPerAccount>,
+        ValueQuery,
+    >;
+
+    /// Listings priced in a reference unit (e.g. USD cents) instead of CLAW.
+    /// The CLAW amount is computed from `T::PriceOracle` at invocation time.
+    #[pallet::storage]
+    pub type StablePrices<T: Config> = StorageMap<_, Blake2_128Concat, ListingId, u64, OptionQuery>;
+
+    /// Start of a provider's current loss window and the disputes lost in it.
+    #[pallet::storage]
+    pub type ProviderDisputeLosses<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, (BlockNumberFor<T>, u32), ValueQuery>;
+
+    /// Suspended providers and the listings paused by their suspension.
+    #[pallet::storage]
+    pub type SuspendedProviders<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        BoundedVec<ListingId, T::MaxListingsPerProvider>,
+        OptionQuery,
+    >;
+
+    /// Reactivation bonds currently reserved, by provider.
+    #[pallet::storage]
+    pub type ReactivationBonds<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>, OptionQuery>;
+
+    /// Outstanding slot reservations per listing. Windows never overlap.
+    #[pallet::storage]
+    pub type ListingReservations<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        ListingId,
+        BoundedVec<SlotReservation<T>, T::MaxReservationsPerListing>,
+        ValueQuery,
+    >;
+
+    /// Pending applications for a provisional listing slot, by applicant.
+    #[pallet::storage]
+    pub type ListingApplications<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, ListingApplication<T>, OptionQuery>;
+
+    /// Listings on which an invoker has already used its trial invocation.
+    #[pallet::storage]
+    pub type TrialsUsed<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        ListingId,
+        Blake2_128Concat,
+        T::AccountId,
+        (),
+        OptionQuery,
+    >;
+
+    /// Approved applicants still on probation.
+    #[pallet::storage]
+    pub type ProvisionalProviders<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, ProvisionalProvider<T>, OptionQuery>;
+
+    /// Dispute counters per listing.
+    #[pallet::storage]
+    pub type ListingDisputeStats<T: Config> =
+        StorageMap<_, Blake2_128Concat, ListingId, DisputeStats, ValueQuery>;
+
+    /// Provider bonds reserved at listing time, by listing.
+    #[pallet::storage]
+    pub type ProviderBonds<T: Config> =
+        StorageMap<_, Blake2_128Concat, ListingId, BalanceOf<T>, OptionQuery>;
+
+    #[pallet::storage]
+    pub type Subscriptions<T: Config> =
+        StorageMap<_, Blake2_128Concat, SubscriptionId, Subscription<T>, OptionQuery>;
+
+    #[pallet::storage]
+    pub type SubscriptionCount<T: Config> = StorageValue<_, u64, ValueQuery>;
+
+    /// Active subscriptions by the block their next payment falls due.
+    #[pallet::storage]
+    pub type SubscriptionsByDueBlock<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        BlockNumberFor<T>,
+        Blake2_128Concat,
+        SubscriptionId,
+        (),
+        OptionQuery,
+    >;
+
+    /// First due block the subscription payment sweep has not finished with.
+    #[pallet::storage]
+    pub type SubscriptionPaymentCursor<T: Config> = StorageValue<_, BlockNumberFor<T>, ValueQuery>;
+
+    /// Referral terms offered by listings.
+    #[pallet::storage]
+    pub type ListingReferralTerms<T: Config> =
+        StorageMap<_, Blake2_128Concat, ListingId, ReferralTerms<BalanceOf<T>>, OptionQuery>;
+
+    /// Referrers of open invocations.
+    #[pallet::storage]
+    pub type InvocationReferrals<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        InvocationId,
+        Referral<T::AccountId, BalanceOf<T>>,
+        OptionQuery,
+    >;
+
+    /// Off-chain X402 settlements, by invocation.
+    #[pallet::storage]
+    pub type X402Payments<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        InvocationId,
+        X402Payment<T::Hash, BalanceOf<T>, BlockNumberFor<T>>,
+        OptionQuery,
+    >;
+
+    /// Minimum reputation a listing's provider requires of X402 invokers.
+    #[pallet::storage]
+    pub type X402MinReputation<T: Config> =
+        StorageMap<_, Blake2_128Concat, ListingId, u32, OptionQuery>;
+
+    /// Open disputes awaiting the counterparty's response, with the block
+    /// after which default judgment applies.
+    #[pallet::storage]
+    pub type DefaultJudgmentDeadlines<T: Config> =
+        StorageMap<_, Twox64Concat, DisputeId, BlockNumberFor<T>, OptionQuery>;
+
+    /// `DefaultJudgmentDeadlines` by deadline block, for the default-judgment
+    /// sweep.
+    #[pallet::storage]
+    pub type DefaultJudgmentsByDeadline<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        BlockNumberFor<T>,
+        Twox64Concat,
+        DisputeId,
+        (),
+        OptionQuery,
+    >;
+
+    /// First deadline block the default-judgment sweep has not finished with.
+    #[pallet::storage]
+    pub type DefaultJudgmentCursor<T: Config> = StorageValue<_, BlockNumberFor<T>, ValueQuery>;
+
+    /// Agents available to sit on arbitration panels.
+    #[pallet::storage]
+    pub type Arbiters<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, ArbiterInfo<BalanceOf<T>>, OptionQuery>;
+
+    /// Arbiter pool in join order, for panel selection.
+    #[pallet::storage]
+    pub type ArbiterPool<T: Config> =
+        StorageValue<_, BoundedVec<T::AccountId, T::MaxArbiters>, ValueQuery>;
+
+    /// Panels of disputes under arbitration.
+    #[pallet::storage]
+    pub type ArbitrationPanels<T: Config> =
+        StorageMap<_, Twox64Concat, DisputeId, ArbitrationPanel<T>, OptionQuery>;
+
+    /// `ArbitrationPanels` by voting deadline, for the arbitration-expiry
+    /// sweep.
+    #[pallet::storage]
+    pub type ArbitrationsByDeadline<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        BlockNumberFor<T>,
+        Twox64Concat,
+        DisputeId,
+        (),
+        OptionQuery,
+    >;
+
+    /// First voting deadline the arbitration-expiry sweep has not finished
+    /// with.
+    #[pallet::storage]
+    pub type ArbitrationCursor<T: Config> = StorageValue<_, BlockNumberFor<T>, ValueQuery>;
+
+    /// Settled-price summary per (tag, price era).
+    #[pallet::storage]
+    pub type TagPriceHistory<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        BoundedVec<u8, T::MaxTagLength>,
+        Twox64Concat,
+        u32,
+        TagPriceStats<BalanceOf<T>>,
+        OptionQuery,
+    >;
+
+    /// Share of every milestone payout routed to the ecosystem fund, capped
+    /// at `MaxEcosystemFundShare`. The fund finances quadratic-funding
+    /// rounds.
+    #[pallet::storage]
+    pub type EcosystemFundShare<T: Config> = StorageValue<_, Perbill, ValueQuery>;
+
+    /// Cumulative ecosystem-fund contributions per provider.
+    #[pallet::storage]
+    pub type EcosystemContributions<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
+
+    /// Number of quadratic-funding rounds opened so far.
+    #[pallet::storage]
+    pub type FundingRoundCount<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+    /// The quadratic-funding round currently open, if any.
+    #[pallet::storage]
+    pub type ActiveFundingRound<T: Config> = StorageValue<_, FundingRound<T>, OptionQuery>;
+
+    /// Contribution tallies per (round, project).
+    #[pallet::storage]
+    pub type RoundTallies<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        u32,
+        Blake2_128Concat,
+        T::AccountId,
+        ProjectTally<BalanceOf<T>>,
+        ValueQuery,
+    >;
+
+    /// Liveness bonds reserved from providers, by listing.
+    #[pallet::storage]
+    pub type LivenessBonds<T: Config> =
+        StorageMap<_, Blake2_128Concat, ListingId, BalanceOf<T>, OptionQuery>;
+
+    /// Open liveness challenges, by listing. At most one per listing.
+    #[pallet::storage]
+    pub type LivenessChallenges<T: Config> =
+        StorageMap<_, Blake2_128Concat, ListingId, LivenessChallenge<T>, OptionQuery>;
+
+    /// Governed invocation-cap formula. `None` means the flat
+    /// `MaxActiveInvocationsPerAccount` applies.
+    ///
+    /// Otherwise an invoker's cap is `base + per_tier * (reputation /
+    /// tier_width)`, scaled by its fully approved share of finished
+    /// invocations once it has `min_history` of them (never below `base`),
+    /// and bounded by `MaxActiveInvocationsPerAccount`.
+    #[pallet::storage]
+    pub type InvocationCapParameters<T: Config> = StorageValue<_, InvocationCapParams, OptionQuery>;
+
+    /// Finished invocations per invoker.
+    #[pallet::storage]
+    pub type InvokerHistories<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, InvokerHistory, ValueQuery>;
+
+    /// Each contributor's total per (round, project).
+    #[pallet::storage]
+    pub type RoundContributions<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        (u32, T::AccountId),
+        Blake2_128Concat,
+        T::AccountId,
+        BalanceOf<T>,
+        ValueQuery,
+    >;
+
+    /// Open competitions.
+    #[pallet::storage]
+    pub type Competitions<T: Config> =
+        StorageMap<_, Blake2_128Concat, CompetitionId, Competition<T>, OptionQuery>;
+
+    #[pallet::storage]
+    pub type CompetitionCount<T: Config> = StorageValue<_, u64, ValueQuery>;
+
+    /// Accounts approved by `AttestorOrigin` to attest builds.
+    #[pallet::storage]
+    pub type BuildAttestors<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, (), OptionQuery>;
+
+    /// Build attestations, by code hash.
+    #[pallet::storage]
+    pub type BuildAttestations<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::Hash, BuildAttestation<T>, OptionQuery>;
+
+    /// The attested code hash each listing is bound to.
+    #[pallet::storage]
+    pub type ListingBuilds<T: Config> =
+        StorageMap<_, Blake2_128Concat, ListingId, T::Hash, OptionQuery>;
+
+    /// Listings bound to each code hash, so revocation can find them.
+    #[pallet::storage]
+    pub type ListingsByBuild<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::Hash,
+        BoundedVec<ListingId, T::MaxListingsPerBuild>,
+        ValueQuery,
+    >;
+
+    /// Listings paused because their attestation was revoked. Binding a new
+    /// attestation reactivates them.
+    #[pallet::storage]
+    pub type ListingsAwaitingAttestation<T: Config> =
+        StorageMap<_, Blake2_128Concat, ListingId, (), OptionQuery>;
+
+    /// Invokers that only invoke listings bound to an attested build.
+    #[pallet::storage]
+    pub type RequireAttestedListings<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, (), OptionQuery>;
+
+    /// Accounts allowed to invoke or subscribe to a private listing. Listings
+    /// without an entry are open to everyone.
+    #[pallet::storage]
+    pub type ListingAllowlists<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        ListingId,
+        BoundedVec<T::AccountId, T::MaxAllowlistSize>,
+        OptionQuery,
+    >;
+
+    /// Providers whose milestone payouts accrue to settlement statements.
+    #[pallet::storage]
+    pub type DeferredSettlement<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, (), OptionQuery>;
+
+    /// Open settlement statement per payee.
+    #[pallet::storage]
+    pub type SettlementStatements<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, SettlementStatement<T>, OptionQuery>;
+
+    /// Open statements by due block, for the settlement sweep.
+    #[pallet::storage]
+    pub type SettlementsDue<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        BlockNumberFor<T>,
+        Blake2_128Concat,
+        T::AccountId,
+        (),
+        OptionQuery,
+    >;
+
+    /// First due block the settlement sweep has not finished with.
+    #[pallet::storage]
+    pub type SettlementCursor<T: Config> = StorageValue<_, BlockNumberFor<T>, ValueQuery>;
+
+    /// Invocation price above which listings in a tag need insurance.
+    #[pallet::storage]
+    pub type TagInsuranceThresholds<T: Config> =
+        StorageMap<_, Blake2_128Concat, BoundedVec<u8, T::MaxTagLength>, BalanceOf<T>, OptionQuery>;
+
+    /// Insurance policy covering an invocation that required one.
+    #[pallet::storage]
+    pub type InvocationInsurance<T: Config> =
+        StorageMap<_, Blake2_128Concat, InvocationId, InsurancePolicyId, OptionQuery>;
+
+    // =========================================================
+    // Events
+    // =========================================================
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        ServiceListed {
+            listing_id: ListingId,
+            provider: T::AccountId,
+            min_price: BalanceOf<T>,
+        },
+        ListingUpdated {
+            listing_id: ListingId,
+        },
+        ServiceDelisted {
+            listing_id: ListingId,
+        },
+        ServiceInvoked {
+            invocation_id: InvocationId,
+            listing_id: ListingId,
+            invoker: T::AccountId,
+            provider: T::AccountId,
+            price: BalanceOf<T>,
+        },
+        WorkSubmitted {
+            invocation_id: InvocationId,
+            milestone_index: Option<u32>,
+        },
+        InvocationAccepted {
+            invocation_id: InvocationId,
+            provider: T::AccountId,
+        },
+        EcosystemFundShareSet {
+            share: Perbill,
+        },
+        /// Part of a milestone payout went to the ecosystem fund.
+        EcosystemContribution {
+            invocation_id: InvocationId,
+            provider: T::AccountId,
+            amount: BalanceOf<T>,
+        },
+        FundingRoundOpened {
+            round_id: u32,
+            ends_at: BlockNumberFor<T>,
+            projects: u32,
+        },
+        ProjectContribution {
+            round_id: u32,
+            project: T::AccountId,
+            contributor: T::AccountId,
+            amount: BalanceOf<T>,
+        },
+        MatchingFundsPaid {
+            round_id: u32,
+            project: T::AccountId,
+            amount: BalanceOf<T>,
+        },
+        FundingRoundFinalized {
+            round_id: u32,
+            matched: BalanceOf<T>,
+        },
+        /// `total` is the listing's bond after the deposit.
+        LivenessBondPosted {
+            listing_id: ListingId,
+            total: BalanceOf<T>,
+        },
+        LivenessBondWithdrawn {
+            listing_id: ListingId,
+            amount: BalanceOf<T>,
+        },
+        LivenessChallenged {
+            listing_id: ListingId,
+            challenger: T::AccountId,
+            deadline: BlockNumberFor<T>,
+        },
+        /// The provider answered in time and collected the challenge fee.
+        LivenessProven {
+            listing_id: ListingId,
+            challenger: T::AccountId,
+        },
+        LivenessBondSlashed {
+            listing_id: ListingId,
+            challenger: T::AccountId,
+            amount: BalanceOf<T>,
+        },
+        InvocationCapParamsSet {
+            params: Option<InvocationCapParams>,
+        },
+        /// The provider declined a pending invocation and the invoker was refunded.
+        InvocationRejected {
+            invocation_id: InvocationId,
+            provider: T::AccountId,
+            reason: BoundedVec<u8, T::MaxDescriptionLength>,
+        },
+        MilestoneApproved {
+            invocation_id: InvocationId,
+            milestone_index: u32,
+            amount_released: BalanceOf<T>,
+        },
+        InvocationFullyApproved {
+            invocation_id: InvocationId,
+            total_paid: BalanceOf<T>,
+        },
+        InvocationCancelled {
+            invocation_id: InvocationId,
+        },
+        ProgressReported {
+            invocation_id: InvocationId,
+            percent: u8,
+            proof_cid: Vec<u8>,
+        },
+        StalledInvocationCancelled {
+            invocation_id: InvocationId,
+            paid_to_provider: BalanceOf<T>,
+            refunded: BalanceOf<T>,
+        },
+        CancellationRequested {
+            invocation_id: InvocationId,
+            timeout_at: BlockNumberFor<T>,
+        },
+        InvocationCancelledInProgress {
+            invocation_id: InvocationId,
+            kill_fee: BalanceOf<T>,
+            refunded: BalanceOf<T>,
+            acknowledged: bool,
+        },
+        InvocationExpired {
+            invocation_id: InvocationId,
+            expired_by: T::AccountId,
+        },
+        DisputeRaised {
+            invocation_id: InvocationId,
+            dispute_id: DisputeId,
+            raised_by: T::AccountId,
+            tier: DisputeTier,
+            evidence_deadline: BlockNumberFor<T>,
+        },
+        DisputeEvidenceSubmitted {
+            dispute_id: DisputeId,
+            submitted_by: T::AccountId,
+        },
+        DisputeResolvedByGovernance {
+            dispute_id: DisputeId,
+            winner: T::AccountId,
+            amount: BalanceOf<T>,
+        },
+        /// A dispute's escrow was split between the parties rather than
+        /// paid to the winner.
+        DisputeSplitSettled {
+            dispute_id: DisputeId,
+            to_provider: BalanceOf<T>,
+            to_invoker: BalanceOf<T>,
+        },
+        /// The counterparty did not respond in time; the raiser won by default.
+        DisputeDefaultJudgment {
+            dispute_id: DisputeId,
+            winner: T::AccountId,
+            amount: BalanceOf<T>,
+        },
+        /// Governance took a dispute out of default judgment.
+        DisputeEscalated {
+            dispute_id: DisputeId,
+        },
+        ArbiterJoined {
+            who: T::AccountId,
+            stake: BalanceOf<T>,
+        },
+        ArbiterLeft {
+            who: T::AccountId,
+        },
+        /// A party took a dispute to an arbitration panel, paying `fee`.
+        ArbitrationStarted {
+            dispute_id: DisputeId,
+            escalated_by: T::AccountId,
+            panel_size: u32,
+            fee: BalanceOf<T>,
+            voting_deadline: BlockNumberFor<T>,
+        },
+        ArbitrationVoteCast {
+            dispute_id: DisputeId,
+            arbiter: T::AccountId,
+        },
+        /// A panel majority decided the dispute.
+        DisputeResolvedByArbitration {
+            dispute_id: DisputeId,
+            winner: T::AccountId,
+            amount: BalanceOf<T>,
+        },
+        /// The panel reached no majority in time; the dispute now waits for
+        /// governance.
+        ArbitrationExpired {
+            dispute_id: DisputeId,
+        },
+        /// The invoker recorded the off-chain payment of an X402 invocation.
+        X402PaymentRegistered {
+            invocation_id: InvocationId,
+            proof_hash: T::Hash,
+            amount: BalanceOf<T>,
+        },
+        /// A provider set or cleared the reputation it requires of X402 invokers.
+        X402MinReputationSet {
+            listing_id: ListingId,
+            min_reputation: Option<u32>,
+        },
+        /// A provider bond was reserved for a new listing.
+        ProviderBondLocked {
+            listing_id: ListingId,
+            amount: BalanceOf<T>,
+        },
+        /// A provider bond was slashed for a missed SLA or a lost dispute.
+        ProviderBondSlashed {
+            listing_id: ListingId,
+            invocation_id: InvocationId,
+            to_invoker: BalanceOf<T>,
+            to_treasury: BalanceOf<T>,
+        },
+        /// The remainder of a delisted listing's provider bond was returned.
+        ProviderBondReleased {
+            listing_id: ListingId,
+            amount: BalanceOf<T>,
+        },
+        /// A provider set or cleared a listing's referral terms.
+        ReferralTermsSet {
+            listing_id: ListingId,
+            terms: Option<ReferralTerms<BalanceOf<T>>>,
+        },
+        /// A referrer was paid its share of a milestone payout.
+        ReferralPaid {
+            invocation_id: InvocationId,
+            referrer: T::AccountId,
+            amount: BalanceOf<T>,
+        },
+        /// A subscriber escrowed `total` for a recurring subscription.
+        SubscriptionCreated {
+            subscription_id: SubscriptionId,
+            listing_id: ListingId,
+            subscriber: T::AccountId,
+            total: BalanceOf<T>,
+        },
+        /// A period's payment was released to the provider.
+        SubscriptionPaymentReleased {
+            subscription_id: SubscriptionId,
+            amount: BalanceOf<T>,
+            periods_remaining: u32,
+        },
+        /// Every period of a subscription was paid out.
+        SubscriptionCompleted {
+            subscription_id: SubscriptionId,
+        },
+        SubscriptionPaused {
+            subscription_id: SubscriptionId,
+        },
+        SubscriptionResumed {
+            subscription_id: SubscriptionId,
+            next_payment: BlockNumberFor<T>,
+        },
+        /// A subscription ended early: the provider was paid for the elapsed
+        /// part of the current period and the rest refunded.
+        SubscriptionCancelled {
+            subscription_id: SubscriptionId,
+            to_provider: BalanceOf<T>,
+            refunded: BalanceOf<T>,
+        },
+        /// The subscriber disputed a subscription, holding its payments.
+        SubscriptionDisputed {
+            subscription_id: SubscriptionId,
+        },
+        /// Governance split a disputed subscription's escrow.
+        SubscriptionDisputeResolved {
+            subscription_id: SubscriptionId,
+            to_provider: BalanceOf<T>,
+            refunded: BalanceOf<T>,
+        },
+        InvocationTemplateCreated {
+            template_id: TemplateId,
+            owner: T::AccountId,
+            listing_id: ListingId,
+        },
+        InvocationTemplateRemoved {
+            template_id: TemplateId,
+        },
+        StablePriceSet {
+            listing_id: ListingId,
+            reference_price: Option<u64>,
+        },
+        /// A provider was suspended and its active listings paused.
+        ProviderSuspended {
+            provider: T::AccountId,
+            paused_listings: u32,
+        },
+        /// A suspended provider's listings were restored, with or without a bond.
+        ProviderReactivated {
+            provider: T::AccountId,
+            bond: Option<BalanceOf<T>>,
+        },
+        ListingJurisdictionsSet {
+            listing_id: ListingId,
+            jurisdictions: Vec<JurisdictionCode>,
+        },
+        ReactivationBondReleased {
+            provider: T::AccountId,
+            amount: BalanceOf<T>,
+        },
+        SlotReserved {
+            listing_id: ListingId,
+            invoker: T::AccountId,
+            start_block: BlockNumberFor<T>,
+            end_block: BlockNumberFor<T>,
+            fee: BalanceOf<T>,
+        },
+        /// A reservation was used; its fee went towards the invocation's escrow.
+        SlotRedeemed {
+            listing_id: ListingId,
+            invocation_id: InvocationId,
+            start_block: BlockNumberFor<T>,
+        },
+        /// An unused reservation ended and its fee went to the provider.
+        SlotForfeited {
+            listing_id: ListingId,
+            invoker: T::AccountId,
+            start_block: BlockNumberFor<T>,
+            fee: BalanceOf<T>,
+        },
+        /// A reservation's fee was returned because the listing was delisted.
+        SlotReleased {
+            listing_id: ListingId,
+            invoker: T::AccountId,
+            start_block: BlockNumberFor<T>,
+        },
+        ListingApplicationSubmitted {
+            applicant: T::AccountId,
+            bond: BalanceOf<T>,
+        },
+        ApplicationVoted {
+            applicant: T::AccountId,
+            voter: T::AccountId,
+            approve: bool,
+        },
+        /// The applicant may now hold one provisional listing.
+        ListingApplicationApproved {
+            applicant: T::AccountId,
+        },
+        /// The application was rejected and its bond returned.
+        ListingApplicationRejected {
+            applicant: T::AccountId,
+        },
+        /// A provisional provider missed a deadline and paid part of its bond.
+        ProvisionalSlaPenalized {
+            provider: T::AccountId,
+            invocation_id: InvocationId,
+            amount: BalanceOf<T>,
+        },
+        /// A provisional provider completed probation; its bond was returned.
+        ProviderGraduated {
+            provider: T::AccountId,
+            bond_returned: BalanceOf<T>,
+        },
+        /// The invoker rejected a trial invocation and was refunded in full.
+        TrialRefunded {
+            invocation_id: InvocationId,
+            amount: BalanceOf<T>,
+        },
+        /// A listing's terms changed and were archived as a new version.
+        ListingTermsVersioned {
+            listing_id: ListingId,
+            version: u32,
+            terms_hash: T::Hash,
+        },
+        /// The invoker rated the provider while approving a milestone.
+        MilestoneReviewed {
+            invocation_id: InvocationId,
+            milestone_index: u32,
+            rating: u8,
+            comment_hash: T::Hash,
+        },
+        /// Several milestones of one invocation were approved in one call.
+        MilestonesApproved {
+            invocation_id: InvocationId,
+            count: u32,
+            amount_released: BalanceOf<T>,
+        },
+        /// Several pending invocations were cancelled in one call.
+        InvocationsCancelled {
+            invoker: T::AccountId,
+            count: u32,
+            amount_refunded: BalanceOf<T>,
+        },
+        /// A provider's payout controller was bound or rotated.
+        PayoutControllerSet {
+            provider: T::AccountId,
+            controller: T::AccountId,
+        },
+        PayoutChangeScheduled {
+            listing_id: ListingId,
+            payout_account: Option<T::AccountId>,
+            effective_at: BlockNumberFor<T>,
+        },
+        PayoutChangeApplied {
+            listing_id: ListingId,
+            payout_account: Option<T::AccountId>,
+        },
+        ProviderCapacitySet {
+            provider: T::AccountId,
+            max_concurrent_invocations: Option<u32>,
+            paused_until: Option<BlockNumberFor<T>>,
+        },
+        ListingFlagged {
+            listing_id: ListingId,
+            flagger: T::AccountId,
+            flags: u32,
+        },
+        /// A listing reached `FlagThreshold` flags and was paused for review.
+        ListingPausedForReview {
+            listing_id: ListingId,
+            flags: u32,
+        },
+        /// Moderation removed a listing and slashed its provider bond.
+        ListingTakenDown {
+            listing_id: ListingId,
+            bond_slashed: BalanceOf<T>,
+        },
+        /// Moderation cleared a listing; its flaggers forfeited their bonds.
+        ListingFlagsCleared {
+            listing_id: ListingId,
+            bonds_forfeited: BalanceOf<T>,
+        },
+        EscrowStaked {
+            invocation_id: InvocationId,
+            amount: BalanceOf<T>,
+        },
+        /// A yield position was closed and its reward split.
+        EscrowYieldPaid {
+            invocation_id: InvocationId,
+            principal: BalanceOf<T>,
+            to_invoker: BalanceOf<T>,
+            to_provider: BalanceOf<T>,
+        },
+        /// A yield position returned less than was staked; the escrow's
+        /// remaining payouts are scaled down pro rata.
+        EscrowYieldShortfall {
+            invocation_id: InvocationId,
+            principal: BalanceOf<T>,
+            shortfall: BalanceOf<T>,
+        },
+        CompetitionOpened {
+            competition_id: CompetitionId,
+            invoker: T::AccountId,
+            escrowed: BalanceOf<T>,
+        },
+        CompetitionEntered {
+            competition_id: CompetitionId,
+            provider: T::AccountId,
+            listing_id: ListingId,
+        },
+        CompetitionResultSubmitted {
+            competition_id: CompetitionId,
+            provider: T::AccountId,
+        },
+        /// The invoker picked a winner; `fees_paid` went to the other
+        /// submitters and the rest of the escrow was refunded.
+        CompetitionAwarded {
+            competition_id: CompetitionId,
+            winner: T::AccountId,
+            reward: BalanceOf<T>,
+            fees_paid: BalanceOf<T>,
+            refunded: BalanceOf<T>,
+        },
+        /// A competition ended without a winner; `paid` was split among the
+        /// submitters and the rest refunded.
+        CompetitionClosed {
+            competition_id: CompetitionId,
+            paid: BalanceOf<T>,
+            refunded: BalanceOf<T>,
+        },
+        BuildAttestorSet {
+            attestor: T::AccountId,
+            approved: bool,
+        },
+        BuildAttested {
+            code_hash: T::Hash,
+            attestor: T::AccountId,
+        },
+        ListingBuildBound {
+            listing_id: ListingId,
+            code_hash: T::Hash,
+            /// The listing had been paused by a revocation and is active again.
+            reactivated: bool,
+        },
+        /// A build attestation was revoked; its active listings were paused.
+        BuildAttestationRevoked {
+            code_hash: T::Hash,
+            paused_listings: u32,
+        },
+        AttestationRequirementSet {
+            invoker: T::AccountId,
+            required: bool,
+        },
+        /// A listing's allowlist was replaced; 0 accounts makes it public.
+        ListingAllowlistSet {
+            listing_id: ListingId,
+            accounts: u32,
+        },
+        DeferredSettlementSet {
+            provider: T::AccountId,
+            enabled: bool,
+        },
+        /// A settlement statement was paid out in one transfer.
+        SettlementPaid {
+            payee: T::AccountId,
+            total: BalanceOf<T>,
+            entries: BoundedVec<SettlementEntry<BalanceOf<T>>, T::MaxSettlementEntries>,
+        },
+        /// Governance set or cleared (`None`) a tag's insurance threshold.
+        TagInsuranceRequirementSet {
+            tag: BoundedVec<u8, T::MaxTagLength>,
+            threshold: Option<BalanceOf<T>>,
+        },
+    }
+
+    // =========================================================
+    // Errors
+    // =========================================================
+
+    #[pallet::error]
+    pub enum Error<T> {
+        ListingNotFound,
+        ListingNotActive,
+        NotProvider,
+        NotInvoker,
+        InsufficientReputation,
+        InvocationNotFound,
+        InvalidInvocationStatus,
+        MilestoneIndexOutOfBounds,
+        MilestoneAlreadyApproved,
+        MilestoneNotSubmitted,
+        /// Multi-milestone invocations take work per milestone.
+        MilestoneIndexRequired,
+        /// The provider has not accepted the invocation yet.
+        InvocationNotAccepted,
+        /// The listing's `sla_response_blocks` have passed since the invocation was created.
+        AcceptanceWindowClosed,
+        /// The listing does not take this payment mode.
+        PaymentModeNotAccepted,
+        /// Trial invocations are refunded from escrow, so cannot use X402.
+        TrialRequiresEscrow,
+        /// The invocation is not paid via X402.
+        NotX402Invocation,
+        /// An X402 payment is already recorded for the invocation.
+        X402PaymentAlreadyRegistered,
+        /// The recorded X402 payment is below the invocation price.
+        X402PaymentTooLow,
+        /// The provider cannot accept an X402 invocation before it is paid.
+        X402PaymentMissing,
+        /// The listing has no provider bond.
+        NoProviderBond,
+        /// The provider bond of an active listing cannot be withdrawn.
+        ListingStillActive,
+        /// The referral fee exceeds `MaxReferralFee`.
+        ReferralFeeTooHigh,
+        /// The listing offers no referral terms.
+        ReferralsNotAccepted,
+        /// The invoker or provider cannot refer its own invocation.
+        SelfReferral,
+        SubscriptionNotFound,
+        /// Only the subscriber may do this.
+        NotSubscriber,
+        /// Only the subscriber or the provider may do this.
+        NotSubscriptionParty,
+        /// Zero periods, zero-length periods or more than `MaxSubscriptionPeriods`.
+        InvalidSubscriptionTerms,
+        /// Providers cannot subscribe to their own listings.
+        SelfSubscription,
+        /// The subscription is not in a status that allows this.
+        InvalidSubscriptionStatus,
+        /// The account is already in the arbiter pool.
+        AlreadyArbiter,
+        /// The account is not in the arbiter pool.
+        NotArbiter,
+        /// The arbiter pool holds `MaxArbiters` agents.
+        ArbiterPoolFull,
+        /// The arbiter still sits on a panel.
+        ArbiterOnPanel,
+        /// Fewer eligible arbiters than the dispute track's panel size.
+        NotEnoughArbiters,
+        /// The dispute is not under arbitration.
+        NotInArbitration,
+        /// The caller is not on the dispute's panel.
+        NotOnPanel,
+        /// The panel's voting period has ended.
+        ArbitrationVotingClosed,
+        /// The winner must be the invoker or the provider.
+        InvalidDisputeWinner,
+        /// Above `MaxEcosystemFundShare`.
+        EcosystemFundShareTooHigh,
+        /// A funding round is already open.
+        FundingRoundActive,
+        /// No funding round is open.
+        NoFundingRound,
+        /// The funding round has ended; finalize it.
+        FundingRoundEnded,
+        /// The funding round is still accepting contributions.
+        FundingRoundNotEnded,
+        /// Rounds need between one and `MaxRoundProjects` distinct projects.
+        InvalidRoundProjects,
+        /// The account is not a project in the open round.
+        NotRoundProject,
+        /// Contributions must be non-zero.
+        ZeroContribution,
+        /// The listing has no liveness bond.
+        NoLivenessBond,
+        /// The listing already has an open liveness challenge.
+        LivenessChallengeOpen,
+        /// The listing has no open liveness challenge.
+        NoLivenessChallenge,
+        /// The liveness challenge's response window has passed.
+        LivenessDeadlinePassed,
+        /// The provider can still respond to the liveness challenge.
+        LivenessDeadlineNotReached,
+        /// Bonds must be non-zero.
+        ZeroBond,
+        MilestonePercentagesInvalid,
+        TooManyMilestones,
+        TooManyTags,
+        TagTooLong,
+        TooManyListingsForTag,
+        TooManyListingsForProvider,
+        TooManyActiveInvocations,
+        NameTooLong,
+        DescriptionTooLong,
+        CidTooLong,
+        AutoApproveDelayTooLong,
+        PriceBelowMinimum,
+        PriceAboveMaximum,
+        CannotCancelActiveInvocation,
+        DeadlineNotPassed,
+        ListingHasActiveInvocations,
+        InsufficientBalance,
+        DisputeNotFound,
+        DisputeNotEscalated,
+        InvocationAlreadyDisputed,
+        NotPartyToInvocation,
+        RequirementsEmpty,
+        TemplateNotFound,
+        NotTemplateOwner,
+        TooManyTemplates,
+        /// A template batch is empty or longer than `MaxTemplateBatch`.
+        InvalidTemplateBatch,
+        /// No oracle rate is available for a stable-priced listing.
+        OracleUnavailable,
+        /// The oracle rate is older than `MaxOracleAge`.
+        OracleStale,
+        /// The converted CLAW price exceeds the invoker's accepted amount.
+        StablePriceExceedsLimit,
+        /// A stable reference price must be non-zero.
+        ZeroStablePrice,
+        /// Converting the stable reference price to CLAW overflowed.
+        StablePriceOverflow,
+        /// The provider is suspended after repeated dispute losses.
+        ProviderSuspended,
+        ProviderNotSuspended,
+        NoReactivationBond,
+        TooManyJurisdictions,
+        /// Jurisdiction codes must be two uppercase ASCII letters.
+        InvalidJurisdictionCode,
+        JurisdictionIndexFull,
+        /// The listing does not serve the invoker's required jurisdiction.
+        JurisdictionMismatch,
+        /// Slot must start now or later and last 1..=`MaxSlotDuration` blocks.
+        InvalidSlotWindow,
+        /// The window overlaps, or the current block is inside, another invoker's slot.
+        SlotUnavailable,
+        TooManyReservations,
+        SlotNotFound,
+        /// The reservation window has not ended yet.
+        SlotNotEnded,
+        /// The dispute is no longer open.
+        DisputeNotOpen,
+        /// The dispute's evidence window has closed.
+        EvidenceWindowClosed,
+        /// Progress must be 1–100 and above the last checkpoint.
+        InvalidProgress,
+        TooManyCheckpoints,
+        /// The provider reported progress within `ProgressStallPeriod`.
+        ProgressNotStalled,
+        CancellationAlreadyRequested,
+        NoCancellationRequested,
+        /// `CancellationAckTimeout` has not passed since the request.
+        CancellationTimeoutNotReached,
+        /// The caller already meets `MinListingReputation` and can list directly.
+        ApplicationNotNeeded,
+        /// The caller already has a pending application or provisional slot.
+        ApplicationAlreadyExists,
+        ApplicationNotFound,
+        AlreadyVoted,
+        /// A provisional provider may hold only one listing.
+        ProvisionalSlotTaken,
+        /// Trial invocations are capped at `MaxTrialPrice`.
+        TrialPriceTooHigh,
+        /// Trial invocations cannot use milestones.
+        TrialMilestonesNotAllowed,
+        /// The invoker already used its trial on this listing.
+        TrialAlreadyUsed,
+        NotTrialInvocation,
+        /// `TrialRefundWindow` has passed.
+        TrialWindowClosed,
+        /// Ratings are 1 to 5 stars.
+        InvalidRating,
+        /// A batch call was given nothing to do.
+        EmptyBatch,
+        /// A batch exceeds `MaxMilestones` approvals or
+        /// `MaxActiveInvocationsPerAccount` cancellations.
+        BatchTooLarge,
+        /// The provider already has a payout controller; only it can rotate.
+        PayoutControllerAlreadyBound,
+        /// The caller is not the provider's payout controller.
+        NotPayoutController,
+        NoPendingPayoutChange,
+        /// `PayoutChangeDelay` has not passed since the change was scheduled.
+        PayoutChangeNotDue,
+        /// The provider has as many open invocations as its capacity allows.
+        ProviderAtCapacity,
+        /// The provider paused new invocations until a later block.
+        ProviderUnavailable,
+        /// The listing is paused pending moderation review.
+        ListingUnderReview,
+        /// The caller already has an open flag on this listing.
+        AlreadyFlagged,
+        /// The listing carries `MaxFlagsPerListing` open flags.
+        TooManyFlags,
+        FlagReasonTooLong,
+        /// Providers cannot flag their own listings.
+        CannotFlagOwnListing,
+        /// The listing has no open flags to review.
+        NoListingFlags,
+        /// The invocation's escrow is already staked.
+        EscrowAlreadyStaked,
+        /// The invocation has no escrow left to stake.
+        NothingToStake,
+        CompetitionNotFound,
+        /// Competitions need a reward, a deadline and 2 to
+        /// `MaxCompetitionEntrants` entrants.
+        InvalidCompetitionTerms,
+        /// The competition's deadline has passed.
+        CompetitionDeadlinePassed,
+        /// The listing is not tagged with the competition's tag.
+        ListingTagMismatch,
+        CannotEnterOwnCompetition,
+        AlreadyEntered,
+        CompetitionFull,
+        NotEntrant,
+        ResultAlreadySubmitted,
+        /// The chosen winner has not submitted a result.
+        NoSubmission,
+        /// The competition has entrants and its award period is still running.
+        CompetitionStillRunning,
+        /// The caller is not an approved attestor, or not the attestor of
+        /// this build.
+        NotBuildAttestor,
+        BuildAlreadyAttested,
+        BuildAttestationNotFound,
+        ToolchainTooLong,
+        BuilderSignatureTooLong,
+        /// The code hash is bound to `MaxListingsPerBuild` listings.
+        TooManyListingsForBuild,
+        /// The invoker requires attested listings and this one is not bound
+        /// to an attested build.
+        ListingNotAttested,
+        /// The listing is private and the caller is not on its allowlist.
+        NotOnAllowlist,
+        TooManyAllowlistEntries,
+        /// The caller has no open settlement statement.
+        NoSettlement,
+        /// The listing is in a tag that requires insurance at this price and
+        /// its provider has no active policy covering it.
+        InsuranceRequired,
+    }
+
+    // =========================================================
+    // Weight trait
+    // =========================================================
+
+    pub trait WeightInfo {
+        fn list_service() -> Weight;
+        fn update_listing() -> Weight;
+        fn delist_service() -> Weight;
+        fn invoke_service() -> Weight;
+        fn submit_invocation_work() -> Weight;
+        fn approve_milestone() -> Weight;
+        fn raise_dispute() -> Weight;
+        fn resolve_dispute_governance() -> Weight;
+        fn cancel_invocation() -> Weight;
+        fn try_expire_invocation() -> Weight;
+        fn create_invocation_template() -> Weight;
+        fn remove_invocation_template() -> Weight;
+        fn invoke_from_template() -> Weight;
+        fn set_stable_price() -> Weight;
+        fn post_reactivation_bond() -> Weight;
+        fn set_provider_suspension() -> Weight;
+        fn release_reactivation_bond() -> Weight;
+        fn set_listing_jurisdictions() -> Weight;
+        fn reserve_slot() -> Weight;
+        fn forfeit_slot() -> Weight;
+        fn submit_dispute_evidence() -> Weight;
+        fn report_progress() -> Weight;
+        fn cancel_stalled_invocation() -> Weight;
+        fn cancel_in_progress() -> Weight;
+        fn ack_cancellation() -> Weight;
+        fn finalize_cancellation() -> Weight;
+        fn apply_for_listing() -> Weight;
+        fn vote_on_application() -> Weight;
+        fn decide_application() -> Weight;
+        fn reject_trial() -> Weight;
+        fn accept_invocation() -> Weight;
+        fn set_ecosystem_fund_share() -> Weight;
+        fn open_funding_round() -> Weight;
+        fn contribute_to_project() -> Weight;
+        fn finalize_funding_round() -> Weight;
+        fn post_liveness_bond() -> Weight;
+        fn withdraw_liveness_bond() -> Weight;
+        fn challenge_liveness() -> Weight;
+        fn respond_to_liveness_challenge() -> Weight;
+        fn resolve_liveness_challenge() -> Weight;
+        fn set_invocation_cap_params() -> Weight;
+        fn reject_invocation() -> Weight;
+        fn escalate_dispute() -> Weight;
+        fn invoke_service_x402() -> Weight;
+        fn register_x402_payment() -> Weight;
+        fn set_x402_min_reputation() -> Weight;
+        fn withdraw_provider_bond() -> Weight;
+        fn set_referral_terms() -> Weight;
+        fn subscribe_service() -> Weight;
+        fn set_subscription_paused() -> Weight;
+        fn cancel_subscription() -> Weight;
+        fn dispute_subscription() -> Weight;
+        fn resolve_subscription_dispute() -> Weight;
+        fn join_arbiter_pool() -> Weight;
+        fn leave_arbiter_pool() -> Weight;
+        fn escalate_to_arbitration() -> Weight;
+        fn cast_arbitration_vote() -> Weight;
+        fn approve_and_review() -> Weight;
+        fn approve_milestones(n: u32) -> Weight;
+        fn batch_cancel(n: u32) -> Weight;
+        fn bind_payout_controller() -> Weight;
+        fn rotate_payout_controller() -> Weight;
+        fn schedule_payout_change() -> Weight;
+        fn apply_payout_change() -> Weight;
+        fn set_provider_capacity() -> Weight;
+        fn flag_listing() -> Weight;
+        fn resolve_listing_flags(n: u32) -> Weight;
+        fn stake_escrow() -> Weight;
+        fn open_competition() -> Weight;
+        fn enter_competition() -> Weight;
+        fn submit_competition_result() -> Weight;
+        fn award_competition(n: u32) -> Weight;
+        fn close_competition(n: u32) -> Weight;
+        fn set_build_attestor() -> Weight;
+        fn attest_build() -> Weight;
+        fn bind_build_attestation() -> Weight;
+        fn revoke_build_attestation(n: u32) -> Weight;
+        fn set_attestation_requirement() -> Weight;
+        fn set_listing_allowlist(n: u32) -> Weight;
+        fn set_deferred_settlement() -> Weight;
+        fn claim_settlement() -> Weight;
+        fn set_tag_insurance_requirement() -> Weight;
+    }
+
+    pub struct SubstrateWeight<T>(core::marker::PhantomData<T>);
+
+    impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+        fn list_service() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn update_listing() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn delist_service() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn invoke_service() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn submit_invocation_work() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn approve_milestone() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn raise_dispute() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn resolve_dispute_governance() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn cancel_invocation() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn try_expire_invocation() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn create_invocation_template() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn remove_invocation_template() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn invoke_from_template() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn set_stable_price() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn post_reactivation_bond() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn set_provider_suspension() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn release_reactivation_bond() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn set_listing_jurisdictions() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn reserve_slot() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn forfeit_slot() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn submit_dispute_evidence() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn report_progress() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn cancel_stalled_invocation() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn cancel_in_progress() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn ack_cancellation() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn finalize_cancellation() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn apply_for_listing() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn vote_on_application() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn decide_application() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn reject_trial() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn accept_invocation() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn set_ecosystem_fund_share() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn open_funding_round() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn contribute_to_project() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn finalize_funding_round() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn post_liveness_bond() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn withdraw_liveness_bond() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn challenge_liveness() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn respond_to_liveness_challenge() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn resolve_liveness_challenge() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn set_invocation_cap_params() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn reject_invocation() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn escalate_dispute() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn invoke_service_x402() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn register_x402_payment() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn set_x402_min_reputation() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn withdraw_provider_bond() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn set_referral_terms() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn subscribe_service() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn set_subscription_paused() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn cancel_subscription() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn dispute_subscription() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn resolve_subscription_dispute() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn join_arbiter_pool() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn leave_arbiter_pool() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn escalate_to_arbitration() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn cast_arbitration_vote() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn approve_and_review() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn approve_milestones(n: u32) -> Weight {
+            Self::approve_milestone().saturating_mul(n.into())
+        }
+        fn batch_cancel(n: u32) -> Weight {
+            Self::cancel_invocation().saturating_mul(n.into())
+        }
+        fn bind_payout_controller() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn rotate_payout_controller() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn schedule_payout_change() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn apply_payout_change() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn set_provider_capacity() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn flag_listing() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn resolve_listing_flags(n: u32) -> Weight {
+            Weight::from_parts(10_000, 0)
+                .saturating_add(Self::flag_listing().saturating_mul(n.into()))
+        }
+        fn stake_escrow() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn open_competition() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn enter_competition() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn submit_competition_result() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn award_competition(n: u32) -> Weight {
+            Weight::from_parts(10_000, 0)
+                .saturating_add(Weight::from_parts(5_000, 0).saturating_mul(n.into()))
+        }
+        fn close_competition(n: u32) -> Weight {
+            Weight::from_parts(10_000, 0)
+                .saturating_add(Weight::from_parts(5_000, 0).saturating_mul(n.into()))
+        }
+        fn set_build_attestor() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn attest_build() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn bind_build_attestation() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn revoke_build_attestation(n: u32) -> Weight {
+            Weight::from_parts(10_000, 0)
+                .saturating_add(Weight::from_parts(5_000, 0).saturating_mul(n.into()))
+        }
+        fn set_attestation_requirement() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn set_listing_allowlist(n: u32) -> Weight {
+            Weight::from_parts(10_000, 0)
+                .saturating_add(Weight::from_parts(1_000, 0).saturating_mul(n.into()))
+        }
+        fn set_deferred_settlement() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn claim_settlement() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn set_tag_insurance_requirement() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+    }
+
+    // =========================================================
+    // Extrinsics
+    // =========================================================
+
+    #[allow(clippy::too_many_arguments)]
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// (Index 10) Create a persistent service listing.
+        ///
+        /// The caller must have sufficient reputation (`MinListingReputation`)
+        /// or be a provisional provider without a listing yet.
+        #[pallet::call_index(10)]
+        #[pallet::weight(T::WeightInfo::list_service())]
+        pub fn list_service(
+            origin: OriginFor<T>,
+            name: Vec<u8>,
+            description: Vec<u8>,
+            tags: Vec<Vec<u8>>,
+            min_price: BalanceOf<T>,
+            max_price: BalanceOf<T>,
+            payment_mode: PaymentMode,
+            sla_response_blocks: u32,
+            sla_completion_blocks: u32,
+            auto_approve_delay_blocks: u32,
+            min_invoker_reputation: Option<u32>,
+            milestones_required: bool,
+        ) -> DispatchResult {
+            let provider = ensure_signed(origin)?;
+
+            ensure!(
+                !SuspendedProviders::<T>::contains_key(&provider),
+                Error::<T>::ProviderSuspended
+            );
+
+            // Reputation gate, or the single provisional slot
+            if !T::ReputationManager::meets_minimum_reputation(
+                &provider,
+                T::MinListingReputation::get(),
+            ) {
+                ensure!(
+                    ProvisionalProviders::<T>::contains_key(&provider),
+                    Error::<T>::InsufficientReputation
+                );
+                ensure!(
+                    ListingsByProvider::<T>::get(&provider).is_empty(),
+                    Error::<T>::ProvisionalSlotTaken
+                );
+            }
+
+            ensure!(
+                auto_approve_delay_blocks <= T::AutoApproveMaxDelay::get(),
+                Error::<T>::AutoApproveDelayTooLong
+            );
+
+            let name: BoundedVec<u8, T::MaxNameLength> =
+                name.try_into().map_err(|_| Error::<T>::NameTooLong)?;
+            let description: BoundedVec<u8, T::MaxDescriptionLength> = description
+                .try_into()
+                .map_err(|_| Error::<T>::DescriptionTooLong)?;
+
+            ensure!(
+                tags.len() <= T::MaxTagsPerListing::get() as usize,
+                Error::<T>::TooManyTags
+            );
+
+            let mut bounded_tags: BoundedVec<
+                BoundedVec<u8, T::MaxTagLength>,
+                T::MaxTagsPerListing,
+            > = BoundedVec::new();
+            for tag in &tags {
+                let bounded_tag: BoundedVec<u8, T::MaxTagLength> =
+                    tag.clone().try_into().map_err(|_| Error::<T>::TagTooLong)?;
+                bounded_tags
+                    .try_push(bounded_tag)
+                    .map_err(|_| Error::<T>::TooManyTags)?;
+            }
+
+            let listing_id = ListingCount::<T>::get();
+
+            let now = <frame_system::Pallet<T>>::block_number();
+
+            let listing = ServiceListing {
+                id: listing_id,
+                provider: provider.clone(),
+                name,
+                description,
+                tags: bounded_tags.clone(),
+                jurisdictions: BoundedVec::default(),
+                min_price,
+                max_price,
+                payment_mode,
+                sla_response_blocks,
+                sla_completion_blocks,
+                auto_approve_delay_blocks,
+                min_invoker_reputation,
+                milestones_required,
+                active: true,
+                created_at: now,
+                total_invocations: 0,
+                successful_invocations: 0,
+                payout_account: None,
+            };
+
+            let bond = T::ProviderBondAmount::get();
+            if !bond.is_zero() {
+                T::Currency::reserve(&provider, bond)
+                    .map_err(|_| Error::<T>::InsufficientBalance)?;
+                ProviderBonds::<T>::insert(listing_id, bond);
+            }
+
+            Self::record_listing_terms(&listing);
+            ServiceListings::<T>::insert(listing_id, listing);
+            ListingCount::<T>::put(listing_id + 1);
+
+            // Update indexes
+            ListingsByProvider::<T>::try_mutate(&provider, |ids| {
+                ids.try_push(listing_id)
+                    .map_err(|_| Error::<T>::TooManyListingsForProvider)
+            })?;
+
+            for tag in &bounded_tags {
+                ListingsByTag::<T>::try_mutate(tag, |ids| {
+                    ids.try_push(listing_id)
+                        .map_err(|_| Error::<T>::TooManyListingsForTag)
+                })?;
+            }
+
+            Self::deposit_event(Event::ServiceListed {
+                listing_id,
+                provider,
+                min_price,
+            });
+            if !bond.is_zero() {
+                Self::deposit_event(Event::ProviderBondLocked {
+                    listing_id,
+                    amount: bond,
+                });
+            }
+
+            Ok(())
+        }
+
+        /// (Index 11) Update listing metadata.
+        ///
+        /// Only the listing provider may update. Cannot update if there are active invocations.
+        #[pallet::call_index(11)]
+        #[pallet::weight(T::WeightInfo::update_listing())]
+        pub fn update_listing(
+            origin: OriginFor<T>,
+            listing_id: ListingId,
+            name: Option<Vec<u8>>,
+            description: Option<Vec<u8>>,
+            min_price: Option<BalanceOf<T>>,
+            max_price: Option<BalanceOf<T>>,
+            sla_response_blocks: Option<u32>,
+            sla_completion_blocks: Option<u32>,
+            auto_approve_delay_blocks: Option<u32>,
+        ) -> DispatchResult {
+            let provider = ensure_signed(origin)?;
+
+            ServiceListings::<T>::try_mutate(listing_id, |maybe_listing| {
+                let listing = maybe_listing.as_mut().ok_or(Error::<T>::ListingNotFound)?;
+                ensure!(listing.provider == provider, Error::<T>::NotProvider);
+
+                // Ensure no active invocations
+                let active_count = InvocationsByListing::<T>::iter_prefix(listing_id).count();
+                ensure!(active_count == 0, Error::<T>::ListingHasActiveInvocations);
+
+                if let Some(n) = name {
+                    listing.name = n.try_into().map_err(|_| Error::<T>::NameTooLong)?;
+                }
+                if let Some(d) = description {
+                    listing.description =
+                        d.try_into().map_err(|_| Error::<T>::DescriptionTooLong)?;
+                }
+                if let Some(p) = min_price {
+                    listing.min_price = p;
+                }
+                if let Some(p) = max_price {
+                    listing.max_price = p;
+                }
+                if let Some(s) = sla_response_blocks {
+                    listing.sla_response_blocks = s;
+                }
+                if let Some(s) = sla_completion_blocks {
+                    listing.sla_completion_blocks = s;
+                }
+                if let Some(d) = auto_approve_delay_blocks {
+                    ensure!(
+                        d <= T::AutoApproveMaxDelay::get(),
+                        Error::<T>::AutoApproveDelayTooLong
+                    );
+                    listing.auto_approve_delay_blocks = d;
+                }
+
+                Self::record_listing_terms(listing);
+                Ok::<(), DispatchError>(())
+            })?;
+
+            Self::deposit_event(Event::ListingUpdated { listing_id });
+            Ok(())
+        }
+
+        /// (Index 12) Deactivate a service listing.
+        #[pallet::call_index(12)]
+        #[pallet::weight(T::WeightInfo::delist_service())]
+        pub fn delist_service(origin: OriginFor<T>, listing_id: ListingId) -> DispatchResult {
+            let provider = ensure_signed(origin)?;
+
+            ServiceListings::<T>::try_mutate(listing_id, |maybe_listing| {
+                let listing = maybe_listing.as_mut().ok_or(Error::<T>::ListingNotFound)?;
+                ensure!(listing.provider == provider, Error::<T>::NotProvider);
+                ensure!(
+                    !ListingsUnderReview::<T>::contains_key(listing_id),
+                    Error::<T>::ListingUnderReview
+                );
+                listing.active = false;
+                Ok::<(), DispatchError>(())
+            })?;
+
+            let listing =
+                ServiceListings::<T>::get(listing_id).ok_or(Error::<T>::ListingNotFound)?;
+            Self::unindex_listing(&listing);
+            StablePrices::<T>::remove(listing_id);
+
+            Self::deposit_event(Event::ServiceDelisted { listing_id });
+            Ok(())
+        }
+
+        /// (Index 13) Invoke a service listing (escrow path).
+        ///
+        /// Locks `agreed_price` in the pallet's escrow sub-account derived from
+        /// the invocation ID. Provider must accept before work starts.
+        /// If `required_jurisdiction` is set, the listing must declare it.
+        /// A `trial` invocation is capped at `MaxTrialPrice`, limited to one
+        /// per invoker per listing, and refundable via `reject_trial`.
+        /// A `referrer` is paid the listing's referral fee from each
+        /// milestone payout.
+        #[pallet::call_index(13)]
+        #[pallet::weight(T::WeightInfo::invoke_service())]
+        pub fn invoke_service(
+            origin: OriginFor<T>,
+            listing_id: ListingId,
+            requirements: Vec<u8>,
+            milestones: Option<Vec<MilestoneSpec>>,
+            agreed_price: BalanceOf<T>,
+            deadline_blocks: u32,
+            required_jurisdiction: Option<JurisdictionCode>,
+            trial: bool,
+            referrer: Option<T::AccountId>,
+        ) -> DispatchResult {
+            let invoker = ensure_signed(origin)?;
+
+            Self::do_invoke_service(
+                invoker,
+                listing_id,
+                requirements,
+                milestones,
+                agreed_price,
+                deadline_blocks,
+                required_jurisdiction,
+                trial,
+                PaymentMode::Escrow,
+                referrer,
+            )
+            .map(|_| ())
+        }
+
+        /// (Index 18) Provider submits work proof for an invocation.
+        #[pallet::call_index(18)]
+        #[pallet::weight(T::WeightInfo::submit_invocation_work())]
+        pub fn submit_invocation_work(
+            origin: OriginFor<T>,
+            invocation_id: InvocationId,
+            milestone_index: Option<u32>,
+            proof_cid: Vec<u8>,
+            proof_type: ProofType,
+        ) -> DispatchResult {
+            let provider = ensure_signed(origin)?;
+
+            let proof_cid: BoundedVec<u8, T::MaxCidLength> =
+                proof_cid.try_into().map_err(|_| Error::<T>::CidTooLong)?;
+
+            ServiceInvocations::<T>::try_mutate(invocation_id, |maybe| {
+                let inv = maybe.as_mut().ok_or(Error::<T>::InvocationNotFound)?;
+                ensure!(inv.provider == provider, Error::<T>::NotProvider);
+                ensure!(
+                    inv.status != InvocationStatus::Pending,
+                    Error::<T>::InvocationNotAccepted
+                );
+                ensure!(
+                    matches!(
+                        inv.status,
+                        InvocationStatus::Accepted | InvocationStatus::InProgress
+                    ),
+                    Error::<T>::InvalidInvocationStatus
+                );
+
+                let now = <frame_system::Pallet<T>>::block_number();
+
+                match milestone_index {
+                    Some(idx) => {
+                        let ms = inv
+                            .milestones
+                            .get_mut(idx as usize)
+                            .ok_or(Error::<T>::MilestoneIndexOutOfBounds)?;
+                        ensure!(
+                            !matches!(ms.status, MilestoneStatus::Approved),
+                            Error::<T>::MilestoneAlreadyApproved
+                        );
+                        ms.status = MilestoneStatus::Submitted;
+                        ms.submitted_at = Some(now);
+                    }
+                    None => ensure!(
+                        inv.milestones.is_empty(),
+                        Error::<T>::MilestoneIndexRequired
+                    ),
+                }
+
+                // Final submission: no milestone is left pending.
+                let all_submitted = inv
+                    .milestones
+                    .iter()
+                    .all(|ms| !matches!(ms.status, MilestoneStatus::Pending));
+                if all_submitted {
+                    inv.status = InvocationStatus::WorkSubmitted;
+                    inv.completed_at = Some(now);
+                } else {
+                    inv.status = InvocationStatus::InProgress;
+                }
+
+                Ok::<(), DispatchError>(())
+            })?;
+
+            let now = <frame_system::Pallet<T>>::block_number();
+            let proof = WorkProof {
+                proof_cid,
+                proof_type,
+                submitted_at: now,
+            };
+            let key = milestone_index.unwrap_or(u32::MAX);
+            InvocationProofs::<T>::insert(invocation_id, key, proof);
+
+            Self::deposit_event(Event::WorkSubmitted {
+                invocation_id,
+                milestone_index,
+            });
+
+            Ok(())
+        }
+
+        /// (Index 19) Invoker approves a milestone and releases partial escrow.
+        #[pallet::call_index(19)]
+        #[pallet::weight(T::WeightInfo::approve_milestone())]
+        pub fn approve_milestone(
+            origin: OriginFor<T>,
+            invocation_id: InvocationId,
+            milestone_index: u32,
+        ) -> DispatchResult {
+            let invoker = ensure_signed(origin)?;
+
+            Self::do_approve_milestone(&invoker, invocation_id, milestone_index).map(|_| ())
+        }
+
+        /// (Index 20) Raise a dispute on an invocation.
+        #[pallet::call_index(20)]
+        #[pallet::weight(T::WeightInfo::raise_dispute())]
+        pub fn raise_dispute(
+            origin: OriginFor<T>,
+            invocation_id: InvocationId,
+            reason: Vec<u8>,
+            evidence_cid: Option<Vec<u8>>,
+        ) -> DispatchResult {
+            let caller = ensure_signed(origin)?;
+
+            let reason: BoundedVec<u8, T::MaxDescriptionLength> = reason
+                .try_into()
+                .map_err(|_| Error::<T>::DescriptionTooLong)?;
+
+            let evidence: Option<BoundedVec<u8, T::MaxCidLength>> = evidence_cid
+                .map(|c| c.try_into().map_err(|_| Error::<T>::CidTooLong))
+                .transpose()?;
+
+            let (price, listing_id) =
+                ServiceInvocations::<T>::try_mutate(invocation_id, |maybe| {
+                    let inv = maybe.as_mut().ok_or(Error::<T>::InvocationNotFound)?;
+
+                    ensure!(
+                        inv.invoker == caller || inv.provider == caller,
+                        Error::<T>::NotPartyToInvocation
+                    );
+                    ensure!(
+                        !matches!(inv.status, InvocationStatus::Disputed),
+                        Error::<T>::InvocationAlreadyDisputed
+                    );
+                    ensure!(
+                        matches!(
+                            inv.status,
+                            InvocationStatus::Pending
+                                | InvocationStatus::Accepted
+                                | InvocationStatus::InProgress
+                                | InvocationStatus::WorkSubmitted
+                        ),
+                        Error::<T>::InvalidInvocationStatus
+                    );
+
+                    inv.status = InvocationStatus::Disputed;
+                    Ok::<_, DispatchError>((inv.price, inv.listing_id))
+                })?;
+            ListingDisputeStats::<T>::mutate(listing_id, |stats| {
+                stats.raised = stats.raised.saturating_add(1);
+            });
+
+            let dispute_id = DisputeCount::<T>::get();
+            let now = <frame_system::Pallet<T>>::block_number();
+            let tier = Self::dispute_tier(price);
+            let evidence_deadline =
+                now.saturating_add(Self::dispute_track(tier).evidence_window.into());
+
+            let dispute = DisputeRecord {
+                id: dispute_id,
+                invocation_id,
+                raised_by: caller.clone(),
+                reason,
+                evidence_cid: evidence,
+                status: DisputeStatus::Open,
+                raised_at: now,
+                winner: None,
+                tier,
+                evidence_deadline,
+            };
+
+            Disputes::<T>::insert(dispute_id, dispute);
+            DisputeCount::<T>::put(dispute_id + 1);
+            let response_deadline = now.saturating_add(T::DisputeResponseWindow::get().into());
+            DefaultJudgmentDeadlines::<T>::insert(dispute_id, response_deadline);
+            DefaultJudgmentsByDeadline::<T>::insert(response_deadline, dispute_id, ());
+
+            Self::deposit_event(Event::DisputeRaised {
+                invocation_id,
+                dispute_id,
+                raised_by: caller,
+                tier,
+                evidence_deadline,
+            });
+
+            Ok(())
+        }
+
+        /// (Index 23) Governance resolves an escalated dispute. With a
+        /// `provider_share`, the unreleased escrow is split: that share to
+        /// the provider and the rest back to the invoker. `winner` still
+        /// decides the reputation outcome.
+        #[pallet::call_index(23)]
+        #[pallet::weight(T::WeightInfo::resolve_dispute_governance())]
+        pub fn resolve_dispute_governance(
+            origin: OriginFor<T>,
+            dispute_id: DisputeId,
+            winner: T::AccountId,
+            provider_share: Option<Perbill>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            // Governance can always resolve, overriding any arbitration panel.
+            Self::close_arbitration(dispute_id, None);
+            let amount = Self::settle_dispute(dispute_id, &winner, provider_share)?;
+
+            Self::deposit_event(Event::DisputeResolvedByGovernance {
+                dispute_id,
+                winner,
+                amount,
+            });
+
+            Ok(())
+        }
+
+        /// (Index 26) Cancel a pending invocation and refund escrow.
+        #[pallet::call_index(26)]
+        #[pallet::weight(T::WeightInfo::cancel_invocation())]
+        pub fn cancel_invocation(
+            origin: OriginFor<T>,
+            invocation_id: InvocationId,
+        ) -> DispatchResult {
+            let invoker = ensure_signed(origin)?;
+
+            Self::do_cancel_invocation(&invoker, invocation_id).map(|_| ())
+        }
+
+        /// (Index 27) Permissionless: trigger expiry of a deadline-passed invocation.
+        ///
+        /// Caller receives the expiry bounty (see [`Pallet::expire_bounty`])
+        /// from the escrow as reward.
+        #[pallet::call_index(27)]
+        #[pallet::weight(T::WeightInfo::try_expire_invocation())]
+        pub fn try_expire_invocation(
+            origin: OriginFor<T>,
+            invocation_id: InvocationId,
+        ) -> DispatchResult {
+            let caller = ensure_signed(origin)?;
+
+            let now = <frame_system::Pallet<T>>::block_number();
+
+            let (invoker, provider, listing_id) =
+                ServiceInvocations::<T>::try_mutate(invocation_id, |maybe| {
+                    let inv = maybe.as_mut().ok_or(Error::<T>::InvocationNotFound)?;
+                    ensure!(
+                        matches!(
+                            inv.status,
+                            InvocationStatus::Pending
+                                | InvocationStatus::Accepted
+                                | InvocationStatus::InProgress
+                        ),
+                        Error::<T>::InvalidInvocationStatus
+                    );
+                    ensure!(inv.deadline < now, Error::<T>::DeadlineNotPassed);
+                    inv.status = InvocationStatus::Expired;
+                    Ok::<_, DispatchError>((
+                        inv.invoker.clone(),
+                        inv.provider.clone(),
+                        inv.listing_id,
+                    ))
+                })?;
+
+            Self::close_yield_position(invocation_id);
+            Self::settle_expired_escrow(invocation_id, &invoker, &caller);
+            Self::cleanup_invocation(invocation_id);
+            Self::penalize_provisional_sla_miss(&provider, &invoker, invocation_id);
+            Self::slash_provider_bond(listing_id, &provider, &invoker, invocation_id);
+
+            Self::deposit_event(Event::InvocationExpired {
+                invocation_id,
+                expired_by: caller,
+            });
+
+            Ok(())
+        }
+
+        /// (Index 28) Store reusable invocation parameters for a listing.
+        ///
+        /// Milestone percentages are validated up front so that
+        /// `invoke_from_template` only has to lock escrow. `requirements`
+        /// may be left empty if each invocation will supply its own.
+        #[pallet::call_index(28)]
+        #[pallet::weight(T::WeightInfo::create_invocation_template())]
+        pub fn create_invocation_template(
+            origin: OriginFor<T>,
+            listing_id: ListingId,
+            price: BalanceOf<T>,
+            milestones: Option<Vec<MilestoneSpec>>,
+            deadline_blocks: u32,
+            requirements: Vec<u8>,
+        ) -> DispatchResult {
+            let owner = ensure_signed(origin)?;
+
+            ensure!(
+                ServiceListings::<T>::contains_key(listing_id),
+                Error::<T>::ListingNotFound
+            );
+            let requirements: BoundedVec<u8, T::MaxDescriptionLength> = requirements
+                .try_into()
+                .map_err(|_| Error::<T>::DescriptionTooLong)?;
+
+            let milestones = milestones.unwrap_or_default();
+            Self::build_milestones(Some(milestones.clone()))?;
+            let milestones: BoundedVec<MilestoneSpec, T::MaxMilestones> = milestones
+                .try_into()
+                .map_err(|_| Error::<T>::TooManyMilestones)?;
+
+            let template_id = TemplateCount::<T>::get();
+
+            TemplatesByOwner::<T>::try_mutate(&owner, |ids| {
+                ids.try_push(template_id)
+                    .map_err(|_| Error::<T>::TooManyTemplates)
+            })?;
+
+            let template = InvocationTemplate {
+                id: template_id,
+                owner: owner.clone(),
+                listing_id,
+                price,
+                milestones,
+                deadline_blocks,
+                created_at: <frame_system::Pallet<T>>::block_number(),
+                requirements,
+            };
+
+            InvocationTemplates::<T>::insert(template_id, template);
+            TemplateCount::<T>::put(template_id + 1);
+
+            Self::deposit_event(Event::InvocationTemplateCreated {
+                template_id,
+                owner,
+                listing_id,
+            });
+
+            Ok(())
+        }
+
+        /// (Index 29) Remove one of the caller's invocation templates.
+        #[pallet::call_index(29)]
+        #[pallet::weight(T::WeightInfo::remove_invocation_template())]
+        pub fn remove_invocation_template(
+            origin: OriginFor<T>,
+            template_id: TemplateId,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let template =
+                InvocationTemplates::<T>::get(template_id).ok_or(Error::<T>::TemplateNotFound)?;
+            ensure!(template.owner == who, Error::<T>::NotTemplateOwner);
+
+            InvocationTemplates::<T>::remove(template_id);
+            TemplatesByOwner::<T>::mutate(&who, |ids| {
+                ids.retain(|&id| id != template_id);
+            });
+
+            Self::deposit_event(Event::InvocationTemplateRemoved { template_id });
+
+            Ok(())
+        }
+
+        /// (Index 30) Invoke a listing using a stored template.
+        ///
+        /// Listing, price and milestone layout come from the template;
+        /// `requirements_cid` and `deadline_blocks` override the template's
+        /// defaults when given. Listing activity, price bounds and reputation
+        /// gates are re-checked.
+        ///
+        /// Templates belong to an account, so every agent the account
+        /// operates invokes from the same set. `invoke_from_template_batch`
+        /// creates several invocations from one template in a single call.
+        #[pallet::call_index(30)]
+        #[pallet::weight(T::WeightInfo::invoke_from_template())]
+        pub fn invoke_from_template(
+            origin: OriginFor<T>,
+            template_id: TemplateId,
+            requirements_cid: Option<Vec<u8>>,
+            deadline_blocks: Option<u32>,
+        ) -> DispatchResult {
+            let invoker = ensure_signed(origin)?;
+
+            let template =
+                InvocationTemplates::<T>::get(template_id).ok_or(Error::<T>::TemplateNotFound)?;
+            ensure!(template.owner == invoker, Error::<T>::NotTemplateOwner);
+            let requirements =
+                requirements_cid.unwrap_or_else(|| template.requirements.into_inner());
+            ensure!(!requirements.is_empty(), Error::<T>::RequirementsEmpty);
+
+            Self::do_invoke_service(
+                invoker,
+                template.listing_id,
+                requirements,
+                Some(template.milestones.into_inner()),
+                template.price,
+                deadline_blocks.unwrap_or(template.deadline_blocks),
+                None,
+                false,
+                PaymentMode::Escrow,
+                None,
+            )
+            .map(|_| ())
+        }
+
+        /// (Index 31) Set or clear a listing's stable reference price.
+        ///
+        /// While set, invocations lock `reference_price * oracle_rate` CLAW
+        /// instead of the invoker-supplied amount, which then acts as the
+        /// invoker's upper bound. The converted amount must still fall within
+        /// the listing's CLAW price bounds. Settlement always uses the locked
+        /// amount. Delisting clears the stable price.
+        #[pallet::call_index(31)]
+        #[pallet::weight(T::WeightInfo::set_stable_price())]
+        pub fn set_stable_price(
+            origin: OriginFor<T>,
+            listing_id: ListingId,
+            reference_price: Option<u64>,
+        ) -> DispatchResult {
+            let provider = ensure_signed(origin)?;
+
+            let listing =
+                ServiceListings::<T>::get(listing_id).ok_or(Error::<T>::ListingNotFound)?;
+            ensure!(listing.provider == provider, Error::<T>::NotProvider);
+            ensure!(reference_price != Some(0), Error::<T>::ZeroStablePrice);
+
+            StablePrices::<T>::set(listing_id, reference_price);
+            Self::record_listing_terms(&listing);
+
+            Self::deposit_event(Event::StablePriceSet {
+                listing_id,
+                reference_price,
+            });
+
+            Ok(())
+        }
+
+        /// (Index 32) Reserve `ReactivationBond` to lift the caller's suspension.
+        ///
+        /// Listings paused by the suspension are reactivated and the loss
+        /// window restarts. The bond stays reserved until governance releases it.
+        #[pallet::call_index(32)]
+        #[pallet::weight(T::WeightInfo::post_reactivation_bond())]
+        pub fn post_reactivation_bond(origin: OriginFor<T>) -> DispatchResult {
+            let provider = ensure_signed(origin)?;
+
+            ensure!(
+                SuspendedProviders::<T>::contains_key(&provider),
+                Error::<T>::ProviderNotSuspended
+            );
+
+            let bond = T::ReactivationBond::get();
+            T::Currency::reserve(&provider, bond).map_err(|_| Error::<T>::InsufficientBalance)?;
+            ReactivationBonds::<T>::mutate(&provider, |held| {
+                *held = Some(held.unwrap_or_default().saturating_add(bond));
+            });
+
+            Self::reactivate_provider(&provider, Some(bond));
+
+            Ok(())
+        }
+
+        /// (Index 33) Governance override: suspend or reinstate a provider.
+        ///
+        /// Reinstating does not require a bond.
+        #[pallet::call_index(33)]
+        #[pallet::weight(T::WeightInfo::set_provider_suspension())]
+        pub fn set_provider_suspension(
+            origin: OriginFor<T>,
+            provider: T::AccountId,
+            suspended: bool,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            let is_suspended = SuspendedProviders::<T>::contains_key(&provider);
+            if suspended {
+                ensure!(!is_suspended, Error::<T>::ProviderSuspended);
+                Self::suspend_provider(&provider);
+            } else {
+                ensure!(is_suspended, Error::<T>::ProviderNotSuspended);
+                Self::reactivate_provider(&provider, None);
+            }
+
+            Ok(())
+        }
+
+        /// (Index 34) Governance returns a provider's reserved reactivation bond.
+        #[pallet::call_index(34)]
+        #[pallet::weight(T::WeightInfo::release_reactivation_bond())]
+        pub fn release_reactivation_bond(
+            origin: OriginFor<T>,
+            provider: T::AccountId,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            let amount =
+                ReactivationBonds::<T>::take(&provider).ok_or(Error::<T>::NoReactivationBond)?;
+            T::Currency::unreserve(&provider, amount);
+
+            Self::deposit_event(Event::ReactivationBondReleased { provider, amount });
+
+            Ok(())
+        }
+
+        /// (Index 35) Replace the jurisdictions a listing declares it serves.
+        ///
+        /// An empty list clears the declaration. The jurisdiction index is
+        /// updated to match.
+        #[pallet::call_index(35)]
+        #[pallet::weight(T::WeightInfo::set_listing_jurisdictions())]
+        pub fn set_listing_jurisdictions(
+            origin: OriginFor<T>,
+            listing_id: ListingId,
+            jurisdictions: Vec<JurisdictionCode>,
+        ) -> DispatchResult {
+            let provider = ensure_signed(origin)?;
+
+            let mut listing =
+                ServiceListings::<T>::get(listing_id).ok_or(Error::<T>::ListingNotFound)?;
+            ensure!(listing.provider == provider, Error::<T>::NotProvider);
+
+            let mut codes = jurisdictions;
+            codes.sort();
+            codes.dedup();
+            ensure!(
+                codes
+                    .iter()
+                    .all(|code| code.iter().all(|b| b.is_ascii_uppercase())),
+                Error::<T>::InvalidJurisdictionCode
+            );
+            let bounded: BoundedVec<JurisdictionCode, T::MaxJurisdictionsPerListing> = codes
+                .clone()
+                .try_into()
+                .map_err(|_| Error::<T>::TooManyJurisdictions)?;
+
+            for code in &listing.jurisdictions {
+                ListingsByJurisdiction::<T>::mutate(code, |ids| {
+                    ids.retain(|&id| id != listing_id);
+                });
+            }
+            if listing.active {
+                for code in &bounded {
+                    ListingsByJurisdiction::<T>::try_mutate(code, |ids| {
+                        ids.try_push(listing_id)
+                            .map_err(|_| Error::<T>::JurisdictionIndexFull)
+                    })?;
+                }
+            }
+
+            listing.jurisdictions = bounded;
+            ServiceListings::<T>::insert(listing_id, listing);
+
+            Self::deposit_event(Event::ListingJurisdictionsSet {
+                listing_id,
+                jurisdictions: codes,
+            });
+
+            Ok(())
+        }
+
+        /// (Index 36) Book `[start_block, start_block + duration)` on a listing.
+        ///
+        /// Reserves `SlotReservationFee` from the caller. While the window is
+        /// open only the caller may invoke the listing; doing so releases the
+        /// fee into the escrowed price. An unused reservation is forfeited to
+        /// the provider once its window ends.
+        #[pallet::call_index(36)]
+        #[pallet::weight(T::WeightInfo::reserve_slot())]
+        pub fn reserve_slot(
+            origin: OriginFor<T>,
+            listing_id: ListingId,
+            start_block: BlockNumberFor<T>,
+            duration: u32,
+        ) -> DispatchResult {
+            let invoker = ensure_signed(origin)?;
+
+            let listing =
+                ServiceListings::<T>::get(listing_id).ok_or(Error::<T>::ListingNotFound)?;
+            ensure!(listing.active, Error::<T>::ListingNotActive);
+
+            let now = <frame_system::Pallet<T>>::block_number();
+            ensure!(
+                start_block >= now && duration > 0 && duration <= T::MaxSlotDuration::get(),
+                Error::<T>::InvalidSlotWindow
+            );
+            let end_block = start_block.saturating_add(duration.into());
+            let fee = T::SlotReservationFee::get();
+
+            ListingReservations::<T>::try_mutate(listing_id, |slots| {
+                ensure!(
+                    !slots
+                        .iter()
+                        .any(|s| start_block < s.end_block && s.start_block < end_block),
+                    Error::<T>::SlotUnavailable
+                );
+                slots
+                    .try_push(SlotReservation {
+                        invoker: invoker.clone(),
+                        start_block,
+                        end_block,
+                        fee,
+                    })
+                    .map_err(|_| Error::<T>::TooManyReservations)?;
+                Ok::<(), DispatchError>(())
+            })?;
+
+            T::Currency::reserve(&invoker, fee).map_err(|_| Error::<T>::InsufficientBalance)?;
+
+            Self::deposit_event(Event::SlotReserved {
+                listing_id,
+                invoker,
+                start_block,
+                end_block,
+                fee,
+            });
+            Ok(())
+        }
+
+        /// (Index 37) Permissionless: forfeit an unused reservation whose window
+        /// has ended, paying its fee to the provider.
+        #[pallet::call_index(37)]
+        #[pallet::weight(T::WeightInfo::forfeit_slot())]
+        pub fn forfeit_slot(
+            origin: OriginFor<T>,
+            listing_id: ListingId,
+            start_block: BlockNumberFor<T>,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+
+            let listing =
+                ServiceListings::<T>::get(listing_id).ok_or(Error::<T>::ListingNotFound)?;
+            let now = <frame_system::Pallet<T>>::block_number();
+
+            let slot = ListingReservations::<T>::try_mutate(listing_id, |slots| {
+                let pos = slots
+                    .iter()
+                    .position(|s| s.start_block == start_block)
+                    .ok_or(Error::<T>::SlotNotFound)?;
+                ensure!(now >= slots[pos].end_block, Error::<T>::SlotNotEnded);
+                Ok::<_, DispatchError>(slots.remove(pos))
+            })?;
+
+            T::Currency::repatriate_reserved(
+                &slot.invoker,
+                &listing.provider,
+                slot.fee,
+                BalanceStatus::Free,
+            )?;
+
+            Self::deposit_event(Event::SlotForfeited {
+                listing_id,
+                invoker: slot.invoker,
+                start_block,
+                fee: slot.fee,
+            });
+            Ok(())
+        }
+
+        /// (Index 38) Replace a dispute's evidence CID. Either party may call
+        /// this while the dispute is open and its evidence window has not closed.
+        /// A submission by the party that did not raise the dispute counts as
+        /// its response and cancels default judgment.
+        #[pallet::call_index(38)]
+        #[pallet::weight(T::WeightInfo::submit_dispute_evidence())]
+        pub fn submit_dispute_evidence(
+            origin: OriginFor<T>,
+            dispute_id: DisputeId,
+            evidence_cid: Vec<u8>,
+        ) -> DispatchResult {
+            let caller = ensure_signed(origin)?;
+
+            let evidence: BoundedVec<u8, T::MaxCidLength> = evidence_cid
+                .try_into()
+                .map_err(|_| Error::<T>::CidTooLong)?;
+            let now = <frame_system::Pallet<T>>::block_number();
+
+            Disputes::<T>::try_mutate(dispute_id, |maybe| {
+                let dispute = maybe.as_mut().ok_or(Error::<T>::DisputeNotFound)?;
+                ensure!(
+                    matches!(
+                        dispute.status,
+                        DisputeStatus::Open | DisputeStatus::Arbitration
+                    ),
+                    Error::<T>::DisputeNotOpen
+                );
+                ensure!(
+                    now <= dispute.evidence_deadline,
+                    Error::<T>::EvidenceWindowClosed
+                );
+
+                let inv = ServiceInvocations::<T>::get(dispute.invocation_id)
+                    .ok_or(Error::<T>::InvocationNotFound)?;
+                ensure!(
+                    inv.invoker == caller || inv.provider == caller,
+                    Error::<T>::NotPartyToInvocation
+                );
+
+                dispute.evidence_cid = Some(evidence);
+                // The counterparty has responded: no default judgment.
+                if dispute.raised_by != caller {
+                    Self::clear_default_judgment(dispute_id);
+                }
+                Ok::<(), DispatchError>(())
+            })?;
+
+            Self::deposit_event(Event::DisputeEvidenceSubmitted {
+                dispute_id,
+                submitted_by: caller,
+            });
+            Ok(())
+        }
+
+        /// (Index 39) Provider appends a progress checkpoint to an in-flight
+        /// invocation, moving it to `InProgress`.
+        ///
+        /// At most `MaxProgressCheckpoints` per invocation, strictly
+        /// increasing in percent. If none arrives for `ProgressStallPeriod`
+        /// blocks, the invoker may `cancel_stalled_invocation`, paying the
+        /// last reported percentage of the price.
+        #[pallet::call_index(39)]
+        #[pallet::weight(T::WeightInfo::report_progress())]
+        pub fn report_progress(
+            origin: OriginFor<T>,
+            invocation_id: InvocationId,
+            percent: u8,
+            proof_cid: Vec<u8>,
+        ) -> DispatchResult {
+            let provider = ensure_signed(origin)?;
+
+            let bounded_cid: BoundedVec<u8, T::MaxCidLength> = proof_cid
+                .clone()
+                .try_into()
+                .map_err(|_| Error::<T>::CidTooLong)?;
+
+            ServiceInvocations::<T>::try_mutate(invocation_id, |maybe| {
+                let inv = maybe.as_mut().ok_or(Error::<T>::InvocationNotFound)?;
+                ensure!(inv.provider == provider, Error::<T>::NotProvider);
+                ensure!(
+                    inv.status != InvocationStatus::Pending,
+                    Error::<T>::InvocationNotAccepted
+                );
+                ensure!(
+                    matches!(
+                        inv.status,
+                        InvocationStatus::Accepted | InvocationStatus::InProgress
+                    ),
+                    Error::<T>::InvalidInvocationStatus
+                );
+                inv.status = InvocationStatus::InProgress;
+                Ok::<(), DispatchError>(())
+            })?;
+
+            InvocationProgress::<T>::try_mutate(invocation_id, |checkpoints| {
+                let last = checkpoints.last().map_or(0, |c| c.percent);
+                ensure!(
+                    percent > last && percent <= 100,
+                    Error::<T>::InvalidProgress
+                );
+                checkpoints
+                    .try_push(ProgressCheckpoint {
+                        percent,
+                        proof_cid: bounded_cid,
+                        reported_at: <frame_system::Pallet<T>>::block_number(),
+                    })
+                    .map_err(|_| Error::<T>::TooManyCheckpoints)?;
+                Ok::<(), DispatchError>(())
+            })?;
+
+            Self::deposit_event(Event::ProgressReported {
+                invocation_id,
+                percent,
+                proof_cid,
+            });
+            Ok(())
+        }
+
+        /// (Index 40) Invoker cancels an invocation whose provider has not
+        /// reported progress for `ProgressStallPeriod` blocks.
+        ///
+        /// The provider receives the last reported percentage of the price,
+        /// less anything already released; the remaining escrow is refunded.
+        #[pallet::call_index(40)]
+        #[pallet::weight(T::WeightInfo::cancel_stalled_invocation())]
+        pub fn cancel_stalled_invocation(
+            origin: OriginFor<T>,
+            invocation_id: InvocationId,
+        ) -> DispatchResult {
+            let invoker = ensure_signed(origin)?;
+
+            let checkpoints = InvocationProgress::<T>::get(invocation_id);
+            let now = <frame_system::Pallet<T>>::block_number();
+
+            let (provider, earned) = ServiceInvocations::<T>::try_mutate(invocation_id, |maybe| {
+                let inv = maybe.as_mut().ok_or(Error::<T>::InvocationNotFound)?;
+                ensure!(inv.invoker == invoker, Error::<T>::NotInvoker);
+                ensure!(
+                    inv.status == InvocationStatus::InProgress,
+                    Error::<T>::InvalidInvocationStatus
+                );
+                let (last_percent, last_report) = checkpoints
+                    .last()
+                    .map_or((0, inv.created_at), |c| (c.percent, c.reported_at));
+                ensure!(
+                    now >= last_report.saturating_add(T::ProgressStallPeriod::get().into()),
+                    Error::<T>::ProgressNotStalled
+                );
+
+                let earned = Self::percent_of(inv.price, last_percent as u128)
+                    .saturating_sub(inv.released)
+                    .min(inv.price.saturating_sub(inv.released));
+                inv.released = inv.released.saturating_add(earned);
+                inv.status = InvocationStatus::Cancelled;
+                Ok::<_, DispatchError>((inv.provider.clone(), earned))
+            })?;
+
+            Self::close_yield_position(invocation_id);
+            let earned = Self::escrow_payout(invocation_id, earned);
+            let escrow_account = Self::invocation_escrow_account(invocation_id);
+            let existence = Self::escrow_existence_requirement(invocation_id);
+            if !earned.is_zero() && Self::is_escrowed(invocation_id) {
+                let payee = Self::invocation_payee(invocation_id, &provider);
+                T::Currency::transfer(&escrow_account, &payee, earned, existence)
+                    .map_err(|_| Error::<T>::InsufficientBalance)?;
+            }
+            let mut refunded = T::Currency::free_balance(&escrow_account);
+            if existence == ExistenceRequirement::KeepAlive {
+                refunded = refunded.saturating_sub(T::Currency::minimum_balance());
+            }
+            if !refunded.is_zero() {
+                T::Currency::transfer(&escrow_account, &invoker, refunded, existence)
+                    .map_err(|_| Error::<T>::InsufficientBalance)?;
+            }
+
+            Self::cleanup_invocation(invocation_id);
+
+            Self::deposit_event(Event::StalledInvocationCancelled {
+                invocation_id,
+                paid_to_provider: earned,
+                refunded,
+            });
+            Ok(())
+        }
+
+        /// (Index 41) Invoker requests cancellation of an accepted invocation.
+        ///
+        /// Settles on the provider's `ack_cancellation`, or via
+        /// `finalize_cancellation` after `CancellationAckTimeout` blocks:
+        /// approved milestones stay paid, the provider receives
+        /// `KillFeePercent` of the next unapproved milestone (of the whole
+        /// unreleased price for single-milestone invocations), and the rest
+        /// of the escrow is refunded.
+        #[pallet::call_index(41)]
+        #[pallet::weight(T::WeightInfo::cancel_in_progress())]
+        pub fn cancel_in_progress(
+            origin: OriginFor<T>,
+            invocation_id: InvocationId,
+        ) -> DispatchResult {
+            let invoker = ensure_signed(origin)?;
+
+            let inv = ServiceInvocations::<T>::get(invocation_id)
+                .ok_or(Error::<T>::InvocationNotFound)?;
+            ensure!(inv.invoker == invoker, Error::<T>::NotInvoker);
+            ensure!(
+                Self::is_cancellable_in_progress(&inv.status),
+                Error::<T>::InvalidInvocationStatus
+            );
+            ensure!(
+                !PendingCancellations::<T>::contains_key(invocation_id),
+                Error::<T>::CancellationAlreadyRequested
+            );
+
+            let now = <frame_system::Pallet<T>>::block_number();
+            PendingCancellations::<T>::insert(invocation_id, now);
+
+            Self::deposit_event(Event::CancellationRequested {
+                invocation_id,
+                timeout_at: now.saturating_add(T::CancellationAckTimeout::get().into()),
+            });
+            Ok(())
+        }
+
+        /// (Index 42) Provider acknowledges a cancellation request, settling it.
+        #[pallet::call_index(42)]
+        #[pallet::weight(T::WeightInfo::ack_cancellation())]
+        pub fn ack_cancellation(
+            origin: OriginFor<T>,
+            invocation_id: InvocationId,
+        ) -> DispatchResult {
+            let provider = ensure_signed(origin)?;
+
+            let inv = ServiceInvocations::<T>::get(invocation_id)
+                .ok_or(Error::<T>::InvocationNotFound)?;
+            ensure!(inv.provider == provider, Error::<T>::NotProvider);
+            ensure!(
+                PendingCancellations::<T>::contains_key(invocation_id),
+                Error::<T>::NoCancellationRequested
+            );
+
+            Self::settle_cancellation(invocation_id, true)
+        }
+
+        /// (Index 43) Permissionless: settle a cancellation request the
+        /// provider has not acknowledged within `CancellationAckTimeout`.
+        #[pallet::call_index(43)]
+        #[pallet::weight(T::WeightInfo::finalize_cancellation())]
+        pub fn finalize_cancellation(
+            origin: OriginFor<T>,
+            invocation_id: InvocationId,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+
+            let requested_at = PendingCancellations::<T>::get(invocation_id)
+                .ok_or(Error::<T>::NoCancellationRequested)?;
+            let now = <frame_system::Pallet<T>>::block_number();
+            ensure!(
+                now >= requested_at.saturating_add(T::CancellationAckTimeout::get().into()),
+                Error::<T>::CancellationTimeoutNotReached
+            );
+
+            Self::settle_cancellation(invocation_id, false)
+        }
+
+        /// (Index 44) Apply for a provisional listing slot while below
+        /// `MinListingReputation`, reserving `ListingApplicationBond`.
+        ///
+        /// The application is approved once `ApplicationApprovalVotes`
+        /// providers meeting the threshold vote aye (or rejected on as many
+        /// nays), or directly by `OnboardingOrigin`. An approved applicant
+        /// may hold one listing while on probation.
+        #[pallet::call_index(44)]
+        #[pallet::weight(T::WeightInfo::apply_for_listing())]
+        pub fn apply_for_listing(origin: OriginFor<T>) -> DispatchResult {
+            let applicant = ensure_signed(origin)?;
+
+            ensure!(
+                !SuspendedProviders::<T>::contains_key(&applicant),
+                Error::<T>::ProviderSuspended
+            );
+            ensure!(
+                !T::ReputationManager::meets_minimum_reputation(
+                    &applicant,
+                    T::MinListingReputation::get()
+                ),
+                Error::<T>::ApplicationNotNeeded
+            );
+            ensure!(
+                !ListingApplications::<T>::contains_key(&applicant)
+                    && !ProvisionalProviders::<T>::contains_key(&applicant),
+                Error::<T>::ApplicationAlreadyExists
+            );
+
+            let bond = T::ListingApplicationBond::get();
+            T::Currency::reserve(&applicant, bond).map_err(|_| Error::<T>::InsufficientBalance)?;
+            ListingApplications::<T>::insert(
+                &applicant,
+                ListingApplication {
+                    bond,
+                    submitted_at: <frame_system::Pallet<T>>::block_number(),
+                    ayes: BoundedVec::default(),
+                    nays: BoundedVec::default(),
+                },
+            );
+
+            Self::deposit_event(Event::ListingApplicationSubmitted { applicant, bond });
+            Ok(())
+        }
+
+        /// (Index 45) Vote on a listing application. The voter must meet
+        /// `MinListingReputation`; `ApplicationApprovalVotes` ayes approve the
+        /// application and as many nays reject it.
+        #[pallet::call_index(45)]
+        #[pallet::weight(T::WeightInfo::vote_on_application())]
+        pub fn vote_on_application(
+            origin: OriginFor<T>,
+            applicant: T::AccountId,
+            approve: bool,
+        ) -> DispatchResult {
+            let voter = ensure_signed(origin)?;
+
+            ensure!(
+                T::ReputationManager::meets_minimum_reputation(
+                    &voter,
+                    T::MinListingReputation::get()
+                ),
+                Error::<T>::InsufficientReputation
+            );
+
+            let decided = ListingApplications::<T>::try_mutate(&applicant, |maybe| {
+                let application = maybe.as_mut().ok_or(Error::<T>::ApplicationNotFound)?;
+                ensure!(
+                    !application.ayes.contains(&voter) && !application.nays.contains(&voter),
+                    Error::<T>::AlreadyVoted
+                );
+                let side = if approve {
+                    &mut application.ayes
+                } else {
+                    &mut application.nays
+                };
+                // A side is full exactly when it has decided the application,
+                // which removes it, so this push cannot fail.
+                let _ = side.try_push(voter.clone());
+                Ok::<_, DispatchError>(
+                    (side.len() as u32 >= T::ApplicationApprovalVotes::get()).then_some(approve),
+                )
+            })?;
+
+            Self::deposit_event(Event::ApplicationVoted {
+                applicant: applicant.clone(),
+                voter,
+                approve,
+            });
+
+            if let Some(approve) = decided {
+                Self::conclude_application(applicant, approve)?;
+            }
+            Ok(())
+        }
+
+        /// (Index 46) `OnboardingOrigin` approves or rejects an application.
+        #[pallet::call_index(46)]
+        #[pallet::weight(T::WeightInfo::decide_application())]
+        pub fn decide_application(
+            origin: OriginFor<T>,
+            applicant: T::AccountId,
+            approve: bool,
+        ) -> DispatchResult {
+            T::OnboardingOrigin::ensure_origin(origin)?;
+            Self::conclude_application(applicant, approve)
+        }
+
+        /// (Index 47) Invoker rejects a trial invocation within
+        /// `TrialRefundWindow` blocks and is refunded the full price. No
+        /// dispute is involved.
+        #[pallet::call_index(47)]
+        #[pallet::weight(T::WeightInfo::reject_trial())]
+        pub fn reject_trial(origin: OriginFor<T>, invocation_id: InvocationId) -> DispatchResult {
+            let invoker = ensure_signed(origin)?;
+
+            let now = <frame_system::Pallet<T>>::block_number();
+            let price = ServiceInvocations::<T>::try_mutate(invocation_id, |maybe| {
+                let inv = maybe.as_mut().ok_or(Error::<T>::InvocationNotFound)?;
+                ensure!(inv.invoker == invoker, Error::<T>::NotInvoker);
+                ensure!(inv.trial, Error::<T>::NotTrialInvocation);
+                ensure!(
+                    matches!(
+                        inv.status,
+                        InvocationStatus::Pending
+                            | InvocationStatus::Accepted
+                            | InvocationStatus::InProgress
+                            | InvocationStatus::WorkSubmitted
+                    ),
+                    Error::<T>::InvalidInvocationStatus
+                );
+                ensure!(
+                    now < inv.created_at + T::TrialRefundWindow::get().into(),
+                    Error::<T>::TrialWindowClosed
+                );
+                inv.status = InvocationStatus::Cancelled;
+                Ok::<BalanceOf<T>, DispatchError>(inv.price)
+            })?;
+
+            Self::close_yield_position(invocation_id);
+            let price = Self::escrow_payout(invocation_id, price);
+            let escrow_account = Self::invocation_escrow_account(invocation_id);
+            T::Currency::transfer(
+                &escrow_account,
+                &invoker,
+                price,
+                Self::escrow_existence_requirement(invocation_id),
+            )
+            .map_err(|_| Error::<T>::InsufficientBalance)?;
+
+            Self::cleanup_invocation(invocation_id);
+
+            Self::deposit_event(Event::TrialRefunded {
+                invocation_id,
+                amount: price,
+            });
+            Ok(())
+        }
+
+        /// (Index 48) Provider accepts a pending invocation within the
+        /// listing's `sla_response_blocks` of its creation. Work and progress
+        /// can only be submitted once accepted.
+        #[pallet::call_index(48)]
+        #[pallet::weight(T::WeightInfo::accept_invocation())]
+        pub fn accept_invocation(
+            origin: OriginFor<T>,
+            invocation_id: InvocationId,
+        ) -> DispatchResult {
+            let provider = ensure_signed(origin)?;
+
+            ServiceInvocations::<T>::try_mutate(invocation_id, |maybe| {
+                let inv = maybe.as_mut().ok_or(Error::<T>::InvocationNotFound)?;
+                ensure!(inv.provider == provider, Error::<T>::NotProvider);
+                ensure!(
+                    matches!(inv.status, InvocationStatus::Pending),
+                    Error::<T>::InvalidInvocationStatus
+                );
+                let listing =
+                    ServiceListings::<T>::get(inv.listing_id).ok_or(Error::<T>::ListingNotFound)?;
+                let now = <frame_system::Pallet<T>>::block_number();
+                ensure!(
+                    now <= inv
+                        .created_at
+                        .saturating_add(listing.sla_response_blocks.into()),
+                    Error::<T>::AcceptanceWindowClosed
+                );
+                if inv.payment_mode == PaymentMode::X402 {
+                    ensure!(
+                        X402Payments::<T>::contains_key(invocation_id),
+                        Error::<T>::X402PaymentMissing
+                    );
+                }
+                inv.status = InvocationStatus::Accepted;
+                inv.accepted_at = Some(now);
+                Ok::<(), DispatchError>(())
+            })?;
+
+            Self::deposit_event(Event::InvocationAccepted {
+                invocation_id,
+                provider,
+            });
+            Ok(())
+        }
+
+        /// (Index 49) Root sets the share of milestone payouts routed to the
+        /// ecosystem fund.
+        #[pallet::call_index(49)]
+        #[pallet::weight(T::WeightInfo::set_ecosystem_fund_share())]
+        pub fn set_ecosystem_fund_share(origin: OriginFor<T>, share: Perbill) -> DispatchResult {
+            ensure_root(origin)?;
+            ensure!(
+                share <= T::MaxEcosystemFundShare::get(),
+                Error::<T>::EcosystemFundShareTooHigh
+            );
+            EcosystemFundShare::<T>::put(share);
+            Self::deposit_event(Event::EcosystemFundShareSet { share });
+            Ok(())
+        }
+
+        /// (Index 50) Root opens a quadratic-funding round over `projects`,
+        /// running for `FundingRoundLength` blocks.
+        #[pallet::call_index(50)]
+        #[pallet::weight(T::WeightInfo::open_funding_round())]
+        pub fn open_funding_round(
+            origin: OriginFor<T>,
+            projects: Vec<T::AccountId>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            ensure!(
+                !ActiveFundingRound::<T>::exists(),
+                Error::<T>::FundingRoundActive
+            );
+            ensure!(!projects.is_empty(), Error::<T>::InvalidRoundProjects);
+            let mut bounded = BoundedVec::<T::AccountId, T::MaxRoundProjects>::new();
+            for project in projects {
+                ensure!(
+                    !bounded.contains(&project),
+                    Error::<T>::InvalidRoundProjects
+                );
+                bounded
+                    .try_push(project)
+                    .map_err(|_| Error::<T>::InvalidRoundProjects)?;
+            }
+
+            let round_id = FundingRoundCount::<T>::get();
+            FundingRoundCount::<T>::put(round_id.saturating_add(1));
+            let ends_at = <frame_system::Pallet<T>>::block_number()
+                .saturating_add(T::FundingRoundLength::get().into());
+            let projects = bounded.len() as u32;
+            ActiveFundingRound::<T>::put(FundingRound {
+                id: round_id,
+                ends_at,
+                projects: bounded,
+            });
+
+            Self::deposit_event(Event::FundingRoundOpened {
+                round_id,
+                ends_at,
+                projects,
+            });
+            Ok(())
+        }
+
+        /// (Index 51) Contribute `amount` directly to `project` in the open
+        /// round, raising its share of the matching funds.
+        #[pallet::call_index(51)]
+        #[pallet::weight(T::WeightInfo::contribute_to_project())]
+        pub fn contribute_to_project(
+            origin: OriginFor<T>,
+            project: T::AccountId,
+            amount: BalanceOf<T>,
+        ) -> DispatchResult {
+            let contributor = ensure_signed(origin)?;
+            ensure!(!amount.is_zero(), Error::<T>::ZeroContribution);
+            let round = ActiveFundingRound::<T>::get().ok_or(Error::<T>::NoFundingRound)?;
+            ensure!(
+                <frame_system::Pallet<T>>::block_number() < round.ends_at,
+                Error::<T>::FundingRoundEnded
+            );
+            ensure!(
+                round.projects.contains(&project),
+                Error::<T>::NotRoundProject
+            );
+
+            T::Currency::transfer(
+                &contributor,
+                &project,
+                amount,
+                ExistenceRequirement::KeepAlive,
+            )
+            .map_err(|_| Error::<T>::InsufficientBalance)?;
+
+            let previous = RoundContributions::<T>::get((round.id, project.clone()), &contributor);
+            let total = previous.saturating_add(amount);
+            RoundContributions::<T>::insert((round.id, project.clone()), &contributor, total);
+            RoundTallies::<T>::mutate(round.id, &project, |tally| {
+                tally.contributed = tally.contributed.saturating_add(amount);
+                tally.sqrt_sum = tally
+                    .sqrt_sum
+                    .saturating_sub(previous.saturated_into::<u128>().integer_sqrt())
+                    .saturating_add(total.saturated_into::<u128>().integer_sqrt());
+            });
+
+            Self::deposit_event(Event::ProjectContribution {
+                round_id: round.id,
+                project,
+                contributor,
+                amount,
+            });
+            Ok(())
+        }
+
+        /// (Index 52) Close an ended round and split the ecosystem fund's
+        /// transferable balance between its projects, each in proportion to
+        /// the square of its `sqrt_sum`.
+        #[pallet::call_index(52)]
+        #[pallet::weight(T::WeightInfo::finalize_funding_round())]
+        pub fn finalize_funding_round(origin: OriginFor<T>) -> DispatchResult {
+            ensure_signed(origin)?;
+            let round = ActiveFundingRound::<T>::get().ok_or(Error::<T>::NoFundingRound)?;
+            ensure!(
+                <frame_system::Pallet<T>>::block_number() >= round.ends_at,
+                Error::<T>::FundingRoundNotEnded
+            );
+            ActiveFundingRound::<T>::kill();
+
+            let fund = T::EcosystemFund::get();
+            let pool: u128 = T::Currency::free_balance(&fund)
+                .saturating_sub(T::Currency::minimum_balance())
+                .saturated_into();
+            let weights: Vec<u128> = round
+                .projects
+                .iter()
+                .map(|project| {
+                    let sqrt_sum = RoundTallies::<T>::get(round.id, project).sqrt_sum;
+                    sqrt_sum.saturating_mul(sqrt_sum)
+                })
+                .collect();
+            let total_weight = weights
+                .iter()
+                .fold(0u128, |total, weight| total.saturating_add(*weight));
+
+            let mut matched: BalanceOf<T> = Zero::zero();
+            if total_weight > 0 {
+                for (project, weight) in round.projects.iter().zip(weights) {
+                    let amount: BalanceOf<T> = multiply_by_rational_with_rounding(
+                        pool,
+                        weight,
+                        total_weight,
+                        Rounding::Down,
+                    )
+                    .unwrap_or(0)
+                    .saturated_into();
+                    if amount.is_zero() {
+                        continue;
+                    }
+                    if T::Currency::transfer(
+                        &fund,
+                        project,
+                        amount,
+                        ExistenceRequirement::KeepAlive,
+                    )
+                    .is_ok()
+                    {
+                        matched = matched.saturating_add(amount);
+                        Self::deposit_event(Event::MatchingFundsPaid {
+                            round_id: round.id,
+                            project: project.clone(),
+                            amount,
+                        });
+                    }
+                }
+            }
+
+            Self::deposit_event(Event::FundingRoundFinalized {
+                round_id: round.id,
+                matched,
+            });
+            Ok(())
+        }
+
+        /// (Index 53) Reserve `amount` as (or on top of) the liveness bond
+        /// of one of the caller's listings.
+        ///
+        /// A bonded listing can be challenged. An answered challenge pays the
+        /// provider the challenge fee; an unanswered one returns the fee to
+        /// the challenger plus `LivenessSlashPercent` of the bond. A bond
+        /// under challenge cannot be withdrawn.
+        #[pallet::call_index(53)]
+        #[pallet::weight(T::WeightInfo::post_liveness_bond())]
+        pub fn post_liveness_bond(
+            origin: OriginFor<T>,
+            listing_id: ListingId,
+            amount: BalanceOf<T>,
+        ) -> DispatchResult {
+            let provider = ensure_signed(origin)?;
+            ensure!(!amount.is_zero(), Error::<T>::ZeroBond);
+            let listing =
+                ServiceListings::<T>::get(listing_id).ok_or(Error::<T>::ListingNotFound)?;
+            ensure!(listing.provider == provider, Error::<T>::NotProvider);
+
+            T::Currency::reserve(&provider, amount).map_err(|_| Error::<T>::InsufficientBalance)?;
+            let total = LivenessBonds::<T>::get(listing_id)
+                .unwrap_or_default()
+                .saturating_add(amount);
+            LivenessBonds::<T>::insert(listing_id, total);
+
+            Self::deposit_event(Event::LivenessBondPosted { listing_id, total });
+            Ok(())
+        }
+
+        /// (Index 54) Release a listing's liveness bond. Not possible while a
+        /// challenge is open.
+        #[pallet::call_index(54)]
+        #[pallet::weight(T::WeightInfo::withdraw_liveness_bond())]
+        pub fn withdraw_liveness_bond(
+            origin: OriginFor<T>,
+            listing_id: ListingId,
+        ) -> DispatchResult {
+            let provider = ensure_signed(origin)?;
+            let listing =
+                ServiceListings::<T>::get(listing_id).ok_or(Error::<T>::ListingNotFound)?;
+            ensure!(listing.provider == provider, Error::<T>::NotProvider);
+            ensure!(
+                !LivenessChallenges::<T>::contains_key(listing_id),
+                Error::<T>::LivenessChallengeOpen
+            );
+
+            let amount = LivenessBonds::<T>::take(listing_id).ok_or(Error::<T>::NoLivenessBond)?;
+            T::Currency::unreserve(&provider, amount);
+
+            Self::deposit_event(Event::LivenessBondWithdrawn { listing_id, amount });
+            Ok(())
+        }
+
+        /// (Index 55) Challenge a bonded listing to prove it is responsive,
+        /// reserving `LivenessChallengeFee`. The provider has the listing's
+        /// `sla_response_blocks` to answer.
+        #[pallet::call_index(55)]
+        #[pallet::weight(T::WeightInfo::challenge_liveness())]
+        pub fn challenge_liveness(origin: OriginFor<T>, listing_id: ListingId) -> DispatchResult {
+            let challenger = ensure_signed(origin)?;
+            let listing =
+                ServiceListings::<T>::get(listing_id).ok_or(Error::<T>::ListingNotFound)?;
+            ensure!(listing.active, Error::<T>::ListingNotActive);
+            ensure!(
+                LivenessBonds::<T>::contains_key(listing_id),
+                Error::<T>::NoLivenessBond
+            );
+            ensure!(
+                !LivenessChallenges::<T>::contains_key(listing_id),
+                Error::<T>::LivenessChallengeOpen
+            );
+
+            let fee = T::LivenessChallengeFee::get();
+            T::Currency::reserve(&challenger, fee).map_err(|_| Error::<T>::InsufficientBalance)?;
+            let deadline = <frame_system::Pallet<T>>::block_number()
+                .saturating_add(listing.sla_response_blocks.into());
+            LivenessChallenges::<T>::insert(
+                listing_id,
+                LivenessChallenge {
+                    challenger: challenger.clone(),
+                    fee,
+                    deadline,
+                },
+            );
+
+            Self::deposit_event(Event::LivenessChallenged {
+                listing_id,
+                challenger,
+                deadline,
+            });
+            Ok(())
+        }
+
+        /// (Index 56) Provider answers an open liveness challenge before its
+        /// deadline and collects the challenge fee.
+        #[pallet::call_index(56)]
+        #[pallet::weight(T::WeightInfo::respond_to_liveness_challenge())]
+        pub fn respond_to_liveness_challenge(
+            origin: OriginFor<T>,
+            listing_id: ListingId,
+        ) -> DispatchResult {
+            let provider = ensure_signed(origin)?;
+            let listing =
+                ServiceListings::<T>::get(listing_id).ok_or(Error::<T>::ListingNotFound)?;
+            ensure!(listing.provider == provider, Error::<T>::NotProvider);
+            let challenge =
+                LivenessChallenges::<T>::get(listing_id).ok_or(Error::<T>::NoLivenessChallenge)?;
+            ensure!(
+                <frame_system::Pallet<T>>::block_number() <= challenge.deadline,
+                Error::<T>::LivenessDeadlinePassed
+            );
+
+            LivenessChallenges::<T>::remove(listing_id);
+            // Best-effort: whatever part of the fee cannot be moved stays with
+            // the challenger.
+            let unpaid = T::Currency::repatriate_reserved(
+                &challenge.challenger,
+                &provider,
+                challenge.fee,
+                BalanceStatus::Free,
+            )
+            .unwrap_or(challenge.fee);
+            T::Currency::unreserve(&challenge.challenger, unpaid);
+
+            Self::deposit_event(Event::LivenessProven {
+                listing_id,
+                challenger: challenge.challenger,
+            });
+            Ok(())
+        }
+
+        /// (Index 57) Settle a liveness challenge the provider let expire:
+        /// the challenger's fee is returned and `LivenessSlashPercent` of the
+        /// bond is paid to it.
+        #[pallet::call_index(57)]
+        #[pallet::weight(T::WeightInfo::resolve_liveness_challenge())]
+        pub fn resolve_liveness_challenge(
+            origin: OriginFor<T>,
+            listing_id: ListingId,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+            let challenge =
+                LivenessChallenges::<T>::get(listing_id).ok_or(Error::<T>::NoLivenessChallenge)?;
+            ensure!(
+                <frame_system::Pallet<T>>::block_number() > challenge.deadline,
+                Error::<T>::LivenessDeadlineNotReached
+            );
+            let listing =
+                ServiceListings::<T>::get(listing_id).ok_or(Error::<T>::ListingNotFound)?;
+
+            LivenessChallenges::<T>::remove(listing_id);
+            T::Currency::unreserve(&challenge.challenger, challenge.fee);
+
+            let bond = LivenessBonds::<T>::get(listing_id).unwrap_or_default();
+            let slash_pct = T::LivenessSlashPercent::get().min(100) as u128;
+            let penalty = Self::percent_of(bond, slash_pct);
+            let unpaid = T::Currency::repatriate_reserved(
+                &listing.provider,
+                &challenge.challenger,
+                penalty,
+                BalanceStatus::Free,
+            )
+            .unwrap_or(penalty);
+            let amount = penalty.saturating_sub(unpaid);
+            let remaining = bond.saturating_sub(amount);
+            if remaining.is_zero() {
+                LivenessBonds::<T>::remove(listing_id);
+            } else {
+                LivenessBonds::<T>::insert(listing_id, remaining);
+            }
+
+            Self::deposit_event(Event::LivenessBondSlashed {
+                listing_id,
+                challenger: challenge.challenger,
+                amount,
+            });
+            Ok(())
+        }
+
+        /// (Index 58) Root sets the invocation-cap formula, or clears it to
+        /// fall back to the flat `MaxActiveInvocationsPerAccount`.
+        #[pallet::call_index(58)]
+        #[pallet::weight(T::WeightInfo::set_invocation_cap_params())]
+        pub fn set_invocation_cap_params(
+            origin: OriginFor<T>,
+            params: Option<InvocationCapParams>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            InvocationCapParameters::<T>::set(params.clone());
+            Self::deposit_event(Event::InvocationCapParamsSet { params });
+            Ok(())
+        }
+
+        /// (Index 59) Provider declines a pending invocation. The escrow is
+        /// refunded to the invoker in full.
+        #[pallet::call_index(59)]
+        #[pallet::weight(T::WeightInfo::reject_invocation())]
+        pub fn reject_invocation(
+            origin: OriginFor<T>,
+            invocation_id: InvocationId,
+            reason: Vec<u8>,
+        ) -> DispatchResult {
+            let provider = ensure_signed(origin)?;
+
+            let reason: BoundedVec<u8, T::MaxDescriptionLength> = reason
+                .try_into()
+                .map_err(|_| Error::<T>::DescriptionTooLong)?;
+
+            let (invoker, price) = ServiceInvocations::<T>::try_mutate(invocation_id, |maybe| {
+                let inv = maybe.as_mut().ok_or(Error::<T>::InvocationNotFound)?;
+                ensure!(inv.provider == provider, Error::<T>::NotProvider);
+                ensure!(
+                    matches!(inv.status, InvocationStatus::Pending),
+                    Error::<T>::InvalidInvocationStatus
+                );
+                inv.status = InvocationStatus::Rejected;
+                Ok::<_, DispatchError>((inv.invoker.clone(), inv.price))
+            })?;
+
+            if Self::is_escrowed(invocation_id) {
+                Self::close_yield_position(invocation_id);
+                let escrow_account = Self::invocation_escrow_account(invocation_id);
+                T::Currency::transfer(
+                    &escrow_account,
+                    &invoker,
+                    Self::escrow_payout(invocation_id, price),
+                    Self::escrow_existence_requirement(invocation_id),
+                )
+                .map_err(|_| Error::<T>::InsufficientBalance)?;
+            }
+
+            Self::cleanup_invocation(invocation_id);
+
+            Self::deposit_event(Event::InvocationRejected {
+                invocation_id,
+                provider,
+                reason,
+            });
+            Ok(())
+        }
+
+        /// (Index 60) Governance escalates an open dispute before default
+        /// judgment applies; it then waits for `resolve_dispute_governance`.
+        #[pallet::call_index(60)]
+        #[pallet::weight(T::WeightInfo::escalate_dispute())]
+        pub fn escalate_dispute(origin: OriginFor<T>, dispute_id: DisputeId) -> DispatchResult {
+            ensure_root(origin)?;
+
+            Disputes::<T>::try_mutate(dispute_id, |maybe| {
+                let dispute = maybe.as_mut().ok_or(Error::<T>::DisputeNotFound)?;
+                ensure!(
+                    dispute.status == DisputeStatus::Open,
+                    Error::<T>::DisputeNotOpen
+                );
+                dispute.status = DisputeStatus::Escalated;
+                Ok::<(), DispatchError>(())
+            })?;
+            Self::clear_default_judgment(dispute_id);
+
+            Self::deposit_event(Event::DisputeEscalated { dispute_id });
+            Ok(())
+        }
+
+        /// (Index 61) Invoke a listing that takes X402 payments. No funds are
+        /// locked: the invoker pays off-chain and records it with
+        /// `register_x402_payment` before the provider can accept.
+        #[pallet::call_index(61)]
+        #[pallet::weight(T::WeightInfo::invoke_service_x402())]
+        pub fn invoke_service_x402(
+            origin: OriginFor<T>,
+            listing_id: ListingId,
+            requirements: Vec<u8>,
+            milestones: Option<Vec<MilestoneSpec>>,
+            agreed_price: BalanceOf<T>,
+            deadline_blocks: u32,
+        ) -> DispatchResult {
+            let invoker = ensure_signed(origin)?;
+
+            Self::do_invoke_service(
+                invoker,
+                listing_id,
+                requirements,
+                milestones,
+                agreed_price,
+                deadline_blocks,
+                None,
+                false,
+                PaymentMode::X402,
+                None,
+            )
+            .map(|_| ())
+        }
+
+        /// (Index 62) Invoker records the off-chain settlement of a pending
+        /// X402 invocation. `amount` must cover the agreed price.
+        #[pallet::call_index(62)]
+        #[pallet::weight(T::WeightInfo::register_x402_payment())]
+        pub fn register_x402_payment(
+            origin: OriginFor<T>,
+            invocation_id: InvocationId,
+            payment_proof_hash: T::Hash,
+            amount: BalanceOf<T>,
+        ) -> DispatchResult {
+            let invoker = ensure_signed(origin)?;
+
+            let inv = ServiceInvocations::<T>::get(invocation_id)
+                .ok_or(Error::<T>::InvocationNotFound)?;
+            ensure!(inv.invoker == invoker, Error::<T>::NotInvoker);
+            ensure!(
+                inv.payment_mode == PaymentMode::X402,
+                Error::<T>::NotX402Invocation
+            );
+            ensure!(
+                inv.status == InvocationStatus::Pending,
+                Error::<T>::InvalidInvocationStatus
+            );
+            ensure!(
+                !X402Payments::<T>::contains_key(invocation_id),
+                Error::<T>::X402PaymentAlreadyRegistered
+            );
+            ensure!(amount >= inv.price, Error::<T>::X402PaymentTooLow);
+
+            X402Payments::<T>::insert(
+                invocation_id,
+                X402Payment {
+                    proof_hash: payment_proof_hash,
+                    amount,
+                    registered_at: <frame_system::Pallet<T>>::block_number(),
+                },
+            );
+
+            Self::deposit_event(Event::X402PaymentRegistered {
+                invocation_id,
+                proof_hash: payment_proof_hash,
+                amount,
+            });
+            Ok(())
+        }
+
+        /// (Index 63) Provider sets or clears the minimum reputation it
+        /// requires of X402 invokers on a listing.
+        #[pallet::call_index(63)]
+        #[pallet::weight(T::WeightInfo::set_x402_min_reputation())]
+        pub fn set_x402_min_reputation(
+            origin: OriginFor<T>,
+            listing_id: ListingId,
+            min_reputation: Option<u32>,
+        ) -> DispatchResult {
+            let provider = ensure_signed(origin)?;
+
+            let listing =
+                ServiceListings::<T>::get(listing_id).ok_or(Error::<T>::ListingNotFound)?;
+            ensure!(listing.provider == provider, Error::<T>::NotProvider);
+
+            X402MinReputation::<T>::set(listing_id, min_reputation);
+            Self::deposit_event(Event::X402MinReputationSet {
+                listing_id,
+                min_reputation,
+            });
+            Ok(())
+        }
+
+        /// (Index 64) Return what is left of a delisted listing's provider
+        /// bond once none of its invocations are open.
+        #[pallet::call_index(64)]
+        #[pallet::weight(T::WeightInfo::withdraw_provider_bond())]
+        pub fn withdraw_provider_bond(
+            origin: OriginFor<T>,
+            listing_id: ListingId,
+        ) -> DispatchResult {
+            let provider = ensure_signed(origin)?;
+            let listing =
+                ServiceListings::<T>::get(listing_id).ok_or(Error::<T>::ListingNotFound)?;
+            ensure!(listing.provider == provider, Error::<T>::NotProvider);
+            ensure!(!listing.active, Error::<T>::ListingStillActive);
+            ensure!(
+                !ListingsUnderReview::<T>::contains_key(listing_id),
+                Error::<T>::ListingUnderReview
+            );
+            ensure!(
+                InvocationsByListing::<T>::iter_prefix(listing_id)
+                    .next()
+                    .is_none(),
+                Error::<T>::ListingHasActiveInvocations
+            );
+
+            let amount = ProviderBonds::<T>::take(listing_id).ok_or(Error::<T>::NoProviderBond)?;
+            T::Currency::unreserve(&provider, amount);
 
-    #[pallet::hooks]
-    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
-        fn on_initialize(n: BlockNumberFor<T>) -> Weight {
-            Self::expire_overdue_invocations(n)
+            Self::deposit_event(Event::ProviderBondReleased { listing_id, amount });
+            Ok(())
         }
-    }
 
-    // =========================================================
-    // Events
-    // =========================================================
-
-    #[pallet::event]
-    #[pallet::generate_deposit(pub(super) fn deposit_event)]
-    pub enum Event<T: Config> {
-        ServiceListed {
-            listing_id: ListingId,
-            provider: T::AccountId,
-            min_price: BalanceOf<T>,
-        },
-        ListingUpdated {
-            listing_id: ListingId,
-        },
-        ServiceDelisted {
+        /// (Index 65) Provider sets or clears the referral fee and
+        /// per-invocation cap a listing pays to referrers. Invocations already
+        /// referred keep their terms.
+        ///
+        /// The fee is at most `MaxReferralFee` and is paid out of every
+        /// approved milestone until the cap is reached. The referrer is never
+        /// the invoker or the provider.
+        #[pallet::call_index(65)]
+        #[pallet::weight(T::WeightInfo::set_referral_terms())]
+        pub fn set_referral_terms(
+            origin: OriginFor<T>,
             listing_id: ListingId,
-        },
-        ServiceInvoked {
-            invocation_id: InvocationId,
+            terms: Option<ReferralTerms<BalanceOf<T>>>,
+        ) -> DispatchResult {
+            let provider = ensure_signed(origin)?;
+            let listing =
+                ServiceListings::<T>::get(listing_id).ok_or(Error::<T>::ListingNotFound)?;
+            ensure!(listing.provider == provider, Error::<T>::NotProvider);
+            if let Some(t) = &terms {
+                ensure!(
+                    t.fee <= T::MaxReferralFee::get(),
+                    Error::<T>::ReferralFeeTooHigh
+                );
+            }
+
+            ListingReferralTerms::<T>::set(listing_id, terms.clone());
+            Self::deposit_event(Event::ReferralTermsSet { listing_id, terms });
+            Ok(())
+        }
+
+        /// (Index 66) Subscribe to a listing for `periods` periods of
+        /// `period_blocks` blocks, escrowing `price_per_period * periods`.
+        /// Each period's payment is released to the provider when it ends.
+        #[pallet::call_index(66)]
+        #[pallet::weight(T::WeightInfo::subscribe_service())]
+        pub fn subscribe_service(
+            origin: OriginFor<T>,
             listing_id: ListingId,
-            invoker: T::AccountId,
-            provider: T::AccountId,
-            price: BalanceOf<T>,
-        },
-        WorkSubmitted {
-            invocation_id: InvocationId,
-            milestone_index: Option<u32>,
-        },
-        MilestoneApproved {
-            invocation_id: InvocationId,
-            milestone_index: u32,
-            amount_released: BalanceOf<T>,
-        },
-        InvocationFullyApproved {
-            invocation_id: InvocationId,
-            total_paid: BalanceOf<T>,
-        },
-        InvocationCancelled {
-            invocation_id: InvocationId,
-        },
-        InvocationExpired {
-            invocation_id: InvocationId,
-            expired_by: T::AccountId,
-        },
-        DisputeRaised {
-            invocation_id: InvocationId,
+            price_per_period: BalanceOf<T>,
+            period_blocks: u32,
+            periods: u32,
+        ) -> DispatchResult {
+            let subscriber = ensure_signed(origin)?;
+            let listing =
+                ServiceListings::<T>::get(listing_id).ok_or(Error::<T>::ListingNotFound)?;
+            ensure!(listing.active, Error::<T>::ListingNotActive);
+            ensure!(listing.provider != subscriber, Error::<T>::SelfSubscription);
+            Self::ensure_allowlisted(listing_id, &subscriber)?;
+            ensure!(
+                period_blocks > 0 && periods > 0 && periods <= T::MaxSubscriptionPeriods::get(),
+                Error::<T>::InvalidSubscriptionTerms
+            );
+            ensure!(
+                price_per_period >= listing.min_price,
+                Error::<T>::PriceBelowMinimum
+            );
+            ensure!(
+                listing.max_price == listing.min_price || price_per_period <= listing.max_price,
+                Error::<T>::PriceAboveMaximum
+            );
+            if let Some(min_rep) = listing.min_invoker_reputation {
+                ensure!(
+                    T::ReputationManager::meets_minimum_reputation(&subscriber, min_rep),
+                    Error::<T>::InsufficientReputation
+                );
+            }
+
+            let subscription_id = SubscriptionCount::<T>::get();
+            let total = price_per_period.saturating_mul(periods.into());
+            T::Currency::transfer(
+                &subscriber,
+                &Self::subscription_escrow_account(subscription_id),
+                total,
+                ExistenceRequirement::KeepAlive,
+            )
+            .map_err(|_| Error::<T>::InsufficientBalance)?;
+
+            let next_payment =
+                <frame_system::Pallet<T>>::block_number().saturating_add(period_blocks.into());
+            Subscriptions::<T>::insert(
+                subscription_id,
+                Subscription {
+                    listing_id,
+                    subscriber: subscriber.clone(),
+                    provider: listing.provider,
+                    price_per_period,
+                    period_blocks,
+                    periods_remaining: periods,
+                    next_payment,
+                    paused_at: None,
+                    status: SubscriptionStatus::Active,
+                },
+            );
+            SubscriptionCount::<T>::put(subscription_id + 1);
+            SubscriptionsByDueBlock::<T>::insert(next_payment, subscription_id, ());
+
+            Self::deposit_event(Event::SubscriptionCreated {
+                subscription_id,
+                listing_id,
+                subscriber,
+                total,
+            });
+            Ok(())
+        }
+
+        /// (Index 67) Subscriber pauses or resumes a subscription. A paused
+        /// subscription's current period stops elapsing until it resumes.
+        #[pallet::call_index(67)]
+        #[pallet::weight(T::WeightInfo::set_subscription_paused())]
+        pub fn set_subscription_paused(
+            origin: OriginFor<T>,
+            subscription_id: SubscriptionId,
+            paused: bool,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let now = <frame_system::Pallet<T>>::block_number();
+
+            Subscriptions::<T>::try_mutate(subscription_id, |maybe| {
+                let sub = maybe.as_mut().ok_or(Error::<T>::SubscriptionNotFound)?;
+                ensure!(sub.subscriber == who, Error::<T>::NotSubscriber);
+                match (sub.status, paused) {
+                    (SubscriptionStatus::Active, true) => {
+                        SubscriptionsByDueBlock::<T>::remove(sub.next_payment, subscription_id);
+                        sub.status = SubscriptionStatus::Paused;
+                        sub.paused_at = Some(now);
+                        Self::deposit_event(Event::SubscriptionPaused { subscription_id });
+                    }
+                    (SubscriptionStatus::Paused, false) => {
+                        let left = sub
+                            .next_payment
+                            .saturating_sub(sub.paused_at.take().unwrap_or(now));
+                        sub.next_payment = now.saturating_add(left);
+                        sub.status = SubscriptionStatus::Active;
+                        SubscriptionsByDueBlock::<T>::insert(sub.next_payment, subscription_id, ());
+                        Self::deposit_event(Event::SubscriptionResumed {
+                            subscription_id,
+                            next_payment: sub.next_payment,
+                        });
+                    }
+                    _ => return Err(Error::<T>::InvalidSubscriptionStatus.into()),
+                }
+                Ok::<_, DispatchError>(())
+            })
+        }
+
+        /// (Index 68) Subscriber or provider ends a subscription. The
+        /// provider is paid for the elapsed part of the current period and
+        /// the rest of the escrow is refunded.
+        #[pallet::call_index(68)]
+        #[pallet::weight(T::WeightInfo::cancel_subscription())]
+        pub fn cancel_subscription(
+            origin: OriginFor<T>,
+            subscription_id: SubscriptionId,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let sub =
+                Subscriptions::<T>::get(subscription_id).ok_or(Error::<T>::SubscriptionNotFound)?;
+            ensure!(
+                sub.subscriber == who || sub.provider == who,
+                Error::<T>::NotSubscriptionParty
+            );
+            ensure!(
+                sub.status != SubscriptionStatus::Disputed,
+                Error::<T>::InvalidSubscriptionStatus
+            );
+
+            let now = <frame_system::Pallet<T>>::block_number();
+            let left: u32 = sub
+                .next_payment
+                .saturating_sub(sub.paused_at.unwrap_or(now))
+                .saturated_into();
+            let elapsed = sub.period_blocks.saturating_sub(left);
+            let earned = Perbill::from_rational(elapsed, sub.period_blocks.max(1))
+                .mul_floor(sub.price_per_period);
+
+            let (to_provider, refunded) = Self::close_subscription(subscription_id, &sub, earned);
+            Self::deposit_event(Event::SubscriptionCancelled {
+                subscription_id,
+                to_provider,
+                refunded,
+            });
+            Ok(())
+        }
+
+        /// (Index 69) Subscriber disputes a subscription. No further payments
+        /// are released until governance resolves it.
+        #[pallet::call_index(69)]
+        #[pallet::weight(T::WeightInfo::dispute_subscription())]
+        pub fn dispute_subscription(
+            origin: OriginFor<T>,
+            subscription_id: SubscriptionId,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let now = <frame_system::Pallet<T>>::block_number();
+
+            Subscriptions::<T>::try_mutate(subscription_id, |maybe| {
+                let sub = maybe.as_mut().ok_or(Error::<T>::SubscriptionNotFound)?;
+                ensure!(sub.subscriber == who, Error::<T>::NotSubscriber);
+                ensure!(
+                    sub.status != SubscriptionStatus::Disputed,
+                    Error::<T>::InvalidSubscriptionStatus
+                );
+                SubscriptionsByDueBlock::<T>::remove(sub.next_payment, subscription_id);
+                sub.paused_at = Some(sub.paused_at.unwrap_or(now));
+                sub.status = SubscriptionStatus::Disputed;
+                Ok::<_, DispatchError>(())
+            })?;
+
+            Self::deposit_event(Event::SubscriptionDisputed { subscription_id });
+            Ok(())
+        }
+
+        /// (Index 70) Governance settles a disputed subscription, paying
+        /// `provider_share` of the escrow to the provider and refunding the
+        /// rest.
+        #[pallet::call_index(70)]
+        #[pallet::weight(T::WeightInfo::resolve_subscription_dispute())]
+        pub fn resolve_subscription_dispute(
+            origin: OriginFor<T>,
+            subscription_id: SubscriptionId,
+            provider_share: Perbill,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            let sub =
+                Subscriptions::<T>::get(subscription_id).ok_or(Error::<T>::SubscriptionNotFound)?;
+            ensure!(
+                sub.status == SubscriptionStatus::Disputed,
+                Error::<T>::InvalidSubscriptionStatus
+            );
+
+            let held =
+                T::Currency::free_balance(&Self::subscription_escrow_account(subscription_id));
+            let (to_provider, refunded) =
+                Self::close_subscription(subscription_id, &sub, provider_share.mul_floor(held));
+            Self::deposit_event(Event::SubscriptionDisputeResolved {
+                subscription_id,
+                to_provider,
+                refunded,
+            });
+            Ok(())
+        }
+
+        /// (Index 71) Join the arbiter pool, reserving `stake`. Requires
+        /// `MinArbiterReputation`.
+        #[pallet::call_index(71)]
+        #[pallet::weight(T::WeightInfo::join_arbiter_pool())]
+        pub fn join_arbiter_pool(origin: OriginFor<T>, stake: BalanceOf<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(
+                !Arbiters::<T>::contains_key(&who),
+                Error::<T>::AlreadyArbiter
+            );
+            ensure!(
+                T::ReputationManager::meets_minimum_reputation(
+                    &who,
+                    T::MinArbiterReputation::get()
+                ),
+                Error::<T>::InsufficientReputation
+            );
+
+            ArbiterPool::<T>::try_mutate(|pool| pool.try_push(who.clone()))
+                .map_err(|_| Error::<T>::ArbiterPoolFull)?;
+            T::Currency::reserve(&who, stake).map_err(|_| Error::<T>::InsufficientBalance)?;
+            Arbiters::<T>::insert(
+                &who,
+                ArbiterInfo {
+                    stake,
+                    active_panels: 0,
+                },
+            );
+
+            Self::deposit_event(Event::ArbiterJoined { who, stake });
+            Ok(())
+        }
+
+        /// (Index 72) Leave the arbiter pool and unreserve the stake. Not
+        /// possible while sitting on a panel.
+        #[pallet::call_index(72)]
+        #[pallet::weight(T::WeightInfo::leave_arbiter_pool())]
+        pub fn leave_arbiter_pool(origin: OriginFor<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let info = Arbiters::<T>::get(&who).ok_or(Error::<T>::NotArbiter)?;
+            ensure!(info.active_panels == 0, Error::<T>::ArbiterOnPanel);
+
+            Arbiters::<T>::remove(&who);
+            ArbiterPool::<T>::mutate(|pool| pool.retain(|a| a != &who));
+            T::Currency::unreserve(&who, info.stake);
+
+            Self::deposit_event(Event::ArbiterLeft { who });
+            Ok(())
+        }
+
+        /// (Index 73) A party takes an open dispute to arbitration. The
+        /// caller pays the track's `juror_reward` for every seat, and
+        /// `juror_panel_size` arbiters staking at least `juror_stake` are
+        /// drawn from the pool, weighted by stake.
+        #[pallet::call_index(73)]
+        #[pallet::weight(T::WeightInfo::escalate_to_arbitration())]
+        pub fn escalate_to_arbitration(
+            origin: OriginFor<T>,
             dispute_id: DisputeId,
-            raised_by: T::AccountId,
-        },
-        DisputeResolvedByGovernance {
+        ) -> DispatchResult {
+            let caller = ensure_signed(origin)?;
+            let dispute = Disputes::<T>::get(dispute_id).ok_or(Error::<T>::DisputeNotFound)?;
+            ensure!(
+                dispute.status == DisputeStatus::Open,
+                Error::<T>::DisputeNotOpen
+            );
+            let inv = ServiceInvocations::<T>::get(dispute.invocation_id)
+                .ok_or(Error::<T>::InvocationNotFound)?;
+            ensure!(
+                inv.invoker == caller || inv.provider == caller,
+                Error::<T>::NotPartyToInvocation
+            );
+
+            let track = Self::dispute_track(dispute.tier);
+            let panel = Self::draw_arbitration_panel(
+                dispute_id,
+                track.juror_panel_size,
+                track.juror_stake,
+                &[inv.invoker, inv.provider],
+            )?;
+
+            let fee = track
+                .juror_reward
+                .saturating_mul(track.juror_panel_size.into());
+            if !fee.is_zero() {
+                T::Currency::transfer(
+                    &caller,
+                    &Self::arbitration_fee_account(dispute_id),
+                    fee,
+                    ExistenceRequirement::KeepAlive,
+                )
+                .map_err(|_| Error::<T>::InsufficientBalance)?;
+            }
+
+            for arbiter in &panel {
+                Arbiters::<T>::mutate(arbiter, |info| {
+                    if let Some(info) = info {
+                        info.active_panels = info.active_panels.saturating_add(1);
+                    }
+                });
+            }
+            let voting_deadline = <frame_system::Pallet<T>>::block_number()
+                .saturating_add(T::ArbitrationVotingPeriod::get().into());
+            let arbiters = panel
+                .into_iter()
+                .map(|a| (a, None))
+                .collect::<Vec<_>>()
+                .try_into()
+                .map_err(|_| Error::<T>::NotEnoughArbiters)?;
+            ArbitrationPanels::<T>::insert(
+                dispute_id,
+                ArbitrationPanel {
+                    arbiters,
+                    voting_deadline,
+                    fee_payer: caller.clone(),
+                },
+            );
+            ArbitrationsByDeadline::<T>::insert(voting_deadline, dispute_id, ());
+            Disputes::<T>::mutate(dispute_id, |maybe| {
+                if let Some(d) = maybe {
+                    d.status = DisputeStatus::Arbitration;
+                }
+            });
+            Self::clear_default_judgment(dispute_id);
+
+            Self::deposit_event(Event::ArbitrationStarted {
+                dispute_id,
+                escalated_by: caller,
+                panel_size: track.juror_panel_size,
+                fee,
+                voting_deadline,
+            });
+            Ok(())
+        }
+
+        /// (Index 74) An arbiter on the panel votes for the dispute's winner,
+        /// optionally proposing a provider share of the escrow. The first
+        /// winner to gather a majority of the panel settles it, and the
+        /// arbiters who voted for it are paid from the fee. If any of them
+        /// proposed a share, the escrow is split by the median proposal.
+        #[pallet::call_index(74)]
+        #[pallet::weight(T::WeightInfo::cast_arbitration_vote())]
+        pub fn cast_arbitration_vote(
+            origin: OriginFor<T>,
             dispute_id: DisputeId,
             winner: T::AccountId,
-        },
-    }
+            provider_share: Option<Perbill>,
+        ) -> DispatchResult {
+            let arbiter = ensure_signed(origin)?;
+            let dispute = Disputes::<T>::get(dispute_id).ok_or(Error::<T>::DisputeNotFound)?;
+            ensure!(
+                dispute.status == DisputeStatus::Arbitration,
+                Error::<T>::NotInArbitration
+            );
+            let inv = ServiceInvocations::<T>::get(dispute.invocation_id)
+                .ok_or(Error::<T>::InvocationNotFound)?;
+            ensure!(
+                inv.invoker == winner || inv.provider == winner,
+                Error::<T>::InvalidDisputeWinner
+            );
 
-    // =========================================================
-    // Errors
-    // =========================================================
+            let now = <frame_system::Pallet<T>>::block_number();
+            let votes = ArbitrationPanels::<T>::try_mutate(dispute_id, |maybe| {
+                let panel = maybe.as_mut().ok_or(Error::<T>::NotInArbitration)?;
+                ensure!(
+                    now <= panel.voting_deadline,
+                    Error::<T>::ArbitrationVotingClosed
+                );
+                let seat = panel
+                    .arbiters
+                    .iter_mut()
+                    .find(|(a, _)| *a == arbiter)
+                    .ok_or(Error::<T>::NotOnPanel)?;
+                ensure!(seat.1.is_none(), Error::<T>::AlreadyVoted);
+                seat.1 = Some((winner.clone(), provider_share));
+
+                let majority: Vec<Option<Perbill>> = panel
+                    .arbiters
+                    .iter()
+                    .filter_map(|(_, vote)| vote.as_ref())
+                    .filter(|(w, _)| *w == winner)
+                    .map(|(_, share)| *share)
+                    .collect();
+                Ok::<_, DispatchError>((majority, panel.arbiters.len()))
+            })?;
 
-    #[pallet::error]
-    pub enum Error<T> {
-        ListingNotFound,
-        ListingNotActive,
-        NotProvider,
-        NotInvoker,
-        InsufficientReputation,
-        InvocationNotFound,
-        InvalidInvocationStatus,
-        MilestoneIndexOutOfBounds,
-        MilestoneAlreadyApproved,
-        MilestoneNotSubmitted,
-        MilestonePercentagesInvalid,
-        TooManyMilestones,
-        TooManyTags,
-        TagTooLong,
-        TooManyListingsForTag,
-        TooManyListingsForProvider,
-        TooManyActiveInvocations,
-        NameTooLong,
-        DescriptionTooLong,
-        CidTooLong,
-        AutoApproveDelayTooLong,
-        PriceBelowMinimum,
-        PriceAboveMaximum,
-        CannotCancelActiveInvocation,
-        DeadlineNotPassed,
-        ListingHasActiveInvocations,
-        InsufficientBalance,
-        DisputeNotFound,
-        DisputeNotEscalated,
-        InvocationAlreadyDisputed,
-        NotPartyToInvocation,
-        RequirementsEmpty,
-    }
+            Self::deposit_event(Event::ArbitrationVoteCast {
+                dispute_id,
+                arbiter,
+            });
+
+            let (for_winner, panel_size) = votes;
+            if for_winner.len() > panel_size / 2 {
+                let mut shares: Vec<Perbill> = for_winner.into_iter().flatten().collect();
+                shares.sort();
+                let split = shares.get(shares.len().saturating_sub(1) / 2).copied();
+
+                // Settle the escrow before paying the panel out of the fee
+                // account, so the fee account's leftovers are all fee.
+                let amount = Self::settle_dispute(dispute_id, &winner, split)?;
+                Self::close_arbitration(dispute_id, Some(&winner));
+                Self::deposit_event(Event::DisputeResolvedByArbitration {
+                    dispute_id,
+                    winner,
+                    amount,
+                });
+            }
+            Ok(())
+        }
+
+        /// (Index 75) Approve a milestone and rate the provider in one call.
+        ///
+        /// Releases the milestone's escrow as `approve_milestone` does and
+        /// records a review of `rating` (1–5 stars) through the reputation
+        /// manager, with the hash of an off-chain comment. Both happen or
+        /// neither does, and the pair costs one quota unit rather than two
+        /// transactions.
+        #[pallet::call_index(75)]
+        #[pallet::weight(T::WeightInfo::approve_and_review())]
+        pub fn approve_and_review(
+            origin: OriginFor<T>,
+            invocation_id: InvocationId,
+            milestone_index: u32,
+            rating: u8,
+            comment_hash: T::Hash,
+        ) -> DispatchResult {
+            let invoker = ensure_signed(origin)?;
+            ensure!((1_u8..=5).contains(&rating), Error::<T>::InvalidRating);
+
+            let (provider, _) =
+                Self::do_approve_milestone(&invoker, invocation_id, milestone_index)?;
+            T::ReputationManager::on_review(
+                &invoker,
+                &provider,
+                rating,
+                invocation_id,
+                comment_hash.as_ref(),
+            );
 
-    // =========================================================
-    // Weight trait
-    // =========================================================
+            Self::deposit_event(Event::MilestoneReviewed {
+                invocation_id,
+                milestone_index,
+                rating,
+                comment_hash,
+            });
+            Ok(())
+        }
 
-    pub trait WeightInfo {
-        fn list_service() -> Weight;
-        fn update_listing() -> Weight;
-        fn delist_service() -> Weight;
-        fn invoke_service() -> Weight;
-        fn submit_invocation_work() -> Weight;
-        fn approve_milestone() -> Weight;
-        fn raise_dispute() -> Weight;
-        fn resolve_dispute_governance() -> Weight;
-        fn cancel_invocation() -> Weight;
-        fn try_expire_invocation() -> Weight;
-    }
+        /// (Index 76) Approve several milestones of one invocation.
+        ///
+        /// Each milestone is approved as by `approve_milestone`; if any
+        /// approval fails, none take effect.
+        #[pallet::call_index(76)]
+        #[pallet::weight(T::WeightInfo::approve_milestones(indices.len() as u32))]
+        pub fn approve_milestones(
+            origin: OriginFor<T>,
+            invocation_id: InvocationId,
+            indices: Vec<u32>,
+        ) -> DispatchResult {
+            let invoker = ensure_signed(origin)?;
+            ensure!(!indices.is_empty(), Error::<T>::EmptyBatch);
+            ensure!(
+                indices.len() <= T::MaxMilestones::get() as usize,
+                Error::<T>::BatchTooLarge
+            );
 
-    pub struct SubstrateWeight<T>(core::marker::PhantomData<T>);
+            let mut amount_released: BalanceOf<T> = Zero::zero();
+            for &milestone_index in &indices {
+                let (_, amount) =
+                    Self::do_approve_milestone(&invoker, invocation_id, milestone_index)?;
+                amount_released = amount_released.saturating_add(amount);
+            }
 
-    impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
-        fn list_service() -> Weight {
-            Weight::from_parts(10_000, 0)
-        }
-        fn update_listing() -> Weight {
-            Weight::from_parts(10_000, 0)
-        }
-        fn delist_service() -> Weight {
-            Weight::from_parts(10_000, 0)
-        }
-        fn invoke_service() -> Weight {
-            Weight::from_parts(10_000, 0)
-        }
-        fn submit_invocation_work() -> Weight {
-            Weight::from_parts(10_000, 0)
-        }
-        fn approve_milestone() -> Weight {
-            Weight::from_parts(10_000, 0)
-        }
-        fn raise_dispute() -> Weight {
-            Weight::from_parts(10_000, 0)
-        }
-        fn resolve_dispute_governance() -> Weight {
-            Weight::from_parts(10_000, 0)
+            Self::deposit_event(Event::MilestonesApproved {
+                invocation_id,
+                count: indices.len() as u32,
+                amount_released,
+            });
+            Ok(())
         }
-        fn cancel_invocation() -> Weight {
-            Weight::from_parts(10_000, 0)
+
+        /// (Index 77) Cancel several pending invocations and refund their
+        /// escrow.
+        ///
+        /// Each invocation is cancelled as by `cancel_invocation`; if any
+        /// cancellation fails, none take effect.
+        #[pallet::call_index(77)]
+        #[pallet::weight(T::WeightInfo::batch_cancel(invocation_ids.len() as u32))]
+        pub fn batch_cancel(
+            origin: OriginFor<T>,
+            invocation_ids: Vec<InvocationId>,
+        ) -> DispatchResult {
+            let invoker = ensure_signed(origin)?;
+            ensure!(!invocation_ids.is_empty(), Error::<T>::EmptyBatch);
+            ensure!(
+                invocation_ids.len() <= T::MaxActiveInvocationsPerAccount::get() as usize,
+                Error::<T>::BatchTooLarge
+            );
+
+            let mut amount_refunded: BalanceOf<T> = Zero::zero();
+            for &invocation_id in &invocation_ids {
+                let refund = Self::do_cancel_invocation(&invoker, invocation_id)?;
+                amount_refunded = amount_refunded.saturating_add(refund);
+            }
+
+            Self::deposit_event(Event::InvocationsCancelled {
+                invoker,
+                count: invocation_ids.len() as u32,
+                amount_refunded,
+            });
+            Ok(())
         }
-        fn try_expire_invocation() -> Weight {
-            Weight::from_parts(10_000, 0)
+
+        /// (Index 78) Bind a cold controller to the caller's provider account.
+        ///
+        /// Only possible while no controller is bound; afterwards only the
+        /// controller can rotate itself or change where a listing's escrow
+        /// releases go, so earnings stay away from the operational key.
+        /// Changes take `PayoutChangeDelay` blocks to apply.
+        #[pallet::call_index(78)]
+        #[pallet::weight(T::WeightInfo::bind_payout_controller())]
+        pub fn bind_payout_controller(
+            origin: OriginFor<T>,
+            controller: T::AccountId,
+        ) -> DispatchResult {
+            let provider = ensure_signed(origin)?;
+            ensure!(
+                !PayoutControllers::<T>::contains_key(&provider),
+                Error::<T>::PayoutControllerAlreadyBound
+            );
+
+            PayoutControllers::<T>::insert(&provider, &controller);
+            Self::deposit_event(Event::PayoutControllerSet {
+                provider,
+                controller,
+            });
+            Ok(())
         }
-    }
 
-    // =========================================================
-    // Extrinsics
-    // =========================================================
+        /// (Index 79) Hand `provider`'s payout control to a new controller.
+        #[pallet::call_index(79)]
+        #[pallet::weight(T::WeightInfo::rotate_payout_controller())]
+        pub fn rotate_payout_controller(
+            origin: OriginFor<T>,
+            provider: T::AccountId,
+            new_controller: T::AccountId,
+        ) -> DispatchResult {
+            let controller = ensure_signed(origin)?;
+            Self::ensure_payout_controller(&provider, &controller)?;
 
-    #[allow(clippy::too_many_arguments)]
-    #[pallet::call]
-    impl<T: Config> Pallet<T> {
-        /// (Index 10) Create a persistent service listing.
+            PayoutControllers::<T>::insert(&provider, &new_controller);
+            Self::deposit_event(Event::PayoutControllerSet {
+                provider,
+                controller: new_controller,
+            });
+            Ok(())
+        }
+
+        /// (Index 80) Schedule a new payout account for a listing.
         ///
-        /// The caller must have sufficient reputation (`MinListingReputation`).
-        #[pallet::call_index(10)]
-        #[pallet::weight(T::WeightInfo::list_service())]
-        pub fn list_service(
+        /// Callable only by the provider's payout controller. The change
+        /// applies after `PayoutChangeDelay` blocks; scheduling again replaces
+        /// the pending change and restarts the delay.
+        #[pallet::call_index(80)]
+        #[pallet::weight(T::WeightInfo::schedule_payout_change())]
+        pub fn schedule_payout_change(
             origin: OriginFor<T>,
-            name: Vec<u8>,
-            description: Vec<u8>,
-            tags: Vec<Vec<u8>>,
-            min_price: BalanceOf<T>,
-            max_price: BalanceOf<T>,
-            payment_mode: PaymentMode,
-            sla_response_blocks: u32,
-            sla_completion_blocks: u32,
-            auto_approve_delay_blocks: u32,
-            min_invoker_reputation: Option<u32>,
-            milestones_required: bool,
+            listing_id: ListingId,
+            payout_account: Option<T::AccountId>,
         ) -> DispatchResult {
-            let provider = ensure_signed(origin)?;
+            let controller = ensure_signed(origin)?;
+            let listing =
+                ServiceListings::<T>::get(listing_id).ok_or(Error::<T>::ListingNotFound)?;
+            Self::ensure_payout_controller(&listing.provider, &controller)?;
 
-            // Reputation gate
-            ensure!(
-                T::ReputationManager::meets_minimum_reputation(
-                    &provider,
-                    T::MinListingReputation::get()
-                ),
-                Error::<T>::InsufficientReputation
+            let effective_at = <frame_system::Pallet<T>>::block_number()
+                .saturating_add(T::PayoutChangeDelay::get().into());
+            PendingPayoutChanges::<T>::insert(
+                listing_id,
+                PendingPayoutChange {
+                    payout_account: payout_account.clone(),
+                    effective_at,
+                },
             );
+            Self::deposit_event(Event::PayoutChangeScheduled {
+                listing_id,
+                payout_account,
+                effective_at,
+            });
+            Ok(())
+        }
 
+        /// (Index 81) Permissionless: apply a payout-account change whose
+        /// delay has passed.
+        #[pallet::call_index(81)]
+        #[pallet::weight(T::WeightInfo::apply_payout_change())]
+        pub fn apply_payout_change(origin: OriginFor<T>, listing_id: ListingId) -> DispatchResult {
+            ensure_signed(origin)?;
+            let change = PendingPayoutChanges::<T>::get(listing_id)
+                .ok_or(Error::<T>::NoPendingPayoutChange)?;
             ensure!(
-                auto_approve_delay_blocks <= T::AutoApproveMaxDelay::get(),
-                Error::<T>::AutoApproveDelayTooLong
+                <frame_system::Pallet<T>>::block_number() >= change.effective_at,
+                Error::<T>::PayoutChangeNotDue
             );
 
-            let name: BoundedVec<u8, T::MaxNameLength> =
-                name.try_into().map_err(|_| Error::<T>::NameTooLong)?;
-            let description: BoundedVec<u8, T::MaxDescriptionLength> = description
-                .try_into()
-                .map_err(|_| Error::<T>::DescriptionTooLong)?;
+            ServiceListings::<T>::try_mutate(listing_id, |maybe| {
+                let listing = maybe.as_mut().ok_or(Error::<T>::ListingNotFound)?;
+                listing.payout_account = change.payout_account.clone();
+                Ok::<_, DispatchError>(())
+            })?;
+            PendingPayoutChanges::<T>::remove(listing_id);
+
+            Self::deposit_event(Event::PayoutChangeApplied {
+                listing_id,
+                payout_account: change.payout_account,
+            });
+            Ok(())
+        }
 
+        /// (Index 82) Limit the invocations the caller accepts as a provider.
+        ///
+        /// `max_concurrent_invocations` caps open invocations across all of
+        /// the caller's listings; `paused_until` refuses new invocations
+        /// before that block. Existing invocations are unaffected. Passing
+        /// `None` for both removes the limits. `invoke_service` then fails
+        /// with `ProviderAtCapacity` or `ProviderUnavailable` rather than
+        /// accept work the provider cannot meet its SLA on.
+        #[pallet::call_index(82)]
+        #[pallet::weight(T::WeightInfo::set_provider_capacity())]
+        pub fn set_provider_capacity(
+            origin: OriginFor<T>,
+            max_concurrent_invocations: Option<u32>,
+            paused_until: Option<BlockNumberFor<T>>,
+        ) -> DispatchResult {
+            let provider = ensure_signed(origin)?;
+
+            let capacity = ProviderCapacity {
+                max_concurrent_invocations,
+                paused_until,
+            };
+            if capacity == ProviderCapacity::default() {
+                ProviderCapacities::<T>::remove(&provider);
+            } else {
+                ProviderCapacities::<T>::insert(&provider, capacity);
+            }
+
+            Self::deposit_event(Event::ProviderCapacitySet {
+                provider,
+                max_concurrent_invocations,
+                paused_until,
+            });
+            Ok(())
+        }
+
+        /// (Index 83) Flag a listing as illegal or malicious, reserving
+        /// `FlagBond`.
+        ///
+        /// Once `FlagThreshold` distinct accounts have flagged it the listing
+        /// is paused until `ModerationOrigin` resolves the flags.
+        #[pallet::call_index(83)]
+        #[pallet::weight(T::WeightInfo::flag_listing())]
+        pub fn flag_listing(
+            origin: OriginFor<T>,
+            listing_id: ListingId,
+            reason: Vec<u8>,
+        ) -> DispatchResult {
+            let flagger = ensure_signed(origin)?;
+            let listing =
+                ServiceListings::<T>::get(listing_id).ok_or(Error::<T>::ListingNotFound)?;
+            let under_review = ListingsUnderReview::<T>::contains_key(listing_id);
+            ensure!(listing.active || under_review, Error::<T>::ListingNotActive);
             ensure!(
-                tags.len() <= T::MaxTagsPerListing::get() as usize,
-                Error::<T>::TooManyTags
+                listing.provider != flagger,
+                Error::<T>::CannotFlagOwnListing
             );
+            let reason: BoundedVec<u8, T::MaxFlagReasonLength> = reason
+                .try_into()
+                .map_err(|_| Error::<T>::FlagReasonTooLong)?;
 
-            let mut bounded_tags: BoundedVec<
-                BoundedVec<u8, T::MaxTagLength>,
-                T::MaxTagsPerListing,
-            > = BoundedVec::new();
-            for tag in &tags {
-                let bounded_tag: BoundedVec<u8, T::MaxTagLength> =
-                    tag.clone().try_into().map_err(|_| Error::<T>::TagTooLong)?;
-                bounded_tags
-                    .try_push(bounded_tag)
-                    .map_err(|_| Error::<T>::TooManyTags)?;
-            }
+            let bond = T::FlagBond::get();
+            let flags = ListingFlags::<T>::try_mutate(listing_id, |flags| {
+                ensure!(
+                    !flags.iter().any(|flag| flag.flagger == flagger),
+                    Error::<T>::AlreadyFlagged
+                );
+                flags
+                    .try_push(ListingFlag {
+                        flagger: flagger.clone(),
+                        reason,
+                        bond,
+                    })
+                    .map_err(|_| Error::<T>::TooManyFlags)?;
+                T::Currency::reserve(&flagger, bond)?;
+                Ok::<u32, DispatchError>(flags.len() as u32)
+            })?;
 
-            let listing_id = ListingCount::<T>::get();
+            Self::deposit_event(Event::ListingFlagged {
+                listing_id,
+                flagger,
+                flags,
+            });
 
-            let now = <frame_system::Pallet<T>>::block_number();
+            if !under_review && flags >= T::FlagThreshold::get() {
+                ServiceListings::<T>::mutate(listing_id, |maybe| {
+                    if let Some(listing) = maybe {
+                        listing.active = false;
+                    }
+                });
+                let now = <frame_system::Pallet<T>>::block_number();
+                ListingsUnderReview::<T>::insert(listing_id, now);
+                Self::deposit_event(Event::ListingPausedForReview { listing_id, flags });
+            }
+            Ok(())
+        }
 
-            let listing = ServiceListing {
-                id: listing_id,
-                provider: provider.clone(),
-                name,
-                description,
-                tags: bounded_tags.clone(),
-                min_price,
-                max_price,
-                payment_mode,
-                sla_response_blocks,
-                sla_completion_blocks,
-                auto_approve_delay_blocks,
-                min_invoker_reputation,
-                milestones_required,
-                active: true,
-                created_at: now,
-                total_invocations: 0,
-                successful_invocations: 0,
-            };
+        /// (Index 84) `ModerationOrigin` resolves the open flags on a listing.
+        ///
+        /// With `remove`, the listing is taken down for good, its whole
+        /// provider bond goes to `SlashTreasury` and flaggers get their bonds
+        /// back. Otherwise the listing is restored and the flaggers' bonds go
+        /// to `SlashTreasury`.
+        #[pallet::call_index(84)]
+        #[pallet::weight(T::WeightInfo::resolve_listing_flags(T::MaxFlagsPerListing::get()))]
+        pub fn resolve_listing_flags(
+            origin: OriginFor<T>,
+            listing_id: ListingId,
+            remove: bool,
+        ) -> DispatchResult {
+            T::ModerationOrigin::ensure_origin(origin)?;
+            let listing =
+                ServiceListings::<T>::get(listing_id).ok_or(Error::<T>::ListingNotFound)?;
+            let flags = ListingFlags::<T>::take(listing_id);
+            ensure!(!flags.is_empty(), Error::<T>::NoListingFlags);
+            let was_under_review = ListingsUnderReview::<T>::take(listing_id).is_some();
+            let treasury = T::SlashTreasury::get();
+
+            if remove {
+                for flag in flags {
+                    T::Currency::unreserve(&flag.flagger, flag.bond);
+                }
+                ServiceListings::<T>::mutate(listing_id, |maybe| {
+                    if let Some(listing) = maybe {
+                        listing.active = false;
+                    }
+                });
+                Self::unindex_listing(&listing);
+                // Lifting a suspension must not bring the listing back.
+                SuspendedProviders::<T>::mutate(&listing.provider, |maybe| {
+                    if let Some(paused) = maybe {
+                        paused.retain(|&id| id != listing_id);
+                    }
+                });
 
-            ServiceListings::<T>::insert(listing_id, listing);
-            ListingCount::<T>::put(listing_id + 1);
+                let bond = ProviderBonds::<T>::take(listing_id).unwrap_or_default();
+                let unpaid = T::Currency::repatriate_reserved(
+                    &listing.provider,
+                    &treasury,
+                    bond,
+                    BalanceStatus::Free,
+                )
+                .unwrap_or(bond);
+                // Whatever could not be moved stays withdrawable by the provider.
+                if !unpaid.is_zero() {
+                    ProviderBonds::<T>::insert(listing_id, unpaid);
+                }
 
-            // Update indexes
-            ListingsByProvider::<T>::try_mutate(&provider, |ids| {
-                ids.try_push(listing_id)
-                    .map_err(|_| Error::<T>::TooManyListingsForProvider)
-            })?;
+                Self::deposit_event(Event::ListingTakenDown {
+                    listing_id,
+                    bond_slashed: bond.saturating_sub(unpaid),
+                });
+            } else {
+                let mut bonds_forfeited = BalanceOf::<T>::zero();
+                for flag in flags {
+                    let unpaid = T::Currency::repatriate_reserved(
+                        &flag.flagger,
+                        &treasury,
+                        flag.bond,
+                        BalanceStatus::Free,
+                    )
+                    .unwrap_or(flag.bond);
+                    T::Currency::unreserve(&flag.flagger, unpaid);
+                    bonds_forfeited =
+                        bonds_forfeited.saturating_add(flag.bond.saturating_sub(unpaid));
+                }
 
-            for tag in &bounded_tags {
-                ListingsByTag::<T>::try_mutate(tag, |ids| {
-                    ids.try_push(listing_id)
-                        .map_err(|_| Error::<T>::TooManyListingsForTag)
-                })?;
+                if was_under_review {
+                    // A provider suspended during review gets the listing
+                    // back when the suspension lifts.
+                    let suspended =
+                        SuspendedProviders::<T>::mutate(&listing.provider, |maybe| match maybe {
+                            Some(paused) => {
+                                // Bounded by the same limit as ListingsByProvider.
+                                let _ = paused.try_push(listing_id);
+                                true
+                            }
+                            None => false,
+                        });
+                    if !suspended {
+                        ServiceListings::<T>::mutate(listing_id, |maybe| {
+                            if let Some(listing) = maybe {
+                                listing.active = true;
+                            }
+                        });
+                    }
+                }
+
+                Self::deposit_event(Event::ListingFlagsCleared {
+                    listing_id,
+                    bonds_forfeited,
+                });
             }
+            Ok(())
+        }
+
+        /// (Index 85) Invoker stakes an open invocation's escrow through
+        /// `EscrowYield`.
+        ///
+        /// The position is closed before the escrow next pays out, refunds or
+        /// settles, and its reward is split by `YieldInvokerShare`.
+        #[pallet::call_index(85)]
+        #[pallet::weight(T::WeightInfo::stake_escrow())]
+        pub fn stake_escrow(origin: OriginFor<T>, invocation_id: InvocationId) -> DispatchResult {
+            let invoker = ensure_signed(origin)?;
+            let inv = ServiceInvocations::<T>::get(invocation_id)
+                .ok_or(Error::<T>::InvocationNotFound)?;
+            ensure!(inv.invoker == invoker, Error::<T>::NotInvoker);
+            ensure!(
+                matches!(
+                    inv.status,
+                    InvocationStatus::Pending
+                        | InvocationStatus::Accepted
+                        | InvocationStatus::InProgress
+                        | InvocationStatus::WorkSubmitted
+                ),
+                Error::<T>::InvalidInvocationStatus
+            );
+            ensure!(
+                !YieldPositions::<T>::contains_key(invocation_id),
+                Error::<T>::EscrowAlreadyStaked
+            );
+
+            let escrow_account = Self::invocation_escrow_account(invocation_id);
+            let amount = if Self::is_escrowed(invocation_id) {
+                T::Currency::free_balance(&escrow_account)
+                    .saturating_sub(T::Currency::minimum_balance())
+            } else {
+                Zero::zero()
+            };
+            ensure!(!amount.is_zero(), Error::<T>::NothingToStake);
 
-            Self::deposit_event(Event::ServiceListed {
-                listing_id,
-                provider,
-                min_price,
-            });
+            T::EscrowYield::stake(&escrow_account, amount)?;
+            YieldPositions::<T>::insert(invocation_id, amount);
 
+            Self::deposit_event(Event::EscrowStaked {
+                invocation_id,
+                amount,
+            });
             Ok(())
         }
 
-        /// (Index 11) Update listing metadata.
+        /// (Index 86) Open a competition among providers of `tag`.
         ///
-        /// Only the listing provider may update. Cannot update if there are active invocations.
-        #[pallet::call_index(11)]
-        #[pallet::weight(T::WeightInfo::update_listing())]
-        pub fn update_listing(
+        /// Escrows `reward` plus `participation_fee` for every entrant but
+        /// the winner. Up to `max_entrants` providers may enter and submit
+        /// until `deadline_blocks` from now.
+        #[pallet::call_index(86)]
+        #[pallet::weight(T::WeightInfo::open_competition())]
+        pub fn open_competition(
             origin: OriginFor<T>,
-            listing_id: ListingId,
-            name: Option<Vec<u8>>,
-            description: Option<Vec<u8>>,
-            min_price: Option<BalanceOf<T>>,
-            max_price: Option<BalanceOf<T>>,
-            sla_response_blocks: Option<u32>,
-            sla_completion_blocks: Option<u32>,
-            auto_approve_delay_blocks: Option<u32>,
+            tag: Vec<u8>,
+            requirements: Vec<u8>,
+            reward: BalanceOf<T>,
+            participation_fee: BalanceOf<T>,
+            max_entrants: u32,
+            deadline_blocks: u32,
         ) -> DispatchResult {
-            let provider = ensure_signed(origin)?;
+            let invoker = ensure_signed(origin)?;
+            ensure!(
+                !reward.is_zero()
+                    && deadline_blocks > 0
+                    && max_entrants >= 2
+                    && max_entrants <= T::MaxCompetitionEntrants::get(),
+                Error::<T>::InvalidCompetitionTerms
+            );
+            ensure!(!requirements.is_empty(), Error::<T>::RequirementsEmpty);
+            let tag: BoundedVec<u8, T::MaxTagLength> =
+                tag.try_into().map_err(|_| Error::<T>::TagTooLong)?;
+            let requirements: BoundedVec<u8, T::MaxDescriptionLength> = requirements
+                .try_into()
+                .map_err(|_| Error::<T>::DescriptionTooLong)?;
 
-            ServiceListings::<T>::try_mutate(listing_id, |maybe_listing| {
-                let listing = maybe_listing.as_mut().ok_or(Error::<T>::ListingNotFound)?;
-                ensure!(listing.provider == provider, Error::<T>::NotProvider);
+            let competition_id = CompetitionCount::<T>::get();
+            let escrowed = participation_fee
+                .saturating_mul((max_entrants - 1).into())
+                .saturating_add(reward);
+            T::Currency::transfer(
+                &invoker,
+                &Self::competition_escrow_account(competition_id),
+                escrowed,
+                ExistenceRequirement::KeepAlive,
+            )
+            .map_err(|_| Error::<T>::InsufficientBalance)?;
 
-                // Ensure no active invocations
-                let active_count = InvocationsByListing::<T>::iter_prefix(listing_id).count();
-                ensure!(active_count == 0, Error::<T>::ListingHasActiveInvocations);
+            let deadline =
+                <frame_system::Pallet<T>>::block_number().saturating_add(deadline_blocks.into());
+            Competitions::<T>::insert(
+                competition_id,
+                Competition {
+                    invoker: invoker.clone(),
+                    tag,
+                    requirements,
+                    reward,
+                    participation_fee,
+                    max_entrants,
+                    deadline,
+                    entries: BoundedVec::new(),
+                },
+            );
+            CompetitionCount::<T>::put(competition_id + 1);
 
-                if let Some(n) = name {
-                    listing.name = n.try_into().map_err(|_| Error::<T>::NameTooLong)?;
-                }
-                if let Some(d) = description {
-                    listing.description =
-                        d.try_into().map_err(|_| Error::<T>::DescriptionTooLong)?;
-                }
-                if let Some(p) = min_price {
-                    listing.min_price = p;
-                }
-                if let Some(p) = max_price {
-                    listing.max_price = p;
-                }
-                if let Some(s) = sla_response_blocks {
-                    listing.sla_response_blocks = s;
-                }
-                if let Some(s) = sla_completion_blocks {
-                    listing.sla_completion_blocks = s;
-                }
-                if let Some(d) = auto_approve_delay_blocks {
-                    ensure!(
-                        d <= T::AutoApproveMaxDelay::get(),
-                        Error::<T>::AutoApproveDelayTooLong
-                    );
-                    listing.auto_approve_delay_blocks = d;
-                }
+            Self::deposit_event(Event::CompetitionOpened {
+                competition_id,
+                invoker,
+                escrowed,
+            });
+            Ok(())
+        }
 
-                Ok::<(), DispatchError>(())
+        /// (Index 87) Provider claims a slot in a competition with one of
+        /// its active listings carrying the competition's tag.
+        #[pallet::call_index(87)]
+        #[pallet::weight(T::WeightInfo::enter_competition())]
+        pub fn enter_competition(
+            origin: OriginFor<T>,
+            competition_id: CompetitionId,
+            listing_id: ListingId,
+        ) -> DispatchResult {
+            let provider = ensure_signed(origin)?;
+            let listing =
+                ServiceListings::<T>::get(listing_id).ok_or(Error::<T>::ListingNotFound)?;
+            ensure!(listing.provider == provider, Error::<T>::NotProvider);
+            ensure!(listing.active, Error::<T>::ListingNotActive);
+            ensure!(
+                !SuspendedProviders::<T>::contains_key(&provider),
+                Error::<T>::ProviderSuspended
+            );
+
+            Competitions::<T>::try_mutate(competition_id, |maybe| {
+                let competition = maybe.as_mut().ok_or(Error::<T>::CompetitionNotFound)?;
+                ensure!(
+                    <frame_system::Pallet<T>>::block_number() <= competition.deadline,
+                    Error::<T>::CompetitionDeadlinePassed
+                );
+                ensure!(
+                    competition.invoker != provider,
+                    Error::<T>::CannotEnterOwnCompetition
+                );
+                ensure!(
+                    listing.tags.contains(&competition.tag),
+                    Error::<T>::ListingTagMismatch
+                );
+                ensure!(
+                    !competition.entries.iter().any(|e| e.provider == provider),
+                    Error::<T>::AlreadyEntered
+                );
+                ensure!(
+                    (competition.entries.len() as u32) < competition.max_entrants,
+                    Error::<T>::CompetitionFull
+                );
+                competition
+                    .entries
+                    .try_push(CompetitionEntry {
+                        provider: provider.clone(),
+                        listing_id,
+                        result: None,
+                    })
+                    .map_err(|_| Error::<T>::CompetitionFull)?;
+                Ok::<_, DispatchError>(())
             })?;
 
-            Self::deposit_event(Event::ListingUpdated { listing_id });
+            Self::deposit_event(Event::CompetitionEntered {
+                competition_id,
+                provider,
+                listing_id,
+            });
             Ok(())
         }
 
-        /// (Index 12) Deactivate a service listing.
-        #[pallet::call_index(12)]
-        #[pallet::weight(T::WeightInfo::delist_service())]
-        pub fn delist_service(origin: OriginFor<T>, listing_id: ListingId) -> DispatchResult {
+        /// (Index 88) Entrant submits its result CID before the deadline.
+        #[pallet::call_index(88)]
+        #[pallet::weight(T::WeightInfo::submit_competition_result())]
+        pub fn submit_competition_result(
+            origin: OriginFor<T>,
+            competition_id: CompetitionId,
+            result: Vec<u8>,
+        ) -> DispatchResult {
             let provider = ensure_signed(origin)?;
+            ensure!(!result.is_empty(), Error::<T>::RequirementsEmpty);
+            let result: BoundedVec<u8, T::MaxDescriptionLength> = result
+                .try_into()
+                .map_err(|_| Error::<T>::DescriptionTooLong)?;
 
-            ServiceListings::<T>::try_mutate(listing_id, |maybe_listing| {
-                let listing = maybe_listing.as_mut().ok_or(Error::<T>::ListingNotFound)?;
-                ensure!(listing.provider == provider, Error::<T>::NotProvider);
-                listing.active = false;
-                Ok::<(), DispatchError>(())
+            Competitions::<T>::try_mutate(competition_id, |maybe| {
+                let competition = maybe.as_mut().ok_or(Error::<T>::CompetitionNotFound)?;
+                ensure!(
+                    <frame_system::Pallet<T>>::block_number() <= competition.deadline,
+                    Error::<T>::CompetitionDeadlinePassed
+                );
+                let entry = competition
+                    .entries
+                    .iter_mut()
+                    .find(|e| e.provider == provider)
+                    .ok_or(Error::<T>::NotEntrant)?;
+                ensure!(entry.result.is_none(), Error::<T>::ResultAlreadySubmitted);
+                entry.result = Some(result);
+                Ok::<_, DispatchError>(())
             })?;
 
-            // Remove from tag indexes
-            let listing =
-                ServiceListings::<T>::get(listing_id).ok_or(Error::<T>::ListingNotFound)?;
-            for tag in &listing.tags {
-                ListingsByTag::<T>::mutate(tag, |ids| {
-                    ids.retain(|&id| id != listing_id);
-                });
-            }
-
-            Self::deposit_event(Event::ServiceDelisted { listing_id });
+            Self::deposit_event(Event::CompetitionResultSubmitted {
+                competition_id,
+                provider,
+            });
             Ok(())
         }
 
-        /// (Index 13) Invoke a service listing (escrow path).
+        /// (Index 89) Invoker picks the winning submission.
         ///
-        /// Locks `agreed_price` in the pallet's escrow sub-account derived from
-        /// the invocation ID. Provider must accept before work starts.
-        #[pallet::call_index(13)]
-        #[pallet::weight(T::WeightInfo::invoke_service())]
-        pub fn invoke_service(
+        /// The winner's listing payee receives the reward, every other
+        /// submitter the participation fee, and the invoker the rest.
+        #[pallet::call_index(89)]
+        #[pallet::weight(T::WeightInfo::award_competition(T::MaxCompetitionEntrants::get()))]
+        pub fn award_competition(
             origin: OriginFor<T>,
-            listing_id: ListingId,
-            requirements: Vec<u8>,
-            milestones: Option<Vec<MilestoneSpec>>,
-            agreed_price: BalanceOf<T>,
-            deadline_blocks: u32,
+            competition_id: CompetitionId,
+            winner: T::AccountId,
         ) -> DispatchResult {
             let invoker = ensure_signed(origin)?;
-
-            let listing =
-                ServiceListings::<T>::get(listing_id).ok_or(Error::<T>::ListingNotFound)?;
-
-            ensure!(listing.active, Error::<T>::ListingNotActive);
+            let competition =
+                Competitions::<T>::get(competition_id).ok_or(Error::<T>::CompetitionNotFound)?;
+            ensure!(competition.invoker == invoker, Error::<T>::NotInvoker);
             ensure!(
-                agreed_price >= listing.min_price,
-                Error::<T>::PriceBelowMinimum
+                competition
+                    .entries
+                    .iter()
+                    .any(|e| e.provider == winner && e.result.is_some()),
+                Error::<T>::NoSubmission
             );
+
+            Competitions::<T>::remove(competition_id);
+            let (paid, refunded) =
+                Self::settle_competition(competition_id, &competition, Some(&winner));
+            let fees_paid = paid.saturating_sub(competition.reward);
+            T::ReputationManager::on_task_completed(&winner, competition.reward);
+
+            Self::deposit_event(Event::CompetitionAwarded {
+                competition_id,
+                winner,
+                reward: competition.reward,
+                fees_paid,
+                refunded,
+            });
+            Ok(())
+        }
+
+        /// (Index 90) Close a competition without a winner.
+        ///
+        /// The invoker may close it while nobody has entered. Anyone may
+        /// close it once the deadline has passed with no submissions, or
+        /// once `CompetitionAwardPeriod` has also passed without an award;
+        /// in that case the reward is split equally among the submitters.
+        #[pallet::call_index(90)]
+        #[pallet::weight(T::WeightInfo::close_competition(T::MaxCompetitionEntrants::get()))]
+        pub fn close_competition(
+            origin: OriginFor<T>,
+            competition_id: CompetitionId,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let competition =
+                Competitions::<T>::get(competition_id).ok_or(Error::<T>::CompetitionNotFound)?;
+
+            let now = <frame_system::Pallet<T>>::block_number();
+            let submitted = competition.entries.iter().any(|e| e.result.is_some());
+            let award_end = competition
+                .deadline
+                .saturating_add(T::CompetitionAwardPeriod::get().into());
             ensure!(
-                listing.max_price == listing.min_price || agreed_price <= listing.max_price,
-                Error::<T>::PriceAboveMaximum
+                (who == competition.invoker && competition.entries.is_empty())
+                    || (now > competition.deadline && !submitted)
+                    || now > award_end,
+                Error::<T>::CompetitionStillRunning
             );
 
-            // Per-listing invoker reputation gate
-            if let Some(min_rep) = listing.min_invoker_reputation {
-                ensure!(
-                    T::ReputationManager::meets_minimum_reputation(&invoker, min_rep),
-                    Error::<T>::InsufficientReputation
-                );
+            Competitions::<T>::remove(competition_id);
+            let (paid, refunded) = Self::settle_competition(competition_id, &competition, None);
+
+            Self::deposit_event(Event::CompetitionClosed {
+                competition_id,
+                paid,
+                refunded,
+            });
+            Ok(())
+        }
+
+        /// (Index 91) `AttestorOrigin` approves or removes a build attestor.
+        ///
+        /// Removing an attestor leaves its attestations in place; revoke
+        /// them separately if they are no longer trusted.
+        #[pallet::call_index(91)]
+        #[pallet::weight(T::WeightInfo::set_build_attestor())]
+        pub fn set_build_attestor(
+            origin: OriginFor<T>,
+            attestor: T::AccountId,
+            approved: bool,
+        ) -> DispatchResult {
+            T::AttestorOrigin::ensure_origin(origin)?;
+            if approved {
+                BuildAttestors::<T>::insert(&attestor, ());
+            } else {
+                BuildAttestors::<T>::remove(&attestor);
             }
+            Self::deposit_event(Event::BuildAttestorSet { attestor, approved });
+            Ok(())
+        }
 
-            let requirements: BoundedVec<u8, T::MaxDescriptionLength> = requirements
+        /// (Index 92) Approved attestor records a reproducible build of
+        /// `code_hash`.
+        ///
+        /// Providers bind listings to attested builds, and invokers that
+        /// `set_attestation_requirement` can only invoke bound listings.
+        #[pallet::call_index(92)]
+        #[pallet::weight(T::WeightInfo::attest_build())]
+        pub fn attest_build(
+            origin: OriginFor<T>,
+            code_hash: T::Hash,
+            toolchain: Vec<u8>,
+            builder_signature: Vec<u8>,
+        ) -> DispatchResult {
+            let attestor = ensure_signed(origin)?;
+            ensure!(
+                BuildAttestors::<T>::contains_key(&attestor),
+                Error::<T>::NotBuildAttestor
+            );
+            ensure!(
+                !BuildAttestations::<T>::contains_key(code_hash),
+                Error::<T>::BuildAlreadyAttested
+            );
+            let toolchain: BoundedVec<u8, T::MaxToolchainLength> = toolchain
                 .try_into()
-                .map_err(|_| Error::<T>::DescriptionTooLong)?;
+                .map_err(|_| Error::<T>::ToolchainTooLong)?;
+            let builder_signature: BoundedVec<u8, ConstU32<MAX_BUILD_SIGNATURE_LEN>> =
+                builder_signature
+                    .try_into()
+                    .map_err(|_| Error::<T>::BuilderSignatureTooLong)?;
+
+            BuildAttestations::<T>::insert(
+                code_hash,
+                BuildAttestation {
+                    attestor: attestor.clone(),
+                    toolchain,
+                    builder_signature,
+                    attested_at: <frame_system::Pallet<T>>::block_number(),
+                },
+            );
 
-            // Build milestones
-            let bounded_milestones = Self::build_milestones(milestones)?;
+            Self::deposit_event(Event::BuildAttested {
+                code_hash,
+                attestor,
+            });
+            Ok(())
+        }
 
-            let invocation_id = InvocationCount::<T>::get();
-            let now = <frame_system::Pallet<T>>::block_number();
-            let deadline = now + deadline_blocks.into();
+        /// (Index 93) Provider binds a listing to an attested build,
+        /// replacing any previous binding.
+        ///
+        /// A listing paused by a revocation is reactivated, unless its
+        /// provider is suspended, in which case it returns with the
+        /// suspension's other listings.
+        #[pallet::call_index(93)]
+        #[pallet::weight(T::WeightInfo::bind_build_attestation())]
+        pub fn bind_build_attestation(
+            origin: OriginFor<T>,
+            listing_id: ListingId,
+            code_hash: T::Hash,
+        ) -> DispatchResult {
+            let provider = ensure_signed(origin)?;
+            let listing =
+                ServiceListings::<T>::get(listing_id).ok_or(Error::<T>::ListingNotFound)?;
+            ensure!(listing.provider == provider, Error::<T>::NotProvider);
+            ensure!(
+                BuildAttestations::<T>::contains_key(code_hash),
+                Error::<T>::BuildAttestationNotFound
+            );
 
-            // Lock escrow (transfer from invoker to pallet escrow sub-account)
-            let escrow_account = Self::invocation_escrow_account(invocation_id);
-            T::Currency::transfer(
-                &invoker,
-                &escrow_account,
-                agreed_price,
-                ExistenceRequirement::KeepAlive,
-            )
-            .map_err(|_| Error::<T>::InsufficientBalance)?;
+            Self::unbind_build(listing_id);
+            ListingsByBuild::<T>::try_mutate(code_hash, |ids| {
+                ids.try_push(listing_id)
+                    .map_err(|_| Error::<T>::TooManyListingsForBuild)
+            })?;
+            ListingBuilds::<T>::insert(listing_id, code_hash);
+
+            let reactivated = ListingsAwaitingAttestation::<T>::take(listing_id).is_some();
+            if reactivated {
+                let suspended = SuspendedProviders::<T>::mutate(&provider, |maybe| match maybe {
+                    Some(paused) => {
+                        // Bounded by the same limit as ListingsByProvider.
+                        let _ = paused.try_push(listing_id);
+                        true
+                    }
+                    None => false,
+                });
+                if !suspended {
+                    ServiceListings::<T>::mutate(listing_id, |maybe| {
+                        if let Some(listing) = maybe {
+                            listing.active = true;
+                        }
+                    });
+                }
+            }
 
-            let invocation = ServiceInvocation {
-                id: invocation_id,
+            Self::deposit_event(Event::ListingBuildBound {
                 listing_id,
-                invoker: invoker.clone(),
-                provider: listing.provider.clone(),
-                requirements,
-                price: agreed_price,
-                payment_mode: PaymentMode::Escrow,
-                status: InvocationStatus::Pending,
-                milestones: bounded_milestones,
-                deadline,
-                created_at: now,
-                accepted_at: None,
-                completed_at: None,
-            };
+                code_hash,
+                reactivated,
+            });
+            Ok(())
+        }
 
-            ServiceInvocations::<T>::insert(invocation_id, invocation);
-            InvocationCount::<T>::put(invocation_id + 1);
-            InvocationsByListing::<T>::insert(listing_id, invocation_id, ());
-            InvocationsByDeadline::<T>::insert(deadline, invocation_id, ());
+        /// (Index 94) Revoke a build attestation, pausing every active
+        /// listing bound to it.
+        ///
+        /// Callable by the attestation's attestor or `AttestorOrigin`.
+        #[pallet::call_index(94)]
+        #[pallet::weight(T::WeightInfo::revoke_build_attestation(T::MaxListingsPerBuild::get()))]
+        pub fn revoke_build_attestation(
+            origin: OriginFor<T>,
+            code_hash: T::Hash,
+        ) -> DispatchResult {
+            let attestation = BuildAttestations::<T>::get(code_hash)
+                .ok_or(Error::<T>::BuildAttestationNotFound)?;
+            if T::AttestorOrigin::ensure_origin(origin.clone()).is_err() {
+                let who = ensure_signed(origin)?;
+                ensure!(who == attestation.attestor, Error::<T>::NotBuildAttestor);
+            }
 
-            InvocationsByInvoker::<T>::try_mutate(&invoker, |ids| {
-                ids.try_push(invocation_id)
-                    .map_err(|_| Error::<T>::TooManyActiveInvocations)
-            })?;
+            BuildAttestations::<T>::remove(code_hash);
+            let mut paused_listings = 0u32;
+            for listing_id in ListingsByBuild::<T>::take(code_hash) {
+                ListingBuilds::<T>::remove(listing_id);
+                ServiceListings::<T>::mutate(listing_id, |maybe| {
+                    if let Some(listing) = maybe {
+                        if listing.active {
+                            listing.active = false;
+                            ListingsAwaitingAttestation::<T>::insert(listing_id, ());
+                            paused_listings += 1;
+                        }
+                    }
+                });
+            }
 
-            // Update listing stats
-            ServiceListings::<T>::mutate(listing_id, |maybe| {
-                if let Some(l) = maybe {
-                    l.total_invocations = l.total_invocations.saturating_add(1);
-                }
+            Self::deposit_event(Event::BuildAttestationRevoked {
+                code_hash,
+                paused_listings,
             });
+            Ok(())
+        }
+
+        /// (Index 95) Invoker opts in or out of invoking only listings bound
+        /// to an attested build.
+        #[pallet::call_index(95)]
+        #[pallet::weight(T::WeightInfo::set_attestation_requirement())]
+        pub fn set_attestation_requirement(origin: OriginFor<T>, required: bool) -> DispatchResult {
+            let invoker = ensure_signed(origin)?;
+            if required {
+                RequireAttestedListings::<T>::insert(&invoker, ());
+            } else {
+                RequireAttestedListings::<T>::remove(&invoker);
+            }
+            Self::deposit_event(Event::AttestationRequirementSet { invoker, required });
+            Ok(())
+        }
 
-            Self::deposit_event(Event::ServiceInvoked {
-                invocation_id,
+        /// (Index 96) Provider restricts a listing to the given invokers.
+        ///
+        /// Only allowlisted accounts may invoke or subscribe to the listing;
+        /// they keep the usual escrow and dispute protection. An empty list
+        /// makes the listing public again.
+        #[pallet::call_index(96)]
+        #[pallet::weight(T::WeightInfo::set_listing_allowlist(accounts.len() as u32))]
+        pub fn set_listing_allowlist(
+            origin: OriginFor<T>,
+            listing_id: ListingId,
+            accounts: Vec<T::AccountId>,
+        ) -> DispatchResult {
+            let provider = ensure_signed(origin)?;
+            let listing =
+                ServiceListings::<T>::get(listing_id).ok_or(Error::<T>::ListingNotFound)?;
+            ensure!(listing.provider == provider, Error::<T>::NotProvider);
+
+            let mut accounts = accounts;
+            accounts.sort();
+            accounts.dedup();
+            let count = accounts.len() as u32;
+            if accounts.is_empty() {
+                ListingAllowlists::<T>::remove(listing_id);
+            } else {
+                let bounded: BoundedVec<T::AccountId, T::MaxAllowlistSize> = accounts
+                    .try_into()
+                    .map_err(|_| Error::<T>::TooManyAllowlistEntries)?;
+                ListingAllowlists::<T>::insert(listing_id, bounded);
+            }
+
+            Self::deposit_event(Event::ListingAllowlistSet {
                 listing_id,
-                invoker,
-                provider: listing.provider,
-                price: agreed_price,
+                accounts: count,
             });
+            Ok(())
+        }
 
+        /// (Index 97) Provider opts in or out of deferred settlement.
+        ///
+        /// While enabled, milestone payouts accrue to the payee's settlement
+        /// statement instead of being transferred one by one. Opting out
+        /// leaves an open statement to be paid out as scheduled.
+        #[pallet::call_index(97)]
+        #[pallet::weight(T::WeightInfo::set_deferred_settlement())]
+        pub fn set_deferred_settlement(origin: OriginFor<T>, enabled: bool) -> DispatchResult {
+            let provider = ensure_signed(origin)?;
+            if enabled {
+                DeferredSettlement::<T>::insert(&provider, ());
+            } else {
+                DeferredSettlement::<T>::remove(&provider);
+            }
+            Self::deposit_event(Event::DeferredSettlementSet { provider, enabled });
             Ok(())
         }
 
-        /// (Index 18) Provider submits work proof for an invocation.
-        #[pallet::call_index(18)]
-        #[pallet::weight(T::WeightInfo::submit_invocation_work())]
-        pub fn submit_invocation_work(
+        /// (Index 98) Pay out the caller's settlement statement now.
+        #[pallet::call_index(98)]
+        #[pallet::weight(T::WeightInfo::claim_settlement())]
+        pub fn claim_settlement(origin: OriginFor<T>) -> DispatchResult {
+            let payee = ensure_signed(origin)?;
+            ensure!(
+                SettlementStatements::<T>::contains_key(&payee),
+                Error::<T>::NoSettlement
+            );
+            Self::pay_settlement(&payee)
+        }
+
+        /// (Index 99) Governance requires insurance for invocations priced
+        /// above `threshold` on listings tagged `tag`. `None` lifts the
+        /// requirement.
+        ///
+        /// Such invocations fail with `InsuranceRequired` unless
+        /// `Config::Insurance` reports an active policy of the provider
+        /// covering the price; the policy is recorded in
+        /// `InvocationInsurance`.
+        #[pallet::call_index(99)]
+        #[pallet::weight(T::WeightInfo::set_tag_insurance_requirement())]
+        pub fn set_tag_insurance_requirement(
             origin: OriginFor<T>,
-            invocation_id: InvocationId,
-            milestone_index: Option<u32>,
-            proof_cid: Vec<u8>,
-            proof_type: ProofType,
+            tag: Vec<u8>,
+            threshold: Option<BalanceOf<T>>,
         ) -> DispatchResult {
-            let provider = ensure_signed(origin)?;
+            ensure_root(origin)?;
+            let tag: BoundedVec<u8, T::MaxTagLength> =
+                tag.try_into().map_err(|_| Error::<T>::TagTooLong)?;
 
-            let proof_cid: BoundedVec<u8, T::MaxCidLength> =
-                proof_cid.try_into().map_err(|_| Error::<T>::CidTooLong)?;
+            match threshold {
+                Some(threshold) => TagInsuranceThresholds::<T>::insert(&tag, threshold),
+                None => TagInsuranceThresholds::<T>::remove(&tag),
+            }
+            Self::deposit_event(Event::TagInsuranceRequirementSet { tag, threshold });
+            Ok(())
+        }
 
-            ServiceInvocations::<T>::try_mutate(invocation_id, |maybe| {
-                let inv = maybe.as_mut().ok_or(Error::<T>::InvocationNotFound)?;
-                ensure!(inv.provider == provider, Error::<T>::NotProvider);
-                ensure!(
-                    matches!(
-                        inv.status,
-                        InvocationStatus::Pending
-                            | InvocationStatus::Accepted
-                            | InvocationStatus::InProgress
-                            | InvocationStatus::WorkSubmitted
-                    ),
-                    Error::<T>::InvalidInvocationStatus
-                );
+        /// (Index 100) Invoke a template once per entry of `requirements_cids`,
+        /// each with the template's deadline.
+        ///
+        /// All invocations are created or none are. Limited to
+        /// `MaxTemplateBatch` entries.
+        #[pallet::call_index(100)]
+        #[pallet::weight(
+            T::WeightInfo::invoke_from_template()
+                .saturating_mul(T::MaxTemplateBatch::get().into())
+        )]
+        pub fn invoke_from_template_batch(
+            origin: OriginFor<T>,
+            template_id: TemplateId,
+            requirements_cids: Vec<Vec<u8>>,
+        ) -> DispatchResult {
+            let invoker = ensure_signed(origin)?;
+            ensure!(
+                !requirements_cids.is_empty()
+                    && requirements_cids.len() as u32 <= T::MaxTemplateBatch::get(),
+                Error::<T>::InvalidTemplateBatch
+            );
 
-                let now = <frame_system::Pallet<T>>::block_number();
+            let template =
+                InvocationTemplates::<T>::get(template_id).ok_or(Error::<T>::TemplateNotFound)?;
+            ensure!(template.owner == invoker, Error::<T>::NotTemplateOwner);
+
+            for requirements in requirements_cids {
+                ensure!(!requirements.is_empty(), Error::<T>::RequirementsEmpty);
+                Self::do_invoke_service(
+                    invoker.clone(),
+                    template.listing_id,
+                    requirements,
+                    Some(template.milestones.clone().into_inner()),
+                    template.price,
+                    template.deadline_blocks,
+                    None,
+                    false,
+                    PaymentMode::Escrow,
+                    None,
+                )?;
+            }
+            Ok(())
+        }
+    }
 
-                // Update milestone status if milestone_index provided
-                if let Some(idx) = milestone_index {
-                    let ms = inv
-                        .milestones
-                        .get_mut(idx as usize)
-                        .ok_or(Error::<T>::MilestoneIndexOutOfBounds)?;
-                    ms.status = MilestoneStatus::Submitted;
-                    ms.submitted_at = Some(now);
-                }
+    // =========================================================
+    // Internal helpers
+    // =========================================================
 
-                // Mark invocation as work submitted if no milestones or all submitted
-                inv.status = InvocationStatus::WorkSubmitted;
-                inv.completed_at = Some(now);
+    impl<T: Config> Pallet<T> {
+        /// Cancel `invoker`'s pending invocation and refund its escrow.
+        /// Returns the amount refunded.
+        fn do_cancel_invocation(
+            invoker: &T::AccountId,
+            invocation_id: InvocationId,
+        ) -> Result<BalanceOf<T>, DispatchError> {
+            let (price, listing_id, provider, created_at) =
+                ServiceInvocations::<T>::try_mutate(invocation_id, |maybe| {
+                    let inv = maybe.as_mut().ok_or(Error::<T>::InvocationNotFound)?;
+                    ensure!(&inv.invoker == invoker, Error::<T>::NotInvoker);
+                    ensure!(
+                        matches!(inv.status, InvocationStatus::Pending),
+                        Error::<T>::CannotCancelActiveInvocation
+                    );
+                    inv.status = InvocationStatus::Cancelled;
+                    Ok::<_, DispatchError>((
+                        inv.price,
+                        inv.listing_id,
+                        inv.provider.clone(),
+                        inv.created_at,
+                    ))
+                })?;
 
-                Ok::<(), DispatchError>(())
-            })?;
+            // Refund escrow
+            let escrowed = Self::is_escrowed(invocation_id);
+            let mut price = price;
+            if escrowed {
+                Self::close_yield_position(invocation_id);
+                price = Self::escrow_payout(invocation_id, price);
+                let escrow_account = Self::invocation_escrow_account(invocation_id);
+                T::Currency::transfer(
+                    &escrow_account,
+                    invoker,
+                    price,
+                    Self::escrow_existence_requirement(invocation_id),
+                )
+                .map_err(|_| Error::<T>::InsufficientBalance)?;
+            }
 
-            let now = <frame_system::Pallet<T>>::block_number();
-            let proof = WorkProof {
-                proof_cid,
-                proof_type,
-                submitted_at: now,
-            };
-            let key = milestone_index.unwrap_or(u32::MAX);
-            InvocationProofs::<T>::insert(invocation_id, key, proof);
+            Self::cleanup_invocation(invocation_id);
 
-            Self::deposit_event(Event::WorkSubmitted {
-                invocation_id,
-                milestone_index,
+            // Cancelling after the provider let the response window lapse
+            // is a response-SLA miss.
+            let response_missed = ServiceListings::<T>::get(listing_id).is_some_and(|l| {
+                <frame_system::Pallet<T>>::block_number()
+                    > created_at.saturating_add(l.sla_response_blocks.into())
             });
+            if response_missed {
+                Self::slash_provider_bond(listing_id, &provider, invoker, invocation_id);
+            }
 
-            Ok(())
+            Self::deposit_event(Event::InvocationCancelled { invocation_id });
+
+            Ok(if escrowed { price } else { Zero::zero() })
         }
 
-        /// (Index 19) Invoker approves a milestone and releases partial escrow.
-        #[pallet::call_index(19)]
-        #[pallet::weight(T::WeightInfo::approve_milestone())]
-        pub fn approve_milestone(
-            origin: OriginFor<T>,
+        /// Approve `milestone_index` of `invocation_id` on behalf of its
+        /// invoker and pay the provider. Returns the provider and the amount
+        /// released from escrow.
+        fn do_approve_milestone(
+            invoker: &T::AccountId,
             invocation_id: InvocationId,
             milestone_index: u32,
-        ) -> DispatchResult {
-            let invoker = ensure_signed(origin)?;
-
+        ) -> Result<(T::AccountId, BalanceOf<T>), DispatchError> {
             let (provider, amount_released, fully_approved) =
                 ServiceInvocations::<T>::try_mutate(invocation_id, |maybe| {
                     let inv = maybe.as_mut().ok_or(Error::<T>::InvocationNotFound)?;
-                    ensure!(inv.invoker == invoker, Error::<T>::NotInvoker);
+                    ensure!(&inv.invoker == invoker, Error::<T>::NotInvoker);
                     ensure!(
                         matches!(
                             inv.status,
@@ -1013,6 +6486,7 @@ pub mod pallet {
                     if inv.milestones.is_empty() {
                         // Single-milestone: release everything
                         inv.status = InvocationStatus::FullyApproved;
+                        inv.released = total_price;
                         return Ok((provider, total_price, true));
                     }
 
@@ -1047,19 +6521,44 @@ pub mod pallet {
                     if all_approved {
                         inv.status = InvocationStatus::FullyApproved;
                     }
+                    inv.released = inv.released.saturating_add(amount_released);
 
                     Ok::<_, DispatchError>((provider, amount_released, all_approved))
                 })?;
 
-            // Transfer from escrow to provider
-            let escrow_account = Self::invocation_escrow_account(invocation_id);
-            T::Currency::transfer(
-                &escrow_account,
-                &provider,
-                amount_released,
-                ExistenceRequirement::AllowDeath,
-            )
-            .map_err(|_| Error::<T>::InsufficientBalance)?;
+            // Transfer from escrow to provider, less the ecosystem-fund and
+            // referral shares. X402 invocations were paid off-chain.
+            if Self::is_escrowed(invocation_id) {
+                Self::close_yield_position(invocation_id);
+                let amount_released = Self::escrow_payout(invocation_id, amount_released);
+                let escrow_account = Self::invocation_escrow_account(invocation_id);
+                let contribution =
+                    Self::contribute_to_ecosystem(invocation_id, &provider, amount_released);
+                let referral = Self::pay_referral(invocation_id, amount_released);
+                let fees = contribution.saturating_add(referral);
+                let payee = Self::invocation_payee(invocation_id, &provider);
+                let existence = Self::escrow_existence_requirement(invocation_id);
+                let settled = DeferredSettlement::<T>::contains_key(&provider)
+                    && Self::accrue_settlement(
+                        &escrow_account,
+                        &payee,
+                        SettlementEntry {
+                            invocation_id,
+                            amount: amount_released,
+                            fees,
+                        },
+                        existence,
+                    )?;
+                if !settled {
+                    T::Currency::transfer(
+                        &escrow_account,
+                        &payee,
+                        amount_released.saturating_sub(fees),
+                        existence,
+                    )
+                    .map_err(|_| Error::<T>::InsufficientBalance)?;
+                }
+            }
 
             Self::deposit_event(Event::MilestoneApproved {
                 invocation_id,
@@ -1068,18 +6567,20 @@ pub mod pallet {
             });
 
             if fully_approved {
-                // Update listing success count
-                let listing_id = ServiceInvocations::<T>::get(invocation_id)
-                    .map(|i| i.listing_id)
-                    .unwrap_or(0);
+                // Update listing success count and the tags' price index
+                let (listing_id, price) = ServiceInvocations::<T>::get(invocation_id)
+                    .map_or((0, Zero::zero()), |i| (i.listing_id, i.price));
                 ServiceListings::<T>::mutate(listing_id, |maybe| {
                     if let Some(l) = maybe {
                         l.successful_invocations = l.successful_invocations.saturating_add(1);
+                        Self::record_settled_price(&l.tags, price);
+                        Self::update_rankings(l);
                     }
                 });
 
                 // Reputation updates
                 T::ReputationManager::on_task_completed(&provider, amount_released);
+                Self::record_provisional_success(&provider);
 
                 Self::deposit_event(Event::InvocationFullyApproved {
                     invocation_id,
@@ -1090,252 +6591,606 @@ pub mod pallet {
                 Self::cleanup_invocation(invocation_id);
             }
 
-            Ok(())
+            Ok((provider, amount_released))
         }
 
-        /// (Index 20) Raise a dispute on an invocation.
-        #[pallet::call_index(20)]
-        #[pallet::weight(T::WeightInfo::raise_dispute())]
-        pub fn raise_dispute(
-            origin: OriginFor<T>,
+        /// Enforce slot reservations for an invocation of `listing_id` at `now`.
+        ///
+        /// Fails if another invoker holds the open window. If `invoker` holds
+        /// it, the reservation is consumed and its fee unreserved.
+        fn redeem_slot(
+            listing_id: ListingId,
             invocation_id: InvocationId,
-            reason: Vec<u8>,
-            evidence_cid: Option<Vec<u8>>,
+            invoker: &T::AccountId,
+            now: BlockNumberFor<T>,
         ) -> DispatchResult {
-            let caller = ensure_signed(origin)?;
+            let slot = ListingReservations::<T>::try_mutate(listing_id, |slots| {
+                let Some(pos) = slots
+                    .iter()
+                    .position(|s| s.start_block <= now && now < s.end_block)
+                else {
+                    return Ok(None);
+                };
+                ensure!(slots[pos].invoker == *invoker, Error::<T>::SlotUnavailable);
+                Ok::<_, DispatchError>(Some(slots.remove(pos)))
+            })?;
 
-            let reason: BoundedVec<u8, T::MaxDescriptionLength> = reason
-                .try_into()
-                .map_err(|_| Error::<T>::DescriptionTooLong)?;
+            if let Some(slot) = slot {
+                T::Currency::unreserve(invoker, slot.fee);
+                Self::deposit_event(Event::SlotRedeemed {
+                    listing_id,
+                    invocation_id,
+                    start_block: slot.start_block,
+                });
+            }
+            Ok(())
+        }
 
-            let evidence: Option<BoundedVec<u8, T::MaxCidLength>> = evidence_cid
-                .map(|c| c.try_into().map_err(|_| Error::<T>::CidTooLong))
-                .transpose()?;
+        /// `invoker`'s open-invocation cap, how many it holds and the formula
+        /// in force.
+        pub fn invocation_cap_info(invoker: &T::AccountId) -> runtime_api::InvocationCap {
+            runtime_api::InvocationCap {
+                cap: Self::invocation_cap(invoker),
+                active: InvocationsByInvoker::<T>::decode_len(invoker).unwrap_or(0) as u32,
+                params: InvocationCapParameters::<T>::get(),
+            }
+        }
 
-            ServiceInvocations::<T>::try_mutate(invocation_id, |maybe| {
-                let inv = maybe.as_mut().ok_or(Error::<T>::InvocationNotFound)?;
+        fn ensure_payout_controller(provider: &T::AccountId, who: &T::AccountId) -> DispatchResult {
+            ensure!(
+                PayoutControllers::<T>::get(provider).as_ref() == Some(who),
+                Error::<T>::NotPayoutController
+            );
+            Ok(())
+        }
+
+        /// Account that receives `provider`'s payouts from `listing_id`.
+        pub fn listing_payee(listing_id: ListingId, provider: &T::AccountId) -> T::AccountId {
+            ServiceListings::<T>::get(listing_id)
+                .and_then(|listing| listing.payout_account)
+                .unwrap_or_else(|| provider.clone())
+        }
+
+        /// Account that receives `provider`'s payouts from `invocation_id`.
+        pub(crate) fn invocation_payee(
+            invocation_id: InvocationId,
+            provider: &T::AccountId,
+        ) -> T::AccountId {
+            ServiceInvocations::<T>::get(invocation_id).map_or_else(
+                || provider.clone(),
+                |inv| Self::listing_payee(inv.listing_id, provider),
+            )
+        }
+
+        /// Hash of the terms an invoker agrees to when invoking `listing`.
+        pub fn listing_terms_hash(listing: &ServiceListing<T>) -> T::Hash {
+            T::Hashing::hash_of(&(
+                &listing.name,
+                listing.min_price,
+                listing.max_price,
+                &listing.payment_mode,
+                listing.sla_response_blocks,
+                listing.sla_completion_blocks,
+                listing.auto_approve_delay_blocks,
+                StablePrices::<T>::get(listing.id),
+            ))
+        }
+
+        /// Archive `listing`'s terms as a new version if they differ from the
+        /// current one. A new listing's terms become version 0.
+        fn record_listing_terms(listing: &ServiceListing<T>) {
+            let terms_hash = Self::listing_terms_hash(listing);
+            let current = ListingVersion::<T>::get(listing.id);
+            let version = match ListingTermsHistory::<T>::get(listing.id, current) {
+                Some(terms) if terms.terms_hash == terms_hash => return,
+                Some(_) => current.saturating_add(1),
+                None => current,
+            };
+
+            ListingVersion::<T>::insert(listing.id, version);
+            ListingTermsHistory::<T>::insert(
+                listing.id,
+                version,
+                ListingTerms {
+                    terms_hash,
+                    effective_from: <frame_system::Pallet<T>>::block_number(),
+                },
+            );
+            Self::deposit_event(Event::ListingTermsVersioned {
+                listing_id: listing.id,
+                version,
+                terms_hash,
+            });
+        }
+
+        /// Every archived version of `listing_id`'s terms, oldest first.
+        pub fn listing_terms_history(listing_id: ListingId) -> Vec<(u32, ListingTerms<T>)> {
+            (0..=ListingVersion::<T>::get(listing_id))
+                .filter_map(|version| {
+                    ListingTermsHistory::<T>::get(listing_id, version).map(|terms| (version, terms))
+                })
+                .collect()
+        }
+
+        /// The terms `invocation_id` was created against, for use as dispute
+        /// evidence.
+        pub fn invocation_terms(invocation_id: InvocationId) -> Option<(u32, ListingTerms<T>)> {
+            let invocation = ServiceInvocations::<T>::get(invocation_id)?;
+            ListingTermsHistory::<T>::get(invocation.listing_id, invocation.listing_version)
+                .map(|terms| (invocation.listing_version, terms))
+        }
+
+        /// Open invocations `invoker` may hold, per `InvocationCapParameters`.
+        pub fn invocation_cap(invoker: &T::AccountId) -> u32 {
+            let max = T::MaxActiveInvocationsPerAccount::get();
+            let Some(params) = InvocationCapParameters::<T>::get() else {
+                return max;
+            };
+
+            let tier = T::ReputationManager::get_reputation(invoker)
+                .checked_div(params.tier_width)
+                .unwrap_or(0);
+            let mut cap = params
+                .base
+                .saturating_add(params.per_tier.saturating_mul(tier));
+
+            let history = InvokerHistories::<T>::get(invoker);
+            if history.finished > 0 && history.finished >= params.min_history {
+                let scaled = u64::from(cap) * u64::from(history.completed.min(history.finished))
+                    / u64::from(history.finished);
+                cap = (scaled as u32).max(params.base);
+            }
+            cap.min(max)
+        }
+
+        /// Drop a deactivated listing from the suspension, tag and
+        /// jurisdiction indexes and refund its booked slots.
+        fn unindex_listing(listing: &ServiceListing<T>) {
+            let listing_id = listing.id;
+
+            // A delisted listing must not be revived when a suspension lifts.
+            SuspendedProviders::<T>::mutate(&listing.provider, |maybe| {
+                if let Some(paused) = maybe {
+                    paused.retain(|&id| id != listing_id);
+                }
+            });
+
+            for tag in &listing.tags {
+                ListingsByTag::<T>::mutate(tag, |ids| {
+                    ids.retain(|&id| id != listing_id);
+                });
+                TagRankings::<T>::mutate(tag, |entries| {
+                    entries.retain(|entry| entry.listing_id != listing_id);
+                });
+            }
+            for code in &listing.jurisdictions {
+                ListingsByJurisdiction::<T>::mutate(code, |ids| {
+                    ids.retain(|&id| id != listing_id);
+                });
+            }
+
+            // Booked slots can no longer be used: return their fees.
+            for slot in ListingReservations::<T>::take(listing_id) {
+                T::Currency::unreserve(&slot.invoker, slot.fee);
+                Self::deposit_event(Event::SlotReleased {
+                    listing_id,
+                    invoker: slot.invoker,
+                    start_block: slot.start_block,
+                });
+            }
+
+            // Nor revived by binding a new build attestation.
+            ListingsAwaitingAttestation::<T>::remove(listing_id);
+            Self::unbind_build(listing_id);
+        }
+
+        /// Fail unless `who` may use `listing_id`: the listing is public or
+        /// `who` is on its allowlist.
+        fn ensure_allowlisted(listing_id: ListingId, who: &T::AccountId) -> DispatchResult {
+            if let Some(allowed) = ListingAllowlists::<T>::get(listing_id) {
+                ensure!(
+                    allowed.binary_search(who).is_ok(),
+                    Error::<T>::NotOnAllowlist
+                );
+            }
+            Ok(())
+        }
+
+        /// Drop `listing_id`'s build binding, if any.
+        fn unbind_build(listing_id: ListingId) {
+            if let Some(code_hash) = ListingBuilds::<T>::take(listing_id) {
+                ListingsByBuild::<T>::mutate(code_hash, |ids| {
+                    ids.retain(|&id| id != listing_id);
+                });
+            }
+        }
+
+        /// Convert a stable reference price into CLAW using the oracle rate.
+        ///
+        /// Rejects missing or stale feeds and results above `max_accepted`.
+        fn stable_price_in_claw(
+            reference_price: u64,
+            max_accepted: BalanceOf<T>,
+            now: BlockNumberFor<T>,
+        ) -> Result<BalanceOf<T>, DispatchError> {
+            let (rate, updated_at) = T::PriceOracle::rate().ok_or(Error::<T>::OracleUnavailable)?;
+            ensure!(
+                now.saturating_sub(updated_at) <= T::MaxOracleAge::get().into(),
+                Error::<T>::OracleStale
+            );
+            let price = rate
+                .checked_mul(&reference_price.saturated_into())
+                .ok_or(Error::<T>::StablePriceOverflow)?;
+            ensure!(price <= max_accepted, Error::<T>::StablePriceExceedsLimit);
+            Ok(price)
+        }
+
+        /// Shared invocation path for `invoke_service` and `invoke_from_template`.
+        ///
+        /// Validates the listing, locks `agreed_price` in escrow and indexes the
+        /// new invocation. Returns the new invocation ID.
+        fn do_invoke_service(
+            invoker: T::AccountId,
+            listing_id: ListingId,
+            requirements: Vec<u8>,
+            milestones: Option<Vec<MilestoneSpec>>,
+            agreed_price: BalanceOf<T>,
+            deadline_blocks: u32,
+            required_jurisdiction: Option<JurisdictionCode>,
+            trial: bool,
+            payment_mode: PaymentMode,
+            referrer: Option<T::AccountId>,
+        ) -> Result<InvocationId, DispatchError> {
+            let listing =
+                ServiceListings::<T>::get(listing_id).ok_or(Error::<T>::ListingNotFound)?;
+
+            ensure!(listing.active, Error::<T>::ListingNotActive);
+            let x402 = payment_mode == PaymentMode::X402;
+            ensure!(
+                listing.payment_mode == PaymentMode::Either || listing.payment_mode == payment_mode,
+                Error::<T>::PaymentModeNotAccepted
+            );
+            if x402 {
+                ensure!(!trial, Error::<T>::TrialRequiresEscrow);
+                if let Some(min_rep) = X402MinReputation::<T>::get(listing_id) {
+                    ensure!(
+                        T::ReputationManager::meets_minimum_reputation(&invoker, min_rep),
+                        Error::<T>::InsufficientReputation
+                    );
+                }
+            }
+            let referral = match referrer {
+                Some(referrer) => {
+                    ensure!(
+                        referrer != invoker && referrer != listing.provider,
+                        Error::<T>::SelfReferral
+                    );
+                    let terms = ListingReferralTerms::<T>::get(listing_id)
+                        .ok_or(Error::<T>::ReferralsNotAccepted)?;
+                    Some(Referral {
+                        referrer,
+                        fee: terms.fee,
+                        remaining: terms.cap,
+                    })
+                }
+                None => None,
+            };
+            if let Some(code) = required_jurisdiction {
+                ensure!(
+                    listing.jurisdictions.contains(&code),
+                    Error::<T>::JurisdictionMismatch
+                );
+            }
+            Self::ensure_allowlisted(listing_id, &invoker)?;
+            if RequireAttestedListings::<T>::contains_key(&invoker) {
+                ensure!(
+                    ListingBuilds::<T>::contains_key(listing_id),
+                    Error::<T>::ListingNotAttested
+                );
+            }
+
+            let now = <frame_system::Pallet<T>>::block_number();
+            if let Some(capacity) = ProviderCapacities::<T>::get(&listing.provider) {
+                ensure!(
+                    capacity.paused_until.is_none_or(|until| now >= until),
+                    Error::<T>::ProviderUnavailable
+                );
+                ensure!(
+                    capacity.max_concurrent_invocations.is_none_or(|max| {
+                        ProviderOpenInvocations::<T>::get(&listing.provider) < max
+                    }),
+                    Error::<T>::ProviderAtCapacity
+                );
+            }
+
+            let agreed_price = match StablePrices::<T>::get(listing_id) {
+                Some(reference_price) => {
+                    Self::stable_price_in_claw(reference_price, agreed_price, now)?
+                }
+                None => agreed_price,
+            };
+            ensure!(
+                agreed_price >= listing.min_price,
+                Error::<T>::PriceBelowMinimum
+            );
+            ensure!(
+                listing.max_price == listing.min_price || agreed_price <= listing.max_price,
+                Error::<T>::PriceAboveMaximum
+            );
 
+            let insurance_policy = Self::required_insurance(&listing, agreed_price)?;
+
+            // Per-listing invoker reputation gate
+            if let Some(min_rep) = listing.min_invoker_reputation {
                 ensure!(
-                    inv.invoker == caller || inv.provider == caller,
-                    Error::<T>::NotPartyToInvocation
+                    T::ReputationManager::meets_minimum_reputation(&invoker, min_rep),
+                    Error::<T>::InsufficientReputation
                 );
+            }
+
+            ensure!(
+                (InvocationsByInvoker::<T>::decode_len(&invoker).unwrap_or(0) as u32)
+                    < Self::invocation_cap(&invoker),
+                Error::<T>::TooManyActiveInvocations
+            );
+
+            if trial {
                 ensure!(
-                    !matches!(inv.status, InvocationStatus::Disputed),
-                    Error::<T>::InvocationAlreadyDisputed
+                    agreed_price <= T::MaxTrialPrice::get(),
+                    Error::<T>::TrialPriceTooHigh
                 );
+                ensure!(milestones.is_none(), Error::<T>::TrialMilestonesNotAllowed);
                 ensure!(
-                    matches!(
-                        inv.status,
-                        InvocationStatus::Pending
-                            | InvocationStatus::Accepted
-                            | InvocationStatus::InProgress
-                            | InvocationStatus::WorkSubmitted
-                    ),
-                    Error::<T>::InvalidInvocationStatus
+                    !TrialsUsed::<T>::contains_key(listing_id, &invoker),
+                    Error::<T>::TrialAlreadyUsed
                 );
+            }
 
-                inv.status = InvocationStatus::Disputed;
-                Ok::<(), DispatchError>(())
-            })?;
-
-            let dispute_id = DisputeCount::<T>::get();
-            let now = <frame_system::Pallet<T>>::block_number();
-
-            let dispute = DisputeRecord {
-                id: dispute_id,
-                invocation_id,
-                raised_by: caller.clone(),
-                reason,
-                evidence_cid: evidence,
-                status: DisputeStatus::Open,
-                raised_at: now,
-                winner: None,
-            };
-
-            Disputes::<T>::insert(dispute_id, dispute);
-            DisputeCount::<T>::put(dispute_id + 1);
-
-            Self::deposit_event(Event::DisputeRaised {
-                invocation_id,
-                dispute_id,
-                raised_by: caller,
-            });
+            let requirements: BoundedVec<u8, T::MaxDescriptionLength> = requirements
+                .try_into()
+                .map_err(|_| Error::<T>::DescriptionTooLong)?;
 
-            Ok(())
-        }
+            // Build milestones
+            let bounded_milestones = Self::build_milestones(milestones)?;
 
-        /// (Index 23) Governance resolves an escalated dispute.
-        #[pallet::call_index(23)]
-        #[pallet::weight(T::WeightInfo::resolve_dispute_governance())]
-        pub fn resolve_dispute_governance(
-            origin: OriginFor<T>,
-            dispute_id: DisputeId,
-            winner: T::AccountId,
-        ) -> DispatchResult {
-            ensure_root(origin)?;
+            let invocation_id = InvocationCount::<T>::get();
+            let deadline = now + deadline_blocks.into();
 
-            let invocation_id = Disputes::<T>::try_mutate(dispute_id, |maybe| {
-                let dispute = maybe.as_mut().ok_or(Error::<T>::DisputeNotFound)?;
-                // Allow resolution from Open or Escalated (governance can always resolve)
-                dispute.status = DisputeStatus::Resolved;
-                dispute.winner = Some(winner.clone());
-                Ok::<InvocationId, DispatchError>(dispute.invocation_id)
-            })?;
+            // A reservation fee, if any, is released here and goes into escrow
+            // with the rest of the price.
+            Self::redeem_slot(listing_id, invocation_id, &invoker, now)?;
 
-            // Transfer escrow to winner
-            let escrow_account = Self::invocation_escrow_account(invocation_id);
-            let escrow_balance = T::Currency::free_balance(&escrow_account);
-            if escrow_balance > T::Currency::minimum_balance() {
+            // Lock escrow (transfer from invoker to pallet escrow sub-account).
+            // X402 invocations are paid off-chain instead.
+            if !x402 {
+                let escrow_account = Self::invocation_escrow_account(invocation_id);
                 T::Currency::transfer(
+                    &invoker,
                     &escrow_account,
-                    &winner,
-                    escrow_balance - T::Currency::minimum_balance(),
-                    ExistenceRequirement::AllowDeath,
+                    agreed_price,
+                    ExistenceRequirement::KeepAlive,
                 )
-                .ok(); // Best effort
+                .map_err(|_| Error::<T>::InsufficientBalance)?;
+            }
+
+            let invocation = ServiceInvocation {
+                id: invocation_id,
+                listing_id,
+                listing_version: ListingVersion::<T>::get(listing_id),
+                invoker: invoker.clone(),
+                provider: listing.provider.clone(),
+                requirements,
+                price: agreed_price,
+                released: Zero::zero(),
+                payment_mode,
+                status: InvocationStatus::Pending,
+                milestones: bounded_milestones,
+                deadline,
+                created_at: now,
+                accepted_at: None,
+                completed_at: None,
+                trial,
+            };
+
+            ServiceInvocations::<T>::insert(invocation_id, invocation);
+            if trial {
+                TrialsUsed::<T>::insert(listing_id, &invoker, ());
+            }
+            InvocationCount::<T>::put(invocation_id + 1);
+            InvocationsByListing::<T>::insert(listing_id, invocation_id, ());
+            ProviderOpenInvocations::<T>::mutate(&listing.provider, |open| {
+                *open = open.saturating_add(1)
+            });
+            InvocationsByDeadline::<T>::insert(deadline, invocation_id, ());
+            if let Some(referral) = referral {
+                InvocationReferrals::<T>::insert(invocation_id, referral);
+            }
+            if let Some(policy) = insurance_policy {
+                InvocationInsurance::<T>::insert(invocation_id, policy);
             }
 
-            // Mark invocation resolved
-            ServiceInvocations::<T>::mutate(invocation_id, |maybe| {
-                if let Some(inv) = maybe {
-                    inv.status = InvocationStatus::FullyApproved;
+            InvocationsByInvoker::<T>::try_mutate(&invoker, |ids| {
+                ids.try_push(invocation_id)
+                    .map_err(|_| Error::<T>::TooManyActiveInvocations)
+            })?;
+
+            // Update listing stats
+            ServiceListings::<T>::mutate(listing_id, |maybe| {
+                if let Some(l) = maybe {
+                    l.total_invocations = l.total_invocations.saturating_add(1);
                 }
             });
 
-            // Reputation update via dispute resolution
-            let inv = ServiceInvocations::<T>::get(invocation_id);
-            if let Some(inv) = inv {
-                let loser = if inv.invoker == winner {
-                    inv.provider.clone()
-                } else {
-                    inv.invoker.clone()
-                };
-                T::ReputationManager::on_dispute_resolved(&winner, &loser);
-            }
+            Self::deposit_event(Event::ServiceInvoked {
+                invocation_id,
+                listing_id,
+                invoker,
+                provider: listing.provider,
+                price: agreed_price,
+            });
 
-            Self::cleanup_invocation(invocation_id);
+            Ok(invocation_id)
+        }
 
-            Self::deposit_event(Event::DisputeResolvedByGovernance { dispute_id, winner });
+        /// The policy covering an invocation of `listing` at `price`, if any
+        /// of its tags requires insurance at that price. Fails with
+        /// `InsuranceRequired` when one does and the provider has no cover.
+        fn required_insurance(
+            listing: &ServiceListing<T>,
+            price: BalanceOf<T>,
+        ) -> Result<Option<InsurancePolicyId>, DispatchError> {
+            let required = listing.tags.iter().any(|tag| {
+                TagInsuranceThresholds::<T>::get(tag).is_some_and(|threshold| price > threshold)
+            });
+            if !required {
+                return Ok(None);
+            }
+            T::Insurance::active_policy(&listing.provider, price)
+                .map(Some)
+                .ok_or_else(|| Error::<T>::InsuranceRequired.into())
+        }
 
-            Ok(())
+        /// Escrow sub-account holding a competition's reward and fees.
+        pub fn competition_escrow_account(competition_id: CompetitionId) -> T::AccountId {
+            T::PalletId::get().into_sub_account_truncating((b"competit", competition_id))
         }
 
-        /// (Index 26) Cancel a pending invocation and refund escrow.
-        #[pallet::call_index(26)]
-        #[pallet::weight(T::WeightInfo::cancel_invocation())]
-        pub fn cancel_invocation(
-            origin: OriginFor<T>,
-            invocation_id: InvocationId,
-        ) -> DispatchResult {
-            let invoker = ensure_signed(origin)?;
+        /// Pay out a finished competition's escrow and refund the rest to the
+        /// invoker. With a `winner`, it gets the reward and the other
+        /// submitters the participation fee; without one, the submitters
+        /// share the reward equally. Returns the amounts paid and refunded.
+        fn settle_competition(
+            competition_id: CompetitionId,
+            competition: &Competition<T>,
+            winner: Option<&T::AccountId>,
+        ) -> (BalanceOf<T>, BalanceOf<T>) {
+            let escrow = Self::competition_escrow_account(competition_id);
+            let submitters: Vec<&CompetitionEntry<T>> = competition
+                .entries
+                .iter()
+                .filter(|e| e.result.is_some())
+                .collect();
+            let share = match u32::try_from(submitters.len()) {
+                Ok(n) if n > 0 => competition.reward / n.into(),
+                _ => Zero::zero(),
+            };
 
-            let price = ServiceInvocations::<T>::try_mutate(invocation_id, |maybe| {
-                let inv = maybe.as_mut().ok_or(Error::<T>::InvocationNotFound)?;
-                ensure!(inv.invoker == invoker, Error::<T>::NotInvoker);
-                ensure!(
-                    matches!(inv.status, InvocationStatus::Pending),
-                    Error::<T>::CannotCancelActiveInvocation
-                );
-                inv.status = InvocationStatus::Cancelled;
-                Ok::<BalanceOf<T>, DispatchError>(inv.price)
-            })?;
+            let mut paid = BalanceOf::<T>::zero();
+            for entry in submitters {
+                let amount = match winner {
+                    Some(winner) if *winner == entry.provider => competition.reward,
+                    Some(_) => competition.participation_fee,
+                    None => share,
+                };
+                let payee = Self::listing_payee(entry.listing_id, &entry.provider);
+                if !amount.is_zero()
+                    && T::Currency::transfer(
+                        &escrow,
+                        &payee,
+                        amount,
+                        Self::drain_existence_requirement(&escrow),
+                    )
+                    .is_ok()
+                {
+                    paid = paid.saturating_add(amount);
+                }
+            }
 
-            // Refund escrow
-            let escrow_account = Self::invocation_escrow_account(invocation_id);
-            T::Currency::transfer(
-                &escrow_account,
-                &invoker,
-                price,
-                ExistenceRequirement::AllowDeath,
+            let rest = T::Currency::free_balance(&escrow);
+            let refunded = if T::Currency::transfer(
+                &escrow,
+                &competition.invoker,
+                rest,
+                Self::drain_existence_requirement(&escrow),
             )
-            .map_err(|_| Error::<T>::InsufficientBalance)?;
-
-            Self::cleanup_invocation(invocation_id);
+            .is_ok()
+            {
+                rest
+            } else {
+                Zero::zero()
+            };
+            (paid, refunded)
+        }
 
-            Self::deposit_event(Event::InvocationCancelled { invocation_id });
+        /// Whether the invocation's price is held in its escrow account, i.e.
+        /// it was not paid off-chain via X402.
+        fn is_escrowed(invocation_id: InvocationId) -> bool {
+            ServiceInvocations::<T>::get(invocation_id)
+                .is_some_and(|inv| inv.payment_mode != PaymentMode::X402)
+        }
 
-            Ok(())
+        /// Derive the escrow sub-account for a given invocation.
+        pub fn invocation_escrow_account(invocation_id: InvocationId) -> T::AccountId {
+            T::PalletId::get().into_sub_account_truncating(invocation_id)
         }
 
-        /// (Index 27) Permissionless: trigger expiry of a deadline-passed invocation.
-        ///
-        /// Caller receives `ExpireBounty` from the escrow as reward.
-        #[pallet::call_index(27)]
-        #[pallet::weight(T::WeightInfo::try_expire_invocation())]
-        pub fn try_expire_invocation(
-            origin: OriginFor<T>,
+        /// Move `EcosystemFundShare` of a milestone payout from the escrow to
+        /// the ecosystem fund, returning the amount moved (zero if the transfer
+        /// is not possible, in which case the provider is paid in full).
+        fn contribute_to_ecosystem(
             invocation_id: InvocationId,
-        ) -> DispatchResult {
-            let caller = ensure_signed(origin)?;
-
-            let now = <frame_system::Pallet<T>>::block_number();
-
-            let (invoker, price) = ServiceInvocations::<T>::try_mutate(invocation_id, |maybe| {
-                let inv = maybe.as_mut().ok_or(Error::<T>::InvocationNotFound)?;
-                ensure!(
-                    matches!(
-                        inv.status,
-                        InvocationStatus::Pending
-                            | InvocationStatus::Accepted
-                            | InvocationStatus::InProgress
-                    ),
-                    Error::<T>::InvalidInvocationStatus
-                );
-                ensure!(inv.deadline < now, Error::<T>::DeadlineNotPassed);
-                inv.status = InvocationStatus::Expired;
-                Ok::<(T::AccountId, BalanceOf<T>), DispatchError>((inv.invoker.clone(), inv.price))
-            })?;
-
-            let escrow_account = Self::invocation_escrow_account(invocation_id);
-            let bounty = T::ExpireBounty::get();
-
-            // Pay bounty to caller
-            if bounty > T::Currency::minimum_balance() {
-                T::Currency::transfer(
-                    &escrow_account,
-                    &caller,
-                    bounty,
-                    ExistenceRequirement::AllowDeath,
-                )
-                .ok();
+            provider: &T::AccountId,
+            payout: BalanceOf<T>,
+        ) -> BalanceOf<T> {
+            let amount = EcosystemFundShare::<T>::get().mul_floor(payout);
+            if amount.is_zero() {
+                return amount;
             }
-
-            // Refund remainder to invoker
-            use sp_runtime::traits::Saturating;
-            let remainder = price.saturating_sub(bounty);
-            if remainder > T::Currency::minimum_balance() {
-                T::Currency::transfer(
-                    &escrow_account,
-                    &invoker,
-                    remainder,
-                    ExistenceRequirement::AllowDeath,
-                )
-                .ok();
+            if T::Currency::transfer(
+                &Self::invocation_escrow_account(invocation_id),
+                &T::EcosystemFund::get(),
+                amount,
+                ExistenceRequirement::KeepAlive,
+            )
+            .is_err()
+            {
+                return Zero::zero();
             }
 
-            Self::cleanup_invocation(invocation_id);
-
-            Self::deposit_event(Event::InvocationExpired {
+            EcosystemContributions::<T>::mutate(provider, |total| {
+                *total = total.saturating_add(amount)
+            });
+            Self::deposit_event(Event::EcosystemContribution {
                 invocation_id,
-                expired_by: caller,
+                provider: provider.clone(),
+                amount,
             });
-
-            Ok(())
+            amount
         }
-    }
 
-    // =========================================================
-    // Internal helpers
-    // =========================================================
+        /// Pay an invocation's referrer its fee on `payout`, bounded by what
+        /// is left of its cap. Returns the amount paid.
+        fn pay_referral(invocation_id: InvocationId, payout: BalanceOf<T>) -> BalanceOf<T> {
+            let Some(mut referral) = InvocationReferrals::<T>::get(invocation_id) else {
+                return Zero::zero();
+            };
+            let amount = referral.fee.mul_floor(payout).min(referral.remaining);
+            if amount.is_zero() {
+                return amount;
+            }
+            if T::Currency::transfer(
+                &Self::invocation_escrow_account(invocation_id),
+                &referral.referrer,
+                amount,
+                ExistenceRequirement::KeepAlive,
+            )
+            .is_err()
+            {
+                return Zero::zero();
+            }
 
-    impl<T: Config> Pallet<T> {
-        /// Derive the escrow sub-account for a given invocation.
-        pub fn invocation_escrow_account(invocation_id: InvocationId) -> T::AccountId {
-            T::PalletId::get().into_sub_account_truncating(invocation_id)
+            referral.remaining = referral.remaining.saturating_sub(amount);
+            InvocationReferrals::<T>::insert(invocation_id, &referral);
+            Self::deposit_event(Event::ReferralPaid {
+                invocation_id,
+                referrer: referral.referrer,
+                amount,
+            });
+            amount
         }
 
         /// Compute `(value * pct) / 100` for balance types.
-        fn percent_of(value: BalanceOf<T>, pct: u128) -> BalanceOf<T> {
+        pub(crate) fn percent_of(value: BalanceOf<T>, pct: u128) -> BalanceOf<T> {
             use sp_runtime::traits::SaturatedConversion;
             let v: u128 = value.saturated_into();
             let result = v.saturating_mul(pct) / 100u128;
@@ -1381,65 +7236,152 @@ pub mod pallet {
         }
 
         /// Remove an invocation from the deadline and invoker indexes.
-        fn cleanup_invocation(invocation_id: InvocationId) {
+        pub(crate) fn cleanup_invocation(invocation_id: InvocationId) {
             if let Some(inv) = ServiceInvocations::<T>::get(invocation_id) {
                 InvocationsByDeadline::<T>::remove(inv.deadline, invocation_id);
-                InvocationsByListing::<T>::remove(inv.listing_id, invocation_id);
+                // Only the first cleanup of an invocation frees its slot.
+                if InvocationsByListing::<T>::take(inv.listing_id, invocation_id).is_some() {
+                    ProviderOpenInvocations::<T>::mutate(&inv.provider, |open| {
+                        *open = open.saturating_sub(1)
+                    });
+                }
                 InvocationsByInvoker::<T>::mutate(&inv.invoker, |ids| {
                     ids.retain(|&id| id != invocation_id);
                 });
+                // A provider's rejection says nothing about the invoker.
+                if inv.status != InvocationStatus::Rejected {
+                    InvokerHistories::<T>::mutate(&inv.invoker, |history| {
+                        history.finished = history.finished.saturating_add(1);
+                        if inv.status == InvocationStatus::FullyApproved {
+                            history.completed = history.completed.saturating_add(1);
+                        }
+                    });
+                }
             }
+            InvocationProgress::<T>::remove(invocation_id);
+            PendingCancellations::<T>::remove(invocation_id);
+            InvocationReferrals::<T>::remove(invocation_id);
+            EscrowRecoveryRates::<T>::remove(invocation_id);
         }
 
-        /// Process expired invocations for blocks up to `n`.
-        ///
-        /// Refunds invokers and marks invocations `Expired`.
-        /// Returns the weight consumed.
-        pub fn expire_overdue_invocations(n: BlockNumberFor<T>) -> Weight {
-            let max = T::MaxExpirationsPerBlock::get();
-            let mut count = 0u32;
-
-            // Collect expired invocation IDs first (can't mutate while iterating)
-            let expired: Vec<(BlockNumberFor<T>, InvocationId)> =
-                InvocationsByDeadline::<T>::iter()
-                    .filter(|(deadline, _, _)| *deadline < n)
-                    .take(max as usize)
-                    .map(|(deadline, id, _)| (deadline, id))
-                    .collect();
+        /// Statuses from which the invoker may cancel mid-work.
+        fn is_cancellable_in_progress(status: &InvocationStatus) -> bool {
+            matches!(
+                status,
+                InvocationStatus::Accepted
+                    | InvocationStatus::InProgress
+                    | InvocationStatus::WorkSubmitted
+            )
+        }
 
-            for (deadline, invocation_id) in expired {
-                ServiceInvocations::<T>::mutate(invocation_id, |maybe| {
-                    if let Some(inv) = maybe {
-                        if matches!(
-                            inv.status,
-                            InvocationStatus::Pending
-                                | InvocationStatus::Accepted
-                                | InvocationStatus::InProgress
-                        ) {
-                            inv.status = InvocationStatus::Expired;
-
-                            // Refund escrow
-                            let escrow = Self::invocation_escrow_account(invocation_id);
-                            let bal = T::Currency::free_balance(&escrow);
-                            let min = T::Currency::minimum_balance();
-                            if bal > min {
-                                T::Currency::transfer(
-                                    &escrow,
-                                    &inv.invoker,
-                                    bal - min,
-                                    ExistenceRequirement::AllowDeath,
-                                )
-                                .ok();
-                            }
-                        }
-                    }
-                });
+        /// Settle a requested mid-work cancellation: pay the kill fee on the
+        /// next unapproved milestone and refund the rest of the escrow.
+        fn settle_cancellation(invocation_id: InvocationId, acknowledged: bool) -> DispatchResult {
+            let kill_pct = T::KillFeePercent::get().min(100) as u128;
+
+            let (invoker, provider, kill_fee) =
+                ServiceInvocations::<T>::try_mutate(invocation_id, |maybe| {
+                    let inv = maybe.as_mut().ok_or(Error::<T>::InvocationNotFound)?;
+                    ensure!(
+                        Self::is_cancellable_in_progress(&inv.status),
+                        Error::<T>::InvalidInvocationStatus
+                    );
+
+                    let unreleased = inv.price.saturating_sub(inv.released);
+                    let next_milestone = if inv.milestones.is_empty() {
+                        unreleased
+                    } else {
+                        inv.milestones
+                            .iter()
+                            .find(|m| !matches!(m.status, MilestoneStatus::Approved))
+                            .map_or_else(Zero::zero, |m| {
+                                Self::percent_of(inv.price, m.pct_of_total as u128)
+                            })
+                    };
+                    let kill_fee = Self::percent_of(next_milestone, kill_pct).min(unreleased);
+
+                    inv.released = inv.released.saturating_add(kill_fee);
+                    inv.status = InvocationStatus::Cancelled;
+                    Ok::<_, DispatchError>((inv.invoker.clone(), inv.provider.clone(), kill_fee))
+                })?;
 
-                InvocationsByDeadline::<T>::remove(deadline, invocation_id);
-                count += 1;
+            Self::close_yield_position(invocation_id);
+            let kill_fee = Self::escrow_payout(invocation_id, kill_fee);
+            let escrow_account = Self::invocation_escrow_account(invocation_id);
+            let existence = Self::escrow_existence_requirement(invocation_id);
+            if !kill_fee.is_zero() && Self::is_escrowed(invocation_id) {
+                let payee = Self::invocation_payee(invocation_id, &provider);
+                T::Currency::transfer(&escrow_account, &payee, kill_fee, existence)
+                    .map_err(|_| Error::<T>::InsufficientBalance)?;
             }
+            let mut refunded = T::Currency::free_balance(&escrow_account);
+            if existence == ExistenceRequirement::KeepAlive {
+                refunded = refunded.saturating_sub(T::Currency::minimum_balance());
+            }
+            if !refunded.is_zero() {
+                T::Currency::transfer(&escrow_account, &invoker, refunded, existence)
+                    .map_err(|_| Error::<T>::InsufficientBalance)?;
+            }
+
+            Self::cleanup_invocation(invocation_id);
+
+            Self::deposit_event(Event::InvocationCancelledInProgress {
+                invocation_id,
+                kill_fee,
+                refunded,
+                acknowledged,
+            });
+            Ok(())
+        }
+    }
+
+    // =========================================================
+    // Budgeted hook
+    // =========================================================
 
-            Weight::from_parts(10_000u64 * count as u64, 0)
+    /// The per-block sweeps, run by `pallet-hook-budget` in this order.
+    ///
+    /// Each sweep is charged a cursor read and write plus `SWEEP_ITEM_WEIGHT`
+    /// per item, so a sweep gets at most as many items as the remaining
+    /// budget pays for, capped by `MaxExpirationsPerBlock`. Items left over
+    /// stay in their deadline indexes and are picked up by a later block.
+    /// The indexes are keyed by due block and drained in block order from a
+    /// stored cursor, so a sweep never scans entries that are not yet due.
+    impl<T: Config> BudgetedHook<BlockNumberFor<T>> for Pallet<T> {
+        fn run(now: BlockNumberFor<T>, limit: Weight) -> HookUsage {
+            let sweeps: [fn(BlockNumberFor<T>, u32) -> u32; 5] = [
+                Self::expire_overdue_invocations,
+                Self::apply_default_judgments,
+                Self::release_subscription_payments,
+                Self::expire_arbitrations,
+                Self::pay_due_settlements,
+            ];
+
+            let base = T::DbWeight::get().reads_writes(1, 1);
+            let mut usage = HookUsage::default();
+            for sweep in sweeps {
+                let affordable = limit
+                    .saturating_sub(usage.used)
+                    .saturating_sub(base)
+                    .ref_time()
+                    / SWEEP_ITEM_WEIGHT.ref_time();
+                let max = T::MaxExpirationsPerBlock::get()
+                    .min(u32::try_from(affordable).unwrap_or(u32::MAX));
+                if max == 0 {
+                    usage = usage.merge(HookUsage {
+                        used: Weight::zero(),
+                        deferred: true,
+                    });
+                    continue;
+                }
+                let spent = sweep(now, max);
+                // A sweep that used its whole allowance may have left more.
+                usage = usage.merge(HookUsage {
+                    used: base.saturating_add(SWEEP_ITEM_WEIGHT.saturating_mul(spent.into())),
+                    deferred: spent == max,
+                });
+            }
+            usage
         }
     }
 }
@@ -0,0 +1,102 @@
+//! Runtime API for listing lookups by prospective invokers.
+
+use crate::{DisputeStats, InvocationCapParams, ListingId};
+use alloc::vec::Vec;
+use codec::{Codec, Decode, Encode};
+use scale_info::TypeInfo;
+use sp_runtime::RuntimeDebug;
+
+/// Most results returned in one page by the discovery queries.
+pub const MAX_PAGE_SIZE: u32 = 100;
+
+/// A listing as shown to a prospective invoker.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct ListingSummary<AccountId, Balance> {
+    pub provider: AccountId,
+    pub min_price: Balance,
+    pub max_price: Balance,
+    pub active: bool,
+    pub total_invocations: u32,
+    pub successful_invocations: u32,
+    pub disputes: DisputeStats,
+    /// Disputes reached `HighDisputePercent` of a large enough invocation count.
+    pub high_dispute: bool,
+}
+
+/// An invoker's open-invocation cap.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct InvocationCap {
+    /// Open invocations the account may hold.
+    pub cap: u32,
+    /// Open invocations it holds now.
+    pub active: u32,
+    /// Governed formula, or `None` for the flat per-account maximum.
+    pub params: Option<InvocationCapParams>,
+}
+
+/// Settled prices of a tag over recent price eras.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct PriceStats<Balance> {
+    pub count: u32,
+    pub min: Balance,
+    /// Approximated from the era histograms.
+    pub median: Balance,
+    pub max: Balance,
+}
+
+sp_api::decl_runtime_apis! {
+    /// Listing queries for invokers.
+    pub trait ServiceMarketApi<AccountId, Balance>
+    where
+        AccountId: Codec,
+        Balance: Codec,
+    {
+        /// Listing details and dispute statistics, or `None` if it does not exist.
+        fn listing_summary(listing_id: ListingId) -> Option<ListingSummary<AccountId, Balance>>;
+
+        /// The account's open-invocation cap and the formula behind it.
+        #[api_version(2)]
+        fn invocation_cap(account: AccountId) -> InvocationCap;
+
+        /// Settled prices for `tag` over the last `last_n_eras` price eras,
+        /// or `None` if none settled.
+        #[api_version(3)]
+        fn price_stats(tag: Vec<u8>, last_n_eras: u32) -> Option<PriceStats<Balance>>;
+
+        /// Listings indexed under `tag`, `limit` (at most `MAX_PAGE_SIZE`)
+        /// at a time from `offset`.
+        #[api_version(4)]
+        fn listings_by_tag(
+            tag: Vec<u8>,
+            only_active: bool,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<(ListingId, ListingSummary<AccountId, Balance>)>;
+
+        /// The provider's listings, paginated as `listings_by_tag`.
+        #[api_version(4)]
+        fn listings_by_provider(
+            provider: AccountId,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<(ListingId, ListingSummary<AccountId, Balance>)>;
+
+        /// Active listings with all of `tags`, a provider reputation of at
+        /// least `min_reputation` and a minimum price of at most
+        /// `max_price`, paginated as `listings_by_tag`.
+        #[api_version(4)]
+        fn search(
+            tags: Vec<Vec<u8>>,
+            min_reputation: Option<u32>,
+            max_price: Option<Balance>,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<(ListingId, ListingSummary<AccountId, Balance>)>;
+
+        /// Up to `n` (at most `MAX_PAGE_SIZE`) active listings under `tag`,
+        /// ordered by provider reputation, success rate and how recently
+        /// they completed an invocation.
+        #[api_version(5)]
+        fn top_listings(tag: Vec<u8>, n: u32) -> Vec<(ListingId, ListingSummary<AccountId, Balance>)>;
+    }
+}
@@ -0,0 +1,129 @@
+//! Deferred settlement of milestone payouts.
+//!
+//! While a provider has `set_deferred_settlement`, each net payout is moved
+//! into a settlement account per payee and recorded on an open
+//! [`SettlementStatement`] with the invocation, gross amount and fees
+//! withheld (ecosystem-fund and referral shares). The statement is paid out
+//! in one transfer `SettlementPeriod` blocks after it was opened, when the
+//! payee calls `claim_settlement`, or when it reaches
+//! `MaxSettlementEntries`, and the full statement is emitted in
+//! `SettlementPaid` for accounting. A payout too small to open an empty
+//! settlement account is paid directly.
+
+use crate::pallet::{
+    BalanceOf, Config, Error, Event, Pallet, SettlementCursor, SettlementEntry,
+    SettlementStatement, SettlementStatements, SettlementsDue,
+};
+use clawchain_primitives::hook_budget::drain_due;
+use frame_support::{
+    pallet_prelude::*,
+    traits::{Currency, ExistenceRequirement},
+};
+use frame_system::pallet_prelude::*;
+use sp_runtime::traits::{Saturating, TrailingZeroInput, Zero};
+
+impl<T: Config> Pallet<T> {
+    /// Account holding `payee`'s accrued settlement.
+    ///
+    /// Derived from a hash rather than a truncated sub-account, so payees
+    /// get distinct accounts even when the payee id does not fit in the
+    /// sub-account seed.
+    pub fn settlement_account(payee: &T::AccountId) -> T::AccountId {
+        let entropy =
+            (T::PalletId::get(), b"settlmnt", payee).using_encoded(sp_io::hashing::blake2_256);
+        Decode::decode(&mut TrailingZeroInput::new(entropy.as_ref()))
+            .expect("infinite length input; no invalid inputs for type; qed")
+    }
+
+    /// Move a payout from `escrow` to `payee`'s settlement account and
+    /// record it on the payee's statement, paying out a full statement
+    /// first. Returns `false`, moving nothing, if the payout is too small
+    /// to open an empty settlement account; the caller pays it directly.
+    pub(crate) fn accrue_settlement(
+        escrow: &T::AccountId,
+        payee: &T::AccountId,
+        entry: SettlementEntry<BalanceOf<T>>,
+        existence: ExistenceRequirement,
+    ) -> Result<bool, DispatchError> {
+        if SettlementStatements::<T>::get(payee)
+            .is_some_and(|statement| statement.entries.is_full())
+        {
+            Self::pay_settlement(payee)?;
+        }
+
+        let net = entry.amount.saturating_sub(entry.fees);
+        let account = Self::settlement_account(payee);
+        if T::Currency::total_balance(&account).saturating_add(net) < T::Currency::minimum_balance()
+        {
+            return Ok(false);
+        }
+        T::Currency::transfer(escrow, &account, net, existence)
+            .map_err(|_| Error::<T>::InsufficientBalance)?;
+
+        let mut statement = SettlementStatements::<T>::get(payee).unwrap_or_else(|| {
+            // Never behind the sweep's cursor, or it would not be found.
+            let due = <frame_system::Pallet<T>>::block_number()
+                .saturating_add(T::SettlementPeriod::get().into())
+                .max(SettlementCursor::<T>::get());
+            SettlementsDue::<T>::insert(due, payee, ());
+            SettlementStatement {
+                entries: BoundedVec::default(),
+                total: Zero::zero(),
+                due,
+            }
+        });
+        statement.total = statement.total.saturating_add(net);
+        // Cannot fail: a full statement was paid out above.
+        let _ = statement.entries.try_push(entry);
+        SettlementStatements::<T>::insert(payee, statement);
+        Ok(true)
+    }
+
+    /// Pay out `payee`'s open statement in one transfer.
+    pub(crate) fn pay_settlement(payee: &T::AccountId) -> DispatchResult {
+        let Some(statement) = SettlementStatements::<T>::get(payee) else {
+            return Ok(());
+        };
+        let source = Self::settlement_account(payee);
+        T::Currency::transfer(
+            &source,
+            payee,
+            statement.total,
+            Self::drain_existence_requirement(&source),
+        )
+        .map_err(|_| Error::<T>::InsufficientBalance)?;
+
+        SettlementStatements::<T>::remove(payee);
+        SettlementsDue::<T>::remove(statement.due, payee);
+        Self::deposit_event(Event::SettlementPaid {
+            payee: payee.clone(),
+            total: statement.total,
+            entries: statement.entries,
+        });
+        Ok(())
+    }
+
+    /// Pay out settlement statements due by `n`, at most `max` items,
+    /// walking due blocks from `SettlementCursor`. Returns the number of
+    /// items spent.
+    pub fn pay_due_settlements(n: BlockNumberFor<T>, max: u32) -> u32 {
+        let mut cursor = SettlementCursor::<T>::get();
+        let spent = drain_due(
+            &mut cursor,
+            n,
+            max,
+            |due, count| {
+                SettlementsDue::<T>::iter_key_prefix(due)
+                    .take(count)
+                    .collect()
+            },
+            |due, payee| {
+                // On failure the statement is left for the payee to claim.
+                let _ = Self::pay_settlement(&payee);
+                SettlementsDue::<T>::remove(due, &payee);
+            },
+        );
+        SettlementCursor::<T>::put(cursor);
+        spent
+    }
+}
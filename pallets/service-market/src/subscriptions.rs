@@ -0,0 +1,142 @@
+//! Recurring subscriptions to a listing.
+//!
+//! `subscribe_service` escrows `price_per_period * periods` up front (at most
+//! `MaxSubscriptionPeriods` periods) in a per-subscription sub-account. Each
+//! time a period of `period_blocks` ends, the per-block sweep releases its
+//! payment to the provider. The subscriber may pause the clock and resume it
+//! later. Either party may cancel: the provider keeps the elapsed share of
+//! the current period and the rest is refunded. A disputed subscription
+//! releases nothing until governance splits its escrow with
+//! `resolve_subscription_dispute`.
+
+use crate::pallet::{
+    BalanceOf, Config, Event, Pallet, Subscription, SubscriptionId, SubscriptionPaymentCursor,
+    SubscriptionStatus, Subscriptions, SubscriptionsByDueBlock,
+};
+use clawchain_primitives::hook_budget::drain_due;
+use frame_support::{
+    pallet_prelude::*,
+    traits::{Currency, ExistenceRequirement},
+};
+use frame_system::pallet_prelude::*;
+use sp_runtime::traits::{AccountIdConversion, Saturating, Zero};
+
+impl<T: Config> Pallet<T> {
+    /// Escrow sub-account holding a subscription's unpaid periods.
+    pub fn subscription_escrow_account(subscription_id: SubscriptionId) -> T::AccountId {
+        T::PalletId::get().into_sub_account_truncating((b"subscrip", subscription_id))
+    }
+
+    /// End a subscription: pay up to `to_provider` of its escrow to the
+    /// provider and refund the rest to the subscriber. Returns the amounts
+    /// paid and refunded.
+    pub(crate) fn close_subscription(
+        subscription_id: SubscriptionId,
+        sub: &Subscription<T>,
+        to_provider: BalanceOf<T>,
+    ) -> (BalanceOf<T>, BalanceOf<T>) {
+        let escrow = Self::subscription_escrow_account(subscription_id);
+        let held = T::Currency::free_balance(&escrow);
+        let to_provider = to_provider.min(held);
+        let existence = if to_provider == held {
+            Self::drain_existence_requirement(&escrow)
+        } else {
+            ExistenceRequirement::KeepAlive
+        };
+        let payee = Self::listing_payee(sub.listing_id, &sub.provider);
+        let paid = if !to_provider.is_zero()
+            && T::Currency::transfer(&escrow, &payee, to_provider, existence).is_ok()
+        {
+            to_provider
+        } else {
+            Zero::zero()
+        };
+        let refund = T::Currency::free_balance(&escrow);
+        let refunded = if !refund.is_zero()
+            && T::Currency::transfer(
+                &escrow,
+                &sub.subscriber,
+                refund,
+                Self::drain_existence_requirement(&escrow),
+            )
+            .is_ok()
+        {
+            refund
+        } else {
+            Zero::zero()
+        };
+
+        SubscriptionsByDueBlock::<T>::remove(sub.next_payment, subscription_id);
+        Subscriptions::<T>::remove(subscription_id);
+        (paid, refunded)
+    }
+
+    /// Release the payments of subscription periods that ended by `n`,
+    /// at most `max` items, walking due blocks from
+    /// `SubscriptionPaymentCursor`. Returns the number of items spent.
+    pub fn release_subscription_payments(n: BlockNumberFor<T>, max: u32) -> u32 {
+        let mut cursor = SubscriptionPaymentCursor::<T>::get();
+        let spent = drain_due(
+            &mut cursor,
+            n,
+            max,
+            |due, count| {
+                SubscriptionsByDueBlock::<T>::iter_key_prefix(due)
+                    .take(count)
+                    .collect()
+            },
+            Self::release_subscription_payment,
+        );
+        SubscriptionPaymentCursor::<T>::put(cursor);
+        spent
+    }
+
+    /// Release the payment of `subscription_id`'s period ending at `due`.
+    pub(crate) fn release_subscription_payment(
+        due: BlockNumberFor<T>,
+        subscription_id: SubscriptionId,
+    ) {
+        SubscriptionsByDueBlock::<T>::remove(due, subscription_id);
+        let Some(mut sub) = Subscriptions::<T>::get(subscription_id) else {
+            return;
+        };
+        if sub.status != SubscriptionStatus::Active || sub.next_payment != due {
+            return;
+        }
+        sub.periods_remaining = sub.periods_remaining.saturating_sub(1);
+        let last = sub.periods_remaining == 0;
+        let escrow = Self::subscription_escrow_account(subscription_id);
+        let amount = if last {
+            T::Currency::free_balance(&escrow)
+        } else {
+            sub.price_per_period
+        };
+        let existence = if last {
+            Self::drain_existence_requirement(&escrow)
+        } else {
+            ExistenceRequirement::KeepAlive
+        };
+        // Best effort: an unpaid period stays in escrow and is
+        // refunded or paid when the subscription closes.
+        let payee = Self::listing_payee(sub.listing_id, &sub.provider);
+        let amount = if T::Currency::transfer(&escrow, &payee, amount, existence).is_ok() {
+            amount
+        } else {
+            Zero::zero()
+        };
+        Self::deposit_event(Event::SubscriptionPaymentReleased {
+            subscription_id,
+            amount,
+            periods_remaining: sub.periods_remaining,
+        });
+
+        if last {
+            Self::close_subscription(subscription_id, &sub, Zero::zero());
+            Self::deposit_event(Event::SubscriptionCompleted { subscription_id });
+        } else {
+            sub.next_payment = due.saturating_add(sub.period_blocks.into());
+            SubscriptionsByDueBlock::<T>::insert(sub.next_payment, subscription_id, ());
+            Subscriptions::<T>::insert(subscription_id, sub);
+        }
+    }
+}
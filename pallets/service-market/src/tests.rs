@@ -1,13 +1,13 @@
 //! Unit tests for pallet-service-market v2.
 
-use crate::{self as pallet_service_market, pallet::*, *};
-use frame_support::traits::Hooks;
+use crate::{self as pallet_service_market, *};
+use frame_support::traits::ExistenceRequirement;
 use frame_support::{assert_noop, assert_ok, parameter_types, BoundedVec, PalletId};
 use sp_core::H256;
 use sp_runtime::DispatchResult;
 use sp_runtime::{
     traits::{BlakeTwo256, IdentityLookup},
-    BuildStorage,
+    BuildStorage, Perbill,
 };
 
 type Block = frame_system::mocking::MockBlock<Test>;
@@ -95,6 +95,9 @@ impl pallet_reputation::Config for Test {
     type InitialReputation = InitialReputation;
     type MaxReputationDelta = MaxReputationDelta;
     type MaxHistoryLength = MaxHistoryLength;
+    type MaxLeaderboardSize = frame_support::traits::ConstU32<10>;
+    type LeaderboardEraLength = frame_support::traits::ConstU64<0>;
+    type LeaderboardHistoryDepth = frame_support::traits::ConstU32<0>;
 }
 
 parameter_types! {
@@ -112,8 +115,74 @@ parameter_types! {
     pub const MaxDescriptionLength: u32 = 512;
     pub const MaxCidLength: u32 = 96;
     pub const AutoApproveMaxDelay: u32 = 1000;
-    pub const ExpireBounty: u64 = 10;
+    pub const ExpireBountyShare: Perbill = Perbill::from_percent(10);
+    pub const MinExpireBounty: u64 = 5;
+    pub const MaxExpireBounty: u64 = 50;
     pub const MaxExpirationsPerBlock: u32 = 5;
+    pub const MaxTemplatesPerAccount: u32 = 2;
+    pub const MaxTemplateBatch: u32 = 3;
+    pub const MaxOracleAge: u32 = 10;
+    pub const DisputeLossThreshold: u32 = 2;
+    pub const DisputeLossWindow: u32 = 100;
+    pub const ReactivationBond: u64 = 500;
+    pub const MaxJurisdictionsPerListing: u32 = 3;
+    pub const MaxListingsPerJurisdiction: u32 = 100;
+    pub const SlotReservationFee: u64 = 10;
+    pub const MaxSlotDuration: u32 = 100;
+    pub const MaxReservationsPerListing: u32 = 2;
+    pub const PriorityDisputeThreshold: u64 = 1_000;
+    pub const StandardDisputeTrack: DisputeTrack<u64> = DisputeTrack {
+        evidence_window: 50,
+        juror_panel_size: 3,
+        juror_stake: 10,
+        juror_reward: 5,
+    };
+    pub const PriorityDisputeTrack: DisputeTrack<u64> = DisputeTrack {
+        evidence_window: 10,
+        juror_panel_size: 7,
+        juror_stake: 50,
+        juror_reward: 25,
+    };
+    pub const MaxProgressCheckpoints: u32 = 3;
+    pub const ProgressStallPeriod: u32 = 20;
+    pub const KillFeePercent: u8 = 20;
+    pub const CancellationAckTimeout: u32 = 10;
+    pub const ListingApplicationBond: u64 = 200;
+    pub const ApplicationApprovalVotes: u32 = 2;
+    pub const ProvisionalInvocations: u32 = 2;
+    pub const ProvisionalSlaPenalty: u64 = 50;
+    pub const MaxTrialPrice: u64 = 100;
+    pub const TrialRefundWindow: u32 = 20;
+    pub const HighDisputePercent: u8 = 50;
+    pub const MinInvocationsForDisputeFlag: u32 = 2;
+    pub const EcosystemFund: u64 = 99;
+    pub const MaxEcosystemFundShare: Perbill = Perbill::from_percent(20);
+    pub const FundingRoundLength: u32 = 10;
+    pub const MaxRoundProjects: u32 = 3;
+    pub const LivenessChallengeFee: u64 = 5;
+    pub const LivenessSlashPercent: u8 = 50;
+    pub const PriceEraLength: u32 = 100;
+    pub const MaxPriceEras: u32 = 3;
+    pub const PriceBucketUnit: u64 = 10;
+    pub const DisputeResponseWindow: u32 = 20;
+    pub static ProviderBondAmount: u64 = 0;
+    pub const SlaSlashPercent: u8 = 50;
+    pub const SlashInvokerShare: Perbill = Perbill::from_percent(60);
+    pub const SlashTreasury: u64 = 98;
+    pub const MaxReferralFee: Perbill = Perbill::from_percent(10);
+    pub const MaxSubscriptionPeriods: u32 = 12;
+    pub const MinArbiterReputation: u32 = 5_000;
+    pub const MaxArbiters: u32 = 10;
+    pub const MaxArbitrationPanel: u32 = 7;
+    pub const ArbitrationVotingPeriod: u32 = 10;
+    pub const PayoutChangeDelay: u32 = 20;
+    pub const FlagBond: u64 = 10;
+    pub const FlagThreshold: u32 = 2;
+    pub const MaxFlagsPerListing: u32 = 5;
+    pub const MaxFlagReasonLength: u32 = 64;
+    pub const RankingHalfLife: u32 = 100;
+    pub const YieldInvokerShare: Perbill = Perbill::from_percent(50);
+    pub const CompetitionAwardPeriod: u32 = 10;
 }
 
 impl pallet_service_market::Config for Test {
@@ -133,8 +202,175 @@ impl pallet_service_market::Config for Test {
     type MaxDescriptionLength = MaxDescriptionLength;
     type MaxCidLength = MaxCidLength;
     type AutoApproveMaxDelay = AutoApproveMaxDelay;
-    type ExpireBounty = ExpireBounty;
+    type ExpireBountyShare = ExpireBountyShare;
+    type MinExpireBounty = MinExpireBounty;
+    type MaxExpireBounty = MaxExpireBounty;
     type MaxExpirationsPerBlock = MaxExpirationsPerBlock;
+    type AccountGuards = MockAccountGuards;
+    type MaxTemplatesPerAccount = MaxTemplatesPerAccount;
+    type MaxTemplateBatch = MaxTemplateBatch;
+    type PriceOracle = MockPriceOracle;
+    type MaxOracleAge = MaxOracleAge;
+    type DisputeLossThreshold = DisputeLossThreshold;
+    type DisputeLossWindow = DisputeLossWindow;
+    type ReactivationBond = ReactivationBond;
+    type MaxJurisdictionsPerListing = MaxJurisdictionsPerListing;
+    type MaxListingsPerJurisdiction = MaxListingsPerJurisdiction;
+    type SlotReservationFee = SlotReservationFee;
+    type MaxSlotDuration = MaxSlotDuration;
+    type MaxReservationsPerListing = MaxReservationsPerListing;
+    type PriorityDisputeThreshold = PriorityDisputeThreshold;
+    type StandardDisputeTrack = StandardDisputeTrack;
+    type PriorityDisputeTrack = PriorityDisputeTrack;
+    type MaxProgressCheckpoints = MaxProgressCheckpoints;
+    type ProgressStallPeriod = ProgressStallPeriod;
+    type KillFeePercent = KillFeePercent;
+    type CancellationAckTimeout = CancellationAckTimeout;
+    type OnboardingOrigin = frame_system::EnsureRoot<u64>;
+    type ListingApplicationBond = ListingApplicationBond;
+    type ApplicationApprovalVotes = ApplicationApprovalVotes;
+    type ProvisionalInvocations = ProvisionalInvocations;
+    type ProvisionalSlaPenalty = ProvisionalSlaPenalty;
+    type MaxTrialPrice = MaxTrialPrice;
+    type TrialRefundWindow = TrialRefundWindow;
+    type HighDisputePercent = HighDisputePercent;
+    type MinInvocationsForDisputeFlag = MinInvocationsForDisputeFlag;
+    type EcosystemFund = EcosystemFund;
+    type MaxEcosystemFundShare = MaxEcosystemFundShare;
+    type FundingRoundLength = FundingRoundLength;
+    type MaxRoundProjects = MaxRoundProjects;
+    type LivenessChallengeFee = LivenessChallengeFee;
+    type LivenessSlashPercent = LivenessSlashPercent;
+    type PriceEraLength = PriceEraLength;
+    type MaxPriceEras = MaxPriceEras;
+    type PriceBucketUnit = PriceBucketUnit;
+    type DisputeResponseWindow = DisputeResponseWindow;
+    type ProviderBondAmount = ProviderBondAmount;
+    type SlaSlashPercent = SlaSlashPercent;
+    type SlashInvokerShare = SlashInvokerShare;
+    type SlashTreasury = SlashTreasury;
+    type MaxReferralFee = MaxReferralFee;
+    type MaxSubscriptionPeriods = MaxSubscriptionPeriods;
+    type MinArbiterReputation = MinArbiterReputation;
+    type MaxArbiters = MaxArbiters;
+    type MaxArbitrationPanel = MaxArbitrationPanel;
+    type ArbitrationVotingPeriod = ArbitrationVotingPeriod;
+    type PayoutChangeDelay = PayoutChangeDelay;
+    type ModerationOrigin = frame_system::EnsureRoot<u64>;
+    type FlagBond = FlagBond;
+    type FlagThreshold = FlagThreshold;
+    type MaxFlagsPerListing = MaxFlagsPerListing;
+    type MaxFlagReasonLength = MaxFlagReasonLength;
+    type RankingHalfLife = RankingHalfLife;
+    type EscrowYield = MockEscrowYield;
+    type YieldInvokerShare = YieldInvokerShare;
+    type MaxCompetitionEntrants = frame_support::traits::ConstU32<3>;
+    type CompetitionAwardPeriod = CompetitionAwardPeriod;
+    type AttestorOrigin = frame_system::EnsureRoot<u64>;
+    type MaxToolchainLength = frame_support::traits::ConstU32<64>;
+    type MaxListingsPerBuild = frame_support::traits::ConstU32<4>;
+    type MaxAllowlistSize = frame_support::traits::ConstU32<3>;
+    type SettlementPeriod = frame_support::traits::ConstU32<50>;
+    type MaxSettlementEntries = frame_support::traits::ConstU32<2>;
+    type Insurance = MockInsurance;
+}
+
+thread_local! {
+    static GUARDED_ACCOUNTS: core::cell::RefCell<Vec<u64>> = const { core::cell::RefCell::new(Vec::new()) };
+}
+
+/// Stand-in for guards contributed by other pallets (relayer sets, etc.).
+pub struct MockAccountGuards;
+
+impl MockAccountGuards {
+    fn guard(who: u64) {
+        GUARDED_ACCOUNTS.with(|g| g.borrow_mut().push(who));
+    }
+}
+
+impl ProviderOfAccountGuards<u64> for MockAccountGuards {
+    fn is_guarded(who: &u64) -> bool {
+        GUARDED_ACCOUNTS.with(|g| g.borrow().contains(who))
+    }
+}
+
+thread_local! {
+    static ORACLE_RATE: core::cell::RefCell<Option<(u64, u64)>> = const { core::cell::RefCell::new(None) };
+}
+
+/// Oracle stand-in returning whatever `set_rate` last stored.
+pub struct MockPriceOracle;
+
+impl MockPriceOracle {
+    fn set_rate(rate: u64, updated_at: u64) {
+        ORACLE_RATE.with(|r| *r.borrow_mut() = Some((rate, updated_at)));
+    }
+}
+
+impl PriceOracle<u64, u64> for MockPriceOracle {
+    fn rate() -> Option<(u64, u64)> {
+        ORACLE_RATE.with(|r| *r.borrow())
+    }
+}
+
+thread_local! {
+    static INSURANCE_POLICY: core::cell::RefCell<Option<(u64, u64, InsurancePolicyId)>> = const { core::cell::RefCell::new(None) };
+}
+
+/// Insurance pool stand-in holding at most one policy.
+pub struct MockInsurance;
+
+impl MockInsurance {
+    fn insure(provider: u64, coverage: u64, policy: InsurancePolicyId) {
+        INSURANCE_POLICY.with(|p| *p.borrow_mut() = Some((provider, coverage, policy)));
+    }
+}
+
+impl InsurancePolicies<u64, u64> for MockInsurance {
+    fn active_policy(provider: &u64, coverage: u64) -> Option<InsurancePolicyId> {
+        INSURANCE_POLICY.with(|p| {
+            p.borrow()
+                .filter(|(insured, covered, _)| insured == provider && *covered >= coverage)
+                .map(|(_, _, policy)| policy)
+        })
+    }
+}
+
+thread_local! {
+    static YIELD_SLASH: core::cell::RefCell<u64> = const { core::cell::RefCell::new(0) };
+}
+
+/// Staking stand-in: reserves the principal and pays 10% of it on unstake,
+/// unless `YIELD_SLASH` is set, in which case that much of the principal is
+/// lost and no reward is paid.
+pub struct MockEscrowYield;
+
+impl MockEscrowYield {
+    fn slash_next(amount: u64) {
+        YIELD_SLASH.with(|s| *s.borrow_mut() = amount);
+    }
+}
+
+impl EscrowYield<u64, u64> for MockEscrowYield {
+    fn stake(position: &u64, amount: u64) -> DispatchResult {
+        <Balances as frame_support::traits::ReservableCurrency<u64>>::reserve(position, amount)
+    }
+
+    fn unstake(position: &u64) -> u64 {
+        use frame_support::traits::{Currency, ReservableCurrency};
+        let slash = YIELD_SLASH.with(|s| core::mem::take(&mut *s.borrow_mut()));
+        if slash > 0 {
+            let _ = Balances::slash_reserved(position, slash);
+        }
+        let principal = Balances::reserved_balance(position);
+        Balances::unreserve(position, principal);
+        if slash > 0 {
+            return 0;
+        }
+        let reward = principal / 10;
+        let _ = Balances::deposit_creating(position, reward);
+        reward
+    }
 }
 
 // =========================================================
@@ -157,6 +393,8 @@ fn new_test_ext() -> sp_io::TestExternalities {
             (BOB, 100_000),
             (CHARLIE, 100_000),
             (DAVE, 100_000),
+            // Slashes cannot be repatriated to a dead account.
+            (SlashTreasury::get(), ExistentialDeposit::get()),
         ],
         dev_accounts: Default::default(),
     }
@@ -168,6 +406,14 @@ fn new_test_ext() -> sp_io::TestExternalities {
     ext
 }
 
+/// Run the per-block sweeps with an unlimited budget for every block up to
+/// `n`, as `pallet-hook-budget` would.
+fn run_sweeps_to(n: u64) {
+    for block in 1..=n {
+        <ServiceMarket as BudgetedHook<u64>>::run(block, Weight::MAX);
+    }
+}
+
 fn list_service_default(provider: u64) -> DispatchResult {
     ServiceMarket::list_service(
         RuntimeOrigin::signed(provider),
@@ -193,9 +439,19 @@ fn invoke_service_default(invoker: u64, listing_id: ListingId) -> DispatchResult
         None,
         100,
         100,
+        None,
+        false,
+        None,
     )
 }
 
+fn accept_default(invocation_id: InvocationId) {
+    assert_ok!(ServiceMarket::accept_invocation(
+        RuntimeOrigin::signed(ALICE),
+        invocation_id
+    ));
+}
+
 // =========================================================
 // Listing tests
 // =========================================================
@@ -332,6 +588,61 @@ fn update_listing_succeeds() {
     });
 }
 
+#[test]
+fn listing_updates_archive_terms_versions() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        let original = ServiceMarket::listing_terms_hash(&ServiceListings::<Test>::get(0).unwrap());
+        assert_ok!(invoke_service_default(BOB, 0));
+        assert_eq!(
+            ServiceInvocations::<Test>::get(0).unwrap().listing_version,
+            0
+        );
+        assert_ok!(ServiceMarket::cancel_invocation(
+            RuntimeOrigin::signed(BOB),
+            0
+        ));
+
+        // A description is not part of the terms.
+        assert_ok!(ServiceMarket::update_listing(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            None,
+            Some(b"Now with longer context".to_vec()),
+            None,
+            None,
+            None,
+            None,
+            None,
+        ));
+        assert_eq!(ListingVersion::<Test>::get(0), 0);
+
+        System::set_block_number(5);
+        assert_ok!(ServiceMarket::update_listing(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            None,
+            None,
+            Some(200),
+            Some(200),
+            None,
+            None,
+            None,
+        ));
+        assert_eq!(ListingVersion::<Test>::get(0), 1);
+        let history = ServiceMarket::listing_terms_history(0);
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].1.terms_hash, original);
+        assert_eq!(history[1].1.effective_from, 5);
+        assert_ne!(history[1].1.terms_hash, original);
+
+        // The earlier invocation still points at the terms it agreed to.
+        let (version, terms) = ServiceMarket::invocation_terms(0).unwrap();
+        assert_eq!(version, 0);
+        assert_eq!(terms.terms_hash, original);
+    });
+}
+
 #[test]
 fn update_listing_fails_if_not_provider() {
     new_test_ext().execute_with(|| {
@@ -464,6 +775,9 @@ fn invoke_service_fails_price_below_minimum() {
                 None,
                 50, // below min
                 100,
+                None,
+                false,
+                None,
             ),
             Error::<Test>::PriceBelowMinimum
         );
@@ -535,6 +849,7 @@ fn submit_work_succeeds() {
         assert_ok!(list_service_default(ALICE));
         assert_ok!(invoke_service_default(BOB, 0));
 
+        accept_default(0);
         assert_ok!(ServiceMarket::submit_invocation_work(
             RuntimeOrigin::signed(ALICE),
             0,
@@ -568,87 +883,114 @@ fn submit_work_fails_not_provider() {
 }
 
 #[test]
-fn approve_milestone_single_releases_full_escrow() {
+fn accept_invocation_requires_pending_and_provider() {
     new_test_ext().execute_with(|| {
         assert_ok!(list_service_default(ALICE));
         assert_ok!(invoke_service_default(BOB, 0));
+        System::set_block_number(3);
 
-        // Submit work
-        assert_ok!(ServiceMarket::submit_invocation_work(
-            RuntimeOrigin::signed(ALICE),
-            0,
-            None,
-            b"proof".to_vec(),
-            ProofType::Hash,
-        ));
-
-        let alice_before = Balances::free_balance(ALICE);
-
-        // Approve (single-milestone mode)
-        assert_ok!(ServiceMarket::approve_milestone(
-            RuntimeOrigin::signed(BOB),
-            0,
-            0,
-        ));
-
-        let alice_after = Balances::free_balance(ALICE);
-        assert_eq!(alice_after - alice_before, 100); // full escrow released
+        assert_noop!(
+            ServiceMarket::accept_invocation(RuntimeOrigin::signed(BOB), 0),
+            Error::<Test>::NotProvider
+        );
+        assert_noop!(
+            ServiceMarket::submit_invocation_work(
+                RuntimeOrigin::signed(ALICE),
+                0,
+                None,
+                b"proof".to_vec(),
+                ProofType::Hash,
+            ),
+            Error::<Test>::InvocationNotAccepted
+        );
+        assert_noop!(
+            ServiceMarket::report_progress(RuntimeOrigin::signed(ALICE), 0, 10, b"cid".to_vec()),
+            Error::<Test>::InvocationNotAccepted
+        );
 
+        accept_default(0);
         let inv = ServiceInvocations::<Test>::get(0).unwrap();
-        assert_eq!(inv.status, InvocationStatus::FullyApproved);
+        assert_eq!(inv.status, InvocationStatus::Accepted);
+        assert_eq!(inv.accepted_at, Some(3));
+        assert_eq!(inv.completed_at, None);
+        System::assert_last_event(
+            Event::InvocationAccepted {
+                invocation_id: 0,
+                provider: ALICE,
+            }
+            .into(),
+        );
+        assert_noop!(
+            ServiceMarket::accept_invocation(RuntimeOrigin::signed(ALICE), 0),
+            Error::<Test>::InvalidInvocationStatus
+        );
     });
 }
 
 #[test]
-fn approve_milestone_partial_multi_milestone() {
+fn accept_invocation_enforces_response_sla() {
     new_test_ext().execute_with(|| {
         assert_ok!(list_service_default(ALICE));
+        assert_ok!(invoke_service_default(BOB, 0));
+        assert_ok!(invoke_service_default(BOB, 0));
 
-        // Invoke with 2 milestones: 60% + 40%
-        assert_ok!(ServiceMarket::invoke_service(
+        // Created at block 1 with a 10-block response SLA.
+        System::set_block_number(11);
+        accept_default(0);
+        System::set_block_number(12);
+        assert_noop!(
+            ServiceMarket::accept_invocation(RuntimeOrigin::signed(ALICE), 1),
+            Error::<Test>::AcceptanceWindowClosed
+        );
+        assert_ok!(ServiceMarket::cancel_invocation(
             RuntimeOrigin::signed(BOB),
-            0,
-            b"requirements".to_vec(),
-            Some(vec![
-                MilestoneSpec { pct_of_total: 60 },
-                MilestoneSpec { pct_of_total: 40 },
-            ]),
-            100,
-            100,
-        ));
-
-        // Submit work for milestone 0
-        assert_ok!(ServiceMarket::submit_invocation_work(
-            RuntimeOrigin::signed(ALICE),
-            0,
-            Some(0),
-            b"proof1".to_vec(),
-            ProofType::Hash,
+            1
         ));
+    });
+}
 
-        let alice_before = Balances::free_balance(ALICE);
+#[test]
+fn reject_invocation_refunds_invoker() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(invoke_service_default(BOB, 0));
+        let bob_before = Balances::free_balance(BOB);
 
-        // Approve milestone 0 → 60 tokens released
-        assert_ok!(ServiceMarket::approve_milestone(
-            RuntimeOrigin::signed(BOB),
-            0,
+        assert_noop!(
+            ServiceMarket::reject_invocation(RuntimeOrigin::signed(BOB), 0, b"busy".to_vec()),
+            Error::<Test>::NotProvider
+        );
+        assert_ok!(ServiceMarket::reject_invocation(
+            RuntimeOrigin::signed(ALICE),
             0,
+            b"busy".to_vec()
         ));
 
-        let alice_after = Balances::free_balance(ALICE);
-        assert_eq!(alice_after - alice_before, 60);
-
-        // Invocation should not be fully approved yet
-        let inv = ServiceInvocations::<Test>::get(0).unwrap();
-        assert_ne!(inv.status, InvocationStatus::FullyApproved);
+        assert_eq!(Balances::free_balance(BOB) - bob_before, 100);
+        assert_eq!(
+            ServiceInvocations::<Test>::get(0).unwrap().status,
+            InvocationStatus::Rejected
+        );
+        assert_eq!(InvokerHistories::<Test>::get(BOB).finished, 0);
+        System::assert_last_event(
+            Event::InvocationRejected {
+                invocation_id: 0,
+                provider: ALICE,
+                reason: b"busy".to_vec().try_into().unwrap(),
+            }
+            .into(),
+        );
+        assert_noop!(
+            ServiceMarket::accept_invocation(RuntimeOrigin::signed(ALICE), 0),
+            Error::<Test>::InvalidInvocationStatus
+        );
     });
 }
 
 #[test]
-fn approve_all_milestones_marks_fully_approved() {
+fn multi_milestone_submission_walks_in_progress_to_work_submitted() {
     new_test_ext().execute_with(|| {
         assert_ok!(list_service_default(ALICE));
-
         assert_ok!(ServiceMarket::invoke_service(
             RuntimeOrigin::signed(BOB),
             0,
@@ -659,46 +1001,90 @@ fn approve_all_milestones_marks_fully_approved() {
             ]),
             100,
             100,
+            None,
+            false,
+            None,
         ));
+        accept_default(0);
+        let submit = |milestone_index| {
+            ServiceMarket::submit_invocation_work(
+                RuntimeOrigin::signed(ALICE),
+                0,
+                milestone_index,
+                b"proof".to_vec(),
+                ProofType::Hash,
+            )
+        };
+
+        assert_noop!(submit(None), Error::<Test>::MilestoneIndexRequired);
+
+        System::set_block_number(4);
+        assert_ok!(submit(Some(0)));
+        let inv = ServiceInvocations::<Test>::get(0).unwrap();
+        assert_eq!(inv.status, InvocationStatus::InProgress);
+        assert_eq!(inv.completed_at, None);
+        assert_eq!(inv.milestones[0].status, MilestoneStatus::Submitted);
+        assert_eq!(inv.milestones[0].submitted_at, Some(4));
+        assert_eq!(inv.milestones[1].status, MilestoneStatus::Pending);
 
-        // Submit and approve milestone 0
-        assert_ok!(ServiceMarket::submit_invocation_work(
-            RuntimeOrigin::signed(ALICE),
-            0,
-            Some(0),
-            b"proof1".to_vec(),
-            ProofType::Hash,
-        ));
         assert_ok!(ServiceMarket::approve_milestone(
             RuntimeOrigin::signed(BOB),
             0,
-            0
+            0,
         ));
+        assert_noop!(submit(Some(0)), Error::<Test>::MilestoneAlreadyApproved);
 
-        // Submit and approve milestone 1
+        System::set_block_number(9);
+        assert_ok!(submit(Some(1)));
+        let inv = ServiceInvocations::<Test>::get(0).unwrap();
+        assert_eq!(inv.status, InvocationStatus::WorkSubmitted);
+        assert_eq!(inv.completed_at, Some(9));
+        assert_eq!(inv.milestones[1].submitted_at, Some(9));
+
+        // Nothing left to submit once the final submission is in.
+        assert_noop!(submit(Some(1)), Error::<Test>::InvalidInvocationStatus);
+    });
+}
+
+#[test]
+fn approve_milestone_single_releases_full_escrow() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(invoke_service_default(BOB, 0));
+
+        // Submit work
+        accept_default(0);
         assert_ok!(ServiceMarket::submit_invocation_work(
             RuntimeOrigin::signed(ALICE),
             0,
-            Some(1),
-            b"proof2".to_vec(),
+            None,
+            b"proof".to_vec(),
             ProofType::Hash,
         ));
+
+        let alice_before = Balances::free_balance(ALICE);
+
+        // Approve (single-milestone mode)
         assert_ok!(ServiceMarket::approve_milestone(
             RuntimeOrigin::signed(BOB),
             0,
-            1
+            0,
         ));
 
+        let alice_after = Balances::free_balance(ALICE);
+        assert_eq!(alice_after - alice_before, 100); // full escrow released
+
         let inv = ServiceInvocations::<Test>::get(0).unwrap();
         assert_eq!(inv.status, InvocationStatus::FullyApproved);
     });
 }
 
 #[test]
-fn approve_milestone_fails_not_invoker() {
+fn approve_and_review_pays_provider_and_records_review() {
     new_test_ext().execute_with(|| {
         assert_ok!(list_service_default(ALICE));
         assert_ok!(invoke_service_default(BOB, 0));
+        accept_default(0);
         assert_ok!(ServiceMarket::submit_invocation_work(
             RuntimeOrigin::signed(ALICE),
             0,
@@ -706,463 +1092,4176 @@ fn approve_milestone_fails_not_invoker() {
             b"proof".to_vec(),
             ProofType::Hash,
         ));
+
+        let comment_hash = H256::repeat_byte(7);
         assert_noop!(
-            ServiceMarket::approve_milestone(
-                RuntimeOrigin::signed(CHARLIE), // not invoker
-                0,
-                0,
-            ),
-            Error::<Test>::NotInvoker
+            ServiceMarket::approve_and_review(RuntimeOrigin::signed(BOB), 0, 0, 6, comment_hash),
+            Error::<Test>::InvalidRating
+        );
+
+        let alice_before = Balances::free_balance(ALICE);
+        let score_before = pallet_reputation::Reputations::<Test>::get(ALICE).score;
+        assert_ok!(ServiceMarket::approve_and_review(
+            RuntimeOrigin::signed(BOB),
+            0,
+            0,
+            5,
+            comment_hash,
+        ));
+
+        assert_eq!(Balances::free_balance(ALICE) - alice_before, 100);
+        assert_eq!(
+            ServiceInvocations::<Test>::get(0).unwrap().status,
+            InvocationStatus::FullyApproved
         );
+        let review = pallet_reputation::Reviews::<Test>::get(BOB, ALICE).unwrap();
+        assert_eq!(review.rating, 5);
+        assert_eq!(review.task_id, 0);
+        assert_eq!(&review.comment[..], comment_hash.as_bytes());
+        assert!(pallet_reputation::Reputations::<Test>::get(ALICE).score > score_before);
     });
 }
 
 #[test]
-fn approve_milestone_fails_already_approved() {
+fn payout_controller_redirects_escrow_after_delay() {
     new_test_ext().execute_with(|| {
         assert_ok!(list_service_default(ALICE));
-        assert_ok!(ServiceMarket::invoke_service(
-            RuntimeOrigin::signed(BOB),
-            0,
-            b"req".to_vec(),
-            Some(vec![
-                MilestoneSpec { pct_of_total: 60 },
-                MilestoneSpec { pct_of_total: 40 },
-            ]),
-            100,
-            100,
-        ));
-        assert_ok!(ServiceMarket::submit_invocation_work(
+        assert_ok!(ServiceMarket::bind_payout_controller(
             RuntimeOrigin::signed(ALICE),
+            DAVE
+        ));
+        // The hot key can neither rebind nor redirect payouts.
+        assert_noop!(
+            ServiceMarket::bind_payout_controller(RuntimeOrigin::signed(ALICE), ALICE),
+            Error::<Test>::PayoutControllerAlreadyBound
+        );
+        assert_noop!(
+            ServiceMarket::schedule_payout_change(RuntimeOrigin::signed(ALICE), 0, Some(ALICE)),
+            Error::<Test>::NotPayoutController
+        );
+
+        assert_ok!(ServiceMarket::schedule_payout_change(
+            RuntimeOrigin::signed(DAVE),
             0,
-            Some(0),
+            Some(CHARLIE)
+        ));
+        assert_noop!(
+            ServiceMarket::apply_payout_change(RuntimeOrigin::signed(BOB), 0),
+            Error::<Test>::PayoutChangeNotDue
+        );
+        System::set_block_number(1 + PayoutChangeDelay::get() as u64);
+        assert_ok!(ServiceMarket::apply_payout_change(
+            RuntimeOrigin::signed(BOB),
+            0
+        ));
+        assert_eq!(
+            ServiceListings::<Test>::get(0).unwrap().payout_account,
+            Some(CHARLIE)
+        );
+
+        assert_ok!(invoke_service_default(BOB, 0));
+        accept_default(0);
+        assert_ok!(ServiceMarket::submit_invocation_work(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            None,
             b"proof".to_vec(),
             ProofType::Hash,
         ));
+        let alice_before = Balances::free_balance(ALICE);
+        let charlie_before = Balances::free_balance(CHARLIE);
         assert_ok!(ServiceMarket::approve_milestone(
             RuntimeOrigin::signed(BOB),
             0,
             0
         ));
-
-        // Second approval on same milestone
-        assert_noop!(
-            ServiceMarket::approve_milestone(RuntimeOrigin::signed(BOB), 0, 0),
-            Error::<Test>::MilestoneAlreadyApproved
-        );
+        assert_eq!(Balances::free_balance(ALICE), alice_before);
+        assert_eq!(Balances::free_balance(CHARLIE) - charlie_before, 100);
     });
 }
 
 #[test]
-fn milestone_percentages_must_sum_to_100() {
+fn provider_capacity_limits_new_invocations() {
     new_test_ext().execute_with(|| {
         assert_ok!(list_service_default(ALICE));
-        assert_noop!(
-            ServiceMarket::invoke_service(
-                RuntimeOrigin::signed(BOB),
-                0,
-                b"req".to_vec(),
-                Some(vec![
-                    MilestoneSpec { pct_of_total: 60 },
-                    MilestoneSpec { pct_of_total: 30 }, // sums to 90, not 100
-                ]),
-                100,
-                100,
-            ),
-            Error::<Test>::MilestonePercentagesInvalid
-        );
-    });
-}
+        assert_ok!(ServiceMarket::set_provider_capacity(
+            RuntimeOrigin::signed(ALICE),
+            Some(1),
+            None
+        ));
 
-#[test]
-fn cancel_invocation_refunds_escrow() {
-    new_test_ext().execute_with(|| {
-        assert_ok!(list_service_default(ALICE));
         assert_ok!(invoke_service_default(BOB, 0));
-
-        let bob_before = Balances::free_balance(BOB);
+        assert_noop!(
+            invoke_service_default(CHARLIE, 0),
+            Error::<Test>::ProviderAtCapacity
+        );
+        // Cancelling frees the slot.
         assert_ok!(ServiceMarket::cancel_invocation(
             RuntimeOrigin::signed(BOB),
             0
         ));
-        let bob_after = Balances::free_balance(BOB);
+        assert_eq!(ProviderOpenInvocations::<Test>::get(ALICE), 0);
+        assert_ok!(invoke_service_default(CHARLIE, 0));
 
-        assert_eq!(bob_after - bob_before, 100); // refunded
+        assert_ok!(ServiceMarket::set_provider_capacity(
+            RuntimeOrigin::signed(ALICE),
+            None,
+            Some(10)
+        ));
+        System::assert_last_event(
+            Event::ProviderCapacitySet {
+                provider: ALICE,
+                max_concurrent_invocations: None,
+                paused_until: Some(10),
+            }
+            .into(),
+        );
+        assert_noop!(
+            invoke_service_default(DAVE, 0),
+            Error::<Test>::ProviderUnavailable
+        );
+        System::set_block_number(10);
+        assert_ok!(invoke_service_default(DAVE, 0));
 
-        let inv = ServiceInvocations::<Test>::get(0).unwrap();
-        assert_eq!(inv.status, InvocationStatus::Cancelled);
+        assert_ok!(ServiceMarket::set_provider_capacity(
+            RuntimeOrigin::signed(ALICE),
+            None,
+            None
+        ));
+        assert!(ProviderCapacities::<Test>::get(ALICE).is_none());
     });
 }
 
 #[test]
-fn cancel_invocation_fails_not_invoker() {
+fn flagged_listing_is_paused_then_taken_down_or_cleared() {
+    use frame_support::traits::Currency;
+
     new_test_ext().execute_with(|| {
+        ProviderBondAmount::set(100);
+        assert_ok!(list_service_default(ALICE));
         assert_ok!(list_service_default(ALICE));
-        assert_ok!(invoke_service_default(BOB, 0));
-
         assert_noop!(
-            ServiceMarket::cancel_invocation(RuntimeOrigin::signed(CHARLIE), 0),
-            Error::<Test>::NotInvoker
+            ServiceMarket::flag_listing(RuntimeOrigin::signed(ALICE), 0, b"spam".to_vec()),
+            Error::<Test>::CannotFlagOwnListing
         );
-    });
-}
 
-#[test]
-fn cancel_invocation_fails_not_pending() {
-    new_test_ext().execute_with(|| {
-        assert_ok!(list_service_default(ALICE));
-        assert_ok!(invoke_service_default(BOB, 0));
+        assert_ok!(ServiceMarket::flag_listing(
+            RuntimeOrigin::signed(BOB),
+            0,
+            b"malware".to_vec()
+        ));
+        assert_noop!(
+            ServiceMarket::flag_listing(RuntimeOrigin::signed(BOB), 0, b"again".to_vec()),
+            Error::<Test>::AlreadyFlagged
+        );
+        assert!(ServiceListings::<Test>::get(0).unwrap().active);
+        assert_ok!(ServiceMarket::flag_listing(
+            RuntimeOrigin::signed(CHARLIE),
+            0,
+            b"phishing".to_vec()
+        ));
+        System::assert_last_event(
+            Event::ListingPausedForReview {
+                listing_id: 0,
+                flags: 2,
+            }
+            .into(),
+        );
+        assert!(!ServiceListings::<Test>::get(0).unwrap().active);
+        assert_noop!(
+            ServiceMarket::delist_service(RuntimeOrigin::signed(ALICE), 0),
+            Error::<Test>::ListingUnderReview
+        );
+        assert_noop!(
+            ServiceMarket::withdraw_provider_bond(RuntimeOrigin::signed(ALICE), 0),
+            Error::<Test>::ListingUnderReview
+        );
 
-        // Submit work → status = WorkSubmitted
-        assert_ok!(ServiceMarket::submit_invocation_work(
-            RuntimeOrigin::signed(ALICE),
+        // Removal slashes the provider bond and refunds the flaggers.
+        assert_ok!(ServiceMarket::resolve_listing_flags(
+            RuntimeOrigin::root(),
             0,
-            None,
-            b"proof".to_vec(),
-            ProofType::Hash,
+            true
         ));
+        assert_eq!(
+            Balances::free_balance(SlashTreasury::get()),
+            ExistentialDeposit::get() + 100
+        );
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert!(ProviderBonds::<Test>::get(0).is_none());
+        assert!(!ServiceListings::<Test>::get(0).unwrap().active);
 
+        // Clearing restores the listing and forfeits the flaggers' bonds.
+        assert_ok!(ServiceMarket::flag_listing(
+            RuntimeOrigin::signed(BOB),
+            1,
+            b"malware".to_vec()
+        ));
+        assert_ok!(ServiceMarket::flag_listing(
+            RuntimeOrigin::signed(DAVE),
+            1,
+            b"malware".to_vec()
+        ));
+        let bob_before = Balances::total_balance(&BOB);
+        assert_ok!(ServiceMarket::resolve_listing_flags(
+            RuntimeOrigin::root(),
+            1,
+            false
+        ));
+        System::assert_last_event(
+            Event::ListingFlagsCleared {
+                listing_id: 1,
+                bonds_forfeited: 2 * FlagBond::get(),
+            }
+            .into(),
+        );
+        assert_eq!(bob_before - Balances::total_balance(&BOB), FlagBond::get());
+        assert!(ServiceListings::<Test>::get(1).unwrap().active);
         assert_noop!(
-            ServiceMarket::cancel_invocation(RuntimeOrigin::signed(BOB), 0),
-            Error::<Test>::CannotCancelActiveInvocation
+            ServiceMarket::resolve_listing_flags(RuntimeOrigin::root(), 1, false),
+            Error::<Test>::NoListingFlags
         );
     });
 }
 
-// =========================================================
-// Expiry tests
-// =========================================================
-
 #[test]
-fn try_expire_invocation_pays_bounty_and_refunds() {
+fn top_listings_rank_by_success_and_recency() {
     new_test_ext().execute_with(|| {
-        assert_ok!(list_service_default(ALICE));
-
-        // Invoke with deadline_blocks = 10
-        assert_ok!(ServiceMarket::invoke_service(
-            RuntimeOrigin::signed(BOB),
-            0,
-            b"req".to_vec(),
-            None,
-            100,
-            10, // deadline_blocks
-        ));
-
-        // Advance past deadline
-        System::set_block_number(50);
+        let complete = |invoker: u64, listing_id: ListingId, invocation_id: InvocationId| {
+            let provider = ServiceListings::<Test>::get(listing_id).unwrap().provider;
+            assert_ok!(invoke_service_default(invoker, listing_id));
+            assert_ok!(ServiceMarket::accept_invocation(
+                RuntimeOrigin::signed(provider),
+                invocation_id
+            ));
+            assert_ok!(ServiceMarket::submit_invocation_work(
+                RuntimeOrigin::signed(provider),
+                invocation_id,
+                None,
+                b"proof".to_vec(),
+                ProofType::Hash,
+            ));
+            assert_ok!(ServiceMarket::approve_milestone(
+                RuntimeOrigin::signed(invoker),
+                invocation_id,
+                0
+            ));
+        };
+        let top = || -> Vec<ListingId> {
+            ServiceMarket::top_listings(b"ai/llm-inference", 10)
+                .into_iter()
+                .map(|(id, _)| id)
+                .collect()
+        };
 
-        let charlie_before = Balances::free_balance(CHARLIE);
-        let bob_before = Balances::free_balance(BOB);
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(list_service_default(BOB));
+        // Unranked until an invocation completes.
+        assert!(top().is_empty());
 
-        assert_ok!(ServiceMarket::try_expire_invocation(
+        // Listing 0 completes one of two invocations, listing 1 its only one.
+        assert_ok!(invoke_service_default(CHARLIE, 0));
+        assert_ok!(ServiceMarket::cancel_invocation(
             RuntimeOrigin::signed(CHARLIE),
             0
         ));
+        complete(CHARLIE, 0, 1);
+        complete(DAVE, 1, 2);
+        assert_eq!(top(), vec![1, 0]);
 
-        let charlie_after = Balances::free_balance(CHARLIE);
-        let bob_after = Balances::free_balance(BOB);
-
-        // Charlie got bounty
-        assert_eq!(charlie_after - charlie_before, 10); // ExpireBounty = 10
-                                                        // Bob got refund minus bounty
-        assert_eq!(bob_after - bob_before, 90);
+        // Listing 1's score decays while listing 0 keeps completing work.
+        System::set_block_number(500);
+        complete(DAVE, 0, 3);
+        assert_eq!(top(), vec![0, 1]);
 
-        let inv = ServiceInvocations::<Test>::get(0).unwrap();
-        assert_eq!(inv.status, InvocationStatus::Expired);
+        assert_ok!(ServiceMarket::delist_service(
+            RuntimeOrigin::signed(ALICE),
+            0
+        ));
+        assert_eq!(top(), vec![1]);
     });
 }
 
 #[test]
-fn try_expire_invocation_fails_deadline_not_passed() {
+fn staked_escrow_reward_is_split_on_settlement() {
     new_test_ext().execute_with(|| {
         assert_ok!(list_service_default(ALICE));
-        assert_ok!(ServiceMarket::invoke_service(
-            RuntimeOrigin::signed(BOB),
+        assert_ok!(invoke_service_default(BOB, 0));
+        assert_noop!(
+            ServiceMarket::stake_escrow(RuntimeOrigin::signed(CHARLIE), 0),
+            Error::<Test>::NotInvoker
+        );
+        assert_ok!(ServiceMarket::stake_escrow(RuntimeOrigin::signed(BOB), 0));
+        assert_noop!(
+            ServiceMarket::stake_escrow(RuntimeOrigin::signed(BOB), 0),
+            Error::<Test>::EscrowAlreadyStaked
+        );
+        let escrow = ServiceMarket::invocation_escrow_account(0);
+        // Everything above the existential deposit is staked.
+        assert_eq!(Balances::reserved_balance(escrow), 99);
+        assert_eq!(YieldPositions::<Test>::get(0), Some(99));
+
+        accept_default(0);
+        assert_ok!(ServiceMarket::submit_invocation_work(
+            RuntimeOrigin::signed(ALICE),
             0,
-            b"req".to_vec(),
             None,
-            100,
-            100, // deadline_blocks = 100
+            b"proof".to_vec(),
+            ProofType::Hash,
+        ));
+        let alice_before = Balances::free_balance(ALICE);
+        let bob_before = Balances::free_balance(BOB);
+        assert_ok!(ServiceMarket::approve_milestone(
+            RuntimeOrigin::signed(BOB),
+            0,
+            0
         ));
 
-        // Current block = 1, deadline = 101 → not expired
-        assert_noop!(
-            ServiceMarket::try_expire_invocation(RuntimeOrigin::signed(CHARLIE), 0),
-            Error::<Test>::DeadlineNotPassed
-        );
+        // A reward of 9 is split 4/5; the full price still reaches the provider.
+        assert!(YieldPositions::<Test>::get(0).is_none());
+        assert_eq!(Balances::reserved_balance(escrow), 0);
+        assert_eq!(Balances::free_balance(BOB) - bob_before, 4);
+        assert_eq!(Balances::free_balance(ALICE) - alice_before, 105);
     });
 }
 
 #[test]
-fn on_initialize_expires_overdue_invocations() {
+fn short_yield_position_pays_out_pro_rata() {
     new_test_ext().execute_with(|| {
         assert_ok!(list_service_default(ALICE));
-        assert_ok!(ServiceMarket::invoke_service(
-            RuntimeOrigin::signed(BOB),
+        assert_ok!(invoke_service_default(BOB, 0));
+        assert_ok!(ServiceMarket::stake_escrow(RuntimeOrigin::signed(BOB), 0));
+        accept_default(0);
+        assert_ok!(ServiceMarket::submit_invocation_work(
+            RuntimeOrigin::signed(ALICE),
             0,
-            b"req".to_vec(),
             None,
-            100,
-            5, // deadline = block 6
+            b"proof".to_vec(),
+            ProofType::Hash,
         ));
 
-        // Advance to block 20
-        System::set_block_number(20);
-        <ServiceMarket as Hooks<u64>>::on_initialize(20u64);
+        // The backend loses 50 of the 99 staked: half the escrow's value.
+        MockEscrowYield::slash_next(50);
+        let alice_before = Balances::free_balance(ALICE);
+        assert_ok!(ServiceMarket::approve_milestone(
+            RuntimeOrigin::signed(BOB),
+            0,
+            0
+        ));
 
-        let inv = ServiceInvocations::<Test>::get(0).unwrap();
-        assert_eq!(inv.status, InvocationStatus::Expired);
+        System::assert_has_event(
+            Event::EscrowYieldShortfall {
+                invocation_id: 0,
+                principal: 99,
+                shortfall: 50,
+            }
+            .into(),
+        );
+        assert_eq!(Balances::free_balance(ALICE) - alice_before, 50);
+        assert!(EscrowRecoveryRates::<Test>::get(0).is_none());
     });
 }
 
-// =========================================================
-// Dispute tests
-// =========================================================
-
 #[test]
-fn raise_dispute_succeeds() {
+fn ecosystem_fund_share_is_skimmed_from_payouts() {
     new_test_ext().execute_with(|| {
+        assert_noop!(
+            ServiceMarket::set_ecosystem_fund_share(
+                RuntimeOrigin::root(),
+                Perbill::from_percent(25)
+            ),
+            Error::<Test>::EcosystemFundShareTooHigh
+        );
+        assert_ok!(ServiceMarket::set_ecosystem_fund_share(
+            RuntimeOrigin::root(),
+            Perbill::from_percent(10)
+        ));
+
         assert_ok!(list_service_default(ALICE));
         assert_ok!(invoke_service_default(BOB, 0));
-
-        assert_ok!(ServiceMarket::raise_dispute(
-            RuntimeOrigin::signed(BOB),
+        accept_default(0);
+        assert_ok!(ServiceMarket::submit_invocation_work(
+            RuntimeOrigin::signed(ALICE),
             0,
-            b"Provider did not deliver".to_vec(),
             None,
+            b"proof".to_vec(),
+            ProofType::Hash,
         ));
 
-        let inv = ServiceInvocations::<Test>::get(0).unwrap();
-        assert_eq!(inv.status, InvocationStatus::Disputed);
+        let alice_before = Balances::free_balance(ALICE);
+        assert_ok!(ServiceMarket::approve_milestone(
+            RuntimeOrigin::signed(BOB),
+            0,
+            0,
+        ));
 
-        let dispute = Disputes::<Test>::get(0).unwrap();
-        assert_eq!(dispute.raised_by, BOB);
-        assert_eq!(dispute.invocation_id, 0);
+        assert_eq!(Balances::free_balance(ALICE) - alice_before, 90);
+        assert_eq!(Balances::free_balance(EcosystemFund::get()), 10);
+        assert_eq!(EcosystemContributions::<Test>::get(ALICE), 10);
     });
 }
 
 #[test]
-fn raise_dispute_fails_not_party() {
+fn referrer_is_paid_from_payouts_up_to_cap() {
     new_test_ext().execute_with(|| {
         assert_ok!(list_service_default(ALICE));
-        assert_ok!(invoke_service_default(BOB, 0));
+        let invoke_referred = |referrer| {
+            ServiceMarket::invoke_service(
+                RuntimeOrigin::signed(BOB),
+                0,
+                b"req".to_vec(),
+                None,
+                100,
+                100,
+                None,
+                false,
+                Some(referrer),
+            )
+        };
+        assert_noop!(
+            invoke_referred(CHARLIE),
+            Error::<Test>::ReferralsNotAccepted
+        );
 
         assert_noop!(
-            ServiceMarket::raise_dispute(
-                RuntimeOrigin::signed(CHARLIE), // third party
+            ServiceMarket::set_referral_terms(
+                RuntimeOrigin::signed(ALICE),
                 0,
-                b"reason".to_vec(),
-                None,
+                Some(ReferralTerms {
+                    fee: Perbill::from_percent(20),
+                    cap: 5,
+                }),
             ),
-            Error::<Test>::NotPartyToInvocation
+            Error::<Test>::ReferralFeeTooHigh
         );
-    });
-}
-
+        assert_ok!(ServiceMarket::set_referral_terms(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            Some(ReferralTerms {
+                fee: Perbill::from_percent(10),
+                cap: 5,
+            }),
+        ));
+        assert_noop!(invoke_referred(BOB), Error::<Test>::SelfReferral);
+        assert_noop!(invoke_referred(ALICE), Error::<Test>::SelfReferral);
+        assert_ok!(invoke_referred(CHARLIE));
+
+        accept_default(0);
+        assert_ok!(ServiceMarket::submit_invocation_work(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            None,
+            b"proof".to_vec(),
+            ProofType::Hash,
+        ));
+        let alice_before = Balances::free_balance(ALICE);
+        let charlie_before = Balances::free_balance(CHARLIE);
+        assert_ok!(ServiceMarket::approve_milestone(
+            RuntimeOrigin::signed(BOB),
+            0,
+            0,
+        ));
+
+        // 10% of 100 is 10, capped at 5.
+        assert_eq!(Balances::free_balance(CHARLIE) - charlie_before, 5);
+        assert_eq!(Balances::free_balance(ALICE) - alice_before, 95);
+        assert!(InvocationReferrals::<Test>::get(0).is_none());
+    });
+}
+
+#[test]
+fn funding_round_matches_quadratically() {
+    new_test_ext().execute_with(|| {
+        let fund = EcosystemFund::get();
+        assert_ok!(Balances::transfer_allow_death(
+            RuntimeOrigin::signed(ALICE),
+            fund,
+            10
+        ));
+
+        assert_noop!(
+            ServiceMarket::open_funding_round(RuntimeOrigin::root(), vec![CHARLIE, CHARLIE]),
+            Error::<Test>::InvalidRoundProjects
+        );
+        assert_ok!(ServiceMarket::open_funding_round(
+            RuntimeOrigin::root(),
+            vec![CHARLIE, DAVE]
+        ));
+        assert_noop!(
+            ServiceMarket::contribute_to_project(RuntimeOrigin::signed(BOB), ALICE, 9),
+            Error::<Test>::NotRoundProject
+        );
+
+        // CHARLIE: two backers of 9 (sqrt sum 6); DAVE: one backer of 36 (sqrt sum 6).
+        assert_ok!(ServiceMarket::contribute_to_project(
+            RuntimeOrigin::signed(ALICE),
+            CHARLIE,
+            9
+        ));
+        assert_ok!(ServiceMarket::contribute_to_project(
+            RuntimeOrigin::signed(BOB),
+            CHARLIE,
+            9
+        ));
+        assert_ok!(ServiceMarket::contribute_to_project(
+            RuntimeOrigin::signed(BOB),
+            DAVE,
+            36
+        ));
+        assert_eq!(RoundTallies::<Test>::get(0, CHARLIE).sqrt_sum, 6);
+        assert_eq!(RoundTallies::<Test>::get(0, DAVE).sqrt_sum, 6);
+
+        assert_noop!(
+            ServiceMarket::finalize_funding_round(RuntimeOrigin::signed(BOB)),
+            Error::<Test>::FundingRoundNotEnded
+        );
+        System::set_block_number(11);
+        let charlie_before = Balances::free_balance(CHARLIE);
+        let dave_before = Balances::free_balance(DAVE);
+        assert_ok!(ServiceMarket::finalize_funding_round(
+            RuntimeOrigin::signed(BOB)
+        ));
+
+        // Pool of 9 (fund balance less ED) split evenly despite 18 vs 36 contributed.
+        assert_eq!(Balances::free_balance(CHARLIE) - charlie_before, 4);
+        assert_eq!(Balances::free_balance(DAVE) - dave_before, 4);
+        assert!(ActiveFundingRound::<Test>::get().is_none());
+        System::assert_last_event(
+            Event::FundingRoundFinalized {
+                round_id: 0,
+                matched: 8,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn liveness_challenge_answered_in_time_pays_provider() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_noop!(
+            ServiceMarket::challenge_liveness(RuntimeOrigin::signed(BOB), 0),
+            Error::<Test>::NoLivenessBond
+        );
+        assert_ok!(ServiceMarket::post_liveness_bond(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            200
+        ));
+        assert_ok!(ServiceMarket::challenge_liveness(
+            RuntimeOrigin::signed(BOB),
+            0
+        ));
+        assert_eq!(Balances::reserved_balance(BOB), 5);
+        assert_noop!(
+            ServiceMarket::withdraw_liveness_bond(RuntimeOrigin::signed(ALICE), 0),
+            Error::<Test>::LivenessChallengeOpen
+        );
+
+        // Deadline is block 1 + sla_response_blocks (10).
+        System::set_block_number(11);
+        let alice_before = Balances::free_balance(ALICE);
+        assert_ok!(ServiceMarket::respond_to_liveness_challenge(
+            RuntimeOrigin::signed(ALICE),
+            0
+        ));
+        assert_eq!(Balances::free_balance(ALICE) - alice_before, 5);
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+
+        assert_ok!(ServiceMarket::withdraw_liveness_bond(
+            RuntimeOrigin::signed(ALICE),
+            0
+        ));
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+    });
+}
+
+#[test]
+fn unanswered_liveness_challenge_slashes_bond() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(ServiceMarket::post_liveness_bond(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            200
+        ));
+        assert_ok!(ServiceMarket::challenge_liveness(
+            RuntimeOrigin::signed(BOB),
+            0
+        ));
+        assert_noop!(
+            ServiceMarket::resolve_liveness_challenge(RuntimeOrigin::signed(CHARLIE), 0),
+            Error::<Test>::LivenessDeadlineNotReached
+        );
+
+        System::set_block_number(12);
+        assert_noop!(
+            ServiceMarket::respond_to_liveness_challenge(RuntimeOrigin::signed(ALICE), 0),
+            Error::<Test>::LivenessDeadlinePassed
+        );
+        let bob_before = Balances::free_balance(BOB);
+        assert_ok!(ServiceMarket::resolve_liveness_challenge(
+            RuntimeOrigin::signed(CHARLIE),
+            0
+        ));
+
+        // Fee returned plus half the bond.
+        assert_eq!(Balances::free_balance(BOB) - bob_before, 105);
+        assert_eq!(LivenessBonds::<Test>::get(0), Some(100));
+        assert_eq!(Balances::reserved_balance(ALICE), 100);
+        assert!(LivenessChallenges::<Test>::get(0).is_none());
+    });
+}
+
+#[test]
+fn approve_milestone_partial_multi_milestone() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+
+        // Invoke with 2 milestones: 60% + 40%
+        assert_ok!(ServiceMarket::invoke_service(
+            RuntimeOrigin::signed(BOB),
+            0,
+            b"requirements".to_vec(),
+            Some(vec![
+                MilestoneSpec { pct_of_total: 60 },
+                MilestoneSpec { pct_of_total: 40 },
+            ]),
+            100,
+            100,
+            None,
+            false,
+            None,
+        ));
+
+        // Submit work for milestone 0
+        accept_default(0);
+        assert_ok!(ServiceMarket::submit_invocation_work(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            Some(0),
+            b"proof1".to_vec(),
+            ProofType::Hash,
+        ));
+
+        let alice_before = Balances::free_balance(ALICE);
+
+        // Approve milestone 0 → 60 tokens released
+        assert_ok!(ServiceMarket::approve_milestone(
+            RuntimeOrigin::signed(BOB),
+            0,
+            0,
+        ));
+
+        let alice_after = Balances::free_balance(ALICE);
+        assert_eq!(alice_after - alice_before, 60);
+
+        // Invocation should not be fully approved yet
+        let inv = ServiceInvocations::<Test>::get(0).unwrap();
+        assert_ne!(inv.status, InvocationStatus::FullyApproved);
+    });
+}
+
+#[test]
+fn approve_all_milestones_marks_fully_approved() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+
+        assert_ok!(ServiceMarket::invoke_service(
+            RuntimeOrigin::signed(BOB),
+            0,
+            b"requirements".to_vec(),
+            Some(vec![
+                MilestoneSpec { pct_of_total: 50 },
+                MilestoneSpec { pct_of_total: 50 },
+            ]),
+            100,
+            100,
+            None,
+            false,
+            None,
+        ));
+
+        // Submit and approve milestone 0
+        accept_default(0);
+        assert_ok!(ServiceMarket::submit_invocation_work(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            Some(0),
+            b"proof1".to_vec(),
+            ProofType::Hash,
+        ));
+        assert_ok!(ServiceMarket::approve_milestone(
+            RuntimeOrigin::signed(BOB),
+            0,
+            0
+        ));
+
+        // Submit and approve milestone 1
+        assert_ok!(ServiceMarket::submit_invocation_work(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            Some(1),
+            b"proof2".to_vec(),
+            ProofType::Hash,
+        ));
+        assert_ok!(ServiceMarket::approve_milestone(
+            RuntimeOrigin::signed(BOB),
+            0,
+            1
+        ));
+
+        let inv = ServiceInvocations::<Test>::get(0).unwrap();
+        assert_eq!(inv.status, InvocationStatus::FullyApproved);
+    });
+}
+
+#[test]
+fn approve_milestones_releases_several_at_once() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(ServiceMarket::invoke_service(
+            RuntimeOrigin::signed(BOB),
+            0,
+            b"requirements".to_vec(),
+            Some(vec![
+                MilestoneSpec { pct_of_total: 30 },
+                MilestoneSpec { pct_of_total: 70 },
+            ]),
+            100,
+            100,
+            None,
+            false,
+            None,
+        ));
+        accept_default(0);
+        for index in 0..2 {
+            assert_ok!(ServiceMarket::submit_invocation_work(
+                RuntimeOrigin::signed(ALICE),
+                0,
+                Some(index),
+                b"proof".to_vec(),
+                ProofType::Hash,
+            ));
+        }
+
+        assert_noop!(
+            ServiceMarket::approve_milestones(RuntimeOrigin::signed(BOB), 0, vec![]),
+            Error::<Test>::EmptyBatch
+        );
+        // One bad index rolls back the whole batch.
+        assert_noop!(
+            ServiceMarket::approve_milestones(RuntimeOrigin::signed(BOB), 0, vec![0, 5]),
+            Error::<Test>::MilestoneIndexOutOfBounds
+        );
+
+        let alice_before = Balances::free_balance(ALICE);
+        assert_ok!(ServiceMarket::approve_milestones(
+            RuntimeOrigin::signed(BOB),
+            0,
+            vec![0, 1]
+        ));
+        assert_eq!(Balances::free_balance(ALICE) - alice_before, 100);
+        assert_eq!(
+            ServiceInvocations::<Test>::get(0).unwrap().status,
+            InvocationStatus::FullyApproved
+        );
+        System::assert_last_event(
+            Event::MilestonesApproved {
+                invocation_id: 0,
+                count: 2,
+                amount_released: 100,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn batch_cancel_refunds_every_invocation() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        let bob_before = Balances::free_balance(BOB);
+        assert_ok!(invoke_service_default(BOB, 0));
+        assert_ok!(invoke_service_default(BOB, 0));
+        assert_ok!(invoke_service_default(CHARLIE, 0));
+
+        assert_noop!(
+            ServiceMarket::batch_cancel(RuntimeOrigin::signed(BOB), vec![0, 2]),
+            Error::<Test>::NotInvoker
+        );
+
+        assert_ok!(ServiceMarket::batch_cancel(
+            RuntimeOrigin::signed(BOB),
+            vec![0, 1]
+        ));
+        assert_eq!(Balances::free_balance(BOB), bob_before);
+        for id in 0..2 {
+            assert_eq!(
+                ServiceInvocations::<Test>::get(id).unwrap().status,
+                InvocationStatus::Cancelled
+            );
+        }
+        System::assert_last_event(
+            Event::InvocationsCancelled {
+                invoker: BOB,
+                count: 2,
+                amount_refunded: 200,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn approve_milestone_fails_not_invoker() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(invoke_service_default(BOB, 0));
+        accept_default(0);
+        assert_ok!(ServiceMarket::submit_invocation_work(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            None,
+            b"proof".to_vec(),
+            ProofType::Hash,
+        ));
+        assert_noop!(
+            ServiceMarket::approve_milestone(
+                RuntimeOrigin::signed(CHARLIE), // not invoker
+                0,
+                0,
+            ),
+            Error::<Test>::NotInvoker
+        );
+    });
+}
+
+#[test]
+fn approve_milestone_fails_already_approved() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(ServiceMarket::invoke_service(
+            RuntimeOrigin::signed(BOB),
+            0,
+            b"req".to_vec(),
+            Some(vec![
+                MilestoneSpec { pct_of_total: 60 },
+                MilestoneSpec { pct_of_total: 40 },
+            ]),
+            100,
+            100,
+            None,
+            false,
+            None,
+        ));
+        accept_default(0);
+        assert_ok!(ServiceMarket::submit_invocation_work(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            Some(0),
+            b"proof".to_vec(),
+            ProofType::Hash,
+        ));
+        assert_ok!(ServiceMarket::approve_milestone(
+            RuntimeOrigin::signed(BOB),
+            0,
+            0
+        ));
+
+        // Second approval on same milestone
+        assert_noop!(
+            ServiceMarket::approve_milestone(RuntimeOrigin::signed(BOB), 0, 0),
+            Error::<Test>::MilestoneAlreadyApproved
+        );
+    });
+}
+
+#[test]
+fn milestone_percentages_must_sum_to_100() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_noop!(
+            ServiceMarket::invoke_service(
+                RuntimeOrigin::signed(BOB),
+                0,
+                b"req".to_vec(),
+                Some(vec![
+                    MilestoneSpec { pct_of_total: 60 },
+                    MilestoneSpec { pct_of_total: 30 }, // sums to 90, not 100
+                ]),
+                100,
+                100,
+                None,
+                false,
+                None,
+            ),
+            Error::<Test>::MilestonePercentagesInvalid
+        );
+    });
+}
+
+#[test]
+fn cancel_invocation_refunds_escrow() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(invoke_service_default(BOB, 0));
+
+        let bob_before = Balances::free_balance(BOB);
+        assert_ok!(ServiceMarket::cancel_invocation(
+            RuntimeOrigin::signed(BOB),
+            0
+        ));
+        let bob_after = Balances::free_balance(BOB);
+
+        assert_eq!(bob_after - bob_before, 100); // refunded
+
+        let inv = ServiceInvocations::<Test>::get(0).unwrap();
+        assert_eq!(inv.status, InvocationStatus::Cancelled);
+    });
+}
+
+#[test]
+fn settled_prices_feed_the_tag_price_index() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(ServiceMarket::list_service(
+            RuntimeOrigin::signed(ALICE),
+            b"AI Inference Service".to_vec(),
+            b"Fast LLM inference at scale".to_vec(),
+            vec![b"ai/llm-inference".to_vec()],
+            10,
+            1_000,
+            PaymentMode::Escrow,
+            10,
+            50,
+            0,
+            None,
+            false,
+        ));
+        let settle = |id: InvocationId, price: u64| {
+            assert_ok!(ServiceMarket::invoke_service(
+                RuntimeOrigin::signed(BOB),
+                0,
+                b"req".to_vec(),
+                None,
+                price,
+                100,
+                None,
+                false,
+                None,
+            ));
+            accept_default(id);
+            assert_ok!(ServiceMarket::submit_invocation_work(
+                RuntimeOrigin::signed(ALICE),
+                id,
+                None,
+                b"proof".to_vec(),
+                ProofType::Hash,
+            ));
+            assert_ok!(ServiceMarket::approve_milestone(
+                RuntimeOrigin::signed(BOB),
+                id,
+                0
+            ));
+        };
+
+        for (id, price) in [(0, 30), (1, 40), (2, 500)] {
+            settle(id, price);
+        }
+        // The median (40) falls in the [40, 80) bucket, reported as its midpoint.
+        assert_eq!(
+            ServiceMarket::price_stats(b"ai/llm-inference", 1),
+            Some(runtime_api::PriceStats {
+                count: 3,
+                min: 30,
+                median: 60,
+                max: 500,
+            })
+        );
+        assert_eq!(ServiceMarket::price_stats(b"infra/storage", 1), None);
+
+        // Era 3 drops era 0 from the three-era window.
+        System::set_block_number(350);
+        settle(3, 200);
+        let tag: BoundedVec<u8, MaxTagLength> = b"ai/llm-inference".to_vec().try_into().unwrap();
+        assert!(TagPriceHistory::<Test>::get(&tag, 0).is_none());
+        assert_eq!(
+            ServiceMarket::price_stats(b"ai/llm-inference", 10),
+            Some(runtime_api::PriceStats {
+                count: 1,
+                min: 200,
+                median: 200,
+                max: 200,
+            })
+        );
+    });
+}
+
+#[test]
+fn discovery_queries_page_through_matching_listings() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(ServiceMarket::list_service(
+            RuntimeOrigin::signed(CHARLIE),
+            b"GPU inference".to_vec(),
+            b"desc".to_vec(),
+            vec![b"ai/llm-inference".to_vec(), b"infra/gpu".to_vec()],
+            300,
+            300,
+            PaymentMode::Escrow,
+            10,
+            50,
+            0,
+            None,
+            false,
+        ));
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(ServiceMarket::delist_service(
+            RuntimeOrigin::signed(ALICE),
+            2
+        ));
+
+        let ids = |page: Vec<(ListingId, runtime_api::ListingSummary<u64, u64>)>| {
+            page.into_iter().map(|(id, _)| id).collect::<Vec<_>>()
+        };
+        assert_eq!(
+            ids(ServiceMarket::listings_by_tag(
+                b"ai/llm-inference",
+                true,
+                0,
+                10
+            )),
+            vec![0, 1]
+        );
+        assert_eq!(
+            ids(ServiceMarket::listings_by_tag(
+                b"ai/llm-inference",
+                true,
+                1,
+                1
+            )),
+            vec![1]
+        );
+        assert_eq!(
+            ids(ServiceMarket::listings_by_provider(&ALICE, 0, 10)),
+            vec![0, 2]
+        );
+
+        let tags = vec![b"ai/llm-inference".to_vec(), b"infra/gpu".to_vec()];
+        assert_eq!(
+            ids(ServiceMarket::search(&tags, None, None, 0, 10)),
+            vec![1]
+        );
+        assert!(ServiceMarket::search(&tags, None, Some(200), 0, 10).is_empty());
+        assert!(ServiceMarket::search(&tags, Some(9000), None, 0, 10).is_empty());
+        let mut all = ids(ServiceMarket::search(&[], Some(5000), Some(300), 0, 10));
+        all.sort();
+        assert_eq!(all, vec![0, 1]);
+    });
+}
+
+#[test]
+fn invocation_cap_scales_with_reputation_and_completion() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        let params = InvocationCapParams {
+            base: 1,
+            per_tier: 1,
+            tier_width: 2_500,
+            min_history: 2,
+        };
+        assert_ok!(ServiceMarket::set_invocation_cap_params(
+            RuntimeOrigin::root(),
+            Some(params.clone())
+        ));
+
+        // Reputation 5000 is tier 2: base 1 + 2.
+        assert_eq!(ServiceMarket::invocation_cap(&BOB), 3);
+        for _ in 0..3 {
+            assert_ok!(invoke_service_default(BOB, 0));
+        }
+        assert_noop!(
+            invoke_service_default(BOB, 0),
+            Error::<Test>::TooManyActiveInvocations
+        );
+
+        // Two abandoned invocations: completion ratio 0 drops the cap to base.
+        for id in 0..2 {
+            assert_ok!(ServiceMarket::cancel_invocation(
+                RuntimeOrigin::signed(BOB),
+                id
+            ));
+        }
+        assert_eq!(
+            InvokerHistories::<Test>::get(BOB),
+            InvokerHistory {
+                finished: 2,
+                completed: 0
+            }
+        );
+        let info = ServiceMarket::invocation_cap_info(&BOB);
+        assert_eq!((info.cap, info.active), (1, 1));
+        assert_eq!(info.params, Some(params));
+        assert_noop!(
+            invoke_service_default(BOB, 0),
+            Error::<Test>::TooManyActiveInvocations
+        );
+
+        // Clearing the formula restores the flat maximum.
+        assert_ok!(ServiceMarket::set_invocation_cap_params(
+            RuntimeOrigin::root(),
+            None
+        ));
+        assert_eq!(ServiceMarket::invocation_cap(&BOB), 20);
+    });
+}
+
+#[test]
+fn cancel_invocation_fails_not_invoker() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(invoke_service_default(BOB, 0));
+
+        assert_noop!(
+            ServiceMarket::cancel_invocation(RuntimeOrigin::signed(CHARLIE), 0),
+            Error::<Test>::NotInvoker
+        );
+    });
+}
+
+#[test]
+fn cancel_invocation_fails_not_pending() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(invoke_service_default(BOB, 0));
+
+        // Submit work → status = WorkSubmitted
+        accept_default(0);
+        assert_ok!(ServiceMarket::submit_invocation_work(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            None,
+            b"proof".to_vec(),
+            ProofType::Hash,
+        ));
+
+        assert_noop!(
+            ServiceMarket::cancel_invocation(RuntimeOrigin::signed(BOB), 0),
+            Error::<Test>::CannotCancelActiveInvocation
+        );
+    });
+}
+
+// =========================================================
+// Expiry tests
+// =========================================================
+
+#[test]
+fn try_expire_invocation_pays_bounty_and_refunds() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+
+        // Invoke with deadline_blocks = 10
+        assert_ok!(ServiceMarket::invoke_service(
+            RuntimeOrigin::signed(BOB),
+            0,
+            b"req".to_vec(),
+            None,
+            100,
+            10, // deadline_blocks
+            None,
+            false,
+            None,
+        ));
+
+        // Advance past deadline
+        System::set_block_number(50);
+
+        let charlie_before = Balances::free_balance(CHARLIE);
+        let bob_before = Balances::free_balance(BOB);
+
+        assert_ok!(ServiceMarket::try_expire_invocation(
+            RuntimeOrigin::signed(CHARLIE),
+            0
+        ));
+
+        let charlie_after = Balances::free_balance(CHARLIE);
+        let bob_after = Balances::free_balance(BOB);
+
+        // Charlie got 10% of the 100 escrow, Bob the rest
+        assert_eq!(charlie_after - charlie_before, 10);
+        assert_eq!(bob_after - bob_before, 90);
+
+        let inv = ServiceInvocations::<Test>::get(0).unwrap();
+        assert_eq!(inv.status, InvocationStatus::Expired);
+    });
+}
+
+#[test]
+fn expired_invocation_slashes_provider_bond() {
+    new_test_ext().execute_with(|| {
+        ProviderBondAmount::set(100);
+        assert_ok!(list_service_default(ALICE));
+        assert_eq!(Balances::reserved_balance(ALICE), 100);
+        assert_eq!(ProviderBonds::<Test>::get(0), Some(100));
+
+        assert_ok!(ServiceMarket::invoke_service(
+            RuntimeOrigin::signed(BOB),
+            0,
+            b"req".to_vec(),
+            None,
+            100,
+            10,
+            None,
+            false,
+            None,
+        ));
+        System::set_block_number(50);
+        let bob_before = Balances::free_balance(BOB);
+        assert_ok!(ServiceMarket::try_expire_invocation(
+            RuntimeOrigin::signed(CHARLIE),
+            0
+        ));
+
+        // Half the bond is slashed: 60% to the invoker, 40% to the treasury.
+        assert_eq!(Balances::free_balance(BOB) - bob_before, 90 + 30);
+        assert_eq!(
+            Balances::free_balance(SlashTreasury::get()),
+            ExistentialDeposit::get() + 20
+        );
+        assert_eq!(ProviderBonds::<Test>::get(0), Some(50));
+        System::assert_has_event(
+            Event::ProviderBondSlashed {
+                listing_id: 0,
+                invocation_id: 0,
+                to_invoker: 30,
+                to_treasury: 20,
+            }
+            .into(),
+        );
+
+        assert_noop!(
+            ServiceMarket::withdraw_provider_bond(RuntimeOrigin::signed(ALICE), 0),
+            Error::<Test>::ListingStillActive
+        );
+        assert_ok!(ServiceMarket::delist_service(
+            RuntimeOrigin::signed(ALICE),
+            0
+        ));
+        assert_ok!(ServiceMarket::withdraw_provider_bond(
+            RuntimeOrigin::signed(ALICE),
+            0
+        ));
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+        assert!(ProviderBonds::<Test>::get(0).is_none());
+    });
+}
+
+#[test]
+fn try_expire_invocation_fails_deadline_not_passed() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(ServiceMarket::invoke_service(
+            RuntimeOrigin::signed(BOB),
+            0,
+            b"req".to_vec(),
+            None,
+            100,
+            100, // deadline_blocks = 100
+            None,
+            false,
+            None,
+        ));
+
+        // Current block = 1, deadline = 101 → not expired
+        assert_noop!(
+            ServiceMarket::try_expire_invocation(RuntimeOrigin::signed(CHARLIE), 0),
+            Error::<Test>::DeadlineNotPassed
+        );
+    });
+}
+
+#[test]
+fn on_initialize_expires_overdue_invocations() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(ServiceMarket::invoke_service(
+            RuntimeOrigin::signed(BOB),
+            0,
+            b"req".to_vec(),
+            None,
+            100,
+            5, // deadline = block 6
+            None,
+            false,
+            None,
+        ));
+
+        // Advance to block 20
+        System::set_block_number(20);
+        let escrow = ServiceMarket::invocation_escrow_account(0);
+        let remaining = Balances::free_balance(escrow) - 1; // escrow is kept alive
+        let bob_before = Balances::free_balance(BOB);
+        run_sweeps_to(20);
+
+        let inv = ServiceInvocations::<Test>::get(0).unwrap();
+        assert_eq!(inv.status, InvocationStatus::Expired);
+
+        // Same bounty as a manual expiry, paid to the ecosystem fund.
+        let bounty = ServiceMarket::expire_bounty(remaining);
+        assert_eq!(Balances::free_balance(EcosystemFund::get()), bounty);
+        assert_eq!(Balances::free_balance(BOB) - bob_before, remaining - bounty);
+    });
+}
+
+#[test]
+fn expire_bounty_is_a_clamped_share_of_remaining_escrow() {
+    new_test_ext().execute_with(|| {
+        // 10%, at least 5, at most 50, never more than the escrow.
+        assert_eq!(ServiceMarket::expire_bounty(200), 20);
+        assert_eq!(ServiceMarket::expire_bounty(20), 5);
+        assert_eq!(ServiceMarket::expire_bounty(3), 3);
+        assert_eq!(ServiceMarket::expire_bounty(10_000), 50);
+    });
+}
+
+#[test]
+fn sweeps_defer_what_the_budget_cannot_pay_for() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(invoke_service_default(BOB, 0));
+        assert_ok!(invoke_service_default(CHARLIE, 0));
+        let deadline = ServiceInvocations::<Test>::get(1).unwrap().deadline;
+        let expired = || {
+            ServiceInvocations::<Test>::iter_values()
+                .filter(|inv| inv.status == InvocationStatus::Expired)
+                .count()
+        };
+
+        run_sweeps_to(deadline);
+        // Budget for a single item: one expires now, the other next block.
+        let usage = <ServiceMarket as BudgetedHook<u64>>::run(deadline + 1, SWEEP_ITEM_WEIGHT);
+        assert_eq!(usage.used, SWEEP_ITEM_WEIGHT);
+        assert!(usage.deferred);
+        assert_eq!(expired(), 1);
+
+        let usage = <ServiceMarket as BudgetedHook<u64>>::run(deadline + 2, Weight::MAX);
+        assert!(!usage.deferred);
+        assert_eq!(expired(), 2);
+    });
+}
+
+// =========================================================
+// Dispute tests
+// =========================================================
+
+#[test]
+fn raise_dispute_succeeds() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(invoke_service_default(BOB, 0));
+
+        assert_ok!(ServiceMarket::raise_dispute(
+            RuntimeOrigin::signed(BOB),
+            0,
+            b"Provider did not deliver".to_vec(),
+            None,
+        ));
+
+        let inv = ServiceInvocations::<Test>::get(0).unwrap();
+        assert_eq!(inv.status, InvocationStatus::Disputed);
+
+        let dispute = Disputes::<Test>::get(0).unwrap();
+        assert_eq!(dispute.raised_by, BOB);
+        assert_eq!(dispute.invocation_id, 0);
+    });
+}
+
+#[test]
+fn raise_dispute_fails_not_party() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(invoke_service_default(BOB, 0));
+
+        assert_noop!(
+            ServiceMarket::raise_dispute(
+                RuntimeOrigin::signed(CHARLIE), // third party
+                0,
+                b"reason".to_vec(),
+                None,
+            ),
+            Error::<Test>::NotPartyToInvocation
+        );
+    });
+}
+
+#[test]
+fn raise_dispute_fails_already_disputed() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(invoke_service_default(BOB, 0));
+        assert_ok!(ServiceMarket::raise_dispute(
+            RuntimeOrigin::signed(BOB),
+            0,
+            b"reason".to_vec(),
+            None,
+        ));
+
+        assert_noop!(
+            ServiceMarket::raise_dispute(
+                RuntimeOrigin::signed(ALICE),
+                0,
+                b"counter".to_vec(),
+                None,
+            ),
+            Error::<Test>::InvocationAlreadyDisputed
+        );
+    });
+}
+
+#[test]
+fn provider_can_raise_dispute() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(invoke_service_default(BOB, 0));
+
+        assert_ok!(ServiceMarket::raise_dispute(
+            RuntimeOrigin::signed(ALICE), // provider raises dispute
+            0,
+            b"Invoker not cooperating".to_vec(),
+            None,
+        ));
+
+        let inv = ServiceInvocations::<Test>::get(0).unwrap();
+        assert_eq!(inv.status, InvocationStatus::Disputed);
+    });
+}
+
+#[test]
+fn raise_dispute_assigns_tier_from_invocation_price() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(invoke_service_default(BOB, 0));
+        assert_ok!(ServiceMarket::list_service(
+            RuntimeOrigin::signed(ALICE),
+            b"Large Batch Service".to_vec(),
+            b"High-value batch jobs".to_vec(),
+            vec![b"ai/batch".to_vec()],
+            1_000,
+            2_000,
+            PaymentMode::Escrow,
+            10,
+            50,
+            0,
+            None,
+            false,
+        ));
+        assert_ok!(ServiceMarket::invoke_service(
+            RuntimeOrigin::signed(BOB),
+            1,
+            b"req".to_vec(),
+            None,
+            1_500,
+            100,
+            None,
+            false,
+            None,
+        ));
+
+        System::set_block_number(5);
+        assert_ok!(ServiceMarket::raise_dispute(
+            RuntimeOrigin::signed(BOB),
+            0,
+            b"reason".to_vec(),
+            None,
+        ));
+        assert_ok!(ServiceMarket::raise_dispute(
+            RuntimeOrigin::signed(BOB),
+            1,
+            b"reason".to_vec(),
+            None,
+        ));
+
+        let standard = Disputes::<Test>::get(0).unwrap();
+        assert_eq!(standard.tier, DisputeTier::Standard);
+        assert_eq!(standard.evidence_deadline, 55);
+
+        let priority = Disputes::<Test>::get(1).unwrap();
+        assert_eq!(priority.tier, DisputeTier::Priority);
+        assert_eq!(priority.evidence_deadline, 15);
+        assert_eq!(
+            ServiceMarket::dispute_track(priority.tier).juror_panel_size,
+            7
+        );
+        System::assert_last_event(
+            Event::DisputeRaised {
+                invocation_id: 1,
+                dispute_id: 1,
+                raised_by: BOB,
+                tier: DisputeTier::Priority,
+                evidence_deadline: 15,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn submit_dispute_evidence_respects_window() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(invoke_service_default(BOB, 0));
+        assert_ok!(ServiceMarket::raise_dispute(
+            RuntimeOrigin::signed(BOB),
+            0,
+            b"reason".to_vec(),
+            None,
+        ));
+
+        assert_noop!(
+            ServiceMarket::submit_dispute_evidence(
+                RuntimeOrigin::signed(CHARLIE),
+                0,
+                b"bafy-charlie".to_vec()
+            ),
+            Error::<Test>::NotPartyToInvocation
+        );
+        assert_ok!(ServiceMarket::submit_dispute_evidence(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            b"bafy-alice".to_vec()
+        ));
+        assert_eq!(
+            Disputes::<Test>::get(0)
+                .unwrap()
+                .evidence_cid
+                .unwrap()
+                .to_vec(),
+            b"bafy-alice".to_vec()
+        );
+
+        // Raised at block 1 on the standard track (50-block window).
+        System::set_block_number(52);
+        assert_noop!(
+            ServiceMarket::submit_dispute_evidence(
+                RuntimeOrigin::signed(BOB),
+                0,
+                b"bafy-late".to_vec()
+            ),
+            Error::<Test>::EvidenceWindowClosed
+        );
+    });
+}
+
+#[test]
+fn resolve_dispute_governance_transfers_to_winner() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(invoke_service_default(BOB, 0)); // BOB locked 100 in escrow
+        assert_ok!(ServiceMarket::raise_dispute(
+            RuntimeOrigin::signed(BOB),
+            0,
+            b"reason".to_vec(),
+            None,
+        ));
+
+        let alice_before = Balances::free_balance(ALICE);
+
+        assert_ok!(ServiceMarket::resolve_dispute_governance(
+            RuntimeOrigin::root(),
+            0,
+            ALICE, // provider wins
+            None,
+        ));
+
+        let alice_after = Balances::free_balance(ALICE);
+        // Alice received the escrow (minus existential deposit remainder)
+        assert!(alice_after > alice_before);
+
+        let dispute = Disputes::<Test>::get(0).unwrap();
+        assert_eq!(dispute.status, DisputeStatus::Resolved);
+        assert_eq!(dispute.winner, Some(ALICE));
+    });
+}
+
+#[test]
+fn unanswered_dispute_resolves_by_default_judgment() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        for id in 0..3 {
+            assert_ok!(invoke_service_default(BOB, 0));
+            assert_ok!(ServiceMarket::raise_dispute(
+                RuntimeOrigin::signed(BOB),
+                id,
+                b"no delivery".to_vec(),
+                None,
+            ));
+        }
+        // Dispute 1: the provider responds. Dispute 2: governance escalates.
+        assert_ok!(ServiceMarket::submit_dispute_evidence(
+            RuntimeOrigin::signed(ALICE),
+            1,
+            b"cid".to_vec()
+        ));
+        assert_noop!(
+            ServiceMarket::escalate_dispute(RuntimeOrigin::signed(BOB), 2),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_ok!(ServiceMarket::escalate_dispute(RuntimeOrigin::root(), 2));
+
+        // Raised at block 1 with a 20-block response window.
+        run_sweeps_to(21);
+        assert_eq!(
+            Disputes::<Test>::get(0).unwrap().status,
+            DisputeStatus::Open
+        );
+
+        let bob_before = Balances::free_balance(BOB);
+        run_sweeps_to(22);
+        let dispute = Disputes::<Test>::get(0).unwrap();
+        assert_eq!(dispute.status, DisputeStatus::Resolved);
+        assert_eq!(dispute.winner, Some(BOB));
+        assert!(Balances::free_balance(BOB) > bob_before);
+        System::assert_has_event(
+            Event::DisputeDefaultJudgment {
+                dispute_id: 0,
+                winner: BOB,
+                amount: Balances::free_balance(BOB) - bob_before,
+            }
+            .into(),
+        );
+
+        assert_eq!(
+            Disputes::<Test>::get(1).unwrap().status,
+            DisputeStatus::Open
+        );
+        assert_eq!(
+            Disputes::<Test>::get(2).unwrap().status,
+            DisputeStatus::Escalated
+        );
+        assert!(DefaultJudgmentDeadlines::<Test>::iter().next().is_none());
+        assert!(DefaultJudgmentsByDeadline::<Test>::iter().next().is_none());
+    });
+}
+
+#[test]
+fn dispute_stats_track_outcomes_and_flag_listing() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(invoke_service_default(BOB, 0));
+        assert_ok!(ServiceMarket::raise_dispute(
+            RuntimeOrigin::signed(BOB),
+            0,
+            b"reason".to_vec(),
+            None,
+        ));
+
+        // One dispute in one invocation: below the sample size.
+        let summary = ServiceMarket::listing_summary(0).unwrap();
+        assert_eq!(summary.disputes.raised, 1);
+        assert!(!summary.high_dispute);
+
+        assert_ok!(invoke_service_default(CHARLIE, 0));
+        assert!(ServiceMarket::listing_summary(0).unwrap().high_dispute);
+
+        assert_ok!(ServiceMarket::resolve_dispute_governance(
+            RuntimeOrigin::root(),
+            0,
+            BOB,
+            None,
+        ));
+        assert_eq!(
+            ListingDisputeStats::<Test>::get(0),
+            DisputeStats {
+                raised: 1,
+                lost_by_provider: 1,
+                lost_by_invoker: 0,
+            }
+        );
+        assert!(ServiceMarket::listing_summary(9).is_none());
+    });
+}
+
+#[test]
+fn resolve_dispute_governance_only_disburses_unreleased_remainder() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(ServiceMarket::invoke_service(
+            RuntimeOrigin::signed(BOB),
+            0,
+            b"requirements".to_vec(),
+            Some(vec![
+                MilestoneSpec { pct_of_total: 60 },
+                MilestoneSpec { pct_of_total: 40 },
+            ]),
+            100,
+            100,
+            None,
+            false,
+            None,
+        ));
+        accept_default(0);
+        assert_ok!(ServiceMarket::submit_invocation_work(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            Some(0),
+            b"proof1".to_vec(),
+            ProofType::Hash,
+        ));
+        assert_ok!(ServiceMarket::approve_milestone(
+            RuntimeOrigin::signed(BOB),
+            0,
+            0
+        ));
+        assert_eq!(ServiceInvocations::<Test>::get(0).unwrap().released, 60);
+
+        assert_ok!(ServiceMarket::raise_dispute(
+            RuntimeOrigin::signed(BOB),
+            0,
+            b"second half missing".to_vec(),
+            None,
+        ));
+
+        let alice_before = Balances::free_balance(ALICE);
+        let bob_before = Balances::free_balance(BOB);
+        assert_ok!(ServiceMarket::resolve_dispute_governance(
+            RuntimeOrigin::root(),
+            0,
+            BOB,
+            None,
+        ));
+
+        // The approved 60 stays with the provider; only the 40 is refunded.
+        assert_eq!(Balances::free_balance(ALICE), alice_before);
+        assert_eq!(Balances::free_balance(BOB) - bob_before, 40);
+        assert_eq!(ServiceInvocations::<Test>::get(0).unwrap().released, 100);
+        System::assert_last_event(
+            Event::DisputeResolvedByGovernance {
+                dispute_id: 0,
+                winner: BOB,
+                amount: 40,
+            }
+            .into(),
+        );
+
+        // A resolved dispute cannot be resolved again.
+        assert_noop!(
+            ServiceMarket::resolve_dispute_governance(RuntimeOrigin::root(), 0, ALICE, None),
+            Error::<Test>::DisputeNotOpen
+        );
+        assert_eq!(Balances::free_balance(ALICE), alice_before);
+    });
+}
+
+#[test]
+fn resolve_dispute_governance_splits_escrow() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(invoke_service_default(BOB, 0));
+        assert_ok!(ServiceMarket::raise_dispute(
+            RuntimeOrigin::signed(BOB),
+            0,
+            b"half delivered".to_vec(),
+            None,
+        ));
+
+        let alice_before = Balances::free_balance(ALICE);
+        let bob_before = Balances::free_balance(BOB);
+        assert_ok!(ServiceMarket::resolve_dispute_governance(
+            RuntimeOrigin::root(),
+            0,
+            ALICE,
+            Some(Perbill::from_percent(60)),
+        ));
+
+        assert_eq!(Balances::free_balance(ALICE) - alice_before, 60);
+        assert_eq!(Balances::free_balance(BOB) - bob_before, 40);
+        System::assert_has_event(
+            Event::DisputeSplitSettled {
+                dispute_id: 0,
+                to_provider: 60,
+                to_invoker: 40,
+            }
+            .into(),
+        );
+        System::assert_last_event(
+            Event::DisputeResolvedByGovernance {
+                dispute_id: 0,
+                winner: ALICE,
+                amount: 60,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn resolve_dispute_governance_fails_not_root() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(invoke_service_default(BOB, 0));
+        assert_ok!(ServiceMarket::raise_dispute(
+            RuntimeOrigin::signed(BOB),
+            0,
+            b"reason".to_vec(),
+            None,
+        ));
+
+        assert_noop!(
+            ServiceMarket::resolve_dispute_governance(
+                RuntimeOrigin::signed(CHARLIE),
+                0,
+                ALICE,
+                None
+            ),
+            sp_runtime::DispatchError::BadOrigin
+        );
+    });
+}
+
+#[test]
+fn dispute_not_found_returns_error() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            ServiceMarket::resolve_dispute_governance(RuntimeOrigin::root(), 999, ALICE, None),
+            Error::<Test>::DisputeNotFound
+        );
+    });
+}
+
+#[test]
+fn resolved_dispute_cannot_be_resolved_again() {
+    new_test_ext().execute_with(|| {
+        ProviderBondAmount::set(100);
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(invoke_service_default(BOB, 0));
+        assert_ok!(ServiceMarket::raise_dispute(
+            RuntimeOrigin::signed(BOB),
+            0,
+            b"reason".to_vec(),
+            None,
+        ));
+
+        assert_ok!(ServiceMarket::resolve_dispute_governance(
+            RuntimeOrigin::root(),
+            0,
+            BOB,
+            None
+        ));
+        assert_eq!(ProviderBonds::<Test>::get(0), Some(50));
+
+        assert_noop!(
+            ServiceMarket::resolve_dispute_governance(RuntimeOrigin::root(), 0, BOB, None),
+            Error::<Test>::DisputeNotOpen
+        );
+        // The bond was slashed and the loss counted once.
+        assert_eq!(ProviderBonds::<Test>::get(0), Some(50));
+        assert_eq!(ListingDisputeStats::<Test>::get(0).lost_by_provider, 1);
+    });
+}
+
+// =========================================================
+// Edge case tests
+// =========================================================
+
+#[test]
+fn multiple_listings_multiple_invocations() {
+    new_test_ext().execute_with(|| {
+        // ALICE lists 2 services
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(ServiceMarket::list_service(
+            RuntimeOrigin::signed(ALICE),
+            b"Storage Service".to_vec(),
+            b"Distributed storage".to_vec(),
+            vec![b"infra/storage".to_vec()],
+            200,
+            200,
+            PaymentMode::Escrow,
+            5,
+            20,
+            0,
+            None,
+            false,
+        ));
+
+        // BOB and CHARLIE both invoke listing 0
+        assert_ok!(invoke_service_default(BOB, 0));
+        assert_ok!(invoke_service_default(CHARLIE, 0));
+
+        assert_eq!(InvocationCount::<Test>::get(), 2);
+
+        // Listing 0 total invocations = 2
+        let listing = ServiceListings::<Test>::get(0).unwrap();
+        assert_eq!(listing.total_invocations, 2);
+    });
+}
+
+#[test]
+fn listing_not_found_on_invoke() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            invoke_service_default(BOB, 42),
+            Error::<Test>::ListingNotFound
+        );
+    });
+}
+
+#[test]
+fn escrow_account_derivation_is_deterministic() {
+    new_test_ext().execute_with(|| {
+        // Calling twice with the same ID returns the same account.
+        let escrow0a = ServiceMarket::invocation_escrow_account(0);
+        let escrow0b = ServiceMarket::invocation_escrow_account(0);
+        assert_eq!(escrow0a, escrow0b);
+        // (Uniqueness across IDs is guaranteed in production with 32-byte AccountId;
+        //  with u64 AccountId in tests the truncation collapses them — that is expected.)
+    });
+}
+
+#[test]
+fn submit_work_with_cid_proof_type() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(invoke_service_default(BOB, 0));
+        accept_default(0);
+        assert_ok!(ServiceMarket::submit_invocation_work(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            None,
+            b"QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_vec(),
+            ProofType::Cid,
+        ));
+        let proof = InvocationProofs::<Test>::get(0, u32::MAX).unwrap();
+        assert_eq!(proof.proof_type, ProofType::Cid);
+    });
+}
+
+#[test]
+fn invoke_service_with_x402_payment_mode_listed() {
+    new_test_ext().execute_with(|| {
+        // List a service with X402 payment mode
+        assert_ok!(ServiceMarket::list_service(
+            RuntimeOrigin::signed(ALICE),
+            b"API Service".to_vec(),
+            b"desc".to_vec(),
+            vec![],
+            100,
+            100,
+            PaymentMode::X402,
+            10,
+            50,
+            0,
+            None,
+            false,
+        ));
+        let listing = ServiceListings::<Test>::get(0).unwrap();
+        assert!(matches!(listing.payment_mode, PaymentMode::X402));
+    });
+}
+
+#[test]
+fn x402_invocation_requires_registered_payment_before_accept() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(ServiceMarket::list_service(
+            RuntimeOrigin::signed(ALICE),
+            b"API Service".to_vec(),
+            b"desc".to_vec(),
+            vec![],
+            100,
+            100,
+            PaymentMode::X402,
+            10,
+            50,
+            0,
+            None,
+            false,
+        ));
+        assert_noop!(
+            invoke_service_default(BOB, 0),
+            Error::<Test>::PaymentModeNotAccepted
+        );
+
+        let bob_before = Balances::free_balance(BOB);
+        assert_ok!(ServiceMarket::invoke_service_x402(
+            RuntimeOrigin::signed(BOB),
+            0,
+            b"req".to_vec(),
+            None,
+            100,
+            100,
+        ));
+        assert_eq!(Balances::free_balance(BOB), bob_before);
+        assert_noop!(
+            ServiceMarket::accept_invocation(RuntimeOrigin::signed(ALICE), 0),
+            Error::<Test>::X402PaymentMissing
+        );
+
+        let proof = H256::repeat_byte(7);
+        assert_noop!(
+            ServiceMarket::register_x402_payment(RuntimeOrigin::signed(BOB), 0, proof, 99),
+            Error::<Test>::X402PaymentTooLow
+        );
+        assert_ok!(ServiceMarket::register_x402_payment(
+            RuntimeOrigin::signed(BOB),
+            0,
+            proof,
+            100
+        ));
+        assert_noop!(
+            ServiceMarket::register_x402_payment(RuntimeOrigin::signed(BOB), 0, proof, 100),
+            Error::<Test>::X402PaymentAlreadyRegistered
+        );
+        assert_eq!(X402Payments::<Test>::get(0).unwrap().proof_hash, proof);
+        accept_default(0);
+
+        assert_ok!(ServiceMarket::set_x402_min_reputation(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            Some(u32::MAX),
+        ));
+        assert_noop!(
+            ServiceMarket::invoke_service_x402(
+                RuntimeOrigin::signed(BOB),
+                0,
+                b"req".to_vec(),
+                None,
+                100,
+                100,
+            ),
+            Error::<Test>::InsufficientReputation
+        );
+    });
+}
+
+#[test]
+fn listing_count_starts_at_zero() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(ListingCount::<Test>::get(), 0);
+        assert_eq!(InvocationCount::<Test>::get(), 0);
+        assert_eq!(DisputeCount::<Test>::get(), 0);
+    });
+}
+
+// =========================================================
+// Reaper guard tests
+// =========================================================
+
+#[test]
+fn escrow_is_kept_alive_while_invocation_open() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(invoke_service_default(BOB, 0));
+
+        assert_eq!(
+            ServiceMarket::escrow_existence_requirement(0),
+            ExistenceRequirement::KeepAlive
+        );
+
+        assert_ok!(ServiceMarket::cancel_invocation(
+            RuntimeOrigin::signed(BOB),
+            0
+        ));
+        assert_eq!(
+            ServiceMarket::escrow_existence_requirement(0),
+            ExistenceRequirement::AllowDeath
+        );
+    });
+}
+
+#[test]
+fn final_release_reaps_escrow_account() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(invoke_service_default(BOB, 0));
+        let escrow = ServiceMarket::invocation_escrow_account(0);
+        assert!(System::account_exists(&escrow));
+
+        accept_default(0);
+        assert_ok!(ServiceMarket::submit_invocation_work(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            None,
+            b"proof".to_vec(),
+            ProofType::Hash,
+        ));
+        assert_ok!(ServiceMarket::approve_milestone(
+            RuntimeOrigin::signed(BOB),
+            0,
+            0
+        ));
+
+        assert_eq!(Balances::free_balance(escrow), 0);
+        assert!(!System::account_exists(&escrow));
+    });
+}
+
+#[test]
+fn partial_release_keeps_escrow_above_existential_deposit() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(ServiceMarket::invoke_service(
+            RuntimeOrigin::signed(BOB),
+            0,
+            b"requirements".to_vec(),
+            Some(vec![
+                MilestoneSpec { pct_of_total: 99 },
+                MilestoneSpec { pct_of_total: 1 },
+            ]),
+            100,
+            100,
+            None,
+            false,
+            None,
+        ));
+        accept_default(0);
+        assert_ok!(ServiceMarket::submit_invocation_work(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            Some(0),
+            b"proof".to_vec(),
+            ProofType::Hash,
+        ));
+        assert_ok!(ServiceMarket::approve_milestone(
+            RuntimeOrigin::signed(BOB),
+            0,
+            0
+        ));
+
+        // Exactly the existential deposit remains for the open milestone.
+        let escrow = ServiceMarket::invocation_escrow_account(0);
+        assert_eq!(Balances::free_balance(escrow), ExistentialDeposit::get());
+        assert!(System::account_exists(&escrow));
+    });
+}
+
+#[test]
+fn external_guard_blocks_reaping_escrow() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(invoke_service_default(BOB, 0));
+        accept_default(0);
+        assert_ok!(ServiceMarket::submit_invocation_work(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            None,
+            b"proof".to_vec(),
+            ProofType::Hash,
+        ));
+
+        MockAccountGuards::guard(ServiceMarket::invocation_escrow_account(0));
+
+        // Releasing the full 100 would drop the escrow below the existential deposit.
+        assert_noop!(
+            ServiceMarket::approve_milestone(RuntimeOrigin::signed(BOB), 0, 0),
+            Error::<Test>::InsufficientBalance
+        );
+    });
+}
+
+#[test]
+fn external_guard_allows_release_above_existential_deposit() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(invoke_service_default(BOB, 0));
+        accept_default(0);
+        assert_ok!(ServiceMarket::submit_invocation_work(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            None,
+            b"proof".to_vec(),
+            ProofType::Hash,
+        ));
+
+        let escrow = ServiceMarket::invocation_escrow_account(0);
+        MockAccountGuards::guard(escrow);
+        assert_ok!(Balances::transfer_allow_death(
+            RuntimeOrigin::signed(CHARLIE),
+            escrow,
+            ExistentialDeposit::get(),
+        ));
+
+        assert_ok!(ServiceMarket::approve_milestone(
+            RuntimeOrigin::signed(BOB),
+            0,
+            0
+        ));
+        assert_eq!(Balances::free_balance(escrow), ExistentialDeposit::get());
+    });
+}
+
+#[test]
+fn parties_to_open_invocation_are_guarded() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(invoke_service_default(BOB, 0));
+
+        assert!(<ServiceMarket as ProviderOfAccountGuards<u64>>::is_guarded(
+            &ALICE
+        ));
+        assert!(<ServiceMarket as ProviderOfAccountGuards<u64>>::is_guarded(
+            &BOB
+        ));
+        assert!(!<ServiceMarket as ProviderOfAccountGuards<u64>>::is_guarded(&CHARLIE));
+
+        // Disputed invocations keep both parties guarded.
+        assert_ok!(ServiceMarket::raise_dispute(
+            RuntimeOrigin::signed(BOB),
+            0,
+            b"Provider did not deliver".to_vec(),
+            None,
+        ));
+        assert!(<ServiceMarket as ProviderOfAccountGuards<u64>>::is_guarded(
+            &ALICE
+        ));
+        assert!(<ServiceMarket as ProviderOfAccountGuards<u64>>::is_guarded(
+            &BOB
+        ));
+    });
+}
+
+#[test]
+fn guard_lifts_once_invocation_settles() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(invoke_service_default(BOB, 0));
+        assert_ok!(ServiceMarket::cancel_invocation(
+            RuntimeOrigin::signed(BOB),
+            0
+        ));
+
+        assert!(!<ServiceMarket as ProviderOfAccountGuards<u64>>::is_guarded(&ALICE));
+        assert!(!<ServiceMarket as ProviderOfAccountGuards<u64>>::is_guarded(&BOB));
+    });
+}
+
+// =========================================================
+// Invocation template tests
+// =========================================================
+
+fn create_template_default(owner: u64, listing_id: ListingId) -> DispatchResult {
+    ServiceMarket::create_invocation_template(
+        RuntimeOrigin::signed(owner),
+        listing_id,
+        100,
+        Some(vec![
+            MilestoneSpec { pct_of_total: 50 },
+            MilestoneSpec { pct_of_total: 50 },
+        ]),
+        100,
+        b"bafy-default".to_vec(),
+    )
+}
+
+#[test]
+fn create_invocation_template_works() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(create_template_default(BOB, 0));
+
+        let template = InvocationTemplates::<Test>::get(0).unwrap();
+        assert_eq!(template.owner, BOB);
+        assert_eq!(template.listing_id, 0);
+        assert_eq!(template.price, 100);
+        assert_eq!(template.milestones.len(), 2);
+        assert_eq!(TemplateCount::<Test>::get(), 1);
+        assert_eq!(TemplatesByOwner::<Test>::get(BOB).to_vec(), vec![0]);
+    });
+}
+
+#[test]
+fn create_invocation_template_validates_inputs() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            create_template_default(BOB, 0),
+            Error::<Test>::ListingNotFound
+        );
+
+        assert_ok!(list_service_default(ALICE));
+        assert_noop!(
+            ServiceMarket::create_invocation_template(
+                RuntimeOrigin::signed(BOB),
+                0,
+                100,
+                Some(vec![MilestoneSpec { pct_of_total: 70 }]),
+                100,
+                Vec::new(),
+            ),
+            Error::<Test>::MilestonePercentagesInvalid
+        );
+
+        assert_ok!(create_template_default(BOB, 0));
+        assert_ok!(create_template_default(BOB, 0));
+        assert_noop!(
+            create_template_default(BOB, 0),
+            Error::<Test>::TooManyTemplates
+        );
+    });
+}
+
+#[test]
+fn invoke_from_template_creates_invocation() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(create_template_default(BOB, 0));
+
+        assert_ok!(ServiceMarket::invoke_from_template(
+            RuntimeOrigin::signed(BOB),
+            0,
+            None,
+            None,
+        ));
+        let inv = ServiceInvocations::<Test>::get(0).unwrap();
+        assert_eq!(inv.requirements.to_vec(), b"bafy-default".to_vec());
+
+        assert_ok!(ServiceMarket::invoke_from_template(
+            RuntimeOrigin::signed(BOB),
+            0,
+            Some(b"bafy-requirements-2".to_vec()),
+            None,
+        ));
+
+        let inv = ServiceInvocations::<Test>::get(1).unwrap();
+        assert_eq!(inv.invoker, BOB);
+        assert_eq!(inv.provider, ALICE);
+        assert_eq!(inv.price, 100);
+        assert_eq!(inv.milestones.len(), 2);
+        assert_eq!(inv.deadline, 101);
+        assert_eq!(inv.requirements.to_vec(), b"bafy-requirements-2".to_vec());
+        assert_eq!(Balances::free_balance(BOB), 100_000 - 200);
+    });
+}
+
+#[test]
+fn invoke_from_template_batch_creates_all_or_nothing() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(create_template_default(BOB, 0));
+
+        assert_noop!(
+            ServiceMarket::invoke_from_template_batch(RuntimeOrigin::signed(BOB), 0, Vec::new()),
+            Error::<Test>::InvalidTemplateBatch
+        );
+        assert_noop!(
+            ServiceMarket::invoke_from_template_batch(
+                RuntimeOrigin::signed(BOB),
+                0,
+                vec![b"bafy-1".to_vec(); 4]
+            ),
+            Error::<Test>::InvalidTemplateBatch
+        );
+        assert_noop!(
+            ServiceMarket::invoke_from_template_batch(
+                RuntimeOrigin::signed(CHARLIE),
+                0,
+                vec![b"bafy-1".to_vec()]
+            ),
+            Error::<Test>::NotTemplateOwner
+        );
+        // One bad entry rolls back the whole batch.
+        assert_noop!(
+            ServiceMarket::invoke_from_template_batch(
+                RuntimeOrigin::signed(BOB),
+                0,
+                vec![b"bafy-1".to_vec(), Vec::new()]
+            ),
+            Error::<Test>::RequirementsEmpty
+        );
+
+        assert_ok!(ServiceMarket::invoke_from_template_batch(
+            RuntimeOrigin::signed(BOB),
+            0,
+            vec![b"bafy-1".to_vec(), b"bafy-2".to_vec(), b"bafy-3".to_vec()]
+        ));
+        assert_eq!(InvocationCount::<Test>::get(), 3);
+        for (id, cid) in [b"bafy-1", b"bafy-2", b"bafy-3"].iter().enumerate() {
+            let inv = ServiceInvocations::<Test>::get(id as InvocationId).unwrap();
+            assert_eq!(inv.requirements.to_vec(), cid.to_vec());
+            assert_eq!(inv.price, 100);
+            assert_eq!(inv.milestones.len(), 2);
+        }
+        assert_eq!(Balances::free_balance(BOB), 100_000 - 300);
+    });
+}
+
+#[test]
+fn invoke_from_template_overrides_deadline_and_needs_requirements() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(ServiceMarket::create_invocation_template(
+            RuntimeOrigin::signed(BOB),
+            0,
+            100,
+            None,
+            100,
+            Vec::new(),
+        ));
+
+        assert_noop!(
+            ServiceMarket::invoke_from_template(RuntimeOrigin::signed(BOB), 0, None, None),
+            Error::<Test>::RequirementsEmpty
+        );
+        assert_ok!(ServiceMarket::invoke_from_template(
+            RuntimeOrigin::signed(BOB),
+            0,
+            Some(b"bafy".to_vec()),
+            Some(20),
+        ));
+        assert_eq!(ServiceInvocations::<Test>::get(0).unwrap().deadline, 21);
+    });
+}
+
+#[test]
+fn invoke_from_template_fails_not_owner() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(create_template_default(BOB, 0));
+
+        assert_noop!(
+            ServiceMarket::invoke_from_template(RuntimeOrigin::signed(CHARLIE), 0, None, None,),
+            Error::<Test>::NotTemplateOwner
+        );
+    });
+}
+
+#[test]
+fn invoke_from_template_rechecks_listing() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(create_template_default(BOB, 0));
+        assert_ok!(ServiceMarket::delist_service(
+            RuntimeOrigin::signed(ALICE),
+            0
+        ));
+
+        assert_noop!(
+            ServiceMarket::invoke_from_template(RuntimeOrigin::signed(BOB), 0, None, None),
+            Error::<Test>::ListingNotActive
+        );
+    });
+}
+
+#[test]
+fn remove_invocation_template_works() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(create_template_default(BOB, 0));
+
+        assert_noop!(
+            ServiceMarket::remove_invocation_template(RuntimeOrigin::signed(CHARLIE), 0),
+            Error::<Test>::NotTemplateOwner
+        );
+        assert_ok!(ServiceMarket::remove_invocation_template(
+            RuntimeOrigin::signed(BOB),
+            0
+        ));
+
+        assert!(InvocationTemplates::<Test>::get(0).is_none());
+        assert!(TemplatesByOwner::<Test>::get(BOB).is_empty());
+        assert_noop!(
+            ServiceMarket::invoke_from_template(RuntimeOrigin::signed(BOB), 0, None, None),
+            Error::<Test>::TemplateNotFound
+        );
+    });
+}
+
+// =========================================================
+// Stable-denominated listing tests
+// =========================================================
+
+#[test]
+fn set_stable_price_requires_provider() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_noop!(
+            ServiceMarket::set_stable_price(RuntimeOrigin::signed(BOB), 0, Some(5)),
+            Error::<Test>::NotProvider
+        );
+        assert_ok!(ServiceMarket::set_stable_price(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            Some(5)
+        ));
+        assert_eq!(StablePrices::<Test>::get(0), Some(5));
+        System::assert_last_event(
+            Event::StablePriceSet {
+                listing_id: 0,
+                reference_price: Some(5),
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn stable_listing_locks_oracle_converted_price() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(ServiceMarket::set_stable_price(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            Some(5)
+        ));
+        MockPriceOracle::set_rate(30, 1);
+
+        let bob_before = Balances::free_balance(BOB);
+        assert_ok!(ServiceMarket::invoke_service(
+            RuntimeOrigin::signed(BOB),
+            0,
+            b"stable".to_vec(),
+            None,
+            200, // upper bound accepted by the invoker
+            100,
+            None,
+            false,
+            None,
+        ));
+
+        // 5 reference units * 30 CLAW = 150, above the listing's fixed 100.
+        assert_eq!(bob_before - Balances::free_balance(BOB), 150);
+        assert_eq!(ServiceInvocations::<Test>::get(0).unwrap().price, 150);
+    });
+}
+
+#[test]
+fn stable_listing_rejects_missing_stale_or_excessive_rate() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(ServiceMarket::set_stable_price(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            Some(5)
+        ));
+
+        assert_noop!(
+            invoke_service_default(BOB, 0),
+            Error::<Test>::OracleUnavailable
+        );
+
+        MockPriceOracle::set_rate(10, 1);
+        System::set_block_number(12);
+        assert_noop!(invoke_service_default(BOB, 0), Error::<Test>::OracleStale);
+
+        MockPriceOracle::set_rate(30, 12);
+        assert_noop!(
+            invoke_service_default(BOB, 0),
+            Error::<Test>::StablePriceExceedsLimit
+        );
+    });
+}
+
+#[test]
+fn stable_price_must_be_non_zero_and_convert_within_bounds() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_noop!(
+            ServiceMarket::set_stable_price(RuntimeOrigin::signed(ALICE), 0, Some(0)),
+            Error::<Test>::ZeroStablePrice
+        );
+        assert_ok!(ServiceMarket::set_stable_price(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            Some(5)
+        ));
+
+        // 5 * 10 = 50 is below the listing's 100 minimum.
+        MockPriceOracle::set_rate(10, 1);
+        assert_noop!(
+            invoke_service_default(BOB, 0),
+            Error::<Test>::PriceBelowMinimum
+        );
+
+        MockPriceOracle::set_rate(u64::MAX, 1);
+        assert_noop!(
+            invoke_service_default(BOB, 0),
+            Error::<Test>::StablePriceOverflow
+        );
+    });
+}
+
+#[test]
+fn delisting_clears_stable_price() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(ServiceMarket::set_stable_price(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            Some(5)
+        ));
+        assert_ok!(ServiceMarket::delist_service(
+            RuntimeOrigin::signed(ALICE),
+            0
+        ));
+        assert!(StablePrices::<Test>::get(0).is_none());
+    });
+}
+
+#[test]
+fn clearing_stable_price_restores_claw_pricing() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(ServiceMarket::set_stable_price(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            Some(5)
+        ));
+        assert_ok!(ServiceMarket::set_stable_price(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            None
+        ));
+        assert_ok!(invoke_service_default(BOB, 0));
+        assert_eq!(ServiceInvocations::<Test>::get(0).unwrap().price, 100);
+    });
+}
+
+// =========================================================
+// Dispute consequence tests
+// =========================================================
+
+/// BOB invokes `listing_id`, disputes it and governance rules for BOB.
+fn provider_loses_dispute(listing_id: ListingId) {
+    let invocation_id = InvocationCount::<Test>::get();
+    let dispute_id = DisputeCount::<Test>::get();
+    assert_ok!(invoke_service_default(BOB, listing_id));
+    assert_ok!(ServiceMarket::raise_dispute(
+        RuntimeOrigin::signed(BOB),
+        invocation_id,
+        b"reason".to_vec(),
+        None,
+    ));
+    assert_ok!(ServiceMarket::resolve_dispute_governance(
+        RuntimeOrigin::root(),
+        dispute_id,
+        BOB,
+        None,
+    ));
+}
+
+#[test]
+fn repeated_dispute_losses_suspend_provider() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(list_service_default(ALICE));
+
+        provider_loses_dispute(0);
+        assert!(SuspendedProviders::<Test>::get(ALICE).is_none());
+
+        provider_loses_dispute(1);
+        assert_eq!(
+            SuspendedProviders::<Test>::get(ALICE).unwrap().to_vec(),
+            vec![0, 1]
+        );
+        assert!(!ServiceListings::<Test>::get(0).unwrap().active);
+        assert!(!ServiceListings::<Test>::get(1).unwrap().active);
+        System::assert_has_event(
+            Event::ProviderSuspended {
+                provider: ALICE,
+                paused_listings: 2,
+            }
+            .into(),
+        );
+
+        assert_noop!(
+            list_service_default(ALICE),
+            Error::<Test>::ProviderSuspended
+        );
+    });
+}
+
+#[test]
+fn dispute_losses_outside_window_do_not_suspend() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+
+        provider_loses_dispute(0);
+        System::set_block_number(150);
+        provider_loses_dispute(0);
+
+        assert!(SuspendedProviders::<Test>::get(ALICE).is_none());
+        assert_eq!(ProviderDisputeLosses::<Test>::get(ALICE), (150, 1));
+    });
+}
+
+#[test]
+fn reactivation_bond_restores_listings() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(list_service_default(ALICE));
+        provider_loses_dispute(0);
+        provider_loses_dispute(0);
+
+        // A listing delisted during suspension stays delisted.
+        assert_ok!(ServiceMarket::delist_service(
+            RuntimeOrigin::signed(ALICE),
+            1
+        ));
+
+        assert_noop!(
+            ServiceMarket::post_reactivation_bond(RuntimeOrigin::signed(BOB)),
+            Error::<Test>::ProviderNotSuspended
+        );
+        assert_ok!(ServiceMarket::post_reactivation_bond(
+            RuntimeOrigin::signed(ALICE)
+        ));
+
+        assert_eq!(Balances::reserved_balance(ALICE), 500);
+        assert_eq!(ReactivationBonds::<Test>::get(ALICE), Some(500));
+        assert!(SuspendedProviders::<Test>::get(ALICE).is_none());
+        assert!(ServiceListings::<Test>::get(0).unwrap().active);
+        assert!(!ServiceListings::<Test>::get(1).unwrap().active);
+
+        assert_ok!(ServiceMarket::release_reactivation_bond(
+            RuntimeOrigin::root(),
+            ALICE
+        ));
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+        assert_noop!(
+            ServiceMarket::release_reactivation_bond(RuntimeOrigin::root(), ALICE),
+            Error::<Test>::NoReactivationBond
+        );
+    });
+}
+
+#[test]
+fn governance_can_suspend_and_reinstate_without_bond() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+
+        assert_noop!(
+            ServiceMarket::set_provider_suspension(RuntimeOrigin::signed(BOB), ALICE, true),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_ok!(ServiceMarket::set_provider_suspension(
+            RuntimeOrigin::root(),
+            ALICE,
+            true
+        ));
+        assert!(!ServiceListings::<Test>::get(0).unwrap().active);
+
+        assert_ok!(ServiceMarket::set_provider_suspension(
+            RuntimeOrigin::root(),
+            ALICE,
+            false
+        ));
+        assert!(ServiceListings::<Test>::get(0).unwrap().active);
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+        System::assert_last_event(
+            Event::ProviderReactivated {
+                provider: ALICE,
+                bond: None,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn taken_down_listing_stays_down_after_reactivation() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(ServiceMarket::flag_listing(
+            RuntimeOrigin::signed(BOB),
+            0,
+            b"malware".to_vec()
+        ));
+        assert_ok!(ServiceMarket::set_provider_suspension(
+            RuntimeOrigin::root(),
+            ALICE,
+            true
+        ));
+        assert_eq!(
+            SuspendedProviders::<Test>::get(ALICE).unwrap().to_vec(),
+            vec![0]
+        );
+
+        assert_ok!(ServiceMarket::resolve_listing_flags(
+            RuntimeOrigin::root(),
+            0,
+            true
+        ));
+        assert!(SuspendedProviders::<Test>::get(ALICE).unwrap().is_empty());
+
+        assert_ok!(ServiceMarket::set_provider_suspension(
+            RuntimeOrigin::root(),
+            ALICE,
+            false
+        ));
+        assert!(!ServiceListings::<Test>::get(0).unwrap().active);
+    });
+}
+
+// =========================================================
+// Jurisdiction tests
+// =========================================================
+
+#[test]
+fn set_listing_jurisdictions_updates_index() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+
+        assert_noop!(
+            ServiceMarket::set_listing_jurisdictions(RuntimeOrigin::signed(BOB), 0, vec![*b"DE"]),
+            Error::<Test>::NotProvider
+        );
+        assert_ok!(ServiceMarket::set_listing_jurisdictions(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            vec![*b"US", *b"DE", *b"US"]
+        ));
+        assert_eq!(
+            ServiceListings::<Test>::get(0)
+                .unwrap()
+                .jurisdictions
+                .into_inner(),
+            vec![*b"DE", *b"US"]
+        );
+        assert_eq!(
+            ListingsByJurisdiction::<Test>::get(*b"DE").into_inner(),
+            vec![0]
+        );
+
+        // Replacing the set drops stale index entries.
+        assert_ok!(ServiceMarket::set_listing_jurisdictions(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            vec![*b"FR"]
+        ));
+        assert!(ListingsByJurisdiction::<Test>::get(*b"DE").is_empty());
+        assert_eq!(
+            ListingsByJurisdiction::<Test>::get(*b"FR").into_inner(),
+            vec![0]
+        );
+
+        assert_ok!(ServiceMarket::delist_service(
+            RuntimeOrigin::signed(ALICE),
+            0
+        ));
+        assert!(ListingsByJurisdiction::<Test>::get(*b"FR").is_empty());
+    });
+}
+
+#[test]
+fn set_listing_jurisdictions_validates_codes() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+
+        assert_noop!(
+            ServiceMarket::set_listing_jurisdictions(RuntimeOrigin::signed(ALICE), 0, vec![*b"de"]),
+            Error::<Test>::InvalidJurisdictionCode
+        );
+        assert_noop!(
+            ServiceMarket::set_listing_jurisdictions(
+                RuntimeOrigin::signed(ALICE),
+                0,
+                vec![*b"DE", *b"FR", *b"US", *b"JP"]
+            ),
+            Error::<Test>::TooManyJurisdictions
+        );
+    });
+}
+
+#[test]
+fn invoke_service_enforces_required_jurisdiction() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        let invoke = |code: Option<JurisdictionCode>| {
+            ServiceMarket::invoke_service(
+                RuntimeOrigin::signed(BOB),
+                0,
+                b"req".to_vec(),
+                None,
+                100,
+                100,
+                code,
+                false,
+                None,
+            )
+        };
+
+        // A listing without declared jurisdictions never satisfies a requirement.
+        assert_noop!(invoke(Some(*b"DE")), Error::<Test>::JurisdictionMismatch);
+
+        assert_ok!(ServiceMarket::set_listing_jurisdictions(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            vec![*b"DE"]
+        ));
+        assert_noop!(invoke(Some(*b"US")), Error::<Test>::JurisdictionMismatch);
+        assert_ok!(invoke(Some(*b"DE")));
+        assert_ok!(invoke(None));
+    });
+}
+
+// =========================================================
+// Slot reservation tests
+// =========================================================
+
+#[test]
+fn reserve_slot_validates_window_and_overlap() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+
+        assert_noop!(
+            ServiceMarket::reserve_slot(RuntimeOrigin::signed(BOB), 0, 0, 10),
+            Error::<Test>::InvalidSlotWindow
+        );
+        assert_noop!(
+            ServiceMarket::reserve_slot(RuntimeOrigin::signed(BOB), 0, 5, 101),
+            Error::<Test>::InvalidSlotWindow
+        );
+
+        assert_ok!(ServiceMarket::reserve_slot(
+            RuntimeOrigin::signed(BOB),
+            0,
+            5,
+            10
+        ));
+        assert_eq!(Balances::reserved_balance(BOB), 10);
+
+        // [14, 20) overlaps [5, 15); [15, 20) does not.
+        assert_noop!(
+            ServiceMarket::reserve_slot(RuntimeOrigin::signed(CHARLIE), 0, 14, 6),
+            Error::<Test>::SlotUnavailable
+        );
+        assert_ok!(ServiceMarket::reserve_slot(
+            RuntimeOrigin::signed(CHARLIE),
+            0,
+            15,
+            5
+        ));
+        assert_noop!(
+            ServiceMarket::reserve_slot(RuntimeOrigin::signed(DAVE), 0, 30, 5),
+            Error::<Test>::TooManyReservations
+        );
+    });
+}
+
+#[test]
+fn reserved_slot_blocks_others_and_fee_counts_towards_price() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(ServiceMarket::reserve_slot(
+            RuntimeOrigin::signed(BOB),
+            0,
+            5,
+            10
+        ));
+
+        // Before the window anyone may invoke.
+        assert_ok!(invoke_service_default(DAVE, 0));
+
+        System::set_block_number(5);
+        assert_noop!(
+            invoke_service_default(CHARLIE, 0),
+            Error::<Test>::SlotUnavailable
+        );
+
+        let bob_before = Balances::free_balance(BOB) + Balances::reserved_balance(BOB);
+        assert_ok!(invoke_service_default(BOB, 0));
+
+        // The 10 fee was part of the 100 escrowed, not charged on top.
+        assert_eq!(bob_before - Balances::free_balance(BOB), 100);
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert!(ListingReservations::<Test>::get(0).is_empty());
+        System::assert_has_event(
+            Event::SlotRedeemed {
+                listing_id: 0,
+                invocation_id: 1,
+                start_block: 5,
+            }
+            .into(),
+        );
+
+        // Slot consumed: the rest of the window is open again.
+        assert_ok!(invoke_service_default(CHARLIE, 0));
+    });
+}
+
+#[test]
+fn unused_slot_is_forfeited_to_provider() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(ServiceMarket::reserve_slot(
+            RuntimeOrigin::signed(BOB),
+            0,
+            5,
+            10
+        ));
+
+        System::set_block_number(14);
+        assert_noop!(
+            ServiceMarket::forfeit_slot(RuntimeOrigin::signed(CHARLIE), 0, 5),
+            Error::<Test>::SlotNotEnded
+        );
+        assert_noop!(
+            ServiceMarket::forfeit_slot(RuntimeOrigin::signed(CHARLIE), 0, 6),
+            Error::<Test>::SlotNotFound
+        );
+
+        System::set_block_number(15);
+        let alice_before = Balances::free_balance(ALICE);
+        assert_ok!(ServiceMarket::forfeit_slot(
+            RuntimeOrigin::signed(CHARLIE),
+            0,
+            5
+        ));
+        assert_eq!(Balances::free_balance(ALICE) - alice_before, 10);
+        assert_eq!(Balances::free_balance(BOB), 100_000 - 10);
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert!(ListingReservations::<Test>::get(0).is_empty());
+    });
+}
+
+#[test]
+fn delist_returns_reservation_fees() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(ServiceMarket::reserve_slot(
+            RuntimeOrigin::signed(BOB),
+            0,
+            5,
+            10
+        ));
+        assert_ok!(ServiceMarket::delist_service(
+            RuntimeOrigin::signed(ALICE),
+            0
+        ));
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert!(ListingReservations::<Test>::get(0).is_empty());
+    });
+}
+
+// =========================================================
+// Progress checkpoint tests
+// =========================================================
+
+#[test]
+fn report_progress_appends_increasing_checkpoints() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(invoke_service_default(BOB, 0));
+
+        assert_noop!(
+            ServiceMarket::report_progress(RuntimeOrigin::signed(BOB), 0, 10, b"cid".to_vec()),
+            Error::<Test>::NotProvider
+        );
+        accept_default(0);
+        assert_ok!(ServiceMarket::report_progress(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            25,
+            b"cid-25".to_vec()
+        ));
+        assert_eq!(
+            ServiceInvocations::<Test>::get(0).unwrap().status,
+            InvocationStatus::InProgress
+        );
+        assert_noop!(
+            ServiceMarket::report_progress(RuntimeOrigin::signed(ALICE), 0, 25, b"cid".to_vec()),
+            Error::<Test>::InvalidProgress
+        );
+        assert_noop!(
+            ServiceMarket::report_progress(RuntimeOrigin::signed(ALICE), 0, 101, b"cid".to_vec()),
+            Error::<Test>::InvalidProgress
+        );
+        assert_ok!(ServiceMarket::report_progress(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            50,
+            b"cid-50".to_vec()
+        ));
+        assert_ok!(ServiceMarket::report_progress(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            75,
+            b"cid-75".to_vec()
+        ));
+        assert_noop!(
+            ServiceMarket::report_progress(RuntimeOrigin::signed(ALICE), 0, 90, b"cid".to_vec()),
+            Error::<Test>::TooManyCheckpoints
+        );
+
+        let progress = InvocationProgress::<Test>::get(0);
+        assert_eq!(
+            progress.iter().map(|c| c.percent).collect::<Vec<_>>(),
+            vec![25, 50, 75]
+        );
+        System::assert_last_event(
+            Event::ProgressReported {
+                invocation_id: 0,
+                percent: 75,
+                proof_cid: b"cid-75".to_vec(),
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn cancel_stalled_invocation_pays_reported_progress() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(invoke_service_default(BOB, 0));
+        System::set_block_number(5);
+        accept_default(0);
+        assert_ok!(ServiceMarket::report_progress(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            40,
+            b"cid-40".to_vec()
+        ));
+
+        let alice_before = Balances::free_balance(ALICE);
+        let bob_before = Balances::free_balance(BOB);
+
+        System::set_block_number(24);
+        assert_noop!(
+            ServiceMarket::cancel_stalled_invocation(RuntimeOrigin::signed(BOB), 0),
+            Error::<Test>::ProgressNotStalled
+        );
+        assert_noop!(
+            ServiceMarket::cancel_stalled_invocation(RuntimeOrigin::signed(ALICE), 0),
+            Error::<Test>::NotInvoker
+        );
+
+        System::set_block_number(25);
+        assert_ok!(ServiceMarket::cancel_stalled_invocation(
+            RuntimeOrigin::signed(BOB),
+            0
+        ));
+
+        assert_eq!(Balances::free_balance(ALICE) - alice_before, 40);
+        assert_eq!(Balances::free_balance(BOB) - bob_before, 60);
+        let inv = ServiceInvocations::<Test>::get(0).unwrap();
+        assert_eq!(inv.status, InvocationStatus::Cancelled);
+        assert!(InvocationProgress::<Test>::get(0).is_empty());
+        System::assert_last_event(
+            Event::StalledInvocationCancelled {
+                invocation_id: 0,
+                paid_to_provider: 40,
+                refunded: 60,
+            }
+            .into(),
+        );
+    });
+}
+
+// =========================================================
+// Mid-work cancellation tests
+// =========================================================
+
 #[test]
-fn raise_dispute_fails_already_disputed() {
+fn cancel_in_progress_pays_kill_fee_on_next_milestone_after_ack() {
     new_test_ext().execute_with(|| {
         assert_ok!(list_service_default(ALICE));
-        assert_ok!(invoke_service_default(BOB, 0));
-        assert_ok!(ServiceMarket::raise_dispute(
+        assert_ok!(ServiceMarket::invoke_service(
             RuntimeOrigin::signed(BOB),
             0,
-            b"reason".to_vec(),
+            b"requirements".to_vec(),
+            Some(vec![
+                MilestoneSpec { pct_of_total: 60 },
+                MilestoneSpec { pct_of_total: 40 },
+            ]),
+            100,
+            100,
+            None,
+            false,
             None,
         ));
+        assert_noop!(
+            ServiceMarket::cancel_in_progress(RuntimeOrigin::signed(BOB), 0),
+            Error::<Test>::InvalidInvocationStatus
+        );
+
+        accept_default(0);
+        assert_ok!(ServiceMarket::submit_invocation_work(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            Some(0),
+            b"proof1".to_vec(),
+            ProofType::Hash,
+        ));
+        assert_ok!(ServiceMarket::approve_milestone(
+            RuntimeOrigin::signed(BOB),
+            0,
+            0,
+        ));
 
+        assert_ok!(ServiceMarket::cancel_in_progress(
+            RuntimeOrigin::signed(BOB),
+            0
+        ));
         assert_noop!(
-            ServiceMarket::raise_dispute(
+            ServiceMarket::cancel_in_progress(RuntimeOrigin::signed(BOB), 0),
+            Error::<Test>::CancellationAlreadyRequested
+        );
+
+        let alice_before = Balances::free_balance(ALICE);
+        let bob_before = Balances::free_balance(BOB);
+        assert_noop!(
+            ServiceMarket::ack_cancellation(RuntimeOrigin::signed(BOB), 0),
+            Error::<Test>::NotProvider
+        );
+        assert_ok!(ServiceMarket::ack_cancellation(
+            RuntimeOrigin::signed(ALICE),
+            0
+        ));
+
+        // 20% kill fee on the 40-unit second milestone; the rest refunds.
+        assert_eq!(Balances::free_balance(ALICE) - alice_before, 8);
+        assert_eq!(Balances::free_balance(BOB) - bob_before, 32);
+        assert_eq!(
+            ServiceInvocations::<Test>::get(0).unwrap().status,
+            InvocationStatus::Cancelled
+        );
+        assert!(PendingCancellations::<Test>::get(0).is_none());
+        System::assert_last_event(
+            Event::InvocationCancelledInProgress {
+                invocation_id: 0,
+                kill_fee: 8,
+                refunded: 32,
+                acknowledged: true,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn finalize_cancellation_settles_after_timeout() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(invoke_service_default(BOB, 0));
+        accept_default(0);
+        assert_ok!(ServiceMarket::report_progress(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            10,
+            b"cid".to_vec()
+        ));
+        assert_ok!(ServiceMarket::cancel_in_progress(
+            RuntimeOrigin::signed(BOB),
+            0
+        ));
+
+        System::set_block_number(10);
+        assert_noop!(
+            ServiceMarket::finalize_cancellation(RuntimeOrigin::signed(CHARLIE), 0),
+            Error::<Test>::CancellationTimeoutNotReached
+        );
+
+        let alice_before = Balances::free_balance(ALICE);
+        let bob_before = Balances::free_balance(BOB);
+        System::set_block_number(11);
+        assert_ok!(ServiceMarket::finalize_cancellation(
+            RuntimeOrigin::signed(CHARLIE),
+            0
+        ));
+
+        assert_eq!(Balances::free_balance(ALICE) - alice_before, 20);
+        assert_eq!(Balances::free_balance(BOB) - bob_before, 80);
+        assert_noop!(
+            ServiceMarket::finalize_cancellation(RuntimeOrigin::signed(CHARLIE), 0),
+            Error::<Test>::NoCancellationRequested
+        );
+    });
+}
+
+// =========================================================
+// Provider onboarding tests
+// =========================================================
+
+/// Drop ALICE below `MinListingReputation` and have her apply.
+fn apply_low_reputation_alice() {
+    assert_ok!(pallet_reputation::Pallet::<Test>::slash_reputation(
+        RuntimeOrigin::root(),
+        ALICE,
+        5000,
+        b"test slash".to_vec(),
+    ));
+    assert_ok!(ServiceMarket::apply_for_listing(RuntimeOrigin::signed(
+        ALICE
+    )));
+}
+
+#[test]
+fn listing_application_approved_by_provider_votes() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            ServiceMarket::apply_for_listing(RuntimeOrigin::signed(BOB)),
+            Error::<Test>::ApplicationNotNeeded
+        );
+        apply_low_reputation_alice();
+        assert_eq!(Balances::reserved_balance(ALICE), 200);
+        assert_noop!(
+            ServiceMarket::apply_for_listing(RuntimeOrigin::signed(ALICE)),
+            Error::<Test>::ApplicationAlreadyExists
+        );
+        assert_noop!(
+            list_service_default(ALICE),
+            Error::<Test>::InsufficientReputation
+        );
+
+        assert_ok!(ServiceMarket::vote_on_application(
+            RuntimeOrigin::signed(BOB),
+            ALICE,
+            true
+        ));
+        assert_noop!(
+            ServiceMarket::vote_on_application(RuntimeOrigin::signed(BOB), ALICE, true),
+            Error::<Test>::AlreadyVoted
+        );
+        assert_ok!(ServiceMarket::vote_on_application(
+            RuntimeOrigin::signed(CHARLIE),
+            ALICE,
+            true
+        ));
+        System::assert_last_event(Event::ListingApplicationApproved { applicant: ALICE }.into());
+        assert!(ListingApplications::<Test>::get(ALICE).is_none());
+
+        // One provisional listing only.
+        assert_ok!(list_service_default(ALICE));
+        assert_noop!(
+            list_service_default(ALICE),
+            Error::<Test>::ProvisionalSlotTaken
+        );
+    });
+}
+
+#[test]
+fn rejected_application_returns_bond() {
+    new_test_ext().execute_with(|| {
+        apply_low_reputation_alice();
+        assert_noop!(
+            ServiceMarket::decide_application(RuntimeOrigin::signed(BOB), ALICE, false),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_ok!(ServiceMarket::decide_application(
+            RuntimeOrigin::root(),
+            ALICE,
+            false
+        ));
+
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+        assert!(ProvisionalProviders::<Test>::get(ALICE).is_none());
+        assert_noop!(
+            list_service_default(ALICE),
+            Error::<Test>::InsufficientReputation
+        );
+    });
+}
+
+#[test]
+fn provisional_provider_pays_sla_penalty_then_graduates() {
+    new_test_ext().execute_with(|| {
+        apply_low_reputation_alice();
+        assert_ok!(ServiceMarket::decide_application(
+            RuntimeOrigin::root(),
+            ALICE,
+            true
+        ));
+        assert_ok!(list_service_default(ALICE));
+
+        // Missed deadline: 50 of the bond goes to the invoker.
+        assert_ok!(ServiceMarket::invoke_service(
+            RuntimeOrigin::signed(BOB),
+            0,
+            b"req".to_vec(),
+            None,
+            100,
+            10,
+            None,
+            false,
+            None,
+        ));
+        System::set_block_number(50);
+        let bob_before = Balances::free_balance(BOB);
+        assert_ok!(ServiceMarket::try_expire_invocation(
+            RuntimeOrigin::signed(CHARLIE),
+            0
+        ));
+        assert_eq!(Balances::free_balance(BOB) - bob_before, 90 + 50);
+        assert_eq!(ProvisionalProviders::<Test>::get(ALICE).unwrap().bond, 150);
+
+        // Two fully approved invocations complete probation.
+        for id in 1..=2 {
+            assert_ok!(invoke_service_default(BOB, 0));
+            accept_default(id);
+            assert_ok!(ServiceMarket::submit_invocation_work(
                 RuntimeOrigin::signed(ALICE),
+                id,
+                None,
+                b"proof".to_vec(),
+                ProofType::Hash,
+            ));
+            assert_ok!(ServiceMarket::approve_milestone(
+                RuntimeOrigin::signed(BOB),
+                id,
                 0,
-                b"counter".to_vec(),
+            ));
+        }
+
+        assert!(ProvisionalProviders::<Test>::get(ALICE).is_none());
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+        System::assert_has_event(
+            Event::ProviderGraduated {
+                provider: ALICE,
+                bond_returned: 150,
+            }
+            .into(),
+        );
+    });
+}
+
+// =========================================================
+// Trial invocation tests
+// =========================================================
+
+fn invoke_trial(invoker: u64, listing_id: ListingId) -> DispatchResult {
+    ServiceMarket::invoke_service(
+        RuntimeOrigin::signed(invoker),
+        listing_id,
+        b"trial run".to_vec(),
+        None,
+        100,
+        100,
+        None,
+        true,
+        None,
+    )
+}
+
+#[test]
+fn trial_invocation_is_limited_to_one_per_listing() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_ok!(invoke_trial(BOB, 0));
+        assert!(ServiceInvocations::<Test>::get(0).unwrap().trial);
+        assert!(TrialsUsed::<Test>::contains_key(0, BOB));
+
+        assert_noop!(invoke_trial(BOB, 0), Error::<Test>::TrialAlreadyUsed);
+        // Other invokers still get their own trial.
+        assert_ok!(invoke_trial(CHARLIE, 0));
+        // Paid invocations are unaffected.
+        assert_ok!(invoke_service_default(BOB, 0));
+    });
+}
+
+#[test]
+fn trial_invocation_rejects_price_above_cap_and_milestones() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(ServiceMarket::list_service(
+            RuntimeOrigin::signed(ALICE),
+            b"Premium".to_vec(),
+            b"Expensive".to_vec(),
+            vec![b"ai/llm-inference".to_vec()],
+            500,
+            500,
+            PaymentMode::Escrow,
+            10,
+            50,
+            0,
+            None,
+            false,
+        ));
+        assert_noop!(
+            ServiceMarket::invoke_service(
+                RuntimeOrigin::signed(BOB),
+                0,
+                b"trial".to_vec(),
+                None,
+                500,
+                100,
+                None,
+                true,
                 None,
             ),
-            Error::<Test>::InvocationAlreadyDisputed
+            Error::<Test>::TrialPriceTooHigh
+        );
+
+        assert_ok!(list_service_default(ALICE));
+        assert_noop!(
+            ServiceMarket::invoke_service(
+                RuntimeOrigin::signed(BOB),
+                1,
+                b"trial".to_vec(),
+                Some(vec![MilestoneSpec { pct_of_total: 100 }]),
+                100,
+                100,
+                None,
+                true,
+                None,
+            ),
+            Error::<Test>::TrialMilestonesNotAllowed
         );
     });
 }
 
 #[test]
-fn provider_can_raise_dispute() {
+fn reject_trial_refunds_in_full_within_window() {
     new_test_ext().execute_with(|| {
         assert_ok!(list_service_default(ALICE));
         assert_ok!(invoke_service_default(BOB, 0));
+        assert_ok!(invoke_trial(BOB, 0));
+        assert_ok!(invoke_trial(CHARLIE, 0));
 
-        assert_ok!(ServiceMarket::raise_dispute(
-            RuntimeOrigin::signed(ALICE), // provider raises dispute
+        assert_noop!(
+            ServiceMarket::reject_trial(RuntimeOrigin::signed(BOB), 0),
+            Error::<Test>::NotTrialInvocation
+        );
+
+        let bob_before = Balances::free_balance(BOB);
+        accept_default(1);
+        assert_ok!(ServiceMarket::submit_invocation_work(
+            RuntimeOrigin::signed(ALICE),
+            1,
+            None,
+            b"proof".to_vec(),
+            ProofType::Hash,
+        ));
+        assert_ok!(ServiceMarket::reject_trial(RuntimeOrigin::signed(BOB), 1));
+        assert_eq!(Balances::free_balance(BOB) - bob_before, 100);
+        assert_eq!(
+            ServiceInvocations::<Test>::get(1).unwrap().status,
+            InvocationStatus::Cancelled
+        );
+
+        // Invoked at block 1 with a 20-block window.
+        System::set_block_number(21);
+        assert_noop!(
+            ServiceMarket::reject_trial(RuntimeOrigin::signed(CHARLIE), 2),
+            Error::<Test>::TrialWindowClosed
+        );
+    });
+}
+
+#[test]
+fn subscription_pays_per_period_and_cancels_pro_rata() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(list_service_default(ALICE));
+        assert_noop!(
+            ServiceMarket::subscribe_service(RuntimeOrigin::signed(BOB), 0, 100, 10, 13),
+            Error::<Test>::InvalidSubscriptionTerms
+        );
+        assert_noop!(
+            ServiceMarket::subscribe_service(RuntimeOrigin::signed(ALICE), 0, 100, 10, 3),
+            Error::<Test>::SelfSubscription
+        );
+
+        // Three 10-block periods at 100 each, starting at block 1.
+        let bob_before = Balances::free_balance(BOB);
+        let alice_before = Balances::free_balance(ALICE);
+        assert_ok!(ServiceMarket::subscribe_service(
+            RuntimeOrigin::signed(BOB),
+            0,
+            100,
+            10,
+            3
+        ));
+        assert_eq!(bob_before - Balances::free_balance(BOB), 300);
+
+        run_sweeps_to(10);
+        assert_eq!(Balances::free_balance(ALICE), alice_before);
+        run_sweeps_to(11);
+        assert_eq!(Balances::free_balance(ALICE) - alice_before, 100);
+        assert_eq!(Subscriptions::<Test>::get(0).unwrap().periods_remaining, 2);
+
+        // Paused at 15 with six blocks of the period left; resumed at 30.
+        System::set_block_number(15);
+        assert_noop!(
+            ServiceMarket::set_subscription_paused(RuntimeOrigin::signed(ALICE), 0, true),
+            Error::<Test>::NotSubscriber
+        );
+        assert_ok!(ServiceMarket::set_subscription_paused(
+            RuntimeOrigin::signed(BOB),
+            0,
+            true
+        ));
+        run_sweeps_to(21);
+        assert_eq!(Balances::free_balance(ALICE) - alice_before, 100);
+
+        System::set_block_number(30);
+        assert_ok!(ServiceMarket::set_subscription_paused(
+            RuntimeOrigin::signed(BOB),
             0,
-            b"Invoker not cooperating".to_vec(),
-            None,
+            false
         ));
+        assert_eq!(Subscriptions::<Test>::get(0).unwrap().next_payment, 36);
 
-        let inv = ServiceInvocations::<Test>::get(0).unwrap();
-        assert_eq!(inv.status, InvocationStatus::Disputed);
+        // Cancelled seven blocks into the period: 70 to the provider, 130 back.
+        System::set_block_number(33);
+        assert_ok!(ServiceMarket::cancel_subscription(
+            RuntimeOrigin::signed(ALICE),
+            0
+        ));
+        System::assert_last_event(
+            Event::SubscriptionCancelled {
+                subscription_id: 0,
+                to_provider: 70,
+                refunded: 130,
+            }
+            .into(),
+        );
+        assert_eq!(Balances::free_balance(ALICE) - alice_before, 170);
+        assert_eq!(bob_before - Balances::free_balance(BOB), 170);
+        assert!(Subscriptions::<Test>::get(0).is_none());
+        assert_eq!(
+            Balances::free_balance(ServiceMarket::subscription_escrow_account(0)),
+            0
+        );
     });
 }
 
 #[test]
-fn resolve_dispute_governance_transfers_to_winner() {
+fn arbitration_panel_majority_settles_dispute_and_pays_arbiters() {
     new_test_ext().execute_with(|| {
+        const EVE: u64 = 5;
+        <Balances as frame_support::traits::Currency<u64>>::make_free_balance_be(&EVE, 1_000);
+
         assert_ok!(list_service_default(ALICE));
-        assert_ok!(invoke_service_default(BOB, 0)); // BOB locked 100 in escrow
+        assert_ok!(invoke_service_default(BOB, 0));
         assert_ok!(ServiceMarket::raise_dispute(
             RuntimeOrigin::signed(BOB),
             0,
-            b"reason".to_vec(),
+            b"no delivery".to_vec(),
             None,
         ));
 
-        let alice_before = Balances::free_balance(ALICE);
+        // Standard track: three seats, juror stake 10, reward 5.
+        assert_ok!(ServiceMarket::join_arbiter_pool(
+            RuntimeOrigin::signed(CHARLIE),
+            10
+        ));
+        assert_ok!(ServiceMarket::join_arbiter_pool(
+            RuntimeOrigin::signed(DAVE),
+            20
+        ));
+        assert_noop!(
+            ServiceMarket::escalate_to_arbitration(RuntimeOrigin::signed(BOB), 0),
+            Error::<Test>::NotEnoughArbiters
+        );
+        assert_ok!(ServiceMarket::join_arbiter_pool(
+            RuntimeOrigin::signed(EVE),
+            30
+        ));
 
-        assert_ok!(ServiceMarket::resolve_dispute_governance(
-            RuntimeOrigin::root(),
-            0,
-            ALICE, // provider wins
+        let bob_before = Balances::free_balance(BOB);
+        assert_ok!(ServiceMarket::escalate_to_arbitration(
+            RuntimeOrigin::signed(BOB),
+            0
         ));
+        assert_eq!(bob_before - Balances::free_balance(BOB), 15);
+        assert_eq!(
+            Disputes::<Test>::get(0).unwrap().status,
+            DisputeStatus::Arbitration
+        );
+        assert_noop!(
+            ServiceMarket::leave_arbiter_pool(RuntimeOrigin::signed(CHARLIE)),
+            Error::<Test>::ArbiterOnPanel
+        );
 
-        let alice_after = Balances::free_balance(ALICE);
-        // Alice received the escrow (minus existential deposit remainder)
-        assert!(alice_after > alice_before);
+        assert_noop!(
+            ServiceMarket::cast_arbitration_vote(RuntimeOrigin::signed(ALICE), 0, ALICE, None),
+            Error::<Test>::NotOnPanel
+        );
+        assert_noop!(
+            ServiceMarket::cast_arbitration_vote(RuntimeOrigin::signed(CHARLIE), 0, DAVE, None),
+            Error::<Test>::InvalidDisputeWinner
+        );
+        assert_ok!(ServiceMarket::cast_arbitration_vote(
+            RuntimeOrigin::signed(CHARLIE),
+            0,
+            BOB,
+            None
+        ));
+        assert_ok!(ServiceMarket::cast_arbitration_vote(
+            RuntimeOrigin::signed(EVE),
+            0,
+            BOB,
+            None
+        ));
+        System::assert_last_event(
+            Event::DisputeResolvedByArbitration {
+                dispute_id: 0,
+                winner: BOB,
+                amount: 100,
+            }
+            .into(),
+        );
 
-        let dispute = Disputes::<Test>::get(0).unwrap();
-        assert_eq!(dispute.status, DisputeStatus::Resolved);
-        assert_eq!(dispute.winner, Some(ALICE));
+        // Escrow back to BOB, the unearned seat's reward refunded.
+        assert_eq!(Balances::free_balance(BOB) - bob_before, 100 - 10);
+        assert_eq!(Balances::free_balance(EVE), 1_000 - 30 + 5);
+        assert!(ArbitrationPanels::<Test>::get(0).is_none());
+        assert!(ArbitrationsByDeadline::<Test>::iter().next().is_none());
+        assert_ok!(ServiceMarket::leave_arbiter_pool(RuntimeOrigin::signed(
+            DAVE
+        )));
+        assert_eq!(Balances::reserved_balance(DAVE), 0);
     });
 }
 
 #[test]
-fn resolve_dispute_governance_fails_not_root() {
+fn arbitration_past_voting_deadline_goes_to_governance() {
     new_test_ext().execute_with(|| {
+        const EVE: u64 = 5;
+        <Balances as frame_support::traits::Currency<u64>>::make_free_balance_be(&EVE, 1_000);
+
         assert_ok!(list_service_default(ALICE));
         assert_ok!(invoke_service_default(BOB, 0));
         assert_ok!(ServiceMarket::raise_dispute(
             RuntimeOrigin::signed(BOB),
             0,
-            b"reason".to_vec(),
+            b"no delivery".to_vec(),
             None,
         ));
+        for (arbiter, stake) in [(CHARLIE, 10), (DAVE, 20), (EVE, 30)] {
+            assert_ok!(ServiceMarket::join_arbiter_pool(
+                RuntimeOrigin::signed(arbiter),
+                stake
+            ));
+        }
+        assert_ok!(ServiceMarket::escalate_to_arbitration(
+            RuntimeOrigin::signed(BOB),
+            0
+        ));
 
-        assert_noop!(
-            ServiceMarket::resolve_dispute_governance(RuntimeOrigin::signed(CHARLIE), 0, ALICE),
-            sp_runtime::DispatchError::BadOrigin
+        // Escalated at block 1 with a 10-block voting period.
+        run_sweeps_to(11);
+        assert_eq!(
+            Disputes::<Test>::get(0).unwrap().status,
+            DisputeStatus::Arbitration
+        );
+
+        run_sweeps_to(12);
+        System::assert_has_event(Event::ArbitrationExpired { dispute_id: 0 }.into());
+        assert_eq!(
+            Disputes::<Test>::get(0).unwrap().status,
+            DisputeStatus::Escalated
+        );
+        assert!(ArbitrationPanels::<Test>::get(0).is_none());
+        assert!(ArbitrationsByDeadline::<Test>::iter().next().is_none());
+        assert_eq!(
+            Balances::free_balance(ServiceMarket::arbitration_fee_account(0)),
+            0
         );
+        assert_eq!(ArbitrationCursor::<Test>::get(), 12);
     });
 }
 
+// =========================================================
+// Competition tests
+// =========================================================
+
+fn open_competition_default(invoker: u64) -> DispatchResult {
+    ServiceMarket::open_competition(
+        RuntimeOrigin::signed(invoker),
+        b"ai/llm-inference".to_vec(),
+        b"bafy-task".to_vec(),
+        300,
+        20,
+        3,
+        10, // deadline = block 11
+    )
+}
+
 #[test]
-fn dispute_not_found_returns_error() {
+fn competition_pays_winner_and_participation_fees() {
     new_test_ext().execute_with(|| {
+        for provider in [ALICE, BOB, CHARLIE] {
+            assert_ok!(list_service_default(provider));
+        }
+        let dave_before = Balances::free_balance(DAVE);
+        assert_ok!(open_competition_default(DAVE));
+        assert_eq!(Balances::free_balance(DAVE), dave_before - 340);
+
         assert_noop!(
-            ServiceMarket::resolve_dispute_governance(RuntimeOrigin::root(), 999, ALICE),
-            Error::<Test>::DisputeNotFound
+            ServiceMarket::enter_competition(RuntimeOrigin::signed(BOB), 0, 0),
+            Error::<Test>::NotProvider
+        );
+        for (provider, listing) in [(ALICE, 0), (BOB, 1), (CHARLIE, 2)] {
+            assert_ok!(ServiceMarket::enter_competition(
+                RuntimeOrigin::signed(provider),
+                0,
+                listing
+            ));
+        }
+        assert_ok!(list_service_default(DAVE));
+        assert_noop!(
+            ServiceMarket::enter_competition(RuntimeOrigin::signed(DAVE), 0, 3),
+            Error::<Test>::CannotEnterOwnCompetition
+        );
+
+        for provider in [ALICE, BOB] {
+            assert_ok!(ServiceMarket::submit_competition_result(
+                RuntimeOrigin::signed(provider),
+                0,
+                b"bafy-result".to_vec()
+            ));
+        }
+        assert_noop!(
+            ServiceMarket::award_competition(RuntimeOrigin::signed(DAVE), 0, CHARLIE),
+            Error::<Test>::NoSubmission
+        );
+
+        let before = [ALICE, BOB, CHARLIE].map(Balances::free_balance);
+        let dave_before = Balances::free_balance(DAVE);
+        assert_ok!(ServiceMarket::award_competition(
+            RuntimeOrigin::signed(DAVE),
+            0,
+            BOB
+        ));
+        assert_eq!(Balances::free_balance(ALICE), before[0] + 20);
+        assert_eq!(Balances::free_balance(BOB), before[1] + 300);
+        assert_eq!(Balances::free_balance(CHARLIE), before[2]);
+        assert_eq!(Balances::free_balance(DAVE), dave_before + 20);
+        assert_eq!(
+            Balances::free_balance(ServiceMarket::competition_escrow_account(0)),
+            0
         );
+        assert!(Competitions::<Test>::get(0).is_none());
     });
 }
 
-// =========================================================
-// Edge case tests
-// =========================================================
-
 #[test]
-fn multiple_listings_multiple_invocations() {
+fn unawarded_competition_splits_reward_after_award_period() {
     new_test_ext().execute_with(|| {
-        // ALICE lists 2 services
         assert_ok!(list_service_default(ALICE));
-        assert_ok!(ServiceMarket::list_service(
+        assert_ok!(open_competition_default(DAVE));
+        assert_ok!(ServiceMarket::enter_competition(
             RuntimeOrigin::signed(ALICE),
-            b"Storage Service".to_vec(),
-            b"Distributed storage".to_vec(),
-            vec![b"infra/storage".to_vec()],
-            200,
-            200,
-            PaymentMode::Escrow,
-            5,
-            20,
             0,
-            None,
-            false,
+            0
+        ));
+        assert_noop!(
+            ServiceMarket::close_competition(RuntimeOrigin::signed(DAVE), 0),
+            Error::<Test>::CompetitionStillRunning
+        );
+        assert_ok!(ServiceMarket::submit_competition_result(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            b"bafy-result".to_vec()
         ));
 
-        // BOB and CHARLIE both invoke listing 0
-        assert_ok!(invoke_service_default(BOB, 0));
-        assert_ok!(invoke_service_default(CHARLIE, 0));
-
-        assert_eq!(InvocationCount::<Test>::get(), 2);
+        System::set_block_number(21);
+        assert_noop!(
+            ServiceMarket::close_competition(RuntimeOrigin::signed(CHARLIE), 0),
+            Error::<Test>::CompetitionStillRunning
+        );
 
-        // Listing 0 total invocations = 2
-        let listing = ServiceListings::<Test>::get(0).unwrap();
-        assert_eq!(listing.total_invocations, 2);
+        System::set_block_number(22);
+        let alice_before = Balances::free_balance(ALICE);
+        let dave_before = Balances::free_balance(DAVE);
+        assert_ok!(ServiceMarket::close_competition(
+            RuntimeOrigin::signed(CHARLIE),
+            0
+        ));
+        assert_eq!(Balances::free_balance(ALICE), alice_before + 300);
+        assert_eq!(Balances::free_balance(DAVE), dave_before + 40);
     });
 }
 
+// =========================================================
+// Build attestation tests
+// =========================================================
+
 #[test]
-fn listing_not_found_on_invoke() {
+fn revoked_build_attestation_pauses_listings_until_rebound() {
     new_test_ext().execute_with(|| {
+        let build = H256::repeat_byte(1);
+        let rebuild = H256::repeat_byte(2);
+        assert_ok!(list_service_default(ALICE));
+
         assert_noop!(
-            invoke_service_default(BOB, 42),
-            Error::<Test>::ListingNotFound
+            ServiceMarket::attest_build(
+                RuntimeOrigin::signed(DAVE),
+                build,
+                b"rustc 1.84".to_vec(),
+                vec![7; 64]
+            ),
+            Error::<Test>::NotBuildAttestor
         );
-    });
-}
+        assert_ok!(ServiceMarket::set_build_attestor(
+            RuntimeOrigin::root(),
+            DAVE,
+            true
+        ));
+        for code_hash in [build, rebuild] {
+            assert_ok!(ServiceMarket::attest_build(
+                RuntimeOrigin::signed(DAVE),
+                code_hash,
+                b"rustc 1.84".to_vec(),
+                vec![7; 64]
+            ));
+        }
+
+        // An invoker requiring attestation cannot use the unbound listing.
+        assert_ok!(ServiceMarket::set_attestation_requirement(
+            RuntimeOrigin::signed(BOB),
+            true
+        ));
+        assert_noop!(
+            invoke_service_default(BOB, 0),
+            Error::<Test>::ListingNotAttested
+        );
+        assert_noop!(
+            ServiceMarket::bind_build_attestation(RuntimeOrigin::signed(BOB), 0, build),
+            Error::<Test>::NotProvider
+        );
+        assert_ok!(ServiceMarket::bind_build_attestation(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            build
+        ));
+        assert_ok!(invoke_service_default(BOB, 0));
 
-#[test]
-fn escrow_account_derivation_is_deterministic() {
-    new_test_ext().execute_with(|| {
-        // Calling twice with the same ID returns the same account.
-        let escrow0a = ServiceMarket::invocation_escrow_account(0);
-        let escrow0b = ServiceMarket::invocation_escrow_account(0);
-        assert_eq!(escrow0a, escrow0b);
-        // (Uniqueness across IDs is guaranteed in production with 32-byte AccountId;
-        //  with u64 AccountId in tests the truncation collapses them — that is expected.)
+        // Only the attestor or governance may revoke.
+        assert_noop!(
+            ServiceMarket::revoke_build_attestation(RuntimeOrigin::signed(CHARLIE), build),
+            Error::<Test>::NotBuildAttestor
+        );
+        assert_ok!(ServiceMarket::revoke_build_attestation(
+            RuntimeOrigin::signed(DAVE),
+            build
+        ));
+        System::assert_last_event(
+            Event::BuildAttestationRevoked {
+                code_hash: build,
+                paused_listings: 1,
+            }
+            .into(),
+        );
+        assert!(!ServiceListings::<Test>::get(0).unwrap().active);
+        assert!(ListingBuilds::<Test>::get(0).is_none());
+
+        assert_ok!(ServiceMarket::bind_build_attestation(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            rebuild
+        ));
+        assert!(ServiceListings::<Test>::get(0).unwrap().active);
+        assert_eq!(ListingBuilds::<Test>::get(0), Some(rebuild));
+        assert_eq!(ListingsByBuild::<Test>::get(rebuild).to_vec(), vec![0]);
     });
 }
 
+// =========================================================
+// Allowlist tests
+// =========================================================
+
 #[test]
-fn submit_work_with_cid_proof_type() {
+fn private_listing_only_serves_allowlisted_invokers() {
     new_test_ext().execute_with(|| {
         assert_ok!(list_service_default(ALICE));
+        assert_noop!(
+            ServiceMarket::set_listing_allowlist(RuntimeOrigin::signed(BOB), 0, vec![BOB]),
+            Error::<Test>::NotProvider
+        );
+        assert_noop!(
+            ServiceMarket::set_listing_allowlist(
+                RuntimeOrigin::signed(ALICE),
+                0,
+                vec![BOB, CHARLIE, DAVE, 5]
+            ),
+            Error::<Test>::TooManyAllowlistEntries
+        );
+        assert_ok!(ServiceMarket::set_listing_allowlist(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            vec![BOB, BOB]
+        ));
+        assert_eq!(
+            ListingAllowlists::<Test>::get(0).unwrap().to_vec(),
+            vec![BOB]
+        );
+
         assert_ok!(invoke_service_default(BOB, 0));
-        assert_ok!(ServiceMarket::submit_invocation_work(
+        assert_noop!(
+            invoke_service_default(CHARLIE, 0),
+            Error::<Test>::NotOnAllowlist
+        );
+        assert_noop!(
+            ServiceMarket::subscribe_service(RuntimeOrigin::signed(CHARLIE), 0, 100, 10, 2),
+            Error::<Test>::NotOnAllowlist
+        );
+
+        assert_ok!(ServiceMarket::set_listing_allowlist(
             RuntimeOrigin::signed(ALICE),
             0,
-            None,
-            b"QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_vec(),
-            ProofType::Cid,
+            vec![]
         ));
-        let proof = InvocationProofs::<Test>::get(0, u32::MAX).unwrap();
-        assert_eq!(proof.proof_type, ProofType::Cid);
+        assert!(ListingAllowlists::<Test>::get(0).is_none());
+        assert_ok!(invoke_service_default(CHARLIE, 0));
     });
 }
 
+fn complete_invocation(invocation_id: InvocationId) {
+    accept_default(invocation_id);
+    assert_ok!(ServiceMarket::submit_invocation_work(
+        RuntimeOrigin::signed(ALICE),
+        invocation_id,
+        None,
+        b"proof".to_vec(),
+        ProofType::Hash,
+    ));
+    assert_ok!(ServiceMarket::approve_milestone(
+        RuntimeOrigin::signed(BOB),
+        invocation_id,
+        0,
+    ));
+}
+
 #[test]
-fn invoke_service_with_x402_payment_mode_listed() {
+fn deferred_settlement_batches_payouts_into_statements() {
     new_test_ext().execute_with(|| {
-        // List a service with X402 payment mode
-        assert_ok!(ServiceMarket::list_service(
+        assert_noop!(
+            ServiceMarket::claim_settlement(RuntimeOrigin::signed(ALICE)),
+            Error::<Test>::NoSettlement
+        );
+        assert_ok!(ServiceMarket::set_deferred_settlement(
             RuntimeOrigin::signed(ALICE),
-            b"API Service".to_vec(),
-            b"desc".to_vec(),
-            vec![],
-            100,
-            100,
-            PaymentMode::X402,
-            10,
-            50,
-            0,
-            None,
-            false,
+            true
         ));
-        let listing = ServiceListings::<Test>::get(0).unwrap();
-        assert!(matches!(listing.payment_mode, PaymentMode::X402));
+        assert_ok!(list_service_default(ALICE));
+        for _ in 0..4 {
+            assert_ok!(invoke_service_default(BOB, 0));
+        }
+        let alice_before = Balances::free_balance(ALICE);
+
+        complete_invocation(0);
+        complete_invocation(1);
+        assert_eq!(Balances::free_balance(ALICE), alice_before);
+        let statement = SettlementStatements::<Test>::get(ALICE).unwrap();
+        assert_eq!(statement.total, 200);
+        assert_eq!(statement.due, 51);
+        assert_eq!(
+            statement.entries.to_vec(),
+            vec![
+                SettlementEntry {
+                    invocation_id: 0,
+                    amount: 100,
+                    fees: 0
+                },
+                SettlementEntry {
+                    invocation_id: 1,
+                    amount: 100,
+                    fees: 0
+                },
+            ]
+        );
+        assert_eq!(
+            Balances::free_balance(ServiceMarket::settlement_account(&ALICE)),
+            200
+        );
+        assert_ne!(
+            ServiceMarket::settlement_account(&ALICE),
+            ServiceMarket::settlement_account(&BOB)
+        );
+
+        // A full statement is paid out before the next payout is recorded.
+        System::set_block_number(5);
+        complete_invocation(2);
+        assert_eq!(Balances::free_balance(ALICE) - alice_before, 200);
+        let statement = SettlementStatements::<Test>::get(ALICE).unwrap();
+        assert_eq!(
+            (statement.total, statement.entries.len(), statement.due),
+            (100, 1, 55)
+        );
+        assert!(!SettlementsDue::<Test>::contains_key(51, ALICE));
+
+        // Paid on demand.
+        assert_ok!(ServiceMarket::claim_settlement(RuntimeOrigin::signed(
+            ALICE
+        )));
+        assert_eq!(Balances::free_balance(ALICE) - alice_before, 300);
+        assert!(SettlementStatements::<Test>::get(ALICE).is_none());
+        assert!(SettlementsDue::<Test>::iter().next().is_none());
+
+        // Paid by the sweep once due.
+        complete_invocation(3);
+        run_sweeps_to(54);
+        assert!(SettlementStatements::<Test>::get(ALICE).is_some());
+        run_sweeps_to(55);
+        assert_eq!(Balances::free_balance(ALICE) - alice_before, 400);
+        assert!(SettlementStatements::<Test>::get(ALICE).is_none());
+        assert_eq!(
+            Balances::free_balance(ServiceMarket::settlement_account(&ALICE)),
+            0
+        );
+        System::assert_last_event(
+            Event::SettlementPaid {
+                payee: ALICE,
+                total: 100,
+                entries: vec![SettlementEntry {
+                    invocation_id: 3,
+                    amount: 100,
+                    fees: 0,
+                }]
+                .try_into()
+                .unwrap(),
+            }
+            .into(),
+        );
+
+        // Opting out pays directly again.
+        assert_ok!(ServiceMarket::set_deferred_settlement(
+            RuntimeOrigin::signed(ALICE),
+            false
+        ));
+        assert_ok!(invoke_service_default(BOB, 0));
+        complete_invocation(4);
+        assert_eq!(Balances::free_balance(ALICE) - alice_before, 500);
     });
 }
 
 #[test]
-fn listing_count_starts_at_zero() {
+fn insurance_required_above_tag_threshold() {
     new_test_ext().execute_with(|| {
-        assert_eq!(ListingCount::<Test>::get(), 0);
-        assert_eq!(InvocationCount::<Test>::get(), 0);
-        assert_eq!(DisputeCount::<Test>::get(), 0);
+        assert_noop!(
+            ServiceMarket::set_tag_insurance_requirement(
+                RuntimeOrigin::signed(ALICE),
+                b"ai/llm-inference".to_vec(),
+                Some(50)
+            ),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_ok!(ServiceMarket::set_tag_insurance_requirement(
+            RuntimeOrigin::root(),
+            b"ai/llm-inference".to_vec(),
+            Some(50)
+        ));
+        assert_ok!(list_service_default(ALICE));
+        assert_noop!(
+            invoke_service_default(BOB, 0),
+            Error::<Test>::InsuranceRequired
+        );
+
+        // A policy too small for the price does not count.
+        MockInsurance::insure(ALICE, 99, 7);
+        assert_noop!(
+            invoke_service_default(BOB, 0),
+            Error::<Test>::InsuranceRequired
+        );
+        MockInsurance::insure(ALICE, 100, 7);
+        assert_ok!(invoke_service_default(BOB, 0));
+        assert_eq!(InvocationInsurance::<Test>::get(0), Some(7));
+
+        // At or below the threshold no policy is needed or recorded.
+        assert_ok!(ServiceMarket::set_tag_insurance_requirement(
+            RuntimeOrigin::root(),
+            b"ai/llm-inference".to_vec(),
+            Some(100)
+        ));
+        assert_ok!(invoke_service_default(BOB, 0));
+        assert_eq!(InvocationInsurance::<Test>::get(1), None);
+
+        assert_ok!(ServiceMarket::set_tag_insurance_requirement(
+            RuntimeOrigin::root(),
+            b"ai/llm-inference".to_vec(),
+            None
+        ));
+        let tag: BoundedVec<u8, MaxTagLength> = b"ai/llm-inference".to_vec().try_into().unwrap();
+        assert!(!TagInsuranceThresholds::<Test>::contains_key(&tag));
     });
 }
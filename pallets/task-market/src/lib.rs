@@ -6,7 +6,7 @@
 //!
 //! This pallet provides functionality for:
 //! - Posting tasks with CLAW token escrow
-//! - Submitting bids on open tasks
+//! - Submitting bids on open tasks, optionally gated on bidder reputation
 //! - Assigning tasks to selected bidders
 //! - Submitting work and proof of completion
 //! - Approving work and releasing escrow
@@ -17,14 +17,69 @@
 //!
 //! ### Dispatchable Functions
 //!
-//! - `post_task` - Create a task with locked escrow
-//! - `bid_on_task` - Submit a bid on an open task
+//! - `post_task` - Create a task with locked escrow, optionally requiring a worker bond
+//! - `bid_on_task` - Submit a bid on an open task, reserving the bid deposit
+//! - `withdraw_bid` - Bidder retracts a bid and gets the deposit back
 //! - `assign_task` - Poster selects a bidder
 //! - `submit_work` - Worker submits completion proof
+//! - `request_revision` - Poster sends submitted work back for another submission
 //! - `approve_work` - Poster approves and releases payment
 //! - `dispute_task` - Either party disputes the task
 //! - `cancel_task` - Poster cancels (only if still Open)
 //! - `resolve_dispute` - Governance resolves a dispute
+//! - `set_new_work_cutoff` - Governance sets the block after which no new tasks or bids
+//!   are accepted (task-market deprecation in favour of `pallet-service-market`)
+//! - `try_expire_task` - Anyone expires a task past its deadline for a bounty
+//!
+//! ## Expiry
+//!
+//! Tasks are indexed by deadline in `TasksByDeadline`. Once the deadline has
+//! passed, a task that is still `Open`, `Assigned` or `InProgress` is marked
+//! `Expired` and the poster's escrow is released. The pallet sweeps overdue
+//! tasks as a [`BudgetedHook`] run by `pallet-hook-budget`, at most
+//! `MaxExpirationsPerBlock` per block. If the sweep falls behind, anyone may
+//! expire a task with `try_expire_task` and is paid `ExpireBountyShare` of
+//! the reward out of the released escrow. Submitted work (`Completed`) never
+//! expires; it waits for the poster's review or a dispute.
+//!
+//! ## Bids
+//!
+//! A task takes at most `MaxBidsPerTask` bids, counted in `TaskBidCount`;
+//! bidding again replaces the bidder's earlier bid. Each bid reserves
+//! `BidDeposit` from the bidder (zero disables the deposit). The deposit of
+//! the bid the poster assigns is returned on assignment; other bidders get
+//! theirs back by calling `withdraw_bid`, which is also how a bid is
+//! retracted before assignment.
+//!
+//! ## Work Proofs
+//!
+//! Every `submit_work` is kept in `TaskProofs` under the task and its
+//! submission index, with the proof (a hash, CID or attestation reference,
+//! at most `MaxProofLength` bytes) and its type. `WorkSubmitted` carries the
+//! Blake2-256 hash of the proof. The poster may `request_revision` instead
+//! of approving, which returns the task to `InProgress` for the next
+//! submission, up to `MaxSubmissions` per task; earlier submissions stay on
+//! chain as evidence for disputes.
+//!
+//! ## Worker Bonds
+//!
+//! A poster can require a `worker_bond` when posting. The selected bidder
+//! must reserve it when the task is assigned, and gets it back once the work
+//! is approved or a dispute is resolved in the worker's favour. If the task
+//! expires while the worker holds it, or the worker loses a dispute, the
+//! bond goes to the poster.
+//!
+//! ## Migrations
+//!
+//! [`migrations::v2`] cancels every task still `Open` and releases its escrow,
+//! and sets the new-work cutoff if governance has not. Assigned tasks settle
+//! in this pallet as before.
+//!
+//! Migrations that visit every task or bid only queue their work in
+//! `PendingMigrations` during the upgrade. The budgeted hook works through
+//! the queue a bounded number of entries per block before it resumes the
+//! expiry sweep, and the runtime call filter rejects task-market calls until
+//! the queue is empty.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(deprecated, clippy::let_unit_value)]
@@ -33,21 +88,28 @@ extern crate alloc;
 
 pub use pallet::*;
 
+pub mod migrations;
 #[cfg(test)]
 mod tests;
 
 use alloc::vec::Vec;
+use clawchain_primitives::{hook_budget::drain_due, BudgetedHook, EventBytes, HookUsage};
 
 #[frame_support::pallet]
 pub mod pallet {
     use super::*;
     use frame_support::{
         pallet_prelude::*,
-        traits::{Currency, ExistenceRequirement, ReservableCurrency},
+        traits::{BalanceStatus, Currency, ExistenceRequirement, ReservableCurrency},
         PalletId,
     };
     use frame_system::pallet_prelude::*;
     use pallet_reputation::ReputationManager;
+    use sp_core::H256;
+    use sp_runtime::{
+        traits::{One, Saturating, Zero},
+        Perbill,
+    };
 
     /// Type alias for task IDs.
     pub type TaskId = u64;
@@ -109,6 +171,57 @@ pub mod pallet {
         pub assigned_to: Option<T::AccountId>,
         /// When the task was created.
         pub created_at: BlockNumberFor<T>,
+        /// Minimum reputation (basis points) a bidder needs; 0 means ungated.
+        pub min_bidder_reputation: u32,
+    }
+
+    /// Kind of proof attached to a work submission.
+    #[derive(
+        Clone,
+        Encode,
+        Decode,
+        Eq,
+        PartialEq,
+        RuntimeDebug,
+        TypeInfo,
+        MaxEncodedLen,
+        Default,
+        codec::DecodeWithMemTracking,
+    )]
+    pub enum ProofType {
+        /// Content hash.
+        #[default]
+        Hash,
+        /// IPFS CID.
+        Cid,
+        /// Signed attestation.
+        Attestation,
+    }
+
+    /// A work submission.
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    pub struct TaskProof<T: Config> {
+        /// The proof as submitted.
+        pub proof: BoundedVec<u8, T::MaxProofLength>,
+        pub proof_type: ProofType,
+        /// When the work was submitted.
+        pub submitted_at: BlockNumberFor<T>,
+    }
+
+    /// A step of a multi-block storage migration; see [`crate::migrations`].
+    #[derive(
+        Clone, Copy, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen,
+    )]
+    pub enum MigrationStep {
+        /// v1: rewrite `Tasks` with `min_bidder_reputation`.
+        TranslateTasks,
+        /// v1: count the bids already placed into `TaskBidCount`.
+        CountBids,
+        /// v2: cancel `Open` tasks and refund their escrow.
+        RetireOpenTasks,
+        /// v3: index expirable tasks in `TasksByDeadline`.
+        IndexDeadlines,
     }
 
     /// Bid information.
@@ -167,9 +280,40 @@ pub mod pallet {
         /// Maximum number of active tasks per account.
         #[pallet::constant]
         type MaxActiveTasksPerAccount: Get<u32>;
+
+        /// Bidder reputation floor (basis points) applied when the poster does
+        /// not set one. 0 leaves such tasks ungated.
+        #[pallet::constant]
+        type DefaultMinBidderReputation: Get<u32>;
+
+        /// Maximum overdue tasks the per-block sweep expires.
+        #[pallet::constant]
+        type MaxExpirationsPerBlock: Get<u32>;
+
+        /// Share of the reward paid to the caller of `try_expire_task`.
+        #[pallet::constant]
+        type ExpireBountyShare: Get<Perbill>;
+
+        /// Maximum length of a work proof in bytes.
+        #[pallet::constant]
+        type MaxProofLength: Get<u32>;
+
+        /// Maximum work submissions per task, revisions included.
+        #[pallet::constant]
+        type MaxSubmissions: Get<u32>;
+
+        /// Refundable deposit reserved per bid. Zero disables it.
+        #[pallet::constant]
+        type BidDeposit: Get<BalanceOf<Self>>;
     }
 
+    /// v1: bidder reputation floor and `TaskBidCount`.
+    /// v2: open tasks retired ahead of the move to `pallet-service-market`.
+    /// v3: live tasks indexed in `TasksByDeadline`.
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
+
     #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
     // ========== Storage ==========
@@ -197,6 +341,22 @@ pub mod pallet {
         OptionQuery,
     >;
 
+    /// Number of bids held per task, enforced against `MaxBidsPerTask`.
+    #[pallet::storage]
+    pub type TaskBidCount<T: Config> = StorageMap<_, Blake2_128Concat, TaskId, u32, ValueQuery>;
+
+    /// Deposit reserved for each bid still holding one.
+    #[pallet::storage]
+    pub type BidDeposits<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        TaskId,
+        Blake2_128Concat,
+        T::AccountId,
+        BalanceOf<T>,
+        OptionQuery,
+    >;
+
     /// Map from AccountId to their posted task IDs.
     #[pallet::storage]
     #[pallet::getter(fn active_tasks)]
@@ -208,6 +368,52 @@ pub mod pallet {
         ValueQuery,
     >;
 
+    /// Block from which `post_task` and `bid_on_task` are rejected by the runtime
+    /// call filter. Existing tasks can still be settled. `None` = no cutoff.
+    #[pallet::storage]
+    #[pallet::getter(fn new_work_cutoff)]
+    pub type NewWorkCutoff<T: Config> = StorageValue<_, BlockNumberFor<T>, OptionQuery>;
+
+    /// Tasks by deadline, for the expiry sweep. Entries of tasks that settled
+    /// before their deadline are dropped when the sweep reaches them.
+    #[pallet::storage]
+    pub type TasksByDeadline<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        BlockNumberFor<T>,
+        Blake2_128Concat,
+        TaskId,
+        (),
+        OptionQuery,
+    >;
+
+    /// First deadline block the expiry sweep has not finished with. The
+    /// sweep walks `TasksByDeadline` from here, one block at a time.
+    #[pallet::storage]
+    pub type ExpiryCursor<T: Config> = StorageValue<_, BlockNumberFor<T>, ValueQuery>;
+
+    /// Bond the assigned worker must reserve, per task. Reserved from the
+    /// worker while the task is assigned; removed once released or forfeited.
+    #[pallet::storage]
+    pub type WorkerBonds<T: Config> =
+        StorageMap<_, Blake2_128Concat, TaskId, BalanceOf<T>, OptionQuery>;
+
+    /// Migration steps still to run, oldest first. Task-market calls are
+    /// rejected and the expiry sweep waits until this is empty.
+    #[pallet::storage]
+    pub type PendingMigrations<T: Config> =
+        StorageValue<_, BoundedVec<MigrationStep, ConstU32<4>>, ValueQuery>;
+
+    /// Work submissions by task and submission index.
+    #[pallet::storage]
+    pub type TaskProofs<T: Config> =
+        StorageDoubleMap<_, Blake2_128Concat, TaskId, Twox64Concat, u32, TaskProof<T>, OptionQuery>;
+
+    /// Number of work submissions per task; the next submission's index.
+    #[pallet::storage]
+    pub type TaskSubmissionCount<T: Config> =
+        StorageMap<_, Blake2_128Concat, TaskId, u32, ValueQuery>;
+
     // ========== Events ==========
 
     #[pallet::event]
@@ -225,20 +431,31 @@ pub mod pallet {
             bidder: T::AccountId,
             amount: BalanceOf<T>,
         },
+        /// A bid was withdrawn and its deposit returned.
+        BidWithdrawn {
+            task_id: TaskId,
+            bidder: T::AccountId,
+        },
         /// A task was assigned to a worker.
         TaskAssigned {
             task_id: TaskId,
             worker: T::AccountId,
         },
-        /// Work was submitted.
-        WorkSubmitted { task_id: TaskId },
+        /// Work was submitted. `proof_hash` is the Blake2-256 hash of the proof.
+        WorkSubmitted {
+            task_id: TaskId,
+            submission_index: u32,
+            proof_hash: H256,
+        },
+        /// The poster sent submitted work back for another submission.
+        RevisionRequested { task_id: TaskId },
         /// Work was approved and payment released.
         WorkApproved { task_id: TaskId },
         /// A task was disputed.
         TaskDisputed {
             task_id: TaskId,
             disputer: T::AccountId,
-            reason: Vec<u8>,
+            reason: EventBytes,
         },
         /// A task was cancelled.
         TaskCancelled { task_id: TaskId },
@@ -247,6 +464,27 @@ pub mod pallet {
             task_id: TaskId,
             winner: T::AccountId,
         },
+        /// The cutoff for new tasks and bids was set or cleared.
+        NewWorkCutoffSet { cutoff: Option<BlockNumberFor<T>> },
+        /// A task passed its deadline unfinished and its escrow was released.
+        /// `expired_by` is `None` when the per-block sweep expired it.
+        TaskExpired {
+            task_id: TaskId,
+            expired_by: Option<T::AccountId>,
+            bounty: BalanceOf<T>,
+        },
+        /// The worker's bond was reserved on assignment.
+        WorkerBondReserved {
+            task_id: TaskId,
+            worker: T::AccountId,
+            amount: BalanceOf<T>,
+        },
+        /// The worker's bond was paid to the poster.
+        WorkerBondForfeited {
+            task_id: TaskId,
+            worker: T::AccountId,
+            amount: BalanceOf<T>,
+        },
     }
 
     // ========== Errors ==========
@@ -283,6 +521,18 @@ pub mod pallet {
         InsufficientBalance,
         /// Bidder does not meet minimum reputation requirement.
         InsufficientReputation,
+        /// The task's deadline has not passed yet.
+        DeadlineNotPassed,
+        /// The selected bidder cannot reserve the task's worker bond.
+        InsufficientWorkerBond,
+        /// Proof exceeds maximum length.
+        ProofTooLong,
+        /// The task has reached `MaxSubmissions`.
+        TooManySubmissions,
+        /// The bidder cannot reserve the bid deposit.
+        InsufficientBidDeposit,
+        /// The bid was accepted and the task assigned to the bidder.
+        BidAccepted,
     }
 
     // ========== Extrinsics ==========
@@ -296,14 +546,19 @@ pub mod pallet {
         /// * `description` - Detailed description
         /// * `reward` - CLAW tokens to pay (locked immediately)
         /// * `deadline` - Block number deadline
+        /// * `min_bidder_reputation` - Reputation floor for bidders (basis points);
+        ///   `None` uses `DefaultMinBidderReputation`
+        /// * `worker_bond` - Bond the assigned worker must reserve; `None` for no bond
         #[pallet::call_index(0)]
-        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 4))]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 5))]
         pub fn post_task(
             origin: OriginFor<T>,
             title: Vec<u8>,
             description: Vec<u8>,
             reward: BalanceOf<T>,
             deadline: BlockNumberFor<T>,
+            min_bidder_reputation: Option<u32>,
+            worker_bond: Option<BalanceOf<T>>,
         ) -> DispatchResult {
             let poster = ensure_signed(origin)?;
 
@@ -332,10 +587,16 @@ pub mod pallet {
                 status: TaskStatus::Open,
                 assigned_to: None,
                 created_at: current_block,
+                min_bidder_reputation: min_bidder_reputation
+                    .unwrap_or_else(T::DefaultMinBidderReputation::get),
             };
 
             Tasks::<T>::insert(task_id, task_info);
             TaskCount::<T>::put(task_id.saturating_add(1));
+            TasksByDeadline::<T>::insert(deadline, task_id, ());
+            if let Some(bond) = worker_bond.filter(|bond| !bond.is_zero()) {
+                WorkerBonds::<T>::insert(task_id, bond);
+            }
 
             // Add to poster's active tasks
             ActiveTasks::<T>::try_mutate(&poster, |tasks| {
@@ -363,7 +624,7 @@ pub mod pallet {
         /// * `amount` - How much you'll do it for
         /// * `proposal` - Your proposal text
         #[pallet::call_index(1)]
-        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 2))]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(4, 4))]
         pub fn bid_on_task(
             origin: OriginFor<T>,
             task_id: TaskId,
@@ -383,18 +644,36 @@ pub mod pallet {
             let current_block = <frame_system::Pallet<T>>::block_number();
             ensure!(current_block < task.deadline, Error::<T>::TaskExpired);
 
-            // Optional: Check minimum reputation (example: 3000 = 30%)
-            // Uncomment to enforce:
-            // ensure!(
-            //     T::ReputationManager::meets_minimum_reputation(&bidder, 3000),
-            //     Error::<T>::InsufficientReputation
-            // );
+            // Per-task bidder reputation gate
+            if task.min_bidder_reputation > 0 {
+                ensure!(
+                    T::ReputationManager::meets_minimum_reputation(
+                        &bidder,
+                        task.min_bidder_reputation
+                    ),
+                    Error::<T>::InsufficientReputation
+                );
+            }
 
             let bounded_proposal: BoundedVec<u8, T::MaxProposalLength> = proposal
                 .try_into()
                 .map_err(|_| Error::<T>::ProposalTooLong)?;
 
-            // Check bid count (simple check: if we can insert, there's space)
+            // A new bidder takes a slot and reserves the deposit; a repeat
+            // bid replaces the earlier one.
+            if !TaskBids::<T>::contains_key(task_id, &bidder) {
+                let count = TaskBidCount::<T>::get(task_id);
+                ensure!(count < T::MaxBidsPerTask::get(), Error::<T>::TooManyBids);
+
+                let deposit = T::BidDeposit::get();
+                if !deposit.is_zero() {
+                    T::Currency::reserve(&bidder, deposit)
+                        .map_err(|_| Error::<T>::InsufficientBidDeposit)?;
+                    BidDeposits::<T>::insert(task_id, &bidder, deposit);
+                }
+                TaskBidCount::<T>::insert(task_id, count.saturating_add(1));
+            }
+
             let bid_info = BidInfo::<T> {
                 bidder: bidder.clone(),
                 amount,
@@ -413,13 +692,14 @@ pub mod pallet {
             Ok(())
         }
 
-        /// Assign a task to a selected bidder.
+        /// Assign a task to a selected bidder, returning their bid deposit
+        /// and reserving the task's worker bond from them.
         ///
         /// # Arguments
         /// * `task_id` - The task to assign
         /// * `bidder` - The selected bidder
         #[pallet::call_index(2)]
-        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 1))]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(4, 3))]
         pub fn assign_task(
             origin: OriginFor<T>,
             task_id: TaskId,
@@ -447,6 +727,17 @@ pub mod pallet {
                 Ok(())
             })?;
 
+            Self::release_bid_deposit(task_id, &bidder);
+            if let Some(bond) = WorkerBonds::<T>::get(task_id) {
+                T::Currency::reserve(&bidder, bond)
+                    .map_err(|_| Error::<T>::InsufficientWorkerBond)?;
+                Self::deposit_event(Event::WorkerBondReserved {
+                    task_id,
+                    worker: bidder.clone(),
+                    amount: bond,
+                });
+            }
+
             Self::deposit_event(Event::TaskAssigned {
                 task_id,
                 worker: bidder,
@@ -459,16 +750,27 @@ pub mod pallet {
         ///
         /// # Arguments
         /// * `task_id` - The task being completed
-        /// * `proof` - Proof of completion (URL, hash, etc.)
+        /// * `proof` - Proof of completion (hash, CID, etc.)
+        /// * `proof_type` - What kind of proof `proof` is
         #[pallet::call_index(3)]
-        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 3))]
         pub fn submit_work(
             origin: OriginFor<T>,
             task_id: TaskId,
-            _proof: Vec<u8>, // Could store this in a separate storage map if needed
+            proof: Vec<u8>,
+            proof_type: ProofType,
         ) -> DispatchResult {
             let worker = ensure_signed(origin)?;
 
+            let proof_hash = H256::from(sp_io::hashing::blake2_256(&proof));
+            let bounded_proof: BoundedVec<u8, T::MaxProofLength> =
+                proof.try_into().map_err(|_| Error::<T>::ProofTooLong)?;
+            let submission_index = TaskSubmissionCount::<T>::get(task_id);
+            ensure!(
+                submission_index < T::MaxSubmissions::get(),
+                Error::<T>::TooManySubmissions
+            );
+
             Tasks::<T>::try_mutate(task_id, |maybe_task| -> DispatchResult {
                 let task = maybe_task.as_mut().ok_or(Error::<T>::TaskNotFound)?;
                 ensure!(
@@ -485,17 +787,33 @@ pub mod pallet {
                 Ok(())
             })?;
 
-            Self::deposit_event(Event::WorkSubmitted { task_id });
+            TaskProofs::<T>::insert(
+                task_id,
+                submission_index,
+                TaskProof {
+                    proof: bounded_proof,
+                    proof_type,
+                    submitted_at: <frame_system::Pallet<T>>::block_number(),
+                },
+            );
+            TaskSubmissionCount::<T>::insert(task_id, submission_index.saturating_add(1));
+
+            Self::deposit_event(Event::WorkSubmitted {
+                task_id,
+                submission_index,
+                proof_hash,
+            });
 
             Ok(())
         }
 
-        /// Approve the submitted work and release payment.
+        /// Approve the submitted work, release payment and return the
+        /// worker's bond.
         ///
         /// # Arguments
         /// * `task_id` - The task to approve
         #[pallet::call_index(4)]
-        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 2))]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(3, 3))]
         pub fn approve_work(origin: OriginFor<T>, task_id: TaskId) -> DispatchResult {
             let poster = ensure_signed(origin)?;
 
@@ -523,6 +841,7 @@ pub mod pallet {
                 t.status = TaskStatus::Approved;
                 Ok(())
             })?;
+            Self::release_worker_bond(task_id, &worker);
 
             // Update reputation
             T::ReputationManager::on_task_completed(&worker, task.reward);
@@ -571,7 +890,7 @@ pub mod pallet {
             Self::deposit_event(Event::TaskDisputed {
                 task_id,
                 disputer,
-                reason,
+                reason: EventBytes::new(&reason),
             });
 
             Ok(())
@@ -601,6 +920,7 @@ pub mod pallet {
 
                 Ok(())
             })?;
+            WorkerBonds::<T>::remove(task_id);
 
             Self::deposit_event(Event::TaskCancelled { task_id });
 
@@ -609,11 +929,14 @@ pub mod pallet {
 
         /// Resolve a dispute (governance/sudo only).
         ///
+        /// A worker who loses forfeits their bond to the poster; otherwise it
+        /// is returned.
+        ///
         /// # Arguments
         /// * `task_id` - The disputed task
         /// * `winner` - Who gets the escrow
         #[pallet::call_index(7)]
-        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 2))]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(3, 4))]
         pub fn resolve_dispute(
             origin: OriginFor<T>,
             task_id: TaskId,
@@ -652,6 +975,11 @@ pub mod pallet {
                 t.status = TaskStatus::Approved; // Mark as resolved
                 Ok(())
             })?;
+            if winner == poster {
+                Self::forfeit_worker_bond(task_id, &worker, &poster);
+            } else {
+                Self::release_worker_bond(task_id, &worker);
+            }
 
             // Update reputations
             T::ReputationManager::on_dispute_resolved(&winner, &loser);
@@ -660,6 +988,247 @@ pub mod pallet {
 
             Ok(())
         }
+
+        /// Set (or clear) the block from which new tasks and bids are refused
+        /// (governance/sudo only).
+        ///
+        /// Enforced by the runtime `BaseCallFilter`; approvals, disputes,
+        /// cancellations and dispute resolution stay available so existing
+        /// tasks can be wound down.
+        ///
+        /// # Arguments
+        /// * `cutoff` - First block at which new work is rejected, or `None`
+        #[pallet::call_index(8)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(1))]
+        pub fn set_new_work_cutoff(
+            origin: OriginFor<T>,
+            cutoff: Option<BlockNumberFor<T>>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            match cutoff {
+                Some(block) => NewWorkCutoff::<T>::put(block),
+                None => NewWorkCutoff::<T>::kill(),
+            }
+
+            Self::deposit_event(Event::NewWorkCutoffSet { cutoff });
+
+            Ok(())
+        }
+
+        /// Expire a task whose deadline has passed (permissionless).
+        ///
+        /// The poster's escrow is released, less `ExpireBountyShare` of the
+        /// reward, which is paid to the caller.
+        ///
+        /// # Arguments
+        /// * `task_id` - The overdue task
+        #[pallet::call_index(9)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(3, 6))]
+        pub fn try_expire_task(origin: OriginFor<T>, task_id: TaskId) -> DispatchResult {
+            let caller = ensure_signed(origin)?;
+
+            let task = Tasks::<T>::get(task_id).ok_or(Error::<T>::TaskNotFound)?;
+            ensure!(Self::is_expirable(&task), Error::<T>::InvalidTaskStatus);
+            ensure!(
+                task.deadline < <frame_system::Pallet<T>>::block_number(),
+                Error::<T>::DeadlineNotPassed
+            );
+
+            TasksByDeadline::<T>::remove(task.deadline, task_id);
+            Self::expire_task(task_id, Some(caller));
+
+            Ok(())
+        }
+
+        /// Send submitted work back to the worker for another submission
+        /// instead of approving it (poster only).
+        ///
+        /// # Arguments
+        /// * `task_id` - The task with submitted work
+        #[pallet::call_index(10)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 2))]
+        pub fn request_revision(origin: OriginFor<T>, task_id: TaskId) -> DispatchResult {
+            let poster = ensure_signed(origin)?;
+
+            ensure!(
+                TaskSubmissionCount::<T>::get(task_id) < T::MaxSubmissions::get(),
+                Error::<T>::TooManySubmissions
+            );
+            let deadline = Tasks::<T>::try_mutate(task_id, |maybe_task| {
+                let task = maybe_task.as_mut().ok_or(Error::<T>::TaskNotFound)?;
+                ensure!(task.poster == poster, Error::<T>::NotPoster);
+                ensure!(
+                    task.status == TaskStatus::Completed,
+                    Error::<T>::InvalidTaskStatus
+                );
+
+                task.status = TaskStatus::InProgress;
+
+                Ok::<_, DispatchError>(task.deadline)
+            })?;
+            // The sweep may have dropped the index entry while the work was
+            // under review; the task can expire again now. A deadline the
+            // sweep has already passed is indexed where it will still look.
+            TasksByDeadline::<T>::insert(deadline.max(ExpiryCursor::<T>::get()), task_id, ());
+
+            Self::deposit_event(Event::RevisionRequested { task_id });
+
+            Ok(())
+        }
+
+        /// Withdraw a bid and get its deposit back. The bid the task was
+        /// assigned on stays.
+        ///
+        /// # Arguments
+        /// * `task_id` - The task bid on
+        #[pallet::call_index(11)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(3, 3))]
+        pub fn withdraw_bid(origin: OriginFor<T>, task_id: TaskId) -> DispatchResult {
+            let bidder = ensure_signed(origin)?;
+
+            let task = Tasks::<T>::get(task_id).ok_or(Error::<T>::TaskNotFound)?;
+            ensure!(
+                TaskBids::<T>::contains_key(task_id, &bidder),
+                Error::<T>::BidNotFound
+            );
+            ensure!(
+                task.assigned_to.as_ref() != Some(&bidder),
+                Error::<T>::BidAccepted
+            );
+
+            TaskBids::<T>::remove(task_id, &bidder);
+            TaskBidCount::<T>::mutate(task_id, |count| *count = count.saturating_sub(1));
+            Self::release_bid_deposit(task_id, &bidder);
+
+            Self::deposit_event(Event::BidWithdrawn { task_id, bidder });
+
+            Ok(())
+        }
+    }
+
+    // ========== Public Helpers ==========
+
+    impl<T: Config> Pallet<T> {
+        /// Whether `task` can still expire: nobody has submitted work for it
+        /// and it has not been settled.
+        pub fn is_expirable(task: &TaskInfo<T>) -> bool {
+            matches!(
+                task.status,
+                TaskStatus::Open | TaskStatus::Assigned | TaskStatus::InProgress
+            )
+        }
+
+        /// Mark an expirable task `Expired` and release its escrow, paying
+        /// `ExpireBountyShare` of the reward to `expired_by` if given. A worker
+        /// still holding the task forfeits their bond to the poster.
+        fn expire_task(task_id: TaskId, expired_by: Option<T::AccountId>) {
+            let Some(mut task) = Tasks::<T>::get(task_id) else {
+                return;
+            };
+            if !Self::is_expirable(&task) {
+                return;
+            }
+
+            T::Currency::unreserve(&task.poster, task.reward);
+            let mut bounty = Zero::zero();
+            if let Some(caller) = &expired_by {
+                let share = T::ExpireBountyShare::get().mul_floor(task.reward);
+                if T::Currency::transfer(
+                    &task.poster,
+                    caller,
+                    share,
+                    ExistenceRequirement::AllowDeath,
+                )
+                .is_ok()
+                {
+                    bounty = share;
+                }
+            }
+
+            match &task.assigned_to {
+                Some(worker) => Self::forfeit_worker_bond(task_id, worker, &task.poster),
+                None => WorkerBonds::<T>::remove(task_id),
+            }
+
+            task.status = TaskStatus::Expired;
+            Tasks::<T>::insert(task_id, task);
+
+            Self::deposit_event(Event::TaskExpired {
+                task_id,
+                expired_by,
+                bounty,
+            });
+        }
+
+        /// Return `bidder`'s deposit for a bid on the task.
+        fn release_bid_deposit(task_id: TaskId, bidder: &T::AccountId) {
+            if let Some(deposit) = BidDeposits::<T>::take(task_id, bidder) {
+                T::Currency::unreserve(bidder, deposit);
+            }
+        }
+
+        /// Return the task's worker bond to `worker`.
+        fn release_worker_bond(task_id: TaskId, worker: &T::AccountId) {
+            if let Some(bond) = WorkerBonds::<T>::take(task_id) {
+                T::Currency::unreserve(worker, bond);
+            }
+        }
+
+        /// Pay the task's worker bond from `worker`'s reserve to `poster`.
+        fn forfeit_worker_bond(task_id: TaskId, worker: &T::AccountId, poster: &T::AccountId) {
+            let Some(bond) = WorkerBonds::<T>::take(task_id) else {
+                return;
+            };
+            let missing =
+                T::Currency::repatriate_reserved(worker, poster, bond, BalanceStatus::Free)
+                    .unwrap_or(bond);
+            Self::deposit_event(Event::WorkerBondForfeited {
+                task_id,
+                worker: worker.clone(),
+                amount: bond.saturating_sub(missing),
+            });
+        }
+
+        /// Expire tasks whose deadline is before `now`, walking
+        /// `TasksByDeadline` from `ExpiryCursor` for at most `max` items
+        /// (index entries and empty blocks). Returns the items spent.
+        pub(crate) fn expire_overdue_tasks(now: BlockNumberFor<T>, max: u32) -> u32 {
+            if now.is_zero() {
+                return 0;
+            }
+            let mut cursor = ExpiryCursor::<T>::get();
+            let spent = drain_due(
+                &mut cursor,
+                now.saturating_sub(One::one()),
+                max,
+                |deadline, n| {
+                    TasksByDeadline::<T>::iter_key_prefix(deadline)
+                        .take(n)
+                        .collect()
+                },
+                |deadline, task_id| {
+                    TasksByDeadline::<T>::remove(deadline, task_id);
+                    Self::expire_task(task_id, None);
+                },
+            );
+            ExpiryCursor::<T>::put(cursor);
+            spent
+        }
+
+        /// Whether `post_task` and `bid_on_task` are still accepted at the
+        /// current block.
+        pub fn accepts_new_work() -> bool {
+            match NewWorkCutoff::<T>::get() {
+                Some(cutoff) => frame_system::Pallet::<T>::block_number() < cutoff,
+                None => true,
+            }
+        }
+
+        /// Whether a storage migration is still under way.
+        pub fn migrating() -> bool {
+            !PendingMigrations::<T>::get().is_empty()
+        }
     }
 
     // ========== Weight Info Trait ==========
@@ -673,6 +1242,10 @@ pub mod pallet {
         fn dispute_task() -> Weight;
         fn cancel_task() -> Weight;
         fn resolve_dispute() -> Weight;
+        fn set_new_work_cutoff() -> Weight;
+        fn try_expire_task() -> Weight;
+        fn request_revision() -> Weight;
+        fn withdraw_bid() -> Weight;
     }
 
     impl WeightInfo for () {
@@ -700,5 +1273,64 @@ pub mod pallet {
         fn resolve_dispute() -> Weight {
             Weight::from_parts(10_000, 0)
         }
+        fn set_new_work_cutoff() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn try_expire_task() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn request_revision() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+        fn withdraw_bid() -> Weight {
+            Weight::from_parts(10_000, 0)
+        }
+    }
+}
+
+/// The expiry sweep, run by `pallet-hook-budget`.
+///
+/// Each index entry costs a read of the task plus the escrow release and
+/// writes, and so does each deadline block the sweep finds empty. Entries the
+/// budget cannot pay for stay indexed for a later block.
+///
+/// While a migration is pending, the budget goes to its next entries instead
+/// and the sweep waits.
+impl<T: Config> BudgetedHook<frame_system::pallet_prelude::BlockNumberFor<T>> for Pallet<T> {
+    fn run(
+        now: frame_system::pallet_prelude::BlockNumberFor<T>,
+        limit: frame_support::weights::Weight,
+    ) -> HookUsage {
+        use frame_support::traits::Get;
+
+        let per_item = T::DbWeight::get().reads_writes(3, 6);
+        // Reading and writing the cursor.
+        let base = T::DbWeight::get().reads_writes(1, 1);
+        if Self::migrating() {
+            let per_entry = migrations::entry_weight::<T>();
+            let affordable = limit.saturating_sub(base).ref_time() / per_entry.ref_time().max(1);
+            let max = u32::try_from(affordable).unwrap_or(u32::MAX);
+            let processed = migrations::run_pending::<T>(max);
+            return HookUsage {
+                used: base.saturating_add(per_entry.saturating_mul(processed.into())),
+                deferred: true,
+            };
+        }
+        let affordable = limit.saturating_sub(base).ref_time() / per_item.ref_time().max(1);
+        let max =
+            T::MaxExpirationsPerBlock::get().min(u32::try_from(affordable).unwrap_or(u32::MAX));
+        if max == 0 {
+            return HookUsage {
+                used: frame_support::weights::Weight::zero(),
+                deferred: true,
+            };
+        }
+
+        let processed = Self::expire_overdue_tasks(now, max);
+        HookUsage {
+            used: base.saturating_add(per_item.saturating_mul(processed.into())),
+            // A sweep that used its whole allowance may have left more.
+            deferred: processed == max,
+        }
     }
 }
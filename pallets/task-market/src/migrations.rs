@@ -0,0 +1,397 @@
+//! Storage migrations for pallet-task-market.
+//!
+//! Each migration bumps the storage version during the upgrade but only
+//! queues the work that visits every task or bid as [`MigrationStep`]s in
+//! `PendingMigrations`. [`run_pending`], called from the pallet's budgeted
+//! hook, then works through the queue with
+//! [`clawchain_primitives::migration::translate_chunk`], a bounded number of
+//! entries per block.
+
+use crate::pallet::{
+    BalanceOf, BidInfo, Config, Event, ExpiryCursor, MigrationStep, NewWorkCutoff, Pallet,
+    PendingMigrations, TaskBidCount, TaskBids, TaskId, TaskInfo, TaskStatus, Tasks,
+    TasksByDeadline,
+};
+#[cfg(feature = "try-runtime")]
+use alloc::vec::Vec;
+use clawchain_primitives::migration::{self, ChunkOutcome};
+use frame_support::{
+    migrations::VersionedMigration,
+    pallet_prelude::*,
+    storage::StoragePrefixedMap,
+    traits::{Get, ReservableCurrency, UncheckedOnRuntimeUpgrade},
+};
+use frame_system::pallet_prelude::BlockNumberFor;
+
+/// Where the running step records the last key it reached.
+const PROGRESS_KEY: &[u8] = b":task-market:migration-progress";
+
+/// Weight of one entry of a migration step: the entry itself, the poster's
+/// reserve and, for a retired task, its bids.
+pub fn entry_weight<T: Config>() -> Weight {
+    let bids = u64::from(T::MaxBidsPerTask::get());
+    T::DbWeight::get().reads_writes(3 + bids, 4 + bids)
+}
+
+/// Queue `steps` behind any migration still pending.
+fn enqueue<T: Config>(steps: &[MigrationStep]) -> Weight {
+    PendingMigrations::<T>::mutate(|pending| {
+        for step in steps {
+            if pending.try_push(*step).is_err() {
+                log::error!(
+                    target: "runtime::task-market",
+                    "migration queue full, dropping {:?}",
+                    step
+                );
+            }
+        }
+    });
+    T::DbWeight::get().reads_writes(1, 1)
+}
+
+/// Run pending migration steps over at most `max` entries. Returns the
+/// entries visited.
+pub fn run_pending<T: Config>(max: u32) -> u32 {
+    let mut pending = PendingMigrations::<T>::get();
+    let mut spent = 0u32;
+    while let Some(step) = pending.first().copied() {
+        if spent >= max {
+            break;
+        }
+        let outcome = run_step::<T>(step, max - spent);
+        spent = spent.saturating_add(outcome.processed());
+        if let ChunkOutcome::Partial { .. } = outcome {
+            break;
+        }
+        pending.remove(0);
+        log::info!(
+            target: "runtime::task-market",
+            "migration step {:?} complete",
+            step
+        );
+    }
+    PendingMigrations::<T>::put(pending);
+    spent
+}
+
+/// Task id in a `Tasks` or `TaskBids` key: the first key, after the
+/// 32-byte prefix and its 16-byte `Blake2_128Concat` hash.
+fn task_id_of(key: &[u8]) -> Option<TaskId> {
+    key.get(48..56)
+        .and_then(|mut id| TaskId::decode(&mut id).ok())
+}
+
+fn run_step<T: Config>(step: MigrationStep, max: u32) -> ChunkOutcome {
+    let tasks = Tasks::<T>::final_prefix();
+    match step {
+        MigrationStep::TranslateTasks => migration::translate_chunk::<v1::OldTaskInfoOf<T>, _>(
+            &tasks,
+            PROGRESS_KEY,
+            max,
+            |_, old| Some(v1::translate_task::<T>(old)),
+        ),
+        MigrationStep::CountBids => migration::translate_chunk::<BidInfo<T>, _>(
+            &TaskBids::<T>::final_prefix(),
+            PROGRESS_KEY,
+            max,
+            |key, bid| {
+                if let Some(task_id) = task_id_of(key) {
+                    TaskBidCount::<T>::mutate(task_id, |count| *count = count.saturating_add(1));
+                }
+                Some(bid)
+            },
+        ),
+        MigrationStep::RetireOpenTasks => {
+            migration::translate_chunk::<TaskInfo<T>, _>(&tasks, PROGRESS_KEY, max, |key, task| {
+                Some(match task_id_of(key) {
+                    Some(task_id) => v2::retire_task::<T>(task_id, task),
+                    None => task,
+                })
+            })
+        }
+        MigrationStep::IndexDeadlines => {
+            if migration::progress(PROGRESS_KEY).is_none() {
+                // Start the cursor at the earliest indexed deadline.
+                ExpiryCursor::<T>::kill();
+            }
+            migration::translate_chunk::<TaskInfo<T>, _>(&tasks, PROGRESS_KEY, max, |key, task| {
+                if let Some(task_id) = task_id_of(key) {
+                    v3::index_task::<T>(task_id, &task);
+                }
+                Some(task)
+            })
+        }
+    }
+}
+
+/// Apply every pending step and run `check` on the result, then roll the
+/// state back so the upgrade itself still runs the steps block by block.
+#[cfg(feature = "try-runtime")]
+fn check_after_pending<T: Config>(
+    check: impl FnOnce() -> Result<(), sp_runtime::TryRuntimeError>,
+) -> Result<(), sp_runtime::TryRuntimeError> {
+    frame_support::storage::with_transaction(|| {
+        run_pending::<T>(u32::MAX);
+        let result = if Pallet::<T>::migrating() {
+            Err("migration steps left pending".into())
+        } else {
+            check()
+        };
+        sp_runtime::TransactionOutcome::Rollback(result)
+    })
+}
+
+/// v0 → v1: add `min_bidder_reputation` to every task, ungated, and count the
+/// bids already placed into `TaskBidCount`.
+///
+/// Its steps are queued ahead of the later migrations' steps, which read
+/// `Tasks` in the new layout.
+pub mod v1 {
+    use super::*;
+
+    /// `TaskInfo` as stored before v1.
+    #[derive(Encode, Decode)]
+    pub struct OldTaskInfo<AccountId, Title, Description, Balance, BlockNumber> {
+        pub poster: AccountId,
+        pub title: Title,
+        pub description: Description,
+        pub reward: Balance,
+        pub deadline: BlockNumber,
+        pub status: TaskStatus,
+        pub assigned_to: Option<AccountId>,
+        pub created_at: BlockNumber,
+    }
+
+    pub(super) type OldTaskInfoOf<T> = OldTaskInfo<
+        <T as frame_system::Config>::AccountId,
+        BoundedVec<u8, <T as Config>::MaxTitleLength>,
+        BoundedVec<u8, <T as Config>::MaxDescriptionLength>,
+        BalanceOf<T>,
+        BlockNumberFor<T>,
+    >;
+
+    /// `old` in the v1 layout, ungated.
+    pub(super) fn translate_task<T: Config>(old: OldTaskInfoOf<T>) -> TaskInfo<T> {
+        TaskInfo {
+            poster: old.poster,
+            title: old.title,
+            description: old.description,
+            reward: old.reward,
+            deadline: old.deadline,
+            status: old.status,
+            assigned_to: old.assigned_to,
+            created_at: old.created_at,
+            min_bidder_reputation: 0,
+        }
+    }
+
+    pub struct UncheckedMigrateToV1<T>(core::marker::PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for UncheckedMigrateToV1<T> {
+        fn on_runtime_upgrade() -> Weight {
+            enqueue::<T>(&[MigrationStep::TranslateTasks, MigrationStep::CountBids])
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, sp_runtime::TryRuntimeError> {
+            Ok((Tasks::<T>::iter_keys().count() as u64).encode())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), sp_runtime::TryRuntimeError> {
+            let before = u64::decode(&mut &state[..])
+                .map_err(|_| sp_runtime::TryRuntimeError::Other("bad pre_upgrade state"))?;
+            check_after_pending::<T>(|| {
+                ensure!(
+                    before == Tasks::<T>::iter().count() as u64,
+                    "tasks lost in migration"
+                );
+                for task_id in Tasks::<T>::iter_keys() {
+                    ensure!(
+                        TaskBidCount::<T>::get(task_id) as usize
+                            == TaskBids::<T>::iter_prefix(task_id).count(),
+                        "bid count out of step with bids"
+                    );
+                }
+                Ok(())
+            })
+        }
+    }
+
+    /// Runs [`UncheckedMigrateToV1`] once, bumping the storage version to 1.
+    pub type MigrateToV1<T> = VersionedMigration<
+        0,
+        1,
+        UncheckedMigrateToV1<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
+
+/// v1 → v2: retire the task market's open work.
+///
+/// Tasks still `Open` have no worker committed to them, and
+/// `pallet-service-market` is not in the runtime to take them over, so each
+/// is cancelled and its escrow released to the poster exactly as
+/// `cancel_task` would. Bids on them are dropped. Tasks that already have a
+/// worker stay where they are and settle through the usual calls.
+///
+/// If governance has not set a `NewWorkCutoff` yet, it is set to the upgrade
+/// block, so the runtime call filter rejects new tasks and bids from then on.
+pub mod v2 {
+    use super::*;
+    #[cfg(feature = "try-runtime")]
+    use sp_runtime::traits::Saturating;
+
+    /// Cancel `task` and refund its escrow if it is still `Open`.
+    pub(super) fn retire_task<T: Config>(task_id: TaskId, mut task: TaskInfo<T>) -> TaskInfo<T> {
+        if task.status == TaskStatus::Open {
+            T::Currency::unreserve(&task.poster, task.reward);
+            task.status = TaskStatus::Cancelled;
+            let _ = TaskBids::<T>::clear_prefix(task_id, u32::MAX, None);
+            TaskBidCount::<T>::remove(task_id);
+            Pallet::<T>::deposit_event(Event::TaskCancelled { task_id });
+        }
+        task
+    }
+
+    pub struct UncheckedMigrateToV2<T>(core::marker::PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for UncheckedMigrateToV2<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let mut weight = enqueue::<T>(&[MigrationStep::RetireOpenTasks]);
+
+            weight.saturating_accrue(T::DbWeight::get().reads(1));
+            if NewWorkCutoff::<T>::get().is_none() {
+                let now = frame_system::Pallet::<T>::block_number();
+                NewWorkCutoff::<T>::put(now);
+                Pallet::<T>::deposit_event(Event::NewWorkCutoffSet { cutoff: Some(now) });
+                weight.saturating_accrue(T::DbWeight::get().writes(1));
+            }
+            weight
+        }
+
+        /// Records, per poster of an `Open` task, the reserved balance and
+        /// the escrow the migration must release. Tasks may still be in the
+        /// pre-v1 layout if v1's steps have not run yet.
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, sp_runtime::TryRuntimeError> {
+            let mut refunds = alloc::collections::BTreeMap::<T::AccountId, BalanceOf<T>>::new();
+            for task_id in Tasks::<T>::iter_keys() {
+                let Some(task) =
+                    frame_support::storage::unhashed::get_raw(&Tasks::<T>::hashed_key_for(task_id))
+                        .and_then(|bytes| {
+                            migration::decode_lenient::<v1::OldTaskInfoOf<T>, TaskInfo<T>>(
+                                &bytes,
+                                |old| Some(v1::translate_task::<T>(old)),
+                            )
+                        })
+                else {
+                    continue;
+                };
+                if task.status == TaskStatus::Open {
+                    let refund = refunds.entry(task.poster).or_default();
+                    *refund = refund.saturating_add(task.reward);
+                }
+            }
+            let expected: Vec<(T::AccountId, BalanceOf<T>, BalanceOf<T>)> = refunds
+                .into_iter()
+                .map(|(poster, refund)| {
+                    let reserved = T::Currency::reserved_balance(&poster);
+                    (poster, reserved, refund)
+                })
+                .collect();
+            Ok((Tasks::<T>::iter_keys().count() as u64, expected).encode())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), sp_runtime::TryRuntimeError> {
+            let (before, expected) = <(
+                u64,
+                Vec<(T::AccountId, BalanceOf<T>, BalanceOf<T>)>,
+            )>::decode(&mut &state[..])
+            .map_err(|_| sp_runtime::TryRuntimeError::Other("bad pre_upgrade state"))?;
+            ensure!(NewWorkCutoff::<T>::get().is_some(), "no new-work cutoff");
+            check_after_pending::<T>(|| {
+                ensure!(
+                    before == Tasks::<T>::iter_keys().count() as u64,
+                    "tasks lost in migration"
+                );
+                ensure!(
+                    Tasks::<T>::iter_values().all(|task| task.status != TaskStatus::Open),
+                    "open task left after migration"
+                );
+                for (poster, reserved, refund) in expected {
+                    ensure!(
+                        T::Currency::reserved_balance(&poster) == reserved.saturating_sub(refund),
+                        "released escrow differs from the open tasks' rewards"
+                    );
+                }
+                Ok(())
+            })
+        }
+    }
+
+    /// Runs [`UncheckedMigrateToV2`] once, bumping the storage version to 2.
+    pub type MigrateToV2<T> = VersionedMigration<
+        1,
+        2,
+        UncheckedMigrateToV2<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
+
+/// v2 → v3: index every task that can still expire in `TasksByDeadline`, so
+/// tasks posted before the index existed are swept too. The expiry cursor
+/// starts at the earliest indexed deadline.
+pub mod v3 {
+    use super::*;
+
+    /// Index `task` if it can still expire, moving the cursor back to its
+    /// deadline if that is the earliest so far.
+    pub(super) fn index_task<T: Config>(task_id: TaskId, task: &TaskInfo<T>) {
+        if Pallet::<T>::is_expirable(task) {
+            TasksByDeadline::<T>::insert(task.deadline, task_id, ());
+            if !ExpiryCursor::<T>::exists() || task.deadline < ExpiryCursor::<T>::get() {
+                ExpiryCursor::<T>::put(task.deadline);
+            }
+        }
+    }
+
+    pub struct UncheckedMigrateToV3<T>(core::marker::PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for UncheckedMigrateToV3<T> {
+        fn on_runtime_upgrade() -> Weight {
+            enqueue::<T>(&[MigrationStep::IndexDeadlines])
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(_state: Vec<u8>) -> Result<(), sp_runtime::TryRuntimeError> {
+            check_after_pending::<T>(|| {
+                for (task_id, task) in Tasks::<T>::iter() {
+                    if Pallet::<T>::is_expirable(&task) {
+                        ensure!(
+                            ExpiryCursor::<T>::get() <= task.deadline,
+                            "expiry cursor past an indexed deadline"
+                        );
+                        ensure!(
+                            TasksByDeadline::<T>::contains_key(task.deadline, task_id),
+                            "expirable task not indexed"
+                        );
+                    }
+                }
+                Ok(())
+            })
+        }
+    }
+
+    /// Runs [`UncheckedMigrateToV3`] once, bumping the storage version to 3.
+    pub type MigrateToV3<T> = VersionedMigration<
+        2,
+        3,
+        UncheckedMigrateToV3<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
@@ -1,6 +1,6 @@
 //! Unit tests for the Task Market pallet.
 
-use crate::{self as pallet_task_market, pallet::*, *};
+use crate::{self as pallet_task_market, *};
 use frame_support::{assert_noop, assert_ok, parameter_types, PalletId};
 use sp_core::H256;
 use sp_runtime::{
@@ -94,6 +94,9 @@ impl pallet_reputation::Config for Test {
     type InitialReputation = InitialReputation;
     type MaxReputationDelta = MaxReputationDelta;
     type MaxHistoryLength = MaxHistoryLength;
+    type MaxLeaderboardSize = frame_support::traits::ConstU32<10>;
+    type LeaderboardEraLength = frame_support::traits::ConstU64<0>;
+    type LeaderboardHistoryDepth = frame_support::traits::ConstU32<0>;
 }
 
 parameter_types! {
@@ -101,9 +104,12 @@ parameter_types! {
     pub const MaxTitleLength: u32 = 128;
     pub const MaxDescriptionLength: u32 = 1024;
     pub const MaxProposalLength: u32 = 512;
-    pub const MaxBidsPerTask: u32 = 20;
+    pub static MaxBidsPerTask: u32 = 20;
     pub const MinTaskReward: u64 = 100;
     pub const MaxActiveTasksPerAccount: u32 = 50;
+    pub const DefaultMinBidderReputation: u32 = 3000;
+    pub const ExpireBountyShare: sp_runtime::Perbill = sp_runtime::Perbill::from_percent(5);
+    pub static BidDeposit: u64 = 0; // off by default in tests
 }
 
 impl pallet_task_market::Config for Test {
@@ -118,6 +124,12 @@ impl pallet_task_market::Config for Test {
     type MaxBidsPerTask = MaxBidsPerTask;
     type MinTaskReward = MinTaskReward;
     type MaxActiveTasksPerAccount = MaxActiveTasksPerAccount;
+    type DefaultMinBidderReputation = DefaultMinBidderReputation;
+    type MaxExpirationsPerBlock = frame_support::traits::ConstU32<1>;
+    type ExpireBountyShare = ExpireBountyShare;
+    type MaxProofLength = frame_support::traits::ConstU32<64>;
+    type MaxSubmissions = frame_support::traits::ConstU32<2>;
+    type BidDeposit = BidDeposit;
 }
 
 // Build genesis storage according to the mock runtime.
@@ -145,7 +157,9 @@ fn post_default_task(poster: u64) {
         b"Test Task".to_vec(),
         b"Description".to_vec(),
         1000,
-        1000
+        1000,
+        None,
+        None,
     ));
 }
 
@@ -173,7 +187,8 @@ fn setup_completed_task(poster: u64, worker: u64) -> TaskId {
     assert_ok!(TaskMarket::submit_work(
         RuntimeOrigin::signed(worker),
         task_id,
-        b"proof".to_vec()
+        b"proof".to_vec(),
+        ProofType::Hash,
     ));
     task_id
 }
@@ -194,7 +209,9 @@ fn post_task_works() {
             title.clone(),
             description,
             reward,
-            deadline
+            deadline,
+            None,
+            None,
         ));
 
         let task = TaskMarket::tasks(0).unwrap();
@@ -226,7 +243,9 @@ fn post_task_emits_event() {
             b"Task".to_vec(),
             b"Desc".to_vec(),
             1000,
-            1000
+            1000,
+            None,
+            None,
         ));
 
         System::assert_has_event(
@@ -249,7 +268,9 @@ fn post_task_fails_if_reward_too_low() {
                 b"Task".to_vec(),
                 b"Description".to_vec(),
                 50, // Below MinTaskReward (100)
-                1000
+                1000,
+                None,
+                None,
             ),
             Error::<Test>::RewardTooLow
         );
@@ -265,7 +286,9 @@ fn post_task_fails_if_reward_zero() {
                 b"Task".to_vec(),
                 b"Description".to_vec(),
                 0,
-                1000
+                1000,
+                None,
+                None,
             ),
             Error::<Test>::RewardTooLow
         );
@@ -280,7 +303,9 @@ fn post_task_at_minimum_reward() {
             b"Task".to_vec(),
             b"Desc".to_vec(),
             100, // Exactly MinTaskReward
-            1000
+            1000,
+            None,
+            None,
         ));
     });
 }
@@ -295,7 +320,9 @@ fn post_task_fails_if_title_too_long() {
                 long_title,
                 b"Description".to_vec(),
                 1000,
-                1000
+                1000,
+                None,
+                None,
             ),
             Error::<Test>::TitleTooLong
         );
@@ -311,7 +338,9 @@ fn post_task_max_title_length() {
             title,
             b"Description".to_vec(),
             1000,
-            1000
+            1000,
+            None,
+            None,
         ));
     });
 }
@@ -326,7 +355,9 @@ fn post_task_fails_if_description_too_long() {
                 b"Task".to_vec(),
                 long_desc,
                 1000,
-                1000
+                1000,
+                None,
+                None,
             ),
             Error::<Test>::DescriptionTooLong
         );
@@ -343,7 +374,9 @@ fn post_task_fails_if_deadline_in_past() {
                 b"Task".to_vec(),
                 b"Desc".to_vec(),
                 1000,
-                0
+                0,
+                None,
+                None,
             ),
             Error::<Test>::TaskExpired
         );
@@ -360,7 +393,9 @@ fn post_task_fails_if_deadline_is_current_block() {
                 b"Task".to_vec(),
                 b"Desc".to_vec(),
                 1000,
-                1 // Same as current block
+                1, // Same as current block
+                None,
+                None,
             ),
             Error::<Test>::TaskExpired
         );
@@ -377,7 +412,9 @@ fn post_task_fails_if_insufficient_balance() {
                 b"Task".to_vec(),
                 b"Desc".to_vec(),
                 1000,
-                1000
+                1000,
+                None,
+                None,
             ),
             Error::<Test>::InsufficientBalance
         );
@@ -393,7 +430,9 @@ fn post_task_unsigned_fails() {
                 b"Task".to_vec(),
                 b"Desc".to_vec(),
                 1000,
-                1000
+                1000,
+                None,
+                None,
             ),
             sp_runtime::DispatchError::BadOrigin
         );
@@ -517,7 +556,9 @@ fn bid_fails_if_task_expired() {
             b"Task".to_vec(),
             b"Desc".to_vec(),
             1000,
-            10
+            10,
+            None,
+            None,
         ));
 
         // Advance past deadline
@@ -704,7 +745,8 @@ fn submit_work_works() {
         assert_ok!(TaskMarket::submit_work(
             RuntimeOrigin::signed(2),
             task_id,
-            b"https://proof.com".to_vec()
+            b"https://proof.com".to_vec(),
+            ProofType::Hash,
         ));
 
         let task = TaskMarket::tasks(task_id).unwrap();
@@ -720,10 +762,18 @@ fn submit_work_emits_event() {
         assert_ok!(TaskMarket::submit_work(
             RuntimeOrigin::signed(2),
             task_id,
-            b"proof".to_vec()
+            b"proof".to_vec(),
+            ProofType::Hash,
         ));
 
-        System::assert_has_event(Event::<Test>::WorkSubmitted { task_id }.into());
+        System::assert_has_event(
+            Event::<Test>::WorkSubmitted {
+                task_id,
+                submission_index: 0,
+                proof_hash: H256::from(sp_io::hashing::blake2_256(b"proof")),
+            }
+            .into(),
+        );
     });
 }
 
@@ -734,7 +784,12 @@ fn submit_work_fails_for_non_worker() {
 
         // Account 3 is not the assigned worker
         assert_noop!(
-            TaskMarket::submit_work(RuntimeOrigin::signed(3), task_id, b"proof".to_vec()),
+            TaskMarket::submit_work(
+                RuntimeOrigin::signed(3),
+                task_id,
+                b"proof".to_vec(),
+                ProofType::Hash
+            ),
             Error::<Test>::NotAssignedWorker
         );
     });
@@ -747,7 +802,12 @@ fn submit_work_fails_for_poster() {
 
         // Poster can't submit work
         assert_noop!(
-            TaskMarket::submit_work(RuntimeOrigin::signed(1), task_id, b"proof".to_vec()),
+            TaskMarket::submit_work(
+                RuntimeOrigin::signed(1),
+                task_id,
+                b"proof".to_vec(),
+                ProofType::Hash
+            ),
             Error::<Test>::NotAssignedWorker
         );
     });
@@ -760,7 +820,12 @@ fn submit_work_fails_if_task_open() {
 
         // Task is Open, no worker assigned
         assert_noop!(
-            TaskMarket::submit_work(RuntimeOrigin::signed(2), 0, b"proof".to_vec()),
+            TaskMarket::submit_work(
+                RuntimeOrigin::signed(2),
+                0,
+                b"proof".to_vec(),
+                ProofType::Hash
+            ),
             Error::<Test>::NotAssignedWorker
         );
     });
@@ -770,7 +835,12 @@ fn submit_work_fails_if_task_open() {
 fn submit_work_fails_for_nonexistent_task() {
     new_test_ext().execute_with(|| {
         assert_noop!(
-            TaskMarket::submit_work(RuntimeOrigin::signed(1), 999, b"proof".to_vec()),
+            TaskMarket::submit_work(
+                RuntimeOrigin::signed(1),
+                999,
+                b"proof".to_vec(),
+                ProofType::Hash
+            ),
             Error::<Test>::TaskNotFound
         );
     });
@@ -988,7 +1058,7 @@ fn dispute_task_emits_event() {
             Event::<Test>::TaskDisputed {
                 task_id,
                 disputer: 1,
-                reason,
+                reason: clawchain_primitives::EventBytes::new(&reason),
             }
             .into(),
         );
@@ -1180,7 +1250,9 @@ fn full_happy_path_workflow() {
             b"Build something".to_vec(),
             b"Detailed description".to_vec(),
             2000,
-            1000
+            1000,
+            None,
+            None,
         ));
 
         // 2. Worker bids
@@ -1202,7 +1274,8 @@ fn full_happy_path_workflow() {
         assert_ok!(TaskMarket::submit_work(
             RuntimeOrigin::signed(worker),
             0,
-            b"https://github.com/proof".to_vec()
+            b"https://github.com/proof".to_vec(),
+            ProofType::Hash,
         ));
 
         // 5. Poster approves
@@ -1337,3 +1410,548 @@ fn cannot_bid_on_cancelled_task() {
         );
     });
 }
+
+// ========== Deprecation Cutoff Tests ==========
+
+#[test]
+fn set_new_work_cutoff_works() {
+    new_test_ext().execute_with(|| {
+        assert!(TaskMarket::accepts_new_work());
+
+        assert_ok!(TaskMarket::set_new_work_cutoff(
+            RuntimeOrigin::root(),
+            Some(10)
+        ));
+        assert_eq!(NewWorkCutoff::<Test>::get(), Some(10));
+        System::assert_last_event(RuntimeEvent::TaskMarket(Event::NewWorkCutoffSet {
+            cutoff: Some(10),
+        }));
+
+        assert_ok!(TaskMarket::set_new_work_cutoff(RuntimeOrigin::root(), None));
+        assert_eq!(NewWorkCutoff::<Test>::get(), None);
+    });
+}
+
+#[test]
+fn set_new_work_cutoff_requires_root() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            TaskMarket::set_new_work_cutoff(RuntimeOrigin::signed(1), Some(10)),
+            sp_runtime::DispatchError::BadOrigin
+        );
+    });
+}
+
+#[test]
+fn accepts_new_work_until_cutoff_block() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(TaskMarket::set_new_work_cutoff(
+            RuntimeOrigin::root(),
+            Some(10)
+        ));
+
+        System::set_block_number(9);
+        assert!(TaskMarket::accepts_new_work());
+
+        System::set_block_number(10);
+        assert!(!TaskMarket::accepts_new_work());
+    });
+}
+
+// ========== Bidder Reputation Gate ==========
+
+#[test]
+fn bid_rejected_below_task_reputation_floor() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(TaskMarket::post_task(
+            RuntimeOrigin::signed(1),
+            b"Task".to_vec(),
+            b"Desc".to_vec(),
+            1000,
+            1000,
+            Some(6000),
+            None,
+        ));
+        assert_eq!(TaskMarket::tasks(0).unwrap().min_bidder_reputation, 6000);
+
+        // New accounts start at InitialReputation (5000).
+        assert_noop!(
+            TaskMarket::bid_on_task(RuntimeOrigin::signed(2), 0, 900, b"Bid".to_vec()),
+            Error::<Test>::InsufficientReputation
+        );
+
+        pallet_reputation::Reputations::<Test>::mutate(2, |rep| rep.score = 6000);
+        assert_ok!(TaskMarket::bid_on_task(
+            RuntimeOrigin::signed(2),
+            0,
+            900,
+            b"Bid".to_vec()
+        ));
+    });
+}
+
+#[test]
+fn post_task_defaults_bidder_reputation_floor() {
+    new_test_ext().execute_with(|| {
+        post_default_task(1);
+        assert_eq!(TaskMarket::tasks(0).unwrap().min_bidder_reputation, 3000);
+
+        pallet_reputation::Reputations::<Test>::mutate(2, |rep| rep.score = 2999);
+        assert_noop!(
+            TaskMarket::bid_on_task(RuntimeOrigin::signed(2), 0, 900, b"Bid".to_vec()),
+            Error::<Test>::InsufficientReputation
+        );
+
+        // An explicit zero floor opts the task out of gating.
+        assert_ok!(TaskMarket::post_task(
+            RuntimeOrigin::signed(1),
+            b"Open".to_vec(),
+            b"Desc".to_vec(),
+            1000,
+            1000,
+            Some(0),
+            None,
+        ));
+        assert_ok!(TaskMarket::bid_on_task(
+            RuntimeOrigin::signed(2),
+            1,
+            900,
+            b"Bid".to_vec()
+        ));
+    });
+}
+
+#[test]
+fn migrate_to_v1_adds_reputation_floor_and_counts_bids() {
+    use crate::migrations::v1::{MigrateToV1, OldTaskInfo};
+    use codec::Encode;
+    use frame_support::traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion};
+
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(0).put::<TaskMarket>();
+        post_default_task(1);
+        for bidder in [2, 3] {
+            assert_ok!(TaskMarket::bid_on_task(
+                RuntimeOrigin::signed(bidder),
+                0,
+                900,
+                b"Bid".to_vec()
+            ));
+        }
+        // Rewrite the task and its count as the old runtime left them.
+        let task = TaskMarket::tasks(0).unwrap();
+        let old = OldTaskInfo {
+            poster: task.poster,
+            title: task.title.clone(),
+            description: task.description.clone(),
+            reward: task.reward,
+            deadline: task.deadline,
+            status: task.status.clone(),
+            assigned_to: task.assigned_to,
+            created_at: task.created_at,
+        };
+        frame_support::storage::unhashed::put_raw(&Tasks::<Test>::hashed_key_for(0), &old.encode());
+        TaskBidCount::<Test>::remove(0);
+
+        MigrateToV1::<Test>::on_runtime_upgrade();
+        assert_eq!(TaskMarket::on_chain_storage_version(), 1);
+        assert!(TaskMarket::migrating());
+        // The upgrade only queues the work.
+        assert!(TaskMarket::tasks(0).is_none());
+
+        crate::migrations::run_pending::<Test>(u32::MAX);
+        assert!(!TaskMarket::migrating());
+
+        let migrated = TaskMarket::tasks(0).unwrap();
+        assert_eq!(migrated.min_bidder_reputation, 0);
+        assert_eq!(migrated.title, task.title);
+        assert_eq!(TaskBidCount::<Test>::get(0), 2);
+    });
+}
+
+#[test]
+fn migrate_to_v2_cancels_open_tasks_and_sets_cutoff() {
+    use crate::migrations::v2::MigrateToV2;
+    use frame_support::traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion};
+
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(1).put::<TaskMarket>();
+        post_default_task(1);
+        assert_ok!(TaskMarket::bid_on_task(
+            RuntimeOrigin::signed(3),
+            0,
+            900,
+            b"Bid".to_vec()
+        ));
+        let assigned = setup_assigned_task(1, 2);
+        let reserved = Balances::reserved_balance(1);
+
+        System::set_block_number(5);
+        MigrateToV2::<Test>::on_runtime_upgrade();
+        assert_eq!(TaskMarket::new_work_cutoff(), Some(5));
+        assert_eq!(TaskMarket::on_chain_storage_version(), 2);
+
+        // One task per call, so the two tasks take two calls.
+        assert_eq!(crate::migrations::run_pending::<Test>(1), 1);
+        assert!(TaskMarket::migrating());
+        assert_eq!(crate::migrations::run_pending::<Test>(1), 1);
+        assert!(!TaskMarket::migrating());
+
+        // The open task is cancelled and refunded; the assigned one is untouched.
+        assert_eq!(TaskMarket::tasks(0).unwrap().status, TaskStatus::Cancelled);
+        assert!(TaskMarket::task_bids(0, 3).is_none());
+        assert_eq!(TaskBidCount::<Test>::get(0), 0);
+        assert_eq!(Balances::reserved_balance(1), reserved - 1000);
+        assert_eq!(
+            TaskMarket::tasks(assigned).unwrap().status,
+            TaskStatus::Assigned
+        );
+
+        assert!(!TaskMarket::accepts_new_work());
+    });
+}
+
+// ========== Expiry Tests ==========
+
+#[test]
+fn try_expire_task_releases_escrow_and_pays_bounty() {
+    new_test_ext().execute_with(|| {
+        let task_id = setup_assigned_task(1, 2);
+        assert_noop!(
+            TaskMarket::try_expire_task(RuntimeOrigin::signed(3), task_id),
+            Error::<Test>::DeadlineNotPassed
+        );
+
+        System::set_block_number(1001);
+        let caller_before = Balances::free_balance(3);
+        assert_ok!(TaskMarket::try_expire_task(
+            RuntimeOrigin::signed(3),
+            task_id
+        ));
+
+        // 5% of the 1000 reward goes to the caller, the rest back to the poster.
+        assert_eq!(Balances::free_balance(3) - caller_before, 50);
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_eq!(Balances::free_balance(1), 10000 - 50);
+        assert_eq!(
+            TaskMarket::tasks(task_id).unwrap().status,
+            TaskStatus::Expired
+        );
+        assert!(!TasksByDeadline::<Test>::contains_key(1000, task_id));
+        System::assert_last_event(
+            Event::TaskExpired {
+                task_id,
+                expired_by: Some(3),
+                bounty: 50,
+            }
+            .into(),
+        );
+
+        assert_noop!(
+            TaskMarket::try_expire_task(RuntimeOrigin::signed(3), task_id),
+            Error::<Test>::InvalidTaskStatus
+        );
+    });
+}
+
+#[test]
+fn sweep_expires_overdue_tasks_but_not_submitted_work() {
+    use clawchain_primitives::BudgetedHook;
+    use frame_support::weights::Weight;
+
+    new_test_ext().execute_with(|| {
+        post_default_task(1);
+        let submitted = setup_completed_task(3, 2);
+
+        // One item per block, and an empty block costs an item too: the
+        // cursor first steps over block 999, then drains block 1000.
+        ExpiryCursor::<Test>::put(999);
+        assert!(<TaskMarket as BudgetedHook<u64>>::run(1001, Weight::MAX).deferred);
+        assert_eq!(ExpiryCursor::<Test>::get(), 1000);
+        assert_eq!(TaskMarket::tasks(0).unwrap().status, TaskStatus::Open);
+
+        assert!(<TaskMarket as BudgetedHook<u64>>::run(1001, Weight::MAX).deferred);
+        <TaskMarket as BudgetedHook<u64>>::run(1002, Weight::MAX);
+        assert_eq!(TasksByDeadline::<Test>::iter().count(), 0);
+        assert_eq!(ExpiryCursor::<Test>::get(), 1001);
+
+        assert_eq!(TaskMarket::tasks(0).unwrap().status, TaskStatus::Expired);
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_eq!(Balances::free_balance(1), 10000);
+        assert_eq!(
+            TaskMarket::tasks(submitted).unwrap().status,
+            TaskStatus::Completed
+        );
+        assert_eq!(Balances::reserved_balance(3), 1000);
+    });
+}
+
+#[test]
+fn sweep_waits_for_a_pending_migration() {
+    use crate::migrations::v3::MigrateToV3;
+    use clawchain_primitives::BudgetedHook;
+    use frame_support::{
+        traits::{OnRuntimeUpgrade, StorageVersion},
+        weights::Weight,
+    };
+
+    new_test_ext().execute_with(|| {
+        post_default_task(1);
+        // As before the deadline index existed.
+        let _ = TasksByDeadline::<Test>::clear(u32::MAX, None);
+        ExpiryCursor::<Test>::kill();
+        StorageVersion::new(2).put::<TaskMarket>();
+        MigrateToV3::<Test>::on_runtime_upgrade();
+
+        // The first block indexes the task instead of sweeping.
+        assert!(<TaskMarket as BudgetedHook<u64>>::run(1001, Weight::MAX).deferred);
+        assert!(!TaskMarket::migrating());
+        assert!(TasksByDeadline::<Test>::contains_key(1000, 0));
+        assert_eq!(ExpiryCursor::<Test>::get(), 1000);
+        assert_eq!(TaskMarket::tasks(0).unwrap().status, TaskStatus::Open);
+
+        <TaskMarket as BudgetedHook<u64>>::run(1001, Weight::MAX);
+        assert_eq!(TaskMarket::tasks(0).unwrap().status, TaskStatus::Expired);
+    });
+}
+
+// Helper: post a task requiring `bond` from the worker, bid and assign
+fn setup_bonded_task(poster: u64, worker: u64, bond: u64) -> TaskId {
+    assert_ok!(TaskMarket::post_task(
+        RuntimeOrigin::signed(poster),
+        b"Bonded Task".to_vec(),
+        b"Description".to_vec(),
+        1000,
+        1000,
+        None,
+        Some(bond),
+    ));
+    let task_id = TaskMarket::task_count() - 1;
+    assert_ok!(TaskMarket::bid_on_task(
+        RuntimeOrigin::signed(worker),
+        task_id,
+        800,
+        b"Proposal".to_vec()
+    ));
+    assert_ok!(TaskMarket::assign_task(
+        RuntimeOrigin::signed(poster),
+        task_id,
+        worker
+    ));
+    task_id
+}
+
+#[test]
+fn worker_bond_is_returned_on_approval() {
+    new_test_ext().execute_with(|| {
+        let task_id = setup_bonded_task(1, 2, 300);
+        assert_eq!(Balances::reserved_balance(2), 300);
+        assert_eq!(WorkerBonds::<Test>::get(task_id), Some(300));
+
+        assert_ok!(TaskMarket::submit_work(
+            RuntimeOrigin::signed(2),
+            task_id,
+            b"proof".to_vec(),
+            ProofType::Hash,
+        ));
+        assert_ok!(TaskMarket::approve_work(RuntimeOrigin::signed(1), task_id));
+
+        assert_eq!(Balances::reserved_balance(2), 0);
+        assert_eq!(Balances::free_balance(2), 10000 + 1000);
+        assert!(WorkerBonds::<Test>::get(task_id).is_none());
+    });
+}
+
+#[test]
+fn worker_bond_is_forfeited_on_expiry_or_lost_dispute() {
+    new_test_ext().execute_with(|| {
+        // No bidder can cover this bond.
+        assert_ok!(TaskMarket::post_task(
+            RuntimeOrigin::signed(1),
+            b"Bonded Task".to_vec(),
+            b"Description".to_vec(),
+            1000,
+            1000,
+            None,
+            Some(20_000),
+        ));
+        assert_ok!(TaskMarket::bid_on_task(
+            RuntimeOrigin::signed(2),
+            0,
+            800,
+            b"Proposal".to_vec()
+        ));
+        assert_noop!(
+            TaskMarket::assign_task(RuntimeOrigin::signed(1), 0, 2),
+            Error::<Test>::InsufficientWorkerBond
+        );
+
+        let expiring = setup_bonded_task(3, 2, 300);
+        let disputed = setup_bonded_task(4, 2, 200);
+        assert_eq!(Balances::reserved_balance(2), 500);
+
+        assert_ok!(TaskMarket::dispute_task(
+            RuntimeOrigin::signed(4),
+            disputed,
+            b"never delivered".to_vec()
+        ));
+        assert_ok!(TaskMarket::resolve_dispute(
+            RuntimeOrigin::root(),
+            disputed,
+            4
+        ));
+        assert_eq!(Balances::reserved_balance(2), 300);
+        assert_eq!(Balances::free_balance(4), 10000 + 200);
+        System::assert_has_event(
+            Event::WorkerBondForfeited {
+                task_id: disputed,
+                worker: 2,
+                amount: 200,
+            }
+            .into(),
+        );
+
+        System::set_block_number(1001);
+        assert_ok!(TaskMarket::try_expire_task(
+            RuntimeOrigin::signed(4),
+            expiring
+        ));
+        assert_eq!(Balances::reserved_balance(2), 0);
+        assert_eq!(Balances::free_balance(2), 10000 - 500);
+        assert_eq!(Balances::reserved_balance(3), 0);
+        assert_eq!(Balances::free_balance(3), 10000 - 50 + 300);
+
+        // The unassigned task expires without a bond to forfeit.
+        assert_ok!(TaskMarket::try_expire_task(RuntimeOrigin::signed(4), 0));
+        assert!(WorkerBonds::<Test>::iter().next().is_none());
+    });
+}
+
+#[test]
+fn work_proofs_are_kept_across_revisions() {
+    new_test_ext().execute_with(|| {
+        let task_id = setup_assigned_task(1, 2);
+        assert_noop!(
+            TaskMarket::submit_work(
+                RuntimeOrigin::signed(2),
+                task_id,
+                vec![0u8; 65],
+                ProofType::Hash,
+            ),
+            Error::<Test>::ProofTooLong
+        );
+        assert_ok!(TaskMarket::submit_work(
+            RuntimeOrigin::signed(2),
+            task_id,
+            b"bafy-first".to_vec(),
+            ProofType::Cid,
+        ));
+
+        assert_noop!(
+            TaskMarket::request_revision(RuntimeOrigin::signed(2), task_id),
+            Error::<Test>::NotPoster
+        );
+        assert_ok!(TaskMarket::request_revision(
+            RuntimeOrigin::signed(1),
+            task_id
+        ));
+        assert_eq!(
+            TaskMarket::tasks(task_id).unwrap().status,
+            TaskStatus::InProgress
+        );
+
+        System::set_block_number(5);
+        assert_ok!(TaskMarket::submit_work(
+            RuntimeOrigin::signed(2),
+            task_id,
+            b"bafy-second".to_vec(),
+            ProofType::Cid,
+        ));
+        System::assert_last_event(
+            Event::<Test>::WorkSubmitted {
+                task_id,
+                submission_index: 1,
+                proof_hash: H256::from(sp_io::hashing::blake2_256(b"bafy-second")),
+            }
+            .into(),
+        );
+
+        let first = TaskProofs::<Test>::get(task_id, 0).unwrap();
+        assert_eq!(first.proof.to_vec(), b"bafy-first".to_vec());
+        assert_eq!(first.proof_type, ProofType::Cid);
+        assert_eq!(first.submitted_at, 1);
+        assert_eq!(TaskProofs::<Test>::get(task_id, 1).unwrap().submitted_at, 5);
+
+        // MaxSubmissions is 2: no further revision, only approval or dispute.
+        assert_noop!(
+            TaskMarket::request_revision(RuntimeOrigin::signed(1), task_id),
+            Error::<Test>::TooManySubmissions
+        );
+        assert_ok!(TaskMarket::approve_work(RuntimeOrigin::signed(1), task_id));
+    });
+}
+
+fn bid(bidder: u64, task_id: TaskId) -> sp_runtime::DispatchResult {
+    TaskMarket::bid_on_task(
+        RuntimeOrigin::signed(bidder),
+        task_id,
+        800,
+        b"Proposal".to_vec(),
+    )
+}
+
+#[test]
+fn bids_are_capped_and_can_be_withdrawn() {
+    new_test_ext().execute_with(|| {
+        MaxBidsPerTask::set(2);
+        BidDeposit::set(10);
+        post_default_task(1);
+
+        assert_ok!(bid(2, 0));
+        assert_ok!(bid(3, 0));
+        // Bidding again replaces the bid without taking a slot or deposit.
+        assert_ok!(bid(2, 0));
+        assert_eq!(TaskBidCount::<Test>::get(0), 2);
+        assert_eq!(Balances::reserved_balance(2), 10);
+        assert_noop!(bid(4, 0), Error::<Test>::TooManyBids);
+
+        assert_noop!(
+            TaskMarket::withdraw_bid(RuntimeOrigin::signed(4), 0),
+            Error::<Test>::BidNotFound
+        );
+        assert_ok!(TaskMarket::withdraw_bid(RuntimeOrigin::signed(3), 0));
+        assert_eq!(Balances::reserved_balance(3), 0);
+        assert!(TaskMarket::task_bids(0, 3).is_none());
+        System::assert_last_event(
+            Event::<Test>::BidWithdrawn {
+                task_id: 0,
+                bidder: 3,
+            }
+            .into(),
+        );
+        assert_ok!(bid(4, 0));
+
+        // The accepted bid's deposit is returned and the bid stays.
+        assert_ok!(TaskMarket::assign_task(RuntimeOrigin::signed(1), 0, 2));
+        assert_eq!(Balances::reserved_balance(2), 0);
+        assert_noop!(
+            TaskMarket::withdraw_bid(RuntimeOrigin::signed(2), 0),
+            Error::<Test>::BidAccepted
+        );
+
+        // Losing bidders withdraw to get their deposit back.
+        assert_eq!(Balances::reserved_balance(4), 10);
+        assert_ok!(TaskMarket::withdraw_bid(RuntimeOrigin::signed(4), 0));
+        assert_eq!(Balances::reserved_balance(4), 0);
+    });
+}
+
+#[test]
+fn bid_fails_without_deposit() {
+    new_test_ext().execute_with(|| {
+        BidDeposit::set(20_000);
+        post_default_task(1);
+        assert_noop!(bid(2, 0), Error::<Test>::InsufficientBidDeposit);
+        assert_eq!(TaskBidCount::<Test>::get(0), 0);
+    });
+}
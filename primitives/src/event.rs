@@ -0,0 +1,135 @@
+//! Compact representation of variable-length byte fields in events.
+//!
+//! Events are stored in full in every block, so echoing URLs, identifiers or
+//! free-form reasons verbatim bloats blocks. Pallets emit an [`EventBytes`]
+//! instead: values up to [`EVENT_PREFIX_LEN`] bytes are kept whole, longer
+//! ones are replaced by the blake2-256 hash and length of the original bytes
+//! plus a short prefix for human-readable indexing. Clients that need the
+//! full value read it from storage or the extrinsic and check it against the
+//! hash.
+
+use codec::{Decode, DecodeWithMemTracking, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+use sp_core::{ConstU32, H256};
+use sp_runtime::{BoundedVec, RuntimeDebug};
+
+/// Longest value kept whole, and number of leading bytes kept verbatim of
+/// longer values, in an [`EventBytes`].
+pub const EVENT_PREFIX_LEN: u32 = 32;
+
+/// A byte string for use in events: whole if short, otherwise its hash,
+/// length and bounded prefix.
+#[derive(
+    Clone,
+    Encode,
+    Decode,
+    DecodeWithMemTracking,
+    Eq,
+    PartialEq,
+    RuntimeDebug,
+    TypeInfo,
+    MaxEncodedLen,
+)]
+pub enum EventBytes {
+    /// The full byte string, at most `EVENT_PREFIX_LEN` bytes.
+    Full(BoundedVec<u8, ConstU32<EVENT_PREFIX_LEN>>),
+    /// A byte string longer than `EVENT_PREFIX_LEN` bytes.
+    Trimmed {
+        /// blake2-256 of the full byte string.
+        hash: H256,
+        /// Length of the full byte string.
+        len: u32,
+        /// First `EVENT_PREFIX_LEN` bytes.
+        prefix: BoundedVec<u8, ConstU32<EVENT_PREFIX_LEN>>,
+    },
+}
+
+impl EventBytes {
+    /// Trim `bytes` for an event.
+    pub fn new(bytes: &[u8]) -> Self {
+        if bytes.len() <= EVENT_PREFIX_LEN as usize {
+            return Self::Full(BoundedVec::truncate_from(bytes.to_vec()));
+        }
+        Self::Trimmed {
+            hash: H256(sp_io::hashing::blake2_256(bytes)),
+            len: bytes.len() as u32,
+            prefix: BoundedVec::truncate_from(bytes[..EVENT_PREFIX_LEN as usize].to_vec()),
+        }
+    }
+
+    /// Whether the full value was kept.
+    pub fn is_complete(&self) -> bool {
+        matches!(self, Self::Full(_))
+    }
+
+    /// Length of the full byte string.
+    pub fn len(&self) -> u32 {
+        match self {
+            Self::Full(bytes) => bytes.len() as u32,
+            Self::Trimmed { len, .. } => *len,
+        }
+    }
+
+    /// Whether the full byte string is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The full value if short, otherwise its first `EVENT_PREFIX_LEN` bytes.
+    pub fn prefix(&self) -> &[u8] {
+        match self {
+            Self::Full(bytes) | Self::Trimmed { prefix: bytes, .. } => bytes,
+        }
+    }
+
+    /// Whether `bytes` is the value this was built from.
+    pub fn matches(&self, bytes: &[u8]) -> bool {
+        match self {
+            Self::Full(full) => full.as_slice() == bytes,
+            Self::Trimmed { hash, len, .. } => {
+                *len == bytes.len() as u32 && hash.0 == sp_io::hashing::blake2_256(bytes)
+            }
+        }
+    }
+}
+
+impl From<&[u8]> for EventBytes {
+    fn from(bytes: &[u8]) -> Self {
+        Self::new(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_values_are_kept_whole() {
+        let event = EventBytes::new(b"channel-0");
+        assert!(event.is_complete());
+        assert_eq!(event.prefix(), b"channel-0");
+        assert!(event.matches(b"channel-0"));
+        assert!(!event.matches(b"channel-1"));
+    }
+
+    #[test]
+    fn short_values_encode_smaller_than_a_hash() {
+        // Variant index, compact length and the nine bytes themselves.
+        assert_eq!(EventBytes::new(b"channel-0").encoded_size(), 1 + 1 + 9);
+        assert_eq!(
+            EventBytes::new(&[0u8; EVENT_PREFIX_LEN as usize]).encoded_size(),
+            1 + 1 + EVENT_PREFIX_LEN as usize
+        );
+    }
+
+    #[test]
+    fn long_values_are_truncated_to_the_prefix() {
+        let url = [b'x'; 200];
+        let event = EventBytes::new(&url);
+        assert!(!event.is_complete());
+        assert_eq!(event.len(), 200);
+        assert_eq!(event.prefix(), &url[..EVENT_PREFIX_LEN as usize]);
+        assert!(event.matches(&url));
+        assert!(!event.matches(&url[..199]));
+    }
+}
@@ -0,0 +1,202 @@
+//! Shared `on_initialize` weight budget.
+//!
+//! Several pallets sweep queues at the start of every block: expired
+//! invocations, lapsed pauses, due payments. Each bounds its own work, but
+//! nothing bounds their sum, so a busy block for all of them at once can
+//! exceed the share of the block reserved for initialization. Pallets whose
+//! sweeps can wait a block implement [`BudgetedHook`] instead of doing the
+//! work in `on_initialize`, and the runtime runs them as one tuple under a
+//! single limit.
+//!
+//! The tuple is the priority order: each hook gets whatever the hooks before
+//! it left over. A hook that runs out of budget leaves its unprocessed work
+//! where it is and resumes from there on the next call, so work is delayed
+//! rather than lost.
+//!
+//! Sweeps over deadline indexes keyed by block use [`drain_due`], which walks
+//! the index block by block from a stored cursor instead of scanning it.
+
+use alloc::vec::Vec;
+use sp_runtime::{
+    traits::{One, Saturating},
+    Weight,
+};
+
+/// What a [`BudgetedHook`] did with its share of the budget.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HookUsage {
+    /// Weight consumed.
+    pub used: Weight,
+    /// Whether work already due was left for a later block.
+    pub deferred: bool,
+}
+
+impl HookUsage {
+    /// Combine the usage of two hooks run one after the other.
+    pub fn merge(self, other: HookUsage) -> HookUsage {
+        HookUsage {
+            used: self.used.saturating_add(other.used),
+            deferred: self.deferred || other.deferred,
+        }
+    }
+}
+
+/// Deferrable per-block work run under a shared weight budget.
+pub trait BudgetedHook<BlockNumber> {
+    /// Process work due at `now`, using at most `limit`. Anything not
+    /// processed must still be found by the next call, and is reported
+    /// through [`HookUsage::deferred`].
+    fn run(now: BlockNumber, limit: Weight) -> HookUsage;
+}
+
+/// Drain a queue keyed by block, oldest block first, starting at `cursor`.
+///
+/// `due_at(block, n)` returns up to `n` entries still queued at `block`;
+/// `handle` processes one and must remove it from the queue. Blocks up to and
+/// including `last` are visited in order until `max` items are spent, where
+/// each entry and each block found empty costs one item. `cursor` is left at
+/// the first block that may still hold entries. Returns the items spent.
+pub fn drain_due<BlockNumber, K>(
+    cursor: &mut BlockNumber,
+    last: BlockNumber,
+    max: u32,
+    mut due_at: impl FnMut(BlockNumber, usize) -> Vec<K>,
+    mut handle: impl FnMut(BlockNumber, K),
+) -> u32
+where
+    BlockNumber: Copy + PartialOrd + One + Saturating,
+{
+    let mut spent = 0u32;
+    while *cursor <= last && spent < max {
+        let budget = (max - spent) as usize;
+        let mut due = due_at(*cursor, budget.saturating_add(1));
+        let more = due.len() > budget;
+        due.truncate(budget);
+        spent = spent.saturating_add(due.len().max(1) as u32);
+        for key in due {
+            handle(*cursor, key);
+        }
+        if more {
+            break;
+        }
+        *cursor = cursor.saturating_add(One::one());
+    }
+    spent
+}
+
+impl<BlockNumber> BudgetedHook<BlockNumber> for () {
+    fn run(_: BlockNumber, _: Weight) -> HookUsage {
+        HookUsage::default()
+    }
+}
+
+macro_rules! impl_budgeted_hook_for_tuples {
+    ($($hook:ident),+) => {
+        impl<BlockNumber: Clone, $($hook: BudgetedHook<BlockNumber>),+> BudgetedHook<BlockNumber>
+            for ($($hook,)+)
+        {
+            fn run(now: BlockNumber, limit: Weight) -> HookUsage {
+                let mut usage = HookUsage::default();
+                $(
+                    usage = usage.merge(<$hook as BudgetedHook<BlockNumber>>::run(
+                        now.clone(),
+                        limit.saturating_sub(usage.used),
+                    ));
+                )+
+                usage
+            }
+        }
+    };
+}
+
+impl_budgeted_hook_for_tuples!(A);
+impl_budgeted_hook_for_tuples!(A, B);
+impl_budgeted_hook_for_tuples!(A, B, C);
+impl_budgeted_hook_for_tuples!(A, B, C, D);
+impl_budgeted_hook_for_tuples!(A, B, C, D, E);
+impl_budgeted_hook_for_tuples!(A, B, C, D, E, F);
+impl_budgeted_hook_for_tuples!(A, B, C, D, E, F, G);
+impl_budgeted_hook_for_tuples!(A, B, C, D, E, F, G, H);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Has 30 ref-time of work due every call.
+    struct Greedy;
+    impl BudgetedHook<u32> for Greedy {
+        fn run(_: u32, limit: Weight) -> HookUsage {
+            let want = Weight::from_parts(30, 0);
+            if limit.all_gte(want) {
+                HookUsage {
+                    used: want,
+                    deferred: false,
+                }
+            } else {
+                HookUsage {
+                    used: Weight::zero(),
+                    deferred: true,
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn drain_due_walks_blocks_in_order_within_budget() {
+        let queue = core::cell::RefCell::new(alloc::collections::BTreeMap::from([
+            (2u32, alloc::vec![20, 21, 22]),
+            (4, alloc::vec![40]),
+        ]));
+        let mut drained = Vec::new();
+        let mut cursor = 1u32;
+        let mut drain = |cursor: &mut u32, last, max| {
+            drain_due(
+                cursor,
+                last,
+                max,
+                |block, n| {
+                    queue
+                        .borrow()
+                        .get(&block)
+                        .map(|ids| ids.iter().copied().take(n).collect())
+                        .unwrap_or_default()
+                },
+                |block, id| {
+                    if let Some(ids) = queue.borrow_mut().get_mut(&block) {
+                        ids.retain(|queued| *queued != id);
+                    }
+                    drained.push(id);
+                },
+            )
+        };
+
+        // Block 1 is empty, then two of block 2's three entries fit.
+        assert_eq!(drain(&mut cursor, 4, 3), 3);
+        assert_eq!(cursor, 2);
+        // The rest of block 2, empty block 3, and block 4.
+        assert_eq!(drain(&mut cursor, 4, 10), 3);
+        assert_eq!(cursor, 5);
+        // Nothing is due past `last`.
+        assert_eq!(drain(&mut cursor, 4, 10), 0);
+        assert_eq!(drained, alloc::vec![20, 21, 22, 40]);
+    }
+
+    #[test]
+    fn earlier_hooks_take_priority() {
+        let budget = Weight::from_parts(70, 0);
+        assert_eq!(
+            <(Greedy, Greedy, Greedy)>::run(1, budget),
+            HookUsage {
+                used: Weight::from_parts(60, 0),
+                deferred: true,
+            }
+        );
+        assert_eq!(
+            <(Greedy, Greedy)>::run(1, budget),
+            HookUsage {
+                used: Weight::from_parts(60, 0),
+                deferred: false,
+            }
+        );
+    }
+}
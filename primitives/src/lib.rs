@@ -0,0 +1,14 @@
+//! # ClawChain Primitives
+//!
+//! Small types shared by several pallets that do not belong to any one of them.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub mod event;
+pub mod hook_budget;
+pub mod migration;
+
+pub use event::EventBytes;
+pub use hook_budget::{BudgetedHook, HookUsage};
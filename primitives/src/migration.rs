@@ -0,0 +1,194 @@
+//! Resumable in-place translation of storage maps.
+//!
+//! Raising a bound such as `MaxTagsPerListing` or `MaxInboxSize` changes the
+//! type a map's values are read as. Rewriting every entry in one
+//! `on_runtime_upgrade` does not fit in a block once the map is large, so
+//! [`translate_chunk`] rewrites at most a given number of entries per call
+//! and records the last key it reached under a progress key. A pallet drives
+//! it from `on_idle` (or any other hook) until it reports
+//! [`ChunkOutcome::Complete`]; an interrupted run picks up where the last
+//! call stopped. Until then, reads of not-yet-translated entries can go
+//! through [`decode_lenient`].
+//!
+//! The helpers work on raw storage, so they take the map's prefix (see
+//! [`storage_prefix`]) rather than a typed storage item.
+
+use alloc::vec::Vec;
+use codec::{Decode, DecodeAll, Encode};
+
+/// Prefix of a FRAME storage item: `twox_128(pallet) ++ twox_128(item)`.
+pub fn storage_prefix(pallet: &[u8], item: &[u8]) -> [u8; 32] {
+    let mut prefix = [0u8; 32];
+    prefix[..16].copy_from_slice(&sp_io::hashing::twox_128(pallet));
+    prefix[16..].copy_from_slice(&sp_io::hashing::twox_128(item));
+    prefix
+}
+
+/// Result of one [`translate_chunk`] call.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChunkOutcome {
+    /// `processed` entries were visited and more remain.
+    Partial { processed: u32 },
+    /// The map is fully translated and the progress key has been cleared.
+    Complete { processed: u32 },
+}
+
+impl ChunkOutcome {
+    /// Entries visited by the call.
+    pub fn processed(&self) -> u32 {
+        match self {
+            Self::Partial { processed } | Self::Complete { processed } => *processed,
+        }
+    }
+}
+
+/// Last key a translation under `progress_key` reached, if one is under way.
+pub fn progress(progress_key: &[u8]) -> Option<Vec<u8>> {
+    sp_io::storage::get(progress_key).map(|bytes| bytes.to_vec())
+}
+
+/// Translate up to `max_entries` values under `prefix`, resuming after the
+/// key saved in `progress_key`.
+///
+/// Each value that decodes as `Old` is passed to `f` with its full key and
+/// replaced by the result, or removed if `f` returns `None`. Values that do
+/// not decode as `Old` (for instance ones already written in the new layout)
+/// are left alone but still count as visited.
+pub fn translate_chunk<Old: Decode, New: Encode>(
+    prefix: &[u8],
+    progress_key: &[u8],
+    max_entries: u32,
+    mut f: impl FnMut(&[u8], Old) -> Option<New>,
+) -> ChunkOutcome {
+    let mut cursor = progress(progress_key).unwrap_or_else(|| prefix.to_vec());
+    let mut processed = 0u32;
+
+    while processed < max_entries {
+        let Some(key) = sp_io::storage::next_key(&cursor).filter(|k| k.starts_with(prefix)) else {
+            sp_io::storage::clear(progress_key);
+            return ChunkOutcome::Complete { processed };
+        };
+
+        if let Some(old) =
+            sp_io::storage::get(&key).and_then(|bytes| Old::decode_all(&mut &bytes[..]).ok())
+        {
+            match f(&key, old) {
+                Some(new) => sp_io::storage::set(&key, &new.encode()),
+                None => sp_io::storage::clear(&key),
+            }
+        }
+        processed += 1;
+        cursor = key;
+    }
+
+    // Stopped on the budget: finish now if nothing is left, so callers do
+    // not need an extra empty step.
+    if sp_io::storage::next_key(&cursor).is_some_and(|k| k.starts_with(prefix)) {
+        sp_io::storage::set(progress_key, &cursor);
+        ChunkOutcome::Partial { processed }
+    } else {
+        sp_io::storage::clear(progress_key);
+        ChunkOutcome::Complete { processed }
+    }
+}
+
+/// Decode `bytes` as `New`, falling back to `Old` translated by `f` for
+/// entries a chunked translation has not reached yet.
+pub fn decode_lenient<Old: Decode, New: Decode>(
+    bytes: &[u8],
+    f: impl FnOnce(Old) -> Option<New>,
+) -> Option<New> {
+    New::decode_all(&mut &bytes[..])
+        .ok()
+        .or_else(|| Old::decode_all(&mut &bytes[..]).ok().and_then(f))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROGRESS: &[u8] = b":test:migration:progress";
+
+    fn prefix() -> [u8; 32] {
+        storage_prefix(b"Test", b"Map")
+    }
+
+    fn key(i: u32) -> Vec<u8> {
+        let mut key = prefix().to_vec();
+        key.extend(i.to_be_bytes());
+        key
+    }
+
+    fn seed(n: u32) {
+        for i in 0..n {
+            sp_io::storage::set(&key(i), &(i as u16).encode());
+        }
+        // An unrelated item sorting right after the map must not be touched.
+        let mut outside = prefix().to_vec();
+        outside[31] = outside[31].wrapping_add(1);
+        sp_io::storage::set(&outside, &7u16.encode());
+    }
+
+    fn widen(_: &[u8], old: u16) -> Option<(u16, u32)> {
+        Some((old, 0))
+    }
+
+    #[test]
+    fn chunks_resume_from_saved_progress() {
+        sp_io::TestExternalities::default().execute_with(|| {
+            seed(5);
+
+            let first = translate_chunk(&prefix(), PROGRESS, 2, widen);
+            assert_eq!(first, ChunkOutcome::Partial { processed: 2 });
+            assert_eq!(progress(PROGRESS), Some(key(1)));
+            // Translated and untranslated entries coexist meanwhile.
+            let read = |i| {
+                let bytes = sp_io::storage::get(&key(i)).unwrap();
+                decode_lenient::<u16, (u16, u32)>(&bytes, |old| Some((old, 0)))
+            };
+            assert_eq!(read(0), Some((0, 0)));
+            assert_eq!(read(4), Some((4, 0)));
+
+            // A restart only has the progress key to go on.
+            assert_eq!(
+                translate_chunk(&prefix(), PROGRESS, 2, widen),
+                ChunkOutcome::Partial { processed: 2 }
+            );
+            assert_eq!(
+                translate_chunk(&prefix(), PROGRESS, 2, widen),
+                ChunkOutcome::Complete { processed: 1 }
+            );
+            assert_eq!(progress(PROGRESS), None);
+
+            for i in 0..5 {
+                let bytes = sp_io::storage::get(&key(i)).unwrap();
+                assert_eq!(
+                    <(u16, u32)>::decode_all(&mut &bytes[..]).unwrap(),
+                    (i as u16, 0)
+                );
+            }
+        });
+    }
+
+    #[test]
+    fn exact_budget_completes_and_none_removes() {
+        sp_io::TestExternalities::default().execute_with(|| {
+            seed(4);
+
+            let outcome = translate_chunk(&prefix(), PROGRESS, 4, |_, old: u16| {
+                old.is_multiple_of(2).then_some((old, 1u32))
+            });
+            assert_eq!(outcome, ChunkOutcome::Complete { processed: 4 });
+            assert_eq!(progress(PROGRESS), None);
+            assert!(sp_io::storage::get(&key(1)).is_none());
+            assert!(sp_io::storage::get(&key(2)).is_some());
+
+            let mut outside = prefix().to_vec();
+            outside[31] = outside[31].wrapping_add(1);
+            assert_eq!(
+                sp_io::storage::get(&outside).map(|b| b.to_vec()),
+                Some(7u16.encode())
+            );
+        });
+    }
+}
@@ -0,0 +1,199 @@
+//! Unified identity resolution across agent-registry, agent-did and
+//! reputation, served by [`IdentityApi`].
+//!
+//! Every client SDK used to stitch these together itself: look up the DID
+//! document by account, find the account's agents, map a DID string back to
+//! its owner, and fetch a reputation summary. [`resolve`] does all of it from
+//! whichever identifier the caller happens to hold.
+
+use crate::{AccountId, Balance, BlockNumber, Runtime};
+use alloc::vec::Vec;
+use codec::{Decode, Encode};
+use pallet_agent_registry::{AgentId, AgentStatus};
+use scale_info::TypeInfo;
+use sp_runtime::{BoundedVec, RuntimeDebug};
+
+/// Prefix of every ClawChain DID.
+pub const DID_PREFIX: &[u8] = b"did:claw:";
+
+/// Any of the forms an identity is referred to by.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub enum Identifier {
+    Account(AccountId),
+    Agent(AgentId),
+    /// A `did:claw:` string: either `did:claw:0x<hex account id>` or the
+    /// DID an agent was registered under.
+    Did(Vec<u8>),
+}
+
+/// A verification method of a DID document.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct KeySummary {
+    pub id: Vec<u8>,
+    pub key_type: Vec<u8>,
+    pub public_key_multibase: Vec<u8>,
+}
+
+/// The account's DID document.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct DidSummary {
+    /// `did:claw:0x<hex account id>`.
+    pub did: Vec<u8>,
+    pub deactivated: bool,
+    pub created: BlockNumber,
+    pub updated: BlockNumber,
+    pub service_endpoints: u32,
+    pub verification_methods: Vec<KeySummary>,
+    /// Block the owner's last active agent was deregistered, while the DID
+    /// is dormant.
+    pub dormant_since: Option<BlockNumber>,
+}
+
+/// An agent owned by the account.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct AgentSummary {
+    pub agent_id: AgentId,
+    pub did: Vec<u8>,
+    pub status: AgentStatus,
+    /// Registry-local score in basis points.
+    pub reputation: u32,
+}
+
+/// The account's standing in `pallet-reputation`.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct ReputationSummary {
+    /// Score in basis points (0–10000).
+    pub score: u32,
+    pub total_tasks_completed: u32,
+    pub successful_completions: u32,
+    pub disputes_won: u32,
+    pub disputes_lost: u32,
+    pub total_earned: Balance,
+}
+
+/// Everything the chain knows about one identity.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct IdentityRecord {
+    pub account: AccountId,
+    /// `None` if the account never registered a DID.
+    pub did: Option<DidSummary>,
+    pub agents: Vec<AgentSummary>,
+    pub reputation: ReputationSummary,
+}
+
+sp_api::decl_runtime_apis! {
+    /// Single entry point for resolving identities.
+    pub trait IdentityApi {
+        /// The identity behind `identifier`, or `None` if an agent id or
+        /// DID does not lead to an account.
+        fn resolve(identifier: Identifier) -> Option<IdentityRecord>;
+    }
+}
+
+/// `did:claw:0x` followed by the account id in hex.
+pub fn account_did(account: &AccountId) -> Vec<u8> {
+    const HEX: &[u8; 16] = b"0123456789abcdef";
+    let bytes: &[u8] = account.as_ref();
+    let mut did = Vec::with_capacity(DID_PREFIX.len() + 2 + bytes.len() * 2);
+    did.extend_from_slice(DID_PREFIX);
+    did.extend_from_slice(b"0x");
+    for byte in bytes {
+        did.push(HEX[usize::from(byte >> 4)]);
+        did.push(HEX[usize::from(byte & 0x0f)]);
+    }
+    did
+}
+
+/// The account a `did:claw:0x<hex>` string encodes, if it is one.
+fn account_from_did(did: &[u8]) -> Option<AccountId> {
+    let hex = did.strip_prefix(DID_PREFIX)?.strip_prefix(b"0x")?;
+    if hex.len() != 64 {
+        return None;
+    }
+    let nibble = |c: u8| match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        b'A'..=b'F' => Some(c - b'A' + 10),
+        _ => None,
+    };
+    let mut bytes = [0u8; 32];
+    for (byte, pair) in bytes.iter_mut().zip(hex.chunks_exact(2)) {
+        *byte = (nibble(pair[0])? << 4) | nibble(pair[1])?;
+    }
+    Some(AccountId::from(bytes))
+}
+
+/// The account `identifier` refers to, if any.
+///
+/// A DID that is not in the hex account form resolves to the owner of the
+/// first agent registered under it, until that agent is deregistered.
+pub fn account_of(identifier: Identifier) -> Option<AccountId> {
+    match identifier {
+        Identifier::Account(account) => Some(account),
+        Identifier::Agent(agent_id) => {
+            pallet_agent_registry::AgentRegistry::<Runtime>::get(agent_id).map(|info| info.owner)
+        }
+        Identifier::Did(did) => account_from_did(&did).or_else(|| {
+            let did = BoundedVec::try_from(did).ok()?;
+            let agent_id = pallet_agent_registry::AgentsByDid::<Runtime>::get(did)?;
+            pallet_agent_registry::AgentRegistry::<Runtime>::get(agent_id).map(|info| info.owner)
+        }),
+    }
+}
+
+/// Resolve `identifier` into a full [`IdentityRecord`].
+///
+/// An account with no DID, no agents and default reputation is still
+/// returned when named directly; the other forms return `None` unless they
+/// lead to an account.
+pub fn resolve(identifier: Identifier) -> Option<IdentityRecord> {
+    let account = account_of(identifier)?;
+
+    let did = pallet_agent_did::DIDDocuments::<Runtime>::get(&account).map(|doc| DidSummary {
+        did: account_did(&account),
+        deactivated: doc.deactivated,
+        created: doc.created,
+        updated: doc.updated,
+        service_endpoints: doc.service_endpoint_count,
+        verification_methods: pallet_agent_did::VerificationMethods::<Runtime>::iter_prefix_values(
+            &account,
+        )
+        .map(|method| KeySummary {
+            id: method.id.into_inner(),
+            key_type: method.key_type.into_inner(),
+            public_key_multibase: method.public_key_multibase.into_inner(),
+        })
+        .collect(),
+        dormant_since: pallet_agent_did::DormantControllers::<Runtime>::get(&account),
+    });
+
+    let agents = pallet_agent_registry::OwnerAgents::<Runtime>::get(&account)
+        .into_iter()
+        .filter_map(|agent_id| {
+            let info = pallet_agent_registry::AgentRegistry::<Runtime>::get(agent_id)?;
+            Some(AgentSummary {
+                agent_id,
+                did: info.did.into_inner(),
+                status: info.status,
+                reputation: info.reputation,
+            })
+        })
+        .collect();
+
+    let info = pallet_reputation::Reputations::<Runtime>::get(&account);
+    let reputation = ReputationSummary {
+        score: info.score,
+        total_tasks_completed: info.total_tasks_completed,
+        successful_completions: info.successful_completions,
+        disputes_won: info.disputes_won,
+        disputes_lost: info.disputes_lost,
+        total_earned: info.total_earned,
+    };
+
+    Some(IdentityRecord {
+        account,
+        did,
+        agents,
+        reputation,
+    })
+}
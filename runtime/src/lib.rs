@@ -14,6 +14,8 @@ include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
 extern crate alloc;
 
+pub mod identity;
+
 use alloc::{vec, vec::Vec};
 // codec and scale_info used by FRAME macros
 use frame_election_provider_support::{
@@ -23,7 +25,9 @@ use frame_support::{
     derive_impl,
     genesis_builder_helper::{build_state, get_preset},
     parameter_types,
-    traits::{tokens::PayFromAccount, ConstBool, ConstU128, ConstU32, ConstU64, ConstU8},
+    traits::{
+        tokens::PayFromAccount, ConstBool, ConstU128, ConstU32, ConstU64, ConstU8, Contains, Get,
+    },
     weights::{
         constants::{
             BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_REF_TIME_PER_SECOND,
@@ -105,10 +109,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
     spec_name: create_runtime_str!("clawchain"),
     impl_name: create_runtime_str!("clawchain-node"),
     authoring_version: 1,
-    spec_version: 200,
+    spec_version: 201,
     impl_version: 1,
     apis: RUNTIME_API_VERSIONS,
-    transaction_version: 1,
+    transaction_version: 2,
     system_version: 1,
 };
 
@@ -172,6 +176,28 @@ parameter_types! {
         .build_or_panic();
 }
 
+/// Base call filter coordinating the task-market deprecation.
+///
+/// Once the governance-set `NewWorkCutoff` is reached, `post_task` and
+/// `bid_on_task` are rejected so no new work enters the deprecated market.
+/// While a task-market storage migration is still running, every
+/// task-market call is rejected. Every other call — including task-market
+/// approvals, disputes and cancellations for existing tasks — is allowed.
+pub struct TaskMarketDeprecationFilter;
+
+impl Contains<RuntimeCall> for TaskMarketDeprecationFilter {
+    fn contains(call: &RuntimeCall) -> bool {
+        match call {
+            RuntimeCall::TaskMarket(_) if TaskMarket::migrating() => false,
+            RuntimeCall::TaskMarket(
+                pallet_task_market::Call::post_task { .. }
+                | pallet_task_market::Call::bid_on_task { .. },
+            ) => TaskMarket::accepts_new_work(),
+            _ => true,
+        }
+    }
+}
+
 /// The default types are being injected by [`derive_impl`](`frame_support::derive_impl`) from
 /// [`SoloChainDefaultConfig`](`struct@frame_system::config_preludes::SolochainDefaultConfig`),
 /// but overridden as needed.
@@ -179,6 +205,8 @@ parameter_types! {
 impl frame_system::Config for Runtime {
     /// The block body type.
     type Block = Block;
+    /// Rejects new task-market work after the deprecation cutoff.
+    type BaseCallFilter = TaskMarketDeprecationFilter;
     /// Block & extrinsics weights: base values and limits.
     type BlockWeights = RuntimeBlockWeights;
     /// The maximum length of a block (in bytes).
@@ -231,7 +259,7 @@ parameter_types! {
 
 impl pallet_balances::Config for Runtime {
     type MaxLocks = ConstU32<50>;
-    type MaxReserves = ();
+    type MaxReserves = ConstU32<50>;
     type ReserveIdentifier = [u8; 8];
     /// The type for recording an account's balance.
     type Balance = Balance;
@@ -365,7 +393,7 @@ impl pallet_staking::Config for Runtime {
     type VoterList = BagsList;
     type TargetList = pallet_staking::UseValidatorsMap<Runtime>;
     type MaxUnlockingChunks = ConstU32<32>;
-    type EventListeners = ();
+    type EventListeners = GasQuota;
     type WeightInfo = ();
     type BenchmarkingConfig = StakingBenchmarkingConfig;
     type NominationsQuota = pallet_staking::FixedNominationsQuota<16>;
@@ -462,6 +490,84 @@ impl pallet_agent_registry::Config for Runtime {
     type MaxDidLength = ConstU32<256>;
     type MaxMetadataLength = ConstU32<4096>;
     type MaxAgentsPerOwner = ConstU32<100>;
+    type RegistrarOrigin = frame_system::EnsureRoot<AccountId>;
+    type OffchainSignature = Signature;
+    type SigningPublicKey = <Signature as Verify>::Signer;
+    type MaxBatchRegistrations = ConstU32<50>;
+    type Currency = Balances;
+    type OnboardingFeeAllowance = ConstU128<{ UNITS / 10 }>;
+    type MaxOnboardingGrantsPerPeriod = ConstU32<50>;
+    type OnboardingPeriod = ConstU32<HOURS>;
+    type LifecycleHook = AgentDeregistrationCascade;
+    type MaxPendingCleanups = ConstU32<256>;
+}
+
+/// Carries an agent's deregistration into the other pallets. The DID, its
+/// messaging webhooks and quota delegations belong to the owner account, so
+/// they are touched only once the owner has no active agent left: the DID is
+/// marked dormant right away and delegations are revoked over idle blocks.
+pub struct AgentDeregistrationCascade;
+
+impl AgentDeregistrationCascade {
+    /// Whether `owner` still has an active agent, with the weight of finding
+    /// out.
+    fn owner_still_active(owner: &AccountId) -> (bool, Weight) {
+        let agents = AgentRegistry::owner_agents(owner);
+        let checked = RocksDbWeight::get().reads(1 + agents.len() as u64);
+        let still_active = agents.iter().any(|id| {
+            AgentRegistry::agent_registry(id)
+                .is_some_and(|agent| agent.status == pallet_agent_registry::AgentStatus::Active)
+        });
+        (still_active, checked)
+    }
+}
+
+impl pallet_agent_registry::AgentLifecycleHook<AccountId> for AgentDeregistrationCascade {
+    fn on_deregistered(
+        _agent_id: pallet_agent_registry::AgentId,
+        owner: &AccountId,
+        report: &mut pallet_agent_registry::CascadeReport,
+    ) {
+        if Self::owner_still_active(owner).0 {
+            return;
+        }
+        if let Some(removed) = AgentDid::mark_dormant(owner) {
+            report.did_marked = true;
+            report.messaging_endpoints_removed = removed;
+        }
+    }
+
+    fn on_deregistered_weight() -> Weight {
+        let max_agents: u32 = <Runtime as pallet_agent_registry::Config>::MaxAgentsPerOwner::get();
+        RocksDbWeight::get()
+            .reads(1 + u64::from(max_agents))
+            .saturating_add(AgentDid::mark_dormant_weight())
+    }
+
+    fn cleanup(
+        _agent_id: pallet_agent_registry::AgentId,
+        owner: &AccountId,
+        max_weight: Weight,
+        report: &mut pallet_agent_registry::CascadeReport,
+    ) -> (Weight, bool) {
+        let (still_active, checked) = Self::owner_still_active(owner);
+        if still_active {
+            return (checked, true);
+        }
+
+        let per_delegation = RocksDbWeight::get().reads_writes(2, 3);
+        let limit = max_weight
+            .saturating_sub(checked)
+            .checked_div_per_component(&per_delegation)
+            .unwrap_or(0);
+        let (revoked, done) =
+            GasQuota::revoke_delegations_of(owner, u32::try_from(limit).unwrap_or(u32::MAX));
+        report.delegations_revoked = report.delegations_revoked.saturating_add(revoked);
+        (
+            checked.saturating_add(per_delegation.saturating_mul(revoked as u64)),
+            done,
+        )
+    }
 }
 
 /// Configure the CLAW token pallet.
@@ -471,6 +577,8 @@ impl pallet_claw_token::Config for Runtime {
     type Currency = Balances;
     type AirdropPool = ConstU128<{ 400_000_000 * 1_000_000_000_000u128 }>; // 40% of 1B CLAW
     type MaxContributionScore = ConstU64<{ u64::MAX }>;
+    type MaxTimelockBlocks = ConstU32<{ 30 * DAYS }>;
+    type MaxPreimageLength = ConstU32<64>;
 }
 
 parameter_types! {
@@ -479,15 +587,21 @@ parameter_types! {
     pub const InitialReputation: u32 = 5000;
     pub const MaxReputationDelta: u32 = 500;
     pub const MaxHistoryLength: u32 = 100;
+    pub const MaxLeaderboardSize: u32 = 100;
+    pub const LeaderboardEraLength: BlockNumber = DAYS;
+    pub const LeaderboardHistoryDepth: u32 = 30;
 
     // Task Market parameters
     pub const TaskMarketPalletId: PalletId = PalletId(*b"taskmark");
+    pub const TaskExpireBountyShare: sp_runtime::Perbill = sp_runtime::Perbill::from_percent(1);
     pub const MaxTitleLength: u32 = 128;
     pub const MaxDescriptionLength: u32 = 1024;
     pub const MaxProposalLength: u32 = 512;
     pub const MaxBidsPerTask: u32 = 20;
+    pub const TaskBidDeposit: Balance = UNITS; // 1 CLAW, refundable
     pub const MinTaskReward: Balance = 100 * UNITS; // 100 CLAW minimum
     pub const MaxActiveTasksPerAccount: u32 = 50;
+    pub const DefaultMinBidderReputation: u32 = 3000; // 30%, below InitialReputation
 }
 
 impl pallet_reputation::Config for Runtime {
@@ -498,6 +612,9 @@ impl pallet_reputation::Config for Runtime {
     type InitialReputation = InitialReputation;
     type MaxReputationDelta = MaxReputationDelta;
     type MaxHistoryLength = MaxHistoryLength;
+    type MaxLeaderboardSize = MaxLeaderboardSize;
+    type LeaderboardEraLength = LeaderboardEraLength;
+    type LeaderboardHistoryDepth = LeaderboardHistoryDepth;
 }
 
 impl pallet_task_market::Config for Runtime {
@@ -512,6 +629,16 @@ impl pallet_task_market::Config for Runtime {
     type MaxBidsPerTask = MaxBidsPerTask;
     type MinTaskReward = MinTaskReward;
     type MaxActiveTasksPerAccount = MaxActiveTasksPerAccount;
+    type DefaultMinBidderReputation = DefaultMinBidderReputation;
+    type MaxExpirationsPerBlock = ConstU32<50>;
+    type ExpireBountyShare = TaskExpireBountyShare;
+    type MaxProofLength = ConstU32<256>;
+    type MaxSubmissions = ConstU32<5>;
+    type BidDeposit = TaskBidDeposit;
+}
+
+parameter_types! {
+    pub const RpcEraRewardBudget: Balance = 10 * UNITS;
 }
 
 /// Configure the RPC registry pallet.
@@ -523,6 +650,11 @@ impl pallet_rpc_registry::Config for Runtime {
     type MaxNodesPerOwner = ConstU32<10>;
     type MaxActiveNodes = ConstU32<1000>;
     type MaxHeartbeatInterval = ConstU32<300>; // 300 blocks = ~30 min at 6s/block
+    type Currency = Balances;
+    type RewardSource = TreasuryAccount;
+    type EraRewardBudget = RpcEraRewardBudget;
+    type RewardEraLength = ConstU32<{ SESSION_LENGTH * SESSIONS_PER_ERA }>;
+    type UptimeSampleInterval = ConstU32<100>; // one sample per session
 }
 // Create the runtime by composing the FRAME pallets that were previously configured.
 parameter_types! {
@@ -532,6 +664,14 @@ parameter_types! {
     pub const GasQuotaBaseFee: u128 = 1_000_000_000; // 0.001 CLAW
     pub const GasQuotaFeeDiscount: sp_runtime::Perbill = sp_runtime::Perbill::from_percent(90);
     pub const GasQuotaMinFree: u32 = 10;
+    pub const GasQuotaHighReputation: u32 = 8_000; // 80%
+    pub const GasQuotaVerifiedContributor: u32 = 9_500; // 95%
+    pub const GasQuotaMaxRollover: sp_runtime::Percent = sp_runtime::Percent::from_percent(50);
+    pub const GasQuotaMaxClassWeight: u32 = 100;
+    pub const GasQuotaUnbondingStakeWeight: sp_runtime::Perbill =
+        sp_runtime::Perbill::from_percent(50);
+    pub const GasQuotaPalletId: PalletId = PalletId(*b"gasquota");
+    pub const GasQuotaFeeSweepThreshold: u128 = UNITS; // 1 CLAW
 }
 
 impl pallet_gas_quota::Config for Runtime {
@@ -543,12 +683,102 @@ impl pallet_gas_quota::Config for Runtime {
     type UnlimitedStakeThreshold = GasQuotaUnlimitedThreshold;
     type BaseFeePerTx = GasQuotaBaseFee;
     type FeeDiscountPerKStake = GasQuotaFeeDiscount;
+    type FeeSponsor = OnboardingFeeSponsor;
+    type StakeProvider = StakingStakeProvider;
+    type ReputationProvider = Reputation;
+    type HighReputationScore = GasQuotaHighReputation;
+    type VerifiedContributorScore = GasQuotaVerifiedContributor;
+    type MaxDelegations = ConstU32<32>;
+    type MaxRolloverPct = GasQuotaMaxRollover;
+    type MaxClassWeight = GasQuotaMaxClassWeight;
+    type QuotaWeigher = RuntimeQuotaWeigher;
+    type PalletId = GasQuotaPalletId;
+    type TreasuryAccount = TreasuryAccount;
+    type FeeSweepThreshold = GasQuotaFeeSweepThreshold;
+    type GovernanceOrigin = frame_system::EnsureRoot<AccountId>;
+    type StakeSnapshotEras = ConstU32<4>;
+    type MaxSnapshotPrunePerBlock = ConstU32<256>;
+    type UnbondingStakeWeight = GasQuotaUnbondingStakeWeight;
+}
+
+/// Quota units per call: calls that write large metadata cost more than
+/// plain messaging or voting. Each pallet's calls are counted under a
+/// `CallClass`, whose governance-set weight scales the units.
+pub struct RuntimeQuotaWeigher;
+
+impl pallet_gas_quota::QuotaWeigher<RuntimeCall> for RuntimeQuotaWeigher {
+    fn quota_units(call: &RuntimeCall) -> u32 {
+        match call {
+            RuntimeCall::AgentRegistry(
+                pallet_agent_registry::Call::register_agent { .. }
+                | pallet_agent_registry::Call::update_metadata { .. },
+            ) => 3,
+            RuntimeCall::TaskMarket(pallet_task_market::Call::post_task { .. }) => 3,
+            RuntimeCall::AgentReceipts(pallet_agent_receipts::Call::submit_receipt { .. }) => 2,
+            _ => 1,
+        }
+    }
+
+    fn call_class(call: &RuntimeCall) -> Option<pallet_gas_quota::CallClass> {
+        use pallet_gas_quota::CallClass;
+        match call {
+            RuntimeCall::TaskMarket(..) => Some(CallClass::Market),
+            RuntimeCall::IbcLite(..) => Some(CallClass::Messaging),
+            RuntimeCall::QuadraticGovernance(..) => Some(CallClass::Governance),
+            RuntimeCall::AgentRegistry(..) | RuntimeCall::AgentDid(..) => Some(CallClass::Identity),
+            _ => None,
+        }
+    }
+}
+
+/// Reads an account's active stake from pallet-staking.
+pub struct StakingStakeProvider;
+
+impl pallet_gas_quota::StakeProvider<AccountId, Balance> for StakingStakeProvider {
+    fn active_stake(who: &AccountId) -> Balance {
+        <Staking as sp_staking::StakingInterface>::stake(who)
+            .map(|stake| stake.active)
+            .unwrap_or_default()
+    }
+
+    fn unbonding_stake(who: &AccountId) -> Balance {
+        <Staking as sp_staking::StakingInterface>::stake(who)
+            .map(|stake| stake.total.saturating_sub(stake.active))
+            .unwrap_or_default()
+    }
+
+    fn current_era() -> u32 {
+        <Staking as sp_staking::StakingInterface>::current_era()
+    }
+}
+
+/// Pays over-quota fees from the treasury while the sender's onboarding fee
+/// allowance (pallet-agent-registry) covers them.
+pub struct OnboardingFeeSponsor;
+
+impl pallet_gas_quota::FeeSponsor<AccountId, Balance> for OnboardingFeeSponsor {
+    fn sponsor_of(who: &AccountId, fee: Balance) -> Option<AccountId> {
+        (AgentRegistry::fee_allowance(who) >= fee).then(TreasuryAccount::get)
+    }
+
+    fn on_sponsored(who: &AccountId, fee: Balance) {
+        AgentRegistry::consume_fee_allowance(who, fee);
+    }
+
+    fn sponsor_weight() -> Weight {
+        // The allowance, its update and the provider reference dropped once
+        // it is used up.
+        <Runtime as frame_system::Config>::DbWeight::get().reads_writes(2, 2)
+    }
 }
 // Configure the quadratic governance pallet.
 parameter_types! {
     pub const GovMinProposalDeposit: Balance = 100 * UNITS;      // 100 CLAW
     pub const GovVotingPeriod: BlockNumber = 50_400;             // ~7 days at 6s/block
     pub const GovMinQuorumPct: u32 = 10;                         // require >= 10 total vote-weight
+    pub const GovSpamSlashShare: sp_runtime::Perbill = sp_runtime::Perbill::from_percent(50);
+    pub const GovSpamYesThreshold: sp_runtime::Perbill = sp_runtime::Perbill::from_percent(10);
+    pub const GovSpamQuorumThreshold: sp_runtime::Perbill = sp_runtime::Perbill::from_percent(50);
 }
 
 /// Configure the Quadratic Governance pallet (ADR-004).
@@ -558,7 +788,100 @@ impl pallet_quadratic_governance::Config for Runtime {
     type MinProposalDeposit = GovMinProposalDeposit;
     type VotingPeriod = GovVotingPeriod;
     type MinQuorumPct = GovMinQuorumPct;
+    type TreasuryAccount = TreasuryAccount;
+    type SpamSlashShare = GovSpamSlashShare;
+    type SpamYesThreshold = GovSpamYesThreshold;
+    type SpamQuorumThreshold = GovSpamQuorumThreshold;
     type WeightInfo = ();
+    type ParameterChange = GovernanceParameter;
+    type ParameterHandler = GovernanceParameterHandler;
+    type EnactmentOrigin = frame_system::EnsureRoot<AccountId>;
+}
+
+/// Parameter changes quadratic-governance proposals carry, enacted by Root
+/// once the proposal passes.
+#[derive(
+    Clone,
+    codec::Encode,
+    codec::Decode,
+    codec::DecodeWithMemTracking,
+    Eq,
+    PartialEq,
+    sp_runtime::RuntimeDebug,
+    scale_info::TypeInfo,
+    codec::MaxEncodedLen,
+)]
+pub enum GovernanceParameter {
+    /// Quota units charged per call of a gas-quota class.
+    SetQuotaClassWeight {
+        class: pallet_gas_quota::CallClass,
+        weight: u32,
+    },
+    /// Reputation delta (basis points) for an event source; `None` restores the default.
+    SetReputationWeight {
+        source: pallet_reputation::ReputationSource,
+        weight: Option<i32>,
+    },
+    /// Block from which task-market refuses new work; `None` clears it.
+    SetTaskMarketCutoff { cutoff: Option<BlockNumber> },
+}
+
+/// Routes each [`GovernanceParameter`] to the root-only setter of the pallet
+/// that owns it.
+pub struct GovernanceParameterHandler;
+
+impl pallet_quadratic_governance::ParameterChangeHandler<GovernanceParameter>
+    for GovernanceParameterHandler
+{
+    fn validate(change: &GovernanceParameter) -> sp_runtime::DispatchResult {
+        match change {
+            GovernanceParameter::SetQuotaClassWeight { weight, .. }
+                if !(1..=GasQuotaMaxClassWeight::get()).contains(weight) =>
+            {
+                Err(pallet_gas_quota::Error::<Runtime>::InvalidClassWeight.into())
+            }
+            GovernanceParameter::SetReputationWeight {
+                weight: Some(w), ..
+            } if w.unsigned_abs() > 10_000 => {
+                Err(pallet_reputation::Error::<Runtime>::InvalidWeight.into())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn apply(change: &GovernanceParameter) -> sp_runtime::DispatchResult {
+        let root = || RuntimeOrigin::root();
+        match change.clone() {
+            GovernanceParameter::SetQuotaClassWeight { class, weight } => {
+                GasQuota::set_class_weight(root(), class, weight)
+            }
+            GovernanceParameter::SetReputationWeight { source, weight } => {
+                Reputation::set_reputation_weight(root(), source, weight)
+            }
+            GovernanceParameter::SetTaskMarketCutoff { cutoff } => {
+                TaskMarket::set_new_work_cutoff(root(), cutoff)
+            }
+        }
+    }
+
+    fn weight(change: &GovernanceParameter) -> Weight {
+        use frame_support::dispatch::GetDispatchInfo;
+        let call = match change.clone() {
+            GovernanceParameter::SetQuotaClassWeight { class, weight } => {
+                RuntimeCall::GasQuota(pallet_gas_quota::Call::set_class_weight { class, weight })
+            }
+            GovernanceParameter::SetReputationWeight { source, weight } => {
+                RuntimeCall::Reputation(pallet_reputation::Call::set_reputation_weight {
+                    source,
+                    weight,
+                })
+            }
+            GovernanceParameter::SetTaskMarketCutoff { cutoff } => {
+                RuntimeCall::TaskMarket(pallet_task_market::Call::set_new_work_cutoff { cutoff })
+            }
+        };
+        call.get_dispatch_info().call_weight
+    }
 }
 
 impl pallet_agent_did::Config for Runtime {
@@ -576,6 +899,9 @@ impl pallet_agent_did::Config for Runtime {
     type MaxKeyTypeLength = ConstU32<128>;
     type MaxKeyLength = ConstU32<256>;
     type MaxVerificationMethods = ConstU32<5>;
+    // Relayers check that an endpoint serves its challenge before attesting it.
+    type WebhookVerifier =
+        pallet_committee_membership::EnsureMember<Runtime, RelayerCommitteeInstance>;
 }
 
 /// Configure the Agent Receipts pallet (ProvenanceChain — verifiable agent activity attestation).
@@ -585,6 +911,26 @@ impl pallet_agent_receipts::Config for Runtime {
     type MaxAgentIdLen = ConstU32<64>;
     type MaxActionTypeLen = ConstU32<64>;
     type MaxMetadataLen = ConstU32<512>;
+    type MaxReferences = ConstU32<8>;
+    type ReferenceValidator = ReceiptReferenceValidator;
+}
+
+/// Receipt reference validation against the pallets wired into this runtime.
+///
+/// Service-market invocations and anon-messaging messages are not part of
+/// this runtime yet, so references to them are rejected.
+pub struct ReceiptReferenceValidator;
+
+impl pallet_agent_receipts::ReferenceValidator for ReceiptReferenceValidator {
+    fn exists(reference: &pallet_agent_receipts::ReceiptRef) -> bool {
+        match reference {
+            pallet_agent_receipts::ReceiptRef::Proposal(id) => {
+                pallet_quadratic_governance::Proposals::<Runtime>::contains_key(id)
+            }
+            pallet_agent_receipts::ReceiptRef::Invocation(_)
+            | pallet_agent_receipts::ReceiptRef::Message(_) => false,
+        }
+    }
 }
 
 // =========================================================
@@ -623,6 +969,30 @@ impl pallet_ibc_lite::Config for Runtime {
     type MaxPendingPackets = ConstU32<1000>;
     type PacketTimeoutBlocks = ConstU32<100>;
     type AgentRegistry = IbcAgentRegistry;
+    type IbcApplication = ();
+    type AttestationVerifier = pallet_ibc_lite::CounterpartySignerSet<Runtime>;
+    type AttestationValidityBlocks = ConstU32<{ 30 * DAYS }>;
+    type MaxDeadLetters = ConstU32<64>;
+    type MaxRetainedAckPayloads = ConstU32<64>;
+    type Currency = Balances;
+    type OpenSubmissionBond = ConstU128<{ 100 * UNITS }>;
+    type ChallengePeriod = ConstU32<{ 10 * MINUTES }>;
+    type FraudProofVerifier = pallet_ibc_lite::CounterpartySignerSet<Runtime>;
+    type RelayerStake = StakingRelayerStake;
+    type RelayerExclusivityWindow = ConstU32<{ 2 * MINUTES }>;
+    type RelayerCompensationSource = TreasuryAccount;
+    type PrimaryRelayerCompensation = ConstU128<{ UNITS / 10 }>;
+}
+
+/// Weights primary-relayer rotation by each relayer's active stake.
+pub struct StakingRelayerStake;
+
+impl pallet_ibc_lite::traits::RelayerStake<AccountId> for StakingRelayerStake {
+    fn stake_of(who: &AccountId) -> u128 {
+        <Staking as sp_staking::StakingInterface>::stake(who)
+            .map(|stake| stake.active)
+            .unwrap_or_default()
+    }
 }
 
 // =========================================================
@@ -767,6 +1137,100 @@ impl pallet_audit_attestation::Config for Runtime {
     type AgentRegistry = AuditAgentRegistry;
 }
 
+// ============================================================
+// pallet-committee-membership: standing working-group committees
+// ============================================================
+
+/// Dispute court administrators.
+pub type DisputeCourtInstance = pallet_committee_membership::Instance1;
+/// Price / reputation oracle committee.
+pub type OracleCommitteeInstance = pallet_committee_membership::Instance2;
+/// IBC-lite relayer committee.
+pub type RelayerCommitteeInstance = pallet_committee_membership::Instance3;
+/// Security committee reviewing bounty reports.
+pub type SecurityCommitteeInstance = pallet_committee_membership::Instance4;
+
+parameter_types! {
+    pub const CommitteeTermDuration: BlockNumber = 180 * DAYS;
+}
+
+impl pallet_committee_membership::Config<DisputeCourtInstance> for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type WeightInfo = ();
+    type GovernanceOrigin = frame_system::EnsureRoot<AccountId>;
+    type MaxMembers = ConstU32<9>;
+    type TermDuration = CommitteeTermDuration;
+}
+
+impl pallet_committee_membership::Config<OracleCommitteeInstance> for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type WeightInfo = ();
+    type GovernanceOrigin = frame_system::EnsureRoot<AccountId>;
+    type MaxMembers = ConstU32<16>;
+    type TermDuration = CommitteeTermDuration;
+}
+
+impl pallet_committee_membership::Config<RelayerCommitteeInstance> for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type WeightInfo = ();
+    type GovernanceOrigin = frame_system::EnsureRoot<AccountId>;
+    type MaxMembers = ConstU32<16>;
+    type TermDuration = CommitteeTermDuration;
+}
+
+impl pallet_committee_membership::Config<SecurityCommitteeInstance> for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type WeightInfo = ();
+    type GovernanceOrigin = frame_system::EnsureRoot<AccountId>;
+    type MaxMembers = ConstU32<9>;
+    type TermDuration = CommitteeTermDuration;
+}
+
+// ============================================================
+// pallet-security-bounty: treasury-funded bug bounties
+// ============================================================
+
+parameter_types! {
+    pub const SecurityBountyPalletId: PalletId = PalletId(*b"py/bnty!");
+    pub const BountyReportDeposit: Balance = 10 * UNITS;
+    pub const BountyRevealPeriod: BlockNumber = 7 * DAYS;
+}
+
+impl pallet_security_bounty::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type WeightInfo = ();
+    type Currency = Balances;
+    type GovernanceOrigin = frame_system::EnsureRoot<AccountId>;
+    type SecurityCommittee = SecurityCommittee;
+    // pallet-anon-messaging is not in the runtime yet, so no key registry.
+    type MessagingKeys = ();
+    type TreasuryAccount = TreasuryAccount;
+    type PalletId = SecurityBountyPalletId;
+    type ReportDeposit = BountyReportDeposit;
+    type RevealPeriod = BountyRevealPeriod;
+    // Majority of the nine-seat committee.
+    type ApprovalThreshold = ConstU32<5>;
+    type MaxCommitteeVotes = ConstU32<9>;
+    type MaxNameLength = ConstU32<64>;
+}
+
+// ============================================================
+// pallet-hook-budget: shared on_initialize budget
+// ============================================================
+
+parameter_types! {
+    /// The block-initialization share `BlockWeights` already reserves.
+    pub OnInitializeHookBudget: Weight = AVERAGE_ON_INITIALIZE_RATIO * MAXIMUM_BLOCK_WEIGHT;
+}
+
+impl pallet_hook_budget::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    // Highest priority first. ServiceMarket joins once it is in the runtime.
+    type Hooks = (EmergencyPause, TaskMarket);
+    type HookBudget = OnInitializeHookBudget;
+    type WeightInfo = ();
+}
+
 frame_support::construct_runtime!(
     pub enum Runtime {
         System: frame_system,
@@ -801,6 +1265,12 @@ frame_support::construct_runtime!(
         ReputationRegime: pallet_reputation_regime,
         AuditAttestation: pallet_audit_attestation,
         MoralFoundation: pallet_moral_foundation,
+        DisputeCourt: pallet_committee_membership::<Instance1>,
+        OracleCommittee: pallet_committee_membership::<Instance2>,
+        RelayerCommittee: pallet_committee_membership::<Instance3>,
+        SecurityCommittee: pallet_committee_membership::<Instance4>,
+        SecurityBounty: pallet_security_bounty,
+        HookBudget: pallet_hook_budget,
     }
 );
 
@@ -819,12 +1289,19 @@ pub type SignedExtra = (
     frame_system::CheckEra<Runtime>,
     frame_system::CheckNonce<Runtime>,
     frame_system::CheckWeight<Runtime>,
-    pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+    pallet_gas_quota::ChargeGasQuota<Runtime>,
 );
 
 /// All migrations of the runtime, in order.
 /// Add new migrations here.
-type Migrations = ();
+type Migrations = (
+    pallet_agent_registry::migrations::v1::MigrateToV1<Runtime>,
+    pallet_gas_quota::migrations::v1::MigrateToV1<Runtime>,
+    pallet_gas_quota::migrations::v2::MigrateToV2<Runtime>,
+    pallet_task_market::migrations::v1::MigrateToV1<Runtime>,
+    pallet_task_market::migrations::v2::MigrateToV2<Runtime>,
+    pallet_task_market::migrations::v3::MigrateToV3<Runtime>,
+);
 
 /// Unchecked extrinsic type as expected by this runtime.
 pub type UncheckedExtrinsic =
@@ -995,6 +1472,91 @@ impl_runtime_apis! {
         }
     }
 
+    impl pallet_ibc_lite::runtime_api::IbcLiteApi<Block, pallet_ibc_lite::DeadLetter<Runtime>> for Runtime {
+        fn dead_letters(channel_id: Vec<u8>) -> Vec<pallet_ibc_lite::DeadLetter<Runtime>> {
+            IbcLite::dead_letters(&channel_id)
+        }
+    }
+
+    impl pallet_agent_receipts::runtime_api::AgentReceiptsApi<Block> for Runtime {
+        fn references_of(agent_id: Vec<u8>, nonce: u64) -> Vec<pallet_agent_receipts::ReceiptRef> {
+            AgentReceipts::references_of(&agent_id, nonce)
+        }
+
+        fn receipts_referencing(
+            reference: pallet_agent_receipts::ReceiptRef,
+        ) -> Vec<(Vec<u8>, u64)> {
+            AgentReceipts::receipts_referencing(reference)
+        }
+    }
+
+    impl pallet_gas_quota::runtime_api::DryRunApi<Block, AccountId, RuntimeCall, RuntimeEvent, Balance>
+        for Runtime
+    {
+        fn dry_run_call(
+            origin: AccountId,
+            call: RuntimeCall,
+        ) -> pallet_gas_quota::runtime_api::CallDryRunEffects<RuntimeEvent, Balance> {
+            use codec::Encode;
+            use frame_support::dispatch::GetDispatchInfo;
+            use sp_runtime::traits::DispatchTransaction;
+
+            // Runtime API calls execute on a throwaway overlay, so the dispatch
+            // below is never committed.
+            System::reset_events();
+            let used_before = GasQuota::used_today(&origin);
+            let fees_before = pallet_gas_quota::TotalFeesCollected::<Runtime>::get();
+
+            let info = call.get_dispatch_info();
+            let len = call.encoded_size();
+            let result = pallet_gas_quota::ChargeGasQuota::<Runtime>::new()
+                .dispatch_transaction(RuntimeOrigin::signed(origin.clone()), call, &info, len, 0)
+                .map(|outcome| outcome.map(|_| ()).map_err(|e| e.error));
+
+            pallet_gas_quota::runtime_api::CallDryRunEffects {
+                result,
+                events: System::read_events_no_consensus()
+                    .map(|record| record.event)
+                    .collect(),
+                quota_consumed: GasQuota::used_today(&origin).saturating_sub(used_before),
+                fee: pallet_gas_quota::TotalFeesCollected::<Runtime>::get()
+                    .saturating_sub(fees_before),
+            }
+        }
+    }
+
+    impl pallet_gas_quota::runtime_api::GasQuotaApi<Block, AccountId, Balance, BlockNumber>
+        for Runtime
+    {
+        fn remaining_free_tx(account: AccountId) -> pallet_gas_quota::runtime_api::FreeTxStatus {
+            GasQuota::remaining_free_tx(&account)
+        }
+
+        fn projected_fee(account: AccountId, n_tx: u32) -> Balance {
+            GasQuota::projected_fee(&account, n_tx)
+        }
+
+        fn quota_reset_block(_account: AccountId) -> BlockNumber {
+            GasQuota::quota_reset_block()
+        }
+
+        fn class_usage(account: AccountId) -> Vec<(pallet_gas_quota::CallClass, u32)> {
+            GasQuota::class_usage(&account)
+        }
+    }
+
+    impl pallet_reputation::runtime_api::ReputationApi<Block, AccountId> for Runtime {
+        fn leaderboard(era: Option<u32>) -> Vec<(AccountId, u32)> {
+            Reputation::leaderboard(era)
+        }
+    }
+
+    impl identity::IdentityApi<Block> for Runtime {
+        fn resolve(identifier: identity::Identifier) -> Option<identity::IdentityRecord> {
+            identity::resolve(identifier)
+        }
+    }
+
     impl sp_genesis_builder::GenesisBuilder<Block> for Runtime {
         fn build_state(config: Vec<u8>) -> sp_genesis_builder::Result {
             build_state::<RuntimeGenesisConfig>(config)
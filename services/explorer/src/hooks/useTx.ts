@@ -74,7 +74,8 @@ export function useTx(txHash: string): UseTxResult {
           // Fetch events for this extrinsic
           const events: DecodedEvent[] = [];
           let success = true;
-          let tip = '0';
+          // The gas-quota extension takes no tip.
+          const tip = '0';
           let fee = '0';
 
           try {
@@ -100,10 +101,9 @@ export function useTx(txHash: string): UseTxResult {
                 if (ev.event.section === 'system' && ev.event.method === 'ExtrinsicFailed') {
                   success = false;
                 }
-                if (ev.event.section === 'transactionPayment' && ev.event.method === 'TransactionFeePaid') {
-                  const d = ev.event.data as { actualFee?: string; tip?: string };
-                  fee = d.actualFee ?? '0';
-                  tip = d.tip ?? '0';
+                if (ev.event.section === 'gasQuota' && ev.event.method === 'FeeCharged') {
+                  const d = ev.event.data as { amount?: string };
+                  fee = d.amount ?? '0';
                 }
               }
             }
@@ -80,9 +80,9 @@ describe('useTx', () => {
       {
         phase: { applyExtrinsic: '0' },
         event: {
-          section: 'transactionPayment',
-          method: 'TransactionFeePaid',
-          data: { actualFee: '1000000000', tip: '0' },
+          section: 'gasQuota',
+          method: 'FeeCharged',
+          data: { agent: '5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY', amount: '1000000000' },
         },
       },
     ];
@@ -0,0 +1,131 @@
+//! Economic invariants checked after every simulated block.
+
+use clawchain_runtime::{AccountId, Balance, Balances, BlockNumber, GasQuota, Runtime};
+use pallet_task_market::TaskStatus;
+use std::collections::BTreeMap;
+
+/// Highest reputation score, in basis points.
+pub const MAX_REPUTATION: u32 = 10_000;
+
+/// An invariant that stopped holding.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Violation {
+    /// Issuance moved by more than the fees burned as dust can explain.
+    IssuanceDrift { expected: Balance, actual: Balance },
+    /// An account's reserved balance differs from the rewards escrowed for
+    /// its unsettled tasks plus the deposits held for its open bids.
+    EscrowMismatch {
+        who: AccountId,
+        reserved: Balance,
+        escrowed: Balance,
+    },
+    /// Quota used this epoch differs from the units the driver charged.
+    QuotaMismatch {
+        who: AccountId,
+        used: u32,
+        charged: u32,
+    },
+    /// Fees swept or sitting in the pot exceed what was collected.
+    FeeOvercount {
+        collected: Balance,
+        pot: Balance,
+        swept: Balance,
+    },
+    /// A reputation score left the `0..=MAX_REPUTATION` range.
+    ReputationOutOfBounds { who: AccountId, score: u32 },
+}
+
+/// Whether `status` still holds the poster's reward in reserve.
+pub fn holds_escrow(status: &TaskStatus) -> bool {
+    !matches!(
+        status,
+        TaskStatus::Approved | TaskStatus::Cancelled | TaskStatus::Expired
+    )
+}
+
+/// Fees counted as collected that never reached the pot or the treasury.
+///
+/// A fee smaller than the existential deposit paid into an empty pot is
+/// dropped by `resolve_creating`, so this is non-zero whenever the pot has
+/// been empty at the time of a charge.
+pub fn fees_burned() -> Balance {
+    let collected = pallet_gas_quota::TotalFeesCollected::<Runtime>::get();
+    let swept = pallet_gas_quota::TotalFeesSwept::<Runtime>::get();
+    let pot = Balances::free_balance(GasQuota::fee_pot_account());
+    collected.saturating_sub(pot.saturating_add(swept))
+}
+
+/// Check every invariant against current storage.
+///
+/// `charged` holds the quota units the driver charged each agent during the
+/// epoch it is keyed with.
+pub fn check(
+    agents: &[AccountId],
+    initial_issuance: Balance,
+    charged: &BTreeMap<AccountId, (BlockNumber, u32)>,
+) -> Result<(), Violation> {
+    // Fees are the only balance that may leave circulation.
+    let collected = pallet_gas_quota::TotalFeesCollected::<Runtime>::get();
+    let swept = pallet_gas_quota::TotalFeesSwept::<Runtime>::get();
+    let pot = Balances::free_balance(GasQuota::fee_pot_account());
+    if pot.saturating_add(swept) > collected {
+        return Err(Violation::FeeOvercount {
+            collected,
+            pot,
+            swept,
+        });
+    }
+    let expected = initial_issuance.saturating_sub(fees_burned());
+    let actual = Balances::total_issuance();
+    if actual != expected {
+        return Err(Violation::IssuanceDrift { expected, actual });
+    }
+
+    let mut escrowed = BTreeMap::<AccountId, Balance>::new();
+    for (_, task) in pallet_task_market::Tasks::<Runtime>::iter() {
+        if holds_escrow(&task.status) {
+            let held = escrowed.entry(task.poster).or_default();
+            *held = held.saturating_add(task.reward);
+        }
+    }
+    for (_, bidder, deposit) in pallet_task_market::BidDeposits::<Runtime>::iter() {
+        let held = escrowed.entry(bidder).or_default();
+        *held = held.saturating_add(deposit);
+    }
+
+    let epoch = GasQuota::current_epoch();
+    for who in agents {
+        let reserved = Balances::reserved_balance(who);
+        let held = escrowed.get(who).copied().unwrap_or_default();
+        if reserved != held {
+            return Err(Violation::EscrowMismatch {
+                who: who.clone(),
+                reserved,
+                escrowed: held,
+            });
+        }
+
+        let used = GasQuota::used_today(who);
+        let expected = charged
+            .get(who)
+            .filter(|(charged_epoch, _)| *charged_epoch == epoch)
+            .map_or(0, |(_, units)| *units);
+        if used != expected {
+            return Err(Violation::QuotaMismatch {
+                who: who.clone(),
+                used,
+                charged: expected,
+            });
+        }
+
+        let score = pallet_reputation::Reputations::<Runtime>::get(who).score;
+        if score > MAX_REPUTATION {
+            return Err(Violation::ReputationOutOfBounds {
+                who: who.clone(),
+                score,
+            });
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,479 @@
+//! # ClawChain Simulation
+//!
+//! Deterministic multi-agent simulation over the full `clawchain-runtime`.
+//! A [`SimConfig`] describes a population of agents, each following a
+//! [`Policy`] — honest participants alongside absentee workers, false
+//! disputers, quota spammers and review rings. [`Simulation::run`] advances
+//! the chain block by block, lets every agent act through real dispatches,
+//! settles disputes the way governance would, and checks the
+//! [`invariants`] after each block:
+//!
+//! - balances only leave circulation as fees, and every fee collected is in
+//!   the gas-quota pot, swept to the treasury, or reported as burned;
+//! - each poster's reserved balance equals the rewards escrowed for their
+//!   unsettled tasks;
+//! - each agent's quota usage matches the units charged for their calls;
+//! - reputation scores stay within `0..=10_000`.
+//!
+//! The same seed always yields the same [`SimReport`], so a parameter change
+//! can be evaluated by comparing reports before and after it ships.
+
+pub mod invariants;
+mod rng;
+
+pub use invariants::Violation;
+pub use rng::SplitMix64;
+
+use clawchain_runtime::{
+    AccountId, Balance, Balances, BlockNumber, GasQuota, Reputation, Runtime, RuntimeCall,
+    RuntimeOrigin, System, UNITS,
+};
+use frame_support::traits::OnInitialize;
+use pallet_task_market::{TaskId, TaskStatus};
+use sp_runtime::{traits::Dispatchable, BuildStorage};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// How an agent behaves.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Policy {
+    /// Posts and bids on tasks, delivers assigned work and approves
+    /// delivered work.
+    Honest,
+    /// Bids on tasks but never delivers once assigned.
+    Absentee,
+    /// Delivers honestly but disputes work delivered on its own tasks
+    /// instead of approving it.
+    FalseDisputer,
+    /// Floods bids every block to run past its free quota.
+    QuotaSpammer,
+    /// Leaves five-star reviews for other ring members every block.
+    ReviewRing,
+}
+
+/// Parameters of one simulation run.
+#[derive(Clone, Debug)]
+pub struct SimConfig {
+    /// Seed for every decision the agents make.
+    pub seed: u64,
+    /// Number of blocks to simulate.
+    pub blocks: BlockNumber,
+    /// Agent populations as `(policy, count)`.
+    pub population: Vec<(Policy, u32)>,
+    /// Balance each agent starts with.
+    pub initial_balance: Balance,
+    /// Reward offered for each posted task.
+    pub reward: Balance,
+    /// Chance, per mille, that an idle agent posts a task in a block.
+    pub post_per_mille: u32,
+    /// Blocks a poster waits on an undelivered or unbid task before
+    /// disputing or cancelling it.
+    pub patience: BlockNumber,
+    /// Bids a [`Policy::QuotaSpammer`] sends per block.
+    pub spam_burst: u32,
+}
+
+impl Default for SimConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            blocks: 200,
+            population: vec![
+                (Policy::Honest, 8),
+                (Policy::Absentee, 2),
+                (Policy::FalseDisputer, 2),
+                (Policy::QuotaSpammer, 1),
+                (Policy::ReviewRing, 3),
+            ],
+            initial_balance: 100_000 * UNITS,
+            reward: 100 * UNITS,
+            post_per_mille: 150,
+            patience: 10,
+            spam_burst: 5,
+        }
+    }
+}
+
+/// Outcome of a run. Two runs with the same config produce equal reports.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SimReport {
+    /// Blocks simulated.
+    pub blocks: BlockNumber,
+    /// Signed calls dispatched.
+    pub dispatched: u32,
+    /// Signed calls that failed quota charging or dispatch.
+    pub rejected: u32,
+    /// Tasks posted.
+    pub tasks_posted: u32,
+    /// Tasks whose delivered work was approved by the poster.
+    pub tasks_approved: u32,
+    /// Disputes settled by governance.
+    pub disputes_resolved: u32,
+    /// Fees charged over free quota.
+    pub fees_collected: Balance,
+    /// Part of `fees_collected` that left circulation.
+    pub fees_burned: Balance,
+    /// Rewards still escrowed at the end of the run.
+    pub escrowed: Balance,
+    /// Highest reputation score any agent reached.
+    pub max_reputation: u32,
+}
+
+/// An invariant violation and the block it was found in.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Failure {
+    pub block: BlockNumber,
+    pub violation: Violation,
+}
+
+#[derive(Clone, Debug)]
+struct Agent {
+    id: AccountId,
+    policy: Policy,
+}
+
+/// Driver state that lives outside the runtime.
+struct World {
+    config: SimConfig,
+    agents: Vec<Agent>,
+    rng: SplitMix64,
+    initial_issuance: Balance,
+    /// Quota units charged per agent in the keyed epoch.
+    charged: BTreeMap<AccountId, (BlockNumber, u32)>,
+    /// Tasks not yet approved, cancelled or expired.
+    live: BTreeSet<TaskId>,
+    /// Tasks whose worker delivered, for dispute settlement.
+    delivered: BTreeSet<TaskId>,
+    /// Block each live task was assigned in.
+    assigned_at: BTreeMap<TaskId, BlockNumber>,
+    report: SimReport,
+}
+
+/// A configured run over fresh runtime storage.
+pub struct Simulation {
+    ext: sp_io::TestExternalities,
+    world: World,
+}
+
+/// Deterministic account for the agent at `index`.
+pub fn agent_account(index: u32) -> AccountId {
+    let mut raw = [0u8; 32];
+    raw[..8].copy_from_slice(b"clawsim:");
+    raw[8..12].copy_from_slice(&index.to_le_bytes());
+    AccountId::from(raw)
+}
+
+impl Simulation {
+    pub fn new(config: SimConfig) -> Self {
+        let mut agents = Vec::new();
+        for (policy, count) in &config.population {
+            for _ in 0..*count {
+                agents.push(Agent {
+                    id: agent_account(agents.len() as u32),
+                    policy: *policy,
+                });
+            }
+        }
+
+        let mut storage = frame_system::GenesisConfig::<Runtime>::default()
+            .build_storage()
+            .unwrap();
+        pallet_balances::GenesisConfig::<Runtime> {
+            balances: agents
+                .iter()
+                .map(|agent| (agent.id.clone(), config.initial_balance))
+                .collect(),
+            ..Default::default()
+        }
+        .assimilate_storage(&mut storage)
+        .unwrap();
+
+        let mut ext = sp_io::TestExternalities::new(storage);
+        let initial_issuance = ext.execute_with(Balances::total_issuance);
+
+        Self {
+            ext,
+            world: World {
+                rng: SplitMix64::new(config.seed),
+                config,
+                agents,
+                initial_issuance,
+                charged: BTreeMap::new(),
+                live: BTreeSet::new(),
+                delivered: BTreeSet::new(),
+                assigned_at: BTreeMap::new(),
+                report: SimReport::default(),
+            },
+        }
+    }
+
+    /// Simulate every configured block, stopping at the first invariant
+    /// violation.
+    pub fn run(mut self) -> Result<SimReport, Failure> {
+        let world = &mut self.world;
+        self.ext.execute_with(|| world.run())
+    }
+}
+
+impl World {
+    fn run(&mut self) -> Result<SimReport, Failure> {
+        let accounts: Vec<AccountId> = self.agents.iter().map(|agent| agent.id.clone()).collect();
+
+        for block in 1..=self.config.blocks {
+            System::reset_events();
+            System::set_block_number(block);
+            GasQuota::on_initialize(block);
+            Reputation::on_initialize(block);
+
+            let mut order: Vec<usize> = (0..self.agents.len()).collect();
+            self.rng.shuffle(&mut order);
+            for index in order {
+                self.act(index, block);
+            }
+            self.settle_disputes();
+
+            invariants::check(&accounts, self.initial_issuance, &self.charged)
+                .map_err(|violation| Failure { block, violation })?;
+            self.report.blocks = block;
+        }
+
+        self.report.fees_collected = pallet_gas_quota::TotalFeesCollected::<Runtime>::get();
+        self.report.fees_burned = invariants::fees_burned();
+        self.report.escrowed = accounts.iter().map(Balances::reserved_balance).sum();
+        self.report.max_reputation = accounts
+            .iter()
+            .map(|who| pallet_reputation::Reputations::<Runtime>::get(who).score)
+            .max()
+            .unwrap_or_default();
+        Ok(self.report.clone())
+    }
+
+    /// Charge quota for `call` as the `ChargeGasQuota` extension would, then
+    /// dispatch it signed by `who`.
+    fn submit(&mut self, who: &AccountId, call: impl Into<RuntimeCall>) -> bool {
+        let call: RuntimeCall = call.into();
+        self.report.dispatched = self.report.dispatched.saturating_add(1);
+
+        let (units, class) = GasQuota::call_quota(&call);
+        if units > 0 {
+            if GasQuota::consume_call_quota(who, units, class).is_err() {
+                self.report.rejected = self.report.rejected.saturating_add(1);
+                return false;
+            }
+            let epoch = GasQuota::current_epoch();
+            let entry = self.charged.entry(who.clone()).or_insert((epoch, 0));
+            if entry.0 != epoch {
+                *entry = (epoch, 0);
+            }
+            entry.1 = entry.1.saturating_add(units);
+        }
+
+        let ok = call.dispatch(RuntimeOrigin::signed(who.clone())).is_ok();
+        if !ok {
+            self.report.rejected = self.report.rejected.saturating_add(1);
+        }
+        ok
+    }
+
+    fn act(&mut self, index: usize, now: BlockNumber) {
+        let agent = self.agents[index].clone();
+        match agent.policy {
+            Policy::QuotaSpammer => {
+                for _ in 0..self.config.spam_burst {
+                    self.bid_on_random_task(&agent.id);
+                }
+            }
+            Policy::ReviewRing => self.review_ring_member(&agent),
+            _ => self.participate(&agent, now),
+        }
+    }
+
+    fn participate(&mut self, agent: &Agent, now: BlockNumber) {
+        let live: Vec<TaskId> = self.live.iter().copied().collect();
+        for task_id in live {
+            let Some(task) = pallet_task_market::Tasks::<Runtime>::get(task_id) else {
+                continue;
+            };
+
+            if task.assigned_to.as_ref() == Some(&agent.id)
+                && task.status == TaskStatus::Assigned
+                && agent.policy != Policy::Absentee
+            {
+                if self.submit(
+                    &agent.id,
+                    pallet_task_market::Call::<Runtime>::submit_work {
+                        task_id,
+                        proof: b"ipfs://sim-delivery".to_vec(),
+                        proof_type: pallet_task_market::ProofType::Cid,
+                    },
+                ) {
+                    self.delivered.insert(task_id);
+                }
+                continue;
+            }
+
+            if task.poster != agent.id {
+                continue;
+            }
+            let waited = now.saturating_sub(
+                self.assigned_at
+                    .get(&task_id)
+                    .copied()
+                    .unwrap_or(task.created_at),
+            );
+            match task.status {
+                TaskStatus::Completed if agent.policy == Policy::FalseDisputer => {
+                    self.submit(
+                        &agent.id,
+                        pallet_task_market::Call::<Runtime>::dispute_task {
+                            task_id,
+                            reason: b"not as specified".to_vec(),
+                        },
+                    );
+                }
+                TaskStatus::Completed => {
+                    let approved = self.submit(
+                        &agent.id,
+                        pallet_task_market::Call::<Runtime>::approve_work { task_id },
+                    );
+                    if approved {
+                        self.report.tasks_approved = self.report.tasks_approved.saturating_add(1);
+                        self.forget(task_id);
+                    }
+                }
+                TaskStatus::Assigned if waited > self.config.patience => {
+                    self.submit(
+                        &agent.id,
+                        pallet_task_market::Call::<Runtime>::dispute_task {
+                            task_id,
+                            reason: b"no delivery".to_vec(),
+                        },
+                    );
+                }
+                TaskStatus::Open => {
+                    let mut bidders: Vec<AccountId> =
+                        pallet_task_market::TaskBids::<Runtime>::iter_key_prefix(task_id).collect();
+                    // Storage order follows the key hash; sort so the pick
+                    // depends on the seed alone.
+                    bidders.sort();
+                    let bidder = (!bidders.is_empty())
+                        .then(|| bidders[self.rng.below(bidders.len() as u64) as usize].clone());
+                    if let Some(bidder) = bidder {
+                        if self.submit(
+                            &agent.id,
+                            pallet_task_market::Call::<Runtime>::assign_task { task_id, bidder },
+                        ) {
+                            self.assigned_at.insert(task_id, now);
+                        }
+                    } else if waited > self.config.patience
+                        && self.submit(
+                            &agent.id,
+                            pallet_task_market::Call::<Runtime>::cancel_task { task_id },
+                        )
+                    {
+                        self.forget(task_id);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if self.rng.chance(self.config.post_per_mille) {
+            self.post_task(&agent.id, now);
+        } else {
+            self.bid_on_random_task(&agent.id);
+        }
+    }
+
+    fn post_task(&mut self, who: &AccountId, now: BlockNumber) {
+        let task_id = pallet_task_market::TaskCount::<Runtime>::get();
+        let posted = self.submit(
+            who,
+            pallet_task_market::Call::<Runtime>::post_task {
+                title: b"sim task".to_vec(),
+                description: b"simulated unit of work".to_vec(),
+                reward: self.config.reward,
+                deadline: now.saturating_add(self.config.blocks),
+                min_bidder_reputation: None,
+                worker_bond: None,
+            },
+        );
+        if posted {
+            self.live.insert(task_id);
+            self.report.tasks_posted = self.report.tasks_posted.saturating_add(1);
+        }
+    }
+
+    fn bid_on_random_task(&mut self, who: &AccountId) {
+        if self.live.is_empty() {
+            return;
+        }
+        let pick = self.rng.below(self.live.len() as u64) as usize;
+        let Some(task_id) = self.live.iter().nth(pick).copied() else {
+            return;
+        };
+        let open = pallet_task_market::Tasks::<Runtime>::get(task_id)
+            .is_some_and(|task| task.status == TaskStatus::Open && &task.poster != who);
+        if open {
+            self.submit(
+                who,
+                pallet_task_market::Call::<Runtime>::bid_on_task {
+                    task_id,
+                    amount: self.config.reward,
+                    proposal: b"will deliver".to_vec(),
+                },
+            );
+        }
+    }
+
+    fn review_ring_member(&mut self, agent: &Agent) {
+        let ring: Vec<AccountId> = self
+            .agents
+            .iter()
+            .filter(|other| other.policy == Policy::ReviewRing && other.id != agent.id)
+            .map(|other| other.id.clone())
+            .collect();
+        if ring.is_empty() {
+            return;
+        }
+        let reviewee = ring[self.rng.below(ring.len() as u64) as usize].clone();
+        self.submit(
+            &agent.id,
+            pallet_reputation::Call::<Runtime>::submit_review {
+                reviewee,
+                rating: 5,
+                comment: b"great work".to_vec(),
+                task_id: 0,
+            },
+        );
+    }
+
+    /// Settle every open dispute as governance would: for the worker if they
+    /// delivered, for the poster otherwise.
+    fn settle_disputes(&mut self) {
+        let live: Vec<TaskId> = self.live.iter().copied().collect();
+        for task_id in live {
+            let Some(task) = pallet_task_market::Tasks::<Runtime>::get(task_id) else {
+                continue;
+            };
+            if task.status != TaskStatus::Disputed {
+                continue;
+            }
+            let winner = match (&task.assigned_to, self.delivered.contains(&task_id)) {
+                (Some(worker), true) => worker.clone(),
+                _ => task.poster.clone(),
+            };
+            let call: RuntimeCall =
+                pallet_task_market::Call::<Runtime>::resolve_dispute { task_id, winner }.into();
+            if call.dispatch(RuntimeOrigin::root()).is_ok() {
+                self.report.disputes_resolved = self.report.disputes_resolved.saturating_add(1);
+                self.forget(task_id);
+            }
+        }
+    }
+
+    fn forget(&mut self, task_id: TaskId) {
+        self.live.remove(&task_id);
+        self.delivered.remove(&task_id);
+        self.assigned_at.remove(&task_id);
+    }
+}
@@ -0,0 +1,45 @@
+//! Simulation scenarios run in CI. A failing invariant reports the block and
+//! the account it broke on; rerun with the same seed to reproduce it.
+
+use clawchain_sim::{Policy, SimConfig, Simulation};
+
+#[test]
+fn mixed_population_preserves_invariants() {
+    let report = Simulation::new(SimConfig::default())
+        .run()
+        .unwrap_or_else(|failure| panic!("{failure:?}"));
+
+    assert_eq!(report.blocks, SimConfig::default().blocks);
+    assert!(report.tasks_approved > 0);
+    // Absentees and false disputers both end up before governance.
+    assert!(report.disputes_resolved > 0);
+    // The spammer runs past its free quota.
+    assert!(report.fees_collected > 0);
+}
+
+#[test]
+fn same_seed_replays_identically() {
+    let config = SimConfig {
+        seed: 42,
+        blocks: 60,
+        ..Default::default()
+    };
+    let first = Simulation::new(config.clone()).run().unwrap();
+    let second = Simulation::new(config).run().unwrap();
+    assert_eq!(first, second);
+}
+
+#[test]
+fn honest_population_settles_without_disputes() {
+    let report = Simulation::new(SimConfig {
+        seed: 7,
+        blocks: 100,
+        population: vec![(Policy::Honest, 6)],
+        ..Default::default()
+    })
+    .run()
+    .unwrap_or_else(|failure| panic!("{failure:?}"));
+
+    assert!(report.tasks_approved > 0);
+    assert_eq!(report.disputes_resolved, 0);
+}